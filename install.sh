@@ -32,6 +32,14 @@ sudo mkdir -p /usr/local/share/applications/
 echo "Installing binary..."
 sudo cp target/release/pro_audio_config /usr/local/bin/pro-audio-config
 
+# Install the privileged helper and its polkit action ("Configure system audio")
+echo "Installing privileged helper..."
+sudo mkdir -p /usr/libexec/ /usr/share/polkit-1/actions/
+sudo cp target/release/pro-audio-config-helper /usr/libexec/pro-audio-config-helper
+sudo chmod 755 /usr/libexec/pro-audio-config-helper
+sudo cp data/com.github.peterlsvk.pro-audio-config.policy /usr/share/polkit-1/actions/
+sudo chmod 644 /usr/share/polkit-1/actions/com.github.peterlsvk.pro-audio-config.policy
+
 # Copy the icons (only if they exist)
 echo "Installing icons..."
 if [ -f "./icons/16x16/icon.png" ]; then
@@ -76,6 +84,8 @@ echo ""
 echo "=== Installation Complete ==="
 echo "Pro Audio Config has been installed to:"
 echo "  Binary: /usr/local/bin/pro-audio-config"
+echo "  Helper: /usr/libexec/pro-audio-config-helper"
+echo "  Polkit action: /usr/share/polkit-1/actions/com.github.peterlsvk.pro-audio-config.policy"
 echo "  Icons: /usr/local/share/icons/hicolor/*/apps/pro-audio-config.png"
 echo "  Desktop entry: /usr/local/share/applications/pro-audio-config.desktop"
 echo ""
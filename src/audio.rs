@@ -8,8 +8,10 @@
  * Core audio device detection and system interaction
  */
 
+use crate::error::AudioConfigError;
 use lazy_static::lazy_static;
-use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::process::Command;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
@@ -28,6 +30,34 @@ lazy_static! {
         Arc::new(Mutex::new(None));
     static ref CACHED_CURRENT_INPUT_DEVICE: Arc<Mutex<Option<(String, Instant)>>> =
         Arc::new(Mutex::new(None));
+    static ref PIPEWIRE_REMOTE_OVERRIDE: Mutex<PipeWireRemote> =
+        Mutex::new(PipeWireRemote::default());
+    // Probed once per session (per remote), see detect_rate_switching
+    static ref CACHED_RATE_SWITCHING: Mutex<Option<RateSwitching>> = Mutex::new(None);
+}
+
+pub const PIPEWIRE_RUNTIME_DIR_ENV: &str = "PIPEWIRE_RUNTIME_DIR";
+pub const PIPEWIRE_REMOTE_ENV: &str = "PIPEWIRE_REMOTE";
+
+/// Where to reach the PipeWire daemon, for sessions whose socket is not in
+/// the default XDG_RUNTIME_DIR (multi-seat, nested or remote sessions)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PipeWireRemote {
+    pub runtime_dir: Option<String>,
+    /// Socket name, e.g. "pipewire-0-manager"
+    pub remote: Option<String>,
+}
+
+impl PipeWireRemote {
+    /// Value for `remote.name` when connecting in-process. PipeWire accepts an
+    /// absolute socket path there, which covers a custom runtime dir.
+    pub fn remote_name(&self) -> Option<String> {
+        match (&self.runtime_dir, &self.remote) {
+            (Some(dir), Some(name)) if !name.starts_with('/') => Some(format!("{}/{}", dir, name)),
+            (Some(dir), None) => Some(format!("{}/pipewire-0", dir)),
+            (_, remote) => remote.clone(),
+        }
+    }
 }
 
 const CACHE_DURATION: Duration = Duration::from_secs(2); // Cache for 2 seconds
@@ -41,20 +71,62 @@ pub struct AudioDevice {
     pub available: bool,
 }
 
+/// Direction of a device, taken from its PipeWire `media.class`
 #[derive(Debug, Clone, PartialEq)]
 pub enum DeviceType {
+    /// `Audio/Source` (microphones, line inputs)
     Input,
+    /// `Audio/Sink` (speakers, headphones, HDMI)
     Output,
+    /// `Audio/Duplex`, or a card listed for both directions
     Duplex,
+    /// No or unrecognized media.class; never assume a direction for these
     Unknown,
 }
 
-#[derive(Clone, Debug)]
+impl DeviceType {
+    /// Maps a PipeWire media.class such as `Audio/Sink` or `Audio/Source/Virtual`
+    pub fn from_media_class(class: &str) -> Self {
+        let mut parts = class.split('/');
+        match (parts.next(), parts.next()) {
+            (Some("Audio"), Some("Source")) => DeviceType::Input,
+            (Some("Audio"), Some("Sink")) => DeviceType::Output,
+            (Some("Audio"), Some("Duplex")) => DeviceType::Duplex,
+            _ => DeviceType::Unknown,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AudioSettings {
+    /// Graph rate (default.clock.rate)
     pub sample_rate: u32,
     pub bit_depth: u32,
     pub buffer_size: u32,
     pub device_id: String,
+    /// Extra rates PipeWire may switch to (default.clock.allowed-rates).
+    /// Empty means only the graph rate.
+    #[serde(default)]
+    pub allowed_rates: Vec<u32>,
+    /// node.description written for the configured device. None keeps the
+    /// device's own name in every app's device list.
+    #[serde(default)]
+    pub node_description: Option<String>,
+    /// ALSA hardware period (api.alsa.period-size), separate from the graph
+    /// quantum in `buffer_size`. None uses the buffer size.
+    #[serde(default)]
+    pub period_size: Option<u32>,
+    /// Channel count written as audio.channels. Settings saved before it
+    /// existed load as stereo.
+    #[serde(default = "default_channel_count")]
+    pub channels: u32,
+    /// audio.position map. Empty uses the standard layout for `channels`.
+    #[serde(default)]
+    pub position: Vec<String>,
+}
+
+fn default_channel_count() -> u32 {
+    2
 }
 
 impl AudioSettings {
@@ -64,13 +136,78 @@ impl AudioSettings {
             bit_depth,
             buffer_size,
             device_id,
+            allowed_rates: Vec::new(),
+            node_description: None,
+            period_size: None,
+            channels: default_channel_count(),
+            position: Vec::new(),
+        }
+    }
+
+    pub fn with_allowed_rates(mut self, allowed_rates: Vec<u32>) -> Self {
+        self.allowed_rates = allowed_rates;
+        self
+    }
+
+    /// Blank descriptions count as "keep the original name"
+    pub fn with_node_description(mut self, description: &str) -> Self {
+        let description = description.trim();
+        self.node_description = (!description.is_empty()).then(|| description.to_string());
+        self
+    }
+
+    pub fn with_period_size(mut self, period_size: Option<u32>) -> Self {
+        self.period_size = period_size;
+        self
+    }
+
+    /// Channel count with an explicit position map; an empty map uses the
+    /// standard layout for the count
+    pub fn with_channels(mut self, channels: u32, position: Vec<String>) -> Self {
+        self.channels = channels;
+        self.position = position;
+        self
+    }
+
+    /// audio.position written for the device, e.g. FL FR for stereo
+    pub fn channel_positions(&self) -> Vec<String> {
+        if self.position.is_empty() {
+            crate::config::default_channel_positions(self.channels)
+        } else {
+            self.position.clone()
         }
     }
 
+    /// Period size written to the device, the buffer size unless set apart
+    pub fn hardware_period_size(&self) -> u32 {
+        self.period_size.unwrap_or(self.buffer_size)
+    }
+
+    /// Sorted allowed rates, always including the graph rate
+    pub fn effective_allowed_rates(&self) -> Vec<u32> {
+        let mut rates = self.allowed_rates.clone();
+        rates.push(self.sample_rate);
+        rates.sort_unstable();
+        rates.dedup();
+        rates
+    }
+
+    /// Allowed rates formatted for a SPA array, e.g. "44100, 48000"
+    pub fn allowed_rates_list(&self) -> String {
+        self.effective_allowed_rates()
+            .iter()
+            .map(|rate| rate.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
     pub fn validate(&self) -> Result<(), String> {
         const VALID_SAMPLE_RATES: [u32; 5] = [44100, 48000, 96000, 192000, 384000];
         const VALID_BIT_DEPTHS: [u32; 3] = [16, 24, 32];
         const VALID_BUFFER_SIZES: [u32; 7] = [128, 256, 512, 1024, 2048, 4096, 8192];
+        const VALID_PERIOD_SIZES: [u32; 9] = [32, 64, 128, 256, 512, 1024, 2048, 4096, 8192];
+        // PipeWire's SPA_AUDIO_MAX_CHANNELS
+        const MAX_CHANNELS: u32 = 64;
 
         if !VALID_SAMPLE_RATES.contains(&self.sample_rate) {
             return Err(format!(
@@ -79,6 +216,17 @@ impl AudioSettings {
             ));
         }
 
+        if let Some(rate) = self
+            .allowed_rates
+            .iter()
+            .find(|rate| !VALID_SAMPLE_RATES.contains(rate))
+        {
+            return Err(format!(
+                "Invalid allowed rate: {}. Valid rates: {:?}",
+                rate, VALID_SAMPLE_RATES
+            ));
+        }
+
         if !VALID_BIT_DEPTHS.contains(&self.bit_depth) {
             return Err(format!(
                 "Invalid bit depth: {}. Valid depths: {:?}",
@@ -93,6 +241,30 @@ impl AudioSettings {
             ));
         }
 
+        if let Some(period_size) = self.period_size
+            && !VALID_PERIOD_SIZES.contains(&period_size)
+        {
+            return Err(format!(
+                "Invalid period size: {}. Valid sizes: {:?}",
+                period_size, VALID_PERIOD_SIZES
+            ));
+        }
+
+        if self.channels == 0 || self.channels > MAX_CHANNELS {
+            return Err(format!(
+                "Invalid channel count: {}. Valid counts: 1-{}",
+                self.channels, MAX_CHANNELS
+            ));
+        }
+
+        if !self.position.is_empty() && self.position.len() != self.channels as usize {
+            return Err(format!(
+                "{} channel positions given for {} channels",
+                self.position.len(),
+                self.channels
+            ));
+        }
+
         if self.device_id.is_empty() {
             return Err("Device ID cannot be empty".to_string());
         }
@@ -115,6 +287,81 @@ impl AudioSettings {
             _ => Err(format!("Invalid bit depth: {}", self.bit_depth)),
         }
     }
+
+    /// Fields whose value differs in `other`, in `SettingField::ALL` order
+    pub fn changed_fields(&self, other: &AudioSettings) -> Vec<SettingField> {
+        SettingField::ALL
+            .into_iter()
+            .filter(|field| field.describe(self) != field.describe(other))
+            .collect()
+    }
+
+    /// These settings with only `field` taken from `previous`
+    pub fn with_field_from(&self, previous: &AudioSettings, field: SettingField) -> AudioSettings {
+        let mut settings = self.clone();
+        match field {
+            SettingField::SampleRate => {
+                settings.sample_rate = previous.sample_rate;
+                settings.allowed_rates = previous.allowed_rates.clone();
+            }
+            SettingField::BitDepth => settings.bit_depth = previous.bit_depth,
+            SettingField::BufferSize => settings.buffer_size = previous.buffer_size,
+            SettingField::PeriodSize => settings.period_size = previous.period_size,
+            SettingField::Channels => {
+                settings.channels = previous.channels;
+                settings.position = previous.position.clone();
+            }
+        }
+        settings
+    }
+}
+
+/// A field of `AudioSettings` that can be reverted on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingField {
+    SampleRate,
+    BitDepth,
+    BufferSize,
+    PeriodSize,
+    Channels,
+}
+
+impl SettingField {
+    pub const ALL: [SettingField; 5] = [
+        SettingField::SampleRate,
+        SettingField::BitDepth,
+        SettingField::BufferSize,
+        SettingField::PeriodSize,
+        SettingField::Channels,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SettingField::SampleRate => "Sample Rate",
+            SettingField::BitDepth => "Bit Depth",
+            SettingField::BufferSize => "Buffer Size",
+            SettingField::PeriodSize => "Hardware Period Size",
+            SettingField::Channels => "Channel Layout",
+        }
+    }
+
+    /// The field's value in `settings`, e.g. "512 samples"
+    pub fn describe(&self, settings: &AudioSettings) -> String {
+        match self {
+            SettingField::SampleRate => format!("{} Hz", settings.sample_rate),
+            SettingField::BitDepth => format!("{} bit", settings.bit_depth),
+            SettingField::BufferSize => format!("{} samples", settings.buffer_size),
+            SettingField::PeriodSize => match settings.period_size {
+                Some(period_size) => format!("{} samples", period_size),
+                None => "same as buffer".to_string(),
+            },
+            SettingField::Channels => format!(
+                "{} channels ({})",
+                settings.channels,
+                settings.channel_positions().join(" ")
+            ),
+        }
+    }
 }
 
 fn is_valid_device_id(device_id: &str) -> bool {
@@ -140,7 +387,7 @@ pub fn detect_all_audio_devices() -> Result<Vec<AudioDevice>, String> {
     if let Some(cached) = CACHED_ALL_DEVICES.lock().unwrap().as_ref()
         && cached.1.elapsed() < CACHE_DURATION
     {
-        println!("DEBUG: Returning cached all devices");
+        log::debug!("Returning cached all devices");
         return Ok(cached.0.clone());
     }
 
@@ -152,8 +399,8 @@ pub fn detect_all_audio_devices() -> Result<Vec<AudioDevice>, String> {
     if let Ok(devices) = detect_pipewire_only_devices()
         && !devices.is_empty()
     {
-        println!(
-            "DEBUG: Using PipeWire device detection ({} devices)",
+        log::debug!(
+            "Using PipeWire device detection ({} devices)",
             devices.len()
         );
         all_devices = devices;
@@ -163,10 +410,7 @@ pub fn detect_all_audio_devices() -> Result<Vec<AudioDevice>, String> {
     if all_devices.is_empty()
         && let Ok(devices) = detect_alsa_only_real_devices()
     {
-        println!(
-            "DEBUG: Falling back to ALSA detection ({} devices)",
-            devices.len()
-        );
+        log::debug!("Falling back to ALSA detection ({} devices)", devices.len());
         all_devices = devices;
     }
 
@@ -184,7 +428,7 @@ pub fn detect_all_audio_devices() -> Result<Vec<AudioDevice>, String> {
     // Deduplicate by ID
     let unique_devices = deduplicate_devices(all_devices);
 
-    println!("Found {} unique audio devices", unique_devices.len());
+    log::info!("Found {} unique audio devices", unique_devices.len());
 
     // Update cache
     *CACHED_ALL_DEVICES.lock().unwrap() = Some((unique_devices.clone(), Instant::now()));
@@ -195,7 +439,7 @@ pub fn detect_all_audio_devices() -> Result<Vec<AudioDevice>, String> {
 pub fn detect_pipewire_only_devices() -> Result<Vec<AudioDevice>, String> {
     let mut devices = Vec::new();
 
-    let output = Command::new("pw-cli")
+    let output = pipewire_command("pw-cli")
         .args(["list-objects", "Node"])
         .output()
         .map_err(|e| format!("Failed to run pw-cli: {}", e))?;
@@ -243,7 +487,7 @@ pub fn detect_pipewire_only_devices() -> Result<Vec<AudioDevice>, String> {
                 && let Some(class) = line.split('=').nth(1)
             {
                 let class_clean = class.trim().trim_matches('"');
-                device.device_type = classify_real_device_type(class_clean, device);
+                device.device_type = DeviceType::from_media_class(class_clean);
             }
 
             if line.contains("device.class")
@@ -355,38 +599,6 @@ fn extract_pipewire_node_id(line: &str) -> String {
         .unwrap_or_else(|| "unknown".to_string())
 }
 
-/// Classify device type with better heuristics
-fn classify_real_device_type(class: &str, device: &AudioDevice) -> DeviceType {
-    match class {
-        "Audio/Source" => DeviceType::Input,
-        "Audio/Sink" => DeviceType::Output,
-        "Audio/Duplex" => DeviceType::Duplex,
-        _ => {
-            let text = format!(
-                "{} {}",
-                device.name.to_lowercase(),
-                device.description.to_lowercase()
-            );
-
-            if text.contains("source")
-                || text.contains("capture")
-                || text.contains("input")
-                || text.contains("mic")
-            {
-                DeviceType::Input
-            } else if text.contains("sink")
-                || text.contains("playback")
-                || text.contains("output")
-                || text.contains("speaker")
-            {
-                DeviceType::Output
-            } else {
-                DeviceType::Duplex
-            }
-        }
-    }
-}
-
 /// Check if a device is a valid real device (not virtual/software)
 fn is_valid_real_device(device: &AudioDevice) -> bool {
     if device.name.is_empty() {
@@ -547,7 +759,7 @@ pub fn detect_output_audio_devices() -> Result<Vec<AudioDevice>, String> {
     if let Some(cached) = CACHED_OUTPUT_DEVICES.lock().unwrap().as_ref()
         && cached.1.elapsed() < CACHE_DURATION
     {
-        println!("DEBUG: Returning cached output devices");
+        log::debug!("Returning cached output devices");
         return Ok(cached.0.clone());
     }
 
@@ -557,7 +769,7 @@ pub fn detect_output_audio_devices() -> Result<Vec<AudioDevice>, String> {
         .filter(|d| matches!(d.device_type, DeviceType::Output | DeviceType::Duplex))
         .collect();
 
-    println!("Found {} output audio devices", output_devices.len());
+    log::info!("Found {} output audio devices", output_devices.len());
 
     *CACHED_OUTPUT_DEVICES.lock().unwrap() = Some((output_devices.clone(), Instant::now()));
     Ok(output_devices)
@@ -567,7 +779,7 @@ pub fn detect_input_audio_devices() -> Result<Vec<AudioDevice>, String> {
     if let Some(cached) = CACHED_INPUT_DEVICES.lock().unwrap().as_ref()
         && cached.1.elapsed() < CACHE_DURATION
     {
-        println!("DEBUG: Returning cached input devices");
+        log::debug!("Returning cached input devices");
         return Ok(cached.0.clone());
     }
 
@@ -577,7 +789,7 @@ pub fn detect_input_audio_devices() -> Result<Vec<AudioDevice>, String> {
         .filter(|d| matches!(d.device_type, DeviceType::Input | DeviceType::Duplex))
         .collect();
 
-    println!("Found {} input audio devices", input_devices.len());
+    log::info!("Found {} input audio devices", input_devices.len());
 
     *CACHED_INPUT_DEVICES.lock().unwrap() = Some((input_devices.clone(), Instant::now()));
     Ok(input_devices)
@@ -625,7 +837,10 @@ fn detect_pulse_fallback_devices() -> Result<Vec<AudioDevice>, String> {
 
 // Keep for system detection, but simplified
 pub fn detect_audio_system() -> String {
-    if Command::new("pw-cli").args(["info", "0"]).output().is_ok()
+    if pipewire_command("pw-cli")
+        .args(["info", "0"])
+        .output()
+        .is_ok()
         || Command::new("systemctl")
             .args(["--user", "is-active", "pipewire"])
             .output()
@@ -651,45 +866,53 @@ pub fn detect_audio_system() -> String {
 
 // ========== REST OF ORIGINAL FUNCTIONS (unchanged) ==========
 
+/// Reads the running graph rate/quantum. Fails instead of inventing values
+/// when PipeWire can't be queried, so callers can say detection failed.
 pub fn detect_current_audio_settings() -> Result<AudioSettings, String> {
     if let Some(cached) = CACHED_AUDIO_SETTINGS.lock().unwrap().as_ref()
         && cached.1.elapsed() < CACHE_DURATION
     {
-        println!("DEBUG: Returning cached audio settings");
+        log::debug!("Returning cached audio settings");
         return Ok(cached.0.clone());
     }
 
-    println!("=== DEBUG: Starting audio settings detection ===");
+    log::info!("=== DEBUG: Starting audio settings detection ===");
 
-    if let Ok(output) = Command::new("pw-cli").args(["info", "0"]).output() {
-        println!("DEBUG: pw-cli command executed, status: {}", output.status);
-        if output.status.success() {
-            let output_str = String::from_utf8_lossy(&output.stdout);
-            let (sample_rate, bit_depth, buffer_size) = parse_pipewire_settings(&output_str);
-            println!(
-                "DEBUG: Parsed values - {}Hz/{}bit/{}samples",
-                sample_rate, bit_depth, buffer_size
-            );
-
-            let settings =
-                AudioSettings::new(sample_rate, bit_depth, buffer_size, "default".to_string());
+    let output = pipewire_command("pw-cli")
+        .args(["info", "0"])
+        .output()
+        .map_err(|e| format!("Failed to run pw-cli: {}", e))?;
+    log::debug!("pw-cli command executed, status: {}", output.status);
 
-            *CACHED_AUDIO_SETTINGS.lock().unwrap() = Some((settings.clone(), Instant::now()));
-            return Ok(settings);
-        }
+    if !output.status.success() {
+        return Err(format!(
+            "pw-cli info failed ({}), PipeWire may not be running",
+            output.status
+        ));
     }
 
-    println!("DEBUG: Falling back to default values");
-    let settings = AudioSettings::new(48000, 24, 512, "default".to_string());
+    let output_str = String::from_utf8_lossy(&output.stdout);
+    let (sample_rate, bit_depth, buffer_size) = parse_pipewire_settings(&output_str)
+        .ok_or_else(|| "PipeWire did not report default.clock.rate/quantum".to_string())?;
+    log::debug!(
+        "Parsed values - {}Hz/{}bit/{}samples",
+        sample_rate,
+        bit_depth,
+        buffer_size
+    );
+
+    let settings = AudioSettings::new(sample_rate, bit_depth, buffer_size, "default".to_string());
 
     *CACHED_AUDIO_SETTINGS.lock().unwrap() = Some((settings.clone(), Instant::now()));
     Ok(settings)
 }
 
-fn parse_pipewire_settings(output: &str) -> (u32, u32, u32) {
-    let mut sample_rate = 48000;
+/// Returns None unless both clock rate and quantum were reported. The graph
+/// itself has no sample format, so bit depth falls back to 24 when absent.
+fn parse_pipewire_settings(output: &str) -> Option<(u32, u32, u32)> {
+    let mut sample_rate = None;
     let mut bit_depth = 24;
-    let mut buffer_size = 512;
+    let mut buffer_size = None;
 
     for line in output.lines() {
         let trimmed = line.trim();
@@ -706,7 +929,7 @@ fn parse_pipewire_settings(output: &str) -> (u32, u32, u32) {
                 .trim_start_matches('*')
                 .trim();
             if let Ok(rate) = rate_clean.parse::<u32>() {
-                sample_rate = rate;
+                sample_rate = Some(rate);
             }
         }
 
@@ -743,12 +966,12 @@ fn parse_pipewire_settings(output: &str) -> (u32, u32, u32) {
                 .trim_start_matches('*')
                 .trim();
             if let Ok(quantum) = quantum_clean.parse::<u32>() {
-                buffer_size = quantum;
+                buffer_size = Some(quantum);
             }
         }
     }
 
-    (sample_rate, bit_depth, buffer_size)
+    Some((sample_rate?, bit_depth, buffer_size?))
 }
 
 pub fn detect_audio_device() -> Result<String, String> {
@@ -773,7 +996,7 @@ pub fn detect_output_audio_device() -> Result<String, String> {
     if let Some(cached) = CACHED_CURRENT_OUTPUT_DEVICE.lock().unwrap().as_ref()
         && cached.1.elapsed() < CACHE_DURATION
     {
-        println!("DEBUG: Returning cached output device");
+        log::debug!("Returning cached output device");
         return Ok(cached.0.clone());
     }
 
@@ -804,7 +1027,7 @@ pub fn detect_input_audio_device() -> Result<String, String> {
     if let Some(cached) = CACHED_CURRENT_INPUT_DEVICE.lock().unwrap().as_ref()
         && cached.1.elapsed() < CACHE_DURATION
     {
-        println!("DEBUG: Returning cached input device");
+        log::debug!("Returning cached input device");
         return Ok(cached.0.clone());
     }
 
@@ -831,198 +1054,1789 @@ pub fn detect_input_audio_device() -> Result<String, String> {
     Ok(result)
 }
 
-pub fn resolve_pipewire_device_name(node_id: &str) -> Result<String, String> {
-    let output = Command::new("pw-cli")
-        .args(["info", node_id])
-        .output()
-        .map_err(|e| format!("Failed to query PipeWire node {}: {}", node_id, e))?;
-
-    if !output.status.success() {
-        return Err(format!("PipeWire query failed for node {}", node_id));
-    }
-
-    let output_str = String::from_utf8_lossy(&output.stdout);
+/// Whether the running server changes the graph rate without a restart
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateSwitching {
+    /// clock.force-rate set through the settings metadata is honored live
+    Live,
+    /// The server ignores runtime rate changes; PipeWire must be restarted
+    RestartRequired,
+}
 
-    for line in output_str.lines() {
-        if line.contains("device.name")
-            && line.contains('=')
-            && let Some(name_part) = line.split('=').nth(1)
-        {
-            let name = name_part.trim().trim_matches('"').to_string();
-            if !name.is_empty() {
-                return Ok(name);
+impl RateSwitching {
+    pub fn description(&self) -> &'static str {
+        match self {
+            RateSwitching::Live => "Sample rate changes can be applied live (no restart needed)",
+            RateSwitching::RestartRequired => {
+                "Sample rate changes need an audio service restart on this system"
             }
         }
     }
+}
 
-    for line in output_str.lines() {
-        if line.contains("node.name")
-            && line.contains('=')
-            && let Some(name_part) = line.split('=').nth(1)
-        {
-            let name = name_part.trim().trim_matches('"').to_string();
-            if !name.is_empty() {
-                return Ok(name);
-            }
-        }
+/// Probes whether the server honors clock.force-rate at runtime. The result
+/// is cached for the session; a failed probe counts as RestartRequired.
+pub fn detect_rate_switching() -> RateSwitching {
+    // Held across the probe so concurrent callers don't probe twice
+    let mut cached = CACHED_RATE_SWITCHING.lock().unwrap();
+    if let Some(result) = *cached {
+        return result;
     }
 
-    Err(format!(
-        "Could not find device name for PipeWire node {}",
-        node_id
-    ))
+    let result = probe_rate_switching().unwrap_or_else(|e| {
+        log::debug!("Rate switching probe failed: {}", e);
+        RateSwitching::RestartRequired
+    });
+    log::debug!("Rate switching support: {:?}", result);
+
+    *cached = Some(result);
+    result
 }
 
-pub fn resolve_pulse_device_name(pulse_id: &str) -> Result<String, String> {
-    let output = Command::new("pactl")
-        .args(["list", "sinks", "short"])
-        .output()
-        .map_err(|e| format!("Failed to query PulseAudio devices: {}", e))?;
+/// Desktop effects apps that route audio through their own virtual
+/// sink/source: (process name, display name)
+const EFFECTS_MANAGERS: &[(&str, &str)] = &[
+    ("easyeffects", "EasyEffects"),
+    ("pulseeffects", "PulseEffects"),
+    ("jamesdsp", "JamesDSP"),
+];
+
+/// Lowercase node name prefixes of the virtual devices those apps create
+const EFFECTS_NODE_PREFIXES: &[&str] = &["easyeffects_", "pulseeffects_", "jamesdsp_"];
+
+/// Running effects apps and whether their virtual devices are the defaults
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EffectsManagers {
+    /// Display names of the running effects apps
+    pub running: Vec<String>,
+    /// Default sink/source node names, set only when they belong to an effects app
+    pub default_sink: Option<String>,
+    pub default_source: Option<String>,
+}
 
-    if output.status.success() {
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        for line in output_str.lines() {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 2 && parts[0] == pulse_id {
-                return Ok(parts[1].to_string());
-            }
-        }
-    }
+impl EffectsManagers {
+    /// Note for the output or input side, None when no effects app is involved
+    pub fn warning(&self, input: bool) -> Option<String> {
+        let (default, kind) = if input {
+            (&self.default_source, "input")
+        } else {
+            (&self.default_sink, "output")
+        };
+        let running = self.running.join(", ");
+        let owner = if running.is_empty() {
+            "an effects app"
+        } else {
+            running.as_str()
+        };
 
-    if let Ok(output) = Command::new("pactl")
-        .args(["list", "sources", "short"])
-        .output()
-    {
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        for line in output_str.lines() {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 2 && parts[0] == pulse_id {
-                return Ok(parts[1].to_string());
-            }
+        match default {
+            Some(node) => Some(format!(
+                "⚠ The default {} is {}, a virtual device of {}. Apps play into it and it \
+                 forwards to your interface, so set the interface as the {} device inside {} \
+                 too.",
+                kind, node, owner, kind, owner
+            )),
+            None if !running.is_empty() => Some(format!(
+                "Note: {} is running and may switch the default {} to its own virtual device.",
+                running, kind
+            )),
+            None => None,
         }
     }
+}
 
-    Err(format!("PulseAudio device {} not found", pulse_id))
+/// True for the virtual sinks/sources created by EasyEffects, PulseEffects or JamesDSP
+pub fn is_effects_node(node_name: &str) -> bool {
+    let name = node_name.to_lowercase();
+    EFFECTS_NODE_PREFIXES
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
 }
 
-pub fn extract_actual_device_name(device_info: &str) -> Option<String> {
-    let cleaned = device_info
-        .replace("PipeWire:", "")
-        .replace("PulseAudio:", "")
-        .replace("ALSA:", "")
-        .trim()
-        .to_string();
+/// Looks for running effects apps and checks whether the default sink and
+/// source are their virtual devices
+pub fn detect_effects_managers() -> EffectsManagers {
+    let running = EFFECTS_MANAGERS
+        .iter()
+        .filter(|(process, _)| crate::watch::is_process_running(process))
+        .map(|(_, name)| name.to_string())
+        .collect();
 
-    if cleaned.is_empty() {
-        None
-    } else {
-        Some(cleaned)
+    let default_node = |query: &str| pactl_default_node(query).filter(|node| is_effects_node(node));
+
+    EffectsManagers {
+        running,
+        default_sink: default_node("get-default-sink"),
+        default_source: default_node("get-default-source"),
     }
 }
 
-pub fn detect_high_performance_devices() -> Result<Vec<AudioDevice>, String> {
-    let mut devices = detect_output_audio_devices()?;
+/// Forces another allowed rate, checks whether the graph actually switched
+/// to it and restores the previous force-rate, so the probe only changes the
+/// running rate for a moment
+fn probe_rate_switching() -> Result<RateSwitching, String> {
+    let original = read_settings_metadata("clock.force-rate")?;
+    let current_rate = read_graph_rate()?;
+
+    // A graph limited to one rate can't switch without a config change
+    let allowed_rates = read_settings_metadata("clock.allowed-rates")?
+        .map(|value| parse_allowed_rates(&value))
+        .unwrap_or_default();
+    let Some(probe_rate) = allowed_rates.into_iter().find(|&rate| rate != current_rate) else {
+        return Ok(RateSwitching::RestartRequired);
+    };
 
-    devices.retain(|device| {
-        let name_lower = device.name.to_lowercase();
-        let desc_lower = device.description.to_lowercase();
-        let id_lower = device.id.to_lowercase();
+    write_settings_metadata("clock.force-rate", &probe_rate.to_string())?;
+    // The driver picks the new rate up on its next cycle
+    std::thread::sleep(Duration::from_millis(200));
+    let switched_rate = read_graph_rate();
 
-        desc_lower.contains("usb") ||
-        name_lower.contains("usb") ||
-        id_lower.contains("usb") ||
-        desc_lower.contains("firewire") ||
-        desc_lower.contains("thunderbolt") ||
-        desc_lower.contains("pci") ||
-        desc_lower.contains("external") ||
-        desc_lower.contains("interface") ||
-        desc_lower.contains("studio") ||
-        desc_lower.contains("pro") ||
-        // Professional audio brands
-        desc_lower.contains("focusrite") ||
-        desc_lower.contains("presonus") ||
-        desc_lower.contains("behringer") ||
-        desc_lower.contains("motu") ||
-        desc_lower.contains("rme") ||
-        desc_lower.contains("universal audio") ||
-        desc_lower.contains("audient") ||
-        desc_lower.contains("steinberg") ||
-        desc_lower.contains("tascam") ||
-        desc_lower.contains("zoom") ||
-        desc_lower.contains("arturia") ||
-        desc_lower.contains("native instruments") ||
-        desc_lower.contains("akai") ||
-        desc_lower.contains("novation") ||
-        desc_lower.contains("mackie") ||
-        desc_lower.contains("onyx") ||
-        desc_lower.contains("roland") ||
-        desc_lower.contains("yamaha") ||
-        desc_lower.contains("allen heath") ||
-        desc_lower.contains("ssl") ||
-        desc_lower.contains("solid state logic") ||
-        desc_lower.contains("antelope") ||
-        desc_lower.contains("apogee") ||
-        desc_lower.contains("lynx") ||
-        desc_lower.contains("ferrofish") ||
-        desc_lower.contains("merging") ||
-        desc_lower.contains("neve") ||
-        desc_lower.contains("api") ||
-        desc_lower.contains("chandler limited") ||
-        desc_lower.contains("dangerous music") ||
-        desc_lower.contains("burl") ||
-        desc_lower.contains("cranborne") ||
-        desc_lower.contains("heritage audio") ||
-        desc_lower.contains("black lion") ||
-        desc_lower.contains("quantum") ||
-        desc_lower.contains("clarett") ||
-        desc_lower.contains("scarlett") ||
-        desc_lower.contains("vocaster")
-    });
+    let restore = original.unwrap_or_else(|| "0".to_string());
+    if let Err(e) = write_settings_metadata("clock.force-rate", &restore) {
+        log::warn!("Failed to restore clock.force-rate to {}: {}", restore, e);
+    }
 
-    println!("Found {} high-performance audio devices", devices.len());
-    Ok(devices)
+    Ok(if switched_rate? == probe_rate {
+        RateSwitching::Live
+    } else {
+        RateSwitching::RestartRequired
+    })
 }
 
-#[derive(Clone, Debug)]
-pub struct DeviceCapabilities {
-    pub sample_rates: Vec<u32>,
-    pub formats: Vec<String>,
-    pub buffer_sizes: Vec<u32>,
-    pub min_buffer_size: u32,
-    pub max_buffer_size: u32,
-    pub period_sizes: Vec<u32>,
+/// Rate the graph runs at right now, bypassing the settings cache
+fn read_graph_rate() -> Result<u32, String> {
+    let output = pipewire_command("pw-cli")
+        .args(["info", "0"])
+        .output()
+        .map_err(|e| format!("Failed to run pw-cli: {}", e))?;
+
+    parse_pipewire_settings(&String::from_utf8_lossy(&output.stdout))
+        .map(|(rate, _, _)| rate)
+        .ok_or_else(|| "PipeWire did not report default.clock.rate".to_string())
 }
 
-pub fn get_device_capabilities(device_id: &str) -> Result<DeviceCapabilities, String> {
-    let device_pattern = if device_id == "default" {
-        if let Ok(device_info) = detect_output_audio_device() {
-            extract_actual_device_name(&device_info).unwrap_or_else(|| "alsa_output.*".to_string())
-        } else {
-            "alsa_output.*".to_string()
-        }
-    } else {
-        device_id.to_string()
-    };
+/// Parses a clock.allowed-rates value such as `[ 44100 48000 96000 ]`
+fn parse_allowed_rates(value: &str) -> Vec<u32> {
+    value
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|rate| rate.parse().ok())
+        .collect()
+}
 
-    if let Ok(output) = Command::new("pw-cli")
-        .args(["list-objects", "Node"])
+/// Reads one key of the "settings" metadata; None when the key is unset.
+/// Fails when the server has no settings metadata at all (older PipeWire).
+fn read_settings_metadata(key: &str) -> Result<Option<String>, String> {
+    let output = pipewire_command("pw-metadata")
+        .args(["-n", "settings", "0", key])
         .output()
-    {
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        return parse_device_capabilities(&output_str, &device_pattern);
+        .map_err(|e| format!("Failed to run pw-metadata: {}", e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() || !stdout.contains("\"settings\"") {
+        return Err("PipeWire has no settings metadata".to_string());
     }
 
-    Ok(DeviceCapabilities {
-        sample_rates: vec![44100, 48000, 96000, 192000],
-        formats: vec![
-            "S16LE".to_string(),
-            "S24LE".to_string(),
-            "S32LE".to_string(),
-        ],
+    Ok(parse_metadata_value(&stdout, key))
+}
+
+fn write_settings_metadata(key: &str, value: &str) -> Result<(), String> {
+    let status = pipewire_command("pw-metadata")
+        .args(["-n", "settings", "0", key, value])
+        .status()
+        .map_err(|e| format!("Failed to run pw-metadata: {}", e))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("pw-metadata could not set {}", key))
+    }
+}
+
+/// Switches the running graph to `quantum` (and `sample_rate`, when given)
+/// through the settings metadata. No config is written and nothing restarts;
+/// the change lasts until `clear_live_settings` or the next PipeWire restart.
+pub fn apply_live_settings(quantum: u32, sample_rate: Option<u32>) -> Result<(), String> {
+    // Fails on servers without settings metadata, before anything is changed
+    read_settings_metadata("clock.force-quantum")?;
+
+    write_settings_metadata("clock.force-quantum", &quantum.to_string())?;
+    if let Some(rate) = sample_rate {
+        write_settings_metadata("clock.force-rate", &rate.to_string())?;
+    }
+    clear_cache();
+    Ok(())
+}
+
+/// Drops the live overrides so the configured quantum and rate apply again
+pub fn clear_live_settings() -> Result<(), String> {
+    write_settings_metadata("clock.force-quantum", "0")?;
+    write_settings_metadata("clock.force-rate", "0")?;
+    clear_cache();
+    Ok(())
+}
+
+/// Extracts the value from a pw-metadata line such as
+/// `update: id:0 key:'clock.force-rate' value:'48000' type:''`
+fn parse_metadata_value(output: &str, key: &str) -> Option<String> {
+    let key_field = format!("key:'{}'", key);
+    let line = output.lines().find(|line| line.contains(&key_field))?;
+    let value = line.split("value:'").nth(1)?.split('\'').next()?;
+
+    Some(value.to_string())
+}
+
+/// wpctl target for the default output device
+pub const DEFAULT_SINK_TARGET: &str = "@DEFAULT_AUDIO_SINK@";
+/// wpctl target for the default input device
+pub const DEFAULT_SOURCE_TARGET: &str = "@DEFAULT_AUDIO_SOURCE@";
+
+/// Volume and mute state of a PipeWire node as reported by wpctl
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NodeVolume {
+    /// Linear volume, 1.0 = 100% (can exceed 1.0 when boosted)
+    pub volume: f64,
+    pub muted: bool,
+}
+
+impl NodeVolume {
+    pub fn percent(&self) -> u32 {
+        (self.volume * 100.0).round() as u32
+    }
+}
+
+/// Reads the volume of a node id or wpctl target such as `@DEFAULT_AUDIO_SINK@`
+pub fn get_node_volume(target: &str) -> Result<NodeVolume, String> {
+    let output = pipewire_command("wpctl")
+        .args(["get-volume", target])
+        .output()
+        .map_err(|e| format!("Failed to run wpctl: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "wpctl get-volume {} failed: {}",
+            target,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    parse_wpctl_volume(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| format!("Unexpected wpctl output for {}", target))
+}
+
+/// Sets the linear volume of a node id or wpctl target
+pub fn set_node_volume(target: &str, volume: f64) -> Result<(), String> {
+    let volume = volume.clamp(0.0, 1.5);
+    let status = pipewire_command("wpctl")
+        .args(["set-volume", target, &format!("{:.2}", volume)])
+        .status()
+        .map_err(|e| format!("Failed to run wpctl: {}", e))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("wpctl set-volume {} failed", target))
+    }
+}
+
+/// Mutes or unmutes a node id or wpctl target
+pub fn set_node_mute(target: &str, muted: bool) -> Result<(), String> {
+    let status = pipewire_command("wpctl")
+        .args(["set-mute", target, if muted { "1" } else { "0" }])
+        .status()
+        .map_err(|e| format!("Failed to run wpctl: {}", e))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("wpctl set-mute {} failed", target))
+    }
+}
+
+/// Default devices and their volumes, captured before a service restart so
+/// the restart does not reset the user's mixer
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MixerState {
+    /// Node names rather than ids, the ids change with the restart
+    pub default_sink: Option<String>,
+    pub default_source: Option<String>,
+    pub sink_volume: Option<NodeVolume>,
+    pub source_volume: Option<NodeVolume>,
+}
+
+impl MixerState {
+    pub fn capture() -> Self {
+        MixerState {
+            default_sink: pactl_default_node("get-default-sink"),
+            default_source: pactl_default_node("get-default-source"),
+            sink_volume: get_node_volume(DEFAULT_SINK_TARGET).ok(),
+            source_volume: get_node_volume(DEFAULT_SOURCE_TARGET).ok(),
+        }
+    }
+
+    /// Puts back whatever changed; returns what could not be restored
+    pub fn restore(&self) -> Vec<String> {
+        let mut failures = Vec::new();
+
+        for (node, kind) in [
+            (&self.default_sink, "sink"),
+            (&self.default_source, "source"),
+        ] {
+            if let Some(node) = node
+                && pactl_default_node(&format!("get-default-{}", kind)).as_ref() != Some(node)
+                && let Err(e) = restore_default_node(&format!("set-default-{}", kind), node)
+            {
+                failures.push(e);
+            }
+        }
+
+        // Volumes go to whatever is default now, i.e. after the step above
+        for (volume, target) in [
+            (self.sink_volume, DEFAULT_SINK_TARGET),
+            (self.source_volume, DEFAULT_SOURCE_TARGET),
+        ] {
+            let Some(volume) = volume else { continue };
+            let current = get_node_volume(target).ok();
+            if current.map(|current| current.percent()) != Some(volume.percent())
+                && let Err(e) = set_node_volume(target, volume.volume)
+            {
+                failures.push(e);
+            }
+            if current.map(|current| current.muted) != Some(volume.muted)
+                && let Err(e) = set_node_mute(target, volume.muted)
+            {
+                failures.push(e);
+            }
+        }
+
+        failures
+    }
+}
+
+fn pactl_default_node(query: &str) -> Option<String> {
+    Command::new("pactl")
+        .arg(query)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|node| !node.is_empty())
+}
+
+/// Devices come back one by one after a restart, so this retries briefly
+fn restore_default_node(command: &str, node: &str) -> Result<(), String> {
+    for _ in 0..6 {
+        let status = Command::new("pactl")
+            .args([command, node])
+            .status()
+            .map_err(|e| format!("Failed to run pactl: {}", e))?;
+
+        if status.success() {
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_millis(500));
+    }
+
+    Err(format!("{} did not come back, default left as is", node))
+}
+
+/// Parses `wpctl get-volume` output, e.g. "Volume: 0.40 [MUTED]"
+fn parse_wpctl_volume(output: &str) -> Option<NodeVolume> {
+    let line = output.lines().find(|line| line.starts_with("Volume:"))?;
+    let volume = line
+        .trim_start_matches("Volume:")
+        .split_whitespace()
+        .next()?
+        .parse::<f64>()
+        .ok()?;
+
+    Some(NodeVolume {
+        volume,
+        muted: line.contains("[MUTED]"),
+    })
+}
+
+/// Timing of one active stream as scheduled by PipeWire. A stream always runs
+/// at the quantum and rate of the driver it follows, whatever it asked for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamTiming {
+    pub name: String,
+    /// Device (driver node) the stream follows
+    pub driver: String,
+    /// Quantum and rate the stream actually runs at (its driver's)
+    pub quantum: u32,
+    pub rate: u32,
+    /// What the client asked for (node.latency), if anything
+    pub requested_quantum: Option<u32>,
+    pub requested_rate: Option<u32>,
+}
+
+impl StreamTiming {
+    /// Whether this stream does not run at the global default quantum/rate
+    pub fn differs_from(&self, default_quantum: u32, default_rate: u32) -> bool {
+        self.quantum != default_quantum
+            || self.rate != default_rate
+            || self
+                .requested_quantum
+                .is_some_and(|quantum| quantum != self.quantum)
+    }
+}
+
+/// DSP load (busy time / quantum) above which a node is close to xruns
+pub const DSP_LOAD_WARNING: f64 = 0.8;
+
+/// DSP load of one running node in the last pw-top sample
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeLoad {
+    pub id: u32,
+    pub name: String,
+    /// Driver the node follows; None when the node is a driver itself, whose
+    /// load then covers the whole graph cycle
+    pub driver: Option<String>,
+    pub quantum: u32,
+    pub rate: u32,
+    /// Processing time as a fraction of the quantum (pw-top B/Q)
+    pub load: f64,
+    /// Xruns and other errors counted for the node
+    pub errors: u32,
+}
+
+impl NodeLoad {
+    pub fn is_overloaded(&self) -> bool {
+        self.load >= DSP_LOAD_WARNING
+    }
+}
+
+/// Streams and node loads from one pw-top run
+#[derive(Debug, Clone, Default)]
+pub struct PwTopSample {
+    pub streams: Vec<StreamTiming>,
+    pub loads: Vec<NodeLoad>,
+    /// ERR counter (xruns and other errors) per node id, running or not
+    pub node_errors: HashMap<u32, u32>,
+}
+
+/// Reads the running streams from `pw-top` in batch mode (two samples, the
+/// first one only primes the statistics)
+pub fn detect_stream_timings() -> Result<Vec<StreamTiming>, String> {
+    sample_pw_top().map(|sample| sample.streams)
+}
+
+/// Runs `pw-top` once and parses both the stream timings and the node loads
+pub fn sample_pw_top() -> Result<PwTopSample, String> {
+    let output = pipewire_command("pw-top")
+        .args(["-b", "-n", "2"])
+        .output()
+        .map_err(|e| format!("Failed to run pw-top: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "pw-top failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(PwTopSample {
+        streams: parse_pw_top(&stdout),
+        loads: parse_pw_top_loads(&stdout),
+        node_errors: parse_pw_top_errors(&stdout),
+    })
+}
+
+/// Parses the last sample of `pw-top -b`. Rows are
+/// `S ID QUANT RATE WAIT BUSY W/Q B/Q ERR FORMAT NAME`, followers carry a
+/// "+" before the name and are listed right after their driver.
+fn parse_pw_top(output: &str) -> Vec<StreamTiming> {
+    let mut streams = Vec::new();
+    let mut driver: Option<(String, u32, u32)> = None;
+
+    for line in output.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 10 {
+            continue;
+        }
+
+        // Every sample starts with a header row
+        if fields[1] == "ID" {
+            streams.clear();
+            driver = None;
+            continue;
+        }
+
+        let (Ok(quantum), Ok(rate)) = (fields[2].parse::<u32>(), fields[3].parse::<u32>()) else {
+            continue;
+        };
+        let name = fields[fields.len() - 1].to_string();
+        let is_follower = fields[fields.len() - 2] == "+";
+
+        if !is_follower {
+            driver = Some((name, quantum, rate));
+            continue;
+        }
+
+        // Only streams that are actually running
+        if fields[0] != "R" {
+            continue;
+        }
+
+        if let Some((driver_name, driver_quantum, driver_rate)) = &driver {
+            streams.push(StreamTiming {
+                name,
+                driver: driver_name.clone(),
+                quantum: *driver_quantum,
+                rate: *driver_rate,
+                requested_quantum: (quantum > 0).then_some(quantum),
+                requested_rate: (rate > 0).then_some(rate),
+            });
+        }
+    }
+
+    streams
+}
+
+/// Node loads from the last sample of `pw-top -b`, drivers and followers
+/// alike. Nodes that are not running or report no busy time are left out.
+fn parse_pw_top_loads(output: &str) -> Vec<NodeLoad> {
+    let mut loads = Vec::new();
+    let mut driver: Option<String> = None;
+
+    for line in output.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 10 {
+            continue;
+        }
+
+        if fields[1] == "ID" {
+            loads.clear();
+            driver = None;
+            continue;
+        }
+
+        let (Ok(id), Ok(quantum), Ok(rate)) = (
+            fields[1].parse::<u32>(),
+            fields[2].parse::<u32>(),
+            fields[3].parse::<u32>(),
+        ) else {
+            continue;
+        };
+        let name = fields[fields.len() - 1].to_string();
+        let is_follower = fields[fields.len() - 2] == "+";
+
+        let follows = if is_follower {
+            driver.clone()
+        } else {
+            driver = Some(name.clone());
+            None
+        };
+
+        if fields[0] != "R" {
+            continue;
+        }
+        let Ok(load) = fields[7].parse::<f64>() else {
+            continue;
+        };
+
+        loads.push(NodeLoad {
+            id,
+            name,
+            driver: follows,
+            quantum,
+            rate,
+            load,
+            errors: fields[8].parse().unwrap_or(0),
+        });
+    }
+
+    loads
+}
+
+/// ERR column of every node in the last sample of `pw-top -b`, by node id.
+/// pw-top counts errors since the node was created.
+fn parse_pw_top_errors(output: &str) -> HashMap<u32, u32> {
+    let mut errors = HashMap::new();
+
+    for line in output.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 10 {
+            continue;
+        }
+
+        if fields[1] == "ID" {
+            errors.clear();
+            continue;
+        }
+
+        if let (Ok(id), Ok(count)) = (fields[1].parse::<u32>(), fields[8].parse::<u32>()) {
+            errors.insert(id, count);
+        }
+    }
+
+    errors
+}
+
+/// Whether the device behind `device_id` is still known to PipeWire, checked
+/// with pw-dump right before applying. "default", ALSA and Pulse ids cannot be
+/// matched against the dump and count as present.
+pub fn is_device_present(device_id: &str) -> Result<bool, String> {
+    if device_id == "default" || device_id.starts_with("alsa:") || device_id.starts_with("pulse:") {
+        return Ok(true);
+    }
+
+    let output = pipewire_command("pw-dump")
+        .output()
+        .map_err(|e| format!("Failed to run pw-dump: {}", e))?;
+
+    if !output.status.success() {
+        return Err("pw-dump failed".to_string());
+    }
+
+    pw_dump_contains_device(&String::from_utf8_lossy(&output.stdout), device_id)
+}
+
+/// Looks for `device_id` ("pipewire:<object id>" or a node/device name) in pw-dump JSON
+fn pw_dump_contains_device(dump: &str, device_id: &str) -> Result<bool, String> {
+    let objects: Vec<serde_json::Value> =
+        serde_json::from_str(dump).map_err(|e| format!("Failed to parse pw-dump output: {}", e))?;
+
+    let object_id = device_id
+        .strip_prefix("pipewire:")
+        .and_then(|id| id.parse::<u64>().ok());
+
+    Ok(objects.iter().any(|object| {
+        if let Some(object_id) = object_id {
+            return object["id"].as_u64() == Some(object_id);
+        }
+
+        let props = &object["info"]["props"];
+        ["node.name", "device.name"]
+            .iter()
+            .any(|key| props[*key].as_str() == Some(device_id))
+    }))
+}
+
+/// Card profile that exposes every channel of the interface as one raw node,
+/// without ALSA UCM mixing or channel mapping
+pub const PRO_AUDIO_PROFILE: &str = "pro-audio";
+
+/// One entry of a card's EnumProfile param
+#[derive(Debug, Clone, PartialEq)]
+pub struct CardProfile {
+    pub index: u32,
+    pub name: String,
+    pub description: String,
+    /// False when PipeWire reports the profile as unavailable (e.g. nothing plugged in)
+    pub available: bool,
+}
+
+/// The profiles of the card (PipeWire Device) behind an audio node
+#[derive(Debug, Clone, PartialEq)]
+pub struct CardProfiles {
+    /// PipeWire object id of the card, what `wpctl set-profile` expects
+    pub device_object_id: u32,
+    /// device.name of the card, stable across restarts unlike the object id
+    pub device_name: String,
+    pub device_description: String,
+    pub profiles: Vec<CardProfile>,
+    /// Name of the active profile
+    pub active: Option<String>,
+}
+
+impl CardProfiles {
+    pub fn find(&self, name: &str) -> Option<&CardProfile> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+
+    /// The pro-audio profile, if the card offers it and it is not active yet
+    pub fn pending_pro_audio(&self) -> Option<&CardProfile> {
+        if self.active.as_deref() == Some(PRO_AUDIO_PROFILE) {
+            return None;
+        }
+        self.find(PRO_AUDIO_PROFILE)
+            .filter(|profile| profile.available)
+    }
+}
+
+/// Profiles of the card that `device_id` ("default", "pipewire:<object id>",
+/// a node name or a card's device.name) belongs to
+pub fn detect_card_profiles(device_id: &str) -> Result<CardProfiles, String> {
+    parse_card_profiles(&run_pw_dump()?, device_id)
+}
+
+/// Switches the card named `device_name` to `profile_name`. Both are looked up
+/// again first, since object ids and profile indexes are not stable.
+pub fn set_card_profile(device_name: &str, profile_name: &str) -> Result<(), String> {
+    let card = detect_card_profiles(device_name)?;
+    let profile = card.find(profile_name).ok_or_else(|| {
+        format!(
+            "{} has no \"{}\" profile",
+            card.device_description, profile_name
+        )
+    })?;
+
+    log::debug!(
+        "Switching card {} to profile {} (index {})",
+        card.device_name,
+        profile.name,
+        profile.index
+    );
+
+    let output = pipewire_command("wpctl")
+        .args([
+            "set-profile",
+            &card.device_object_id.to_string(),
+            &profile.index.to_string(),
+        ])
+        .output()
+        .map_err(|e| format!("Failed to run wpctl: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "wpctl set-profile failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Waits for the card to publish an output node in its new profile and returns
+/// it as a "pipewire:<object id>" device id. The old node goes away on a
+/// profile switch, so ids picked before the switch no longer work.
+pub fn wait_for_card_output(device_name: &str) -> Result<String, String> {
+    let deadline = Instant::now() + Duration::from_secs(3);
+
+    loop {
+        let dump = run_pw_dump()?;
+        if let Some(node_id) = find_card_output_node(&dump, device_name)? {
+            return Ok(format!("pipewire:{}", node_id));
+        }
+        if Instant::now() >= deadline {
+            return Err(format!(
+                "{} did not come back after the profile switch",
+                device_name
+            ));
+        }
+        std::thread::sleep(Duration::from_millis(200));
+    }
+}
+
+fn run_pw_dump() -> Result<String, String> {
+    let output = pipewire_command("pw-dump")
+        .output()
+        .map_err(|e| format!("Failed to run pw-dump: {}", e))?;
+
+    if !output.status.success() {
+        return Err("pw-dump failed".to_string());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// How an entered process name compares to the clients connected to PipeWire
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProcessBinaryCheck {
+    /// A running client reports exactly this application.process.binary
+    Matches,
+    /// No exact match, but this running client's binary looks like the one meant
+    Suggestion(String),
+    /// Nothing similar is connected, the app is probably not running
+    NotRunning,
+}
+
+/// Checks `process_name` against the application.process.binary of the live
+/// clients. Rules match the binary exactly, so "reaper" never matches a
+/// client that reports "REAPER" or "reaper6".
+pub fn check_process_binary(
+    process_name: &str,
+    app_name: &str,
+) -> Result<ProcessBinaryCheck, String> {
+    let clients = parse_client_binaries(&run_pw_dump()?)?;
+    Ok(match_process_binary(&clients, process_name, app_name))
+}
+
+/// (application.process.binary, application.name) of every client in pw-dump JSON
+fn parse_client_binaries(dump: &str) -> Result<Vec<(String, String)>, String> {
+    let objects: Vec<serde_json::Value> =
+        serde_json::from_str(dump).map_err(|e| format!("Failed to parse pw-dump output: {}", e))?;
+
+    Ok(objects
+        .iter()
+        .filter(|object| object["type"].as_str() == Some("PipeWire:Interface:Client"))
+        .filter_map(|object| {
+            let props = &object["info"]["props"];
+            let binary = props["application.process.binary"].as_str()?;
+            let name = props["application.name"].as_str().unwrap_or_default();
+            Some((binary.to_string(), name.to_string()))
+        })
+        .collect())
+}
+
+fn match_process_binary(
+    clients: &[(String, String)],
+    process_name: &str,
+    app_name: &str,
+) -> ProcessBinaryCheck {
+    if clients.iter().any(|(binary, _)| binary == process_name) {
+        return ProcessBinaryCheck::Matches;
+    }
+
+    let wanted = process_name.to_lowercase();
+    // Lower is closer: other case, name with a version suffix, same app name, typo
+    let closeness = |binary: &str, name: &str| {
+        let binary = binary.to_lowercase();
+        if binary == wanted {
+            Some(0)
+        } else if binary.contains(&wanted) || wanted.contains(&binary) {
+            Some(1)
+        } else if !app_name.is_empty() && name.eq_ignore_ascii_case(app_name) {
+            Some(2)
+        } else {
+            let distance = crate::utils::edit_distance(&binary, &wanted);
+            (distance <= 2).then_some(2 + distance)
+        }
+    };
+
+    clients
+        .iter()
+        .filter_map(|(binary, name)| closeness(binary, name).map(|score| (score, binary)))
+        .min_by_key(|(score, _)| *score)
+        .map_or(ProcessBinaryCheck::NotRunning, |(_, binary)| {
+            ProcessBinaryCheck::Suggestion(binary.clone())
+        })
+}
+
+/// PipeWire's built-in default.clock.quantum-floor
+pub const PIPEWIRE_DEFAULT_QUANTUM_FLOOR: u32 = 4;
+
+/// Reads the quantum-floor of the running server. Buffer sizes below it are
+/// clamped up to it, whatever the config asks for.
+pub fn detect_quantum_floor() -> Result<u32, String> {
+    parse_quantum_floor(&run_pw_dump()?)
+}
+
+/// clock.quantum-floor from the "settings" metadata, else the core's
+/// default.clock.quantum-floor, else PipeWire's built-in default
+fn parse_quantum_floor(dump: &str) -> Result<u32, String> {
+    let objects: Vec<serde_json::Value> =
+        serde_json::from_str(dump).map_err(|e| format!("Failed to parse pw-dump output: {}", e))?;
+    // Integer in newer dumps, string in some older ones
+    let as_u32 = |value: &serde_json::Value| {
+        value
+            .as_u64()
+            .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+            .map(|floor| floor as u32)
+    };
+
+    let from_metadata = objects
+        .iter()
+        .filter(|object| {
+            object["type"].as_str() == Some("PipeWire:Interface:Metadata")
+                && object["props"]["metadata.name"].as_str() == Some("settings")
+        })
+        .filter_map(|object| object["metadata"].as_array())
+        .flatten()
+        .find(|entry| entry["key"].as_str() == Some("clock.quantum-floor"))
+        .and_then(|entry| as_u32(&entry["value"]));
+
+    let from_core = || {
+        objects
+            .iter()
+            .find(|object| object["type"].as_str() == Some("PipeWire:Interface:Core"))
+            .and_then(|core| as_u32(&core["info"]["props"]["default.clock.quantum-floor"]))
+    };
+
+    Ok(from_metadata
+        .or_else(from_core)
+        .unwrap_or(PIPEWIRE_DEFAULT_QUANTUM_FLOOR))
+}
+
+/// Tells apart devices that show up under the same name, e.g. two identical
+/// USB interfaces: device id -> "card 2, USB port 0:3.2:1.0". Only the given
+/// ids are looked up; devices without details are left out.
+pub fn detect_device_discriminators(device_ids: &[String]) -> HashMap<String, String> {
+    if device_ids.is_empty() {
+        return HashMap::new();
+    }
+
+    // pw-dump is only needed for PipeWire node ids
+    let dump = if device_ids.iter().any(|id| id.starts_with("pipewire:")) {
+        run_pw_dump().unwrap_or_else(|e| {
+            log::debug!("Could not read device details: {}", e);
+            String::new()
+        })
+    } else {
+        String::new()
+    };
+
+    parse_device_discriminators(&dump, device_ids)
+}
+
+fn parse_device_discriminators(dump: &str, device_ids: &[String]) -> HashMap<String, String> {
+    let objects: Vec<serde_json::Value> = serde_json::from_str(dump).unwrap_or_default();
+    let find = |kind: &str, id: u64| {
+        objects.iter().find(|object| {
+            object["type"].as_str() == Some(format!("PipeWire:Interface:{}", kind).as_str())
+                && object["id"].as_u64() == Some(id)
+        })
+    };
+    // Card numbers are integers in newer dumps, strings in older ones
+    let as_text = |value: &serde_json::Value| match value {
+        serde_json::Value::String(text) => Some(text.clone()),
+        serde_json::Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    };
+
+    device_ids
+        .iter()
+        .filter_map(|device_id| {
+            let mut parts = Vec::new();
+
+            if let Some(card) = device_id.strip_prefix("alsa:") {
+                parts.push(format!("card {}", card));
+            } else if let Some(node_id) = device_id
+                .strip_prefix("pipewire:")
+                .and_then(|id| id.parse::<u64>().ok())
+                && let Some(node) = find("Node", node_id)
+            {
+                let props = &node["info"]["props"];
+                if let Some(card) = as_text(&props["api.alsa.card"]) {
+                    parts.push(format!("card {}", card));
+                }
+                let bus_path = props["device.id"]
+                    .as_u64()
+                    .and_then(|id| find("Device", id))
+                    .and_then(|device| device["info"]["props"]["device.bus-path"].as_str());
+                // e.g. "pci-0000:00:14.0-usb-0:3.2:1.0"
+                if let Some((_, port)) = bus_path.and_then(|path| path.split_once("usb-")) {
+                    parts.push(format!("USB port {}", port));
+                }
+            }
+
+            (!parts.is_empty()).then(|| (device_id.clone(), parts.join(", ")))
+        })
+        .collect()
+}
+
+/// 24-bit formats in order of preference: packed-in-32 first, then 3-byte
+/// packed, then a full 32-bit container
+const FORMATS_24_BIT: [&str; 3] = ["S24LE", "S24_3LE", "S32LE"];
+
+/// Sample formats the device's node advertises in its EnumFormat params.
+/// `device` is "default", "pipewire:<id>", "alsa:<card>" or a node.name;
+/// "default" is the default sink for output and the default source for input.
+pub fn detect_device_formats(device: &str, stream_type: &str) -> Result<Vec<String>, String> {
+    parse_node_formats(&run_pw_dump()?, device, stream_type)
+}
+
+fn parse_node_formats(dump: &str, device: &str, stream_type: &str) -> Result<Vec<String>, String> {
+    let objects: Vec<serde_json::Value> =
+        serde_json::from_str(dump).map_err(|e| format!("Failed to parse pw-dump output: {}", e))?;
+    let node = find_node_object(&objects, device, stream_type)?;
+    Ok(node_formats(node))
+}
+
+/// The Node object for `device` in a pw-dump, see `detect_device_formats`
+fn find_node_object<'a>(
+    objects: &'a [serde_json::Value],
+    device: &str,
+    stream_type: &str,
+) -> Result<&'a serde_json::Value, String> {
+    let default_key = if stream_type == "input" {
+        "default.audio.source"
+    } else {
+        "default.audio.sink"
+    };
+    let default_name = objects
+        .iter()
+        .filter(|object| {
+            object["type"].as_str() == Some("PipeWire:Interface:Metadata")
+                && object["props"]["metadata.name"].as_str() == Some("default")
+        })
+        .filter_map(|object| object["metadata"].as_array())
+        .flatten()
+        .find(|entry| entry["key"].as_str() == Some(default_key))
+        .and_then(|entry| entry["value"]["name"].as_str());
+
+    objects
+        .iter()
+        .filter(|object| object["type"].as_str() == Some("PipeWire:Interface:Node"))
+        .find(|object| {
+            let props = &object["info"]["props"];
+            if device == "default" {
+                default_name.is_some() && props["node.name"].as_str() == default_name
+            } else if let Some(id) = device.strip_prefix("pipewire:") {
+                id.parse::<u64>().ok() == object["id"].as_u64()
+            } else if let Some(card) = device.strip_prefix("alsa:") {
+                // Card numbers are integers in newer dumps, strings in older ones
+                let node_card = match &props["api.alsa.card"] {
+                    serde_json::Value::Number(number) => number.to_string(),
+                    value => value.as_str().unwrap_or_default().to_string(),
+                };
+                node_card == card
+            } else {
+                props["node.name"].as_str() == Some(device)
+            }
+        })
+        .ok_or_else(|| format!("No PipeWire node found for device {}", device))
+}
+
+fn node_formats(node: &serde_json::Value) -> Vec<String> {
+    // A fixed format is a string, a choice an array or {"default", "alt1", ...}
+    let mut formats = Vec::new();
+    for param in node["info"]["params"]["EnumFormat"]
+        .as_array()
+        .into_iter()
+        .flatten()
+    {
+        let names: Vec<&str> = match &param["format"] {
+            serde_json::Value::String(name) => vec![name.as_str()],
+            serde_json::Value::Array(names) => names.iter().filter_map(|n| n.as_str()).collect(),
+            serde_json::Value::Object(choice) => {
+                choice.values().filter_map(|n| n.as_str()).collect()
+            }
+            _ => Vec::new(),
+        };
+        for name in names {
+            if !formats.iter().any(|known| known == name) {
+                formats.push(name.to_string());
+            }
+        }
+    }
+
+    formats
+}
+
+/// Object id of the sink (Output) or source (Input) node behind `device`,
+/// which is "pipewire:<id>", "alsa:<card>" or a node.name, e.g. for
+/// `wpctl set-default`
+pub fn resolve_node_id(device: &str, device_type: &DeviceType) -> Result<u32, String> {
+    parse_node_id(&run_pw_dump()?, device, device_type)
+}
+
+fn parse_node_id(dump: &str, device: &str, device_type: &DeviceType) -> Result<u32, String> {
+    let (media_class, stream_type) = match device_type {
+        DeviceType::Output => ("Audio/Sink", "output"),
+        DeviceType::Input => ("Audio/Source", "input"),
+        _ => return Err(format!("{} is neither an output nor an input", device)),
+    };
+
+    let objects: Vec<serde_json::Value> =
+        serde_json::from_str(dump).map_err(|e| format!("Failed to parse pw-dump output: {}", e))?;
+    // A card has a sink and a source node, only one of them is meant
+    let nodes: Vec<serde_json::Value> = objects
+        .into_iter()
+        .filter(|object| object["info"]["props"]["media.class"].as_str() == Some(media_class))
+        .collect();
+
+    let node = find_node_object(&nodes, device, stream_type)
+        .map_err(|_| format!("{} has no {} node", device, stream_type))?;
+    node["id"]
+        .as_u64()
+        .map(|id| id as u32)
+        .ok_or_else(|| format!("PipeWire node of {} has no id", device))
+}
+
+/// Rates offered when a device advertises a range instead of a list
+const STANDARD_SAMPLE_RATES: [u32; 8] =
+    [44100, 48000, 88200, 96000, 176400, 192000, 352800, 384000];
+
+/// Sample rates in the node's EnumFormat params, sorted. A range
+/// ({"default", "min", "max"}) becomes the standard rates inside it.
+fn node_sample_rates(node: &serde_json::Value) -> Vec<u32> {
+    let mut rates = Vec::new();
+    for param in node["info"]["params"]["EnumFormat"]
+        .as_array()
+        .into_iter()
+        .flatten()
+    {
+        let rate = &param["rate"];
+        let found: Vec<u32> = match rate {
+            serde_json::Value::Number(_) => rate.as_u64().into_iter().map(|r| r as u32).collect(),
+            serde_json::Value::Array(values) => values
+                .iter()
+                .filter_map(|r| r.as_u64())
+                .map(|r| r as u32)
+                .collect(),
+            serde_json::Value::Object(choice)
+                if choice.contains_key("min") && choice.contains_key("max") =>
+            {
+                let min = rate["min"].as_u64().unwrap_or(0) as u32;
+                let max = rate["max"].as_u64().unwrap_or(0) as u32;
+                STANDARD_SAMPLE_RATES
+                    .into_iter()
+                    .filter(|r| (min..=max).contains(r))
+                    .collect()
+            }
+            serde_json::Value::Object(choice) => choice
+                .values()
+                .filter_map(|r| r.as_u64())
+                .map(|r| r as u32)
+                .collect(),
+            _ => Vec::new(),
+        };
+        for rate in found {
+            if rate > 0 && !rates.contains(&rate) {
+                rates.push(rate);
+            }
+        }
+    }
+
+    rates.sort();
+    rates
+}
+
+/// SPA format name for `bit_depth` on a device advertising `formats`, plus a
+/// note for the user when the device needs something other than asked for.
+/// With no known formats the plain S16LE/S24LE/S32LE mapping is used.
+pub fn choose_audio_format(bit_depth: u32, formats: &[String]) -> (&'static str, Option<String>) {
+    let supports = |name: &str| formats.iter().any(|format| format == name);
+
+    match bit_depth {
+        16 => ("S16LE", None),
+        32 => ("S32LE", None),
+        _ => match FORMATS_24_BIT.into_iter().find(|name| supports(name)) {
+            Some("S32LE") => (
+                "S32LE",
+                Some(
+                    "The device only accepts 24-bit audio in a 32-bit container, \
+                     so S32LE is used"
+                        .to_string(),
+                ),
+            ),
+            Some(name) => (name, None),
+            None => ("S24LE", None),
+        },
+    }
+}
+
+fn parse_card_profiles(dump: &str, device_id: &str) -> Result<CardProfiles, String> {
+    let objects: Vec<serde_json::Value> =
+        serde_json::from_str(dump).map_err(|e| format!("Failed to parse pw-dump output: {}", e))?;
+    let card = find_card_object(&objects, device_id)?;
+
+    let props = &card["info"]["props"];
+    let params = &card["info"]["params"];
+
+    let profiles = params["EnumProfile"]
+        .as_array()
+        .map(|profiles| {
+            profiles
+                .iter()
+                .filter_map(|profile| {
+                    Some(CardProfile {
+                        index: profile["index"].as_u64()? as u32,
+                        name: profile["name"].as_str()?.to_string(),
+                        description: profile["description"].as_str().unwrap_or("").to_string(),
+                        available: profile["available"].as_str() != Some("no"),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let active = params["Profile"]
+        .as_array()
+        .and_then(|active| active.first())
+        .and_then(|profile| profile["name"].as_str())
+        .map(|name| name.to_string());
+
+    let device_name = props["device.name"]
+        .as_str()
+        .unwrap_or(device_id)
+        .to_string();
+    Ok(CardProfiles {
+        device_object_id: card["id"].as_u64().unwrap_or_default() as u32,
+        device_description: props["device.description"]
+            .as_str()
+            .unwrap_or(&device_name)
+            .to_string(),
+        device_name,
+        profiles,
+        active,
+    })
+}
+
+/// The PipeWire Device object (sound card) that `device_id` refers to or belongs to
+fn find_card_object<'a>(
+    objects: &'a [serde_json::Value],
+    device_id: &str,
+) -> Result<&'a serde_json::Value, String> {
+    let is_type = |object: &serde_json::Value, kind: &str| {
+        object["type"].as_str() == Some(format!("PipeWire:Interface:{}", kind).as_str())
+    };
+
+    // "default" means the sink WirePlumber currently routes to
+    let default_sink = objects
+        .iter()
+        .filter(|object| {
+            is_type(object, "Metadata")
+                && object["props"]["metadata.name"].as_str() == Some("default")
+        })
+        .filter_map(|object| object["metadata"].as_array())
+        .flatten()
+        .find(|entry| entry["key"].as_str() == Some("default.audio.sink"))
+        .and_then(|entry| entry["value"]["name"].as_str());
+    let device_id = match (device_id, default_sink) {
+        ("default", Some(sink)) => sink,
+        ("default", None) => return Err("No default output device is set".to_string()),
+        _ => device_id,
+    };
+    let object_id = device_id
+        .strip_prefix("pipewire:")
+        .and_then(|id| id.parse::<u64>().ok());
+
+    objects
+        .iter()
+        .find(|object| {
+            is_type(object, "Device") && object["info"]["props"]["device.name"] == device_id
+        })
+        .or_else(|| {
+            let node = objects.iter().find(|object| {
+                is_type(object, "Node")
+                    && match object_id {
+                        Some(id) => object["id"].as_u64() == Some(id),
+                        None => object["info"]["props"]["node.name"] == device_id,
+                    }
+            })?;
+            let card_id = node["info"]["props"]["device.id"].as_u64()?;
+            objects
+                .iter()
+                .find(|object| is_type(object, "Device") && object["id"].as_u64() == Some(card_id))
+        })
+        .ok_or_else(|| format!("No sound card found for device {}", device_id))
+}
+
+/// Object id of the card's output node, if it has one right now
+fn find_card_output_node(dump: &str, device_name: &str) -> Result<Option<u64>, String> {
+    let card = parse_card_profiles(dump, device_name)?;
+    let objects: Vec<serde_json::Value> =
+        serde_json::from_str(dump).map_err(|e| format!("Failed to parse pw-dump output: {}", e))?;
+
+    Ok(objects
+        .iter()
+        .find(|object| {
+            let props = &object["info"]["props"];
+            object["type"].as_str() == Some("PipeWire:Interface:Node")
+                && props["device.id"].as_u64() == Some(card.device_object_id as u64)
+                && props["media.class"].as_str() == Some("Audio/Sink")
+        })
+        .and_then(|object| object["id"].as_u64()))
+}
+
+/// One ALSA simple mixer control of a card, as listed by `amixer scontents`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MixerControl {
+    pub name: String,
+    pub index: u32,
+    /// e.g. "pvolume", "pswitch", "enum"
+    pub capabilities: Vec<String>,
+    /// Current value as amixer prints it for the first channel
+    pub value: String,
+    /// On/off state of controls that are only a switch (e.g. "Direct Monitor"
+    /// or phantom power on some interfaces); these can be toggled
+    pub switch: Option<bool>,
+}
+
+impl MixerControl {
+    /// Control id in the form amixer's sset/sget expect
+    pub fn amixer_id(&self) -> String {
+        format!("{},{}", self.name, self.index)
+    }
+}
+
+/// ALSA card number of `device_id` ("alsa:hw:N", "default", "pipewire:<object id>"
+/// or a node/device name), needed for amixer
+pub fn alsa_card_for_device(device_id: &str) -> Result<u32, String> {
+    if let Some(card) = device_id.strip_prefix("alsa:hw:") {
+        return card
+            .split(',')
+            .next()
+            .and_then(|card| card.parse().ok())
+            .ok_or_else(|| format!("Invalid ALSA device {}", device_id));
+    }
+
+    parse_alsa_card(&run_pw_dump()?, device_id)
+}
+
+fn parse_alsa_card(dump: &str, device_id: &str) -> Result<u32, String> {
+    let objects: Vec<serde_json::Value> =
+        serde_json::from_str(dump).map_err(|e| format!("Failed to parse pw-dump output: {}", e))?;
+    let card = find_card_object(&objects, device_id)?;
+
+    // Recent PipeWire publishes it as a string, older versions as a number
+    let value = &card["info"]["props"]["api.alsa.card"];
+    value
+        .as_u64()
+        .map(|card| card as u32)
+        .or_else(|| value.as_str().and_then(|card| card.parse().ok()))
+        .ok_or_else(|| format!("{} is not an ALSA sound card", device_id))
+}
+
+/// Simple mixer controls of ALSA card `card`
+pub fn alsa_mixer_controls(card: u32) -> Result<Vec<MixerControl>, String> {
+    let output = Command::new("amixer")
+        .args(["-c", &card.to_string(), "scontents"])
+        .output()
+        .map_err(|e| format!("Failed to run amixer: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "amixer failed for card {}: {}",
+            card,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(parse_amixer_scontents(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Turns a switch-only control on or off
+pub fn set_alsa_mixer_switch(card: u32, control: &MixerControl, on: bool) -> Result<(), String> {
+    if control.switch.is_none() {
+        return Err(format!("{} is not an on/off control", control.name));
+    }
+
+    let output = Command::new("amixer")
+        .args([
+            "-c",
+            &card.to_string(),
+            "sset",
+            &control.amixer_id(),
+            if on { "on" } else { "off" },
+        ])
+        .output()
+        .map_err(|e| format!("Failed to run amixer: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to set {}: {}",
+            control.name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(())
+}
+
+fn parse_amixer_scontents(output: &str) -> Vec<MixerControl> {
+    const DESCRIPTION_KEYS: [&str; 5] = [
+        "Capabilities",
+        "Playback channels",
+        "Capture channels",
+        "Limits",
+        "Items",
+    ];
+
+    let mut controls: Vec<MixerControl> = Vec::new();
+
+    for line in output.lines() {
+        if let Some(header) = line.strip_prefix("Simple mixer control ") {
+            // 'Direct Monitor',0
+            let Some((quoted, index)) = header.rsplit_once(',') else {
+                continue;
+            };
+            controls.push(MixerControl {
+                name: quoted.trim_matches('\'').to_string(),
+                index: index.trim().parse().unwrap_or(0),
+                capabilities: Vec::new(),
+                value: String::new(),
+                switch: None,
+            });
+            continue;
+        }
+
+        let Some(control) = controls.last_mut() else {
+            continue;
+        };
+        let Some((key, value)) = line.trim().split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+
+        if key == "Capabilities" {
+            control.capabilities = value.split_whitespace().map(str::to_string).collect();
+        } else if !DESCRIPTION_KEYS.contains(&key) && control.value.is_empty() && !value.is_empty()
+        {
+            control.value = value.to_string();
+        }
+    }
+
+    for control in &mut controls {
+        let has = |capability: &str| control.capabilities.iter().any(|c| c == capability);
+        let switch_only = (has("pswitch") || has("cswitch") || has("switch"))
+            && !(has("pvolume") || has("cvolume") || has("volume"));
+
+        if switch_only {
+            control.switch = if control.value.contains("[on]") {
+                Some(true)
+            } else if control.value.contains("[off]") {
+                Some(false)
+            } else {
+                None
+            };
+        }
+    }
+
+    controls
+}
+
+pub fn resolve_pipewire_device_name(node_id: &str) -> Result<String, AudioConfigError> {
+    let output = pipewire_command("pw-cli")
+        .args(["info", node_id])
+        .output()
+        .map_err(|e| format!("Failed to query PipeWire node {}: {}", node_id, e))?;
+
+    if !output.status.success() {
+        return Err(AudioConfigError::Other(format!(
+            "PipeWire query failed for node {}",
+            node_id
+        )));
+    }
+
+    let output_str = String::from_utf8_lossy(&output.stdout);
+
+    for line in output_str.lines() {
+        if line.contains("device.name")
+            && line.contains('=')
+            && let Some(name_part) = line.split('=').nth(1)
+        {
+            let name = name_part.trim().trim_matches('"').to_string();
+            if !name.is_empty() {
+                return Ok(name);
+            }
+        }
+    }
+
+    for line in output_str.lines() {
+        if line.contains("node.name")
+            && line.contains('=')
+            && let Some(name_part) = line.split('=').nth(1)
+        {
+            let name = name_part.trim().trim_matches('"').to_string();
+            if !name.is_empty() {
+                return Ok(name);
+            }
+        }
+    }
+
+    Err(AudioConfigError::DeviceNotFound(format!(
+        "node {}",
+        node_id
+    )))
+}
+
+pub fn resolve_pulse_device_name(pulse_id: &str) -> Result<String, AudioConfigError> {
+    let output = Command::new("pactl")
+        .args(["list", "sinks", "short"])
+        .output()
+        .map_err(|e| format!("Failed to query PulseAudio devices: {}", e))?;
+
+    if output.status.success() {
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        for line in output_str.lines() {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() >= 2 && parts[0] == pulse_id {
+                return Ok(parts[1].to_string());
+            }
+        }
+    }
+
+    if let Ok(output) = Command::new("pactl")
+        .args(["list", "sources", "short"])
+        .output()
+    {
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        for line in output_str.lines() {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() >= 2 && parts[0] == pulse_id {
+                return Ok(parts[1].to_string());
+            }
+        }
+    }
+
+    Err(AudioConfigError::DeviceNotFound(pulse_id.to_string()))
+}
+
+pub fn extract_actual_device_name(device_info: &str) -> Option<String> {
+    let cleaned = device_info
+        .replace("PipeWire:", "")
+        .replace("PulseAudio:", "")
+        .replace("ALSA:", "")
+        .trim()
+        .to_string();
+
+    if cleaned.is_empty() {
+        None
+    } else {
+        Some(cleaned)
+    }
+}
+
+pub fn detect_high_performance_devices() -> Result<Vec<AudioDevice>, String> {
+    let mut devices = detect_output_audio_devices()?;
+
+    devices.retain(|device| {
+        let name_lower = device.name.to_lowercase();
+        let desc_lower = device.description.to_lowercase();
+        let id_lower = device.id.to_lowercase();
+
+        desc_lower.contains("usb") ||
+        name_lower.contains("usb") ||
+        id_lower.contains("usb") ||
+        desc_lower.contains("firewire") ||
+        desc_lower.contains("thunderbolt") ||
+        desc_lower.contains("pci") ||
+        desc_lower.contains("external") ||
+        desc_lower.contains("interface") ||
+        desc_lower.contains("studio") ||
+        desc_lower.contains("pro") ||
+        // Professional audio brands
+        desc_lower.contains("focusrite") ||
+        desc_lower.contains("presonus") ||
+        desc_lower.contains("behringer") ||
+        desc_lower.contains("motu") ||
+        desc_lower.contains("rme") ||
+        desc_lower.contains("universal audio") ||
+        desc_lower.contains("audient") ||
+        desc_lower.contains("steinberg") ||
+        desc_lower.contains("tascam") ||
+        desc_lower.contains("zoom") ||
+        desc_lower.contains("arturia") ||
+        desc_lower.contains("native instruments") ||
+        desc_lower.contains("akai") ||
+        desc_lower.contains("novation") ||
+        desc_lower.contains("mackie") ||
+        desc_lower.contains("onyx") ||
+        desc_lower.contains("roland") ||
+        desc_lower.contains("yamaha") ||
+        desc_lower.contains("allen heath") ||
+        desc_lower.contains("ssl") ||
+        desc_lower.contains("solid state logic") ||
+        desc_lower.contains("antelope") ||
+        desc_lower.contains("apogee") ||
+        desc_lower.contains("lynx") ||
+        desc_lower.contains("ferrofish") ||
+        desc_lower.contains("merging") ||
+        desc_lower.contains("neve") ||
+        desc_lower.contains("api") ||
+        desc_lower.contains("chandler limited") ||
+        desc_lower.contains("dangerous music") ||
+        desc_lower.contains("burl") ||
+        desc_lower.contains("cranborne") ||
+        desc_lower.contains("heritage audio") ||
+        desc_lower.contains("black lion") ||
+        desc_lower.contains("quantum") ||
+        desc_lower.contains("clarett") ||
+        desc_lower.contains("scarlett") ||
+        desc_lower.contains("vocaster")
+    });
+
+    log::info!("Found {} high-performance audio devices", devices.len());
+    Ok(devices)
+}
+
+#[derive(Clone, Debug)]
+pub struct DeviceCapabilities {
+    pub sample_rates: Vec<u32>,
+    pub formats: Vec<String>,
+    pub buffer_sizes: Vec<u32>,
+    pub min_buffer_size: u32,
+    pub max_buffer_size: u32,
+    pub period_sizes: Vec<u32>,
+    /// False when the device reported nothing and the lists are generic
+    /// defaults, so they should not be used to hide options
+    pub detected: bool,
+}
+
+impl DeviceCapabilities {
+    /// Bit depths the listed formats can carry; all three when none match
+    pub fn bit_depths(&self) -> Vec<u32> {
+        let supports = |name: &str| self.formats.iter().any(|format| format == name);
+
+        let mut depths = Vec::new();
+        if supports("S16LE") {
+            depths.push(16);
+        }
+        if FORMATS_24_BIT.into_iter().any(supports) {
+            depths.push(24);
+        }
+        if supports("S32LE") {
+            depths.push(32);
+        }
+
+        if depths.is_empty() {
+            vec![16, 24, 32]
+        } else {
+            depths
+        }
+    }
+
+    /// Rates from `rates` the device does not list, in the order given
+    pub fn unsupported_rates(&self, rates: &[u32]) -> Vec<u32> {
+        rates
+            .iter()
+            .copied()
+            .filter(|rate| !self.sample_rates.contains(rate))
+            .collect()
+    }
+}
+
+/// Checks `settings` against what the device reports before they are
+/// written. Each Err entry is one problem, e.g. a rate the device does not
+/// list or a buffer below its minimum; PipeWire would otherwise fall back to
+/// other values without saying so.
+pub fn validate_settings_against_device(
+    settings: &AudioSettings,
+    capabilities: &DeviceCapabilities,
+) -> Result<(), Vec<String>> {
+    let mut warnings = Vec::new();
+
+    if !capabilities.sample_rates.contains(&settings.sample_rate) {
+        warnings.push(format!(
+            "Sample rate {} Hz is not supported",
+            settings.sample_rate
+        ));
+    }
+
+    for rate in capabilities.unsupported_rates(&settings.allowed_rates) {
+        if rate != settings.sample_rate {
+            warnings.push(format!("Allowed rate {} Hz is not supported", rate));
+        }
+    }
+
+    if settings.buffer_size < capabilities.min_buffer_size {
+        warnings.push(format!(
+            "Buffer size {} is below the device minimum of {} samples",
+            settings.buffer_size, capabilities.min_buffer_size
+        ));
+    } else if settings.buffer_size > capabilities.max_buffer_size {
+        warnings.push(format!(
+            "Buffer size {} is above the device maximum of {} samples",
+            settings.buffer_size, capabilities.max_buffer_size
+        ));
+    }
+
+    if warnings.is_empty() {
+        Ok(())
+    } else {
+        Err(warnings)
+    }
+}
+
+/// Inverse of the latency display: buffer = round(ms * rate / 1000), snapped to
+/// the closest of `buffer_sizes` (the smaller one on a tie)
+pub fn buffer_for_latency(latency_ms: f64, sample_rate: u32, buffer_sizes: &[u32]) -> Option<u32> {
+    if !latency_ms.is_finite() || latency_ms <= 0.0 || sample_rate == 0 {
+        return None;
+    }
+
+    let target = (latency_ms * sample_rate as f64 / 1000.0).round() as i64;
+    buffer_sizes
+        .iter()
+        .copied()
+        .min_by_key(|size| ((*size as i64 - target).abs(), *size))
+}
+
+/// Buffer that keeps the latency of `buffer_size` at `old_rate` after a switch
+/// to `new_rate`, snapped like `buffer_for_latency`
+pub fn buffer_for_rate_change(
+    buffer_size: u32,
+    old_rate: u32,
+    new_rate: u32,
+    buffer_sizes: &[u32],
+) -> Option<u32> {
+    if old_rate == 0 {
+        return None;
+    }
+
+    let latency_ms = buffer_size as f64 * 1000.0 / old_rate as f64;
+    buffer_for_latency(latency_ms, new_rate, buffer_sizes)
+}
+
+pub fn get_device_capabilities(device_id: &str) -> Result<DeviceCapabilities, String> {
+    match detect_device_capabilities(device_id, "output") {
+        Ok(capabilities) => return Ok(capabilities),
+        Err(e) => log::debug!("No EnumFormat capabilities for {}: {}", device_id, e),
+    }
+
+    let device_pattern = if device_id == "default" {
+        if let Ok(device_info) = detect_output_audio_device() {
+            extract_actual_device_name(&device_info).unwrap_or_else(|| "alsa_output.*".to_string())
+        } else {
+            "alsa_output.*".to_string()
+        }
+    } else {
+        device_id.to_string()
+    };
+
+    if let Ok(output) = pipewire_command("pw-cli")
+        .args(["list-objects", "Node"])
+        .output()
+    {
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        return parse_device_capabilities(&output_str, &device_pattern);
+    }
+
+    Ok(DeviceCapabilities {
+        sample_rates: vec![44100, 48000, 96000, 192000],
+        formats: vec![
+            "S16LE".to_string(),
+            "S24LE".to_string(),
+            "S32LE".to_string(),
+        ],
         buffer_sizes: vec![64, 128, 256, 512, 1024, 2048],
         min_buffer_size: 64,
         max_buffer_size: 4096,
         period_sizes: vec![32, 64, 128, 256, 512],
+        detected: false,
+    })
+}
+
+/// Capabilities from the rates and formats the device's node advertises in
+/// its EnumFormat params. `device` and `stream_type` as for
+/// `detect_device_formats`; Err when the node lists no rates.
+pub fn detect_device_capabilities(
+    device: &str,
+    stream_type: &str,
+) -> Result<DeviceCapabilities, String> {
+    parse_node_capabilities(&run_pw_dump()?, device, stream_type)
+}
+
+fn parse_node_capabilities(
+    dump: &str,
+    device: &str,
+    stream_type: &str,
+) -> Result<DeviceCapabilities, String> {
+    let objects: Vec<serde_json::Value> =
+        serde_json::from_str(dump).map_err(|e| format!("Failed to parse pw-dump output: {}", e))?;
+    let node = find_node_object(&objects, device, stream_type)?;
+
+    let sample_rates = node_sample_rates(node);
+    if sample_rates.is_empty() {
+        return Err(format!("Device {} advertises no sample rates", device));
+    }
+
+    // The node's quantum limit caps the buffer, otherwise PipeWire's default
+    let props = &node["info"]["props"];
+    let max_buffer_size = props["clock.quantum-limit"]
+        .as_u64()
+        .or_else(|| props["clock.quantum-limit"].as_str()?.parse().ok())
+        .map_or(8192, |limit| limit as u32);
+    let buffer_sizes: Vec<u32> = (5..=13)
+        .map(|shift| 1 << shift)
+        .filter(|size| *size <= max_buffer_size)
+        .collect();
+
+    Ok(DeviceCapabilities {
+        sample_rates,
+        formats: node_formats(node),
+        min_buffer_size: buffer_sizes.first().copied().unwrap_or(32),
+        max_buffer_size,
+        period_sizes: buffer_sizes.iter().map(|&size| size / 2).collect(),
+        buffer_sizes,
+        detected: true,
     })
 }
 
@@ -1079,6 +2893,7 @@ fn parse_device_capabilities(
         }
     }
 
+    let detected = !sample_rates.is_empty();
     if sample_rates.is_empty() {
         sample_rates = vec![44100, 48000, 96000, 192000];
     }
@@ -1103,6 +2918,7 @@ fn parse_device_capabilities(
         min_buffer_size: *buffer_sizes.iter().min().unwrap_or(&64),
         max_buffer_size: *buffer_sizes.iter().max().unwrap_or(&4096),
         period_sizes: buffer_sizes.iter().map(|&size| size / 2).collect(),
+        detected,
     })
 }
 
@@ -1132,7 +2948,7 @@ pub fn detect_recommended_devices() -> Result<Vec<AudioDevice>, String> {
         // Note: We're NOT filtering by specific brands here for ordinary modes
     });
 
-    println!(
+    log::info!(
         "Found {} recommended audio devices for ordinary usage",
         devices.len()
     );
@@ -1182,6 +2998,149 @@ pub fn is_device_suitable_for_exclusive_mode(device: &AudioDevice) -> bool {
     desc_lower.contains("scarlett")
 }
 
+/// Pick the device best suited for exclusive-mode work from a detected list.
+/// External interfaces (USB/FireWire/Thunderbolt) win over internal cards;
+/// HDMI/DisplayPort sinks are never picked because of their large buffers.
+pub fn select_best_exclusive_device(devices: &[AudioDevice]) -> Option<&AudioDevice> {
+    devices
+        .iter()
+        .filter(|device| device.available)
+        .map(|device| (exclusive_device_score(device), device))
+        .filter(|(score, _)| *score > 0)
+        .max_by_key(|(score, _)| *score)
+        .map(|(_, device)| device)
+}
+
+fn exclusive_device_score(device: &AudioDevice) -> u32 {
+    let name_lower = device.name.to_lowercase();
+    let desc_lower = device.description.to_lowercase();
+    let id_lower = device.id.to_lowercase();
+
+    if desc_lower.contains("hdmi")
+        || name_lower.contains("hdmi")
+        || desc_lower.contains("displayport")
+        || name_lower.contains("displayport")
+    {
+        return 0;
+    }
+
+    if !is_device_suitable_for_exclusive_mode(device) {
+        return 0;
+    }
+
+    let mut score = 1;
+
+    if desc_lower.contains("usb")
+        || name_lower.contains("usb")
+        || id_lower.contains("usb")
+        || desc_lower.contains("firewire")
+        || desc_lower.contains("thunderbolt")
+    {
+        score += 2;
+    }
+
+    // Full-duplex interfaces are the typical recording setup
+    if device.device_type == DeviceType::Duplex {
+        score += 1;
+    }
+
+    score
+}
+
+/// What the machine is mainly used for, picked in the first-run wizard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UseCase {
+    Music,
+    Production,
+    Gaming,
+}
+
+impl UseCase {
+    pub const ALL: [UseCase; 3] = [UseCase::Music, UseCase::Production, UseCase::Gaming];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            UseCase::Music => "Music listening",
+            UseCase::Production => "Recording / production",
+            UseCase::Gaming => "Gaming",
+        }
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            UseCase::Music => "music",
+            UseCase::Production => "production",
+            UseCase::Gaming => "gaming",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|use_case| use_case.id() == id)
+    }
+}
+
+/// Sensible starting settings for a use case on the given device.
+/// Listening favours stability, production favours low latency (lower still on
+/// external interfaces), gaming sits in between at 16-bit.
+pub fn suggest_settings(use_case: UseCase, device: Option<&AudioDevice>) -> AudioSettings {
+    let device_id = device
+        .map(|device| device.id.clone())
+        .unwrap_or_else(|| "default".to_string());
+
+    let (bit_depth, buffer_size) = match use_case {
+        UseCase::Music => (24, 1024),
+        UseCase::Production => {
+            let external = device.is_some_and(|device| exclusive_device_score(device) >= 3);
+            (24, if external { 128 } else { 256 })
+        }
+        UseCase::Gaming => (16, 256),
+    };
+
+    AudioSettings::new(48000, bit_depth, buffer_size, device_id)
+}
+
+/// Sets the remote configured in preferences. Empty fields fall back to the
+/// PIPEWIRE_RUNTIME_DIR / PIPEWIRE_REMOTE environment of this process.
+pub fn set_pipewire_remote(remote: PipeWireRemote) {
+    *PIPEWIRE_REMOTE_OVERRIDE.lock().unwrap() = remote;
+    *CACHED_RATE_SWITCHING.lock().unwrap() = None;
+    clear_cache();
+}
+
+/// The remote every PipeWire client spawned by this tool should use
+pub fn resolve_pipewire_remote() -> PipeWireRemote {
+    let configured = PIPEWIRE_REMOTE_OVERRIDE.lock().unwrap().clone();
+
+    PipeWireRemote {
+        runtime_dir: resolve_remote_setting(configured.runtime_dir, PIPEWIRE_RUNTIME_DIR_ENV),
+        remote: resolve_remote_setting(configured.remote, PIPEWIRE_REMOTE_ENV),
+    }
+}
+
+/// Builds a Command for a PipeWire client tool (pw-cli, pw-dump, pw-link, wpctl...)
+/// that talks to the resolved PipeWire instance
+pub fn pipewire_command(program: &str) -> Command {
+    let mut command = Command::new(program);
+    let remote = resolve_pipewire_remote();
+
+    if let Some(runtime_dir) = &remote.runtime_dir {
+        command.env(PIPEWIRE_RUNTIME_DIR_ENV, runtime_dir);
+    }
+    if let Some(name) = &remote.remote {
+        command.env(PIPEWIRE_REMOTE_ENV, name);
+    }
+
+    command
+}
+
+/// A configured value wins over the environment; blank values count as unset
+fn resolve_remote_setting(configured: Option<String>, env_name: &str) -> Option<String> {
+    configured
+        .or_else(|| std::env::var(env_name).ok())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
 pub fn clear_cache() {
     *CACHED_AUDIO_SETTINGS.lock().unwrap() = None;
     *CACHED_OUTPUT_DEVICES.lock().unwrap() = None;
@@ -1195,6 +3154,588 @@ pub fn clear_cache() {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_device_type_from_media_class() {
+        assert_eq!(
+            DeviceType::from_media_class("Audio/Sink"),
+            DeviceType::Output
+        );
+        assert_eq!(
+            DeviceType::from_media_class("Audio/Source"),
+            DeviceType::Input
+        );
+        assert_eq!(
+            DeviceType::from_media_class("Audio/Source/Virtual"),
+            DeviceType::Input
+        );
+        assert_eq!(
+            DeviceType::from_media_class("Audio/Duplex"),
+            DeviceType::Duplex
+        );
+        assert_eq!(
+            DeviceType::from_media_class("Stream/Output/Audio"),
+            DeviceType::Unknown
+        );
+        assert_eq!(DeviceType::from_media_class(""), DeviceType::Unknown);
+    }
+
+    #[test]
+    fn test_buffer_for_latency() {
+        let sizes = [64, 128, 256, 512, 1024];
+        // 5 ms @ 48 kHz = 240 samples
+        assert_eq!(buffer_for_latency(5.0, 48000, &sizes), Some(256));
+        assert_eq!(buffer_for_latency(2.0, 48000, &sizes), Some(64));
+        assert_eq!(buffer_for_latency(100.0, 48000, &sizes), Some(1024));
+        // 192 samples is exactly between 128 and 256
+        assert_eq!(buffer_for_latency(2.0, 96000, &sizes), Some(128));
+        assert_eq!(buffer_for_latency(0.0, 48000, &sizes), None);
+        assert_eq!(buffer_for_latency(5.0, 48000, &[]), None);
+    }
+
+    #[test]
+    fn test_buffer_for_rate_change() {
+        let sizes = [128, 256, 512, 1024, 2048];
+        assert_eq!(
+            buffer_for_rate_change(512, 48000, 96000, &sizes),
+            Some(1024)
+        );
+        assert_eq!(
+            buffer_for_rate_change(1024, 96000, 48000, &sizes),
+            Some(512)
+        );
+        // 10.7 ms @ 44.1 kHz = 470 samples
+        assert_eq!(buffer_for_rate_change(512, 48000, 44100, &sizes), Some(512));
+        assert_eq!(
+            buffer_for_rate_change(512, 48000, 192000, &sizes),
+            Some(2048)
+        );
+        assert_eq!(buffer_for_rate_change(512, 0, 48000, &sizes), None);
+    }
+
+    #[test]
+    fn test_parse_pw_top() {
+        let output = "\
+S   ID  QUANT   RATE    WAIT    BUSY   W/Q   B/Q  ERR FORMAT           NAME
+R   46   1024  48000  88.4us  18.2us  0.00  0.00    0    S32LE 2 48000 alsa_output.old
+S   ID  QUANT   RATE    WAIT    BUSY   W/Q   B/Q  ERR FORMAT           NAME
+S   28      0      0    ---     ---   ---   ---     0                  Dummy-Driver
+R   46    128  48000  88.4us  18.2us  0.01  0.00    0    S32LE 2 48000 alsa_output.usb-Focusrite
+R   80    128  48000  21.0us  25.3us  0.00  0.00    0    F32LE 2 48000  + REAPER
+R   81      0      0  11.0us  12.3us  0.00  0.00    0    F32LE 2 48000  + Firefox
+S   82   1024  44100    ---     ---   ---   ---     0    F32LE 2 44100  + mpv
+";
+        let streams = parse_pw_top(output);
+        assert_eq!(streams.len(), 2);
+
+        assert_eq!(streams[0].name, "REAPER");
+        assert_eq!(streams[0].driver, "alsa_output.usb-Focusrite");
+        assert_eq!((streams[0].quantum, streams[0].rate), (128, 48000));
+        assert_eq!(streams[0].requested_quantum, Some(128));
+        assert!(streams[0].differs_from(1024, 48000));
+        assert!(!streams[0].differs_from(128, 48000));
+
+        assert_eq!(streams[1].name, "Firefox");
+        assert_eq!(streams[1].requested_quantum, None);
+        assert_eq!(streams[1].requested_rate, None);
+    }
+
+    #[test]
+    fn test_parse_pw_top_loads() {
+        let output = "\
+S   ID  QUANT   RATE    WAIT    BUSY   W/Q   B/Q  ERR FORMAT           NAME
+R   46   1024  48000  88.4us  18.2us  0.00  0.02    0    S32LE 2 48000 alsa_output.old
+S   ID  QUANT   RATE    WAIT    BUSY   W/Q   B/Q  ERR FORMAT           NAME
+S   28      0      0    ---     ---   ---   ---     0                  Dummy-Driver
+R   46    128  48000  88.4us 2.2ms  0.03  0.83    4    S32LE 2 48000 alsa_output.usb-Focusrite
+R   80    128  48000  21.0us  1.8ms  0.01  0.67    0    F32LE 2 48000  + REAPER
+S   82   1024  44100    ---     ---   ---   ---     0    F32LE 2 44100  + mpv
+";
+        let loads = parse_pw_top_loads(output);
+        assert_eq!(loads.len(), 2);
+
+        assert_eq!(loads[0].id, 46);
+        assert_eq!(loads[0].name, "alsa_output.usb-Focusrite");
+        assert_eq!(loads[0].driver, None);
+        assert_eq!(loads[0].errors, 4);
+        assert!(loads[0].is_overloaded());
+
+        assert_eq!(loads[1].name, "REAPER");
+        assert_eq!(
+            loads[1].driver.as_deref(),
+            Some("alsa_output.usb-Focusrite")
+        );
+        assert_eq!((loads[1].quantum, loads[1].rate), (128, 48000));
+        assert!((loads[1].load - 0.67).abs() < 1e-9);
+        assert!(!loads[1].is_overloaded());
+
+        // Error counters come from the last sample, stopped nodes included
+        let errors = parse_pw_top_errors(output);
+        assert_eq!(errors.len(), 4);
+        assert_eq!(errors[&46], 4);
+        assert_eq!(errors[&82], 0);
+    }
+
+    #[test]
+    fn test_parse_quantum_floor() {
+        let dump = r#"[
+            { "id": 0, "type": "PipeWire:Interface:Core",
+              "info": { "props": { "default.clock.quantum-floor": "32" } } },
+            { "id": 31, "type": "PipeWire:Interface:Metadata",
+              "props": { "metadata.name": "settings" },
+              "metadata": [ { "subject": 0, "key": "clock.quantum-floor", "type": "Spa:Int", "value": 64 } ] }
+        ]"#;
+        assert_eq!(parse_quantum_floor(dump), Ok(64));
+
+        let core_only = r#"[
+            { "id": 0, "type": "PipeWire:Interface:Core",
+              "info": { "props": { "default.clock.quantum-floor": "32" } } }
+        ]"#;
+        assert_eq!(parse_quantum_floor(core_only), Ok(32));
+        assert_eq!(
+            parse_quantum_floor("[]"),
+            Ok(PIPEWIRE_DEFAULT_QUANTUM_FLOOR)
+        );
+        assert!(parse_quantum_floor("not json").is_err());
+    }
+
+    #[test]
+    fn test_parse_device_discriminators() {
+        let dump = r#"[
+            { "id": 51, "type": "PipeWire:Interface:Node",
+              "info": { "props": { "api.alsa.card": 2, "device.id": 48 } } },
+            { "id": 48, "type": "PipeWire:Interface:Device",
+              "info": { "props": { "device.bus-path": "pci-0000:00:14.0-usb-0:3.2:1.0" } } },
+            { "id": 60, "type": "PipeWire:Interface:Node",
+              "info": { "props": { "node.name": "no-details" } } }
+        ]"#;
+        let ids = [
+            "pipewire:51".to_string(),
+            "pipewire:60".to_string(),
+            "alsa:3".to_string(),
+        ];
+
+        let discriminators = parse_device_discriminators(dump, &ids);
+        assert_eq!(discriminators["pipewire:51"], "card 2, USB port 0:3.2:1.0");
+        assert_eq!(discriminators["alsa:3"], "card 3");
+        assert!(!discriminators.contains_key("pipewire:60"));
+    }
+
+    #[test]
+    fn test_device_format_choice() {
+        let dump = r#"[
+            { "id": 0, "type": "PipeWire:Interface:Metadata",
+              "props": { "metadata.name": "default" },
+              "metadata": [ { "subject": 0, "key": "default.audio.sink",
+                              "value": { "name": "alsa_output.usb-Interface" } } ] },
+            { "id": 51, "type": "PipeWire:Interface:Node",
+              "info": { "props": { "node.name": "alsa_output.usb-Interface", "api.alsa.card": 2 },
+                        "params": { "EnumFormat": [
+                            { "mediaType": "audio", "format": { "default": "S32LE", "alt1": "S16LE" } }
+                        ] } } },
+            { "id": 60, "type": "PipeWire:Interface:Node",
+              "info": { "props": { "node.name": "alsa_output.pci-Onboard" },
+                        "params": { "EnumFormat": [ { "format": "S24_3LE" } ] } } }
+        ]"#;
+
+        let mut formats = parse_node_formats(dump, "default", "output").unwrap();
+        formats.sort();
+        assert_eq!(formats, vec!["S16LE", "S32LE"]);
+        assert_eq!(
+            parse_node_formats(dump, "alsa:2", "output").unwrap().len(),
+            2
+        );
+        assert_eq!(
+            parse_node_formats(dump, "pipewire:60", "output").unwrap(),
+            vec!["S24_3LE"]
+        );
+        assert!(parse_node_formats(dump, "default", "input").is_err());
+
+        let (format, note) = choose_audio_format(24, &formats);
+        assert_eq!(format, "S32LE");
+        assert!(note.is_some());
+        assert_eq!(
+            choose_audio_format(24, &["S24_3LE".to_string()]),
+            ("S24_3LE", None)
+        );
+        assert_eq!(choose_audio_format(24, &[]), ("S24LE", None));
+        assert_eq!(choose_audio_format(16, &formats), ("S16LE", None));
+    }
+
+    #[test]
+    fn test_parse_node_id() {
+        let dump = r#"[
+            { "id": 51, "type": "PipeWire:Interface:Node",
+              "info": { "props": { "node.name": "alsa_output.usb-Interface",
+                                   "media.class": "Audio/Sink", "api.alsa.card": 2 } } },
+            { "id": 52, "type": "PipeWire:Interface:Node",
+              "info": { "props": { "node.name": "alsa_input.usb-Interface",
+                                   "media.class": "Audio/Source", "api.alsa.card": 2 } } }
+        ]"#;
+
+        assert_eq!(parse_node_id(dump, "alsa:2", &DeviceType::Output), Ok(51));
+        assert_eq!(parse_node_id(dump, "alsa:2", &DeviceType::Input), Ok(52));
+        assert_eq!(
+            parse_node_id(dump, "alsa_input.usb-Interface", &DeviceType::Input),
+            Ok(52)
+        );
+        assert_eq!(
+            parse_node_id(dump, "pipewire:51", &DeviceType::Output),
+            Ok(51)
+        );
+        assert!(parse_node_id(dump, "pipewire:51", &DeviceType::Input).is_err());
+        assert!(parse_node_id(dump, "alsa:2", &DeviceType::Duplex).is_err());
+    }
+
+    #[test]
+    fn test_device_capabilities_from_dump() {
+        let dump = r#"[
+            { "id": 51, "type": "PipeWire:Interface:Node",
+              "info": { "props": { "node.name": "alsa_output.usb-Interface",
+                                   "clock.quantum-limit": 2048 },
+                        "params": { "EnumFormat": [
+                            { "format": { "default": "S32LE", "alt1": "S16LE" },
+                              "rate": { "default": 48000, "alt1": 44100 } }
+                        ] } } },
+            { "id": 60, "type": "PipeWire:Interface:Node",
+              "info": { "props": { "node.name": "alsa_output.pci-Onboard" },
+                        "params": { "EnumFormat": [
+                            { "format": "S24_3LE",
+                              "rate": { "default": 48000, "min": 32000, "max": 96000 } }
+                        ] } } },
+            { "id": 70, "type": "PipeWire:Interface:Node",
+              "info": { "props": { "node.name": "bluez_output.headset" },
+                        "params": { "EnumFormat": [ { "format": "S16LE" } ] } } }
+        ]"#;
+
+        let usb = parse_node_capabilities(dump, "pipewire:51", "output").unwrap();
+        assert!(usb.detected);
+        assert_eq!(usb.sample_rates, vec![44100, 48000]);
+        assert_eq!(usb.bit_depths(), vec![16, 24, 32]);
+        assert_eq!(usb.max_buffer_size, 2048);
+        assert_eq!(usb.buffer_sizes.last(), Some(&2048));
+
+        let onboard = parse_node_capabilities(dump, "alsa_output.pci-Onboard", "output").unwrap();
+        assert_eq!(onboard.sample_rates, vec![44100, 48000, 88200, 96000]);
+        assert_eq!(onboard.bit_depths(), vec![24]);
+        assert_eq!(onboard.max_buffer_size, 8192);
+
+        // No rates advertised, the caller falls back to showing everything
+        assert!(parse_node_capabilities(dump, "pipewire:70", "output").is_err());
+    }
+
+    #[test]
+    fn test_revert_single_field() {
+        let previous = AudioSettings::new(44100, 24, 512, "default".to_string());
+        let current = AudioSettings::new(48000, 24, 128, "default".to_string());
+        assert_eq!(
+            current.changed_fields(&previous),
+            vec![SettingField::SampleRate, SettingField::BufferSize]
+        );
+
+        let reverted = current.with_field_from(&previous, SettingField::BufferSize);
+        assert_eq!((reverted.sample_rate, reverted.buffer_size), (48000, 512));
+        assert_eq!(
+            reverted.changed_fields(&current),
+            vec![SettingField::BufferSize]
+        );
+        assert_eq!(
+            SettingField::PeriodSize.describe(&current),
+            "same as buffer"
+        );
+    }
+
+    #[test]
+    fn test_channel_layout_settings() {
+        // Settings saved before the layout existed load as stereo
+        let saved: AudioSettings = serde_json::from_str(
+            r#"{"sample_rate":48000,"bit_depth":24,"buffer_size":512,"device_id":"default"}"#,
+        )
+        .unwrap();
+        assert_eq!(saved.channels, 2);
+        assert_eq!(saved.channel_positions(), vec!["FL", "FR"]);
+        assert!(saved.validate().is_ok());
+
+        let surround = saved.clone().with_channels(6, Vec::new());
+        assert_eq!(surround.channel_positions().len(), 6);
+        assert_eq!(
+            surround.changed_fields(&saved),
+            vec![SettingField::Channels]
+        );
+        assert_eq!(
+            SettingField::Channels.describe(&saved),
+            "2 channels (FL FR)"
+        );
+
+        assert!(
+            saved
+                .clone()
+                .with_channels(0, Vec::new())
+                .validate()
+                .is_err()
+        );
+        assert!(
+            saved
+                .with_channels(4, vec!["FL".to_string(), "FR".to_string()])
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_card_profiles() {
+        let dump = r#"[
+            { "id": 0, "type": "PipeWire:Interface:Metadata",
+              "props": { "metadata.name": "default" },
+              "metadata": [ { "subject": 0, "key": "default.audio.sink",
+                              "value": { "name": "alsa_output.usb-Focusrite_Scarlett_2i2.analog-stereo" } } ] },
+            { "id": 42, "type": "PipeWire:Interface:Node",
+              "info": { "props": { "node.name": "alsa_output.usb-Focusrite_Scarlett_2i2.analog-stereo",
+                                   "device.id": 7, "media.class": "Audio/Sink" } } },
+            { "id": 7, "type": "PipeWire:Interface:Device",
+              "info": { "props": { "device.name": "alsa_card.usb-Focusrite_Scarlett_2i2",
+                                   "device.description": "Scarlett 2i2 USB" },
+                        "params": {
+                          "EnumProfile": [
+                            { "index": 0, "name": "off", "description": "Off", "available": "yes" },
+                            { "index": 1, "name": "output:analog-stereo", "description": "Analog Stereo Output", "available": "yes" },
+                            { "index": 2, "name": "pro-audio", "description": "Pro Audio", "available": "unknown" }
+                          ],
+                          "Profile": [ { "index": 1, "name": "output:analog-stereo" } ] } } }
+        ]"#;
+
+        let card = parse_card_profiles(dump, "pipewire:42").unwrap();
+        assert_eq!(card.device_object_id, 7);
+        assert_eq!(card.device_name, "alsa_card.usb-Focusrite_Scarlett_2i2");
+        assert_eq!(card.device_description, "Scarlett 2i2 USB");
+        assert_eq!(card.profiles.len(), 3);
+        assert_eq!(card.active.as_deref(), Some("output:analog-stereo"));
+        assert_eq!(
+            card.pending_pro_audio().map(|profile| profile.index),
+            Some(2)
+        );
+
+        assert_eq!(parse_card_profiles(dump, "default").unwrap(), card);
+        assert_eq!(
+            parse_card_profiles(dump, "alsa_card.usb-Focusrite_Scarlett_2i2").unwrap(),
+            card
+        );
+        assert!(parse_card_profiles(dump, "pipewire:43").is_err());
+        assert_eq!(
+            find_card_output_node(dump, "alsa_card.usb-Focusrite_Scarlett_2i2"),
+            Ok(Some(42))
+        );
+
+        let active = CardProfiles {
+            active: Some(PRO_AUDIO_PROFILE.to_string()),
+            ..card
+        };
+        assert!(active.pending_pro_audio().is_none());
+    }
+
+    #[test]
+    fn test_parse_amixer_scontents() {
+        let output = "\
+Simple mixer control 'Master',0
+  Capabilities: pvolume pswitch pswitch-joined
+  Playback channels: Front Left - Front Right
+  Limits: Playback 0 - 127
+  Mono:
+  Front Left: Playback 100 [79%] [-27.00dB] [on]
+  Front Right: Playback 100 [79%] [-27.00dB] [on]
+Simple mixer control 'Direct Monitor',0
+  Capabilities: pswitch pswitch-joined
+  Playback channels: Mono
+  Mono: Playback [off]
+Simple mixer control 'Input 1 Level',0
+  Capabilities: enum
+  Items: 'Line' 'Inst'
+  Item0: 'Inst'
+";
+        let controls = parse_amixer_scontents(output);
+        assert_eq!(controls.len(), 3);
+
+        assert_eq!(controls[0].name, "Master");
+        assert_eq!(controls[0].value, "Playback 100 [79%] [-27.00dB] [on]");
+        assert_eq!(controls[0].switch, None);
+
+        assert_eq!(controls[1].amixer_id(), "Direct Monitor,0");
+        assert_eq!(controls[1].switch, Some(false));
+
+        assert_eq!(controls[2].capabilities, vec!["enum"]);
+        assert_eq!(controls[2].value, "'Inst'");
+        assert_eq!(controls[2].switch, None);
+    }
+
+    #[test]
+    fn test_parse_alsa_card() {
+        let dump = r#"[
+            { "id": 42, "type": "PipeWire:Interface:Node",
+              "info": { "props": { "node.name": "alsa_output.usb-Focusrite_Scarlett_2i2", "device.id": 7 } } },
+            { "id": 7, "type": "PipeWire:Interface:Device",
+              "info": { "props": { "device.name": "alsa_card.usb-Focusrite_Scarlett_2i2",
+                                   "api.alsa.card": "2" } } },
+            { "id": 9, "type": "PipeWire:Interface:Device",
+              "info": { "props": { "device.name": "bluez_card.00_11_22" } } }
+        ]"#;
+
+        assert_eq!(parse_alsa_card(dump, "pipewire:42"), Ok(2));
+        assert_eq!(
+            parse_alsa_card(dump, "alsa_output.usb-Focusrite_Scarlett_2i2"),
+            Ok(2)
+        );
+        assert!(parse_alsa_card(dump, "bluez_card.00_11_22").is_err());
+        assert_eq!(alsa_card_for_device("alsa:hw:1"), Ok(1));
+    }
+
+    #[test]
+    fn test_pw_dump_contains_device() {
+        let dump = r#"[
+            { "id": 42, "type": "PipeWire:Interface:Node",
+              "info": { "props": { "node.name": "alsa_output.usb-Focusrite_Scarlett_2i2" } } },
+            { "id": 7, "type": "PipeWire:Interface:Device",
+              "info": { "props": { "device.name": "alsa_card.pci-0000_00_1f.3" } } }
+        ]"#;
+
+        assert_eq!(pw_dump_contains_device(dump, "pipewire:42"), Ok(true));
+        assert_eq!(pw_dump_contains_device(dump, "pipewire:43"), Ok(false));
+        assert_eq!(
+            pw_dump_contains_device(dump, "alsa_output.usb-Focusrite_Scarlett_2i2"),
+            Ok(true)
+        );
+        assert_eq!(
+            pw_dump_contains_device(dump, "alsa_card.pci-0000_00_1f.3"),
+            Ok(true)
+        );
+        assert_eq!(
+            pw_dump_contains_device(dump, "alsa_output.usb-Gone"),
+            Ok(false)
+        );
+        assert!(pw_dump_contains_device("not json", "pipewire:42").is_err());
+    }
+
+    #[test]
+    fn test_suggest_settings() {
+        let interface = AudioDevice {
+            name: "alsa_output.usb-Focusrite_Scarlett_2i2".to_string(),
+            description: "Scarlett 2i2 USB".to_string(),
+            id: "pipewire:42".to_string(),
+            device_type: DeviceType::Duplex,
+            available: true,
+        };
+
+        let production = suggest_settings(UseCase::Production, Some(&interface));
+        assert_eq!(production.buffer_size, 128);
+        assert_eq!(production.device_id, "pipewire:42");
+        assert!(production.validate().is_ok());
+
+        let music = suggest_settings(UseCase::Music, None);
+        assert_eq!(music.buffer_size, 1024);
+        assert_eq!(music.device_id, "default");
+
+        for use_case in UseCase::ALL {
+            assert_eq!(UseCase::from_id(use_case.id()), Some(use_case));
+            assert!(suggest_settings(use_case, None).validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_resolve_remote_setting() {
+        assert_eq!(
+            resolve_remote_setting(Some(" /run/user/1001 ".to_string()), "PRO_AUDIO_UNSET_VAR"),
+            Some("/run/user/1001".to_string())
+        );
+        assert_eq!(
+            resolve_remote_setting(Some("  ".to_string()), "PRO_AUDIO_UNSET_VAR"),
+            None
+        );
+        assert_eq!(resolve_remote_setting(None, "PRO_AUDIO_UNSET_VAR"), None);
+
+        let remote = PipeWireRemote {
+            runtime_dir: Some("/run/user/1001".to_string()),
+            remote: None,
+        };
+        assert_eq!(
+            remote.remote_name(),
+            Some("/run/user/1001/pipewire-0".to_string())
+        );
+        assert_eq!(PipeWireRemote::default().remote_name(), None);
+    }
+
+    #[test]
+    fn test_match_process_binary() {
+        let dump = r#"[
+            {"id": 40, "type": "PipeWire:Interface:Client", "info": {"props": {
+                "application.name": "REAPER", "application.process.binary": "reaper6"}}},
+            {"id": 41, "type": "PipeWire:Interface:Client", "info": {"props": {
+                "application.name": "Firefox", "application.process.binary": "firefox"}}},
+            {"id": 42, "type": "PipeWire:Interface:Node", "info": {"props": {
+                "application.process.binary": "ardour"}}}
+        ]"#;
+        let clients = parse_client_binaries(dump).unwrap();
+        assert_eq!(clients.len(), 2);
+
+        assert_eq!(
+            match_process_binary(&clients, "firefox", ""),
+            ProcessBinaryCheck::Matches
+        );
+        assert_eq!(
+            match_process_binary(&clients, "reaper", ""),
+            ProcessBinaryCheck::Suggestion("reaper6".to_string())
+        );
+        assert_eq!(
+            match_process_binary(&clients, "rpr", "Reaper"),
+            ProcessBinaryCheck::Suggestion("reaper6".to_string())
+        );
+        assert_eq!(
+            match_process_binary(&clients, "firefx", ""),
+            ProcessBinaryCheck::Suggestion("firefox".to_string())
+        );
+        assert_eq!(
+            match_process_binary(&clients, "ardour", "Ardour"),
+            ProcessBinaryCheck::NotRunning
+        );
+    }
+
+    #[test]
+    fn test_parse_wpctl_volume() {
+        let volume = parse_wpctl_volume("Volume: 0.40\n").unwrap();
+        assert_eq!(volume.percent(), 40);
+        assert!(!volume.muted);
+
+        let muted = parse_wpctl_volume("Volume: 1.00 [MUTED]\n").unwrap();
+        assert_eq!(muted.percent(), 100);
+        assert!(muted.muted);
+
+        assert!(parse_wpctl_volume("Translate ID error").is_none());
+    }
+
+    #[test]
+    fn test_select_best_exclusive_device() {
+        let devices = vec![
+            AudioDevice {
+                name: "hdmi-output".to_string(),
+                description: "HDMI Audio".to_string(),
+                id: "alsa:hw:0".to_string(),
+                device_type: DeviceType::Output,
+                available: true,
+            },
+            AudioDevice {
+                name: "scarlett-2i2".to_string(),
+                description: "Focusrite Scarlett 2i2 USB".to_string(),
+                id: "alsa:hw:1".to_string(),
+                device_type: DeviceType::Duplex,
+                available: true,
+            },
+        ];
+
+        let best = select_best_exclusive_device(&devices).unwrap();
+        assert_eq!(best.id, "alsa:hw:1");
+
+        // HDMI alone is never a recommendation
+        assert!(select_best_exclusive_device(&devices[..1]).is_none());
+        assert!(select_best_exclusive_device(&[]).is_none());
+    }
+
     #[test]
     fn test_audio_settings_validation() {
         let valid = AudioSettings::new(48000, 24, 512, "default".to_string());
@@ -1207,6 +3748,129 @@ mod tests {
         assert!(invalid_depth.validate().is_err());
     }
 
+    #[test]
+    fn test_allowed_rates() {
+        let settings = AudioSettings::new(48000, 24, 512, "default".to_string());
+        assert_eq!(settings.allowed_rates_list(), "48000");
+
+        let settings = settings.with_allowed_rates(vec![48000, 44100]);
+        assert_eq!(settings.effective_allowed_rates(), vec![44100, 48000]);
+        assert_eq!(settings.allowed_rates_list(), "44100, 48000");
+        assert!(settings.validate().is_ok());
+
+        let invalid = AudioSettings::new(48000, 24, 512, "default".to_string())
+            .with_allowed_rates(vec![22050]);
+        assert!(invalid.validate().is_err());
+
+        // Preferences written before allowed rates existed still load
+        let old: AudioSettings = toml::from_str(
+            "sample_rate = 48000\nbit_depth = 24\nbuffer_size = 512\ndevice_id = \"default\"",
+        )
+        .unwrap();
+        assert!(old.allowed_rates.is_empty());
+        assert!(old.node_description.is_none());
+        assert!(old.period_size.is_none());
+    }
+
+    #[test]
+    fn test_period_size() {
+        let settings = AudioSettings::new(48000, 24, 512, "default".to_string());
+        assert_eq!(settings.hardware_period_size(), 512);
+
+        let split = settings.clone().with_period_size(Some(128));
+        assert_eq!(split.hardware_period_size(), 128);
+        assert_eq!(split.buffer_size, 512);
+        assert!(split.validate().is_ok());
+
+        assert!(settings.with_period_size(Some(100)).validate().is_err());
+    }
+
+    #[test]
+    fn test_node_description() {
+        let settings = AudioSettings::new(48000, 24, 512, "default".to_string());
+        assert!(settings.node_description.is_none());
+
+        let named = settings.clone().with_node_description("  Studio Monitors ");
+        assert_eq!(named.node_description.as_deref(), Some("Studio Monitors"));
+
+        let blank = settings.with_node_description("   ");
+        assert!(blank.node_description.is_none());
+    }
+
+    #[test]
+    fn test_parse_metadata_value() {
+        let output = "Found \"settings\" metadata 31\n\
+                      update: id:0 key:'clock.force-rate' value:'96000' type:''\n";
+        assert_eq!(
+            parse_metadata_value(output, "clock.force-rate"),
+            Some("96000".to_string())
+        );
+        assert_eq!(parse_metadata_value(output, "clock.force-quantum"), None);
+        assert_eq!(
+            parse_metadata_value("Found \"settings\" metadata 31\n", "clock.force-rate"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_allowed_rates() {
+        assert_eq!(
+            parse_allowed_rates("[ 44100 48000 96000 ]"),
+            vec![44100, 48000, 96000]
+        );
+        assert_eq!(parse_allowed_rates("[ 48000, 96000 ]"), vec![48000, 96000]);
+        assert_eq!(parse_allowed_rates("[ ]"), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn test_effects_managers() {
+        assert!(is_effects_node("easyeffects_sink"));
+        assert!(is_effects_node("PulseEffects_apps"));
+        assert!(!is_effects_node("alsa_output.usb-Focusrite_Scarlett_2i2"));
+
+        assert_eq!(EffectsManagers::default().warning(false), None);
+
+        let effects = EffectsManagers {
+            running: vec!["EasyEffects".to_string()],
+            default_sink: Some("easyeffects_sink".to_string()),
+            default_source: None,
+        };
+        let output = effects.warning(false).unwrap();
+        assert!(output.contains("easyeffects_sink"));
+        assert!(output.contains("inside EasyEffects"));
+        assert!(effects.warning(true).unwrap().starts_with("Note:"));
+    }
+
+    #[test]
+    fn test_unsupported_rates() {
+        let capabilities = DeviceCapabilities {
+            sample_rates: vec![44100, 48000, 96000],
+            formats: vec!["S24LE".to_string()],
+            buffer_sizes: vec![128, 256],
+            min_buffer_size: 128,
+            max_buffer_size: 256,
+            period_sizes: vec![64, 128],
+            detected: true,
+        };
+
+        assert!(capabilities.unsupported_rates(&[48000]).is_empty());
+        assert_eq!(
+            capabilities.unsupported_rates(&[384000, 44100, 192000]),
+            vec![384000, 192000]
+        );
+
+        let settings = AudioSettings::new(48000, 24, 256, "default".to_string());
+        assert!(validate_settings_against_device(&settings, &capabilities).is_ok());
+
+        let settings = AudioSettings::new(192000, 24, 1024, "default".to_string())
+            .with_allowed_rates(vec![44100, 384000]);
+        let warnings = validate_settings_against_device(&settings, &capabilities).unwrap_err();
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings[0].contains("192000 Hz"));
+        assert!(warnings[1].contains("384000 Hz"));
+        assert!(warnings[2].contains("maximum of 256"));
+    }
+
     #[test]
     fn test_device_id_validation() {
         assert!(is_valid_device_id("default"));
@@ -1286,11 +3950,15 @@ mod tests {
             default.clock.quantum = 256
         "#;
 
-        let (sample_rate, bit_depth, buffer_size) = parse_pipewire_settings(test_output);
+        let (sample_rate, bit_depth, buffer_size) = parse_pipewire_settings(test_output).unwrap();
 
         assert_eq!(sample_rate, 96000);
         assert_eq!(bit_depth, 32);
         assert_eq!(buffer_size, 256);
+
+        // Missing values must not be replaced with made-up defaults
+        assert_eq!(parse_pipewire_settings("default.clock.rate = 48000"), None);
+        assert_eq!(parse_pipewire_settings(""), None);
     }
 
     #[test]
@@ -7,27 +7,28 @@
  * Real-time audio monitoring with auto-detection
  */
 
+use crate::audio::pipewire_command;
 use bytemuck;
 use std::f32;
 use std::process::Command;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::mpsc;
 use std::thread;
 use std::time::{Duration, Instant};
 
 // ====== AUTO-CONNECT FUNCTION (PUBLIC, MODULE LEVEL) ======
 
-pub fn auto_connect_monitor_delayed() -> Result<(), String> {
+pub fn auto_connect_monitor_delayed() -> Result<LinkResult, String> {
     use std::process::Command;
     use std::thread;
     use std::time::Duration;
 
-    println!("DEBUG: Starting automatic audio connection...");
+    log::debug!("Starting automatic audio connection...");
     thread::sleep(Duration::from_millis(1500));
 
     // 1. Get all monitor ports
-    let output = Command::new("pw-link")
+    let output = pipewire_command("pw-link")
         .args(["--output"])
         .output()
         .map_err(|e| format!("Failed to run pw-link: {}", e))?;
@@ -53,9 +54,9 @@ pub fn auto_connect_monitor_delayed() -> Result<(), String> {
         );
     }
 
-    println!("DEBUG: Found {} monitor ports:", monitor_ports.len());
+    log::debug!("Found {} monitor ports:", monitor_ports.len());
     for port in &monitor_ports {
-        println!("  - {}", port);
+        log::info!("  - {}", port);
     }
 
     // 3. Group ports by their output device
@@ -91,7 +92,7 @@ pub fn auto_connect_monitor_delayed() -> Result<(), String> {
         };
 
         if !active_device.is_empty() {
-            println!("DEBUG: Active audio sink: {}", active_device);
+            log::debug!("Active audio sink: {}", active_device);
             // Find matching device
             devices
                 .keys()
@@ -109,14 +110,14 @@ pub fn auto_connect_monitor_delayed() -> Result<(), String> {
     };
 
     let device_ports = &devices[&chosen_device];
-    println!(
-        "DEBUG: Selected device: {} ({} channels)",
+    log::debug!(
+        "Selected device: {} ({} channels)",
         chosen_device,
         device_ports.len()
     );
 
     // 5. Connect each channel
-    let mut successful_connections = 0;
+    let mut pairs = Vec::new();
 
     for monitor_port in device_ports {
         // Extract channel name (e.g., "FL" from "monitor_FL")
@@ -128,81 +129,240 @@ pub fn auto_connect_monitor_delayed() -> Result<(), String> {
 
         // Build target port name
         let target_port = format!("pro_audio_config:input_{}", channel);
+        pairs.push((monitor_port.clone(), target_port));
+    }
 
-        println!("DEBUG: Connecting {} -> {}", monitor_port, target_port);
-
-        // Try to connect
-        for attempt in 1..=3 {
-            match Command::new("pw-link")
-                .args([monitor_port, &target_port])
-                .status()
-            {
-                Ok(status) if status.success() => {
-                    println!("DEBUG: ✓ Connected {} channel", channel);
-                    successful_connections += 1;
-                    break;
-                }
-                Ok(_) if attempt < 3 => {
-                    println!(
-                        "DEBUG: Retrying {} channel (attempt {})...",
-                        channel, attempt
+    // 6. Verify and report
+    let result = connect_port_pairs(&pairs);
+
+    if result.is_success() {
+        log::debug!(
+            "✓ Successfully connected {}/{} channels",
+            result.verified,
+            device_ports.len()
+        );
+        log::debug!("Play audio to see levels in your app!");
+        Ok(result)
+    } else {
+        Err("Failed to connect any audio channels".to_string())
+    }
+}
+
+// ====== PW-LINK HELPERS ======
+
+/// Outcome of linking monitor ports to the meter input ports
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LinkResult {
+    /// Channels we attempted to link
+    pub attempted: usize,
+    /// Channels where pw-link reported success
+    pub connected: usize,
+    /// Channels confirmed present in `pw-link --links`
+    pub verified: usize,
+}
+
+impl LinkResult {
+    pub fn is_success(&self) -> bool {
+        self.verified > 0
+    }
+}
+
+/// Run `op` up to `attempts` times, doubling the delay after each failure.
+/// The closure receives the 1-based attempt number.
+pub fn retry_with_backoff<T, F>(mut op: F, attempts: u32, base_delay: Duration) -> Result<T, String>
+where
+    F: FnMut(u32) -> Result<T, String>,
+{
+    let attempts = attempts.max(1);
+    let mut delay = base_delay;
+    let mut last_error = String::new();
+
+    for attempt in 1..=attempts {
+        match op(attempt) {
+            Ok(value) => return Ok(value),
+            Err(e) => {
+                last_error = e;
+                if attempt < attempts {
+                    log::debug!(
+                        "Attempt {}/{} failed: {}, retrying in {}ms...",
+                        attempt,
+                        attempts,
+                        last_error,
+                        delay.as_millis()
                     );
-                    thread::sleep(Duration::from_millis(300));
-                }
-                Err(e) if attempt < 3 => {
-                    println!("DEBUG: Error connecting {}: {}, retrying...", channel, e);
-                    thread::sleep(Duration::from_millis(300));
-                }
-                _ => {
-                    println!("DEBUG: ✗ Failed to connect {} channel", channel);
+                    thread::sleep(delay);
+                    delay = delay.saturating_mul(2);
                 }
             }
         }
     }
 
-    // 6. Verify and report
-    thread::sleep(Duration::from_millis(500));
-
-    // Check connections
-    if let Ok(links_output) = Command::new("pw-link").args(["--links"]).output() {
-        let links_str = String::from_utf8_lossy(&links_output.stdout);
-        let our_links = links_str
-            .lines()
-            .filter(|line| line.contains("pro_audio_config"))
-            .count();
-
-        println!(
-            "DEBUG: Active connections to pro_audio_config: {}",
-            our_links
+    Err(format!(
+        "Failed after {} attempts: {}",
+        attempts, last_error
+    ))
+}
+
+/// Link each (source, target) pair with backoff, then confirm the links
+/// really exist in the graph before reporting them as connected.
+pub fn connect_port_pairs(pairs: &[(String, String)]) -> LinkResult {
+    let mut result = LinkResult {
+        attempted: pairs.len(),
+        ..Default::default()
+    };
+
+    for (source, target) in pairs {
+        log::debug!("Connecting {} -> {}", source, target);
+
+        let linked = retry_with_backoff(
+            |_| link_ports(source, target),
+            5,
+            Duration::from_millis(100),
         );
+
+        match linked {
+            Ok(()) => {
+                log::debug!("✓ Connected {}", source);
+                result.connected += 1;
+            }
+            Err(e) => log::debug!("✗ Failed to connect {}: {}", source, e),
+        }
     }
 
-    if successful_connections > 0 {
-        println!(
-            "DEBUG: ✓ Successfully connected {}/{} channels",
-            successful_connections,
-            device_ports.len()
-        );
-        println!("DEBUG: Play audio to see levels in your app!");
-        Ok(())
-    } else {
-        Err("Failed to connect any audio channels".to_string())
+    if result.connected == 0 {
+        return result;
     }
+
+    // Give the graph a moment to settle before checking
+    thread::sleep(Duration::from_millis(300));
+
+    match list_active_links() {
+        Ok(links) => {
+            result.verified = pairs.iter().filter(|pair| links.contains(pair)).count();
+            log::debug!(
+                "Verified {}/{} links to pro_audio_config",
+                result.verified,
+                result.attempted
+            );
+        }
+        Err(e) => {
+            log::warn!("Could not verify links ({}), trusting pw-link", e);
+            result.verified = result.connected;
+        }
+    }
+
+    result
+}
+
+fn link_ports(source: &str, target: &str) -> Result<(), String> {
+    let output = pipewire_command("pw-link")
+        .args([source, target])
+        .output()
+        .map_err(|e| format!("Failed to run pw-link: {}", e))?;
+
+    if output.status.success() {
+        return Ok(());
+    }
+
+    // pw-link refuses to create a link that already exists
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.contains("File exists") {
+        return Ok(());
+    }
+
+    Err(format!("pw-link failed: {}", stderr.trim()))
+}
+
+fn list_active_links() -> Result<Vec<(String, String)>, String> {
+    let output = pipewire_command("pw-link")
+        .args(["--links"])
+        .output()
+        .map_err(|e| format!("Failed to run pw-link --links: {}", e))?;
+
+    if !output.status.success() {
+        return Err("pw-link --links command failed".to_string());
+    }
+
+    Ok(parse_pw_link_links(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Parse `pw-link --links` output into (output port, input port) pairs
+fn parse_pw_link_links(output: &str) -> Vec<(String, String)> {
+    let mut links = Vec::new();
+    let mut current_source: Option<String> = None;
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        if let Some(target) = trimmed.strip_prefix("|->") {
+            if let Some(source) = &current_source {
+                links.push((source.clone(), target.trim().to_string()));
+            }
+        } else if trimmed.starts_with("|<-") {
+            continue;
+        } else {
+            current_source = Some(trimmed.to_string());
+        }
+    }
+
+    links
 }
 
 // ====== AUDIO LEVELS STRUCT ======
 #[derive(Debug, Clone)]
 pub struct AudioLevels {
+    /// Meter positions, 0.0 (-60 dBFS or below) to 1.0 (0 dBFS)
     pub left_peak: f64,
     pub right_peak: f64,
     pub left_db: String,
     pub right_db: String,
+    /// Peaks in dBFS, for callers that need the number
+    pub left_dbfs: f64,
+    pub right_dbfs: f64,
+}
+
+/// Lowest level the meters show
+const METER_FLOOR_DB: f64 = -60.0;
+
+impl AudioLevels {
+    /// Levels from linear sample peaks (1.0 = full scale)
+    pub fn from_peaks(left_peak: f32, right_peak: f32) -> Self {
+        let left_dbfs = peak_to_dbfs(left_peak);
+        let right_dbfs = peak_to_dbfs(right_peak);
+        let meter = |db: f64| ((db - METER_FLOOR_DB) / -METER_FLOOR_DB).clamp(0.0, 1.0);
+
+        AudioLevels {
+            left_peak: meter(left_dbfs),
+            right_peak: meter(right_dbfs),
+            left_db: format!("{:.1} dB", left_dbfs),
+            right_db: format!("{:.1} dB", right_dbfs),
+            left_dbfs,
+            right_dbfs,
+        }
+    }
+}
+
+/// Converts a linear peak to dBFS, -80 dB for silence
+pub fn peak_to_dbfs(peak: f32) -> f64 {
+    20.0 * (peak.abs() as f64).max(0.0001).log10()
 }
 
 // ====== PIPE WIRE MONITOR ======
+/// Level update interval unless the user picked another one
+pub const DEFAULT_UPDATE_INTERVAL_MS: u64 = 100;
+
 pub struct PipeWireMonitor {
     running: Arc<AtomicBool>,
     use_real_monitoring: Arc<AtomicBool>,
+    /// How often levels are sent, in ms; shared so it can change while running
+    update_interval_ms: Arc<AtomicU64>,
+    /// Send simulated levels when real capture is not possible
+    simulation_fallback: bool,
 }
 
 impl Default for PipeWireMonitor {
@@ -219,13 +379,33 @@ impl PipeWireMonitor {
         PipeWireMonitor {
             running: Arc::new(AtomicBool::new(false)),
             use_real_monitoring: Arc::new(AtomicBool::new(use_real)),
+            update_interval_ms: Arc::new(AtomicU64::new(DEFAULT_UPDATE_INTERVAL_MS)),
+            simulation_fallback: true,
         }
     }
 
+    /// Reads the level update interval from `interval_ms` on every update,
+    /// so the owner can change it without restarting the monitor
+    pub fn with_update_interval(mut self, interval_ms: Arc<AtomicU64>) -> Self {
+        self.update_interval_ms = interval_ms;
+        self
+    }
+
+    /// Stops instead of simulating when real capture fails, closing the
+    /// channel, for callers that must not mistake made-up levels for audio
+    pub fn without_simulation(mut self) -> Self {
+        self.simulation_fallback = false;
+        self
+    }
+
     /// Detect if PipeWire is available and running
     fn detect_pipewire_available() -> bool {
         // Method 1: Check if pw-dump command works
-        if Command::new("pw-dump").arg("--version").output().is_ok() {
+        if pipewire_command("pw-dump")
+            .arg("--version")
+            .output()
+            .is_ok()
+        {
             return true;
         }
 
@@ -270,38 +450,53 @@ impl PipeWireMonitor {
     ) -> Result<thread::JoinHandle<()>, String> {
         let running = Arc::clone(&self.running);
         let use_real = self.use_real_monitoring.load(Ordering::SeqCst);
+        let interval_ms = Arc::clone(&self.update_interval_ms);
+        let simulation_fallback = self.simulation_fallback;
 
         running.store(true, Ordering::SeqCst);
 
         let handle = thread::spawn(move || {
             if use_real {
-                println!("INFO: Starting real PipeWire audio monitoring");
+                log::info!("Starting real PipeWire audio monitoring");
 
                 // Clone sender for fallback if real monitoring fails
                 let sender_clone = sender.clone();
 
-                match Self::start_real_monitoring(Arc::clone(&running), sender) {
+                match Self::start_real_monitoring(
+                    Arc::clone(&running),
+                    sender,
+                    Arc::clone(&interval_ms),
+                ) {
                     Ok(_) => {
-                        println!("INFO: Real monitoring completed");
+                        log::info!("Real monitoring completed");
+                    }
+                    Err(e) if !simulation_fallback => {
+                        log::error!("Real monitoring failed: {}", e);
                     }
                     Err(e) => {
-                        eprintln!("WARNING: Real monitoring failed: {}", e);
-                        eprintln!("Falling back to simulation mode");
+                        log::warn!("Real monitoring failed: {}", e);
+                        log::warn!("Falling back to simulation mode");
                         // Use cloned sender for simulation
-                        Self::start_simulation(Arc::clone(&running), sender_clone);
+                        Self::start_simulation(Arc::clone(&running), sender_clone, interval_ms);
                     }
                 }
+            } else if !simulation_fallback {
+                log::error!("PipeWire not detected, no levels to monitor");
             } else {
                 // Start simulation directly
-                println!("INFO: Starting simulated audio monitoring");
-                Self::start_simulation(Arc::clone(&running), sender);
+                log::info!("Starting simulated audio monitoring");
+                Self::start_simulation(Arc::clone(&running), sender, interval_ms);
             }
         });
 
         Ok(handle)
     }
 
-    fn start_simulation(running: Arc<AtomicBool>, sender: mpsc::Sender<AudioLevels>) {
+    fn start_simulation(
+        running: Arc<AtomicBool>,
+        sender: mpsc::Sender<AudioLevels>,
+        interval_ms: Arc<AtomicU64>,
+    ) {
         let mut iteration = 0;
         let start_time = Instant::now();
 
@@ -316,7 +511,7 @@ impl PipeWireMonitor {
             }
 
             iteration += 1;
-            thread::sleep(Duration::from_millis(100)); // 10 FPS update
+            thread::sleep(Duration::from_millis(interval_ms.load(Ordering::Relaxed)));
         }
     }
 
@@ -324,6 +519,7 @@ impl PipeWireMonitor {
     fn start_real_monitoring(
         running: Arc<AtomicBool>,
         sender: mpsc::Sender<AudioLevels>,
+        interval_ms: Arc<AtomicU64>,
     ) -> Result<(), String> {
         use libspa::pod::Pod;
         use libspa::utils::Direction;
@@ -340,8 +536,13 @@ impl PipeWireMonitor {
         let context = pw::context::ContextRc::new(&mainloop, None)
             .map_err(|e| format!("Failed to create Context: {}", e))?;
 
+        // Honor a configured runtime dir / remote (multi-seat, nested sessions)
+        let core_props = crate::audio::resolve_pipewire_remote()
+            .remote_name()
+            .map(|name| pw::properties::properties! { *pw::keys::REMOTE_NAME => name });
+
         let core = context
-            .connect_rc(None)
+            .connect_rc(core_props)
             .map_err(|e| format!("Failed to connect Core: {}", e))?;
 
         // 2. Create stream properties
@@ -356,7 +557,7 @@ impl PipeWireMonitor {
         let stream = pw::stream::StreamBox::new(&core, "ProAudioMonitor", props)
             .map_err(|e| format!("Failed to create Stream: {}", e))?;
 
-        println!("DEBUG: Stream created successfully");
+        log::debug!("Stream created successfully");
 
         // 4. Start auto-connect in a separate thread
         let running_clone = Arc::clone(&running);
@@ -367,11 +568,11 @@ impl PipeWireMonitor {
                 return;
             }
 
-            println!("DEBUG: Auto-connecting to audio outputs...");
+            log::debug!("Auto-connecting to audio outputs...");
             // Call your existing auto_connect_monitor_delayed function
             if let Err(e) = auto_connect_monitor_delayed() {
-                println!("WARN: Auto-connect failed: {}", e);
-                println!("INFO: Manual connection may be needed");
+                log::warn!("Auto-connect failed: {}", e);
+                log::info!("Manual connection may be needed");
             }
         });
 
@@ -424,22 +625,15 @@ impl PipeWireMonitor {
                             }
                         }
 
-                        // Send updates periodically (every 100ms)
+                        // Send updates periodically, peaks held in between
                         if let Some(last) = user_data.last_update
-                            && last.elapsed() >= Duration::from_millis(100)
+                            && last.elapsed()
+                                >= Duration::from_millis(interval_ms.load(Ordering::Relaxed))
                         {
-                            let left_db = 20.0 * (user_data.left_peak.max(0.0001).log10());
-                            let right_db = 20.0 * (user_data.right_peak.max(0.0001).log10());
-
-                            let left_level = ((left_db + 60.0) / 60.0).clamp(0.0, 1.0) as f64;
-                            let right_level = ((right_db + 60.0) / 60.0).clamp(0.0, 1.0) as f64;
-
-                            let _ = sender_clone.send(AudioLevels {
-                                left_peak: left_level,
-                                right_peak: right_level,
-                                left_db: format!("{:.1} dB", left_db),
-                                right_db: format!("{:.1} dB", right_db),
-                            });
+                            let _ = sender_clone.send(AudioLevels::from_peaks(
+                                user_data.left_peak,
+                                user_data.right_peak,
+                            ));
 
                             user_data.left_peak = 0.0;
                             user_data.right_peak = 0.0;
@@ -480,7 +674,7 @@ impl PipeWireMonitor {
             )
             .map_err(|e| format!("Failed to connect stream: {}", e))?;
 
-        println!("DEBUG: Stream connected with AUTOCONNECT flag");
+        log::debug!("Stream connected with AUTOCONNECT flag");
 
         // 9. Run the main loop
         while running.load(Ordering::SeqCst) {
@@ -498,6 +692,7 @@ impl PipeWireMonitor {
     fn start_real_monitoring(
         _running: Arc<AtomicBool>,
         _sender: mpsc::Sender<AudioLevels>,
+        _interval_ms: Arc<AtomicU64>,
     ) -> Result<(), String> {
         Err(
             "PipeWire feature not compiled in. Rebuild with --features pipewire-monitoring"
@@ -544,6 +739,8 @@ impl PipeWireMonitor {
             right_peak: right_level,
             left_db: format!("{:.1} dB", left_db),
             right_db: format!("{:.1} dB", right_db),
+            left_dbfs: left_db,
+            right_dbfs: right_db,
         }
     }
 
@@ -563,6 +760,8 @@ mod tests {
             right_peak: 0.6,
             left_db: "-6.0 dB".to_string(),
             right_db: "-4.0 dB".to_string(),
+            left_dbfs: -6.0,
+            right_dbfs: -4.0,
         };
 
         assert_eq!(levels.left_peak, 0.5);
@@ -571,6 +770,59 @@ mod tests {
         assert!(levels.right_db.contains("dB"));
     }
 
+    #[test]
+    fn test_levels_from_peaks() {
+        let levels = AudioLevels::from_peaks(1.0, 0.5);
+        assert_eq!(levels.left_dbfs, 0.0);
+        assert_eq!(levels.left_peak, 1.0);
+        assert!((levels.right_dbfs + 6.02).abs() < 0.01);
+        assert_eq!(levels.right_db, "-6.0 dB");
+
+        let silence = AudioLevels::from_peaks(0.0, 0.0);
+        assert_eq!(silence.left_dbfs, -80.0);
+        assert_eq!(silence.left_peak, 0.0);
+    }
+
+    #[test]
+    fn test_retry_with_backoff() {
+        let mut calls = 0;
+        let result = retry_with_backoff(
+            |attempt| {
+                calls += 1;
+                if attempt < 3 {
+                    Err("busy".to_string())
+                } else {
+                    Ok(attempt)
+                }
+            },
+            5,
+            Duration::from_millis(1),
+        );
+        assert_eq!(result, Ok(3));
+        assert_eq!(calls, 3);
+
+        let failed: Result<(), String> =
+            retry_with_backoff(|_| Err("busy".to_string()), 2, Duration::from_millis(1));
+        assert!(failed.unwrap_err().contains("2 attempts"));
+    }
+
+    #[test]
+    fn test_parse_pw_link_links() {
+        let output = "alsa_output.usb:monitor_FL\n  |->  pro_audio_config:input_FL\n\
+                      alsa_output.usb:monitor_FR\n  |->  pro_audio_config:input_FR\n\
+                      pro_audio_config:input_FR\n  |<-  alsa_output.usb:monitor_FR\n";
+        let links = parse_pw_link_links(output);
+
+        assert_eq!(links.len(), 2);
+        assert_eq!(
+            links[0],
+            (
+                "alsa_output.usb:monitor_FL".to_string(),
+                "pro_audio_config:input_FL".to_string()
+            )
+        );
+    }
+
     #[test]
     fn test_simulation() {
         let levels = PipeWireMonitor::simulate_audio_levels(0, 0.0);
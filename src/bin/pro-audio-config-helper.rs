@@ -0,0 +1,27 @@
+/*
+ * Pro Audio Config - Privileged Helper
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * Started through pkexec by the app. Reads a batch of file operations
+ * (one JSON object per line) from stdin and applies them to
+ * /etc/pipewire and /etc/wireplumber
+ */
+
+fn main() {
+    // Only meaningful as root; pkexec is the intended way in
+    if unsafe { libc::geteuid() } != 0 {
+        eprintln!("Error: pro-audio-config-helper must be started through pkexec");
+        std::process::exit(1);
+    }
+
+    match pro_audio_config::privileged::run_helper(std::io::stdin().lock()) {
+        Ok(count) => println!("✓ {} system config operation(s) completed", count),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,262 @@
+/*
+ * Pro Audio Config - Command Line Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * Headless apply and detection (--apply-output, --apply-input, --detect)
+ * for machines without a display, e.g. a studio box reached over SSH, and
+ * the long-running --watch and --meter modes
+ */
+
+use crate::audio::{
+    AudioDevice, AudioSettings, DeviceType, detect_all_audio_devices, detect_current_audio_settings,
+};
+use crate::config::{
+    apply_input_audio_settings_with_auth_blocking, apply_output_audio_settings_with_auth_blocking,
+    apply_user_audio_settings,
+};
+use crate::meter::{parse_interval, run_meter};
+use crate::watch::run_watch;
+
+pub const CLI_USAGE: &str = "\
+Usage:
+  pro-audio-config --apply-output [options]   apply output settings
+  pro-audio-config --apply-input [options]    apply input settings
+  pro-audio-config --detect                   list devices and the running settings
+  pro-audio-config --watch <process>          apply the session profile while <process> runs
+  pro-audio-config --meter [ms]               print left/right output levels in dBFS
+
+Apply options:
+  --rate <hz>         sample rate (default 48000)
+  --buffer <samples>  buffer size (default 512)
+  --bit-depth <bits>  bit depth (default 24)
+  --device <id>       device id as listed by --detect (default \"default\")
+  --system            write the system-wide config (asks for the admin password)
+
+Any mode:
+  --verbose, -v       show debug output (RUST_LOG=debug does the same)";
+
+/// A command-line mode that runs instead of the GUI
+#[derive(Clone, Debug)]
+pub enum CliCommand {
+    Apply {
+        /// "output" or "input"
+        stream_type: &'static str,
+        settings: AudioSettings,
+        system_wide: bool,
+    },
+    Detect,
+    /// Process name to watch for
+    Watch(String),
+    /// Update interval in milliseconds, None for the default
+    Meter(Option<u64>),
+    Help,
+}
+
+/// The command in `args` (without the program name). None when the first
+/// argument is not one of ours, so the GUI starts as before.
+pub fn parse_cli_args(args: &[String]) -> Result<Option<CliCommand>, String> {
+    let stream_type = match args.first().map(String::as_str) {
+        Some("--apply-output") => "output",
+        Some("--apply-input") => "input",
+        Some("--detect") if args.len() == 1 => return Ok(Some(CliCommand::Detect)),
+        Some("--detect") => return Err("--detect takes no options".to_string()),
+        Some("--watch") => {
+            return match &args[1..] {
+                [process_name] => Ok(Some(CliCommand::Watch(process_name.clone()))),
+                [] => Err("--watch needs a process name, e.g. --watch reaper".to_string()),
+                _ => Err("--watch takes a single process name".to_string()),
+            };
+        }
+        Some("--meter") => {
+            return match &args[1..] {
+                [] => Ok(Some(CliCommand::Meter(None))),
+                [interval] => parse_interval(interval).map(|ms| Some(CliCommand::Meter(Some(ms)))),
+                _ => Err("--meter takes at most an interval in ms".to_string()),
+            };
+        }
+        Some("--help" | "-h") => return Ok(Some(CliCommand::Help)),
+        _ => return Ok(None),
+    };
+
+    let mut settings = AudioSettings::new(48000, 24, 512, "default".to_string());
+    let mut system_wide = false;
+
+    let mut options = args[1..].iter();
+    while let Some(option) = options.next() {
+        if option == "--system" {
+            system_wide = true;
+            continue;
+        }
+
+        let value = options
+            .next()
+            .ok_or_else(|| format!("{} needs a value", option))?;
+        match option.as_str() {
+            "--rate" => settings.sample_rate = parse_number(option, value)?,
+            "--buffer" => settings.buffer_size = parse_number(option, value)?,
+            "--bit-depth" => settings.bit_depth = parse_number(option, value)?,
+            "--device" => settings.device_id = value.clone(),
+            _ => return Err(format!("Unknown option: {}", option)),
+        }
+    }
+
+    settings.validate()?;
+
+    Ok(Some(CliCommand::Apply {
+        stream_type,
+        settings,
+        system_wide,
+    }))
+}
+
+fn parse_number(option: &str, value: &str) -> Result<u32, String> {
+    value
+        .parse::<u32>()
+        .map_err(|_| format!("{} expects a number, got '{}'", option, value))
+}
+
+/// Runs `command`, printing the result to stdout. An Err is a failed apply
+/// or detection; the caller turns it into a non-zero exit code.
+pub fn run_cli(command: CliCommand) -> Result<(), String> {
+    match command {
+        CliCommand::Apply {
+            stream_type,
+            settings,
+            system_wide,
+        } => run_apply(stream_type, settings, system_wide),
+        CliCommand::Detect => run_detect(),
+        CliCommand::Watch(process_name) => run_watch(&process_name),
+        CliCommand::Meter(interval_ms) => run_meter(interval_ms),
+        CliCommand::Help => {
+            println!("{}", CLI_USAGE);
+            Ok(())
+        }
+    }
+}
+
+fn run_apply(stream_type: &str, settings: AudioSettings, system_wide: bool) -> Result<(), String> {
+    let summary = format!(
+        "{}Hz/{}bit/{} samples on {}",
+        settings.sample_rate, settings.bit_depth, settings.buffer_size, settings.device_id
+    );
+
+    if system_wide {
+        match stream_type {
+            "input" => apply_input_audio_settings_with_auth_blocking(settings)?,
+            _ => apply_output_audio_settings_with_auth_blocking(settings)?,
+        }
+        println!("Applied system-wide {} settings: {}", stream_type, summary);
+    } else {
+        let outcome = apply_user_audio_settings(settings, stream_type)?;
+        println!("Applied {} settings: {}", stream_type, summary);
+        println!("{}", outcome.summary());
+        for warning in &outcome.warnings {
+            println!("Warning: {}", warning);
+        }
+    }
+
+    Ok(())
+}
+
+fn run_detect() -> Result<(), String> {
+    let devices = detect_all_audio_devices()?;
+    let current = detect_current_audio_settings()?;
+
+    println!("Devices:");
+    for device in &devices {
+        println!("  {}", format_device(device));
+    }
+    println!();
+    println!("Current settings:");
+    println!("  Sample rate: {} Hz", current.sample_rate);
+    println!("  Bit depth: {} bit", current.bit_depth);
+    println!("  Buffer size: {} samples", current.buffer_size);
+    println!("  Device: {}", current.device_id);
+
+    Ok(())
+}
+
+/// One device line: id, description, direction, e.g.
+/// "alsa:0  Scarlett 2i2 USB (duplex)"
+fn format_device(device: &AudioDevice) -> String {
+    let direction = match device.device_type {
+        DeviceType::Input => "input",
+        DeviceType::Output => "output",
+        DeviceType::Duplex => "duplex",
+        DeviceType::Unknown => "unknown",
+    };
+    let unavailable = if device.available {
+        ""
+    } else {
+        ", unavailable"
+    };
+
+    format!(
+        "{}  {} ({}{})",
+        device.id, device.description, direction, unavailable
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse_cli_args() {
+        assert!(parse_cli_args(&[]).unwrap().is_none());
+        assert!(
+            parse_cli_args(&args("--gapplication-service"))
+                .unwrap()
+                .is_none()
+        );
+        assert!(matches!(
+            parse_cli_args(&args("--watch reaper")).unwrap(),
+            Some(CliCommand::Watch(name)) if name == "reaper"
+        ));
+        assert!(parse_cli_args(&args("--watch")).is_err());
+        assert!(matches!(
+            parse_cli_args(&args("--meter")).unwrap(),
+            Some(CliCommand::Meter(None))
+        ));
+        assert!(matches!(
+            parse_cli_args(&args("--meter 50")).unwrap(),
+            Some(CliCommand::Meter(Some(50)))
+        ));
+        assert!(parse_cli_args(&args("--meter 5")).is_err());
+        assert!(matches!(
+            parse_cli_args(&args("--detect")).unwrap(),
+            Some(CliCommand::Detect)
+        ));
+
+        let Some(CliCommand::Apply {
+            stream_type,
+            settings,
+            system_wide,
+        }) = parse_cli_args(&args(
+            "--apply-output --rate 96000 --buffer 256 --device alsa:1 --system",
+        ))
+        .unwrap()
+        else {
+            panic!("expected an apply command");
+        };
+        assert_eq!(stream_type, "output");
+        assert_eq!(settings.sample_rate, 96000);
+        assert_eq!(settings.buffer_size, 256);
+        assert_eq!(settings.bit_depth, 24);
+        assert_eq!(settings.device_id, "alsa:1");
+        assert!(system_wide);
+
+        assert!(parse_cli_args(&args("--apply-input --rate")).is_err());
+        assert!(parse_cli_args(&args("--apply-input --rate fast")).is_err());
+        assert!(parse_cli_args(&args("--apply-input --rate 12345")).is_err());
+        assert!(parse_cli_args(&args("--apply-input --latency 5")).is_err());
+        assert!(parse_cli_args(&args("--detect --system")).is_err());
+    }
+}
@@ -8,20 +8,42 @@
  * and multiple fallback approaches.
  */
 
-use crate::audio::AudioSettings;
-use chrono::Local;
-use glob::glob;
+use crate::audio::{
+    AudioDevice, AudioSettings, DeviceType, MixerState, detect_current_audio_settings,
+    pipewire_command,
+};
+use crate::error::AudioConfigError;
+use crate::pipewire_config::{PipeWireConfig, PipeWireModule, PipeWireObject, quote_string};
+use crate::privileged::{
+    PrivilegedOp, helper_handles, pkexec_cancelled, run_with_helper, shell_batch_args,
+};
+use crate::utils::unified_diff;
+use crate::wireplumber_config::{WirePlumberConfig, WirePlumberRule};
+use chrono::{Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::time::{Duration, Instant};
 
+/// Also write pulse.* quantum limits for pipewire-pulse, set from preferences
+static SYNC_PULSE_QUANTUM: AtomicBool = AtomicBool::new(false);
+
+/// rt.time.soft/hard written into every libpipewire-module-rt block, set from preferences
+static RT_TIME_SOFT_US: AtomicU32 = AtomicU32::new(DEFAULT_RT_TIME_US);
+static RT_TIME_HARD_US: AtomicU32 = AtomicU32::new(DEFAULT_RT_TIME_US);
+
+/// Service names for restarts, set from preferences; None uses the defaults
+static SERVICE_NAMES: Mutex<Option<ServiceNames>> = Mutex::new(None);
+
 // ============================================================================
 // PUBLIC API FUNCTIONS (Alphabetically)
 // ============================================================================
 
 /// Advanced audio settings structure
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct AdvancedAudioSettings {
     pub exclusive_mode: bool,
     pub direct_hardware: bool,
@@ -30,6 +52,365 @@ pub struct AdvancedAudioSettings {
     pub sample_rate: u32,
 }
 
+/// Professional options of the Advanced tab's global apply
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProfessionalOptions {
+    pub min_buffer: u32,
+    pub max_buffer: u32,
+    /// "normal", "high" or "realtime"
+    pub thread_priority: String,
+    pub memory_lock: bool,
+    /// session.suspend-timeout-seconds, globally and in the ALSA device rule
+    pub suspend_timeout: u32,
+    pub disable_remixing: bool,
+    pub disable_resampling: bool,
+    pub resampler_quality: String,
+    pub clock_source: String,
+    /// priority.driver/session of the ALSA device rule
+    pub priority_driver: u32,
+    pub priority_session: u32,
+}
+
+/// Configuration approach that ended up being used by `update_audio_settings`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ApplyMethod {
+    PipeWireFragment,
+    WirePlumberConfig,
+    MainConfigEdit,
+}
+
+impl ApplyMethod {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ApplyMethod::PipeWireFragment => "PipeWire config fragment",
+            ApplyMethod::WirePlumberConfig => "WirePlumber config",
+            ApplyMethod::MainConfigEdit => "main pipewire.conf edit",
+        }
+    }
+}
+
+/// What actually happened when settings were applied
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApplyOutcome {
+    pub method_used: ApplyMethod,
+    pub verified: bool,
+    pub detected_rate: Option<u32>,
+    pub detected_quantum: Option<u32>,
+    pub warnings: Vec<String>,
+}
+
+impl ApplyOutcome {
+    pub fn new(method_used: ApplyMethod) -> Self {
+        Self {
+            method_used,
+            verified: false,
+            detected_rate: None,
+            detected_quantum: None,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Human readable one-line summary for status labels
+    pub fn summary(&self) -> String {
+        let detected = match (self.detected_rate, self.detected_quantum) {
+            (Some(rate), Some(quantum)) => format!(" (running at {}Hz/{} samples)", rate, quantum),
+            _ => String::new(),
+        };
+
+        if self.verified {
+            format!(
+                "Applied via {} and confirmed{}",
+                self.method_used.label(),
+                detected
+            )
+        } else {
+            format!(
+                "Applied via {} but not verified{}",
+                self.method_used.label(),
+                detected
+            )
+        }
+    }
+}
+
+/// Result of comparing one expected setting against the running system
+#[derive(Clone, Debug, PartialEq)]
+pub struct SettingCheck {
+    pub field: &'static str,
+    pub expected: u32,
+    pub actual: u32,
+    pub matches: bool,
+}
+
+impl SettingCheck {
+    pub fn describe(&self) -> String {
+        if self.matches {
+            format!("✓ {}: {}", self.field, self.actual)
+        } else {
+            format!(
+                "✗ {}: expected {}, running {}",
+                self.field, self.expected, self.actual
+            )
+        }
+    }
+}
+
+/// Realtime scheduling privileges available to the current user
+#[derive(Clone, Debug, PartialEq)]
+pub struct RealtimePrivileges {
+    pub groups: Vec<String>,
+    pub in_realtime_group: bool,
+    /// Soft RLIMIT_RTPRIO, `None` when unlimited
+    pub rtprio_limit: Option<u64>,
+    /// Soft RLIMIT_MEMLOCK in bytes, `None` when unlimited
+    pub memlock_limit: Option<u64>,
+    pub rtkit_available: bool,
+}
+
+impl RealtimePrivileges {
+    /// Whether PipeWire can get RT priority for its data threads
+    pub fn can_use_realtime(&self) -> bool {
+        self.rtkit_available || self.rtprio_limit.is_none_or(|limit| limit > 0)
+    }
+
+    /// Explanation and fix-up hint when privileges are missing
+    pub fn warning(&self) -> Option<String> {
+        let mut problems = Vec::new();
+
+        if !self.can_use_realtime() {
+            problems
+                .push("real-time priority is capped at 0 (RLIMIT_RTPRIO) and rtkit is not running");
+        }
+        if !self.in_realtime_group
+            && self
+                .memlock_limit
+                .is_some_and(|limit| limit < 64 * 1024 * 1024)
+        {
+            problems.push("locked memory is limited (RLIMIT_MEMLOCK), mlock will be capped");
+        }
+
+        if problems.is_empty() {
+            return None;
+        }
+
+        Some(format!(
+            "⚠ Realtime privileges missing: {}.\nAdd yourself to the audio or realtime group \
+             (e.g. 'sudo usermod -aG audio {}'), then log out and back in.",
+            problems.join("; "),
+            whoami::username()
+        ))
+    }
+}
+
+/// A backup directory written before an apply
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigBackup {
+    pub path: PathBuf,
+    pub created: NaiveDateTime,
+    /// Directory the files were copied from; None when the backup does not
+    /// tell, e.g. a symlinked config dir with an unusual name
+    pub origin: Option<PathBuf>,
+    /// The `.conf` files in the backup
+    pub files: Vec<PathBuf>,
+}
+
+impl ConfigBackup {
+    pub fn is_system(&self) -> bool {
+        self.origin
+            .as_deref()
+            .is_some_and(|origin| origin.starts_with("/etc"))
+    }
+}
+
+/// Which config roots `managed_config_paths` should cover
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigScope {
+    /// The user's config dir (see `user_config_dir`)
+    User,
+    /// `/etc`
+    System,
+    All,
+}
+
+/// Application routed to the exclusive device, one rule each in the exclusive config
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExclusiveApp {
+    pub name: String,
+    /// Binary as reported in application.process.binary, may be empty
+    pub process_name: String,
+}
+
+impl ExclusiveApp {
+    pub fn new(name: &str, process_name: &str) -> Self {
+        Self {
+            name: name.trim().to_string(),
+            process_name: process_name.trim().to_string(),
+        }
+    }
+
+    /// "Reaper (reaper)" style text for lists and logs
+    pub fn label(&self) -> String {
+        if self.process_name.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} ({})", self.name, self.process_name)
+        }
+    }
+
+    fn sanitized_name(&self) -> String {
+        self.name.replace([' ', '.', ':', '"', '\''], "_")
+    }
+
+    /// Match on the process binary when known, the application name otherwise
+    fn match_rule(&self) -> String {
+        let (property, value) = self.match_property();
+        format!("{} = {}", property, quote_string(value))
+    }
+
+    fn match_property(&self) -> (&'static str, &str) {
+        if self.process_name.is_empty() {
+            ("application.name", &self.name)
+        } else {
+            ("application.process.binary", &self.process_name)
+        }
+    }
+}
+
+/// Config files written from the current settings, relative to a config root
+/// (`/etc` or the user config dir). Applies may clear these before writing.
+const APPLY_CONFIG_FILES: &[&str] = &[
+    "pipewire/pipewire.conf.d/99-pro-audio.conf",
+    "pipewire/pipewire.conf.d/99-pro-audio-high-priority.conf",
+    "pipewire/pipewire.conf.d/99-pro-audio-advanced.conf",
+    "pipewire/pipewire.conf.d/99-pro-audio-quantum-override.conf",
+    "pipewire/pipewire.conf.d/99-pro-audio-exclusive.conf",
+    "wireplumber/wireplumber.conf.d/50-pro-audio.conf",
+    "wireplumber/wireplumber.conf.d/99-pro-audio.conf",
+    "wireplumber/wireplumber.conf.d/99-pro-audio-devices.conf",
+    "wireplumber/wireplumber.conf.d/99-pro-audio-output.conf",
+    "wireplumber/wireplumber.conf.d/99-pro-audio-input.conf",
+    "wireplumber/wireplumber.conf.d/99-pro-audio-exclusive.conf",
+    "wireplumber/main.lua.d/50-pro-audio-output.lua",
+    "wireplumber/main.lua.d/50-pro-audio-input.lua",
+    "pipewire/pipewire-pulse.conf.d/99-pro-audio-pulse.conf",
+    "pipewire/pipewire.conf.d/99-pro-audio-safe.conf",
+];
+
+/// Config files set up with their own tools (combined sink, virtual devices,
+/// JACK and Pulse resampling). They outlive applies and only a reset or the
+/// tool that wrote them removes them.
+const RESET_ONLY_CONFIG_FILES: &[&str] = &[
+    "pipewire/pipewire.conf.d/99-pro-audio-combine.conf",
+    "pipewire/pipewire.conf.d/99-pro-audio-virtual.conf",
+    "pipewire/pipewire-pulse.conf.d/99-pro-audio-pulse-resample.conf",
+    "pipewire/jack.conf.d/99-pro-audio-jack.conf",
+];
+
+/// Every config file this tool can create. New config types only need a
+/// line in one of the two lists above.
+fn managed_config_files() -> impl Iterator<Item = &'static str> {
+    APPLY_CONFIG_FILES
+        .iter()
+        .chain(RESET_ONLY_CONFIG_FILES)
+        .copied()
+}
+
+/// Written as `pro-audio-config.version` into generated configs. Bump it when
+/// the generated format changes so `migrate_configs` retires older files.
+pub const CONFIG_FORMAT_VERSION: &str = "2.1";
+
+/// Why older config formats are retired, newest first
+const CONFIG_FORMAT_CHANGES: &[(&str, &str)] = &[(
+    "2.1",
+    "the exclusive-mode config had an unterminated version string that broke parsing",
+)];
+
+/// Location used by very old versions, only ever written per user
+const LEGACY_USER_CONFIG_FILE: &str = ".local/share/pipewire/pipewire.conf.d/99-pro-audio.conf";
+
+/// File names grouped by the cleanup modes of `cleanup_audio_configs`
+pub const EXCLUSIVE_CONFIG_FILE: &str = "99-pro-audio-exclusive.conf";
+/// Prefix of the header lines naming each exclusive-mode app
+const EXCLUSIVE_APP_MARKER: &str = "# Exclusive app:";
+const BASIC_CONFIG_FILES: &[&str] = &[
+    "99-pro-audio-high-priority.conf",
+    "99-pro-audio.conf",
+    "50-pro-audio.conf",
+];
+/// module-combine-stream fragment that duplicates output to several sinks
+pub const COMBINE_CONFIG_FILE: &str = "99-pro-audio-combine.conf";
+/// node.name of the combined sink, selectable like any other output
+pub const COMBINE_SINK_NAME: &str = "pro_audio_combined";
+/// Prefix of the header lines naming each combined sink member
+const COMBINE_MEMBER_MARKER: &str = "# Combined member:";
+/// Null sinks created with the virtual device tool, e.g. a stream mix for OBS
+pub const VIRTUAL_DEVICES_CONFIG_FILE: &str = "99-pro-audio-virtual.conf";
+/// node.name prefix of the virtual sinks
+pub const VIRTUAL_SINK_PREFIX: &str = "pro_audio_virtual_";
+/// Header line text naming each virtual device, after the `# `
+const VIRTUAL_DEVICE_MARKER: &str = "Virtual device:";
+/// pipewire-pulse fragment carrying the pulse.* quantum limits
+pub const PULSE_CONFIG_FILE: &str = "99-pro-audio-pulse.conf";
+/// pipewire-pulse fragment with the resampler quality of Pulse client streams
+pub const PULSE_RESAMPLE_CONFIG_FILE: &str = "99-pro-audio-pulse-resample.conf";
+/// Named resampler qualities and the resample.quality level (0-14) each maps to
+pub const RESAMPLE_QUALITY_LEVELS: &[(&str, u32)] = &[
+    ("fastest", 0),
+    ("low", 2),
+    ("medium", 4),
+    ("high", 10),
+    ("highest", 14),
+];
+/// The only file left by `apply_minimal_safe_config`
+pub const SAFE_CONFIG_FILE: &str = "99-pro-audio-safe.conf";
+/// Conservative clock of the minimal safe config
+pub const SAFE_SAMPLE_RATE: u32 = 48000;
+pub const SAFE_QUANTUM: u32 = 1024;
+/// pipewire-pulse's built-in pulse.min.quantum/min.req (128/48000, ~2.7 ms)
+pub const PULSE_DEFAULT_MIN_QUANTUM: u32 = 128;
+/// RLIMIT_RTTIME (µs) for PipeWire's realtime threads when none is configured
+pub const DEFAULT_RT_TIME_US: u32 = 200000;
+/// priority.driver/priority.session of the ALSA device rule written by the
+/// professional apply. A higher driver priority makes that device the graph
+/// driver when several interfaces are present.
+pub const DEFAULT_PRIORITY_DRIVER: u32 = 200;
+pub const DEFAULT_PRIORITY_SESSION: u32 = 200;
+/// WirePlumber's session.suspend-timeout-seconds: idle nodes are suspended
+/// after this long, 0 never suspends them
+pub const DEFAULT_SUSPEND_TIMEOUT_SECONDS: u32 = 5;
+const ADVANCED_CONFIG_FILES: &[&str] = &[
+    "99-pro-audio-advanced.conf",
+    "99-pro-audio-quantum-override.conf",
+];
+/// Clock fragment written by the Output/Input apply
+const HIGH_PRIORITY_CONFIG_FILE: &str = "99-pro-audio-high-priority.conf";
+/// Clock fragment written by the professional apply
+const QUANTUM_OVERRIDE_CONFIG_FILE: &str = "99-pro-audio-quantum-override.conf";
+/// ALSA device rule written by the professional apply
+const DEVICE_RULES_CONFIG_FILE: &str = "99-pro-audio-devices.conf";
+
+/// Values recovered from an existing PipeWire/WirePlumber config fragment
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImportedSettings {
+    pub sample_rate: Option<u32>,
+    pub quantum: Option<u32>,
+    pub min_quantum: Option<u32>,
+    pub max_quantum: Option<u32>,
+    pub period_size: Option<u32>,
+}
+
+impl ImportedSettings {
+    /// Buffer size to show in the UI; the ALSA period size stands in when
+    /// the fragment does not set a quantum
+    pub fn buffer_size(&self) -> Option<u32> {
+        self.quantum.or(self.period_size)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 /// Apply advanced audio settings with exclusive mode support
 pub fn apply_advanced_audio_settings(
     exclusive_mode: bool,
@@ -37,19 +418,24 @@ pub fn apply_advanced_audio_settings(
     low_latency: bool,
     buffer_size: u32,
     sample_rate: u32,
+    channel_positions: Vec<String>,
     device_pattern: Option<String>,
-    app_name: Option<String>,
-    app_process_name: Option<String>,
-) -> Result<(), String> {
-    println!("Applying advanced audio settings:");
-    println!("  Exclusive Mode: {}", exclusive_mode);
-    println!("  Direct Hardware: {}", direct_hardware);
-    println!("  Low Latency: {}", low_latency);
-    println!("  Buffer Size: {}", buffer_size);
-    println!("  Sample Rate: {}", sample_rate);
+    apps: Vec<ExclusiveApp>,
+) -> Result<(), AudioConfigError> {
+    log::info!("Applying advanced audio settings:");
+    log::info!("  Exclusive Mode: {}", exclusive_mode);
+    log::info!("  Direct Hardware: {}", direct_hardware);
+    log::info!("  Low Latency: {}", low_latency);
+    log::info!("  Buffer Size: {}", buffer_size);
+    log::info!("  Sample Rate: {}", sample_rate);
+    log::info!("  Channel Positions: {}", channel_positions.join(" "));
 
     if let Some(pattern) = &device_pattern {
-        println!("  Device Pattern: {}", pattern);
+        log::info!("  Device Pattern: {}", pattern);
+    }
+
+    for app in &apps {
+        log::info!("  Exclusive App: {}", app.label());
     }
 
     if exclusive_mode {
@@ -61,9 +447,9 @@ pub fn apply_advanced_audio_settings(
             low_latency,
             buffer_size,
             sample_rate,
+            &channel_positions,
             &device,
-            &app_name.unwrap_or_default(),
-            &app_process_name.unwrap_or_default(),
+            &apps,
         )?;
     } else {
         // Return to standard shared mode
@@ -76,80 +462,158 @@ pub fn apply_advanced_audio_settings(
         bit_depth: if low_latency { 32 } else { 24 },
         buffer_size,
         device_id: device_pattern.unwrap_or("default".to_string()),
+        allowed_rates: Vec::new(),
+        node_description: None,
+        period_size: None,
+        channels: channel_positions.len() as u32,
+        position: channel_positions,
     };
 
     verify_advanced_settings_applied(&settings, true)?;
     Ok(())
 }
 
+/// Creates a sink that plays to all `members` (sink node names) at once, e.g.
+/// monitors and headphones. Always user scope; optionally made the default output.
+pub fn apply_combined_output(
+    members: &[String],
+    make_default: bool,
+) -> Result<(), AudioConfigError> {
+    if members.len() < 2 {
+        return Err("Select at least two output devices to combine".into());
+    }
+
+    let config_dir = format!("{}/pipewire/pipewire.conf.d", user_config_dir());
+    let config_path = format!("{}/{}", config_dir, COMBINE_CONFIG_FILE);
+
+    create_dir_all_with_privileges(&config_dir)?;
+    write_config_with_privileges(&config_path, &generate_combine_sink_config(members))?;
+    log::info!("✓ Combined output config created: {}", config_path);
+
+    restart_audio_services(false, false)?;
+
+    if make_default {
+        set_default_sink(COMBINE_SINK_NAME)?;
+    }
+
+    Ok(())
+}
+
 /// Apply advanced/professional audio settings with verification
 pub fn apply_advanced_professional_settings(
     settings: &AudioSettings,
     system_wide: bool,
-    min_buffer: u32,
-    max_buffer: u32,
-    thread_priority: &str,
-    memory_lock: bool,
-    prevent_suspend: bool,
-    disable_remixing: bool,
-    disable_resampling: bool,
-    resampler_quality: &str,
-    clock_source: &str,
-) -> Result<(), String> {
-    println!("=== QUANTUM FLOOR OVERRIDE ===");
-    println!(
+    options: &ProfessionalOptions,
+) -> Result<(), AudioConfigError> {
+    log::info!("=== QUANTUM FLOOR OVERRIDE ===");
+    log::info!(
         "Target: {}Hz, {} samples",
-        settings.sample_rate, settings.buffer_size
+        settings.sample_rate,
+        settings.buffer_size
     );
-    println!("Fixing quantum-floor issue...");
+    log::info!("Fixing quantum-floor issue...");
+
+    // System files are written together below, with one password prompt
+    let mut batch = PrivilegedBatch::default();
 
     // 1. NUCLEAR OPTION: Modify main pipewire.conf
-    modify_main_pipewire_quantum_floor(settings, system_wide)?;
+    modify_main_pipewire_quantum_floor(settings, system_wide, &mut batch)?;
 
     // 2. Create quantum-override config
-    create_advanced_pipewire_fragment(
+    create_advanced_pipewire_fragment(settings, system_wide, options, &mut batch)?;
+
+    // 3. Device rules with the chosen driver/session priorities
+    create_wireplumber_device_config(
         settings,
         system_wide,
-        min_buffer,
-        max_buffer,
-        thread_priority,
-        memory_lock,
-        prevent_suspend,
-        disable_remixing,
-        disable_resampling,
-        resampler_quality,
-        clock_source,
+        options.priority_driver,
+        options.priority_session,
+        options.suspend_timeout,
+        &mut batch,
     )?;
 
-    // 3. Clean up basic configs to avoid conflicts
+    batch.run()?;
+
+    // 4. Clean up basic configs to avoid conflicts
     cleanup_audio_configs(system_wide, "pipewire", "conflicting")?;
 
-    // 4. RESTART with verification
-    println!("\nRestarting with quantum verification...");
+    // 5. RESTART with verification
+    log::info!("\nRestarting with quantum verification...");
     quantum_verified_restart(system_wide)?;
 
-    // 5. Verify
+    // 6. Verify
     verify_advanced_settings_applied(settings, system_wide)?;
-    verify_quantum_applied(settings)
+    verify_quantum_applied(settings)?;
+    Ok(())
+}
+
+/// Apply the same rate/buffer to both the default sink and source with a
+/// single service restart
+pub fn apply_duplex_audio_settings(
+    settings: &AudioSettings,
+    system_wide: bool,
+) -> Result<ApplyOutcome, AudioConfigError> {
+    log::info!(
+        "Applying {} input+output audio settings: {}Hz/{}bit/{} samples",
+        if system_wide { "system-wide" } else { "user" },
+        settings.sample_rate,
+        settings.bit_depth,
+        settings.buffer_size
+    );
+
+    settings.validate()?;
+
+    if system_wide {
+        cleanup_system_pipewire_configs()?;
+    } else {
+        cleanup_user_pipewire_configs()?;
+    }
+
+    // System files are written together below, with one password prompt
+    let mut batch = PrivilegedBatch::default();
+    create_pipewire_fragment(settings, system_wide, &mut batch)?;
+
+    // Device rules for both directions, written before the one restart
+    let format_notes = [
+        write_wireplumber_device_config(settings, "output", None)?,
+        write_wireplumber_device_config(settings, "input", None)?,
+    ];
+    let pulse_warnings = sync_pipewire_pulse_quantum(settings, system_wide, &mut batch);
+    batch.run()?;
+    log::info!("✓ Successfully created PipeWire config fragment");
+
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    restart_audio_services(false, system_wide)?;
+    log::info!("✓ Audio services restarted successfully");
+
+    let mut outcome = ApplyOutcome::new(ApplyMethod::PipeWireFragment);
+    verify_settings_applied(settings, &mut outcome);
+    outcome.warnings.extend(pulse_warnings);
+    outcome.warnings.extend(format_notes.into_iter().flatten());
+
+    Ok(outcome)
 }
 
 /// Apply input audio settings with authentication
 pub fn apply_input_audio_settings_with_auth_blocking(
     settings: AudioSettings,
-) -> Result<(), String> {
+) -> Result<(), AudioConfigError> {
     apply_audio_settings_with_auth(settings, "input")
 }
 
 /// Apply output audio settings with authentication
 pub fn apply_output_audio_settings_with_auth_blocking(
     settings: AudioSettings,
-) -> Result<(), String> {
+) -> Result<(), AudioConfigError> {
     apply_audio_settings_with_auth(settings, "output")
 }
 
 /// Apply audio settings for user-specific configuration
-pub fn apply_user_audio_settings(settings: AudioSettings, tab_type: &str) -> Result<(), String> {
-    println!("Applying user-specific {} audio settings", tab_type);
+pub fn apply_user_audio_settings(
+    settings: AudioSettings,
+    tab_type: &str,
+) -> Result<ApplyOutcome, AudioConfigError> {
+    log::info!("Applying user-specific {} audio settings", tab_type);
     update_audio_settings(&settings, false, None) // false = not system-wide
 }
 
@@ -180,84 +644,115 @@ pub fn check_audio_services() -> Result<(), String> {
     }
 }
 
+/// Sink node names in the current combined output, empty when there is none
+pub fn combined_output_members() -> Vec<String> {
+    managed_config_paths(ConfigScope::User)
+        .iter()
+        .filter(|path| is_config_file_named(path, COMBINE_CONFIG_FILE))
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .flat_map(|content| {
+            content
+                .lines()
+                .filter_map(|line| line.strip_prefix(COMBINE_MEMBER_MARKER))
+                .map(|member| member.trim().to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
 /// Check if exclusive mode is currently active
 pub fn check_exclusive_mode_status() -> Result<bool, String> {
-    let username = whoami::username();
-    let exclusive_config = format!(
-        "/home/{}/.config/pipewire/pipewire.conf.d/99-pro-audio-exclusive.conf",
-        username
+    Ok(managed_config_paths(ConfigScope::User)
+        .iter()
+        .any(|path| is_config_file_named(path, EXCLUSIVE_CONFIG_FILE) && path.exists()))
+}
+
+/// Check whether the user may run threads with realtime priority and lock memory
+pub fn check_realtime_privileges() -> RealtimePrivileges {
+    let groups = Command::new("id")
+        .arg("-Gn")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .split_whitespace()
+                .map(|group| group.to_string())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    let rtkit_available = Command::new("pgrep")
+        .args(["-x", "rtkit-daemon"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+
+    let privileges = RealtimePrivileges {
+        in_realtime_group: has_realtime_group(&groups),
+        groups,
+        rtprio_limit: read_resource_limit("Max realtime priority"),
+        memlock_limit: read_resource_limit("Max locked memory"),
+        rtkit_available,
+    };
+
+    log::debug!(
+        "Realtime privileges - groups: {:?}, rtprio: {:?}, memlock: {:?}, rtkit: {}",
+        privileges.groups,
+        privileges.rtprio_limit,
+        privileges.memlock_limit,
+        privileges.rtkit_available
     );
 
-    Ok(Path::new(&exclusive_config).exists())
+    privileges
 }
 
 /// Removes any configuration files created by this application
 pub fn cleanup_config_files() -> Result<(), String> {
-    let username = whoami::username();
-    let files_to_remove = [
-        "/etc/pipewire/pipewire.conf.d/99-pro-audio-high-priority.conf",
-        "/etc/pipewire/pipewire.conf.d/99-pro-audio.conf",
-        &format!(
-            "/home/{}/.config/pipewire/pipewire.conf.d/99-pro-audio-high-priority.conf",
-            username
-        ),
-        &format!(
-            "/home/{}/.config/pipewire/pipewire.conf.d/99-pro-audio.conf",
-            username
-        ),
-        &format!(
-            "/home/{}/.local/share/pipewire/pipewire.conf.d/99-pro-audio.conf",
-            username
-        ),
-        "/etc/wireplumber/wireplumber.conf.d/99-pro-audio.conf",
-        "/etc/wireplumber/wireplumber.conf.d/50-pro-audio.conf",
-        &format!(
-            "/home/{}/.config/wireplumber/wireplumber.conf.d/99-pro-audio.conf",
-            username
-        ),
-        &format!(
-            "/home/{}/.config/wireplumber/wireplumber.conf.d/50-pro-audio.conf",
-            username
-        ),
-        &format!(
-            "/home/{}/.config/wireplumber/main.lua.d/50-pro-audio-output.lua",
-            username
-        ),
-        &format!(
-            "/home/{}/.config/wireplumber/main.lua.d/50-pro-audio-input.lua",
-            username
-        ),
-    ];
-
     let mut removed_count = 0;
-    for file in &files_to_remove {
-        let expanded_path = shellexpand::full(file).map_err(|e| e.to_string())?;
-        if Path::new(&*expanded_path).exists() {
-            if expanded_path.starts_with("/etc/") {
-                // System path - need privileges
-                if execute_with_privileges("rm", &["-f", &expanded_path]).is_ok() {
-                    println!("Removed system config: {}", expanded_path);
+    // System files are removed together, with one password prompt
+    let mut batch = PrivilegedBatch::default();
+    let mut system_files = Vec::new();
+    for path in managed_config_paths(ConfigScope::All) {
+        if !path.exists() {
+            continue;
+        }
+
+        let file = path.to_string_lossy();
+        if file.starts_with("/etc/") {
+            // System path - need privileges
+            match batch.remove(&file) {
+                Ok(()) => system_files.push(file.to_string()),
+                Err(e) => log::warn!("Failed to remove {}: {}", file, e),
+            }
+        } else {
+            // User path - no privileges needed
+            match fs::remove_file(&path) {
+                Ok(_) => {
+                    log::info!("Removed config file: {}", file);
                     removed_count += 1;
                 }
-            } else {
-                // User path - no privileges needed
-                match fs::remove_file(&*expanded_path) {
-                    Ok(_) => {
-                        println!("Removed config file: {}", expanded_path);
-                        removed_count += 1;
-                    }
-                    Err(e) => {
-                        println!("Warning: Failed to remove {}: {}", expanded_path, e);
-                    }
+                Err(e) => {
+                    log::warn!("Failed to remove {}: {}", file, e);
                 }
             }
         }
     }
 
+    match batch.run() {
+        Ok(()) => {
+            for file in &system_files {
+                log::info!("Removed system config: {}", file);
+            }
+            removed_count += system_files.len();
+        }
+        Err(e) => log::warn!("Failed to remove system configs: {}", e),
+    }
+
     if removed_count > 0 {
         // Use the improved restart function
         restart_audio_services(false, false)?;
-        println!(
+        log::info!(
             "Removed {} configuration files and restarted services",
             removed_count
         );
@@ -266,2340 +761,5896 @@ pub fn cleanup_config_files() -> Result<(), String> {
     Ok(())
 }
 
-/// Emergency recovery function for when audio system breaks
-pub fn recover_audio_system() -> Result<(), String> {
-    println!("=== EMERGENCY AUDIO SYSTEM RECOVERY ===");
+/// Default `audio.position` map for a channel count, using SPA channel names.
+/// Counts without a common speaker layout get AUX channels.
+pub fn default_channel_positions(channels: u32) -> Vec<String> {
+    let positions: &[&str] = match channels {
+        1 => &["MONO"],
+        2 => &["FL", "FR"],
+        3 => &["FL", "FR", "LFE"],
+        4 => &["FL", "FR", "RL", "RR"],
+        6 => &["FL", "FR", "FC", "LFE", "RL", "RR"],
+        8 => &["FL", "FR", "FC", "LFE", "RL", "RR", "SL", "SR"],
+        _ => {
+            return (0..channels).map(|index| format!("AUX{}", index)).collect();
+        }
+    };
 
-    let username = whoami::username();
+    positions
+        .iter()
+        .map(|position| position.to_string())
+        .collect()
+}
 
-    // List all problematic configs to remove
-    let problematic_configs = [
-        format!(
-            "/home/{}/.config/pipewire/pipewire.conf.d/99-pro-audio-exclusive.conf",
-            username
-        ),
-        format!(
-            "/home/{}/.config/pipewire/pipewire.conf.d/99-pro-audio-high-priority.conf",
-            username
-        ),
-        format!(
-            "/home/{}/.config/wireplumber/wireplumber.conf.d/99-pro-audio-exclusive.conf",
-            username
-        ),
-        format!(
-            "/home/{}/.config/wireplumber/wireplumber.conf.d/99-pro-audio.conf",
-            username
-        ),
-    ];
+/// What the Output/Input apply would change: a unified diff per file whose
+/// content on disk differs from the one `preview_audio_settings` generates
+pub fn diff_pending_config(
+    settings: &AudioSettings,
+    system_wide: bool,
+) -> Result<Vec<(PathBuf, String)>, String> {
+    diff_against_disk(preview_audio_settings(settings, system_wide)?)
+}
 
-    let mut removed = 0;
-    for config in &problematic_configs {
-        if Path::new(config).exists() {
-            if let Ok(()) = fs::remove_file(config) {
-                println!("✓ Removed: {}", config);
-                removed += 1;
-            } else {
-                println!("⚠ Could not remove: {}", config);
-            }
+/// What the professional apply would change, including the edit to the main
+/// pipewire.conf, as in `diff_pending_config`
+pub fn diff_pending_professional_config(
+    settings: &AudioSettings,
+    system_wide: bool,
+    options: &ProfessionalOptions,
+) -> Result<Vec<(PathBuf, String)>, String> {
+    diff_against_disk(preview_advanced_professional_settings(
+        settings,
+        system_wide,
+        options,
+    )?)
+}
+
+/// Diffs each pending (path, content) against the file on disk. Unchanged
+/// files are left out; a missing file is diffed against /dev/null.
+fn diff_against_disk(files: Vec<(PathBuf, String)>) -> Result<Vec<(PathBuf, String)>, String> {
+    let mut diffs = Vec::new();
+
+    for (path, pending) in files {
+        let label = path.display().to_string();
+        let (old_label, current) = match fs::read_to_string(&path) {
+            Ok(current) => (label.as_str(), current),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => ("/dev/null", String::new()),
+            Err(e) => return Err(format!("Failed to read {}: {}", label, e)),
+        };
+
+        let diff = unified_diff(old_label, &current, &label, &pending);
+        if !diff.is_empty() {
+            diffs.push((path, diff));
         }
     }
 
-    if removed > 0 {
-        println!("\n✓ Removed {} problematic configuration files", removed);
-        println!("Restarting audio services...");
+    Ok(diffs)
+}
 
-        // Simple restart without complex logic
-        let _ = Command::new("systemctl")
-            .args([
-                "--user",
-                "restart",
-                "pipewire",
-                "pipewire-pulse",
-                "wireplumber",
-            ])
-            .status();
+/// Returns every config file this tool can create in the given scope,
+/// whether or not it currently exists
+pub fn managed_config_paths(scope: ConfigScope) -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    if matches!(scope, ConfigScope::System | ConfigScope::All) {
+        roots.push(PathBuf::from("/etc"));
+    }
+    if matches!(scope, ConfigScope::User | ConfigScope::All) {
+        roots.push(PathBuf::from(user_config_dir()));
+    }
 
-        // Wait for services to come back
-        std::thread::sleep(std::time::Duration::from_secs(3));
+    let mut paths: Vec<PathBuf> = roots
+        .iter()
+        .flat_map(|root| managed_config_files().map(move |file| root.join(file)))
+        .collect();
 
-        println!("✓ Audio system should be recovered");
-        println!("If audio is still not working, try logging out and back in, or reboot.");
-    } else {
-        println!("✓ No problematic configurations found");
+    if matches!(scope, ConfigScope::User | ConfigScope::All) {
+        paths.push(user_home_dir().join(LEGACY_USER_CONFIG_FILE));
     }
 
-    Ok(())
+    paths
 }
 
-/// Restore standard shared audio mode
-pub fn restore_standard_audio_mode() -> Result<(), String> {
-    println!("Restoring standard shared audio mode...");
+/// The subset of `managed_config_paths` an apply may clear before writing
+fn apply_config_paths(scope: ConfigScope) -> Vec<PathBuf> {
+    managed_config_paths(scope)
+        .into_iter()
+        .filter(|path| APPLY_CONFIG_FILES.iter().any(|file| path.ends_with(file)))
+        .collect()
+}
 
-    // Remove exclusive mode configurations
-    let username = whoami::username();
-    let config_files = [
-        format!(
-            "/home/{}/.config/pipewire/pipewire.conf.d/99-pro-audio-exclusive.conf",
-            username
-        ),
-        format!(
-            "/home/{}/.config/wireplumber/wireplumber.conf.d/99-pro-audio-exclusive.conf",
-            username
-        ),
-    ];
+/// Retires config files written by older versions of this tool: user files
+/// whose `pro-audio-config.version` differs from `CONFIG_FORMAT_VERSION` (or
+/// the legacy location) are moved aside, outdated system-wide files are only
+/// reported because changing /etc needs authentication. Returns one line per
+/// file for the startup report.
+pub fn migrate_configs() -> Vec<String> {
+    let user_root = PathBuf::from(user_config_dir());
+    let mut report = Vec::new();
+
+    for path in managed_config_paths(ConfigScope::All) {
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
 
-    let mut removed_count = 0;
-    for config_file in &config_files {
-        if Path::new(config_file).exists()
-            && let Ok(()) = fs::remove_file(config_file)
-        {
-            println!("✓ Removed exclusive config: {}", config_file);
-            removed_count += 1;
+        let legacy = path.ends_with(LEGACY_USER_CONFIG_FILE);
+        let found_version = config_format_version(&content);
+        let outdated = legacy
+            || found_version
+                .as_deref()
+                .is_some_and(|version| version != CONFIG_FORMAT_VERSION);
+        if !outdated {
+            continue;
+        }
+
+        let version = found_version.unwrap_or_else(|| "legacy".to_string());
+        let reason = config_format_change_reason(&version);
+
+        if !path.starts_with(&user_root) && !legacy {
+            report.push(format!(
+                "{} is from format {} ({}); re-apply system-wide settings to update it",
+                path.display(),
+                version,
+                reason
+            ));
+            continue;
+        }
+
+        let timestamp = Local::now().format("%Y%m%d_%H%M%S");
+        let retired = PathBuf::from(format!("{}.migrated-{}", path.display(), timestamp));
+        match fs::rename(&path, &retired) {
+            Ok(()) => report.push(format!(
+                "Moved {} (format {}: {}) to {}; re-apply your settings to recreate it",
+                path.display(),
+                version,
+                reason,
+                retired.display()
+            )),
+            Err(e) => report.push(format!(
+                "Could not retire outdated {}: {}",
+                path.display(),
+                e
+            )),
         }
     }
 
-    if removed_count > 0 {
-        restart_audio_services(false, true)?;
-        println!("✓ Standard audio mode restored");
-    } else {
-        println!("✓ Already in standard audio mode");
+    for line in &report {
+        log::info!("Config migration: {}", line);
     }
 
-    Ok(())
+    report
 }
 
-// ============================================================================
-// PRIVATE CONFIGURATION FUNCTIONS (Alphabetically within groups)
-// ============================================================================
+/// Schema of exported profile bundles. Bump it when a field changes meaning;
+/// `import_profile` refuses bundles of any other version.
+pub const PROFILE_SCHEMA_VERSION: u32 = 1;
+
+/// A complete setup in one TOML file, for moving it to another machine
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProfileBundle {
+    pub schema_version: u32,
+    /// `CONFIG_FORMAT_VERSION` of the exporting version, for reference
+    pub config_format: String,
+    /// Running settings at export time
+    pub settings: AudioSettings,
+    pub professional: ProfessionalProfile,
+    pub exclusive: ExclusiveProfile,
+    /// User config files written by this tool, the actual payload
+    pub files: Vec<ProfileFile>,
+}
 
-// ----------------------------------------------------------------------------
-// Configuration File Creation
-// ----------------------------------------------------------------------------
-
-/// Creates an ADVANCED PipeWire configuration fragment for professional use
-fn create_advanced_pipewire_fragment(
-    settings: &AudioSettings,
-    system_wide: bool,
-    min_buffer: u32,
-    max_buffer: u32,
-    thread_priority: &str,
-    memory_lock: bool,
-    prevent_suspend: bool,
-    disable_remixing: bool,
-    disable_resampling: bool,
-    resampler_quality: &str,
-    clock_source: &str,
-) -> Result<(), String> {
-    // Map thread priority
-    let (nice_level, rt_prio) = match thread_priority {
-        "normal" => (-11, 88),
-        "high" => (-15, 90),
-        "realtime" => (-20, 99),
-        _ => (-15, 90),
-    };
+/// Professional settings: the buffer range of the advanced fragment and the
+/// rt.time limits in use
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProfessionalProfile {
+    pub min_buffer: Option<u32>,
+    pub max_buffer: Option<u32>,
+    pub rt_time_soft_us: u32,
+    pub rt_time_hard_us: u32,
+}
 
-    // Override quantum-floor AND set force-quantum
-    let config_content = format!(
-        r#"# Pro Audio Config - Quantum Floor Override
-# This OVERRIDES default.clock.quantum-floor = 4
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExclusiveProfile {
+    pub active: bool,
+    pub apps: Vec<ExclusiveApp>,
+}
 
-context.properties = {{
-    # CORE SETTINGS - MUST override quantum-floor
-    default.clock.rate = {}
-    default.clock.quantum = {}
-    default.clock.allowed-rates = [ {} ]
+/// One config file, `path` relative to the user config dir
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProfileFile {
+    pub path: String,
+    pub content: String,
+}
 
-    # CRITICAL: Override the quantum-floor that's blocking our settings
-    default.clock.quantum-floor = {}
-    default.clock.min-quantum = {}
-    default.clock.max-quantum = {}
-    default.clock.quantum-limit = {}
+/// Writes the running settings, the professional and exclusive state and
+/// every user config file this tool manages into one TOML file
+pub fn export_profile(path: &Path) -> Result<(), String> {
+    let settings = detect_current_audio_settings()?;
+    settings
+        .validate()
+        .map_err(|e| format!("The running settings can't be exported: {}", e))?;
+
+    let bundle = build_profile_bundle(settings);
+    let content = toml::to_string_pretty(&bundle)
+        .map_err(|e| format!("Failed to serialize profile: {}", e))?;
+    fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    log::info!(
+        "✓ Profile exported to {} ({} config files)",
+        path.display(),
+        bundle.files.len()
+    );
+    Ok(())
+}
 
-    # Force settings (if supported by your PipeWire version)
-    default.clock.force-quantum = {}
-    default.clock.force-rate = {}
+fn build_profile_bundle(settings: AudioSettings) -> ProfileBundle {
+    let user_root = PathBuf::from(user_config_dir());
+    let files: Vec<ProfileFile> = managed_config_files()
+        .filter_map(|file| {
+            fs::read_to_string(user_root.join(file))
+                .ok()
+                .map(|content| ProfileFile {
+                    path: file.to_string(),
+                    content,
+                })
+        })
+        .collect();
+
+    let buffer_range = files
+        .iter()
+        .filter(|file| {
+            ADVANCED_CONFIG_FILES
+                .iter()
+                .any(|name| file.path.ends_with(name))
+        })
+        .map(|file| parse_config_content(&file.content))
+        .find(|imported| imported.min_quantum.is_some() || imported.max_quantum.is_some())
+        .unwrap_or_default();
+    let (rt_time_soft_us, rt_time_hard_us) = rt_time_limits();
+
+    ProfileBundle {
+        schema_version: PROFILE_SCHEMA_VERSION,
+        config_format: CONFIG_FORMAT_VERSION.to_string(),
+        settings,
+        professional: ProfessionalProfile {
+            min_buffer: buffer_range.min_quantum,
+            max_buffer: buffer_range.max_quantum,
+            rt_time_soft_us,
+            rt_time_hard_us,
+        },
+        exclusive: ExclusiveProfile {
+            active: check_exclusive_mode_status().unwrap_or(false),
+            apps: read_exclusive_apps(),
+        },
+        files,
+    }
+}
 
-    # Clock source
-    default.clock.source = "{}"
+/// Reads a bundle written by `export_profile`, checks it and replaces the
+/// user config files this tool manages with the bundle's, then restarts the
+/// audio services. Nothing is written when the schema version, the settings
+/// or a file path is not accepted.
+pub fn import_profile(path: &Path) -> Result<ProfileBundle, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let bundle = parse_profile_bundle(&content)?;
+
+    let user_root = PathBuf::from(user_config_dir());
+    for file in managed_config_files() {
+        let target = user_root.join(file);
+        if target.exists() && !bundle.files.iter().any(|bundled| bundled.path == file) {
+            fs::remove_file(&target)
+                .map_err(|e| format!("Failed to remove {}: {}", target.display(), e))?;
+            log::info!("✓ Removed (not in profile): {}", target.display());
+        }
+    }
 
-    # DISABLE all quantum checking
-    settings.check-quantum = false
-    settings.check-rate = false
-    settings.check-quantum-limit = false
-    settings.check-quantum-floor = false
+    for file in &bundle.files {
+        let target = user_root.join(&file.path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        fs::write(&target, &file.content)
+            .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+        log::info!("✓ Imported: {}", target.display());
+    }
 
-    # Memory settings
-    mem.warn-mlock = {}
-    mem.allow-mlock = {}
+    restart_audio_services(false, false)?;
+    Ok(bundle)
+}
 
-    # Resampler
-    resample.quality = "{}"
+/// Parses and checks a profile bundle without writing anything
+fn parse_profile_bundle(content: &str) -> Result<ProfileBundle, String> {
+    // The version is read first so a changed schema is reported as such
+    // rather than as whichever field no longer parses
+    let value: toml::Value =
+        toml::from_str(content).map_err(|e| format!("Not a profile file: {}", e))?;
+    match value
+        .get("schema_version")
+        .and_then(toml::Value::as_integer)
+    {
+        Some(version) if version == PROFILE_SCHEMA_VERSION as i64 => {}
+        Some(version) => {
+            return Err(format!(
+                "This profile uses schema version {}, this version of Pro Audio Config \
+                 reads version {}",
+                version, PROFILE_SCHEMA_VERSION
+            ));
+        }
+        None => return Err("Not a profile file: schema_version is missing".to_string()),
+    }
 
-    # Thread settings
-    cpu.zero.denormals = true
+    let bundle: ProfileBundle =
+        toml::from_str(content).map_err(|e| format!("Invalid profile: {}", e))?;
+    bundle
+        .settings
+        .validate()
+        .map_err(|e| format!("Invalid profile settings: {}", e))?;
+
+    // Only files this tool manages, so a bundle can't write anywhere else
+    if let Some(file) = bundle
+        .files
+        .iter()
+        .find(|file| !managed_config_files().any(|managed| managed == file.path))
+    {
+        return Err(format!(
+            "The profile contains {}, which is not a Pro Audio Config file",
+            file.path
+        ));
+    }
 
-    # Session
-    session.suspend-timeout-seconds = {}
+    Ok(bundle)
+}
 
-    # Stream settings
-    stream.dont-remix = {}
-    stream.dont-resample = {}
+/// Reads clock rate, quantum and ALSA period size from a hand-written config
+/// fragment so its values can be taken over by the UI
+pub fn parse_existing_config(path: &Path) -> Result<ImportedSettings, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
 
-    # Additional overrides
-    link.max-buffers = 64
-    core.daemon = true
+    let imported = parse_config_content(&content);
+    if imported.is_empty() {
+        return Err(format!(
+            "No clock rate, quantum or period size found in {}",
+            path.display()
+        ));
+    }
 
-    # DEBUG: Add properties to verify our config is loaded
-    pro-audio-config.rate = {}
-    pro-audio-config.quantum = {}
-    pro-audio-config.version = "1.7"
-}}
+    log::info!(
+        "✓ Imported settings from {}: {:?}",
+        path.display(),
+        imported
+    );
+    Ok(imported)
+}
 
-# Real-time module
-context.modules = [
-    {{
-        name = libpipewire-module-rt
-        args = {{
-            nice.level = {}
-            rt.prio = {}
-            rt.time.soft = 100000
-            rt.time.hard = 100000
-        }}
-        flags = [ ifexists nofail ]
-    }}
-]
+/// Parses a hand-edited position list ("FL, FR" or "[ FL FR ]") and checks
+/// that every entry is a known SPA channel and the count matches
+pub fn parse_channel_positions(text: &str, channels: u32) -> Result<Vec<String>, String> {
+    let positions: Vec<String> = text
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|position| !position.is_empty())
+        .map(|position| position.trim_matches('"').to_uppercase())
+        .collect();
+
+    if let Some(unknown) = positions
+        .iter()
+        .find(|position| !is_known_channel_position(position))
+    {
+        return Err(format!("Unknown channel position: {}", unknown));
+    }
 
-# Debug object to verify settings
-context.objects = [
-    {{
-        factory = adapter
-        args = {{
-            factory.name     = support.null-audio-sink
-            node.name        = "quantum-test-{}"
-            node.description = "Quantum Test: {} samples @ {}Hz"
-            media.class      = "Audio/Sink"
-            audio.rate       = {}
-            audio.position   = [ FL, FR ]
-            audio.channels   = 2
-            audio.format     = "S{}LE"
-            # High priority to ensure it's visible
-            priority.session = 9999,
-            # Add our quantum to node properties
-            node.quantum = {},
-            node.lock-quantum = true
-        }}
-    }}
-]
-"#,
-        // Core settings
-        settings.sample_rate,
-        settings.buffer_size,
-        settings.sample_rate,
-        // QUANTUM FLOOR OVERRIDE - MUST be >= our quantum
-        settings.buffer_size,     // quantum-floor = our target
-        settings.buffer_size,     // min-quantum = our target
-        settings.buffer_size * 2, // max-quantum
-        settings.buffer_size * 4, // quantum-limit
-        // Force settings
-        settings.buffer_size, // force-quantum
-        settings.sample_rate, // force-rate
-        // Other settings
-        clock_source,
-        // Memory (inverted)
-        !memory_lock,
-        memory_lock,
-        // Resampler
-        resampler_quality,
-        // Session
-        if prevent_suspend { 0 } else { 5 },
-        // Stream
-        disable_remixing,
-        disable_resampling,
-        // Debug properties
-        settings.sample_rate,
-        settings.buffer_size,
-        // RT module
-        nice_level,
-        rt_prio,
-        // Debug object
-        settings.buffer_size,
-        settings.buffer_size,
-        settings.sample_rate,
-        settings.sample_rate,
-        settings.bit_depth,
-        settings.buffer_size,
-    );
+    if positions.len() != channels as usize {
+        return Err(format!(
+            "{} channel positions given for {} channels",
+            positions.len(),
+            channels
+        ));
+    }
 
-    // Use consistent String type for both branches
-    let config_path = if system_wide {
-        "/etc/pipewire/pipewire.conf.d/99-pro-audio-quantum-override.conf".to_string()
-    } else {
-        let username = whoami::username();
-        format!(
-            "/home/{}/.config/pipewire/pipewire.conf.d/99-pro-audio-quantum-override.conf",
-            username
-        )
-    };
+    Ok(positions)
+}
 
-    // Clean up ALL conflicting configs before creating
-    cleanup_audio_configs(system_wide, "pipewire", "conflicting")?;
+/// The files the professional apply would write, as (path, content), without
+/// writing anything: the edited main pipewire.conf if there is one, the
+/// quantum-override fragment and the ALSA device rule
+pub fn preview_advanced_professional_settings(
+    settings: &AudioSettings,
+    system_wide: bool,
+    options: &ProfessionalOptions,
+) -> Result<Vec<(PathBuf, String)>, String> {
+    let mut files = Vec::new();
 
-    // Write the quantum-override config
-    write_config_with_privileges(&config_path, &config_content)?;
+    let main_conf_path = main_pipewire_conf(system_wide);
+    if let Some(content) = main_pipewire_quantum_floor_content(settings, &main_conf_path)? {
+        files.push((PathBuf::from(main_conf_path), content));
+    }
 
-    println!("✓ Quantum override config created: {}", config_path);
-    println!("  Overriding quantum-floor with: {}", settings.buffer_size);
+    files.push((
+        Path::new(&pipewire_conf_d(system_wide)).join(QUANTUM_OVERRIDE_CONFIG_FILE),
+        generate_professional_pipewire_config(settings, options),
+    ));
+
+    let (audio_format, _) = resolve_audio_format(settings, &settings.device_id, "output");
+    files.push((
+        Path::new(&wireplumber_conf_d(system_wide)).join(DEVICE_RULES_CONFIG_FILE),
+        generate_wireplumber_professional_device_config(
+            settings,
+            audio_format,
+            options.priority_driver,
+            options.priority_session,
+            options.suspend_timeout,
+        ),
+    ));
 
-    Ok(())
+    Ok(files)
 }
 
-/// Creates a PipeWire configuration fragment file with proper privilege handling
-fn create_pipewire_fragment(settings: &AudioSettings, system_wide: bool) -> Result<(), String> {
-    let config_content = format!(
-        r#"# Pro Audio Config - High Priority Settings
-# This file overrides default PipeWire settings
-
-context.properties = {{
-    default.clock.rate = {}
-    default.clock.quantum = {}
-    default.clock.allowed-rates = [ {} ]
-    # Disable rate/quantum checking to ensure our settings are applied
-    settings.check-quantum = false
-    settings.check-rate = false
-    # Force settings to be used
-    default.clock.force-quantum = {}
-    default.clock.force-rate = {}
-}}
+/// The files `update_audio_settings` would write, as (path, content), without
+/// writing anything or restarting services. Stale fragments it removes first
+/// are not listed.
+pub fn preview_audio_settings(
+    settings: &AudioSettings,
+    system_wide: bool,
+) -> Result<Vec<(PathBuf, String)>, String> {
+    settings.validate()?;
+
+    let mut files = vec![(
+        Path::new(&pipewire_conf_d(system_wide)).join(HIGH_PRIORITY_CONFIG_FILE),
+        generate_pipewire_fragment(settings),
+    )];
+
+    if SYNC_PULSE_QUANTUM.load(Ordering::SeqCst) {
+        files.push((
+            Path::new(&pipewire_pulse_conf_d(system_wide)).join(PULSE_CONFIG_FILE),
+            generate_pipewire_pulse_config(settings),
+        ));
+    }
 
-context.modules = [
-    {{
-        name = libpipewire-module-rt
-        args = {{
-            nice.level = -11
-            rt.prio = 88
-            rt.time.soft = 200000
-            rt.time.hard = 200000
-        }}
-        flags = [ ifexists nofail ]
-    }}
-]"#,
-        settings.sample_rate,
-        settings.buffer_size,
-        settings.sample_rate,
-        settings.buffer_size,
-        settings.sample_rate
-    );
+    Ok(files)
+}
 
-    // Try multiple standard locations - use higher number for higher priority
-    let username = whoami::username();
-    let config_dirs = if system_wide {
-        vec!["/etc/pipewire/pipewire.conf.d".to_string()]
-    } else {
-        vec![format!(
-            "/home/{}/.config/pipewire/pipewire.conf.d",
-            username
-        )]
-    };
+/// Quantum Pulse clients will actually get when the chosen buffer is below
+/// pipewire-pulse's default minimum at the chosen rate. None if not clamped.
+pub fn pulse_clamped_quantum(settings: &AudioSettings) -> Option<u32> {
+    let min_at_rate =
+        (PULSE_DEFAULT_MIN_QUANTUM as u64 * settings.sample_rate as u64).div_ceil(48000) as u32;
 
-    // Clean up ALL conflicting configs before creating exclusive
-    cleanup_audio_configs(system_wide, "pipewire", "conflicting")?;
+    (settings.buffer_size < min_at_rate).then_some(min_at_rate)
+}
 
-    for dir in &config_dirs {
-        let config_path = format!("{}/99-pro-audio-high-priority.conf", dir);
+/// Emergency recovery function for when audio system breaks
+pub fn recover_audio_system() -> Result<(), String> {
+    log::info!("=== EMERGENCY AUDIO SYSTEM RECOVERY ===");
 
-        // Skip backup for system directories to avoid permission issues
-        // The backup is just a safety measure, not critical
-        if !dir.starts_with("/etc/")
-            && let Err(e) = backup_current_config(dir)
-        {
-            println!("Note: Could not backup config (non-fatal): {}", e);
-        }
+    // Every user-level config we may have written is a suspect
+    let problematic_configs = managed_config_paths(ConfigScope::User);
 
-        // Write file with proper privilege handling
-        if let Err(e) = write_config_with_privileges(&config_path, &config_content) {
-            println!(
-                "Failed to write to {}: {}, trying next location...",
-                config_path, e
-            );
-            continue;
+    let mut removed = 0;
+    for config in &problematic_configs {
+        if config.exists() {
+            if let Ok(()) = fs::remove_file(config) {
+                log::info!("✓ Removed: {}", config.display());
+                removed += 1;
+            } else {
+                log::warn!("Could not remove: {}", config.display());
+            }
         }
-
-        println!("✓ PipeWire config created: {}", config_path);
-
-        return Ok(());
     }
 
-    Err("Failed to write PipeWire configuration to any location".to_string())
-}
+    if removed > 0 {
+        log::info!("\n✓ Removed {} problematic configuration files", removed);
+        log::info!("Restarting audio services...");
 
-/// Creates a WirePlumber configuration file (updated format for versions >= 0.5)
-fn create_wireplumber_config_new(
-    settings: &AudioSettings,
-    system_wide: bool,
-    actual_device_name: Option<&str>,
-) -> Result<(), String> {
-    let username = whoami::username();
+        // Simple restart without complex logic
+        let _ = Command::new("systemctl")
+            .args([
+                "--user",
+                "restart",
+                "pipewire",
+                "pipewire-pulse",
+                "wireplumber",
+            ])
+            .status();
 
-    let config_dirs = if system_wide {
-        vec!["/etc/wireplumber/wireplumber.conf.d".to_string()]
-    } else {
-        vec![format!(
-            "/home/{}/.config/wireplumber/wireplumber.conf.d",
-            username
-        )]
-    };
+        // Wait for services to come back
+        std::thread::sleep(std::time::Duration::from_secs(3));
 
-    // Get the actual device pattern
-    let device_pattern = if let Some(name) = actual_device_name {
-        name.to_string()
-    } else if settings.device_id == "default" {
-        "~alsa.*".to_string()
+        log::info!("✓ Audio system should be recovered");
+        log::info!("If audio is still not working, try logging out and back in, or reboot.");
     } else {
-        settings.device_id.clone()
-    };
+        log::info!("✓ No problematic configurations found");
+    }
 
-    for dir in &config_dirs {
-        // Create directory if it doesn't exist
-        create_dir_all_with_privileges(dir)?;
+    Ok(())
+}
 
-        let config_path = format!("{}/99-pro-audio.conf", dir);
+/// Removes the user-level rate/buffer configs written by normal and advanced
+/// applies so PipeWire falls back to its own defaults
+pub fn remove_user_audio_settings() -> Result<(), String> {
+    cleanup_audio_configs(false, "pipewire", "conflicting")?;
+    cleanup_audio_configs(false, "wireplumber", "basic")?;
+    remove_pipewire_pulse_fragment(false, &mut PrivilegedBatch::default())?;
 
-        let content = format!(
-            r#"{{
-  "monitor.alsa.rules": [
-    {{
-      "matches": [
-        {{
-          "node.name": "{}"
-        }}
-      ],
-      "actions": {{
-        "update-props": {{
-          "audio.rate": {},
-          "audio.allowed-rates": [ {} ],
-          "api.alsa.period-size": {}
-        }}
-      }}
-    }}
-  ]
-}}"#,
-            device_pattern, settings.sample_rate, settings.sample_rate, settings.buffer_size
-        );
+    restart_audio_services(false, false)?;
+    log::info!("✓ User audio settings removed, PipeWire defaults restored");
+    Ok(())
+}
 
-        if let Err(e) = write_config_with_privileges(&config_path, &content) {
-            println!(
-                "Failed to write to {}: {}, trying next location...",
-                config_path, e
-            );
+/// Troubleshooting fallback: removes every user config this tool wrote and
+/// leaves one fragment with only a conservative rate and quantum (no realtime,
+/// memory locking, exclusive or device rules). Returns what was done, one
+/// line per step.
+pub fn apply_minimal_safe_config() -> Result<Vec<String>, String> {
+    let mut report = Vec::new();
+
+    for path in managed_config_paths(ConfigScope::User) {
+        if !path.exists() {
             continue;
         }
+        fs::remove_file(&path)
+            .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+        log::info!("✓ Removed: {}", path.display());
+        report.push(format!("Removed {}", path.display()));
+    }
 
-        println!(
-            "✓ Created WirePlumber config: {} for device: {}",
-            config_path, device_pattern
-        );
-        return Ok(());
+    let config_path = format!(
+        "{}/pipewire/pipewire.conf.d/{}",
+        user_config_dir(),
+        SAFE_CONFIG_FILE
+    );
+    write_config_with_privileges(&config_path, &generate_minimal_safe_config())?;
+    log::info!("✓ Minimal safe config written: {}", config_path);
+    report.push(format!(
+        "Wrote {} ({} Hz, {} samples)",
+        config_path, SAFE_SAMPLE_RATE, SAFE_QUANTUM
+    ));
+
+    // Changing /etc needs authentication, so system-wide files are only reported
+    for path in managed_config_paths(ConfigScope::System) {
+        if path.exists() {
+            report.push(format!(
+                "Kept system-wide {}; it still applies and needs root to remove",
+                path.display()
+            ));
+        }
     }
 
-    Err("No WirePlumber config directory found".to_string())
+    restart_audio_services(false, false)?;
+    report.push("Restarted audio services".to_string());
+
+    Ok(report)
 }
 
-/// Create WirePlumber configuration for hardware device rules
-#[allow(dead_code)]
-fn create_wireplumber_device_config(
-    settings: &AudioSettings,
-    system_wide: bool,
-    _min_buffer: u32,
-    _max_buffer: u32,
-    _disable_remixing: bool,
-    _disable_resampling: bool,
-) -> Result<(), String> {
-    println!("Creating WirePlumber device configuration...");
+fn generate_minimal_safe_config() -> String {
+    format!(
+        r#"# Pro Audio Config - Minimal Safe Settings
+# Written by "Apply Minimal Safe Config" for troubleshooting: only the clock,
+# no realtime, memory locking, exclusive or device rules
 
-    let username = whoami::username();
-    let wireplumber_dirs = if system_wide {
-        vec!["/etc/wireplumber/wireplumber.conf.d".to_string()]
-    } else {
-        vec![format!(
-            "/home/{}/.config/wireplumber/wireplumber.conf.d",
-            username
-        )]
-    };
+context.properties = {{
+    default.clock.rate = {}
+    default.clock.quantum = {}
+    pro-audio-config.version = "{}"
+}}
+"#,
+        SAFE_SAMPLE_RATE, SAFE_QUANTUM, CONFIG_FORMAT_VERSION
+    )
+}
 
-    // WirePlumber uses JSON for its configuration (version 0.5+)
-    let wireplumber_config = format!(
-        r#"{{
-  "alsa-monitor": {{
-    "rules": [
-      {{
-        "matches": [
-          {{
-            "node.name": "~alsa.*"
-          }}
-        ],
-        "actions": {{
-          "update-props": {{
-            "api.alsa.period-size": {},
-            "api.alsa.period-num": 2,
-            "api.alsa.headroom": 4096,
-            "api.alsa.disable-batch": true,
-            "api.alsa.use-acp": true,
-            "api.alsa.disable-mmap": false,
-            "api.alsa.disable-tsched": false,
-            "audio.format": "S{}LE",
-            "audio.rate": {},
-            "audio.allowed-rates": [ {} ],
-            "audio.channels": 2,
-            "audio.position": [ "FL", "FR" ],
-            "priority.driver": 200,
-            "priority.session": 200,
-            "device.suspend-on-idle": false,
-            "node.description": "Pro Audio Device (Configured)"
-          }}
-        }}
-      }}
-    ]
-  }}
-}}"#,
-        settings.buffer_size, settings.bit_depth, settings.sample_rate, settings.sample_rate
-    );
+/// Drops the combined output sink; streams on it move back to the fallback sink
+pub fn remove_combined_output() -> Result<(), AudioConfigError> {
+    let mut removed = false;
+    for path in managed_config_paths(ConfigScope::User) {
+        if is_config_file_named(&path, COMBINE_CONFIG_FILE) && path.exists() {
+            fs::remove_file(&path)
+                .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+            log::info!("✓ Removed: {}", path.display());
+            removed = true;
+        }
+    }
 
-    for dir in &wireplumber_dirs {
-        let config_path = format!("{}/99-pro-audio-devices.conf", dir);
+    if removed {
+        restart_audio_services(false, false)?;
+    }
+    Ok(())
+}
 
-        // Create directory if needed
-        if let Err(e) = create_dir_all_with_privileges(dir) {
-            println!(
-                "Note: Could not create WirePlumber directory {}: {}",
-                dir, e
-            );
-            continue;
-        }
+/// Makes the device the default sink (Output) or source (Input) with
+/// `wpctl set-default`. Takes effect right away; WirePlumber remembers it.
+pub fn set_default_device(device_id: &str, device_type: DeviceType) -> Result<(), String> {
+    if device_id == "default" {
+        return Err("The default device is already the default".to_string());
+    }
 
-        // Write the WirePlumber config
-        if let Err(e) = write_config_with_privileges(&config_path, &wireplumber_config) {
-            println!("Failed to write WirePlumber config {}: {}", config_path, e);
-            continue;
-        }
+    // Pulse ids are indexes into pactl's list, PipeWire knows the name
+    let device = match device_id.strip_prefix("pulse:") {
+        Some(pulse_id) => crate::audio::resolve_pulse_device_name(pulse_id)?,
+        None => device_id.to_string(),
+    };
+    let node_id = crate::audio::resolve_node_id(&device, &device_type)?;
+    log::info!(
+        "Setting node {} ({}) as the default device",
+        node_id,
+        device_id
+    );
 
-        println!("✓ WirePlumber device config created: {}", config_path);
-        return Ok(());
+    let output = pipewire_command("wpctl")
+        .args(["set-default", &node_id.to_string()])
+        .output()
+        .map_err(|e| format!("Failed to run wpctl: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "wpctl set-default {} failed: {}",
+            node_id,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
     }
 
-    println!("Note: Could not create WirePlumber config, using defaults");
+    crate::audio::clear_cache();
     Ok(())
 }
 
-// ----------------------------------------------------------------------------
-// Exclusive Mode Functions
-// ----------------------------------------------------------------------------
+/// Sets the resampler quality of streams created through pipewire-pulse
+/// (browsers, games), independent of the global resample.quality. Always user
+/// scope; restarts the audio services.
+pub fn apply_pulse_resample_quality(quality: u32) -> Result<(), AudioConfigError> {
+    let max = RESAMPLE_QUALITY_LEVELS
+        .last()
+        .map_or(14, |(_, level)| *level);
+    if quality > max {
+        return Err(format!("Resampler quality {} is out of range (0-{})", quality, max).into());
+    }
 
-/// Apply exclusive mode configuration
-fn apply_exclusive_mode_settings(
-    direct_hardware: bool,
-    low_latency: bool,
-    buffer_size: u32,
-    sample_rate: u32,
-    app_name: &str,
-    app_process_name: &str,
-) -> Result<(), String> {
-    println!("Configuring exclusive audio access mode...");
+    let config_dir = format!("{}/pipewire/pipewire-pulse.conf.d", user_config_dir());
+    let config_path = format!("{}/{}", config_dir, PULSE_RESAMPLE_CONFIG_FILE);
 
-    // Clean up ALL conflicting configs before creating exclusive
-    cleanup_audio_configs(false, "pipewire", "all")?;
-    cleanup_audio_configs(false, "wireplumber", "all")?;
+    create_dir_all_with_privileges(&config_dir)?;
+    write_config_with_privileges(&config_path, &generate_pulse_resample_config(quality))?;
+    log::info!("✓ pipewire-pulse resampler config created: {}", config_path);
 
-    // First, try the modern PipeWire exclusive mode approach
-    match create_pipewire_exclusive_config(
-        direct_hardware,
-        low_latency,
-        buffer_size,
-        sample_rate,
-        app_name,
-        app_process_name,
-    ) {
-        Ok(()) => {
-            println!("✓ PipeWire exclusive mode configured successfully");
-            restart_audio_services(false, true)?;
-            return Ok(());
+    restart_audio_services(false, false)?;
+    Ok(())
+}
+
+/// Drops the pulse resampler fragment; Pulse clients go back to pipewire-pulse's default
+pub fn remove_pulse_resample_quality() -> Result<(), AudioConfigError> {
+    let mut removed = false;
+    for path in managed_config_paths(ConfigScope::User) {
+        if is_config_file_named(&path, PULSE_RESAMPLE_CONFIG_FILE) && path.exists() {
+            fs::remove_file(&path)
+                .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+            log::info!("✓ Removed: {}", path.display());
+            removed = true;
         }
-        Err(e) => {
-            println!("PipeWire exclusive mode failed: {}, trying fallback...", e);
+    }
+
+    if removed {
+        restart_audio_services(false, false)?;
+    }
+    Ok(())
+}
+
+/// Resampler quality currently set for Pulse clients, None when not configured
+pub fn pulse_resample_quality() -> Option<u32> {
+    managed_config_paths(ConfigScope::User)
+        .iter()
+        .filter(|path| is_config_file_named(path, PULSE_RESAMPLE_CONFIG_FILE))
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .find_map(|content| parse_resample_quality(&content))
+}
+
+/// Level for a named quality ("fastest" ... "highest")
+pub fn resample_quality_level(name: &str) -> Option<u32> {
+    RESAMPLE_QUALITY_LEVELS
+        .iter()
+        .find(|(quality, _)| *quality == name)
+        .map(|(_, level)| *level)
+}
+
+// pulse.properties only holds server settings; the streams pipewire-pulse
+// creates for its clients take their properties from stream.properties
+fn generate_pulse_resample_config(quality: u32) -> String {
+    format!(
+        r#"# Pro Audio Config - pipewire-pulse Resampler
+# Resampler quality for PulseAudio clients (browsers, games), independent
+# of the global resample.quality
+
+stream.properties = {{
+    resample.quality = {}
+}}
+"#,
+        quality
+    )
+}
+
+fn parse_resample_quality(content: &str) -> Option<u32> {
+    content.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix("resample.quality")?
+            .trim_start()
+            .strip_prefix('=')?
+            .trim()
+            .trim_matches('"')
+            .parse()
+            .ok()
+    })
+}
+
+/// Plain-ALSA PCM names written to ~/.asoundrc for apps that bypass PipeWire
+pub const DIRECT_ALSA_PLAYBACK_PCM: &str = "pro_audio_playback";
+pub const DIRECT_ALSA_CAPTURE_PCM: &str = "pro_audio_capture";
+
+const ASOUNDRC_BLOCK_BEGIN: &str = "# >>> Pro Audio Config";
+const ASOUNDRC_BLOCK_END: &str = "# <<< Pro Audio Config";
+
+/// The user's ~/.asoundrc (inside `PRO_AUDIO_CONFIG_DIR` when that is set)
+pub fn asoundrc_path() -> PathBuf {
+    match std::env::var(CONFIG_DIR_ENV) {
+        Ok(dir) if !dir.trim().is_empty() => Path::new(dir.trim()).join(".asoundrc"),
+        _ => user_home_dir().join(".asoundrc"),
+    }
+}
+
+/// Writes rate and period/buffer defaults to ~/.asoundrc for apps that open
+/// ALSA directly instead of going through PipeWire. Only the block between
+/// our markers is touched. Fails when the device has no direct ALSA path
+/// (Bluetooth, virtual sinks). `device` is the node or device name behind
+/// the settings. Returns the PCM name apps should open.
+pub fn apply_direct_alsa_config(
+    settings: &AudioSettings,
+    stream_type: &str,
+    device: &str,
+) -> Result<String, String> {
+    let card = crate::audio::alsa_card_for_device(device)
+        .map_err(|e| format!("No direct ALSA path for this device: {}", e))?;
+    let block = generate_asoundrc_block(settings, stream_type, card);
+    update_asoundrc(stream_type, Some(&block))?;
+
+    Ok(direct_alsa_pcm_name(stream_type).to_string())
+}
+
+/// Drops our ~/.asoundrc block for `stream_type`; nothing happens when there is none
+pub fn remove_direct_alsa_config(stream_type: &str) -> Result<(), String> {
+    update_asoundrc(stream_type, None)
+}
+
+fn direct_alsa_pcm_name(stream_type: &str) -> &'static str {
+    if stream_type == "input" {
+        DIRECT_ALSA_CAPTURE_PCM
+    } else {
+        DIRECT_ALSA_PLAYBACK_PCM
+    }
+}
+
+fn update_asoundrc(stream_type: &str, block: Option<&str>) -> Result<(), String> {
+    let path = resolve_config_path(&asoundrc_path());
+    let existing = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+
+    let updated = replace_managed_block(&existing, stream_type, block);
+    if updated == existing {
+        return Ok(());
+    }
+
+    write_file_atomically(&path, &updated)?;
+    let action = if block.is_some() {
+        "written"
+    } else {
+        "removed"
+    };
+    log::info!(
+        "✓ Direct ALSA {} config {}: {}",
+        stream_type,
+        action,
+        path.display()
+    );
+    Ok(())
+}
+
+/// Replaces (or with `None` removes) the marked block for `stream_type`,
+/// keeping everything else in the file as it was
+fn replace_managed_block(existing: &str, stream_type: &str, block: Option<&str>) -> String {
+    let begin = format!("{} ({})", ASOUNDRC_BLOCK_BEGIN, stream_type);
+    let end = format!("{} ({})", ASOUNDRC_BLOCK_END, stream_type);
+
+    let mut kept = Vec::new();
+    let mut inside = false;
+    for line in existing.lines() {
+        if line.trim() == begin {
+            inside = true;
+        } else if inside && line.trim() == end {
+            inside = false;
+        } else if !inside {
+            kept.push(line);
         }
     }
+    while kept.last().is_some_and(|line| line.trim().is_empty()) {
+        kept.pop();
+    }
 
-    // Fallback: Use WirePlumber configuration for exclusive access
-    match create_wireplumber_exclusive_config(
-        direct_hardware,
-        low_latency,
-        buffer_size,
-        sample_rate,
-        app_name,
-        app_process_name,
-    ) {
-        Ok(()) => {
-            println!("✓ WirePlumber exclusive mode configured successfully");
-            restart_audio_services(false, true)?;
-            Ok(())
+    let mut content = kept.join("\n");
+    if let Some(block) = block {
+        if !content.is_empty() {
+            content.push_str("\n\n");
         }
-        Err(e) => Err(format!("All exclusive mode approaches failed: {}", e)),
+        content.push_str(&begin);
+        content.push('\n');
+        content.push_str(block.trim_end());
+        content.push('\n');
+        content.push_str(&end);
     }
+    if !content.is_empty() {
+        content.push('\n');
+    }
+    content
 }
 
-/// Enhanced exclusive mode with device capability checking
-fn apply_enhanced_exclusive_mode_settings(
-    direct_hardware: bool,
-    low_latency: bool,
-    buffer_size: u32,
-    sample_rate: u32,
-    device_pattern: &str,
-    app_name: &str,
-    app_process_name: &str,
-) -> Result<(), String> {
-    println!("Configuring enhanced exclusive audio access mode...");
+/// dmix/dsnoop keep the card shareable between direct-ALSA apps; the period is
+/// the hardware period and the buffer holds two of them, as in the WirePlumber
+/// rules. pcm.!default is left alone so PipeWire stays the default device.
+fn generate_asoundrc_block(settings: &AudioSettings, stream_type: &str, card: u32) -> String {
+    let (plugin, rate_key, ipc_key) = if stream_type == "input" {
+        ("dsnoop", "defaults.pcm.dsnoop.rate", 41954)
+    } else {
+        ("dmix", "defaults.pcm.dmix.rate", 41953)
+    };
+    let period_size = settings.hardware_period_size();
 
-    // Check device suitability for exclusive mode
-    if let Ok(devices) = crate::audio::detect_high_performance_devices() {
-        let target_device = if device_pattern == "default" {
-            devices.first()
-        } else {
-            devices.iter().find(|d| d.id.contains(device_pattern))
-        };
+    format!(
+        r#"# Written by Pro Audio Config for apps that use ALSA directly.
+# The card must not be held by PipeWire (e.g. profile "Off") to open it.
+defaults.pcm.rate_converter [ "speexrate_medium" "samplerate_medium" "linear" ]
+{rate_key} {rate}
+
+pcm.{pcm} {{
+    type plug
+    slave.pcm {{
+        type {plugin}
+        ipc_key {ipc_key}
+        ipc_key_add_uid true
+        slave {{
+            pcm "hw:{card},0"
+            rate {rate}
+            period_size {period_size}
+            buffer_size {buffer_size}
+        }}
+    }}
+}}
+"#,
+        rate_key = rate_key,
+        rate = settings.sample_rate,
+        pcm = direct_alsa_pcm_name(stream_type),
+        plugin = plugin,
+        ipc_key = ipc_key,
+        card = card,
+        period_size = period_size,
+        buffer_size = period_size * 2,
+    )
+}
 
-        if let Some(device) = target_device {
-            if !crate::audio::is_device_suitable_for_exclusive_mode(device) {
-                println!("Warning: Selected device may not be ideal for exclusive mode");
-            }
+/// pipewire-jack fragment with the quantum and rate JACK clients ask for
+pub const JACK_CONFIG_FILE: &str = "99-pro-audio-jack.conf";
+/// jackd's default number of periods per buffer
+pub const DEFAULT_JACK_PERIODS: u32 = 2;
+
+/// The JACK server the JACK settings are written for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JackBackend {
+    /// Real jackd/jackdbus, configured through ~/.config/jack/conf.xml
+    Jackd,
+    /// PipeWire's JACK replacement, configured through jack.conf.d
+    PipeWireJack,
+}
 
-            // Get device capabilities
-            if let Ok(capabilities) = crate::audio::get_device_capabilities(&device.id) {
-                if !capabilities.buffer_sizes.contains(&buffer_size) {
-                    println!(
-                        "Warning: Buffer size {} may not be optimal for {}",
-                        buffer_size, device.name
-                    );
-                }
-                if !capabilities.sample_rates.contains(&sample_rate) {
-                    println!(
-                        "Warning: Sample rate {} may not be supported by {}",
-                        sample_rate, device.name
-                    );
-                }
-            }
+impl JackBackend {
+    pub fn label(&self) -> &'static str {
+        match self {
+            JackBackend::Jackd => "jackd",
+            JackBackend::PipeWireJack => "pipewire-jack",
         }
     }
+}
 
-    // Proceed with existing exclusive mode configuration
-    apply_exclusive_mode_settings(
-        direct_hardware,
-        low_latency,
-        buffer_size,
-        sample_rate,
-        app_name,
-        app_process_name,
+/// What `apply_jack_settings` wrote and what the JACK tools reported back
+#[derive(Clone, Debug, PartialEq)]
+pub struct JackOutcome {
+    pub backend: JackBackend,
+    pub config_path: String,
+    pub warnings: Vec<String>,
+}
+
+/// jackd when a jackd server is running or pipewire-jack is missing,
+/// pipewire-jack otherwise. None when neither is installed.
+pub fn detect_jack_backend() -> Option<JackBackend> {
+    let jackd_running = ["jackd", "jackdbus"].iter().any(|name| {
+        Command::new("pgrep")
+            .args(["-x", name])
+            .output()
+            .is_ok_and(|output| output.status.success())
+    });
+    // pw-jack ships with pipewire-jack, jack.conf with its config
+    let pipewire_jack = crate::utils::find_in_path("pw-jack")
+        || Path::new("/usr/share/pipewire/jack.conf").exists();
+
+    choose_jack_backend(
+        jackd_running,
+        pipewire_jack,
+        crate::utils::find_in_path("jackd"),
     )
 }
 
-/// Create PipeWire configuration for exclusive mode with application targeting
-fn create_pipewire_exclusive_config(
-    direct_hardware: bool,
-    low_latency: bool,
-    buffer_size: u32,
-    sample_rate: u32,
-    app_name: &str,
-    app_process_name: &str,
-) -> Result<(), String> {
-    let username = whoami::username();
-    let config_dir = format!("/home/{}/.config/pipewire/pipewire.conf.d", username);
-    let config_path = format!("{}/99-pro-audio-exclusive.conf", config_dir);
+fn choose_jack_backend(
+    jackd_running: bool,
+    pipewire_jack: bool,
+    jackd_installed: bool,
+) -> Option<JackBackend> {
+    if jackd_running || (jackd_installed && !pipewire_jack) {
+        Some(JackBackend::Jackd)
+    } else if pipewire_jack {
+        Some(JackBackend::PipeWireJack)
+    } else {
+        None
+    }
+}
 
-    let audio_format = if low_latency { "S32LE" } else { "S24LE" };
+/// Writes rate and buffer for JACK clients: conf.xml for jackd with the
+/// period count and driver ("alsa" or "dummy"), a jack.conf.d fragment for
+/// pipewire-jack, which has neither. Checks the result with jack_samplerate
+/// and jack_bufsize when they are installed; a server not running yet is
+/// only a warning, jackd reads conf.xml when it starts.
+pub fn apply_jack_settings(
+    settings: &AudioSettings,
+    periods: u32,
+    driver: &str,
+) -> Result<JackOutcome, String> {
+    if !(2..=8).contains(&periods) {
+        return Err(format!("Invalid JACK period count: {}", periods));
+    }
+    if !matches!(driver, "alsa" | "dummy") {
+        return Err(format!("Unknown JACK driver: {}", driver));
+    }
 
-    // Clean up ALL conflicting configs before creating exclusive
-    cleanup_audio_configs(false, "pipewire", "conflicting")?;
-    cleanup_audio_configs(false, "wireplumber", "conflicting")?;
+    let backend = detect_jack_backend()
+        .ok_or_else(|| "Neither jackd nor pipewire-jack is installed".to_string())?;
+    log::info!(
+        "Applying JACK settings for {}: {}Hz/{} samples",
+        backend.label(),
+        settings.sample_rate,
+        settings.buffer_size
+    );
 
-    // Get the current default device to target
-    let target_device = match crate::audio::detect_output_audio_device() {
-        Ok(device_info) => {
-            if let Some(device_name) = crate::audio::extract_actual_device_name(&device_info) {
-                println!("Targeting exclusive mode to device: {}", device_name);
-                device_name
-            } else {
-                println!("Warning: Could not extract device name, using default pattern");
-                "alsa.*".to_string()
-            }
+    let (config_path, content) = match backend {
+        JackBackend::Jackd => (
+            format!("{}/jack/conf.xml", user_config_dir()),
+            generate_jackd_config(settings, periods, driver),
+        ),
+        JackBackend::PipeWireJack => (
+            format!(
+                "{}/pipewire/jack.conf.d/{}",
+                user_config_dir(),
+                JACK_CONFIG_FILE
+            ),
+            generate_pipewire_jack_config(settings),
+        ),
+    };
+
+    // conf.xml is jackdbus's own file, keep what was there before
+    if backend == JackBackend::Jackd && Path::new(&config_path).exists() {
+        let backup_path = format!("{}.bak", config_path);
+        fs::copy(&config_path, &backup_path)
+            .map_err(|e| format!("Failed to back up {}: {}", config_path, e))?;
+        log::info!("✓ Previous JACK config backed up to: {}", backup_path);
+    }
+
+    write_config_with_privileges(&config_path, &content)?;
+    log::info!("✓ JACK config created: {}", config_path);
+
+    Ok(JackOutcome {
+        backend,
+        warnings: verify_jack_settings(settings, backend),
+        config_path,
+    })
+}
+
+/// jackdbus configuration; the dummy driver has no periods
+fn generate_jackd_config(settings: &AudioSettings, periods: u32, driver: &str) -> String {
+    let nperiods = if driver == "alsa" {
+        format!("\n   <option name=\"nperiods\">{}</option>", periods)
+    } else {
+        String::new()
+    };
+
+    format!(
+        r#"<?xml version="1.0"?>
+<!--
+Written by Pro Audio Config
+-->
+<jack>
+ <engine>
+  <option name="driver">{driver}</option>
+  <option name="realtime">true</option>
+ </engine>
+ <drivers>
+  <driver name="{driver}">
+   <option name="rate">{rate}</option>
+   <option name="period">{period}</option>{nperiods}
+  </driver>
+ </drivers>
+</jack>
+"#,
+        driver = driver,
+        rate = settings.sample_rate,
+        period = settings.buffer_size,
+        nperiods = nperiods,
+    )
+}
+
+/// jack.properties every pipewire-jack client starts with
+fn generate_pipewire_jack_config(settings: &AudioSettings) -> String {
+    format!(
+        r#"# Pro Audio Config - pipewire-jack Settings
+# JACK clients ask for this buffer and rate
+
+jack.properties = {{
+    node.latency = {quantum}/{rate}
+    node.rate = 1/{rate}
+    node.force-quantum = {quantum}
+    node.force-rate = {rate}
+}}
+"#,
+        quantum = settings.buffer_size,
+        rate = settings.sample_rate,
+    )
+}
+
+/// Compares what jack_samplerate/jack_bufsize report with the settings.
+/// Returns warnings; none when the tools aren't installed.
+fn verify_jack_settings(settings: &AudioSettings, backend: JackBackend) -> Vec<String> {
+    let mut warnings = Vec::new();
+    // pw-jack points the tools at PipeWire where libjack is still the real one
+    let mut run = |tool: &str| -> Option<String> {
+        if !crate::utils::find_in_path(tool) {
+            return None;
         }
-        Err(e) => {
-            println!(
-                "Warning: Could not detect default device: {}, using default pattern",
-                e
-            );
-            "alsa.*".to_string()
+        let mut command =
+            if backend == JackBackend::PipeWireJack && crate::utils::find_in_path("pw-jack") {
+                let mut command = Command::new("pw-jack");
+                command.arg(tool);
+                command
+            } else {
+                Command::new(tool)
+            };
+        let output = command.output().ok()?;
+        if !output.status.success() {
+            warnings.push(format!("{} could not reach a JACK server", tool));
+            return None;
         }
+        Some(String::from_utf8_lossy(&output.stdout).into_owned())
     };
 
-    // Sanitize app names for use in config
-    let sanitized_app_name = app_name.replace([' ', '.', ':', '"', '\''], "_");
-    let sanitized_process_name = app_process_name.replace([' ', '.', ':', '"', '\''], "_");
-
-    let config_content = format!(
-        r#"# Pro Audio Config - Exclusive Mode for {}
-# Target application: {} (process: {})
-# This configuration enables ASIO-like exclusive mode for specific application
+    let rate = run("jack_samplerate").and_then(|output| parse_jack_number(&output, None));
+    let buffer =
+        run("jack_bufsize").and_then(|output| parse_jack_number(&output, Some("buffer size")));
 
-context.properties = {{
-    # CRITICAL: Override quantum-floor to allow our buffer size
-    default.clock.quantum-floor = {}
-    default.clock.min-quantum = {}
-    default.clock.max-quantum = {}
+    if let Some(rate) = rate
+        && rate != settings.sample_rate
+    {
+        warnings.push(format!(
+            "JACK runs at {}Hz, not {}Hz; restart the JACK server",
+            rate, settings.sample_rate
+        ));
+    }
+    if let Some(buffer) = buffer
+        && buffer != settings.buffer_size
+    {
+        warnings.push(format!(
+            "JACK buffer is {} samples, not {}; restart the JACK server",
+            buffer, settings.buffer_size
+        ));
+    }
 
-    # Core settings
-    default.clock.rate = {}
-    default.clock.quantum = {}
-    default.clock.allowed-rates = [ {} ]
+    warnings
+}
 
-    # Force settings to be used
-    default.clock.force-quantum = {}
-    default.clock.force-rate = {}
+/// The number after `label` (e.g. "buffer size = 256"), or the first number
+/// in the output when there is no label
+fn parse_jack_number(output: &str, label: Option<&str>) -> Option<u32> {
+    let text = match label {
+        Some(label) => &output[output.find(label)? + label.len()..],
+        None => output,
+    };
+    text.split(|c: char| !c.is_ascii_digit())
+        .find(|part| !part.is_empty())?
+        .parse()
+        .ok()
+}
 
-    # IMPORTANT: Disable quantum checking
-    settings.check-quantum = false
-    settings.check-rate = false
-    settings.check-quantum-limit = false
-    settings.check-quantum-floor = false
+/// Apps named in the active exclusive config, empty when exclusive mode is off
+pub fn read_exclusive_apps() -> Vec<ExclusiveApp> {
+    managed_config_paths(ConfigScope::User)
+        .iter()
+        .filter(|path| is_config_file_named(path, EXCLUSIVE_CONFIG_FILE))
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .map(|content| parse_exclusive_apps(&content))
+        .find(|apps| !apps.is_empty())
+        .unwrap_or_default()
+}
 
-    # Additional exclusive mode settings
-    stream.dont-remix = true
-    stream.dont-resample = true
-    mem.allow-mlock = true
-    session.suspend-timeout-seconds = 0
+/// Restore standard shared audio mode
+pub fn restore_standard_audio_mode() -> Result<(), AudioConfigError> {
+    log::info!("Restoring standard shared audio mode...");
 
-    # Debug properties to verify our config is loaded
-    pro-audio-config.rate = {}
-    pro-audio-config.quantum = {}
-    pro-audio-config.version = "1.7
-    pro-audio-config.exclusive = true
-    pro-audio-config.target-app = "{}"
-    pro-audio-config.target-process = "{}"
-    pro-audio-config.target-device = "{}"
-}}
+    // Remove exclusive mode configurations
+    let config_files: Vec<PathBuf> = managed_config_paths(ConfigScope::User)
+        .into_iter()
+        .filter(|path| is_config_file_named(path, EXCLUSIVE_CONFIG_FILE))
+        .collect();
 
-# Device-specific rules with CORRECT JSON SYNTAX
-# NOTE: PipeWire uses JSON syntax, NOT Lua table syntax
-context.rules = [
-    {{
-        matches = [
-            {{
-                node.name = "~{}"
-            }}
-        ],
-        actions = {{
-            update-props = {{
-                # Override device properties for exclusive access
-                api.alsa.period-size = {}
-                api.alsa.period-num = 2
-                api.alsa.disable-batch = true
-                api.alsa.use-acp = false
-                audio.rate = {}
-                audio.allowed-rates = [ {} ]
-                audio.format = "{}"
-                # Device priority
-                priority.driver = 1000
-                priority.session = 1000
-                # Disable power management
-                device.suspend-on-idle = false
-                node.suspend-on-idle = false
-                # Force our quantum settings on the device
-                node.quantum = {}
-                node.min-quantum = {}
-                node.max-quantum = {}
-                node.lock-quantum = true
-                # Application binding
-                target.object = "exclusive-playback-{}"
-                # Debug info
-                pro-audio.exclusive = true
-                pro-audio.target-app = "{}"
-                pro-audio.target-rate = {}
-                pro-audio.target-quantum = {}
-            }}
-        }}
-    }}
-]
-
-# Real-time module for better performance
-context.modules = [
-    {{
-        name = libpipewire-module-rt
-        args = {{
-            nice.level = -11
-            rt.prio = 80
-            rt.time.soft = 200000
-            rt.time.hard = 200000
-        }}
-        flags = [ ifexists nofail ]
-    }}
-]
-"#,
-        // Header and app info (3 args)
-        sanitized_app_name,
-        app_name,
-        app_process_name,
-        // Global quantum settings (3 args)
-        buffer_size,
-        buffer_size,
-        buffer_size * 2,
-        // Core settings (3 args)
-        sample_rate,
-        buffer_size,
-        sample_rate,
-        // Force settings (2 args)
-        buffer_size,
-        sample_rate,
-        // Debug properties (5 args)
-        sample_rate,
-        buffer_size,
-        app_name,
-        app_process_name,
-        target_device,
-        // Device rules (11 args)
-        target_device,
-        buffer_size,
-        sample_rate,
-        sample_rate,
-        audio_format,
-        buffer_size,
-        buffer_size,
-        buffer_size * 2,
-        sanitized_app_name,
-        app_name,
-        sample_rate,
-        buffer_size,
-    );
+    let mut removed_count = 0;
+    for config_file in &config_files {
+        if config_file.exists()
+            && let Ok(()) = fs::remove_file(config_file)
+        {
+            log::info!("✓ Removed exclusive config: {}", config_file.display());
+            removed_count += 1;
+        }
+    }
 
-    // Backup current config before writing
-    if let Err(e) = backup_current_config(&config_dir) {
-        println!("Note: Could not backup config (non-fatal): {}", e);
+    if removed_count > 0 {
+        restart_audio_services(false, true)?;
+        log::info!("✓ Standard audio mode restored");
+    } else {
+        log::info!("✓ Already in standard audio mode");
     }
 
-    write_config_with_privileges(&config_path, &config_content)?;
-    println!("✓ Exclusive mode configuration created for:");
-    println!("  Application: {} ({})", app_name, app_process_name);
-    println!("  Device: {}", target_device);
-    println!("  Settings: {}Hz/{} samples", sample_rate, buffer_size);
-    println!("  Config: {}", config_path);
     Ok(())
 }
 
-/// Create WirePlumber configuration for exclusive access
-fn create_wireplumber_exclusive_config(
-    direct_hardware: bool,
-    low_latency: bool,
-    buffer_size: u32,
-    sample_rate: u32,
-    app_name: &str,
-    app_process_name: &str,
-) -> Result<(), String> {
-    let username = whoami::username();
-    let config_dir = format!("/home/{}/.config/wireplumber/wireplumber.conf.d", username);
-    let config_path = format!("{}/99-pro-audio-exclusive.conf", config_dir);
+/// When enabled, applies also write a pipewire-pulse fragment so Pulse clients
+/// (browsers, games) follow the chosen buffer instead of their own minimum
+pub fn set_pulse_quantum_sync(enabled: bool) {
+    SYNC_PULSE_QUANTUM.store(enabled, Ordering::SeqCst);
+}
 
-    // Create sanitized names for config
-    let sanitized_app_name = app_name.replace([' ', '.', ':', '"', '\''], "_");
-    let sanitized_process_name = app_process_name.replace([' ', '.', ':', '"', '\''], "_");
+/// Sets the RLIMIT_RTTIME (µs) every generated libpipewire-module-rt block
+/// uses: how long a realtime audio thread may run without blocking before
+/// the kernel sends SIGXCPU (soft) or kills it (hard)
+pub fn set_rt_time_limits(soft_us: u32, hard_us: u32) -> Result<(), String> {
+    if soft_us == 0 || soft_us > hard_us {
+        return Err(format!(
+            "Invalid RT time limits: soft {} µs must be above 0 and at most hard {} µs",
+            soft_us, hard_us
+        ));
+    }
+    RT_TIME_SOFT_US.store(soft_us, Ordering::SeqCst);
+    RT_TIME_HARD_US.store(hard_us, Ordering::SeqCst);
+    Ok(())
+}
 
-    let audio_format = if low_latency { "S32LE" } else { "S24LE" };
+/// Current (soft, hard) rt.time limits in µs
+pub fn rt_time_limits() -> (u32, u32) {
+    (
+        RT_TIME_SOFT_US.load(Ordering::SeqCst),
+        RT_TIME_HARD_US.load(Ordering::SeqCst),
+    )
+}
 
-    let config_content = format!(
-        r#"# Pro Audio Config - Exclusive Mode for WirePlumber
-# Target application: {} (process: {})
-# This configuration enables ASIO-like exclusive mode for specific application
-
-override.monitor.alsa.rules = [
-  {{
-    matches = [
-      {{
-        application.name = "{}",
-        application.process.binary = "{}"
-      }}
-    ],
-    actions = {{
-      update-props = {{
-        # Route this app to our exclusive device
-        node.target = "exclusive-playback-{}",
-        media.class = "Audio/Sink",
-        # Apply exclusive audio settings to this app
-        audio.rate = {},
-        audio.allowed-rates = [ {} ],
-        audio.format = "{}",
-        node.quantum = {},
-        node.pause-on-idle = false,
-        # High priority
-        priority.driver = 3000,
-        priority.session = 3000,
-        # Mark as exclusive
-        stream.exclusive = true
-      }}
-    }}
-  }}
-]
+/// libpipewire-module-rt with the configured rt.time limits
+fn rt_module(nice_level: i32, rt_prio: u32) -> PipeWireModule {
+    let (soft, hard) = rt_time_limits();
+    PipeWireModule::rt(nice_level, rt_prio, soft, hard)
+}
 
-# Additional device rules for low-latency exclusive access
-override.monitor.alsa.rules = [
-  {{
-    matches = [
-      {{
-        device.name = "~alsa.*"
-      }}
-    ],
-    actions = {{
-      update-props = {{
-        audio.rate = {},
-        audio.allowed-rates = [ {} ],
-        api.alsa.period-size = {},
-        api.alsa.period-num = 2,
-        api.alsa.headroom = {},
-        api.alsa.disable-batch = {},
-        api.alsa.use-acp = false,
-        priority.driver = 200,
-        session.suspend-timeout-seconds = 0  # Never suspend for exclusive mode
-      }}
-    }}
-  }}
-]
-"#,
-        // Application matching section (7 args)
-        app_name,
-        app_process_name,
-        app_name,
-        app_process_name,
-        sanitized_app_name,
-        sample_rate,
-        sample_rate,
-        audio_format,
-        buffer_size,
-        // Device configuration section (5 args)
-        sample_rate,
-        sample_rate,
-        buffer_size,
-        buffer_size / 2,
-        direct_hardware
-    );
+/// Environment variable that redirects all user-level config reads/writes
+pub const CONFIG_DIR_ENV: &str = "PRO_AUDIO_CONFIG_DIR";
 
-    // Backup current config before writing
-    if let Err(e) = backup_current_config(&config_dir) {
-        println!("Note: Could not backup config (non-fatal): {}", e);
-    }
+/// Environment variable that makes applies write their files only: no service
+/// restart and no verification against the running server
+pub const OFFLINE_ENV: &str = "PRO_AUDIO_OFFLINE";
 
-    create_dir_all_with_privileges(&config_dir)?;
-    write_config_with_privileges(&config_path, &config_content)?;
-    println!(
-        "✓ WirePlumber exclusive configuration created: {}",
-        config_path
-    );
-    Ok(())
+/// Whether `PRO_AUDIO_OFFLINE` is set to anything but "" or "0"
+pub fn offline_mode() -> bool {
+    std::env::var(OFFLINE_ENV).is_ok_and(|value| !value.trim().is_empty() && value.trim() != "0")
 }
 
-// ----------------------------------------------------------------------------
-// Configuration Generation Functions
-// ----------------------------------------------------------------------------
+/// Base directory holding the user's `pipewire/` and `wireplumber/` config trees:
+/// $XDG_CONFIG_HOME, else ~/.config. Honors `PRO_AUDIO_CONFIG_DIR` so tests and
+/// sandboxed runs never touch ~/.config.
+pub fn user_config_dir() -> String {
+    match std::env::var(CONFIG_DIR_ENV) {
+        Ok(dir) if !dir.trim().is_empty() => dir.trim_end_matches('/').to_string(),
+        _ => directories::BaseDirs::new()
+            .map(|dirs| dirs.config_dir().to_string_lossy().into_owned())
+            .unwrap_or_else(|| format!("{}/.config", user_home_dir().display())),
+    }
+}
 
-/// Generates modern WirePlumber JSON configuration content for versions >= 0.5
-fn generate_wireplumber_config(settings: &AudioSettings, _stream_type: &str) -> String {
-    let device_pattern = if settings.device_id == "default" {
-        "~alsa.*".to_string()
+/// pipewire.conf.d of the user config dir or, with `system_wide`, of /etc
+fn pipewire_conf_d(system_wide: bool) -> String {
+    if system_wide {
+        "/etc/pipewire/pipewire.conf.d".to_string()
     } else {
-        settings.device_id.clone()
-    };
-
-    let audio_format = match settings.bit_depth {
-        16 => "S16LE",
-        24 => "S24LE",
-        32 => "S32LE",
-        _ => "S24LE",
-    };
+        format!("{}/pipewire/pipewire.conf.d", user_config_dir())
+    }
+}
 
-    format!(
-        r#"{{
-  "alsa-monitor": {{
-    "rules": [
-      {{
-        "matches": [
-          {{
-            "device.name": "{}"
-          }}
-        ],
-        "actions": [
-          {{
-            "update-props": {{
-              "audio.format": "{}",
-              "audio.rate": {},
-              "api.alsa.period-size": {},
-              "api.alsa.period-num"] = 2,
-              "api.alsa.headroom"] = 8192
-            }}
-          }}
-        ]
-      }}
-    ]
-  }}
-}}"#,
-        device_pattern, audio_format, settings.sample_rate, settings.buffer_size
-    )
+fn pipewire_pulse_conf_d(system_wide: bool) -> String {
+    if system_wide {
+        "/etc/pipewire/pipewire-pulse.conf.d".to_string()
+    } else {
+        format!("{}/pipewire/pipewire-pulse.conf.d", user_config_dir())
+    }
 }
 
-// ----------------------------------------------------------------------------
-// Configuration Management
-// ----------------------------------------------------------------------------
+fn wireplumber_conf_d(system_wide: bool) -> String {
+    if system_wide {
+        "/etc/wireplumber/wireplumber.conf.d".to_string()
+    } else {
+        format!("{}/wireplumber/wireplumber.conf.d", user_config_dir())
+    }
+}
 
-/// Clean up conflicting system PipeWire configurations
-fn cleanup_system_pipewire_configs() -> Result<(), String> {
-    cleanup_audio_configs(true, "pipewire", "conflicting")
+/// The main pipewire.conf the professional apply edits
+fn main_pipewire_conf(system_wide: bool) -> String {
+    if system_wide {
+        "/etc/pipewire/pipewire.conf".to_string()
+    } else {
+        format!("{}/pipewire/pipewire.conf", user_config_dir())
+    }
 }
 
-/// Clean up conflicting user PipeWire configurations
-fn cleanup_user_pipewire_configs() -> Result<(), String> {
-    cleanup_audio_configs(false, "pipewire", "conflicting")
+/// The user's home directory: $HOME, else the passwd entry. Homes are not
+/// always under /home (/var/home on Silverblue, network mounts).
+pub fn user_home_dir() -> PathBuf {
+    directories::BaseDirs::new()
+        .map(|dirs| dirs.home_dir().to_path_buf())
+        .unwrap_or_else(|| PathBuf::from(format!("/home/{}", whoami::username())))
 }
 
-/// Unified cleanup function for all PipeWire/WirePlumber configurations
-/// system_wide: true for /etc/, false for user configs
-/// config_type: "pipewire" or "wireplumber"
-/// mode: "all", "basic", "advanced", "exclusive", "conflicting"
-fn cleanup_audio_configs(system_wide: bool, config_type: &str, mode: &str) -> Result<(), String> {
-    let username = whoami::username();
+/// Compare the running PipeWire settings against previously applied ones
+/// without reapplying anything
+pub fn verify_current_settings(expected: &AudioSettings) -> Result<Vec<SettingCheck>, String> {
+    // Always look at the live graph, not a cached snapshot
+    crate::audio::clear_cache();
 
-    // Determine base directory
-    let base_dir = if system_wide {
-        "/etc".to_string()
-    } else {
-        format!("/home/{}", username)
-    };
+    let current = crate::audio::detect_current_audio_settings()
+        .map_err(|e| format!("Failed to detect current settings for verification: {}", e))?;
 
-    // Build config directory path
-    let config_dir = match config_type {
-        "pipewire" => format!("{}/.config/pipewire/pipewire.conf.d", base_dir),
-        "wireplumber" => format!("{}/.config/wireplumber/wireplumber.conf.d", base_dir),
-        _ => return Err(format!("Unknown config type: {}", config_type)),
-    };
+    Ok(compare_audio_settings(expected, &current))
+}
 
-    println!("Cleaning up {} configs in: {}", mode, config_dir);
+// ============================================================================
+// PRIVATE CONFIGURATION FUNCTIONS (Alphabetically within groups)
+// ============================================================================
 
-    // Define which files to remove based on mode
-    let patterns = match mode {
-        "all" => vec![
-            format!("{}/*pro-audio*.conf", config_dir),
-            format!("{}/*quantum*.conf", config_dir),
-            format!("{}/*exclusive*.conf", config_dir),
-        ],
-        "basic" => vec![
-            format!("{}/99-pro-audio-high-priority.conf", config_dir),
-            format!("{}/99-pro-audio.conf", config_dir),
-            format!("{}/50-pro-audio.conf", config_dir),
-        ],
-        "advanced" => vec![
-            format!("{}/99-pro-audio-advanced.conf", config_dir),
-            format!("{}/99-pro-audio-quantum-override.conf", config_dir),
-        ],
-        "exclusive" => vec![format!("{}/99-pro-audio-exclusive.conf", config_dir)],
-        "conflicting" => vec![
-            format!("{}/99-pro-audio-high-priority.conf", config_dir),
-            format!("{}/99-pro-audio.conf", config_dir),
-            format!("{}/50-pro-audio.conf", config_dir),
-            format!("{}/99-pro-audio-advanced.conf", config_dir),
-            format!("{}/99-pro-audio-quantum-override.conf", config_dir),
-        ],
-        _ => return Err(format!("Unknown cleanup mode: {}", mode)),
-    };
+// ----------------------------------------------------------------------------
+// Configuration File Creation
+// ----------------------------------------------------------------------------
 
-    let mut removed_count = 0;
+/// Creates an ADVANCED PipeWire configuration fragment for professional use
+fn create_advanced_pipewire_fragment(
+    settings: &AudioSettings,
+    system_wide: bool,
+    options: &ProfessionalOptions,
+    batch: &mut PrivilegedBatch,
+) -> Result<(), String> {
+    let config_content = generate_professional_pipewire_config(settings, options);
+    let config_path = format!(
+        "{}/{}",
+        pipewire_conf_d(system_wide),
+        QUANTUM_OVERRIDE_CONFIG_FILE
+    );
 
-    for pattern in patterns {
-        // Use glob pattern matching for wildcards
-        if pattern.contains('*') {
-            if let Ok(entries) = glob(&pattern) {
-                for entry in entries.flatten() {
-                    if let Ok(()) = fs::remove_file(&entry) {
-                        println!("✓ Removed: {}", entry.display());
-                        removed_count += 1;
-                    }
-                }
-            }
-        } else {
-            // Direct file path
-            if Path::new(&pattern).exists()
-                && let Ok(()) = fs::remove_file(&pattern)
-            {
-                println!("✓ Removed: {}", pattern);
-                removed_count += 1;
-            }
-        }
-    }
+    // Clean up ALL conflicting configs before creating
+    cleanup_audio_configs(system_wide, "pipewire", "conflicting")?;
 
-    if removed_count > 0 {
-        println!("✓ Removed {} configuration files", removed_count);
-    }
+    // Write the quantum-override config
+    batch.write(&config_path, &config_content)?;
+
+    log::info!("✓ Quantum override config created: {}", config_path);
+    log::info!("  Overriding quantum-floor with: {}", settings.buffer_size);
 
     Ok(())
 }
 
-/// Modifies the main PipeWire configuration file as a fallback
-fn modify_main_pipewire_config(settings: &AudioSettings, system_wide: bool) -> Result<(), String> {
-    let username = whoami::username();
+fn generate_professional_pipewire_config(
+    settings: &AudioSettings,
+    options: &ProfessionalOptions,
+) -> String {
+    generate_advanced_pipewire_config(
+        settings,
+        &options.thread_priority,
+        options.memory_lock,
+        options.suspend_timeout,
+        options.disable_remixing,
+        options.disable_resampling,
+        &options.resampler_quality,
+        &options.clock_source,
+    )
+}
 
-    let config_paths = if system_wide {
-        vec!["/etc/pipewire/pipewire.conf".to_string()]
-    } else {
-        vec![format!("/home/{}/.config/pipewire/pipewire.conf", username)]
+/// Quantum-override fragment: pins the clock (floor, min and force quantum at
+/// the target), applies the professional options and adds a null sink named
+/// after the settings to verify they were loaded
+fn generate_advanced_pipewire_config(
+    settings: &AudioSettings,
+    thread_priority: &str,
+    memory_lock: bool,
+    suspend_timeout: u32,
+    disable_remixing: bool,
+    disable_resampling: bool,
+    resampler_quality: &str,
+    clock_source: &str,
+) -> String {
+    // Map thread priority
+    let (nice_level, rt_prio) = match thread_priority {
+        "normal" => (-11, 88),
+        "high" => (-15, 90),
+        "realtime" => (-20, 99),
+        _ => (-15, 90),
     };
 
-    for path in &config_paths {
-        if !Path::new(path).exists() {
-            continue;
-        }
+    let quantum = settings.buffer_size;
+    let channel_positions = settings.channel_positions();
+    PipeWireConfig::new()
+        .comment("Pro Audio Config - Quantum Floor Override")
+        .comment("This OVERRIDES default.clock.quantum-floor = 4")
+        .clock_rate(settings.sample_rate)
+        .clock_quantum(quantum)
+        .allowed_rates(&settings.effective_allowed_rates())
+        // The floor must not be below our quantum or PipeWire raises it
+        .quantum_floor(quantum)
+        .min_quantum(quantum)
+        .max_quantum(quantum * 2)
+        .quantum_limit(quantum * 4)
+        .force_quantum(quantum)
+        .force_rate(settings.sample_rate)
+        .property("default.clock.source", clock_source)
+        .property("settings.check-quantum", false)
+        .property("settings.check-rate", false)
+        .property("settings.check-quantum-limit", false)
+        .property("settings.check-quantum-floor", false)
+        .property("mem.warn-mlock", !memory_lock)
+        .property("mem.allow-mlock", memory_lock)
+        .property("resample.quality", resampler_quality)
+        .property("cpu.zero.denormals", true)
+        // Global default; the ALSA device rule sets the same timeout
+        .property("session.suspend-timeout-seconds", suspend_timeout)
+        .property("stream.dont-remix", disable_remixing)
+        .property("stream.dont-resample", disable_resampling)
+        .property("link.max-buffers", 64)
+        .property("core.daemon", true)
+        // Read back to verify the config is loaded
+        .property("pro-audio-config.rate", settings.sample_rate)
+        .property("pro-audio-config.quantum", quantum)
+        .property("pro-audio-config.version", CONFIG_FORMAT_VERSION)
+        .module(rt_module(nice_level, rt_prio))
+        .object(
+            PipeWireObject::null_sink(
+                &format!("quantum-test-{}", quantum),
+                &format!(
+                    "Quantum Test: {} samples @ {}Hz",
+                    quantum, settings.sample_rate
+                ),
+            )
+            .arg("audio.rate", settings.sample_rate)
+            .arg("audio.channels", channel_positions.len())
+            .arg("audio.position", channel_positions)
+            .arg("audio.format", format!("S{}LE", settings.bit_depth))
+            .arg("priority.session", 9999)
+            .arg("node.quantum", quantum)
+            .arg("node.lock-quantum", true),
+        )
+        .render()
+}
 
-        let content =
-            fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+/// High-priority fragment written by the basic apply
+fn generate_pipewire_fragment(settings: &AudioSettings) -> String {
+    PipeWireConfig::new()
+        .comment("Pro Audio Config - High Priority Settings")
+        .comment("This file overrides default PipeWire settings")
+        .clock_rate(settings.sample_rate)
+        .clock_quantum(settings.buffer_size)
+        .allowed_rates(&settings.effective_allowed_rates())
+        // Disable rate/quantum checking so our settings are applied
+        .property("settings.check-quantum", false)
+        .property("settings.check-rate", false)
+        .force_quantum(settings.buffer_size)
+        .force_rate(settings.sample_rate)
+        // Layout the device rules are written with, for reading it back
+        .property("pro-audio-config.channels", settings.channels)
+        .property("pro-audio-config.position", settings.channel_positions())
+        .property("pro-audio-config.version", CONFIG_FORMAT_VERSION)
+        .module(rt_module(-11, 88))
+        .render()
+}
 
-        // Try multiple possible patterns for clock rate and quantum
-        let patterns = [
-            (
-                "#default.clock.rate          = 48000",
-                format!("default.clock.rate          = {}", settings.sample_rate),
-            ),
-            (
-                "#default.clock.rate          = 192000",
-                format!("default.clock.rate          = {}", settings.sample_rate),
-            ),
-            (
-                "default.clock.rate          = 48000",
-                format!("default.clock.rate          = {}", settings.sample_rate),
-            ),
-            (
-                "#default.clock.quantum       = 1024",
-                format!("default.clock.quantum       = {}", settings.buffer_size),
-            ),
-            (
-                "#default.clock.quantum       = 8192",
-                format!("default.clock.quantum       = {}", settings.buffer_size),
-            ),
-            (
-                "default.clock.quantum       = 1024",
-                format!("default.clock.quantum       = {}", settings.buffer_size),
-            ),
-            // Also update the allowed rates
-            (
-                "default.clock.allowed-rates = \\[ 44100, 48000, 96000, 192000 \\]",
-                format!("default.clock.allowed-rates = [ {} ]", settings.sample_rate),
-            ),
-        ];
+/// Creates a PipeWire configuration fragment file with proper privilege handling
+fn create_pipewire_fragment(
+    settings: &AudioSettings,
+    system_wide: bool,
+    batch: &mut PrivilegedBatch,
+) -> Result<(), AudioConfigError> {
+    let config_content = generate_pipewire_fragment(settings);
 
-        let mut updated_content = content.clone();
-        for (pattern, replacement) in &patterns {
-            updated_content = updated_content.replace(pattern, replacement);
-        }
+    // Try multiple standard locations - use higher number for higher priority
+    let config_dirs = vec![pipewire_conf_d(system_wide)];
 
-        // Only write if changes were made
-        if updated_content != content {
-            // Backup original
-            let backup_path = format!("{}.backup", path);
-            if system_wide {
-                execute_with_privileges("cp", &[path, &backup_path])?;
-                write_config_with_privileges(path, &updated_content)?;
-            } else {
-                fs::copy(path, &backup_path)
-                    .map_err(|e| format!("Failed to backup {}: {}", path, e))?;
-                fs::write(path, updated_content)
-                    .map_err(|e| format!("Failed to write {}: {}", path, e))?;
-            }
+    // Clean up ALL conflicting configs before creating exclusive
+    cleanup_audio_configs(system_wide, "pipewire", "conflicting")?;
 
-            println!("Modified main config: {} (backup: {})", path, backup_path);
-        } else {
-            println!("No changes needed for: {}", path);
+    for dir in &config_dirs {
+        let config_path = format!("{}/{}", dir, HIGH_PRIORITY_CONFIG_FILE);
+
+        // Skip backup for system directories to avoid permission issues
+        // The backup is just a safety measure, not critical
+        if !dir.starts_with("/etc/")
+            && let Err(e) = backup_current_config(dir)
+        {
+            log::info!("Could not backup config (non-fatal): {}", e);
+        }
+
+        // Write file with proper privilege handling
+        if let Err(e) = batch.write(&config_path, &config_content) {
+            log::warn!(
+                "Failed to write to {}: {}, trying next location...",
+                config_path,
+                e
+            );
+            continue;
         }
 
+        log::info!("✓ PipeWire config created: {}", config_path);
+
         return Ok(());
     }
 
-    Err("No main PipeWire config file found".to_string())
+    Err(AudioConfigError::Other(
+        "Failed to write PipeWire configuration to any location".to_string(),
+    ))
 }
 
-/// Modify the main pipewire.conf to change quantum-floor
-fn modify_main_pipewire_quantum_floor(
+/// Writes the pipewire-pulse fragment so Pulse clients accept the chosen buffer.
+/// Without it pipewire-pulse keeps its own pulse.min.quantum and clamps them.
+fn create_pipewire_pulse_fragment(
     settings: &AudioSettings,
     system_wide: bool,
+    batch: &mut PrivilegedBatch,
 ) -> Result<(), String> {
-    println!("=== NUCLEAR OPTION: Modifying main pipewire.conf ===");
-
-    // Use consistent String type
-    let main_conf_path = if system_wide {
-        "/etc/pipewire/pipewire.conf".to_string()
-    } else {
-        let username = whoami::username();
-        format!("/home/{}/.config/pipewire/pipewire.conf", username)
-    };
-
-    if !Path::new(&main_conf_path).exists() {
-        println!("Main config not found at: {}", main_conf_path);
-        return Ok(()); // Not an error, just skip
-    }
+    let config_content = generate_pipewire_pulse_config(settings);
 
-    // Read the file
-    let content = fs::read_to_string(&main_conf_path)
-        .map_err(|e| format!("Failed to read {}: {}", main_conf_path, e))?;
+    let config_dir = pipewire_pulse_conf_d(system_wide);
+    batch.create_dir_all(&config_dir)?;
 
-    // Replace or add quantum-floor setting
-    let mut updated = false;
-    let mut new_content = String::new();
+    let config_path = format!("{}/{}", config_dir, PULSE_CONFIG_FILE);
+    batch.write(&config_path, &config_content)?;
 
-    for line in content.lines() {
-        let trimmed = line.trim();
+    log::info!("✓ pipewire-pulse config created: {}", config_path);
+    Ok(())
+}
 
-        // Look for quantum-floor setting
-        if trimmed.contains("default.clock.quantum-floor") {
-            // Replace it with our value
-            new_content.push_str(&format!(
-                "    default.clock.quantum-floor = {}\n",
-                settings.buffer_size
-            ));
-            println!("✓ Replaced quantum-floor in main config");
-            updated = true;
-        } else if trimmed.contains("default.clock.min-quantum") {
-            // Also update min-quantum
-            new_content.push_str(&format!(
-                "    default.clock.min-quantum = {}\n",
-                settings.buffer_size
-            ));
-            updated = true;
-        } else {
-            new_content.push_str(line);
-            new_content.push('\n');
-        }
-    }
+fn generate_pipewire_pulse_config(settings: &AudioSettings) -> String {
+    let fraction = format!("{}/{}", settings.buffer_size, settings.sample_rate);
 
-    // If didn't find quantum-floor, adds it
-    if !updated {
-        // Find where to insert it (after other quantum settings)
-        let mut final_content = String::new();
-        let mut inserted = false;
+    format!(
+        r#"# Pro Audio Config - pipewire-pulse Quantum Limits
+# Lets PulseAudio clients follow the buffer chosen for native PipeWire clients
+
+pulse.properties = {{
+    pulse.min.req = {fraction}
+    pulse.default.req = {fraction}
+    pulse.min.frag = {fraction}
+    pulse.min.quantum = {fraction}
+}}
+"#
+    )
+}
 
-        for line in content.lines() {
-            final_content.push_str(line);
-            final_content.push('\n');
+/// Drops a pulse fragment left by an earlier apply so stale limits don't linger
+fn remove_pipewire_pulse_fragment(
+    system_wide: bool,
+    batch: &mut PrivilegedBatch,
+) -> Result<(), String> {
+    let scope = if system_wide {
+        ConfigScope::System
+    } else {
+        ConfigScope::User
+    };
 
-            // Insert after finding quantum settings section
-            if !inserted && line.trim().contains("default.clock.quantum") {
-                final_content.push_str(&format!(
-                    "    default.clock.quantum-floor = {}\n",
-                    settings.buffer_size
-                ));
-                final_content.push_str(&format!(
-                    "    default.clock.min-quantum = {}\n",
-                    settings.buffer_size
-                ));
-                inserted = true;
-                println!("✓ Added quantum-floor to main config");
-            }
+    for path in managed_config_paths(scope) {
+        if !is_config_file_named(&path, PULSE_CONFIG_FILE) || !path.exists() {
+            continue;
         }
 
-        new_content = final_content;
+        let file = path.to_string_lossy();
+        batch.remove(&file)?;
+        log::info!("✓ Removed: {}", file);
     }
 
-    // Backup and write
-    let backup_path = format!("{}.backup-pro-audio", main_conf_path);
-    fs::copy(&main_conf_path, &backup_path).map_err(|e| format!("Failed to backup: {}", e))?;
-
-    fs::write(&main_conf_path, new_content).map_err(|e| format!("Failed to write: {}", e))?;
-
-    println!(
-        "✓ Modified main config: {} (backup: {})",
-        main_conf_path, backup_path
-    );
     Ok(())
 }
 
-// ----------------------------------------------------------------------------
-// Core Audio Application Functions
-// ----------------------------------------------------------------------------
-
-/// Main function to apply audio settings with authentication
-fn apply_audio_settings_with_auth(
-    settings: AudioSettings,
-    stream_type: &str,
-) -> Result<(), String> {
-    println!(
-        "Applying {} audio settings with authentication: {}Hz/{}bit/{} samples",
-        stream_type, settings.sample_rate, settings.bit_depth, settings.buffer_size
-    );
+/// Writes or removes the pipewire-pulse fragment depending on the preference.
+/// Returns advisory warnings for the apply outcome.
+fn sync_pipewire_pulse_quantum(
+    settings: &AudioSettings,
+    system_wide: bool,
+    batch: &mut PrivilegedBatch,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
 
-    // Try PipeWire configuration first
-    match update_audio_settings(&settings, true, None) {
-        Ok(()) => {
-            println!("✓ Applied via PipeWire configuration");
-            println!("✓ Settings applied successfully - monitoring tab should refresh");
-            return Ok(());
+    if SYNC_PULSE_QUANTUM.load(Ordering::SeqCst) {
+        if let Err(e) = create_pipewire_pulse_fragment(settings, system_wide, batch) {
+            warnings.push(format!("Could not write pipewire-pulse limits: {}", e));
         }
-        Err(e) => {
-            println!("PipeWire config failed ({}), trying WirePlumber...", e);
+    } else {
+        if let Err(e) = remove_pipewire_pulse_fragment(system_wide, batch) {
+            log::info!("Could not remove old pipewire-pulse config: {}", e);
+        }
+        if let Some(clamped) = pulse_clamped_quantum(settings) {
+            warnings.push(format!(
+                "PulseAudio clients (browsers, games) will be clamped to {} samples by \
+                 pulse.min.quantum; enable \"Apply Buffer to PulseAudio Clients\" to lift it",
+                clamped
+            ));
         }
     }
 
-    // Fallback to WirePlumber device-specific configuration
-    apply_wireplumber_device_config(&settings, stream_type, None)
+    warnings
 }
 
-/// Main function to apply audio settings using multiple configuration approaches with fallbacks
-pub fn update_audio_settings(
+/// Creates a WirePlumber configuration file (updated format for versions >= 0.5)
+fn create_wireplumber_config_new(
     settings: &AudioSettings,
     system_wide: bool,
     actual_device_name: Option<&str>,
-) -> Result<(), String> {
-    println!(
-        "Applying {} audio settings: {}Hz/{}bit/{} samples",
-        if system_wide { "system-wide" } else { "user" },
-        settings.sample_rate,
-        settings.bit_depth,
-        settings.buffer_size
-    );
-
-    // Try multiple configuration approaches in order of preference
-    let mut success = false;
+    batch: &mut PrivilegedBatch,
+) -> Result<(), AudioConfigError> {
+    let config_dirs = if system_wide {
+        vec!["/etc/wireplumber/wireplumber.conf.d".to_string()]
+    } else {
+        vec![format!(
+            "{}/wireplumber/wireplumber.conf.d",
+            user_config_dir()
+        )]
+    };
 
-    // Clean up conflicting configs first
-    if system_wide {
-        cleanup_system_pipewire_configs()?;
+    // Get the actual device pattern
+    let device_pattern = if let Some(name) = actual_device_name {
+        name.to_string()
+    } else if settings.device_id == "default" {
+        "~alsa.*".to_string()
     } else {
-        cleanup_user_pipewire_configs()?;
-    }
+        settings.device_id.clone()
+    };
 
-    // Approach 1: Create PipeWire config fragment
-    match create_pipewire_fragment(settings, system_wide) {
-        Ok(_) => {
-            println!("✓ Successfully created PipeWire config fragment");
-            success = true;
-        }
-        Err(e) => {
-            println!(
-                "PipeWire fragment approach failed: {}, trying next approach...",
+    for dir in &config_dirs {
+        // Create directory if it doesn't exist
+        batch.create_dir_all(dir)?;
+
+        let config_path = format!("{}/99-pro-audio.conf", dir);
+
+        let content = WirePlumberConfig::new(&["monitor.alsa.rules"])
+            .rule(
+                WirePlumberRule::matching("node.name", &device_pattern)
+                    .set("audio.rate", settings.sample_rate)
+                    .set("audio.allowed-rates", vec![settings.sample_rate])
+                    .set("audio.channels", settings.channels)
+                    .set("audio.position", settings.channel_positions())
+                    .set("api.alsa.period-size", settings.hardware_period_size()),
+            )
+            .render();
+
+        if let Err(e) = batch.write(&config_path, &content) {
+            log::warn!(
+                "Failed to write to {}: {}, trying next location...",
+                config_path,
                 e
             );
+            continue;
         }
+
+        log::info!(
+            "✓ Created WirePlumber config: {} for device: {}",
+            config_path,
+            device_pattern
+        );
+        return Ok(());
     }
 
-    if !success {
-        // Approach 2: Create WirePlumber config with specific device
-        match create_wireplumber_config_new(settings, system_wide, actual_device_name) {
-            Ok(_) => {
-                println!("✓ Successfully created WirePlumber config");
-                success = true;
-            }
-            Err(e) => {
-                println!(
-                    "WirePlumber approach failed: {}, trying final approach...",
-                    e
-                );
+    Err(AudioConfigError::Other(
+        "No WirePlumber config directory found".to_string(),
+    ))
+}
 
-                // Approach 3: Modify main pipewire.conf as fallback
-                match modify_main_pipewire_config(settings, system_wide) {
-                    Ok(_) => {
-                        println!("✓ Successfully modified main PipeWire config");
-                        success = true;
-                    }
-                    Err(e) => {
-                        return Err(format!("All configuration approaches failed: {}", e));
-                    }
-                }
-            }
-        }
-    }
+/// Create WirePlumber configuration for hardware device rules
+fn create_wireplumber_device_config(
+    settings: &AudioSettings,
+    system_wide: bool,
+    priority_driver: u32,
+    priority_session: u32,
+    suspend_timeout: u32,
+    batch: &mut PrivilegedBatch,
+) -> Result<(), String> {
+    log::info!("Creating WirePlumber device configuration...");
+    log::info!(
+        "  Priority: driver {}, session {}",
+        priority_driver,
+        priority_session
+    );
+    log::info!("  Suspend timeout: {} s", suspend_timeout);
 
-    if success {
-        // Wait a bit for the config to be written
-        std::thread::sleep(std::time::Duration::from_millis(500));
-        restart_audio_services(false, system_wide)?;
-        println!("✓ Audio services restarted successfully");
+    let wireplumber_dirs = vec![wireplumber_conf_d(system_wide)];
 
-        // Verify the settings were applied
-        verify_settings_applied(settings)?;
+    let (audio_format, _) = resolve_audio_format(settings, &settings.device_id, "output");
+    let wireplumber_config = generate_wireplumber_professional_device_config(
+        settings,
+        audio_format,
+        priority_driver,
+        priority_session,
+        suspend_timeout,
+    );
 
-        Ok(())
-    } else {
-        Err("Failed to apply audio settings through any method".to_string())
-    }
-}
+    for dir in &wireplumber_dirs {
+        let config_path = format!("{}/{}", dir, DEVICE_RULES_CONFIG_FILE);
 
-// ----------------------------------------------------------------------------
-// Privilege & System Functions
-// ----------------------------------------------------------------------------
+        // Create directory if needed
+        if let Err(e) = batch.create_dir_all(dir) {
+            log::info!("Could not create WirePlumber directory {}: {}", dir, e);
+            continue;
+        }
 
-/// Create directory with proper privilege escalation for system paths
-fn create_dir_all_with_privileges(path: &str) -> Result<(), String> {
-    if path.starts_with("/etc/") {
-        // For system paths, check if directory already exists first
-        if Path::new(path).exists() {
-            return Ok(());
+        // Write the WirePlumber config
+        if let Err(e) = batch.write(&config_path, &wireplumber_config) {
+            log::warn!("Failed to write WirePlumber config {}: {}", config_path, e);
+            continue;
         }
-        // System path - need privileges
-        execute_with_privileges("mkdir", &["-p", path])?;
-    } else {
-        // User path - no privileges needed
-        fs::create_dir_all(path).map_err(|e| format!("Failed to create directory: {}", e))?;
+
+        log::info!("✓ WirePlumber device config created: {}", config_path);
+        return Ok(());
     }
 
+    log::info!("Could not create WirePlumber config, using defaults");
     Ok(())
 }
 
-/// Execute a command with privilege escalation for system-wide changes
-fn execute_with_privileges(command: &str, args: &[&str]) -> Result<(), String> {
-    println!("Requesting administrator privileges for system-wide changes...");
+/// ALSA device rule of the professional apply. WirePlumber picks the device
+/// with the highest priority.driver as the graph driver.
+fn generate_wireplumber_professional_device_config(
+    settings: &AudioSettings,
+    audio_format: &str,
+    priority_driver: u32,
+    priority_session: u32,
+    suspend_timeout: u32,
+) -> String {
+    // WirePlumber uses JSON for its configuration (version 0.5+)
+    WirePlumberConfig::new(&["alsa-monitor", "rules"])
+        .rule(
+            WirePlumberRule::matching("node.name", "~alsa.*")
+                .set("api.alsa.period-size", settings.hardware_period_size())
+                .set("api.alsa.period-num", 2)
+                .set("api.alsa.headroom", 4096)
+                .set("api.alsa.disable-batch", true)
+                .set("api.alsa.use-acp", true)
+                .set("api.alsa.disable-mmap", false)
+                .set("api.alsa.disable-tsched", false)
+                .set("audio.format", audio_format)
+                .set("audio.rate", settings.sample_rate)
+                .set("audio.allowed-rates", vec![settings.sample_rate])
+                .set("audio.channels", settings.channels)
+                .set("audio.position", settings.channel_positions())
+                .set("priority.driver", priority_driver)
+                .set("priority.session", priority_session)
+                .set("session.suspend-timeout-seconds", suspend_timeout),
+        )
+        .render()
+}
 
-    // Try pkexec first (common on modern Linux systems)
-    let status = Command::new("pkexec")
-        .arg(command)
-        .args(args)
-        .status()
-        .map_err(|e| {
-            format!(
-                "Failed to execute with pkexec: {}. Make sure pkexec is available.",
-                e
-            )
-        })?;
+// ----------------------------------------------------------------------------
+// Exclusive Mode Functions
+// ----------------------------------------------------------------------------
 
-    if status.success() {
-        Ok(())
-    } else {
-        // Fallback to sudo
-        println!("pkexec failed, trying sudo...");
-        let status = Command::new("sudo")
-            .arg(command)
-            .args(args)
-            .status()
-            .map_err(|e| {
-                format!(
-                    "Failed to execute with sudo: {}. Please run with administrator privileges.",
-                    e
-                )
-            })?;
+/// Apply exclusive mode configuration
+fn apply_exclusive_mode_settings(
+    direct_hardware: bool,
+    low_latency: bool,
+    buffer_size: u32,
+    sample_rate: u32,
+    channel_positions: &[String],
+    device_pattern: &str,
+    apps: &[ExclusiveApp],
+) -> Result<(), AudioConfigError> {
+    log::info!("Configuring exclusive audio access mode...");
+
+    // Clean up conflicting configs before creating exclusive, keeping the
+    // combined, virtual, JACK and pulse fragments the user set up separately
+    for config_type in ["pipewire", "wireplumber"] {
+        cleanup_audio_configs(false, config_type, "conflicting")?;
+        cleanup_audio_configs(false, config_type, "exclusive")?;
+    }
 
-        if status.success() {
+    // First, try the modern PipeWire exclusive mode approach
+    match create_pipewire_exclusive_config(
+        direct_hardware,
+        low_latency,
+        buffer_size,
+        sample_rate,
+        channel_positions,
+        device_pattern,
+        apps,
+    ) {
+        Ok(()) => {
+            log::info!("✓ PipeWire exclusive mode configured successfully");
+            restart_audio_services(false, true)?;
+            return Ok(());
+        }
+        Err(e) => {
+            log::info!("PipeWire exclusive mode failed: {}, trying fallback...", e);
+        }
+    }
+
+    // Fallback: Use WirePlumber configuration for exclusive access
+    match create_wireplumber_exclusive_config(
+        direct_hardware,
+        low_latency,
+        buffer_size,
+        sample_rate,
+        channel_positions,
+        apps,
+    ) {
+        Ok(()) => {
+            log::info!("✓ WirePlumber exclusive mode configured successfully");
+            restart_audio_services(false, true)?;
             Ok(())
-        } else {
-            Err(
-                "Privilege escalation failed. Please run as root or configure sudo/pkexec."
-                    .to_string(),
-            )
         }
+        Err(e) => Err(format!("All exclusive mode approaches failed: {}", e).into()),
     }
 }
 
-/// Write configuration file with proper privilege escalation for system paths
-fn write_config_with_privileges(config_path: &str, content: &str) -> Result<(), String> {
-    if config_path.starts_with("/etc/") {
-        // System path - need privileges
-        let temp_file = format!("/tmp/pro-audio-config-{}", std::process::id());
-
-        // Write to temp file first
-        fs::write(&temp_file, content).map_err(|e| format!("Failed to write temp file: {}", e))?;
+/// Enhanced exclusive mode with device capability checking
+fn apply_enhanced_exclusive_mode_settings(
+    direct_hardware: bool,
+    low_latency: bool,
+    buffer_size: u32,
+    sample_rate: u32,
+    channel_positions: &[String],
+    device_pattern: &str,
+    apps: &[ExclusiveApp],
+) -> Result<(), AudioConfigError> {
+    log::info!("Configuring enhanced exclusive audio access mode...");
 
-        // Copy to system location with privileges
-        execute_with_privileges("cp", &[&temp_file, config_path])?;
+    // Check device suitability for exclusive mode
+    if let Ok(devices) = crate::audio::detect_high_performance_devices() {
+        let target_device = if device_pattern == "default" {
+            devices.first()
+        } else {
+            devices.iter().find(|d| d.id.contains(device_pattern))
+        };
 
-        // Clean up temp file
-        let _ = fs::remove_file(&temp_file);
+        if let Some(device) = target_device {
+            if !crate::audio::is_device_suitable_for_exclusive_mode(device) {
+                log::warn!("Selected device may not be ideal for exclusive mode");
+            }
 
-        println!("✓ System config created with privileges: {}", config_path);
-    } else {
-        // User path - no privileges needed
-        if let Some(parent) = Path::new(config_path).parent() {
-            fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+            // Get device capabilities
+            if let Ok(capabilities) = crate::audio::get_device_capabilities(&device.id) {
+                if !capabilities.buffer_sizes.contains(&buffer_size) {
+                    log::warn!(
+                        "Buffer size {} may not be optimal for {}",
+                        buffer_size,
+                        device.name
+                    );
+                }
+                if !capabilities.sample_rates.contains(&sample_rate) {
+                    log::warn!(
+                        "Sample rate {} may not be supported by {}",
+                        sample_rate,
+                        device.name
+                    );
+                }
+            }
         }
-
-        fs::write(config_path, content)
-            .map_err(|e| format!("Failed to write config file: {}", e))?;
     }
 
-    Ok(())
+    // Proceed with existing exclusive mode configuration
+    apply_exclusive_mode_settings(
+        direct_hardware,
+        low_latency,
+        buffer_size,
+        sample_rate,
+        channel_positions,
+        device_pattern,
+        apps,
+    )
 }
 
-// ----------------------------------------------------------------------------
-// Wireplumber configuration
-// ----------------------------------------------------------------------------
-
-/// Applies device-specific audio settings via a WirePlumber SPA-JSON configuration fragment.
-// Update create_wireplumber_config_new to accept device name
-pub fn apply_wireplumber_device_config(
-    settings: &AudioSettings,
-    stream_type: &str,
-    actual_device_name: Option<&str>,
+/// Create PipeWire configuration for exclusive mode with application targeting.
+/// `device_pattern` is the device id picked in the Advanced tab or "default".
+fn create_pipewire_exclusive_config(
+    direct_hardware: bool,
+    low_latency: bool,
+    buffer_size: u32,
+    sample_rate: u32,
+    channel_positions: &[String],
+    device_pattern: &str,
+    apps: &[ExclusiveApp],
 ) -> Result<(), String> {
-    println!("Applying WirePlumber device configuration...");
-
-    // Use the actual device name if provided, otherwise fall back to the device_id
-    let device_pattern = if let Some(real_name) = actual_device_name {
-        println!("Using real device name: {}", real_name);
-        real_name.to_string()
-    } else if settings.device_id == "default" {
-        // Try to detect the actual default device
-        match if stream_type == "output" {
-            crate::audio::detect_output_audio_device()
-        } else {
-            crate::audio::detect_input_audio_device()
-        } {
-            Ok(device_info) => {
-                if let Some(name) = crate::audio::extract_actual_device_name(&device_info) {
-                    println!("Detected actual device: {}", name);
-                    name
-                } else {
-                    "~alsa.*".to_string()
-                }
-            }
-            Err(_) => "~alsa.*".to_string(),
-        }
-    } else {
-        // Use the provided device_id directly
-        settings.device_id.clone()
-    };
-
-    // Generate SPA-JSON for WirePlumber >= 0.5
-    let config_content = generate_wireplumber_device_config(settings, stream_type, &device_pattern);
+    let config_dir = format!("{}/pipewire/pipewire.conf.d", user_config_dir());
+    let config_path = format!("{}/{}", config_dir, EXCLUSIVE_CONFIG_FILE);
 
-    let username = whoami::username();
-    // CRITICAL: Use the correct path and extension for WirePlumber >= 0.5
-    let config_path = if stream_type == "output" {
-        format!(
-            "/home/{}/.config/wireplumber/wireplumber.conf.d/99-pro-audio-output.conf",
-            username
-        )
-    } else {
-        format!(
-            "/home/{}/.config/wireplumber/wireplumber.conf.d/99-pro-audio-input.conf",
-            username
-        )
-    };
+    let audio_format = if low_latency { "S32LE" } else { "S24LE" };
 
-    // Create the config directory if it doesn't exist
-    if let Some(parent) = Path::new(&config_path).parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create config directory: {}", e))?;
-    }
+    // Clean up ALL conflicting configs before creating exclusive
+    cleanup_audio_configs(false, "pipewire", "conflicting")?;
+    cleanup_audio_configs(false, "wireplumber", "conflicting")?;
 
-    // Write the SPA-JSON configuration file
-    fs::write(&config_path, config_content)
-        .map_err(|e| format!("Failed to write WirePlumber config: {}", e))?;
+    let target_device = exclusive_target_device(device_pattern);
 
-    println!(
-        "✓ WirePlumber SPA-JSON configuration created: {} for device: {}",
-        config_path, device_pattern
+    let config_content = generate_pipewire_exclusive_config(
+        &target_device,
+        buffer_size,
+        sample_rate,
+        audio_format,
+        channel_positions,
+        apps,
     );
 
-    // Restart services to apply the new config
-    restart_audio_services(true, false)?;
+    // Backup current config before writing
+    if let Err(e) = backup_current_config(&config_dir) {
+        log::info!("Could not backup config (non-fatal): {}", e);
+    }
 
-    println!("✓ Audio services restarted successfully");
+    write_config_with_privileges(&config_path, &config_content)?;
+    log::info!("✓ Exclusive mode configuration created for:");
+    for app in apps {
+        log::info!("  Application: {}", app.label());
+    }
+    log::info!("  Device: {}", target_device);
+    log::info!("  Settings: {}Hz/{} samples", sample_rate, buffer_size);
+    log::info!("  Config: {}", config_path);
     Ok(())
 }
 
-/// Generate device-specific WirePlumber configuration
-fn generate_wireplumber_device_config(
-    settings: &AudioSettings,
-    stream_type: &str,
-    device_pattern: &str,
-) -> String {
-    let audio_format = match settings.bit_depth {
-        16 => "S16LE",
-        24 => "S24LE",
-        32 => "S32LE",
-        _ => "S24LE",
-    };
+/// Node name the exclusive rules target: the node of the selected device, or
+/// the current default output for "default" and devices no longer listed
+fn exclusive_target_device(device_pattern: &str) -> String {
+    if device_pattern != "default" {
+        let devices = crate::audio::detect_all_audio_devices().unwrap_or_default();
+        if let Some(name) = exclusive_target_node(device_pattern, &devices) {
+            log::info!("Targeting exclusive mode to selected device: {}", name);
+            return name;
+        }
+        log::warn!(
+            "Device {} not found, targeting the default output",
+            device_pattern
+        );
+    }
 
-    let direction = if stream_type == "output" {
-        "sink"
-    } else {
-        "source"
-    };
+    match crate::audio::detect_output_audio_device() {
+        Ok(device_info) => {
+            if let Some(device_name) = crate::audio::extract_actual_device_name(&device_info) {
+                log::info!("Targeting exclusive mode to device: {}", device_name);
+                device_name
+            } else {
+                log::warn!("Could not extract device name, using default pattern");
+                "alsa.*".to_string()
+            }
+        }
+        Err(e) => {
+            log::warn!(
+                "Could not detect default device: {}, using default pattern",
+                e
+            );
+            "alsa.*".to_string()
+        }
+    }
+}
 
-    format!(
-        r#"{{
-  "alsa-monitor": {{
-    "rules": [
-      {{
-        "matches": [
-          {{
-            "node.name": "{}"
-          }}
+fn exclusive_target_node(device_pattern: &str, devices: &[AudioDevice]) -> Option<String> {
+    devices
+        .iter()
+        .find(|device| device.id == device_pattern)
+        .map(|device| device.name.clone())
+}
+
+/// Builds the exclusive-mode fragment: one device rule plus one stream rule per app
+fn generate_pipewire_exclusive_config(
+    target_device: &str,
+    buffer_size: u32,
+    sample_rate: u32,
+    audio_format: &str,
+    channel_positions: &[String],
+    apps: &[ExclusiveApp],
+) -> String {
+    let target_apps = apps
+        .iter()
+        .map(|app| quote_string(&app.name))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let app_rules: String = apps
+        .iter()
+        .map(|app| {
+            format!(
+                r#"    {{
+        matches = [
+            {{
+                {}
+            }}
         ],
-        "actions": {{
-          "update-props": {{
-            "audio.format": "{}",
-            "audio.rate": {},
-            "audio.allowed-rates": [ {} ],
-            "api.alsa.period-size": {},
-            "api.alsa.period-num": 2,
-            "api.alsa.headroom": 8192,
-            "node.description": "Pro Audio {} Device",
-            "priority.session": 1500,
-            "priority.driver": 1500
-          }}
+        actions = {{
+            update-props = {{
+                # Keep this client on the exclusive device and quantum
+                target.object = "{}"
+                node.latency = "{}/{}"
+                node.lock-quantum = true
+                priority.session = 1000
+                pro-audio.exclusive-app = "{}"
+            }}
         }}
-      }}
-    ]
-  }}
-}}"#,
-        device_pattern,
-        audio_format,
-        settings.sample_rate,
-        settings.sample_rate,
-        settings.buffer_size,
-        stream_type
-    )
-}
+    }}
+"#,
+                app.match_rule(),
+                target_device,
+                buffer_size,
+                sample_rate,
+                app.sanitized_name(),
+            )
+        })
+        .collect();
+    let (rt_soft, rt_hard) = rt_time_limits();
 
-// ----------------------------------------------------------------------------
-// Service Management Functions
-// ----------------------------------------------------------------------------
+    format!(
+        r#"# Pro Audio Config - Exclusive Mode
+{}# This configuration enables ASIO-like exclusive mode for the applications above
 
-/// Aggressive restart that kills everything and forces restart
-#[allow(dead_code)]
-fn aggressive_restart_audio_services(system_wide: bool) -> Result<(), String> {
-    println!("=== AGGRESSIVE AUDIO SERVICE RESTART ===");
+context.properties = {{
+    # CRITICAL: Override quantum-floor to allow our buffer size
+    default.clock.quantum-floor = {}
+    default.clock.min-quantum = {}
+    default.clock.max-quantum = {}
 
-    let username = whoami::username();
+    # Core settings
+    default.clock.rate = {}
+    default.clock.quantum = {}
+    default.clock.allowed-rates = [ {} ]
 
-    // Step 1: Kill all audio processes
-    println!("Step 1: Killing all audio processes...");
-    let _ = Command::new("pkill").arg("-9").arg("pipewire").status();
-    let _ = Command::new("pkill").arg("-9").arg("wireplumber").status();
-    let _ = Command::new("pkill").arg("-9").arg("pulseaudio").status();
+    # Force settings to be used
+    default.clock.force-quantum = {}
+    default.clock.force-rate = {}
 
-    std::thread::sleep(std::time::Duration::from_secs(1));
+    # IMPORTANT: Disable quantum checking
+    settings.check-quantum = false
+    settings.check-rate = false
+    settings.check-quantum-limit = false
+    settings.check-quantum-floor = false
 
-    // Step 2: Restart with systemd
-    println!("Step 2: Restarting with systemd...");
-    if system_wide {
-        let status = Command::new("sudo")
-            .args([
-                "-u",
-                &username,
-                "systemctl",
-                "--user",
-                "restart",
-                "pipewire",
-                "pipewire-pulse",
-                "wireplumber",
-            ])
+    # Additional exclusive mode settings
+    stream.dont-remix = true
+    stream.dont-resample = true
+    mem.allow-mlock = true
+    session.suspend-timeout-seconds = 0
+
+    # Debug properties to verify our config is loaded
+    pro-audio-config.rate = {}
+    pro-audio-config.quantum = {}
+    pro-audio-config.version = "{}"
+    pro-audio-config.exclusive = true
+    pro-audio-config.target-apps = [ {} ]
+    pro-audio-config.target-device = "{}"
+}}
+
+# Device-specific rules with CORRECT JSON SYNTAX
+# NOTE: PipeWire uses JSON syntax, NOT Lua table syntax
+context.rules = [
+    {{
+        matches = [
+            {{
+                node.name = "~{}"
+            }}
+        ],
+        actions = {{
+            update-props = {{
+                # Override device properties for exclusive access
+                api.alsa.period-size = {}
+                api.alsa.period-num = 2
+                api.alsa.disable-batch = true
+                api.alsa.use-acp = false
+                audio.rate = {}
+                audio.allowed-rates = [ {} ]
+                audio.format = "{}"
+                audio.channels = {}
+                audio.position = [ {} ]
+                # Device priority
+                priority.driver = 1000
+                priority.session = 1000
+                # Disable power management
+                device.suspend-on-idle = false
+                node.suspend-on-idle = false
+                # Force our quantum settings on the device
+                node.quantum = {}
+                node.min-quantum = {}
+                node.max-quantum = {}
+                node.lock-quantum = true
+                # Debug info
+                pro-audio.exclusive = true
+                pro-audio.target-rate = {}
+                pro-audio.target-quantum = {}
+            }}
+        }}
+    }}
+{}]
+
+# Real-time module for better performance
+context.modules = [
+    {{
+        name = libpipewire-module-rt
+        args = {{
+            nice.level = -11
+            rt.prio = 80
+            rt.time.soft = {}
+            rt.time.hard = {}
+        }}
+        flags = [ ifexists nofail ]
+    }}
+]
+"#,
+        // Header with one line per app (1 arg)
+        exclusive_apps_header(apps),
+        // Global quantum settings (3 args)
+        buffer_size,
+        buffer_size,
+        buffer_size * 2,
+        // Core settings (3 args)
+        sample_rate,
+        buffer_size,
+        sample_rate,
+        // Force settings (2 args)
+        buffer_size,
+        sample_rate,
+        // Debug properties (5 args)
+        sample_rate,
+        buffer_size,
+        CONFIG_FORMAT_VERSION,
+        target_apps,
+        target_device,
+        // Device rules (12 args)
+        target_device,
+        buffer_size,
+        sample_rate,
+        sample_rate,
+        audio_format,
+        channel_positions.len(),
+        channel_positions.join(" "),
+        buffer_size,
+        buffer_size,
+        buffer_size * 2,
+        sample_rate,
+        buffer_size,
+        // Application rules (1 arg)
+        app_rules,
+        // Real-time limits (2 args)
+        rt_soft,
+        rt_hard,
+    )
+}
+
+/// Create WirePlumber configuration for exclusive access
+fn create_wireplumber_exclusive_config(
+    direct_hardware: bool,
+    low_latency: bool,
+    buffer_size: u32,
+    sample_rate: u32,
+    channel_positions: &[String],
+    apps: &[ExclusiveApp],
+) -> Result<(), String> {
+    let config_dir = format!("{}/wireplumber/wireplumber.conf.d", user_config_dir());
+    let config_path = format!("{}/{}", config_dir, EXCLUSIVE_CONFIG_FILE);
+
+    let audio_format = if low_latency { "S32LE" } else { "S24LE" };
+    let config_content = generate_wireplumber_exclusive_config(
+        direct_hardware,
+        buffer_size,
+        sample_rate,
+        audio_format,
+        channel_positions,
+        apps,
+    );
+
+    // Backup current config before writing
+    if let Err(e) = backup_current_config(&config_dir) {
+        log::info!("Could not backup config (non-fatal): {}", e);
+    }
+
+    create_dir_all_with_privileges(&config_dir)?;
+    write_config_with_privileges(&config_path, &config_content)?;
+    log::info!(
+        "✓ WirePlumber exclusive configuration created: {}",
+        config_path
+    );
+    Ok(())
+}
+
+/// Exclusive WirePlumber fragment: one rule per app routing it to the exclusive
+/// device, then the low-latency rule for the ALSA devices
+fn generate_wireplumber_exclusive_config(
+    direct_hardware: bool,
+    buffer_size: u32,
+    sample_rate: u32,
+    audio_format: &str,
+    channel_positions: &[String],
+    apps: &[ExclusiveApp],
+) -> String {
+    let app_rules = apps.iter().map(|app| {
+        let (property, value) = app.match_property();
+        WirePlumberRule::matching(property, value)
+            .set(
+                "node.target",
+                format!("exclusive-playback-{}", app.sanitized_name()),
+            )
+            .set("media.class", "Audio/Sink")
+            .set("audio.rate", sample_rate)
+            .set("audio.allowed-rates", vec![sample_rate])
+            .set("audio.format", audio_format)
+            .set("audio.channels", channel_positions.len())
+            .set("audio.position", channel_positions.to_vec())
+            .set("node.quantum", buffer_size)
+            .set("node.pause-on-idle", false)
+            .set("priority.driver", 3000)
+            .set("priority.session", 3000)
+            .set("stream.exclusive", true)
+    });
+
+    // Never suspended while exclusive mode is on
+    let device_rule = WirePlumberRule::matching("device.name", "~alsa.*")
+        .set("audio.rate", sample_rate)
+        .set("audio.allowed-rates", vec![sample_rate])
+        .set("api.alsa.period-size", buffer_size)
+        .set("api.alsa.period-num", 2)
+        .set("api.alsa.headroom", buffer_size / 2)
+        .set("api.alsa.disable-batch", direct_hardware)
+        .set("api.alsa.use-acp", false)
+        .set("priority.driver", 200)
+        .set("session.suspend-timeout-seconds", 0);
+
+    format!(
+        "# Pro Audio Config - Exclusive Mode for WirePlumber\n{}\
+         # This configuration enables ASIO-like exclusive mode for the applications above\n\n{}",
+        exclusive_apps_header(apps),
+        WirePlumberConfig::new(&["override.monitor.alsa.rules"])
+            .rules(app_rules)
+            .rule(device_rule)
+            .render()
+    )
+}
+
+/// Header comment lines listing the apps, read back by `parse_exclusive_apps`
+fn exclusive_apps_header(apps: &[ExclusiveApp]) -> String {
+    apps.iter()
+        .map(|app| {
+            format!(
+                "{} {} (process: {})\n",
+                EXCLUSIVE_APP_MARKER, app.name, app.process_name
+            )
+        })
+        .collect()
+}
+
+/// Recovers the app list from the header written by `exclusive_apps_header`
+fn parse_exclusive_apps(content: &str) -> Vec<ExclusiveApp> {
+    content
+        .lines()
+        .filter_map(|line| line.strip_prefix(EXCLUSIVE_APP_MARKER))
+        .filter_map(|entry| {
+            let (name, process) = entry.trim().rsplit_once(" (process: ")?;
+            Some(ExclusiveApp::new(name, process.trim_end_matches(')')))
+        })
+        .collect()
+}
+
+// ----------------------------------------------------------------------------
+// Combined Output Functions
+// ----------------------------------------------------------------------------
+
+/// module-combine-stream in sink mode with one output stream per member
+fn generate_combine_sink_config(members: &[String]) -> String {
+    let header: String = members
+        .iter()
+        .map(|member| format!("{} {}\n", COMBINE_MEMBER_MARKER, member))
+        .collect();
+    let matches: String = members
+        .iter()
+        .map(|member| {
+            format!(
+                "                        {{ media.class = \"Audio/Sink\" node.name = \"{}\" }}\n",
+                member
+            )
+        })
+        .collect();
+
+    format!(
+        r#"# Pro Audio Config - Combined Output
+# Plays the same audio on several outputs at once
+{}
+context.modules = [
+    {{
+        name = libpipewire-module-combine-stream
+        args = {{
+            combine.mode = sink
+            node.name = "{}"
+            node.description = "Combined Output (Pro Audio Config)"
+            combine.latency-compensate = false
+            combine.props = {{
+                audio.position = [ FL FR ]
+            }}
+            stream.rules = [
+                {{
+                    matches = [
+{}                    ]
+                    actions = {{
+                        create-stream = {{
+                        }}
+                    }}
+                }}
+            ]
+        }}
+    }}
+]
+"#,
+        header, COMBINE_SINK_NAME, matches
+    )
+}
+
+/// Makes `sink_name` the default output. The sink only appears once PipeWire
+/// has finished restarting, so this retries for a few seconds.
+fn set_default_sink(sink_name: &str) -> Result<(), String> {
+    for _ in 0..10 {
+        let status = Command::new("pactl")
+            .args(["set-default-sink", sink_name])
             .status()
-            .map_err(|e| format!("Failed to restart user services: {}", e))?;
+            .map_err(|e| format!("Failed to run pactl: {}", e))?;
 
-        if !status.success() {
-            println!("⚠ Systemd restart failed, trying manual restart...");
+        if status.success() {
+            log::info!("✓ Default output set to {}", sink_name);
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_millis(500));
+    }
+
+    Err(format!(
+        "{} did not appear, default output unchanged",
+        sink_name
+    ))
+}
+
+// ----------------------------------------------------------------------------
+// Virtual Device Functions
+// ----------------------------------------------------------------------------
+
+/// Writes one null sink per name (the name apps and OBS show) and restarts
+/// the audio services. An empty list removes the config. Always user scope.
+pub fn set_virtual_devices(names: &[String]) -> Result<(), AudioConfigError> {
+    // Names that map to the same node.name would clash, keep the first
+    let mut unique: Vec<String> = Vec::new();
+    for name in names.iter().map(|name| name.trim()) {
+        let node_name = virtual_sink_node_name(name);
+        if !name.is_empty()
+            && !unique
+                .iter()
+                .any(|kept| virtual_sink_node_name(kept) == node_name)
+        {
+            unique.push(name.to_string());
+        }
+    }
+
+    let config_path = format!(
+        "{}/pipewire/pipewire.conf.d/{}",
+        user_config_dir(),
+        VIRTUAL_DEVICES_CONFIG_FILE
+    );
+
+    if unique.is_empty() {
+        if !Path::new(&config_path).exists() {
+            return Ok(());
+        }
+        fs::remove_file(&config_path)
+            .map_err(|e| format!("Failed to remove {}: {}", config_path, e))?;
+        log::info!("✓ Removed: {}", config_path);
+    } else {
+        let config_dir = format!("{}/pipewire/pipewire.conf.d", user_config_dir());
+        create_dir_all_with_privileges(&config_dir)?;
+        write_config_with_privileges(&config_path, &generate_virtual_devices_config(&unique))?;
+        log::info!("✓ Virtual devices config created: {}", config_path);
+    }
+
+    restart_audio_services(false, false)?;
+    Ok(())
+}
+
+/// Names of the virtual devices in the user config, empty when there are none
+pub fn virtual_devices() -> Vec<String> {
+    let config_path = format!(
+        "{}/pipewire/pipewire.conf.d/{}",
+        user_config_dir(),
+        VIRTUAL_DEVICES_CONFIG_FILE
+    );
+    fs::read_to_string(config_path)
+        .map(|content| parse_virtual_devices(&content))
+        .unwrap_or_default()
+}
+
+/// "Stream Mix" -> "pro_audio_virtual_stream_mix"
+pub fn virtual_sink_node_name(name: &str) -> String {
+    let slug: String = name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("{}{}", VIRTUAL_SINK_PREFIX, slug)
+}
+
+fn parse_virtual_devices(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter_map(|line| line.strip_prefix("# ")?.strip_prefix(VIRTUAL_DEVICE_MARKER))
+        .map(|name| name.trim().to_string())
+        .collect()
+}
+
+fn generate_virtual_devices_config(names: &[String]) -> String {
+    let mut config = PipeWireConfig::new()
+        .comment("Pro Audio Config - Virtual Devices")
+        .comment("Sinks that play nowhere; route apps to one and record its monitor");
+
+    for name in names {
+        config = config.comment(&format!("{} {}", VIRTUAL_DEVICE_MARKER, name));
+    }
+    for name in names {
+        config = config.object(
+            PipeWireObject::null_sink(&virtual_sink_node_name(name), name)
+                .arg("audio.position", vec!["FL", "FR"])
+                .arg("monitor.channel-volumes", true),
+        );
+    }
+
+    config.render()
+}
+
+// ----------------------------------------------------------------------------
+// Configuration Generation Functions
+// ----------------------------------------------------------------------------
+
+/// Generates modern WirePlumber JSON configuration content for versions >= 0.5
+fn generate_wireplumber_config(settings: &AudioSettings, _stream_type: &str) -> String {
+    let device_pattern = if settings.device_id == "default" {
+        "~alsa.*".to_string()
+    } else {
+        settings.device_id.clone()
+    };
+
+    let (audio_format, _) = crate::audio::choose_audio_format(settings.bit_depth, &[]);
+
+    WirePlumberConfig::new(&["alsa-monitor", "rules"])
+        .rule(
+            WirePlumberRule::matching("device.name", &device_pattern)
+                .set("audio.format", audio_format)
+                .set("audio.rate", settings.sample_rate)
+                .set("audio.channels", settings.channels)
+                .set("audio.position", settings.channel_positions())
+                .set("api.alsa.period-size", settings.hardware_period_size())
+                .set("api.alsa.period-num", 2)
+                .set("api.alsa.headroom", 8192),
+        )
+        .render()
+}
+
+/// Validates WirePlumber 0.4 Lua rule content before it is written to disk.
+/// Checks bracket balance (ignoring strings and comments) and the rule table
+/// structure, then does a real parse with `luac -p` when one is installed.
+pub fn validate_lua_config(content: &str) -> Result<(), String> {
+    check_lua_bracket_balance(content)?;
+
+    for required in ["matches", "apply_properties"] {
+        if !content.contains(required) {
+            return Err(format!(
+                "Lua config is missing required '{}' table",
+                required
+            ));
+        }
+    }
+
+    if !content.contains("table.insert(") {
+        return Err("Lua config does not register any rule with table.insert()".to_string());
+    }
+
+    // Optional real parse if a Lua compiler is available
+    for luac in ["luac", "luac5.4", "luac5.3"] {
+        let child = Command::new(luac)
+            .args(["-p", "-"])
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::piped())
+            .spawn();
+
+        let Ok(mut child) = child else {
+            continue;
+        };
+
+        if let Some(mut stdin) = child.stdin.take() {
+            use std::io::Write;
+            stdin
+                .write_all(content.as_bytes())
+                .map_err(|e| format!("Failed to pass Lua config to {}: {}", luac, e))?;
+        }
+
+        let output = child
+            .wait_with_output()
+            .map_err(|e| format!("Failed to run {}: {}", luac, e))?;
+
+        if !output.status.success() {
+            return Err(format!(
+                "Lua syntax error: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        log::info!("✓ Lua config parsed successfully with {}", luac);
+        break;
+    }
+
+    Ok(())
+}
+
+/// Checks that (), {} and [] are balanced outside of strings and comments
+fn check_lua_bracket_balance(content: &str) -> Result<(), String> {
+    let mut stack = Vec::new();
+
+    for (line_no, line) in content.lines().enumerate() {
+        let mut in_string: Option<char> = None;
+        let mut chars = line.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if let Some(quote) = in_string {
+                if c == '\\' {
+                    chars.next();
+                } else if c == quote {
+                    in_string = None;
+                }
+                continue;
+            }
+
+            match c {
+                '"' | '\'' => in_string = Some(c),
+                '-' if chars.peek() == Some(&'-') => break,
+                '(' | '{' | '[' => stack.push((c, line_no + 1)),
+                ')' | '}' | ']' => {
+                    let expected = match c {
+                        ')' => '(',
+                        '}' => '{',
+                        _ => '[',
+                    };
+                    match stack.pop() {
+                        Some((open, _)) if open == expected => {}
+                        Some((open, open_line)) => {
+                            return Err(format!(
+                                "Mismatched '{}' on line {} (opened '{}' on line {})",
+                                c,
+                                line_no + 1,
+                                open,
+                                open_line
+                            ));
+                        }
+                        None => {
+                            return Err(format!("Unexpected '{}' on line {}", c, line_no + 1));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if in_string.is_some() {
+            return Err(format!("Unterminated string on line {}", line_no + 1));
+        }
+    }
+
+    if let Some((open, line)) = stack.pop() {
+        return Err(format!("Unclosed '{}' opened on line {}", open, line));
+    }
+
+    Ok(())
+}
+
+// ----------------------------------------------------------------------------
+// Configuration Management
+// ----------------------------------------------------------------------------
+
+/// Clean up conflicting system PipeWire configurations
+fn cleanup_system_pipewire_configs() -> Result<(), String> {
+    cleanup_audio_configs(true, "pipewire", "conflicting")
+}
+
+/// Clean up conflicting user PipeWire configurations
+fn cleanup_user_pipewire_configs() -> Result<(), String> {
+    cleanup_audio_configs(false, "pipewire", "conflicting")
+}
+
+/// Unified cleanup function for all PipeWire/WirePlumber configurations
+/// system_wide: true for /etc/, false for user configs
+/// config_type: "pipewire" or "wireplumber"
+/// mode: "all", "basic", "advanced", "exclusive", "conflicting"
+/// Only files an apply writes are touched, "all" included; the reset-only
+/// fragments are left to `cleanup_config_files` and the tools that wrote them.
+fn cleanup_audio_configs(system_wide: bool, config_type: &str, mode: &str) -> Result<(), String> {
+    // Determine base directory
+    let base_dir = if system_wide {
+        "/etc".to_string()
+    } else {
+        user_config_dir()
+    };
+
+    // Build config directory path
+    let config_dir = match config_type {
+        "pipewire" => format!("{}/pipewire/pipewire.conf.d", base_dir),
+        "wireplumber" => format!("{}/wireplumber/wireplumber.conf.d", base_dir),
+        _ => return Err(format!("Unknown config type: {}", config_type)),
+    };
+
+    log::info!("Cleaning up {} configs in: {}", mode, config_dir);
+
+    // Define which files to remove based on mode
+    let wanted: fn(&str) -> bool = match mode {
+        "all" => |_| true,
+        "basic" => |name| BASIC_CONFIG_FILES.contains(&name),
+        "advanced" => |name| ADVANCED_CONFIG_FILES.contains(&name),
+        "exclusive" => |name| name == EXCLUSIVE_CONFIG_FILE,
+        // The safe fallback would keep overriding whatever gets applied next
+        "conflicting" => |name| {
+            BASIC_CONFIG_FILES.contains(&name)
+                || ADVANCED_CONFIG_FILES.contains(&name)
+                || name == SAFE_CONFIG_FILE
+        },
+        _ => return Err(format!("Unknown cleanup mode: {}", mode)),
+    };
+
+    let scope = if system_wide {
+        ConfigScope::System
+    } else {
+        ConfigScope::User
+    };
+
+    let mut removed_count = 0;
+
+    for path in apply_config_paths(scope) {
+        let in_config_dir = path.parent() == Some(Path::new(&config_dir));
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+
+        if in_config_dir
+            && wanted(&file_name)
+            && path.exists()
+            && let Ok(()) = fs::remove_file(&path)
+        {
+            log::info!("✓ Removed: {}", path.display());
+            removed_count += 1;
+        }
+    }
+
+    if removed_count > 0 {
+        log::info!("✓ Removed {} configuration files", removed_count);
+    }
+
+    Ok(())
+}
+
+/// Modifies the main PipeWire configuration file as a fallback
+fn modify_main_pipewire_config(
+    settings: &AudioSettings,
+    system_wide: bool,
+    batch: &mut PrivilegedBatch,
+) -> Result<(), String> {
+    let config_paths = if system_wide {
+        vec!["/etc/pipewire/pipewire.conf".to_string()]
+    } else {
+        vec![format!("{}/pipewire/pipewire.conf", user_config_dir())]
+    };
+
+    for path in &config_paths {
+        if !Path::new(path).exists() {
+            continue;
+        }
+
+        let content =
+            fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+
+        // Try multiple possible patterns for clock rate and quantum
+        let patterns = [
+            (
+                "#default.clock.rate          = 48000",
+                format!("default.clock.rate          = {}", settings.sample_rate),
+            ),
+            (
+                "#default.clock.rate          = 192000",
+                format!("default.clock.rate          = {}", settings.sample_rate),
+            ),
+            (
+                "default.clock.rate          = 48000",
+                format!("default.clock.rate          = {}", settings.sample_rate),
+            ),
+            (
+                "#default.clock.quantum       = 1024",
+                format!("default.clock.quantum       = {}", settings.buffer_size),
+            ),
+            (
+                "#default.clock.quantum       = 8192",
+                format!("default.clock.quantum       = {}", settings.buffer_size),
+            ),
+            (
+                "default.clock.quantum       = 1024",
+                format!("default.clock.quantum       = {}", settings.buffer_size),
+            ),
+            // Also update the allowed rates
+            (
+                "default.clock.allowed-rates = \\[ 44100, 48000, 96000, 192000 \\]",
+                format!(
+                    "default.clock.allowed-rates = [ {} ]",
+                    settings.allowed_rates_list()
+                ),
+            ),
+        ];
+
+        let mut updated_content = content.clone();
+        for (pattern, replacement) in &patterns {
+            updated_content = updated_content.replace(pattern, replacement);
+        }
+
+        // Only write if changes were made
+        if updated_content != content {
+            // Backup original
+            let backup_path = format!("{}.backup", path);
+            if system_wide {
+                batch.copy(path, &backup_path)?;
+                batch.write(path, &updated_content)?;
+            } else {
+                fs::copy(path, &backup_path)
+                    .map_err(|e| format!("Failed to backup {}: {}", path, e))?;
+                fs::write(path, updated_content)
+                    .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+            }
+
+            log::info!("Modified main config: {} (backup: {})", path, backup_path);
+        } else {
+            log::info!("No changes needed for: {}", path);
+        }
+
+        return Ok(());
+    }
+
+    Err("No main PipeWire config file found".to_string())
+}
+
+/// Modify the main pipewire.conf to change quantum-floor
+fn modify_main_pipewire_quantum_floor(
+    settings: &AudioSettings,
+    system_wide: bool,
+    batch: &mut PrivilegedBatch,
+) -> Result<(), String> {
+    log::info!("=== NUCLEAR OPTION: Modifying main pipewire.conf ===");
+
+    let main_conf_path = main_pipewire_conf(system_wide);
+    let Some(new_content) = main_pipewire_quantum_floor_content(settings, &main_conf_path)? else {
+        log::info!("Main config not found at: {}", main_conf_path);
+        return Ok(()); // Not an error, just skip
+    };
+
+    // Backup and write
+    let backup_path = format!("{}.backup-pro-audio", main_conf_path);
+    batch.copy(&main_conf_path, &backup_path)?;
+    batch.write(&main_conf_path, &new_content)?;
+
+    log::info!(
+        "✓ Modified main config: {} (backup: {})",
+        main_conf_path,
+        backup_path
+    );
+    Ok(())
+}
+
+/// Content of the main pipewire.conf at `main_conf_path` with quantum-floor and
+/// min-quantum set to the buffer size. None when there is no such file.
+fn main_pipewire_quantum_floor_content(
+    settings: &AudioSettings,
+    main_conf_path: &str,
+) -> Result<Option<String>, String> {
+    if !Path::new(main_conf_path).exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(main_conf_path)
+        .map_err(|e| format!("Failed to read {}: {}", main_conf_path, e))?;
+    Ok(Some(set_main_quantum_floor(&content, settings.buffer_size)))
+}
+
+/// Replaces quantum-floor and min-quantum in a main pipewire.conf, or adds
+/// both after the first default.clock.quantum line
+fn set_main_quantum_floor(content: &str, buffer_size: u32) -> String {
+    // Replace or add quantum-floor setting
+    let mut updated = false;
+    let mut new_content = String::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+
+        // Look for quantum-floor setting
+        if trimmed.contains("default.clock.quantum-floor") {
+            // Replace it with our value
+            new_content.push_str(&format!(
+                "    default.clock.quantum-floor = {}\n",
+                buffer_size
+            ));
+            log::info!("✓ Replaced quantum-floor in main config");
+            updated = true;
+        } else if trimmed.contains("default.clock.min-quantum") {
+            // Also update min-quantum
+            new_content.push_str(&format!(
+                "    default.clock.min-quantum = {}\n",
+                buffer_size
+            ));
+            updated = true;
+        } else {
+            new_content.push_str(line);
+            new_content.push('\n');
+        }
+    }
+
+    // If didn't find quantum-floor, adds it
+    if !updated {
+        // Find where to insert it (after other quantum settings)
+        let mut final_content = String::new();
+        let mut inserted = false;
+
+        for line in content.lines() {
+            final_content.push_str(line);
+            final_content.push('\n');
+
+            // Insert after finding quantum settings section
+            if !inserted && line.trim().contains("default.clock.quantum") {
+                final_content.push_str(&format!(
+                    "    default.clock.quantum-floor = {}\n",
+                    buffer_size
+                ));
+                final_content.push_str(&format!(
+                    "    default.clock.min-quantum = {}\n",
+                    buffer_size
+                ));
+                inserted = true;
+                log::info!("✓ Added quantum-floor to main config");
+            }
+        }
+
+        new_content = final_content;
+    }
+
+    new_content
+}
+
+// ----------------------------------------------------------------------------
+// Core Audio Application Functions
+// ----------------------------------------------------------------------------
+
+/// Main function to apply audio settings with authentication
+fn apply_audio_settings_with_auth(
+    settings: AudioSettings,
+    stream_type: &str,
+) -> Result<(), AudioConfigError> {
+    log::info!(
+        "Applying {} audio settings with authentication: {}Hz/{}bit/{} samples",
+        stream_type,
+        settings.sample_rate,
+        settings.bit_depth,
+        settings.buffer_size
+    );
+
+    // Try PipeWire configuration first
+    let mismatches = match update_audio_settings(&settings, true, None) {
+        Ok(outcome) if outcome.verified => {
+            log::info!("✓ {}", outcome.summary());
+            log::info!("✓ Settings applied successfully - monitoring tab should refresh");
+            return Ok(());
+        }
+        Ok(outcome) => {
+            log::info!(
+                "{} ({}), trying WirePlumber...",
+                outcome.summary(),
+                outcome.warnings.join("; ")
+            );
+            Some(outcome.warnings)
+        }
+        // Another prompt for the fallback would only be refused again
+        Err(e) if e.is_authorization_failure() => return Err(e),
+        Err(e) => {
+            log::info!("PipeWire config failed ({}), trying WirePlumber...", e);
+            None
+        }
+    };
+
+    // Fallback to WirePlumber device-specific configuration
+    let fallback = apply_wireplumber_device_config(&settings, stream_type, None);
+    match (fallback, mismatches) {
+        (Ok(_), _) => Ok(()),
+        // The system config is in place, it just did not take effect
+        (Err(e), Some(mismatches)) => {
+            log::warn!("WirePlumber fallback failed: {}", e);
+            Err(AudioConfigError::VerificationMismatch(mismatches))
+        }
+        (Err(e), None) => Err(e.into()),
+    }
+}
+
+/// Main function to apply audio settings using multiple configuration approaches with fallbacks
+pub fn update_audio_settings(
+    settings: &AudioSettings,
+    system_wide: bool,
+    actual_device_name: Option<&str>,
+) -> Result<ApplyOutcome, AudioConfigError> {
+    log::info!(
+        "Applying {} audio settings: {}Hz/{}bit/{} samples",
+        if system_wide { "system-wide" } else { "user" },
+        settings.sample_rate,
+        settings.bit_depth,
+        settings.buffer_size
+    );
+
+    // Clean up conflicting configs first
+    if system_wide {
+        cleanup_system_pipewire_configs()?;
+    } else {
+        cleanup_user_pipewire_configs()?;
+    }
+
+    // Try multiple configuration approaches in order of preference
+    let mut method_used = None;
+    // System files are written together before the restart, with one password prompt
+    let mut batch = PrivilegedBatch::default();
+
+    // Approach 1: Create PipeWire config fragment
+    match create_pipewire_fragment(settings, system_wide, &mut batch) {
+        Ok(_) => {
+            log::info!("✓ Successfully created PipeWire config fragment");
+            method_used = Some(ApplyMethod::PipeWireFragment);
+        }
+        Err(e) => {
+            log::info!(
+                "PipeWire fragment approach failed: {}, trying next approach...",
+                e
+            );
+        }
+    }
+
+    if method_used.is_none() {
+        // Approach 2: Create WirePlumber config with specific device
+        match create_wireplumber_config_new(settings, system_wide, actual_device_name, &mut batch) {
+            Ok(_) => {
+                log::info!("✓ Successfully created WirePlumber config");
+                method_used = Some(ApplyMethod::WirePlumberConfig);
+            }
+            Err(e) => {
+                log::info!(
+                    "WirePlumber approach failed: {}, trying final approach...",
+                    e
+                );
+
+                // Approach 3: Modify main pipewire.conf as fallback
+                match modify_main_pipewire_config(settings, system_wide, &mut batch) {
+                    Ok(_) => {
+                        log::info!("✓ Successfully modified main PipeWire config");
+                        method_used = Some(ApplyMethod::MainConfigEdit);
+                    }
+                    Err(e) => {
+                        return Err(AudioConfigError::Other(format!(
+                            "All configuration approaches failed: {}",
+                            e
+                        )));
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(method) = method_used {
+        let pulse_warnings = sync_pipewire_pulse_quantum(settings, system_wide, &mut batch);
+        batch.run()?;
+
+        // Wait a bit for the config to be written
+        std::thread::sleep(std::time::Duration::from_millis(500));
+        restart_audio_services(false, system_wide)?;
+        log::info!("✓ Audio services restarted successfully");
+
+        // Verify the settings were applied
+        let mut outcome = ApplyOutcome::new(method);
+        verify_settings_applied(settings, &mut outcome);
+        // Advisory only, added after verification so it doesn't fail the check
+        outcome.warnings.extend(pulse_warnings);
+
+        Ok(outcome)
+    } else {
+        Err(AudioConfigError::Other(
+            "Failed to apply audio settings through any method".to_string(),
+        ))
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Privilege & System Functions
+// ----------------------------------------------------------------------------
+
+/// Create directory with proper privilege escalation for system paths
+fn create_dir_all_with_privileges(path: &str) -> Result<(), AudioConfigError> {
+    let mut batch = PrivilegedBatch::default();
+    batch.create_dir_all(path)?;
+    batch.run()
+}
+
+/// Whether `path` is an existing directory, following symlinks.
+/// An existing non-directory at that path is an error.
+fn directory_exists(path: &str) -> Result<bool, String> {
+    match fs::metadata(path) {
+        Ok(metadata) if metadata.is_dir() => Ok(true),
+        Ok(_) => Err(format!("{} exists but is not a directory", path)),
+        Err(_) => Ok(false),
+    }
+}
+
+/// Whether a path lives under /etc, either literally or through a symlinked ancestor
+fn is_system_config_path(path: &Path) -> bool {
+    if path.starts_with("/etc") {
+        return true;
+    }
+
+    path.ancestors()
+        .find(|ancestor| ancestor.exists())
+        .and_then(|ancestor| ancestor.canonicalize().ok())
+        .is_some_and(|resolved| resolved.starts_with("/etc"))
+}
+
+/// Execute a command with privilege escalation for system-wide changes
+fn execute_with_privileges(command: &str, args: &[&str]) -> Result<(), AudioConfigError> {
+    log::info!("Requesting administrator privileges for system-wide changes...");
+
+    // Try pkexec first (common on modern Linux systems)
+    let output = Command::new("pkexec")
+        .arg(command)
+        .args(args)
+        .stdin(std::process::Stdio::inherit())
+        .output()
+        .map_err(|e| {
+            format!(
+                "Failed to execute with pkexec: {}. Make sure pkexec is available.",
+                e
+            )
+        })?;
+
+    if output.status.success() {
+        return Ok(());
+    }
+    // sudo would prompt again on a terminal the GUI user never sees
+    if pkexec_cancelled(&output.status) {
+        log::info!("Authorization was cancelled, nothing changed");
+        return Err(AudioConfigError::AuthorizationCancelled);
+    }
+
+    let pkexec_error = describe_failed_output("pkexec", &output);
+    log::info!("{}", pkexec_error);
+
+    // Fallback to sudo
+    log::info!("pkexec failed, trying sudo...");
+    let output = Command::new("sudo")
+        .arg(command)
+        .args(args)
+        .stdin(std::process::Stdio::inherit())
+        .output()
+        .map_err(|e| {
+            format!(
+                "Failed to execute with sudo: {}. Please run with administrator privileges.\n\n{}",
+                e, pkexec_error
+            )
+        })?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        let sudo_error = describe_failed_output("sudo", &output);
+        log::info!("{}", sudo_error);
+
+        Err(AudioConfigError::PrivilegeDenied(format!(
+            "'{}' could not be run as root. Please run as root or configure sudo/pkexec.\n\n{}\n{}",
+            command, pkexec_error, sudo_error
+        )))
+    }
+}
+
+/// Runs `ops` through the polkit helper when it is installed, so they need
+/// a single "Configure system audio" authentication. Without it they run as
+/// one shell script through pkexec/sudo, still a single prompt.
+fn run_privileged_ops(ops: &[PrivilegedOp]) -> Result<(), AudioConfigError> {
+    if ops.is_empty() {
+        return Ok(());
+    }
+
+    if helper_handles(ops) {
+        return run_with_helper(ops);
+    }
+
+    let args = shell_batch_args(ops);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    execute_with_privileges("sh", &args)
+}
+
+/// The file changes of one apply. User files, and system files that happen
+/// to be writable, are changed right away. System files that need root are
+/// queued and `run` performs them all with one authentication, so an apply
+/// touching several files under /etc prompts once.
+#[derive(Debug, Default)]
+struct PrivilegedBatch {
+    ops: Vec<PrivilegedOp>,
+}
+
+impl PrivilegedBatch {
+    /// Once something is queued, later system changes are queued behind it
+    /// so they still happen in order. A system path that is not writable or
+    /// whose directory is missing (e.g. no /etc/pipewire yet) is left to the
+    /// privileged batch.
+    fn queue_or_try(
+        &mut self,
+        path: &str,
+        op: PrivilegedOp,
+        direct: impl FnOnce() -> std::io::Result<()>,
+    ) -> Result<(), AudioConfigError> {
+        if !self.ops.is_empty() {
+            self.ops.push(op);
+            return Ok(());
+        }
+
+        match direct() {
+            Ok(()) => Ok(()),
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    std::io::ErrorKind::PermissionDenied | std::io::ErrorKind::NotFound
+                ) && is_system_config_path(Path::new(path)) =>
+            {
+                self.ops.push(op);
+                Ok(())
+            }
+            Err(e) => Err(AudioConfigError::ConfigWriteFailed(PathBuf::from(path), e)),
+        }
+    }
+
+    fn create_dir_all(&mut self, path: &str) -> Result<(), AudioConfigError> {
+        // Already present (symlinks are followed) - never prompt for it
+        if directory_exists(path)? {
+            return Ok(());
+        }
+
+        let op = PrivilegedOp::CreateDir {
+            path: path.to_string(),
+        };
+        // create_dir_all is idempotent, so a concurrent creator is not an error
+        self.queue_or_try(path, op, || match fs::create_dir_all(path) {
+            Err(_) if directory_exists(path).unwrap_or(false) => Ok(()),
+            result => result,
+        })
+    }
+
+    /// Writes to a temporary file next to the target and renames it into
+    /// place, so PipeWire never sees a half-written config
+    fn write(&mut self, config_path: &str, content: &str) -> Result<(), AudioConfigError> {
+        if config_path.starts_with("/etc/") {
+            let op = PrivilegedOp::Write {
+                path: config_path.to_string(),
+                content: content.to_string(),
+            };
+            // Neither the helper nor the shell fallback creates missing parents
+            if let Some(parent) = Path::new(config_path).parent() {
+                self.create_dir_all(&parent.to_string_lossy())?;
+            }
+            // Already root, or /etc is writable - no prompt needed
+            return self.queue_or_try(config_path, op, || {
+                try_write_file_atomically(Path::new(config_path), content)
+            });
+        }
+
+        // User path - no privileges needed
+        if let Some(parent) = Path::new(config_path).parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| AudioConfigError::ConfigWriteFailed(parent.to_path_buf(), e))?;
+        }
+
+        // Renaming over a symlinked file would replace the link with a copy
+        let resolved = resolve_config_path(Path::new(config_path));
+        if resolved != Path::new(config_path) {
+            log::debug!(
+                "Writing {} at its real location {}",
+                config_path,
+                resolved.display()
+            );
+        }
+        try_write_file_atomically(&resolved, content)
+            .map_err(|e| AudioConfigError::ConfigWriteFailed(resolved.clone(), e))
+    }
+
+    fn copy(&mut self, from: &str, to: &str) -> Result<(), AudioConfigError> {
+        let op = PrivilegedOp::Copy {
+            from: from.to_string(),
+            to: to.to_string(),
+        };
+        self.queue_or_try(to, op, || fs::copy(from, to).map(|_| ()))
+    }
+
+    /// A missing file is not an error
+    fn remove(&mut self, path: &str) -> Result<(), AudioConfigError> {
+        let op = PrivilegedOp::Remove {
+            path: path.to_string(),
+        };
+        self.queue_or_try(path, op, || match fs::remove_file(path) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        })
+    }
+
+    /// Performs the queued system changes, asking for authentication once
+    fn run(self) -> Result<(), AudioConfigError> {
+        if !self.ops.is_empty() {
+            log::info!(
+                "Applying {} system config change(s) with privileges",
+                self.ops.len()
+            );
+        }
+        run_privileged_ops(&self.ops)
+    }
+}
+
+/// Summarize a failed command's exit code and stderr for error messages
+fn describe_failed_output(tool: &str, output: &std::process::Output) -> String {
+    let exit = output
+        .status
+        .code()
+        .map(|code| code.to_string())
+        .unwrap_or_else(|| "signal".to_string());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stderr = stderr.trim();
+
+    if stderr.is_empty() {
+        format!("{} exited with {} (no error output)", tool, exit)
+    } else {
+        format!("{} exited with {}: {}", tool, exit, stderr)
+    }
+}
+
+/// Write configuration file with proper privilege escalation for system paths
+fn write_config_with_privileges(config_path: &str, content: &str) -> Result<(), AudioConfigError> {
+    let mut batch = PrivilegedBatch::default();
+    batch.write(config_path, content)?;
+    batch.run()?;
+
+    if config_path.starts_with("/etc/") {
+        log::info!("✓ System config created: {}", config_path);
+    }
+    Ok(())
+}
+
+/// Where `path` really is once symlinks are followed. Config dirs and files are
+/// often symlinks into a dotfiles repo; the part of the path that does not
+/// exist yet is kept as given, below the resolved existing ancestor.
+pub fn resolve_config_path(path: &Path) -> PathBuf {
+    let Some(existing) = path.ancestors().find(|ancestor| ancestor.exists()) else {
+        return path.to_path_buf();
+    };
+    let Ok(resolved) = existing.canonicalize() else {
+        return path.to_path_buf();
+    };
+
+    match path.strip_prefix(existing) {
+        Ok(rest) if !rest.as_os_str().is_empty() => resolved.join(rest),
+        _ => resolved,
+    }
+}
+
+/// `resolve_config_path` for a path whose own components at or below
+/// `config_dir` include a symlink; None otherwise. A symlinked ancestor of
+/// the config dir, e.g. /home -> /var/home, does not count as a dotfiles link.
+fn resolve_config_link(path: &Path, config_dir: &Path) -> Option<PathBuf> {
+    let linked = path
+        .ancestors()
+        .take_while(|ancestor| ancestor.starts_with(config_dir))
+        .any(|ancestor| {
+            fs::symlink_metadata(ancestor).is_ok_and(|metadata| metadata.file_type().is_symlink())
+        });
+
+    linked.then(|| resolve_config_path(path))
+}
+
+/// Writes content to a temp file in the same directory, syncs it and renames
+/// it over the target (atomic on the same filesystem)
+fn write_file_atomically(path: &Path, content: &str) -> Result<(), String> {
+    try_write_file_atomically(path, content)
+        .map_err(|e| format!("Failed to write config file: {}", e))
+}
+
+/// `write_file_atomically` keeping the I/O error, so callers can tell a
+/// permission problem from other failures
+fn try_write_file_atomically(path: &Path, content: &str) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Invalid config path: {}", path.display()),
+            )
+        })?
+        .to_string_lossy();
+    let temp_path = path.with_file_name(format!(".{}.tmp-{}", file_name, std::process::id()));
+
+    let write_result = (|| -> std::io::Result<()> {
+        let mut file = fs::File::create(&temp_path)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)
+    })();
+
+    if write_result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+
+    write_result
+}
+
+// ----------------------------------------------------------------------------
+// Wireplumber configuration
+// ----------------------------------------------------------------------------
+
+/// Applies device-specific audio settings via a WirePlumber SPA-JSON configuration fragment.
+/// Returns a note for the user when the device needed another sample format.
+// Update create_wireplumber_config_new to accept device name
+pub fn apply_wireplumber_device_config(
+    settings: &AudioSettings,
+    stream_type: &str,
+    actual_device_name: Option<&str>,
+) -> Result<Option<String>, String> {
+    log::info!("Applying WirePlumber device configuration...");
+
+    let format_note = write_wireplumber_device_config(settings, stream_type, actual_device_name)?;
+
+    // Restart services to apply the new config
+    restart_audio_services(true, false)?;
+
+    log::info!("✓ Audio services restarted successfully");
+    Ok(format_note)
+}
+
+/// SPA format for the bit depth on `device`, picked from the formats its node
+/// advertises; the plain mapping when they can't be read
+fn resolve_audio_format(
+    settings: &AudioSettings,
+    device: &str,
+    stream_type: &str,
+) -> (&'static str, Option<String>) {
+    let formats = crate::audio::detect_device_formats(device, stream_type).unwrap_or_else(|e| {
+        log::debug!("Could not read device formats: {}", e);
+        Vec::new()
+    });
+
+    let (audio_format, note) = crate::audio::choose_audio_format(settings.bit_depth, &formats);
+    if let Some(note) = &note {
+        log::info!("{}", note);
+    }
+    (audio_format, note)
+}
+
+/// Writes the device-specific WirePlumber fragment without restarting services.
+/// Returns the note of `resolve_audio_format`, if any.
+fn write_wireplumber_device_config(
+    settings: &AudioSettings,
+    stream_type: &str,
+    actual_device_name: Option<&str>,
+) -> Result<Option<String>, String> {
+    // Use the actual device name if provided, otherwise fall back to the device_id
+    let device_pattern = if let Some(real_name) = actual_device_name {
+        log::info!("Using real device name: {}", real_name);
+        real_name.to_string()
+    } else if settings.device_id == "default" {
+        // Try to detect the actual default device
+        match if stream_type == "output" {
+            crate::audio::detect_output_audio_device()
+        } else {
+            crate::audio::detect_input_audio_device()
+        } {
+            Ok(device_info) => {
+                if let Some(name) = crate::audio::extract_actual_device_name(&device_info) {
+                    log::info!("Detected actual device: {}", name);
+                    name
+                } else {
+                    "~alsa.*".to_string()
+                }
+            }
+            Err(_) => "~alsa.*".to_string(),
+        }
+    } else {
+        // Use the provided device_id directly
+        settings.device_id.clone()
+    };
+
+    // A wildcard pattern names no node, the formats come from the selected device
+    let format_device = if device_pattern.starts_with('~') {
+        &settings.device_id
+    } else {
+        &device_pattern
+    };
+    let (audio_format, format_note) = resolve_audio_format(settings, format_device, stream_type);
+
+    // Generate SPA-JSON for WirePlumber >= 0.5
+    let config_content =
+        generate_wireplumber_device_config(settings, stream_type, &device_pattern, audio_format);
+
+    // CRITICAL: Use the correct path and extension for WirePlumber >= 0.5
+    let config_path = if stream_type == "output" {
+        format!(
+            "{}/wireplumber/wireplumber.conf.d/99-pro-audio-output.conf",
+            user_config_dir()
+        )
+    } else {
+        format!(
+            "{}/wireplumber/wireplumber.conf.d/99-pro-audio-input.conf",
+            user_config_dir()
+        )
+    };
+
+    // Create the config directory if it doesn't exist
+    if let Some(parent) = Path::new(&config_path).parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+
+    // Write the SPA-JSON configuration file
+    fs::write(&config_path, config_content)
+        .map_err(|e| format!("Failed to write WirePlumber config: {}", e))?;
+
+    log::info!(
+        "✓ WirePlumber SPA-JSON configuration created: {} for device: {}",
+        config_path,
+        device_pattern
+    );
+
+    Ok(format_note)
+}
+
+/// Generate device-specific WirePlumber configuration
+fn generate_wireplumber_device_config(
+    settings: &AudioSettings,
+    stream_type: &str,
+    device_pattern: &str,
+    audio_format: &str,
+) -> String {
+    let direction = if stream_type == "output" {
+        "sink"
+    } else {
+        "source"
+    };
+
+    // A chosen name only for a specific device; on a wildcard match every
+    // device would get the same name. Unset, apps keep the device's own.
+    let node_description = settings
+        .node_description
+        .as_deref()
+        .filter(|_| !device_pattern.starts_with('~'));
+
+    WirePlumberConfig::new(&["alsa-monitor", "rules"])
+        .rule(
+            WirePlumberRule::matching("node.name", device_pattern)
+                .set("audio.format", audio_format)
+                .set("audio.rate", settings.sample_rate)
+                .set("audio.allowed-rates", vec![settings.sample_rate])
+                .set("audio.channels", settings.channels)
+                .set("audio.position", settings.channel_positions())
+                .set("api.alsa.period-size", settings.hardware_period_size())
+                .set("api.alsa.period-num", 2)
+                .set("api.alsa.headroom", 8192)
+                .set_opt("node.description", node_description)
+                .set("priority.session", 1500)
+                .set("priority.driver", 1500),
+        )
+        .render()
+}
+
+// ----------------------------------------------------------------------------
+// Service Management Functions
+// ----------------------------------------------------------------------------
+
+/// Aggressive restart that kills everything and forces restart
+#[allow(dead_code)]
+fn aggressive_restart_audio_services(system_wide: bool) -> Result<(), String> {
+    log::info!("=== AGGRESSIVE AUDIO SERVICE RESTART ===");
+
+    let username = whoami::username();
+
+    // Step 1: Kill all audio processes
+    log::info!("Step 1: Killing all audio processes...");
+    let _ = Command::new("pkill").arg("-9").arg("pipewire").status();
+    let _ = Command::new("pkill").arg("-9").arg("wireplumber").status();
+    let _ = Command::new("pkill").arg("-9").arg("pulseaudio").status();
+
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    // Step 2: Restart with systemd
+    log::info!("Step 2: Restarting with systemd...");
+    if system_wide {
+        let status = Command::new("sudo")
+            .args([
+                "-u",
+                &username,
+                "systemctl",
+                "--user",
+                "restart",
+                "pipewire",
+                "pipewire-pulse",
+                "wireplumber",
+            ])
+            .status()
+            .map_err(|e| format!("Failed to restart user services: {}", e))?;
+
+        if !status.success() {
+            log::warn!("Systemd restart failed, trying manual restart...");
+        }
+    } else {
+        let _ = Command::new("systemctl")
+            .args([
+                "--user",
+                "restart",
+                "pipewire",
+                "pipewire-pulse",
+                "wireplumber",
+            ])
+            .status();
+    }
+
+    // Step 3: Start processes manually if needed
+    std::thread::sleep(std::time::Duration::from_secs(2));
+
+    log::info!("Step 3: Checking if services are running...");
+    let mut attempts = 0;
+    let max_attempts = 10;
+
+    while attempts < max_attempts {
+        if check_if_services_are_running() {
+            log::info!(
+                "✓ Audio services are running (attempt {}/{})",
+                attempts + 1,
+                max_attempts
+            );
+            break;
+        }
+
+        attempts += 1;
+        std::thread::sleep(std::time::Duration::from_secs(1));
+
+        if attempts % 3 == 0 {
+            log::info!("Attempting to start services manually...");
+            let _ = pipewire_command("pipewire").spawn();
+            let _ = pipewire_command("wireplumber").spawn();
+        }
+    }
+
+    if attempts >= max_attempts {
+        log::warn!("Audio services may not have started properly");
+        log::warn!("Try logging out and back in, or reboot");
+    }
+
+    Ok(())
+}
+
+/// Check if audio services are running
+fn check_if_services_are_running() -> bool {
+    let services = ["pipewire", "pipewire-pulse", "wireplumber"];
+
+    // Check if any of the services are running
+    for service in &services {
+        if let Ok(output) = Command::new("pgrep").arg("-f").arg(service).output()
+            && output.status.success()
+        {
+            // At least one service is running
+            return true;
+        }
+    }
+
+    // Also check with systemctl as fallback
+    if let Ok(output) = Command::new("systemctl")
+        .args(["--user", "is-active", "pipewire"])
+        .output()
+        && output.status.success()
+    {
+        return true;
+    }
+
+    false
+}
+
+/// Force PipeWire to reload configuration
+#[allow(dead_code)]
+fn force_pipewire_reload() -> Result<(), String> {
+    log::info!("Forcing PipeWire to reload configuration...");
+
+    // Method 1: Send SIGHUP to pipewire daemon
+    if let Ok(output) = Command::new("pgrep").arg("pipewire").output()
+        && output.status.success()
+    {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let pid_str = stdout.trim();
+        if !pid_str.is_empty() {
+            // Store the PID in a variable to avoid temporary value issues
+            let pid = pid_str.to_string();
+            let _ = Command::new("kill").args(["-HUP", &pid]).status();
+            log::info!("✓ Sent SIGHUP to PipeWire (PID: {})", pid);
+        }
+    }
+
+    // Method 2: Use pw-cli to reload
+    let _ = pipewire_command("pw-cli").arg("info").arg("0").output();
+
+    // Method 3: Use pactl to trigger reload
+    let _ = Command::new("pactl").arg("info").output();
+
+    // Method 4: Touch config file to trigger reload
+    let config_path = format!("{}/pipewire/pipewire.conf", user_config_dir());
+    if Path::new(&config_path).exists() {
+        let _ = Command::new("touch").arg(&config_path).status();
+    }
+
+    Ok(())
+}
+
+/// The init system / service manager, which decides how the audio services
+/// are restarted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ServiceManager {
+    Systemd,
+    OpenRC,
+    Runit,
+    Dinit,
+    S6,
+    Unknown,
+}
+
+impl ServiceManager {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ServiceManager::Systemd => "systemd",
+            ServiceManager::OpenRC => "OpenRC",
+            ServiceManager::Runit => "runit",
+            ServiceManager::Dinit => "dinit",
+            ServiceManager::S6 => "s6",
+            ServiceManager::Unknown => "unknown",
+        }
+    }
+
+    /// What to run to see whether PipeWire came back up
+    fn status_hint(&self) -> &'static str {
+        match self {
+            ServiceManager::Systemd => "systemctl --user status pipewire",
+            ServiceManager::OpenRC => "rc-service --user pipewire status",
+            ServiceManager::Runit => "sv status ~/service/pipewire",
+            ServiceManager::Dinit => "dinitctl status pipewire",
+            _ => "pgrep -a pipewire",
+        }
+    }
+}
+
+/// Runtime directories each service manager creates while it is running
+const SERVICE_MANAGER_MARKERS: &[(&str, ServiceManager)] = &[
+    ("/run/systemd/seats", ServiceManager::Systemd),
+    ("/run/systemd/system", ServiceManager::Systemd),
+    ("/run/openrc", ServiceManager::OpenRC),
+    ("/run/runit", ServiceManager::Runit),
+    ("/run/dinitctl", ServiceManager::Dinit),
+    ("/run/s6-rc", ServiceManager::S6),
+];
+
+/// OpenRC user services of the audio stack (OpenRC 0.60 and later)
+const OPENRC_USER_INIT_DIR: &str = "/etc/user/init.d";
+
+/// The service manager of the running system, from its runtime directory;
+/// an installed rc-service counts as OpenRC when none is found
+pub fn detect_service_manager() -> ServiceManager {
+    service_manager_from(
+        |path| Path::new(path).exists(),
+        crate::utils::find_in_path("rc-service"),
+    )
+}
+
+fn service_manager_from(exists: impl Fn(&str) -> bool, rc_service: bool) -> ServiceManager {
+    SERVICE_MANAGER_MARKERS
+        .iter()
+        .find(|(path, _)| exists(path))
+        .map(|(_, manager)| *manager)
+        .unwrap_or(if rc_service {
+            ServiceManager::OpenRC
+        } else {
+            ServiceManager::Unknown
+        })
+}
+
+/// One command of a service restart
+struct RestartStep {
+    argv: Vec<String>,
+    /// A failure aborts the restart; otherwise it is only logged
+    required: bool,
+}
+
+impl RestartStep {
+    fn new(argv: &[&str], required: bool) -> Self {
+        Self {
+            argv: argv.iter().map(|arg| arg.to_string()).collect(),
+            required,
+        }
+    }
+
+    fn command_line(&self) -> String {
+        self.argv.join(" ")
+    }
+}
+
+/// Names of the audio services for the service manager. Distributions
+/// that package them under other names set them in the preferences.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ServiceNames {
+    pub pipewire: String,
+    pub pipewire_pulse: String,
+    pub wireplumber: String,
+}
+
+impl Default for ServiceNames {
+    fn default() -> Self {
+        Self {
+            pipewire: "pipewire".to_string(),
+            pipewire_pulse: "pipewire-pulse".to_string(),
+            wireplumber: "wireplumber".to_string(),
+        }
+    }
+}
+
+impl ServiceNames {
+    fn all(&self) -> [&str; 3] {
+        [&self.pipewire, &self.pipewire_pulse, &self.wireplumber]
+    }
+}
+
+/// Sets the service names restarts use, from preferences
+pub fn set_service_names(names: ServiceNames) {
+    *SERVICE_NAMES.lock().unwrap() = Some(names);
+}
+
+pub fn service_names() -> ServiceNames {
+    SERVICE_NAMES.lock().unwrap().clone().unwrap_or_default()
+}
+
+/// runit user service directory: $SVDIR, else ~/service
+fn runit_service_dir() -> PathBuf {
+    match std::env::var("SVDIR") {
+        Ok(dir) if !dir.trim().is_empty() => PathBuf::from(dir.trim()),
+        _ => user_home_dir().join("service"),
+    }
+}
+
+/// The commands `restart_audio_services` runs for this service manager and
+/// scope. System-wide restarts run the same commands for the logged-in user
+/// through sudo; the pkill fallback is the same for both.
+fn restart_steps(
+    manager: ServiceManager,
+    names: &ServiceNames,
+    use_legacy: bool,
+    system_wide: bool,
+) -> Vec<RestartStep> {
+    let services = names.all();
+    let username = whoami::username();
+    // Restart the user services of the logged-in user with privileges
+    let scoped = |argv: &[&str], required: bool| {
+        if system_wide {
+            let mut scoped_argv = vec!["sudo", "-u", username.as_str()];
+            scoped_argv.extend(argv);
+            RestartStep::new(&scoped_argv, required)
+        } else {
+            RestartStep::new(argv, required)
+        }
+    };
+
+    match manager {
+        ServiceManager::Systemd if use_legacy && !system_wide => {
+            // Services one at a time, a failing one does not stop the others
+            return services
+                .iter()
+                .map(|service| scoped(&["systemctl", "--user", "restart", service], false))
+                .collect();
+        }
+        ServiceManager::Systemd => {
+            let mut argv = vec!["systemctl", "--user", "restart"];
+            argv.extend(services);
+            return vec![scoped(&argv, true)];
+        }
+        ServiceManager::OpenRC => {
+            // Without user services the session launcher owns the processes
+            let user_services: Vec<&str> = services
+                .into_iter()
+                .filter(|service| Path::new(OPENRC_USER_INIT_DIR).join(service).exists())
+                .collect();
+            if !user_services.is_empty() {
+                return user_services
+                    .into_iter()
+                    .map(|service| {
+                        scoped(
+                            &["rc-service", "--user", service, "restart"],
+                            service == names.pipewire,
+                        )
+                    })
+                    .collect();
+            }
+        }
+        ServiceManager::Runit => {
+            // Full service paths, so sv needs no SVDIR from the environment
+            let service_dir = runit_service_dir();
+            let service_paths: Vec<String> = services
+                .into_iter()
+                .map(|service| service_dir.join(service))
+                .filter(|path| path.exists())
+                .map(|path| path.to_string_lossy().to_string())
+                .collect();
+            if service_dir.join(&names.pipewire).exists() {
+                let mut argv = vec!["sv", "restart"];
+                argv.extend(service_paths.iter().map(String::as_str));
+                return vec![scoped(&argv, true)];
+            }
+        }
+        ServiceManager::Dinit => {
+            // The user instance's socket lives in the runtime dir, which sudo drops
+            let socket_path = std::env::var("XDG_RUNTIME_DIR")
+                .ok()
+                .filter(|dir| !dir.trim().is_empty())
+                .map(|dir| format!("{}/dinitctl", dir.trim_end_matches('/')));
+            return services
+                .into_iter()
+                .map(|service| {
+                    let mut argv = vec!["dinitctl"];
+                    if let Some(socket_path) = &socket_path {
+                        argv.extend(["--socket-path", socket_path.as_str()]);
+                    }
+                    argv.extend(["restart", service]);
+                    scoped(&argv, service == names.pipewire)
+                })
+                .collect();
+        }
+        ServiceManager::S6 | ServiceManager::Unknown => {}
+    }
+
+    // Kill them and let the session restart them
+    vec![
+        RestartStep::new(&["pkill", "-f", "pipewire"], false),
+        RestartStep::new(&["pkill", "-f", "wireplumber"], false),
+    ]
+}
+
+/// Command lines a restart would run for `system_wide` scope, without running
+/// anything. Detection is the same as for the real restart.
+pub fn plan_restart(system_wide: bool) -> Vec<String> {
+    restart_steps(
+        detect_service_manager(),
+        &service_names(),
+        false,
+        system_wide,
+    )
+    .iter()
+    .map(RestartStep::command_line)
+    .collect()
+}
+
+/// Improved unified function to restart audio services with timeout
+fn restart_audio_services(use_legacy: bool, system_wide: bool) -> Result<(), AudioConfigError> {
+    let manager = detect_service_manager();
+    let steps = restart_steps(manager, &service_names(), use_legacy, system_wide);
+    // Killed without a service manager, only the session can bring them back
+    let killed_only = steps.iter().all(|step| step.argv[0] == "pkill");
+
+    if offline_mode() {
+        for step in steps {
+            log::info!("Offline, not running: {}", step.command_line());
+        }
+        return Ok(());
+    }
+
+    log::info!("Restarting audio services ({})...", manager.label());
+    let start_time = Instant::now();
+
+    // Defaults and volumes sometimes reset with the restart, put them back after
+    let mixer_state = MixerState::capture();
+
+    for step in steps {
+        let command_line = step.command_line();
+        log::info!("Running: {}", command_line);
+
+        let status = Command::new(&step.argv[0]).args(&step.argv[1..]).status();
+        match status {
+            Ok(status) if status.success() => {}
+            Ok(_) if step.required => {
+                return Err(AudioConfigError::Other(format!(
+                    "Failed to restart audio services: {} failed",
+                    command_line
+                )));
+            }
+            Err(e) if step.required => {
+                return Err(AudioConfigError::Other(format!(
+                    "Failed to run {}: {}",
+                    command_line, e
+                )));
+            }
+            _ => log::warn!("{} failed", command_line),
+        }
+
+        if use_legacy {
+            // Brief pause between service restarts
+            std::thread::sleep(std::time::Duration::from_millis(500));
+        }
+    }
+
+    if !system_wide {
+        // Wait a moment for services to restart
+        log::info!("Waiting for services to restart...");
+        std::thread::sleep(std::time::Duration::from_secs(2));
+    }
+
+    // Check if services are actually running with timeout
+    log::info!("Checking if audio services are running...");
+    let max_wait_time = Duration::from_secs(10);
+    let check_interval = Duration::from_millis(500);
+
+    let mut elapsed = Duration::from_secs(0);
+    let mut services_running = false;
+
+    while elapsed < max_wait_time {
+        // Check if at least one audio service is running
+        if check_if_services_are_running() {
+            services_running = true;
+            break;
+        }
+
+        std::thread::sleep(check_interval);
+        elapsed += check_interval;
+
+        if elapsed.as_secs().is_multiple_of(2) {
+            log::info!(
+                "Waiting for audio services... ({:.1}s)",
+                elapsed.as_secs_f32()
+            );
+        }
+    }
+
+    if !services_running && killed_only {
+        return Err(AudioConfigError::ServiceManagerUnsupported(
+            manager.label().to_string(),
+        ));
+    } else if !services_running {
+        log::warn!(
+            "Audio services did not start within timeout. They may start in the background."
+        );
+        log::warn!(
+            "You can check service status with: {}",
+            manager.status_hint()
+        );
+        // Don't fail, just warn - the services might start later
+    } else {
+        log::info!("✓ Audio services are running");
+    }
+
+    let total_time = start_time.elapsed();
+    log::info!(
+        "✓ Audio service restart completed in {:.1}s",
+        total_time.as_secs_f32()
+    );
+
+    // Additional wait for services to fully initialize
+    if services_running {
+        log::info!("Waiting for services to fully initialize...");
+        std::thread::sleep(std::time::Duration::from_secs(2));
+
+        for failure in mixer_state.restore() {
+            log::warn!("Could not restore mixer state: {}", failure);
+        }
+    }
+
+    Ok(())
+}
+
+/// Non-blocking version of restart_audio_services for use in async contexts
+pub fn restart_audio_services_non_blocking() -> Result<(), String> {
+    // Spawn a thread to handle the restart
+    std::thread::spawn(|| match restart_audio_services(false, true) {
+        Ok(_) => log::info!("✓ Audio services restarted successfully in background"),
+        Err(e) => log::warn!("Failed to restart audio services: {}", e),
+    });
+
+    log::info!("Audio service restart initiated in background...");
+    Ok(())
+}
+
+// ----------------------------------------------------------------------------
+// Verification & Backup Functions
+// ----------------------------------------------------------------------------
+
+/// Backup current audio settings
+#[allow(dead_code)]
+fn backup_audio_settings() -> Result<(), String> {
+    let timestamp = Local::now().format("%Y%m%d_%H%M%S");
+    let backup_dir = format!("/tmp/pro-audio-backup-{}", timestamp);
+
+    log::info!("Backing up current audio settings to: {}", backup_dir);
+
+    fs::create_dir_all(&backup_dir)
+        .map_err(|e: std::io::Error| format!("Failed to create backup directory: {}", e))?;
+
+    // Backup PipeWire configs
+    let pw_dirs = ["/etc/pipewire", &format!("{}/pipewire", user_config_dir())];
+
+    for dir in &pw_dirs {
+        if Path::new(dir).exists() {
+            let backup_subdir = format!("{}/{}", backup_dir, dir.replace('/', "_"));
+            fs::create_dir_all(&backup_subdir).map_err(|e: std::io::Error| {
+                format!("Failed to create backup subdirectory: {}", e)
+            })?;
+
+            // Copy config files
+            if let Ok(entries) = fs::read_dir(dir) {
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    if path.is_file()
+                        && path.extension().is_some_and(|ext| ext == "conf")
+                        && let Some(filename) = path.file_name()
+                    {
+                        let dest = format!("{}/{}", backup_subdir, filename.to_string_lossy());
+                        let _ = fs::copy(&path, &dest);
+                    }
+                }
+            }
+        }
+    }
+
+    log::info!("✓ Settings backed up to: {}", backup_dir);
+    Ok(())
+}
+
+/// Backup current configuration before making changes
+fn backup_current_config(config_dir: &str) -> Result<(), String> {
+    // Skip backup for system directories to avoid permission issues
+    // The backup is just a safety measure, not critical
+    if config_dir.starts_with("/etc/") {
+        log::info!("Skipping backup for system directory {}", config_dir);
+        return Ok(());
+    }
+
+    let timestamp = Local::now().format("%Y%m%d_%H%M%S").to_string();
+    let backup_dir = backup_dir_for(config_dir, &timestamp);
+
+    // Remove exclusive mode configurations
+    cleanup_audio_configs(false, "pipewire", "exclusive")?;
+    cleanup_audio_configs(false, "wireplumber", "exclusive")?;
+
+    // Create backup directory
+    create_dir_all_with_privileges(&backup_dir)?;
+
+    // Copy existing configs to backup
+    if Path::new(config_dir).exists()
+        && let Ok(entries) = fs::read_dir(config_dir)
+    {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_file() && path.extension().is_some_and(|ext| ext == "conf") {
+                let filename = path.file_name().unwrap_or_default();
+                let backup_path = format!("{}/{}", backup_dir, filename.to_string_lossy());
+                if let Err(e) = fs::copy(&path, &backup_path) {
+                    log::warn!("Failed to backup {:?}: {}", path, e);
+                }
+            }
+        }
+    }
+
+    log::info!("✓ Configuration backed up to: {}", backup_dir);
+    Ok(())
+}
+
+/// Backups normally go next to the configs. A config dir reached through a
+/// symlink usually lives in a dotfiles repo, so its backups go to the app's
+/// data dir instead of cluttering the repo.
+fn backup_dir_for(config_dir: &str, timestamp: &str) -> String {
+    let Some(resolved) = resolve_config_link(Path::new(config_dir), Path::new(&user_config_dir()))
+    else {
+        return format!("{}/backup_{}", config_dir, timestamp);
+    };
+
+    let data_dir = directories::ProjectDirs::from("com", "proaudioconfig", "Pro Audio Config")
+        .map(|dirs| dirs.data_dir().to_path_buf())
+        .unwrap_or_else(std::env::temp_dir);
+    let dir_name = resolved
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "config".to_string());
+
+    format!(
+        "{}/backups/{}_backup_{}",
+        data_dir.display(),
+        dir_name,
+        timestamp
+    )
+}
+
+/// Backups made before applies, newest first: the `backup_<time>` dirs next
+/// to the user configs, those in the app's data dir (symlinked config dirs)
+/// and the `/tmp/pro-audio-backup-<time>` snapshots
+pub fn list_config_backups() -> Vec<ConfigBackup> {
+    let data_backups = directories::ProjectDirs::from("com", "proaudioconfig", "Pro Audio Config")
+        .map(|dirs| dirs.data_dir().join("backups"));
+
+    collect_backups(
+        Path::new(&user_config_dir()),
+        data_backups.as_deref(),
+        &std::env::temp_dir(),
+    )
+}
+
+fn collect_backups(
+    user_config: &Path,
+    data_backups: Option<&Path>,
+    temp_dir: &Path,
+) -> Vec<ConfigBackup> {
+    let config_dirs = [
+        user_config.join("pipewire/pipewire.conf.d"),
+        user_config.join("pipewire/pipewire-pulse.conf.d"),
+        user_config.join("wireplumber/wireplumber.conf.d"),
+    ];
+    let mut backups = Vec::new();
+
+    for config_dir in &config_dirs {
+        for (path, prefix, created) in backup_dirs_in(config_dir, "backup_") {
+            if prefix.is_empty() {
+                backups.push(read_backup(path, created, Some(config_dir.clone())));
+            }
+        }
+    }
+
+    // Named after the symlinked dir, e.g. "pipewire.conf.d_backup_<time>"
+    if let Some(data_backups) = data_backups {
+        for (path, prefix, created) in backup_dirs_in(data_backups, "backup_") {
+            let dir_name = prefix.trim_end_matches('_');
+            let origin = config_dirs
+                .iter()
+                .find(|dir| dir.file_name().is_some_and(|name| name == dir_name))
+                .cloned();
+            backups.push(read_backup(path, created, origin));
+        }
+    }
+
+    // One subdirectory per source dir, its path with '/' replaced by '_'
+    for (path, prefix, created) in backup_dirs_in(temp_dir, "backup-") {
+        if prefix != "pro-audio-" {
+            continue;
+        }
+        let Ok(entries) = fs::read_dir(&path) else {
+            continue;
+        };
+        for entry in entries.flatten().filter(|entry| entry.path().is_dir()) {
+            let source = PathBuf::from(entry.file_name().to_string_lossy().replace('_', "/"));
+            let origin = source.is_dir().then_some(source);
+            backups.push(read_backup(entry.path(), created, origin));
+        }
+    }
+
+    backups.sort_by(|a, b| b.created.cmp(&a.created).then(a.path.cmp(&b.path)));
+    backups
+}
+
+/// Subdirectories of `dir` named `<prefix><marker><%Y%m%d_%H%M%S>`
+fn backup_dirs_in(dir: &Path, marker: &str) -> Vec<(PathBuf, String, NaiveDateTime)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let (prefix, timestamp) = name.rsplit_once(marker)?;
+            let created = NaiveDateTime::parse_from_str(timestamp, "%Y%m%d_%H%M%S").ok()?;
+            Some((entry.path(), prefix.to_string(), created))
+        })
+        .collect()
+}
+
+fn read_backup(path: PathBuf, created: NaiveDateTime, origin: Option<PathBuf>) -> ConfigBackup {
+    let mut files: Vec<PathBuf> = fs::read_dir(&path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|file| file.is_file() && file.extension().is_some_and(|ext| ext == "conf"))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+
+    ConfigBackup {
+        path,
+        created,
+        origin,
+        files,
+    }
+}
+
+/// Copies the backup's `.conf` files back over their originals (with
+/// privileges for /etc) and restarts the audio services. Returns how many
+/// files were restored.
+pub fn restore_config_backup(backup: &ConfigBackup) -> Result<usize, AudioConfigError> {
+    let restored = copy_backup_files(backup)?;
+    restart_audio_services(false, backup.is_system())?;
+    Ok(restored)
+}
+
+/// The file copy of `restore_config_backup`, read back afterwards so a
+/// restore that did not stick is reported
+fn copy_backup_files(backup: &ConfigBackup) -> Result<usize, AudioConfigError> {
+    let origin = backup.origin.as_ref().ok_or_else(|| {
+        AudioConfigError::Other(format!(
+            "Cannot tell where the files in {} came from",
+            backup.path.display()
+        ))
+    })?;
+    if backup.files.is_empty() {
+        return Err(AudioConfigError::Other(format!(
+            "{} contains no .conf files",
+            backup.path.display()
+        )));
+    }
+
+    log::info!(
+        "Restoring {} file(s) from {} to {}",
+        backup.files.len(),
+        backup.path.display(),
+        origin.display()
+    );
+    // Restoring to /etc asks for the password once for all files
+    let mut batch = PrivilegedBatch::default();
+    batch.create_dir_all(&origin.to_string_lossy())?;
+
+    let mut restored = Vec::new();
+    for file in &backup.files {
+        let content = fs::read_to_string(file)
+            .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+        let target = origin.join(file.file_name().unwrap_or_default());
+        batch.write(&target.to_string_lossy(), &content)?;
+        restored.push((target, content));
+    }
+    batch.run()?;
+
+    let mut mismatches = Vec::new();
+    for (target, content) in &restored {
+        if fs::read_to_string(target).ok().as_deref() != Some(content.as_str()) {
+            mismatches.push(format!("{} differs from the backup", target.display()));
+        }
+    }
+
+    if !mismatches.is_empty() {
+        return Err(AudioConfigError::VerificationMismatch(mismatches));
+    }
+
+    log::info!(
+        "✓ Restored {} file(s) to {}",
+        backup.files.len(),
+        origin.display()
+    );
+    Ok(backup.files.len())
+}
+
+/// Enhanced verification for advanced settings
+fn verify_advanced_settings_applied(
+    settings: &AudioSettings,
+    system_wide: bool,
+) -> Result<(), String> {
+    log::info!("\n=== VERIFYING ADVANCED SETTINGS ===");
+
+    // Wait a bit more for everything to settle
+    std::thread::sleep(std::time::Duration::from_secs(2));
+
+    // Method 1: Check PipeWire core info with MORE DETAIL
+    log::info!("Method 1: Checking PipeWire core info in detail...");
+    let output = pipewire_command("pw-cli")
+        .arg("info")
+        .arg("0")
+        .output()
+        .map_err(|e| format!("Failed to run pw-cli: {}", e))?;
+
+    let output_str = String::from_utf8_lossy(&output.stdout);
+
+    // Look for ALL clock-related properties
+    let mut found_settings = Vec::new();
+    for line in output_str.lines() {
+        if line.contains("clock.") || line.contains("default.") {
+            found_settings.push(line.trim());
+        }
+    }
+
+    log::info!("Found clock/default settings:");
+    for setting in &found_settings {
+        log::info!("  {}", setting);
+    }
+
+    // Extract current settings more carefully
+    let mut current_rate = None;
+    let mut current_quantum = None;
+
+    for line in output_str.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('*') && trimmed.contains("default.clock.rate") {
+            if let Some(value) = extract_number_from_line(trimmed) {
+                current_rate = Some(value);
+            }
+        } else if trimmed.starts_with('*')
+            && trimmed.contains("default.clock.quantum")
+            && let Some(value) = extract_number_from_line(trimmed)
+        {
+            current_quantum = Some(value);
+        }
+    }
+
+    log::info!(
+        "\nDetected settings: rate={:?}, quantum={:?}",
+        current_rate,
+        current_quantum
+    );
+    log::info!(
+        "Expected settings: rate={}, quantum={}",
+        settings.sample_rate,
+        settings.buffer_size
+    );
+
+    // Check if settings match
+    if let (Some(rate), Some(quantum)) = (current_rate, current_quantum) {
+        if rate == settings.sample_rate && quantum == settings.buffer_size {
+            log::info!("✓ SUCCESS: Settings verified successfully via pw-cli");
+            return Ok(());
+        } else {
+            log::warn!("Settings mismatch via pw-cli");
+            log::info!("  Detected: {}Hz/{} samples", rate, quantum);
+            log::info!(
+                "  Expected: {}Hz/{} samples",
+                settings.sample_rate,
+                settings.buffer_size
+            );
+        }
+    } else {
+        log::warn!("Could not detect all settings via pw-cli");
+    }
+
+    // Method 2: Check via pactl with more detail
+    log::info!("\nMethod 2: Checking via pactl with detail...");
+    let output = Command::new("pactl")
+        .arg("info")
+        .output()
+        .map_err(|e| format!("Failed to run pactl: {}", e))?;
+
+    let pactl_output = String::from_utf8_lossy(&output.stdout);
+    log::info!("pactl info output:");
+    for line in pactl_output.lines() {
+        log::info!("  {}", line);
+    }
+
+    // Method 3: Check active configuration files in detail
+    log::info!("\nMethod 3: Checking active configuration files...");
+    let config_dir = if system_wide {
+        "/etc/pipewire/pipewire.conf.d"
+    } else {
+        &format!("{}/pipewire/pipewire.conf.d", user_config_dir())
+    };
+
+    log::info!("Config directory: {}", config_dir);
+    if let Ok(entries) = fs::read_dir(config_dir) {
+        let mut configs = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_file() && path.extension().is_some_and(|ext| ext == "conf") {
+                let filename = path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                configs.push(filename);
+            }
+        }
+        configs.sort();
+        log::info!("Active config files ({}):", configs.len());
+        for config in configs {
+            log::info!("  - {}", config);
+        }
+    }
+
+    // Method 4: Check if our specific config is being loaded
+    log::info!("\nMethod 4: Checking if our config is loaded...");
+    let our_config = format!("{}/99-pro-audio-advanced.conf", config_dir);
+    if Path::new(&our_config).exists() {
+        log::info!("✓ Our config file exists: {}", our_config);
+
+        // Check if it's the only high-priority config
+        if let Ok(entries) = fs::read_dir(config_dir) {
+            let high_priority_configs: Vec<String> = entries
+                .flatten()
+                .filter(|entry| {
+                    let path = entry.path();
+                    path.is_file()
+                        && path.extension().is_some_and(|ext| ext == "conf")
+                        && path
+                            .file_name()
+                            .unwrap_or_default()
+                            .to_string_lossy()
+                            .starts_with("99-")
+                })
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect();
+
+            log::info!("High priority configs (99-*):");
+            for config in &high_priority_configs {
+                log::info!("  - {}", config);
+            }
+
+            if high_priority_configs.len() > 1 {
+                log::warn!("Multiple high-priority configs may be conflicting");
+            }
+        }
+    } else {
+        log::warn!("ERROR: Our config file does not exist!");
+    }
+
+    // Provide detailed troubleshooting
+    let diagnostic = format!(
+        "\n=== TROUBLESHOOTING INFORMATION ===\n\
+        \n\
+        DETECTED SETTINGS:\n\
+        - Sample Rate: {}Hz (expected: {}Hz)\n\
+        - Buffer Size: {} samples (expected: {} samples)\n\
+        \n\
+        CONFIGURATION STATUS:\n\
+        - Config file: {}/99-pro-audio-advanced.conf\n\
+        - System-wide: {}\n\
+        \n\
+        IMMEDIATE TROUBLESHOOTING STEPS:\n\
+        1. Check config syntax:\n\
+           cat {}/99-pro-audio-advanced.conf | grep -E '(default.clock|audio.rate)'\n\
+        \n\
+        2. Force full restart:\n\
+           systemctl --user stop pipewire pipewire-pulse wireplumber\n\
+           pkill -9 pipewire wireplumber\n\
+           systemctl --user start pipewire pipewire-pulse wireplumber\n\
+        \n\
+        3. Check for conflicting configs:\n\
+           ls -la /usr/share/pipewire/pipewire.conf.d/\n\
+           ls -la ~/.config/pipewire/pipewire.conf.d/\n\
+           ls -la /etc/pipewire/pipewire.conf.d/\n\
+        \n\
+        4. Test with minimal config:\n\
+           echo 'context.properties = {{ default.clock.rate = {}, default.clock.quantum = {} }}' > /tmp/test.conf\n\
+           PW_CONFIG_FILE=/tmp.test.conf pipewire --verbose\n\
+        \n\
+        5. Check PipeWire logs:\n\
+           journalctl --user -u pipewire -n 50\n\
+           journalctl --user -u wireplumber -n 50",
+        current_rate.unwrap_or(0),
+        settings.sample_rate,
+        current_quantum.unwrap_or(0),
+        settings.buffer_size,
+        config_dir,
+        system_wide,
+        config_dir,
+        settings.sample_rate,
+        settings.buffer_size
+    );
+
+    log::info!("{}", diagnostic);
+
+    // Don't fail - just warn the user
+    Ok(())
+}
+
+/// Verify quantum was applied
+fn verify_quantum_applied(settings: &AudioSettings) -> Result<(), String> {
+    log::info!("\n=== QUANTUM VERIFICATION ===");
+
+    // Check with pw-cli
+    match pipewire_command("pw-cli").args(["info", "0"]).output() {
+        Ok(output) => {
+            let output_str = String::from_utf8_lossy(&output.stdout);
+            let mut found_quantum = None;
+            let mut found_rate = None;
+
+            for line in output_str.lines() {
+                let trimmed = line.trim();
+                if trimmed.contains("default.clock.quantum") {
+                    log::info!("QUANTUM LINE: {}", trimmed);
+                    found_quantum = extract_number_from_line(trimmed);
+                }
+                if trimmed.contains("default.clock.rate") {
+                    log::info!("RATE LINE: {}", trimmed);
+                    found_rate = extract_number_from_line(trimmed);
+                }
+            }
+
+            log::info!("\nRESULTS:");
+            log::info!("  Detected quantum: {:?}", found_quantum);
+            log::info!("  Detected rate: {:?}", found_rate);
+            log::info!("  Expected quantum: {}", settings.buffer_size);
+            log::info!("  Expected rate: {}", settings.sample_rate);
+
+            if found_quantum == Some(settings.buffer_size) {
+                log::info!(
+                    "✓ SUCCESS: Quantum correctly set to {}!",
+                    settings.buffer_size
+                );
+                return Ok(());
+            } else {
+                log::warn!("FAILED: Quantum not set correctly");
+                log::info!(
+                    "  The quantum-floor in /usr/share/pipewire/pipewire.conf is likely still 4"
+                );
+                log::info!(
+                    "  Try: sudo sed -i 's/default.clock.quantum-floor = 4/default.clock.quantum-floor = {}/' /usr/share/pipewire/pipewire.conf",
+                    settings.buffer_size
+                );
+            }
+        }
+        Err(e) => {
+            log::warn!("Could not run pw-cli: {}", e);
         }
-    } else {
-        let _ = Command::new("systemctl")
-            .args([
-                "--user",
-                "restart",
-                "pipewire",
-                "pipewire-pulse",
-                "wireplumber",
-            ])
-            .status();
     }
 
-    // Step 3: Start processes manually if needed
-    std::thread::sleep(std::time::Duration::from_secs(2));
+    Ok(())
+}
 
-    println!("Step 3: Checking if services are running...");
-    let mut attempts = 0;
-    let max_attempts = 10;
+/// Verifies that the settings were actually applied, recording what was
+/// detected and any mismatches in the outcome
+fn verify_settings_applied(settings: &AudioSettings, outcome: &mut ApplyOutcome) {
+    if offline_mode() {
+        log::info!("Offline, settings not verified against the running server");
+        return;
+    }
 
-    while attempts < max_attempts {
-        if check_if_services_are_running() {
-            println!(
-                "✓ Audio services are running (attempt {}/{})",
-                attempts + 1,
-                max_attempts
-            );
-            break;
+    log::info!("Verifying settings were applied...");
+
+    // Wait a bit for services to fully initialize
+    std::thread::sleep(std::time::Duration::from_secs(2));
+
+    // Get current settings
+    let current_settings = match crate::audio::detect_current_audio_settings() {
+        Ok(current) => current,
+        Err(e) => {
+            outcome.warnings.push(format!(
+                "Failed to detect current settings for verification: {}",
+                e
+            ));
+            return;
         }
+    };
 
-        attempts += 1;
-        std::thread::sleep(std::time::Duration::from_secs(1));
+    outcome.detected_rate = Some(current_settings.sample_rate);
+    outcome.detected_quantum = Some(current_settings.buffer_size);
 
-        if attempts % 3 == 0 {
-            println!("Attempting to start services manually...");
-            let _ = Command::new("pipewire").spawn();
-            let _ = Command::new("wireplumber").spawn();
+    log::info!(
+        "Current settings: {}Hz/{}bit/{} samples",
+        current_settings.sample_rate,
+        current_settings.bit_depth,
+        current_settings.buffer_size
+    );
+    log::info!(
+        "Expected settings: {}Hz/{}bit/{} samples",
+        settings.sample_rate,
+        settings.bit_depth,
+        settings.buffer_size
+    );
+
+    for check in compare_audio_settings(settings, &current_settings) {
+        if !check.matches {
+            outcome.warnings.push(check.describe());
         }
     }
 
-    if attempts >= max_attempts {
-        println!("⚠ Audio services may not have started properly");
-        println!("⚠ Try logging out and back in, or reboot");
+    outcome.verified = outcome.warnings.is_empty();
+    if outcome.verified {
+        log::info!("✓ Settings verified successfully");
+    } else {
+        log::warn!("Verification failed: {}", outcome.warnings.join("; "));
     }
+}
 
-    Ok(())
+// ----------------------------------------------------------------------------
+// Utility Functions
+// ----------------------------------------------------------------------------
+
+/// Compares expected settings with detected ones. PipeWire may adjust the
+/// quantum, so the buffer size is allowed to differ by up to 256 samples.
+fn compare_audio_settings(expected: &AudioSettings, current: &AudioSettings) -> Vec<SettingCheck> {
+    let buffer_diff = (current.buffer_size as i32 - expected.buffer_size as i32).abs();
+
+    vec![
+        SettingCheck {
+            field: "Sample rate",
+            expected: expected.sample_rate,
+            actual: current.sample_rate,
+            matches: current.sample_rate == expected.sample_rate,
+        },
+        SettingCheck {
+            field: "Buffer size",
+            expected: expected.buffer_size,
+            actual: current.buffer_size,
+            matches: buffer_diff <= 256,
+        },
+    ]
 }
 
-/// Check if audio services are running
-fn check_if_services_are_running() -> bool {
-    let services = ["pipewire", "pipewire-pulse", "wireplumber"];
+/// Whether a position is a SPA channel name such as FL, LFE or AUX3
+fn is_known_channel_position(position: &str) -> bool {
+    const CHANNEL_POSITIONS: &[&str] = &[
+        "MONO", "FL", "FR", "FC", "LFE", "SL", "SR", "FLC", "FRC", "RC", "RL", "RR", "TC", "TFL",
+        "TFC", "TFR", "TRL", "TRC", "TRR", "RLC", "RRC", "FLW", "FRW", "LFE2", "FLH", "FCH", "FRH",
+        "TFLC", "TFRC", "TSL", "TSR", "LLFE", "RLFE", "BC", "BLC", "BRC",
+    ];
 
-    // Check if any of the services are running
-    for service in &services {
-        if let Ok(output) = Command::new("pgrep").arg("-f").arg(service).output()
-            && output.status.success()
-        {
-            // At least one service is running
-            return true;
+    CHANNEL_POSITIONS.contains(&position)
+        || position
+            .strip_prefix("AUX")
+            .is_some_and(|index| index.parse::<u32>().is_ok())
+}
+
+/// Whether a managed config path is the file with the given name
+fn is_config_file_named(path: &Path, file_name: &str) -> bool {
+    path.file_name().is_some_and(|name| name == file_name)
+}
+
+/// Value of the `pro-audio-config.version` marker. A value missing its closing
+/// quote (written by 1.7) is still returned so the file gets migrated.
+fn config_format_version(content: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let value = line
+            .trim()
+            .strip_prefix("pro-audio-config.version")?
+            .trim_start()
+            .strip_prefix('=')?;
+        Some(value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// Newest recorded format change that an older `version` predates
+fn config_format_change_reason(version: &str) -> &'static str {
+    CONFIG_FORMAT_CHANGES
+        .iter()
+        .find(|(changed_in, _)| *changed_in != version)
+        .map(|(_, reason)| *reason)
+        .unwrap_or("written by an older version")
+}
+
+/// Groups that commonly grant rtprio/memlock through limits.d
+fn has_realtime_group(groups: &[String]) -> bool {
+    groups
+        .iter()
+        .any(|group| matches!(group.as_str(), "audio" | "realtime" | "pipewire"))
+}
+
+/// Reads a soft limit from /proc/self/limits, `None` meaning unlimited or unknown
+fn read_resource_limit(name: &str) -> Option<u64> {
+    let content = fs::read_to_string("/proc/self/limits").ok()?;
+    parse_resource_limit(&content, name)
+}
+
+/// Parses one row of /proc/self/limits, e.g. "Max realtime priority  0  0"
+fn parse_resource_limit(content: &str, name: &str) -> Option<u64> {
+    let line = content.lines().find(|line| line.starts_with(name))?;
+    let soft = line[name.len()..].split_whitespace().next()?;
+    soft.parse::<u64>().ok()
+}
+
+/// Collects the settings this tool manages from SPA-JSON (`key = value`)
+/// or JSON (`"key": value`) config content. The last assignment wins.
+fn parse_config_content(content: &str) -> ImportedSettings {
+    let mut imported = ImportedSettings::default();
+    let mut node_rate = None;
+
+    for line in content.lines() {
+        let Some((key, value)) = split_config_assignment(line) else {
+            continue;
+        };
+
+        match key {
+            "default.clock.rate" => imported.sample_rate = Some(value),
+            "audio.rate" => node_rate = Some(value),
+            "default.clock.quantum" => imported.quantum = Some(value),
+            "default.clock.min-quantum" => imported.min_quantum = Some(value),
+            "default.clock.max-quantum" => imported.max_quantum = Some(value),
+            "api.alsa.period-size" => imported.period_size = Some(value),
+            _ => {}
         }
     }
 
-    // Also check with systemctl as fallback
-    if let Ok(output) = Command::new("systemctl")
-        .args(["--user", "is-active", "pipewire"])
-        .output()
-        && output.status.success()
-    {
-        return true;
+    // Device rules only carry the per-node rate
+    if imported.sample_rate.is_none() {
+        imported.sample_rate = node_rate;
     }
 
-    false
+    imported
 }
 
-/// Force PipeWire to reload configuration
-#[allow(dead_code)]
-fn force_pipewire_reload() -> Result<(), String> {
-    println!("Forcing PipeWire to reload configuration...");
+/// Splits a single `key = value` / `"key": value` line with a numeric value
+pub fn split_config_assignment(line: &str) -> Option<(&str, u32)> {
+    let line = line.split('#').next()?.trim();
+    let separator = line.find(['=', ':'])?;
 
-    // Method 1: Send SIGHUP to pipewire daemon
-    if let Ok(output) = Command::new("pgrep").arg("pipewire").output()
-        && output.status.success()
-    {
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let pid_str = stdout.trim();
-        if !pid_str.is_empty() {
-            // Store the PID in a variable to avoid temporary value issues
-            let pid = pid_str.to_string();
-            let _ = Command::new("kill").args(["-HUP", &pid]).status();
-            println!("✓ Sent SIGHUP to PipeWire (PID: {})", pid);
-        }
+    let key = line[..separator].trim().trim_matches('"');
+    let value = line[separator + 1..]
+        .trim()
+        .trim_end_matches(',')
+        .trim()
+        .trim_matches('"');
+
+    Some((key, value.parse::<u32>().ok()?))
+}
+
+/// Helper to extract numbers from config lines
+fn extract_number_from_line(line: &str) -> Option<u32> {
+    // Handle lines like: *		default.clock.rate = "48000"
+    let line = line.trim_start_matches('*').trim();
+    let parts: Vec<&str> = line.split('=').collect();
+    if parts.len() >= 2 {
+        parts[1].trim().trim_matches('"').parse::<u32>().ok()
+    } else {
+        None
     }
+}
 
-    // Method 2: Use pw-cli to reload
-    let _ = Command::new("pw-cli").arg("info").arg("0").output();
+/// Command lines `quantum_verified_restart` runs, for previews; the
+/// professional apply restarts this way instead of `plan_restart`
+pub fn plan_quantum_verified_restart(system_wide: bool) -> Vec<String> {
+    let start: &[&str] = if system_wide {
+        &["systemctl --user restart pipewire wireplumber"]
+    } else {
+        &["pipewire &", "wireplumber &"]
+    };
+    ["pkill -9 pipewire", "pkill -9 wireplumber"]
+        .iter()
+        .chain(start)
+        .map(|command| command.to_string())
+        .collect()
+}
 
-    // Method 3: Use pactl to trigger reload
-    let _ = Command::new("pactl").arg("info").output();
+/// Restart with quantum verification. Keep `plan_quantum_verified_restart`
+/// in step with the commands run here.
+fn quantum_verified_restart(system_wide: bool) -> Result<(), String> {
+    // Kill
+    let _ = Command::new("pkill").arg("-9").arg("pipewire").status();
+    let _ = Command::new("pkill").arg("-9").arg("wireplumber").status();
 
-    // Method 4: Touch config file to trigger reload
-    let username = whoami::username();
-    let config_path = format!("/home/{}/.config/pipewire/pipewire.conf", username);
-    if Path::new(&config_path).exists() {
-        let _ = Command::new("touch").arg(&config_path).status();
+    std::thread::sleep(std::time::Duration::from_secs(2));
+
+    // Start
+    if system_wide {
+        let _ = Command::new("systemctl")
+            .args(["--user", "restart", "pipewire", "wireplumber"])
+            .status();
+    } else {
+        let _ = pipewire_command("pipewire").spawn();
+        std::thread::sleep(std::time::Duration::from_millis(500));
+        let _ = pipewire_command("wireplumber").spawn();
     }
 
+    std::thread::sleep(std::time::Duration::from_secs(3));
     Ok(())
 }
 
-/// Improved unified function to restart audio services with timeout
-fn restart_audio_services(use_legacy: bool, system_wide: bool) -> Result<(), String> {
-    println!("Restarting audio services...");
-    let start_time = Instant::now();
+// ============================================================================
+// TESTS
+// ============================================================================
 
-    let username = whoami::username();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipewire_config::parse_spa_json;
 
-    if system_wide {
-        // For system-wide config changes, restart the user services for the target user.
-        println!(
-            "Restarting user audio services for user '{}' with privileges...",
-            username
+    /// The environment is process-wide; tests that change it hold this
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn test_audio_settings_struct() {
+        let settings = AudioSettings {
+            sample_rate: 96000,
+            bit_depth: 24,
+            buffer_size: 512,
+            device_id: "test-device".to_string(),
+            allowed_rates: Vec::new(),
+            node_description: None,
+            period_size: None,
+            channels: 2,
+            position: Vec::new(),
+        };
+
+        assert_eq!(settings.sample_rate, 96000);
+        assert_eq!(settings.bit_depth, 24);
+        assert_eq!(settings.buffer_size, 512);
+    }
+
+    #[test]
+    fn test_apply_outcome_summary() {
+        let mut outcome = ApplyOutcome::new(ApplyMethod::PipeWireFragment);
+        assert!(!outcome.verified);
+        assert!(outcome.summary().contains("not verified"));
+
+        outcome.verified = true;
+        outcome.detected_rate = Some(48000);
+        outcome.detected_quantum = Some(512);
+        let summary = outcome.summary();
+        assert!(summary.contains("confirmed"));
+        assert!(summary.contains("48000Hz/512 samples"));
+    }
+
+    #[test]
+    fn test_validate_lua_config() {
+        let valid = r#"
+-- Pro Audio Config rule
+rule = {
+  matches = {
+    {
+      { "node.name", "matches", "alsa_output.*" },
+    },
+  },
+  apply_properties = {
+    ["audio.rate"] = 48000,
+    ["api.alsa.period-size"] = 256,
+  },
+}
+
+table.insert(alsa_monitor.rules, rule)
+"#;
+        assert!(validate_lua_config(valid).is_ok());
+
+        let unbalanced = valid.replacen("apply_properties = {", "apply_properties = ", 1);
+        assert!(validate_lua_config(&unbalanced).is_err());
+
+        let missing_rule = valid.replace("table.insert(alsa_monitor.rules, rule)", "");
+        assert!(validate_lua_config(&missing_rule).is_err());
+
+        // Brackets inside strings and comments are ignored
+        assert!(check_lua_bracket_balance("x = \"{[(\" -- })]").is_ok());
+    }
+
+    #[test]
+    fn test_write_file_atomically() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("99-test.conf");
+
+        fs::write(&path, "old content").unwrap();
+        write_file_atomically(&path, "context.properties = {}").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "context.properties = {}"
         );
 
-        // Use `sudo -u $username` to run the user service commands as that specific user.
-        let status = Command::new("sudo")
-            .args([
-                "-u",
-                &username,
-                "systemctl",
-                "--user",
-                "restart",
-                "pipewire",
-                "pipewire-pulse",
-                "wireplumber",
-            ])
-            .status()
-            .map_err(|e| format!("Failed to restart user services: {}", e))?;
+        // No temp files are left behind
+        let entries: Vec<_> = fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+    }
 
-        if !status.success() {
-            return Err("Failed to restart user audio services via systemd".to_string());
-        }
-    } else {
-        // User-only restart
-        if use_legacy {
-            // Legacy approach: Restart services individually with pauses
-            let services = ["pipewire", "pipewire-pulse", "wireplumber"];
-
-            for service in &services {
-                println!("Restarting {}...", service);
-                let status = Command::new("systemctl")
-                    .args(["--user", "restart", service])
-                    .status()
-                    .map_err(|e| format!("Failed to restart {}: {}", service, e))?;
-
-                if !status.success() {
-                    println!("Warning: Failed to restart {}", service);
-                    // Continue with other services instead of failing completely
-                }
+    #[test]
+    fn test_diff_against_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let unchanged = dir.path().join("99-unchanged.conf");
+        let changed = dir.path().join("99-changed.conf");
+        let missing = dir.path().join("99-missing.conf");
+        fs::write(&unchanged, "a = 1\n").unwrap();
+        fs::write(&changed, "a = 1\nb = 2\n").unwrap();
+
+        let diffs = diff_against_disk(vec![
+            (unchanged, "a = 1\n".to_string()),
+            (changed.clone(), "a = 1\nb = 3\n".to_string()),
+            (missing.clone(), "c = 4\n".to_string()),
+        ])
+        .unwrap();
+
+        assert_eq!(diffs.len(), 2);
+        assert_eq!(diffs[0].0, changed);
+        assert!(diffs[0].1.contains("-b = 2\n+b = 3\n"));
+        assert_eq!(diffs[1].0, missing);
+        assert!(diffs[1].1.starts_with("--- /dev/null\n"));
+        assert!(diffs[1].1.contains("+c = 4\n"));
+    }
 
-                // Brief pause between service restarts
-                std::thread::sleep(std::time::Duration::from_millis(500));
-            }
-        } else {
-            // New approach: Use systemd if available, otherwise direct commands
-            if Path::new("/run/systemd/seats").exists() {
-                println!("Using systemd to restart user services...");
-                let status = Command::new("systemctl")
-                    .args([
-                        "--user",
-                        "restart",
-                        "pipewire",
-                        "pipewire-pulse",
-                        "wireplumber",
-                    ])
-                    .status()
-                    .map_err(|e| format!("Failed to restart services: {}", e))?;
-
-                if !status.success() {
-                    return Err("Failed to restart audio services via systemd".to_string());
-                }
-            } else {
-                // Fallback: kill and let them restart automatically
-                println!("Systemd not available, using fallback restart method...");
-                Command::new("pkill").args(["-f", "pipewire"]).status().ok(); // Ignore errors here
-                Command::new("pkill")
-                    .args(["-f", "wireplumber"])
-                    .status()
-                    .ok();
-            }
-        }
+    #[test]
+    fn test_describe_failed_output() {
+        let output = Command::new("sh")
+            .args(["-c", "echo 'Not authorized' >&2; exit 127"])
+            .output()
+            .unwrap();
 
-        // Wait a moment for services to restart
-        println!("Waiting for services to restart...");
-        std::thread::sleep(std::time::Duration::from_secs(2));
+        let description = describe_failed_output("pkexec", &output);
+        assert_eq!(description, "pkexec exited with 127: Not authorized");
     }
 
-    // Check if services are actually running with timeout
-    println!("Checking if audio services are running...");
-    let max_wait_time = Duration::from_secs(10);
-    let check_interval = Duration::from_millis(500);
+    #[test]
+    fn test_realtime_privileges_warning() {
+        assert!(has_realtime_group(&[
+            "wheel".to_string(),
+            "audio".to_string()
+        ]));
+        assert!(!has_realtime_group(&["wheel".to_string()]));
+
+        let limits = "Limit                     Soft Limit           Hard Limit           Units\n\
+                      Max locked memory         8388608              8388608              bytes\n\
+                      Max realtime priority     unlimited            unlimited\n";
+        assert_eq!(
+            parse_resource_limit(limits, "Max locked memory"),
+            Some(8388608)
+        );
+        assert_eq!(parse_resource_limit(limits, "Max realtime priority"), None);
+
+        let capped = RealtimePrivileges {
+            groups: vec!["wheel".to_string()],
+            in_realtime_group: false,
+            rtprio_limit: Some(0),
+            memlock_limit: Some(8 * 1024 * 1024),
+            rtkit_available: false,
+        };
+        assert!(!capped.can_use_realtime());
+        assert!(capped.warning().unwrap().contains("usermod"));
+
+        let ok = RealtimePrivileges {
+            groups: vec!["audio".to_string()],
+            in_realtime_group: true,
+            rtprio_limit: Some(95),
+            memlock_limit: None,
+            rtkit_available: false,
+        };
+        assert!(ok.warning().is_none());
+    }
 
-    let mut elapsed = Duration::from_secs(0);
-    let mut services_running = false;
+    #[test]
+    fn test_compare_audio_settings() {
+        let expected = AudioSettings::new(48000, 24, 256, "default".to_string());
+        let drifted = AudioSettings::new(44100, 24, 1024, "default".to_string());
 
-    while elapsed < max_wait_time {
-        // Check if at least one audio service is running
-        if check_if_services_are_running() {
-            services_running = true;
-            break;
+        let checks = compare_audio_settings(&expected, &expected);
+        assert!(checks.iter().all(|check| check.matches));
+
+        let checks = compare_audio_settings(&expected, &drifted);
+        assert!(checks.iter().all(|check| !check.matches));
+        assert_eq!(
+            checks[0].describe(),
+            "✗ Sample rate: expected 48000, running 44100"
+        );
+    }
+
+    #[test]
+    fn test_parse_config_content() {
+        let spa_json = r#"
+context.properties = {
+    default.clock.rate = 96000
+    default.clock.quantum = 256   # hand tuned
+    default.clock.min-quantum = 64
+    default.clock.allowed-rates = [ 44100 48000 96000 ]
+}
+"#;
+        let imported = parse_config_content(spa_json);
+        assert_eq!(imported.sample_rate, Some(96000));
+        assert_eq!(imported.buffer_size(), Some(256));
+        assert_eq!(imported.min_quantum, Some(64));
+        assert_eq!(imported.max_quantum, None);
+
+        let json = r#"
+          "update-props": {
+            "audio.rate": 44100,
+            "api.alsa.period-size": 128
+          }
+"#;
+        let imported = parse_config_content(json);
+        assert_eq!(imported.sample_rate, Some(44100));
+        assert_eq!(imported.quantum, None);
+        assert_eq!(imported.buffer_size(), Some(128));
+
+        assert!(parse_config_content("# nothing here\n").is_empty());
+    }
+
+    #[test]
+    fn test_managed_config_paths() {
+        let user = managed_config_paths(ConfigScope::User);
+        let system = managed_config_paths(ConfigScope::System);
+        let all = managed_config_paths(ConfigScope::All);
+
+        assert_eq!(all.len(), user.len() + system.len());
+        assert!(system.iter().all(|path| path.starts_with("/etc")));
+        assert!(!user.iter().any(|path| path.starts_with("/etc")));
+
+        // Files that used to be missing from cleanup
+        for name in [
+            "99-pro-audio-advanced.conf",
+            "99-pro-audio-quantum-override.conf",
+            EXCLUSIVE_CONFIG_FILE,
+        ] {
+            assert!(system.iter().any(|path| is_config_file_named(path, name)));
         }
 
-        std::thread::sleep(check_interval);
-        elapsed += check_interval;
+        // Applies never clear the fragments made with their own tools
+        let apply = apply_config_paths(ConfigScope::User);
+        assert!(apply.len() < user.len());
+        assert!(apply.iter().all(|path| user.contains(path)));
+        for name in [
+            COMBINE_CONFIG_FILE,
+            VIRTUAL_DEVICES_CONFIG_FILE,
+            PULSE_RESAMPLE_CONFIG_FILE,
+        ] {
+            assert!(user.iter().any(|path| is_config_file_named(path, name)));
+            assert!(!apply.iter().any(|path| is_config_file_named(path, name)));
+        }
+    }
+
+    #[test]
+    fn test_create_dir_all_with_privileges_existing() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let existing = temp_dir.path().to_string_lossy().to_string();
+
+        // Repeated calls on an existing directory succeed without escalation
+        assert!(create_dir_all_with_privileges(&existing).is_ok());
+        assert!(create_dir_all_with_privileges(&existing).is_ok());
 
-        if elapsed.as_secs().is_multiple_of(2) {
-            println!(
-                "Waiting for audio services... ({:.1}s)",
-                elapsed.as_secs_f32()
-            );
-        }
+        let link = temp_dir.path().join("linked");
+        std::os::unix::fs::symlink(temp_dir.path(), &link).unwrap();
+        assert!(create_dir_all_with_privileges(&link.to_string_lossy()).is_ok());
+
+        let nested = temp_dir.path().join("a/b/c");
+        assert!(create_dir_all_with_privileges(&nested.to_string_lossy()).is_ok());
+        assert!(nested.is_dir());
+
+        let file = temp_dir.path().join("file.conf");
+        fs::write(&file, "").unwrap();
+        assert!(create_dir_all_with_privileges(&file.to_string_lossy()).is_err());
     }
 
-    if !services_running {
-        println!(
-            "⚠ Audio services did not start within timeout. They may start in the background."
+    #[test]
+    fn test_symlinked_config_dir() {
+        let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dotfiles = temp_dir.path().join("dotfiles/pipewire");
+        fs::create_dir_all(dotfiles.join("pipewire.conf.d")).unwrap();
+        let real_dotfiles = dotfiles.canonicalize().unwrap();
+
+        let config_dir = temp_dir.path().join("config");
+        fs::create_dir(&config_dir).unwrap();
+        let link = config_dir.join("pipewire");
+        std::os::unix::fs::symlink(&dotfiles, &link).unwrap();
+
+        // New files resolve into the real directory
+        let new_file = link.join("pipewire.conf.d/99-pro-audio-test.conf");
+        assert_eq!(
+            resolve_config_path(&new_file),
+            real_dotfiles.join("pipewire.conf.d/99-pro-audio-test.conf")
         );
-        println!("⚠ You can check service status with: systemctl --user status pipewire");
-        // Don't fail, just warn - the services might start later
-    } else {
-        println!("✓ Audio services are running");
-    }
+        assert_eq!(resolve_config_path(&link), real_dotfiles);
+
+        // A symlinked file stays a symlink and its target gets the content
+        let tracked = real_dotfiles.join("tracked.conf");
+        fs::write(&tracked, "old").unwrap();
+        let linked_file = link.join("pipewire.conf.d/99-linked.conf");
+        std::os::unix::fs::symlink(&tracked, &linked_file).unwrap();
+
+        write_config_with_privileges(&linked_file.to_string_lossy(), "new").unwrap();
+        assert!(
+            fs::symlink_metadata(&linked_file)
+                .unwrap()
+                .file_type()
+                .is_symlink()
+        );
+        assert_eq!(fs::read_to_string(&tracked).unwrap(), "new");
 
-    let total_time = start_time.elapsed();
-    println!(
-        "✓ Audio service restart completed in {:.1}s",
-        total_time.as_secs_f32()
-    );
+        // Backups stay out of the dotfiles repo
+        unsafe { std::env::set_var(CONFIG_DIR_ENV, &config_dir) };
+        let linked_dir = link.join("pipewire.conf.d");
+        let backup = backup_dir_for(&linked_dir.to_string_lossy(), "20260101_000000");
+        assert!(!Path::new(&backup).starts_with(&real_dotfiles));
+        assert!(backup.ends_with("pipewire.conf.d_backup_20260101_000000"));
 
-    // Additional wait for services to fully initialize
-    if services_running {
-        println!("Waiting for services to fully initialize...");
-        std::thread::sleep(std::time::Duration::from_secs(2));
+        let plain = real_dotfiles.join("pipewire.conf.d");
+        assert_eq!(
+            backup_dir_for(&plain.to_string_lossy(), "20260101_000000"),
+            format!("{}/backup_20260101_000000", plain.display())
+        );
+
+        // A symlink above the config dir, like /home -> /var/home, is not a dotfiles link
+        let real_home = temp_dir.path().join("var/home/user");
+        fs::create_dir_all(real_home.join(".config/pipewire/pipewire.conf.d")).unwrap();
+        std::os::unix::fs::symlink(
+            temp_dir.path().join("var/home"),
+            temp_dir.path().join("home"),
+        )
+        .unwrap();
+        let home_config = temp_dir.path().join("home/user/.config");
+        unsafe { std::env::set_var(CONFIG_DIR_ENV, &home_config) };
+        let fragment_dir = home_config.join("pipewire/pipewire.conf.d");
+        assert_eq!(
+            backup_dir_for(&fragment_dir.to_string_lossy(), "20260101_000000"),
+            format!("{}/backup_20260101_000000", fragment_dir.display())
+        );
+
+        unsafe { std::env::remove_var(CONFIG_DIR_ENV) };
     }
 
-    Ok(())
-}
+    #[test]
+    fn test_config_backups() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let user_config = temp_dir.path().join("config");
+        let data_backups = temp_dir.path().join("data/backups");
+        let tmp = temp_dir.path().join("tmp");
+
+        let fragment_dir = user_config.join("pipewire/pipewire.conf.d");
+        let next_to_config = fragment_dir.join("backup_20260102_093000");
+        fs::create_dir_all(&next_to_config).unwrap();
+        fs::write(
+            next_to_config.join("99-pro-audio-high-priority.conf"),
+            "context.properties = {\n    default.clock.rate = 44100\n}\n",
+        )
+        .unwrap();
+        fs::write(next_to_config.join("notes.txt"), "not a config").unwrap();
+        fs::create_dir_all(fragment_dir.join("backup_yesterday")).unwrap();
+
+        fs::create_dir_all(data_backups.join("wireplumber.conf.d_backup_20260103_000000")).unwrap();
+        fs::create_dir_all(data_backups.join("dotfiles-pw_backup_20260101_000000")).unwrap();
+        fs::create_dir_all(tmp.join("pro-audio-backup-20251231_235959/_nonexistent_pipewire"))
+            .unwrap();
+        fs::create_dir_all(tmp.join("other-backup-20251231_235959/_etc_pipewire")).unwrap();
+
+        let backups = collect_backups(&user_config, Some(&data_backups), &tmp);
+        let names: Vec<String> = backups
+            .iter()
+            .map(|backup| {
+                backup
+                    .path
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect();
+        // Newest first, malformed names and foreign /tmp dirs skipped
+        assert_eq!(
+            names,
+            vec![
+                "wireplumber.conf.d_backup_20260103_000000",
+                "backup_20260102_093000",
+                "dotfiles-pw_backup_20260101_000000",
+                "_nonexistent_pipewire",
+            ]
+        );
+        assert_eq!(
+            backups[0].origin,
+            Some(user_config.join("wireplumber/wireplumber.conf.d"))
+        );
+        assert_eq!(backups[1].origin, Some(fragment_dir.clone()));
+        assert_eq!(backups[1].files.len(), 1);
+        assert!(!backups[1].is_system());
+        assert_eq!(backups[2].origin, None);
+        assert_eq!(backups[3].origin, None);
+
+        // Restoring overwrites the current file with the backed up one
+        let current = fragment_dir.join("99-pro-audio-high-priority.conf");
+        fs::write(&current, "context.properties = {}\n").unwrap();
+        assert_eq!(copy_backup_files(&backups[1]).unwrap(), 1);
+        assert!(
+            fs::read_to_string(&current)
+                .unwrap()
+                .contains("default.clock.rate = 44100")
+        );
 
-/// Non-blocking version of restart_audio_services for use in async contexts
-pub fn restart_audio_services_non_blocking() -> Result<(), String> {
-    // Spawn a thread to handle the restart
-    std::thread::spawn(|| match restart_audio_services(false, true) {
-        Ok(_) => println!("✓ Audio services restarted successfully in background"),
-        Err(e) => println!("⚠ Failed to restart audio services: {}", e),
-    });
+        assert!(copy_backup_files(&backups[2]).is_err());
+        // No .conf files to restore
+        assert!(copy_backup_files(&backups[0]).is_err());
+    }
 
-    println!("Audio service restart initiated in background...");
-    Ok(())
-}
+    #[test]
+    fn test_pulse_resample_config() {
+        assert_eq!(resample_quality_level("fastest"), Some(0));
+        assert_eq!(resample_quality_level("highest"), Some(14));
+        assert_eq!(resample_quality_level("best"), None);
+
+        let config = generate_pulse_resample_config(10);
+        assert!(config.contains("stream.properties"));
+        assert_eq!(parse_resample_quality(&config), Some(10));
+        assert_eq!(parse_resample_quality("resample.quality = \"high\""), None);
+        assert!(
+            managed_config_paths(ConfigScope::User)
+                .iter()
+                .any(|path| is_config_file_named(path, PULSE_RESAMPLE_CONFIG_FILE))
+        );
+    }
 
-// ----------------------------------------------------------------------------
-// Verification & Backup Functions
-// ----------------------------------------------------------------------------
+    #[test]
+    fn test_minimal_safe_config() {
+        let config = generate_minimal_safe_config();
+        assert!(config.contains("default.clock.rate = 48000"));
+        assert!(config.contains("default.clock.quantum = 1024"));
+        assert_eq!(
+            config_format_version(&config).as_deref(),
+            Some(CONFIG_FORMAT_VERSION)
+        );
+        for property in ["rt.prio", "mem.mlock", "force-quantum", "node.name"] {
+            assert!(!config.contains(property));
+        }
 
-/// Backup current audio settings
-#[allow(dead_code)]
-fn backup_audio_settings() -> Result<(), String> {
-    let timestamp = Local::now().format("%Y%m%d_%H%M%S");
-    let backup_dir = format!("/tmp/pro-audio-backup-{}", timestamp);
+        assert!(
+            managed_config_paths(ConfigScope::User)
+                .iter()
+                .any(|path| is_config_file_named(path, SAFE_CONFIG_FILE))
+        );
+    }
 
-    println!("Backing up current audio settings to: {}", backup_dir);
+    #[test]
+    fn test_pulse_quantum_limits() {
+        let small = AudioSettings::new(96000, 24, 128, "default".to_string());
+        assert_eq!(pulse_clamped_quantum(&small), Some(256));
 
-    fs::create_dir_all(&backup_dir)
-        .map_err(|e: std::io::Error| format!("Failed to create backup directory: {}", e))?;
+        let fine = AudioSettings::new(48000, 24, 128, "default".to_string());
+        assert_eq!(pulse_clamped_quantum(&fine), None);
 
-    // Backup PipeWire configs
-    let username = whoami::username();
-    let pw_dirs = [
-        "/etc/pipewire",
-        &format!("/home/{}/.config/pipewire", username),
-    ];
+        let config = generate_pipewire_pulse_config(&small);
+        assert!(config.contains("pulse.min.quantum = 128/96000"));
+        assert!(config.contains("pulse.min.req = 128/96000"));
+    }
 
-    for dir in &pw_dirs {
-        if Path::new(dir).exists() {
-            let backup_subdir = format!("{}/{}", backup_dir, dir.replace('/', "_"));
-            fs::create_dir_all(&backup_subdir).map_err(|e: std::io::Error| {
-                format!("Failed to create backup subdirectory: {}", e)
-            })?;
+    #[test]
+    fn test_channel_positions() {
+        assert_eq!(default_channel_positions(1), vec!["MONO"]);
+        assert_eq!(default_channel_positions(6).len(), 6);
+        assert_eq!(default_channel_positions(5)[4], "AUX4");
 
-            // Copy config files
-            if let Ok(entries) = fs::read_dir(dir) {
-                for entry in entries.flatten() {
-                    let path = entry.path();
-                    if path.is_file()
-                        && path.extension().is_some_and(|ext| ext == "conf")
-                        && let Some(filename) = path.file_name()
-                    {
-                        let dest = format!("{}/{}", backup_subdir, filename.to_string_lossy());
-                        let _ = fs::copy(&path, &dest);
-                    }
-                }
-            }
-        }
+        assert_eq!(
+            parse_channel_positions("[ fl, FR ]", 2).unwrap(),
+            vec!["FL", "FR"]
+        );
+        assert_eq!(
+            parse_channel_positions("FL FR RL RR", 4).unwrap(),
+            default_channel_positions(4)
+        );
+        assert!(parse_channel_positions("FL, FR", 1).is_err());
+        assert!(parse_channel_positions("FL, XX", 2).is_err());
     }
 
-    println!("✓ Settings backed up to: {}", backup_dir);
-    Ok(())
-}
+    #[test]
+    fn test_exclusive_config_has_rule_per_app() {
+        let apps = vec![
+            ExclusiveApp::new("Reaper", "reaper"),
+            ExclusiveApp::new("Surge XT", ""),
+        ];
+        let content = generate_pipewire_exclusive_config(
+            "alsa_output.usb",
+            64,
+            48000,
+            "S32LE",
+            &default_channel_positions(2),
+            &apps,
+        );
 
-/// Backup current configuration before making changes
-fn backup_current_config(config_dir: &str) -> Result<(), String> {
-    // Skip backup for system directories to avoid permission issues
-    // The backup is just a safety measure, not critical
-    if config_dir.starts_with("/etc/") {
-        println!("Note: Skipping backup for system directory {}", config_dir);
-        return Ok(());
+        assert!(content.contains("application.process.binary = \"reaper\""));
+        assert!(content.contains("application.name = \"Surge XT\""));
+        assert_eq!(content.matches("pro-audio.exclusive-app = ").count(), 2);
+        assert_eq!(parse_exclusive_apps(&content), apps);
+        assert!(parse_exclusive_apps("# no apps here").is_empty());
     }
 
-    let timestamp = Local::now().format("%Y%m%d_%H%M%S");
-    let backup_dir = format!("{}/backup_{}", config_dir, timestamp);
+    #[test]
+    fn test_exclusive_config_quoted_app_name() {
+        let apps = vec![ExclusiveApp::new("Surge \"XT\" \\ Beta", "")];
+        let content = generate_pipewire_exclusive_config(
+            "alsa_output.usb",
+            64,
+            48000,
+            "S32LE",
+            &default_channel_positions(2),
+            &apps,
+        );
+        let parsed = parse_spa_json(&content).unwrap();
 
-    // Remove exclusive mode configurations
-    cleanup_audio_configs(false, "pipewire", "exclusive")?;
-    cleanup_audio_configs(false, "wireplumber", "exclusive")?;
+        assert_eq!(
+            parsed["context.rules"][1]["matches"][0]["application.name"],
+            "Surge \"XT\" \\ Beta"
+        );
+        assert_eq!(
+            parsed["context.properties"]["pro-audio-config.target-apps"][0],
+            "Surge \"XT\" \\ Beta"
+        );
+    }
 
-    // Create backup directory
-    create_dir_all_with_privileges(&backup_dir)?;
+    #[test]
+    fn test_exclusive_config_targets_selected_device() {
+        let devices = vec![AudioDevice {
+            name: "alsa_output.usb-Focusrite_Scarlett_2i2".to_string(),
+            description: "Scarlett 2i2 USB".to_string(),
+            id: "pipewire:42".to_string(),
+            device_type: crate::audio::DeviceType::Output,
+            available: true,
+        }];
+        let target = exclusive_target_node("pipewire:42", &devices).unwrap();
+        assert_eq!(target, "alsa_output.usb-Focusrite_Scarlett_2i2");
+        assert!(exclusive_target_node("pipewire:7", &devices).is_none());
+
+        let apps = vec![ExclusiveApp::new("Bitwig Studio", "bitwig-studio")];
+        let surround = default_channel_positions(6);
+        let content =
+            generate_pipewire_exclusive_config(&target, 128, 48000, "S32LE", &surround, &apps);
+        let parsed = parse_spa_json(&content).unwrap();
 
-    // Copy existing configs to backup
-    if Path::new(config_dir).exists()
-        && let Ok(entries) = fs::read_dir(config_dir)
-    {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_file() && path.extension().is_some_and(|ext| ext == "conf") {
-                let filename = path.file_name().unwrap_or_default();
-                let backup_path = format!("{}/{}", backup_dir, filename.to_string_lossy());
-                if let Err(e) = fs::copy(&path, &backup_path) {
-                    println!("Warning: Failed to backup {:?}: {}", path, e);
-                }
-            }
-        }
+        let stream_rule = &parsed["context.rules"][1];
+        assert_eq!(
+            stream_rule["matches"][0]["application.process.binary"],
+            "bitwig-studio"
+        );
+        assert_eq!(
+            stream_rule["actions"]["update-props"]["target.object"],
+            "alsa_output.usb-Focusrite_Scarlett_2i2"
+        );
+        assert_eq!(
+            parsed["context.rules"][0]["matches"][0]["node.name"],
+            "~alsa_output.usb-Focusrite_Scarlett_2i2"
+        );
+        let device_props = &parsed["context.rules"][0]["actions"]["update-props"];
+        assert_eq!(device_props["audio.channels"], 6);
+        assert_eq!(device_props["audio.position"][2], "FC");
     }
 
-    println!("✓ Configuration backed up to: {}", backup_dir);
-    Ok(())
-}
+    #[test]
+    fn test_config_format_version() {
+        let current = format!(
+            "    pro-audio-config.version = \"{}\"\n",
+            CONFIG_FORMAT_VERSION
+        );
+        assert_eq!(
+            config_format_version(&current).as_deref(),
+            Some(CONFIG_FORMAT_VERSION)
+        );
+        // 1.7 exclusive configs lacked the closing quote
+        assert_eq!(
+            config_format_version("pro-audio-config.version = \"1.7\n").as_deref(),
+            Some("1.7")
+        );
+        assert_eq!(config_format_version("default.clock.rate = 48000"), None);
+
+        let exclusive = generate_pipewire_exclusive_config(
+            "alsa.*",
+            128,
+            48000,
+            "S24LE",
+            &default_channel_positions(2),
+            &[],
+        );
+        assert_eq!(
+            config_format_version(&exclusive).as_deref(),
+            Some(CONFIG_FORMAT_VERSION)
+        );
+    }
 
-/// Enhanced verification for advanced settings
-fn verify_advanced_settings_applied(
-    settings: &AudioSettings,
-    system_wide: bool,
-) -> Result<(), String> {
-    println!("\n=== VERIFYING ADVANCED SETTINGS ===");
+    #[test]
+    fn test_combine_sink_config() {
+        let members = vec![
+            "alsa_output.pci-0000_00_1f.3.hdmi-stereo".to_string(),
+            "alsa_output.usb-Headphones".to_string(),
+        ];
+        let content = generate_combine_sink_config(&members);
 
-    // Wait a bit more for everything to settle
-    std::thread::sleep(std::time::Duration::from_secs(2));
+        assert!(content.contains("libpipewire-module-combine-stream"));
+        assert!(content.contains(&format!("node.name = \"{}\"", COMBINE_SINK_NAME)));
+        for member in &members {
+            assert!(content.contains(&format!("node.name = \"{}\"", member)));
+            assert!(content.contains(&format!("{} {}", COMBINE_MEMBER_MARKER, member)));
+        }
+    }
 
-    // Method 1: Check PipeWire core info with MORE DETAIL
-    println!("Method 1: Checking PipeWire core info in detail...");
-    let output = Command::new("pw-cli")
-        .arg("info")
-        .arg("0")
-        .output()
-        .map_err(|e| format!("Failed to run pw-cli: {}", e))?;
+    #[test]
+    fn test_virtual_devices_config() {
+        assert_eq!(
+            virtual_sink_node_name(" Stream Mix "),
+            "pro_audio_virtual_stream_mix"
+        );
 
-    let output_str = String::from_utf8_lossy(&output.stdout);
+        let names = vec!["Stream Mix".to_string(), "Voice Chat".to_string()];
+        let content = generate_virtual_devices_config(&names);
+        assert_eq!(parse_virtual_devices(&content), names);
 
-    // Look for ALL clock-related properties
-    let mut found_settings = Vec::new();
-    for line in output_str.lines() {
-        if line.contains("clock.") || line.contains("default.") {
-            found_settings.push(line.trim());
-        }
+        let parsed = parse_spa_json(&content).unwrap();
+        let sink = &parsed["context.objects"][1]["args"];
+        assert_eq!(sink["factory.name"], "support.null-audio-sink");
+        assert_eq!(sink["node.name"], "pro_audio_virtual_voice_chat");
+        assert_eq!(sink["node.description"], "Voice Chat");
+        assert_eq!(sink["media.class"], "Audio/Sink");
     }
 
-    println!("Found clock/default settings:");
-    for setting in &found_settings {
-        println!("  {}", setting);
+    #[test]
+    fn test_asoundrc_block_keeps_user_content() {
+        let user = "pcm.!default {\n    type hw\n    card 1\n}\n";
+        let settings =
+            AudioSettings::new(96000, 24, 256, "alsa:hw:1".to_string()).with_period_size(Some(64));
+        let block = generate_asoundrc_block(&settings, "output", 1);
+        assert!(block.contains("pcm \"hw:1,0\""));
+        assert!(block.contains("period_size 64"));
+        assert!(block.contains("buffer_size 128"));
+        assert!(block.contains("defaults.pcm.dmix.rate 96000"));
+        assert!(!block.contains("pcm.!default"));
+
+        let written = replace_managed_block(user, "output", Some(&block));
+        assert!(written.starts_with(user));
+        assert!(written.contains(&format!("pcm.{}", DIRECT_ALSA_PLAYBACK_PCM)));
+
+        // Applying again replaces the block instead of adding a second one
+        let rewritten = replace_managed_block(&written, "output", Some(&block));
+        assert_eq!(rewritten, written);
+
+        let with_input = replace_managed_block(
+            &written,
+            "input",
+            Some(&generate_asoundrc_block(&settings, "input", 1)),
+        );
+        assert!(with_input.contains(&format!("pcm.{}", DIRECT_ALSA_CAPTURE_PCM)));
+        let output_only = replace_managed_block(&with_input, "input", None);
+        assert_eq!(output_only, written);
+
+        assert_eq!(replace_managed_block(&written, "output", None), user);
     }
 
-    // Extract current settings more carefully
-    let mut current_rate = None;
-    let mut current_quantum = None;
+    #[test]
+    fn test_device_config_node_description() {
+        let settings = AudioSettings::new(48000, 24, 512, "default".to_string());
+        let config = generate_wireplumber_device_config(
+            &settings,
+            "output",
+            "alsa_output.usb-Focusrite",
+            "S24LE",
+        );
+        assert!(!config.contains("node.description"));
+
+        let named = settings.with_node_description("Studio \"Mains\"");
+        let config = generate_wireplumber_device_config(
+            &named,
+            "output",
+            "alsa_output.usb-Focusrite",
+            "S24LE",
+        );
+        assert!(config.contains(r#""node.description": "Studio \"Mains\"","#));
 
-    for line in output_str.lines() {
-        let trimmed = line.trim();
-        if trimmed.starts_with('*') && trimmed.contains("default.clock.rate") {
-            if let Some(value) = extract_number_from_line(trimmed) {
-                current_rate = Some(value);
-            }
-        } else if trimmed.starts_with('*')
-            && trimmed.contains("default.clock.quantum")
-            && let Some(value) = extract_number_from_line(trimmed)
-        {
-            current_quantum = Some(value);
-        }
+        let config = generate_wireplumber_device_config(&named, "output", "~alsa.*", "S24LE");
+        assert!(!config.contains("node.description"));
     }
 
-    println!(
-        "\nDetected settings: rate={:?}, quantum={:?}",
-        current_rate, current_quantum
-    );
-    println!(
-        "Expected settings: rate={}, quantum={}",
-        settings.sample_rate, settings.buffer_size
-    );
+    #[test]
+    fn test_device_config_period_size() {
+        let settings = AudioSettings::new(48000, 24, 512, "default".to_string());
+        let rules = |settings: &AudioSettings| {
+            let config =
+                generate_wireplumber_device_config(settings, "output", "alsa_output.usb", "S24LE");
+            WirePlumberConfig::parse(&config, &["alsa-monitor", "rules"]).unwrap()
+        };
 
-    // Check if settings match
-    if let (Some(rate), Some(quantum)) = (current_rate, current_quantum) {
-        if rate == settings.sample_rate && quantum == settings.buffer_size {
-            println!("✓ SUCCESS: Settings verified successfully via pw-cli");
-            return Ok(());
-        } else {
-            println!("⚠ WARNING: Settings mismatch via pw-cli");
-            println!("  Detected: {}Hz/{} samples", rate, quantum);
-            println!(
-                "  Expected: {}Hz/{} samples",
-                settings.sample_rate, settings.buffer_size
-            );
-        }
-    } else {
-        println!("⚠ WARNING: Could not detect all settings via pw-cli");
+        let same = rules(&settings);
+        assert_eq!(same[0].actions.update_props["api.alsa.period-size"], 512);
+
+        let split = rules(&settings.with_period_size(Some(256)));
+        assert_eq!(split[0].actions.update_props["api.alsa.period-size"], 256);
+        assert_eq!(split[0].actions.update_props["audio.rate"], 48000);
     }
 
-    // Method 2: Check via pactl with more detail
-    println!("\nMethod 2: Checking via pactl with detail...");
-    let output = Command::new("pactl")
-        .arg("info")
-        .output()
-        .map_err(|e| format!("Failed to run pactl: {}", e))?;
+    #[test]
+    fn test_device_config_channel_layout() {
+        let stereo = AudioSettings::new(48000, 24, 512, "default".to_string());
+        let config =
+            generate_wireplumber_device_config(&stereo, "output", "alsa_output.usb", "S24LE");
+        let rules = WirePlumberConfig::parse(&config, &["alsa-monitor", "rules"]).unwrap();
+        assert_eq!(rules[0].actions.update_props["audio.channels"], 2);
+        assert_eq!(
+            rules[0].actions.update_props["audio.position"],
+            serde_json::json!(["FL", "FR"])
+        );
 
-    let pactl_output = String::from_utf8_lossy(&output.stdout);
-    println!("pactl info output:");
-    for line in pactl_output.lines() {
-        println!("  {}", line);
+        let surround = stereo.with_channels(6, Vec::new());
+        let config =
+            generate_wireplumber_device_config(&surround, "output", "alsa_output.usb", "S24LE");
+        let rules = WirePlumberConfig::parse(&config, &["alsa-monitor", "rules"]).unwrap();
+        assert_eq!(rules[0].actions.update_props["audio.channels"], 6);
+        assert_eq!(rules[0].actions.update_props["audio.position"][3], "LFE");
+
+        let custom = stereo.with_channels(2, vec!["AUX0".to_string(), "AUX1".to_string()]);
+        let parsed = parse_spa_json(&generate_pipewire_fragment(&custom)).unwrap();
+        assert_eq!(
+            parsed["context.properties"]["pro-audio-config.position"],
+            serde_json::json!(["AUX0", "AUX1"])
+        );
     }
 
-    // Method 3: Check active configuration files in detail
-    println!("\nMethod 3: Checking active configuration files...");
-    let config_dir = if system_wide {
-        "/etc/pipewire/pipewire.conf.d"
-    } else {
-        let username = whoami::username();
-        &format!("/home/{}/.config/pipewire/pipewire.conf.d", username)
-    };
+    #[test]
+    fn test_pipewire_fragments_load() {
+        let settings = AudioSettings::new(96000, 24, 128, "default".to_string());
 
-    println!("Config directory: {}", config_dir);
-    if let Ok(entries) = fs::read_dir(config_dir) {
-        let mut configs = Vec::new();
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_file() && path.extension().is_some_and(|ext| ext == "conf") {
-                let filename = path
-                    .file_name()
-                    .unwrap_or_default()
-                    .to_string_lossy()
-                    .to_string();
-                configs.push(filename);
-            }
-        }
-        configs.sort();
-        println!("Active config files ({}):", configs.len());
-        for config in configs {
-            println!("  - {}", config);
-        }
+        let basic = generate_pipewire_fragment(&settings);
+        let parsed = parse_spa_json(&basic).unwrap();
+        assert_eq!(parsed["context.properties"]["default.clock.rate"], 96000);
+        assert_eq!(parsed["context.modules"][0]["args"]["rt.prio"], 88);
+        assert_eq!(
+            config_format_version(&basic).as_deref(),
+            Some(CONFIG_FORMAT_VERSION)
+        );
+
+        let advanced = generate_advanced_pipewire_config(
+            &settings,
+            "realtime",
+            true,
+            0,
+            false,
+            false,
+            "high",
+            "monotonic",
+        );
+        let parsed = parse_spa_json(&advanced).unwrap();
+        let properties = &parsed["context.properties"];
+        assert_eq!(properties["default.clock.quantum-floor"], 128);
+        assert_eq!(properties["default.clock.quantum-limit"], 512);
+        assert_eq!(properties["mem.allow-mlock"], true);
+        assert_eq!(properties["session.suspend-timeout-seconds"], 0);
+        assert_eq!(parsed["context.modules"][0]["args"]["rt.prio"], 99);
+        let sink = &parsed["context.objects"][0]["args"];
+        assert_eq!(sink["node.name"], "quantum-test-128");
+        assert_eq!(sink["audio.channels"], 2);
+
+        // The import reads the same files back
+        let imported = parse_config_content(&advanced);
+        assert_eq!(imported.sample_rate, Some(96000));
+        assert_eq!(imported.min_quantum, Some(128));
+        assert_eq!(imported.max_quantum, Some(256));
     }
 
-    // Method 4: Check if our specific config is being loaded
-    println!("\nMethod 4: Checking if our config is loaded...");
-    let our_config = format!("{}/99-pro-audio-advanced.conf", config_dir);
-    if Path::new(&our_config).exists() {
-        println!("✓ Our config file exists: {}", our_config);
+    #[test]
+    fn test_preview_audio_settings() {
+        let settings = AudioSettings::new(96000, 24, 128, "default".to_string());
+        let files = preview_audio_settings(&settings, true).unwrap();
+        let (path, content) = &files[0];
+        assert_eq!(
+            path,
+            Path::new("/etc/pipewire/pipewire.conf.d/99-pro-audio-high-priority.conf")
+        );
+        assert_eq!(content, &generate_pipewire_fragment(&settings));
 
-        // Check if it's the only high-priority config
-        if let Ok(entries) = fs::read_dir(config_dir) {
-            let high_priority_configs: Vec<String> = entries
-                .flatten()
-                .filter(|entry| {
-                    let path = entry.path();
-                    path.is_file()
-                        && path.extension().is_some_and(|ext| ext == "conf")
-                        && path
-                            .file_name()
-                            .unwrap_or_default()
-                            .to_string_lossy()
-                            .starts_with("99-")
-                })
-                .map(|entry| entry.file_name().to_string_lossy().to_string())
-                .collect();
+        let invalid = AudioSettings::new(12345, 24, 128, "default".to_string());
+        assert!(preview_audio_settings(&invalid, true).is_err());
+    }
 
-            println!("High priority configs (99-*):");
-            for config in &high_priority_configs {
-                println!("  - {}", config);
-            }
+    #[test]
+    fn test_main_quantum_floor() {
+        let content = "context.properties = {\n    default.clock.quantum-floor = 4\n    default.clock.min-quantum = 32\n}\n";
+        assert_eq!(
+            set_main_quantum_floor(content, 256),
+            "context.properties = {\n    default.clock.quantum-floor = 256\n    default.clock.min-quantum = 256\n}\n"
+        );
 
-            if high_priority_configs.len() > 1 {
-                println!("⚠ WARNING: Multiple high-priority configs may be conflicting");
-            }
-        }
-    } else {
-        println!("⚠ ERROR: Our config file does not exist!");
+        // Added after the quantum when the floor is missing
+        let content = "context.properties = {\n    default.clock.quantum = 1024\n}\n";
+        assert_eq!(
+            set_main_quantum_floor(content, 256),
+            "context.properties = {\n    default.clock.quantum = 1024\n    default.clock.quantum-floor = 256\n    default.clock.min-quantum = 256\n}\n"
+        );
     }
 
-    // Provide detailed troubleshooting
-    let diagnostic = format!(
-        "\n=== TROUBLESHOOTING INFORMATION ===\n\
-        \n\
-        DETECTED SETTINGS:\n\
-        - Sample Rate: {}Hz (expected: {}Hz)\n\
-        - Buffer Size: {} samples (expected: {} samples)\n\
-        \n\
-        CONFIGURATION STATUS:\n\
-        - Config file: {}/99-pro-audio-advanced.conf\n\
-        - System-wide: {}\n\
-        \n\
-        IMMEDIATE TROUBLESHOOTING STEPS:\n\
-        1. Check config syntax:\n\
-           cat {}/99-pro-audio-advanced.conf | grep -E '(default.clock|audio.rate)'\n\
-        \n\
-        2. Force full restart:\n\
-           systemctl --user stop pipewire pipewire-pulse wireplumber\n\
-           pkill -9 pipewire wireplumber\n\
-           systemctl --user start pipewire pipewire-pulse wireplumber\n\
-        \n\
-        3. Check for conflicting configs:\n\
-           ls -la /usr/share/pipewire/pipewire.conf.d/\n\
-           ls -la ~/.config/pipewire/pipewire.conf.d/\n\
-           ls -la /etc/pipewire/pipewire.conf.d/\n\
-        \n\
-        4. Test with minimal config:\n\
-           echo 'context.properties = {{ default.clock.rate = {}, default.clock.quantum = {} }}' > /tmp/test.conf\n\
-           PW_CONFIG_FILE=/tmp.test.conf pipewire --verbose\n\
-        \n\
-        5. Check PipeWire logs:\n\
-           journalctl --user -u pipewire -n 50\n\
-           journalctl --user -u wireplumber -n 50",
-        current_rate.unwrap_or(0),
-        settings.sample_rate,
-        current_quantum.unwrap_or(0),
-        settings.buffer_size,
-        config_dir,
-        system_wide,
-        config_dir,
-        settings.sample_rate,
-        settings.buffer_size
-    );
+    #[test]
+    fn test_rt_time_limits_consistent() {
+        let settings = AudioSettings::new(48000, 24, 256, "default".to_string());
+        let fragments = [
+            generate_pipewire_fragment(&settings),
+            generate_advanced_pipewire_config(
+                &settings,
+                "high",
+                true,
+                DEFAULT_SUSPEND_TIMEOUT_SECONDS,
+                false,
+                false,
+                "high",
+                "monotonic",
+            ),
+            generate_pipewire_exclusive_config(
+                "alsa.*",
+                256,
+                48000,
+                "S24LE",
+                &default_channel_positions(2),
+                &[],
+            ),
+        ];
 
-    println!("{}", diagnostic);
+        let (soft, hard) = rt_time_limits();
+        for fragment in &fragments {
+            let parsed = parse_spa_json(fragment).unwrap();
+            let args = &parsed["context.modules"][0]["args"];
+            assert_eq!(args["rt.time.soft"], soft);
+            assert_eq!(args["rt.time.hard"], hard);
+        }
 
-    // Don't fail - just warn the user
-    Ok(())
-}
+        assert!(set_rt_time_limits(400000, 200000).is_err());
+        assert!(set_rt_time_limits(0, 200000).is_err());
+    }
 
-/// Verify quantum was applied
-fn verify_quantum_applied(settings: &AudioSettings) -> Result<(), String> {
-    println!("\n=== QUANTUM VERIFICATION ===");
+    #[test]
+    fn test_wireplumber_exclusive_config() {
+        let apps = vec![
+            ExclusiveApp::new("Reaper", "reaper"),
+            ExclusiveApp::new("Surge XT", ""),
+        ];
+        let stereo = default_channel_positions(2);
+        let content =
+            generate_wireplumber_exclusive_config(true, 64, 48000, "S32LE", &stereo, &apps);
+        assert_eq!(parse_exclusive_apps(&content), apps);
 
-    // Check with pw-cli
-    match Command::new("pw-cli").args(["info", "0"]).output() {
-        Ok(output) => {
-            let output_str = String::from_utf8_lossy(&output.stdout);
-            let mut found_quantum = None;
-            let mut found_rate = None;
+        let rules = WirePlumberConfig::parse(&content, &["override.monitor.alsa.rules"]).unwrap();
+        assert_eq!(rules.len(), 3);
+        assert_eq!(rules[0].matches[0]["application.process.binary"], "reaper");
+        assert_eq!(rules[1].matches[0]["application.name"], "Surge XT");
+        assert_eq!(
+            rules[1].actions.update_props["node.target"],
+            "exclusive-playback-Surge_XT"
+        );
+        assert_eq!(
+            rules[2].actions.update_props["api.alsa.disable-batch"],
+            true
+        );
+        assert_eq!(rules[2].actions.update_props["api.alsa.headroom"], 32);
 
-            for line in output_str.lines() {
-                let trimmed = line.trim();
-                if trimmed.contains("default.clock.quantum") {
-                    println!("QUANTUM LINE: {}", trimmed);
-                    found_quantum = extract_number_from_line(trimmed);
-                }
-                if trimmed.contains("default.clock.rate") {
-                    println!("RATE LINE: {}", trimmed);
-                    found_rate = extract_number_from_line(trimmed);
-                }
-            }
+        let device = generate_wireplumber_config(
+            &AudioSettings::new(96000, 24, 128, "default".to_string()),
+            "output",
+        );
+        let rules = WirePlumberConfig::parse(&device, &["alsa-monitor", "rules"]).unwrap();
+        assert_eq!(rules[0].actions.update_props["api.alsa.period-num"], 2);
+    }
 
-            println!("\nRESULTS:");
-            println!("  Detected quantum: {:?}", found_quantum);
-            println!("  Detected rate: {:?}", found_rate);
-            println!("  Expected quantum: {}", settings.buffer_size);
-            println!("  Expected rate: {}", settings.sample_rate);
+    #[test]
+    fn test_wireplumber_configs_are_plain_json() {
+        let apps = vec![ExclusiveApp::new("Reaper", "reaper")];
+        let settings = AudioSettings::new(48000, 24, 256, "default".to_string());
+        let stereo = default_channel_positions(2);
+        let generated = [
+            generate_wireplumber_exclusive_config(true, 128, 48000, "S32LE", &stereo, &apps),
+            generate_wireplumber_exclusive_config(false, 256, 96000, "S24LE", &stereo, &[]),
+            generate_wireplumber_professional_device_config(&settings, "S24LE", 1500, 1500, 5),
+            generate_wireplumber_config(&settings, "output"),
+        ];
 
-            if found_quantum == Some(settings.buffer_size) {
-                println!(
-                    "✓ SUCCESS: Quantum correctly set to {}!",
-                    settings.buffer_size
-                );
-                return Ok(());
-            } else {
-                println!("⚠ FAILED: Quantum not set correctly");
-                println!(
-                    "  The quantum-floor in /usr/share/pipewire/pipewire.conf is likely still 4"
-                );
-                println!(
-                    "  Try: sudo sed -i 's/default.clock.quantum-floor = 4/default.clock.quantum-floor = {}/' /usr/share/pipewire/pipewire.conf",
-                    settings.buffer_size
-                );
-            }
-        }
-        Err(e) => {
-            println!("⚠ Could not run pw-cli: {}", e);
+        for content in generated {
+            // '#' lines are only allowed as a header above the JSON body
+            let body = content
+                .lines()
+                .skip_while(|line| line.starts_with('#') || line.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n");
+            assert!(!body.contains('#'), "comment inside JSON body:\n{}", body);
+
+            let value: serde_json::Value = serde_json::from_str(&body).unwrap();
+            assert_eq!(serde_json::to_string_pretty(&value).unwrap(), body);
         }
     }
 
-    Ok(())
-}
+    #[test]
+    fn test_restart_steps() {
+        let legacy: Vec<String> = restart_steps(
+            ServiceManager::Systemd,
+            &ServiceNames::default(),
+            true,
+            false,
+        )
+        .iter()
+        .map(RestartStep::command_line)
+        .collect();
+        assert_eq!(
+            legacy,
+            vec![
+                "systemctl --user restart pipewire",
+                "systemctl --user restart pipewire-pulse",
+                "systemctl --user restart wireplumber",
+            ]
+        );
+        assert!(
+            restart_steps(
+                ServiceManager::Systemd,
+                &ServiceNames::default(),
+                true,
+                false
+            )
+            .iter()
+            .all(|step| !step.required)
+        );
 
-/// Verifies that the settings were actually applied
-fn verify_settings_applied(settings: &AudioSettings) -> Result<(), String> {
-    println!("Verifying settings were applied...");
+        let system: Vec<String> = restart_steps(
+            ServiceManager::Systemd,
+            &ServiceNames::default(),
+            false,
+            true,
+        )
+        .iter()
+        .map(RestartStep::command_line)
+        .collect();
+        assert_eq!(system.len(), 1);
+        assert!(system[0].starts_with("sudo -u "));
+        assert!(
+            system[0].ends_with("systemctl --user restart pipewire pipewire-pulse wireplumber")
+        );
 
-    // Wait a bit for services to fully initialize
-    std::thread::sleep(std::time::Duration::from_secs(2));
+        // Unknown managers get the pkill fallback whatever the scope
+        let fallback: Vec<String> = restart_steps(
+            ServiceManager::Unknown,
+            &ServiceNames::default(),
+            false,
+            true,
+        )
+        .iter()
+        .map(RestartStep::command_line)
+        .collect();
+        assert_eq!(fallback, vec!["pkill -f pipewire", "pkill -f wireplumber"]);
 
-    // Get current settings
-    let current_settings = crate::audio::detect_current_audio_settings()
-        .map_err(|e| format!("Failed to detect current settings for verification: {}", e))?;
+        assert!(!plan_restart(false).is_empty());
+        assert_eq!(
+            plan_quantum_verified_restart(true),
+            vec![
+                "pkill -9 pipewire",
+                "pkill -9 wireplumber",
+                "systemctl --user restart pipewire wireplumber",
+            ]
+        );
+    }
 
-    println!(
-        "Current settings: {}Hz/{}bit/{} samples",
-        current_settings.sample_rate, current_settings.bit_depth, current_settings.buffer_size
-    );
-    println!(
-        "Expected settings: {}Hz/{}bit/{} samples",
-        settings.sample_rate, settings.bit_depth, settings.buffer_size
-    );
+    #[test]
+    fn test_runit_and_dinit_restart_steps() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let svdir = temp_dir.path().to_string_lossy().to_string();
+        let names = ServiceNames {
+            pipewire: "pipewire-user".to_string(),
+            ..ServiceNames::default()
+        };
+        for service in ["pipewire-user", "wireplumber"] {
+            fs::create_dir_all(temp_dir.path().join(service)).unwrap();
+        }
 
-    // Check if settings match (with some tolerance for buffer size)
-    if current_settings.sample_rate != settings.sample_rate {
-        return Err(format!(
-            "Sample rate not applied: expected {}Hz, got {}Hz",
-            settings.sample_rate, current_settings.sample_rate
-        ));
-    }
+        let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        unsafe { std::env::set_var("SVDIR", &svdir) };
+        let runit: Vec<String> = restart_steps(ServiceManager::Runit, &names, false, false)
+            .iter()
+            .map(RestartStep::command_line)
+            .collect();
+        let runit_system: Vec<String> = restart_steps(ServiceManager::Runit, &names, false, true)
+            .iter()
+            .map(RestartStep::command_line)
+            .collect();
+        // Without the pipewire service there is nothing for sv to restart
+        let runit_missing = restart_steps(
+            ServiceManager::Runit,
+            &ServiceNames::default(),
+            false,
+            false,
+        );
+        unsafe { std::env::remove_var("SVDIR") };
 
-    // Buffer size might be adjusted by PipeWire, so we allow some difference
-    let buffer_diff = (current_settings.buffer_size as i32 - settings.buffer_size as i32).abs();
-    if buffer_diff > 256 {
-        // Allow 256 samples difference
-        return Err(format!(
-            "Buffer size significantly different: expected {}, got {}",
-            settings.buffer_size, current_settings.buffer_size
-        ));
+        // Only the services that exist in the service dir
+        assert_eq!(
+            runit,
+            vec![format!(
+                "sv restart {}/pipewire-user {}/wireplumber",
+                svdir, svdir
+            )]
+        );
+        assert!(runit_system[0].starts_with("sudo -u "));
+        assert!(runit_system[0].ends_with(&runit[0]));
+        assert_eq!(runit_missing[0].command_line(), "pkill -f pipewire");
+
+        let dinit = restart_steps(ServiceManager::Dinit, &names, false, false);
+        assert_eq!(dinit.len(), 3);
+        assert!(dinit[0].command_line().ends_with("restart pipewire-user"));
+        assert!(dinit[0].required);
+        assert!(dinit[2].command_line().ends_with("restart wireplumber"));
+        assert!(!dinit[2].required);
     }
 
-    println!("✓ Settings verified successfully");
-    Ok(())
-}
+    #[test]
+    fn test_service_manager_detection() {
+        let only = |marker: &'static str| move |path: &str| path == marker;
 
-// ----------------------------------------------------------------------------
-// Utility Functions
-// ----------------------------------------------------------------------------
+        assert_eq!(
+            service_manager_from(only("/run/systemd/seats"), false),
+            ServiceManager::Systemd
+        );
+        assert_eq!(
+            service_manager_from(only("/run/openrc"), false),
+            ServiceManager::OpenRC
+        );
+        assert_eq!(
+            service_manager_from(only("/run/runit"), false),
+            ServiceManager::Runit
+        );
+        assert_eq!(
+            service_manager_from(only("/run/dinitctl"), false),
+            ServiceManager::Dinit
+        );
+        assert_eq!(
+            service_manager_from(only("/run/s6-rc"), false),
+            ServiceManager::S6
+        );
+        // rc-service alone only counts when no runtime directory is found
+        assert_eq!(
+            service_manager_from(only("/run/runit"), true),
+            ServiceManager::Runit
+        );
+        assert_eq!(
+            service_manager_from(|_| false, true),
+            ServiceManager::OpenRC
+        );
+        assert_eq!(
+            service_manager_from(|_| false, false),
+            ServiceManager::Unknown
+        );
+    }
 
-/// Helper to extract numbers from config lines
-fn extract_number_from_line(line: &str) -> Option<u32> {
-    // Handle lines like: *		default.clock.rate = "48000"
-    let line = line.trim_start_matches('*').trim();
-    let parts: Vec<&str> = line.split('=').collect();
-    if parts.len() >= 2 {
-        parts[1].trim().trim_matches('"').parse::<u32>().ok()
-    } else {
-        None
+    #[test]
+    fn test_user_config_dir_override() {
+        let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let temp_dir = tempfile::tempdir().unwrap();
+        let override_dir = temp_dir.path().to_string_lossy().to_string();
+
+        unsafe { std::env::set_var(CONFIG_DIR_ENV, format!("{}/", override_dir)) };
+        assert_eq!(user_config_dir(), override_dir);
+        unsafe { std::env::remove_var(CONFIG_DIR_ENV) };
     }
-}
 
-/// Restart with quantum verification
-fn quantum_verified_restart(system_wide: bool) -> Result<(), String> {
-    // Kill
-    let _ = Command::new("pkill").arg("-9").arg("pipewire").status();
-    let _ = Command::new("pkill").arg("-9").arg("wireplumber").status();
+    #[test]
+    fn test_user_config_dir_xdg() {
+        let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let temp_dir = tempfile::tempdir().unwrap();
+        let xdg_dir = temp_dir.path().to_string_lossy().to_string();
+
+        // Without the override the XDG base directory is used
+        let xdg_config_home = std::env::var_os("XDG_CONFIG_HOME");
+        unsafe {
+            std::env::set_var(CONFIG_DIR_ENV, "");
+            std::env::set_var("XDG_CONFIG_HOME", &xdg_dir);
+        }
+        assert_eq!(user_config_dir(), xdg_dir);
 
-    std::thread::sleep(std::time::Duration::from_secs(2));
+        unsafe {
+            std::env::remove_var(CONFIG_DIR_ENV);
+            std::env::remove_var("XDG_CONFIG_HOME");
+        }
+        assert_eq!(
+            PathBuf::from(user_config_dir()),
+            user_home_dir().join(".config")
+        );
+        assert!(asoundrc_path().starts_with(user_home_dir()));
 
-    // Start
-    if system_wide {
-        let _ = Command::new("systemctl")
-            .args(["--user", "restart", "pipewire", "wireplumber"])
-            .status();
-    } else {
-        let _ = Command::new("pipewire").spawn();
-        std::thread::sleep(std::time::Duration::from_millis(500));
-        let _ = Command::new("wireplumber").spawn();
+        if let Some(value) = xdg_config_home {
+            unsafe { std::env::set_var("XDG_CONFIG_HOME", value) };
+        }
     }
 
-    std::thread::sleep(std::time::Duration::from_secs(3));
-    Ok(())
-}
+    #[test]
+    fn test_profile_bundle() {
+        let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let temp_dir = tempfile::tempdir().unwrap();
+        unsafe { std::env::set_var(CONFIG_DIR_ENV, temp_dir.path()) };
+        let fragment_dir = temp_dir.path().join("pipewire/pipewire.conf.d");
+        fs::create_dir_all(&fragment_dir).unwrap();
+        fs::write(
+            fragment_dir.join("99-pro-audio-advanced.conf"),
+            "context.properties = {\n    default.clock.min-quantum = 64\n    default.clock.max-quantum = 2048\n}\n",
+        )
+        .unwrap();
+        fs::write(fragment_dir.join("10-my-own.conf"), "# not ours\n").unwrap();
 
-// ============================================================================
-// TESTS
-// ============================================================================
+        let settings = AudioSettings::new(96000, 24, 256, "default".to_string());
+        let bundle = build_profile_bundle(settings);
+        unsafe { std::env::remove_var(CONFIG_DIR_ENV) };
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        // Only managed files travel, and the buffer range is read from them
+        assert_eq!(bundle.files.len(), 1);
+        assert_eq!(
+            bundle.files[0].path,
+            "pipewire/pipewire.conf.d/99-pro-audio-advanced.conf"
+        );
+        assert_eq!(bundle.professional.min_buffer, Some(64));
+        assert_eq!(bundle.professional.max_buffer, Some(2048));
+
+        let content = toml::to_string_pretty(&bundle).unwrap();
+        assert_eq!(parse_profile_bundle(&content).unwrap(), bundle);
+
+        let newer = content.replace("schema_version = 1", "schema_version = 2");
+        assert!(
+            parse_profile_bundle(&newer)
+                .unwrap_err()
+                .contains("schema version 2")
+        );
+
+        let bad_rate = content.replace("sample_rate = 96000", "sample_rate = 12345");
+        assert!(parse_profile_bundle(&bad_rate).is_err());
+
+        let outside = content.replace(
+            "pipewire/pipewire.conf.d/99-pro-audio-advanced.conf",
+            "../.bashrc",
+        );
+        assert!(parse_profile_bundle(&outside).is_err());
+    }
 
     #[test]
-    fn test_audio_settings_struct() {
-        let settings = AudioSettings {
-            sample_rate: 96000,
-            bit_depth: 24,
-            buffer_size: 512,
-            device_id: "test-device".to_string(),
-        };
+    fn test_jack_config() {
+        assert_eq!(
+            choose_jack_backend(false, true, true),
+            Some(JackBackend::PipeWireJack)
+        );
+        assert_eq!(
+            choose_jack_backend(true, true, true),
+            Some(JackBackend::Jackd)
+        );
+        assert_eq!(
+            choose_jack_backend(false, false, true),
+            Some(JackBackend::Jackd)
+        );
+        assert_eq!(choose_jack_backend(false, false, false), None);
+
+        let settings = AudioSettings::new(96000, 24, 128, "default".to_string());
+        let alsa = generate_jackd_config(&settings, 3, "alsa");
+        assert!(alsa.contains(r#"<driver name="alsa">"#));
+        assert!(alsa.contains(r#"<option name="rate">96000</option>"#));
+        assert!(alsa.contains(r#"<option name="period">128</option>"#));
+        assert!(alsa.contains(r#"<option name="nperiods">3</option>"#));
+        assert!(!generate_jackd_config(&settings, 3, "dummy").contains("nperiods"));
+
+        let parsed = parse_spa_json(&generate_pipewire_jack_config(&settings)).unwrap();
+        let properties = &parsed["jack.properties"];
+        assert_eq!(properties["node.latency"], "128/96000");
+        assert_eq!(properties["node.force-quantum"], 128);
+
+        assert_eq!(parse_jack_number("48000\n", None), Some(48000));
+        assert_eq!(
+            parse_jack_number(
+                "buffer size = 256  sample rate = 48000",
+                Some("buffer size")
+            ),
+            Some(256)
+        );
+        assert_eq!(parse_jack_number("no server", None), None);
+    }
 
-        assert_eq!(settings.sample_rate, 96000);
-        assert_eq!(settings.bit_depth, 24);
-        assert_eq!(settings.buffer_size, 512);
+    #[test]
+    fn test_professional_device_priorities() {
+        let settings = AudioSettings::new(48000, 24, 256, "default".to_string());
+        let settings = settings.with_channels(4, Vec::new());
+        let content =
+            generate_wireplumber_professional_device_config(&settings, "S32LE", 2500, 1800, 30);
+
+        let rules = WirePlumberConfig::parse(&content, &["alsa-monitor", "rules"]).unwrap();
+        let props = &rules[0].actions.update_props;
+        assert_eq!(props["priority.driver"], 2500);
+        assert_eq!(props["priority.session"], 1800);
+        assert_eq!(props["audio.channels"], 4);
+        assert_eq!(props["audio.format"], "S32LE");
+        assert_eq!(props["session.suspend-timeout-seconds"], 30);
     }
 
     #[test]
@@ -2609,6 +6660,11 @@ mod tests {
             bit_depth: 32,
             buffer_size: 256,
             device_id: "test-device".to_string(),
+            allowed_rates: Vec::new(),
+            node_description: None,
+            period_size: None,
+            channels: 2,
+            position: Vec::new(),
         };
 
         let config = generate_wireplumber_config(&settings, "output");
@@ -2626,6 +6682,11 @@ mod tests {
             bit_depth: 16,
             buffer_size: 1024,
             device_id: "default".to_string(),
+            allowed_rates: Vec::new(),
+            node_description: None,
+            period_size: None,
+            channels: 2,
+            position: Vec::new(),
         };
 
         let config = generate_wireplumber_config(&settings, "input");
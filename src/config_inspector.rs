@@ -11,8 +11,9 @@ use chrono::{DateTime, Local};
 use glib::ControlFlow;
 use gtk::prelude::*;
 use gtk::{
-    Box as GtkBox, Button, CellRendererText, Frame, Label, ListStore, Orientation, ScrolledWindow,
-    Separator, TreeView, TreeViewColumn, Window,
+    Box as GtkBox, Button, CellRendererText, Clipboard, DialogFlags, Label, ListStore, Menu,
+    MenuItem, Orientation, ResponseType, ScrolledWindow, SelectionMode, TextView, TreeView,
+    TreeViewColumn, Window,
 };
 use serde_json::Value;
 use std::collections::{HashMap, HashSet};
@@ -23,7 +24,18 @@ use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
-use whoami::username;
+
+use crate::audio::pipewire_command;
+use crate::config::{
+    ConfigBackup, export_profile, import_profile, list_config_backups, resolve_config_path,
+    restore_config_backup, set_rt_time_limits, user_config_dir, user_home_dir,
+};
+use crate::ui::apply_guard::{APPLY_BUSY_TEXT, begin_apply, end_apply, register_apply_widget};
+use crate::ui::components::{create_section_box, mark_verbose_label};
+use crate::ui::dialogs::{confirm_service_restart, show_config_error_dialog};
+use crate::ui::preferences::AppPreferences;
+use crate::ui::tasks::add_ui_timeout;
+use crate::utils::unified_diff;
 
 #[derive(Clone, Debug)]
 pub struct ConfigFileInfo {
@@ -42,8 +54,15 @@ pub struct ConfigInspectorTab {
     pub container: GtkBox,
     pub status_label: Label,
     pub refresh_button: Button,
+    pub export_button: Button,
+    pub import_button: Button,
+    pub diff_button: Button,
     pub user_store: ListStore,
     pub system_store: ListStore,
+    pub backup_store: ListStore,
+    pub restore_button: Button,
+    /// Backups listed in `backup_store`, found again by path on restore
+    backups: Arc<Mutex<Vec<ConfigBackup>>>,
     scan_in_progress: Arc<Mutex<bool>>,
 }
 
@@ -71,14 +90,30 @@ impl ConfigInspectorTab {
         refresh_button
             .set_tooltip_text(Some("Rescan configuration files and active PipeWire state"));
 
+        let export_button = Button::with_label("Export Profile");
+        export_button.set_tooltip_text(Some(
+            "Save the running settings and every config file this tool wrote into one file",
+        ));
+        let import_button = Button::with_label("Import Profile");
+        import_button.set_tooltip_text(Some(
+            "Replace this tool's user config files with an exported profile and restart audio services",
+        ));
+        register_apply_widget(&import_button);
+
+        let buttons_box = GtkBox::new(Orientation::Horizontal, 6);
+        buttons_box.pack_start(&refresh_button, false, false, 0);
+        buttons_box.pack_start(&export_button, false, false, 0);
+        buttons_box.pack_start(&import_button, false, false, 0);
+
         let info_label = Label::new(Some(
             "This tab shows all PipeWire/WirePlumber configuration files and their current status.",
         ));
         info_label.set_line_wrap(true);
         info_label.set_halign(gtk::Align::Start);
+        mark_verbose_label(&info_label);
 
         status_box.pack_start(&status_label, false, false, 0);
-        status_box.pack_start(&refresh_button, false, false, 6);
+        status_box.pack_start(&buttons_box, false, false, 6);
         status_box.pack_start(&info_label, false, false, 0);
 
         // ===== USER CONFIGS SECTION =====
@@ -89,6 +124,7 @@ impl ConfigInspectorTab {
         ));
         user_info_label.set_line_wrap(true);
         user_info_label.set_halign(gtk::Align::Start);
+        mark_verbose_label(&user_info_label);
 
         let user_scrolled = ScrolledWindow::new(None::<&gtk::Adjustment>, None::<&gtk::Adjustment>);
         user_scrolled.set_policy(gtk::PolicyType::Automatic, gtk::PolicyType::Automatic);
@@ -108,6 +144,7 @@ impl ConfigInspectorTab {
         ));
         system_info_label.set_line_wrap(true);
         system_info_label.set_halign(gtk::Align::Start);
+        mark_verbose_label(&system_info_label);
 
         let system_scrolled =
             ScrolledWindow::new(None::<&gtk::Adjustment>, None::<&gtk::Adjustment>);
@@ -120,15 +157,51 @@ impl ConfigInspectorTab {
         system_box.pack_start(&system_info_label, false, false, 0);
         system_box.pack_start(&system_scrolled, true, true, 0);
 
+        // ===== BACKUPS SECTION =====
+        let (backups_frame, backups_box) = create_section_box("Backups");
+
+        let backups_info_label = Label::new(Some(
+            "Copies of the config files taken before settings were applied. Restore puts the \
+             selected backup's files back in place and restarts the audio services.",
+        ));
+        backups_info_label.set_line_wrap(true);
+        backups_info_label.set_halign(gtk::Align::Start);
+        mark_verbose_label(&backups_info_label);
+
+        let backups_scrolled =
+            ScrolledWindow::new(None::<&gtk::Adjustment>, None::<&gtk::Adjustment>);
+        backups_scrolled.set_policy(gtk::PolicyType::Automatic, gtk::PolicyType::Automatic);
+        backups_scrolled.set_min_content_height(120);
+
+        let (backup_tree, backup_store) = Self::create_backup_tree_view();
+        backups_scrolled.add(&backup_tree);
+
+        let restore_button = Button::with_label("Restore Selected Backup");
+        restore_button.set_tooltip_text(Some(
+            "Copy the backup's .conf files back to where they came from and restart audio services",
+        ));
+        restore_button.set_halign(gtk::Align::Start);
+        register_apply_widget(&restore_button);
+
+        backups_box.pack_start(&backups_info_label, false, false, 0);
+        backups_box.pack_start(&backups_scrolled, true, true, 0);
+        backups_box.pack_start(&restore_button, false, false, 0);
+
         // ===== ACTIONS SECTION =====
         let (actions_frame, actions_box) = create_section_box("File Actions");
 
         let actions_info_label = Label::new(Some(
-            "Double-click on any file to open it with the default system editor.",
+            "Double-click on any file to open it with the default system editor.\n\
+             Right-click on any file to copy its full path to the clipboard.\n\
+             Ctrl-click two files (from either list) and press Diff Selected to compare them.",
         ));
         actions_info_label.set_line_wrap(true);
         actions_info_label.set_halign(gtk::Align::Start);
 
+        let diff_button = Button::with_label("Diff Selected");
+        diff_button.set_tooltip_text(Some("Show the differences between the two selected files"));
+        diff_button.set_halign(gtk::Align::Start);
+
         let legend_box = GtkBox::new(Orientation::Horizontal, 12);
         legend_box.set_halign(gtk::Align::Start);
 
@@ -140,19 +213,27 @@ impl ConfigInspectorTab {
 
         actions_box.pack_start(&actions_info_label, false, false, 0);
         actions_box.pack_start(&legend_box, false, false, 6);
+        actions_box.pack_start(&diff_button, false, false, 0);
 
         // ===== ASSEMBLE TAB =====
         container.pack_start(&status_frame, false, false, 0);
         container.pack_start(&user_frame, true, true, 0);
         container.pack_start(&system_frame, true, true, 0);
+        container.pack_start(&backups_frame, true, true, 0);
         container.pack_start(&actions_frame, false, false, 0);
 
         let tab = ConfigInspectorTab {
             container,
             status_label,
             refresh_button,
+            export_button,
+            import_button,
+            diff_button,
             user_store,
             system_store,
+            backup_store,
+            restore_button,
+            backups: Arc::new(Mutex::new(Vec::new())),
             scan_in_progress: Arc::new(Mutex::new(false)),
         };
 
@@ -160,15 +241,45 @@ impl ConfigInspectorTab {
         tab.setup_double_click_events(&user_tree, false);
         tab.setup_double_click_events(&system_tree, true);
 
+        // Set up right-click context menus
+        tab.setup_context_menu(&user_tree, false);
+        tab.setup_context_menu(&system_tree, true);
+
         // Connect refresh button
         let tab_clone = tab.clone();
         tab.refresh_button.connect_clicked(move |_| {
             tab_clone.scan_configs();
         });
 
+        tab.setup_profile_buttons();
+        tab.setup_restore_button(&backup_tree);
+
+        // Connect diff button; the selection may span both lists
+        let trees = [user_tree.clone(), system_tree.clone()];
+        tab.diff_button.connect_clicked(move |_| {
+            let selected: Vec<String> = trees
+                .iter()
+                .flat_map(|tree| {
+                    let (rows, model) = tree.selection().selected_rows();
+                    rows.into_iter()
+                        .filter_map(|path| model.iter(&path))
+                        .map(|iter| model.value(&iter, 1).get::<String>().unwrap_or_default())
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+
+            match selected.as_slice() {
+                [old, new] => Self::show_diff_dialog(old, new),
+                _ => show_error_dialog(&format!(
+                    "Select exactly two files to compare ({} selected).",
+                    selected.len()
+                )),
+            }
+        });
+
         // Trigger initial scan
         let tab_for_timeout = tab.clone();
-        glib::timeout_add_local(Duration::from_millis(500), move || {
+        add_ui_timeout(Duration::from_millis(500), move || {
             tab_for_timeout.scan_configs();
             ControlFlow::Break
         });
@@ -176,6 +287,300 @@ impl ConfigInspectorTab {
         tab
     }
 
+    /// Export writes a profile bundle right away; import replaces the user
+    /// configs and restarts the services, so it runs in the background
+    fn setup_profile_buttons(&self) {
+        let status_label = self.status_label.clone();
+        self.export_button.connect_clicked(move |_| {
+            let dialog = gtk::FileChooserDialog::with_buttons(
+                Some("Export Profile"),
+                None::<&Window>,
+                gtk::FileChooserAction::Save,
+                &[
+                    ("Cancel", ResponseType::Cancel),
+                    ("Export", ResponseType::Accept),
+                ],
+            );
+            dialog.set_do_overwrite_confirmation(true);
+            dialog.set_current_name("pro-audio-profile.toml");
+
+            let status_label = status_label.clone();
+            dialog.connect_response(move |dialog, response| {
+                if response == ResponseType::Accept
+                    && let Some(path) = dialog.filename()
+                {
+                    match export_profile(&path) {
+                        Ok(()) => {
+                            let message = format!("Profile exported to {}", path.display());
+                            status_label.set_text(&message);
+                            show_success_dialog(&message);
+                        }
+                        Err(e) => show_error_dialog(&format!("Failed to export profile: {}", e)),
+                    }
+                }
+                dialog.close();
+            });
+
+            dialog.show_all();
+        });
+
+        let tab = self.clone();
+        self.import_button.connect_clicked(move |_| {
+            let dialog = gtk::FileChooserDialog::with_buttons(
+                Some("Import Profile"),
+                None::<&Window>,
+                gtk::FileChooserAction::Open,
+                &[
+                    ("Cancel", ResponseType::Cancel),
+                    ("Import", ResponseType::Accept),
+                ],
+            );
+
+            let tab = tab.clone();
+            dialog.connect_response(move |dialog, response| {
+                let path = dialog.filename();
+                dialog.close();
+                if response == ResponseType::Accept
+                    && let Some(path) = path
+                    && confirm_service_restart(false)
+                {
+                    tab.import_in_background(path);
+                }
+            });
+
+            dialog.show_all();
+        });
+    }
+
+    fn import_in_background(&self, path: PathBuf) {
+        if !begin_apply() {
+            show_error_dialog(APPLY_BUSY_TEXT);
+            return;
+        }
+        self.status_label
+            .set_text(&format!("Importing profile {}...", path.display()));
+
+        let (tx, rx) = mpsc::channel();
+        let rx_arc = Arc::new(Mutex::new(rx));
+
+        thread::spawn(move || {
+            let _ = tx.send(import_profile(&path));
+        });
+
+        let tab = self.clone();
+        let rx_timeout = Arc::clone(&rx_arc);
+        add_ui_timeout(Duration::from_millis(100), move || {
+            let rx_guard = rx_timeout.lock().unwrap();
+            match rx_guard.try_recv() {
+                Ok(Ok(bundle)) => {
+                    end_apply();
+                    // The rt.time limits live in the preferences, not in a config file
+                    let professional = &bundle.professional;
+                    if let Err(e) = set_rt_time_limits(
+                        professional.rt_time_soft_us,
+                        professional.rt_time_hard_us,
+                    )
+                    .and_then(|()| {
+                        AppPreferences::update(|prefs| {
+                            prefs.rt_time_soft_us = Some(professional.rt_time_soft_us);
+                            prefs.rt_time_hard_us = Some(professional.rt_time_hard_us);
+                        })
+                    }) {
+                        log::warn!("RT time limits of the profile not kept: {}", e);
+                    }
+
+                    let settings = &bundle.settings;
+                    let message = format!(
+                        "Profile imported: {} Hz, {} bit, {} samples, {} config files{}",
+                        settings.sample_rate,
+                        settings.bit_depth,
+                        settings.buffer_size,
+                        bundle.files.len(),
+                        if bundle.exclusive.active {
+                            ", exclusive mode on"
+                        } else {
+                            ""
+                        }
+                    );
+                    tab.status_label.set_text(&message);
+                    show_success_dialog(&message);
+                    tab.scan_configs();
+                    ControlFlow::Break
+                }
+                Ok(Err(e)) => {
+                    end_apply();
+                    tab.status_label.set_text("Profile import failed");
+                    show_error_dialog(&format!("Failed to import profile: {}", e));
+                    ControlFlow::Break
+                }
+                Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    end_apply();
+                    ControlFlow::Break
+                }
+            }
+        });
+    }
+
+    /// Asks before restoring the selected backup, then restores it in the
+    /// background and rescans to show the result
+    fn setup_restore_button(&self, backup_tree: &TreeView) {
+        let tab = self.clone();
+        let backup_tree = backup_tree.clone();
+        self.restore_button.connect_clicked(move |_| {
+            let (rows, model) = backup_tree.selection().selected_rows();
+            let Some(path) = rows
+                .first()
+                .and_then(|row| model.iter(row))
+                .and_then(|iter| model.value(&iter, 3).get::<String>().ok())
+            else {
+                show_error_dialog("Select a backup to restore.");
+                return;
+            };
+            let Some(backup) = tab
+                .backups
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|backup| backup.path == Path::new(&path))
+                .cloned()
+            else {
+                return;
+            };
+            let Some(origin) = &backup.origin else {
+                show_error_dialog(&format!(
+                    "It is not known where the files in {} came from, so they cannot be \
+                     restored automatically. Copy them back by hand.",
+                    backup.path.display()
+                ));
+                return;
+            };
+
+            let dialog = gtk::MessageDialog::new(
+                None::<&Window>,
+                DialogFlags::MODAL,
+                gtk::MessageType::Question,
+                gtk::ButtonsType::YesNo,
+                &format!(
+                    "Restore the backup from {}?",
+                    backup.created.format("%Y-%m-%d %H:%M:%S")
+                ),
+            );
+            dialog.set_secondary_text(Some(&format!(
+                "{} file(s) will be copied back to {}, replacing the files with the same \
+                 names, and the audio services will restart.{}",
+                backup.files.len(),
+                origin.display(),
+                if backup.is_system() {
+                    "\n\nThis needs administrator privileges."
+                } else {
+                    ""
+                }
+            )));
+            let response = dialog.run();
+            dialog.close();
+
+            if response == ResponseType::Yes {
+                tab.restore_in_background(backup);
+            }
+        });
+    }
+
+    fn restore_in_background(&self, backup: ConfigBackup) {
+        if !begin_apply() {
+            show_error_dialog(APPLY_BUSY_TEXT);
+            return;
+        }
+        let created = backup.created.format("%Y-%m-%d %H:%M:%S").to_string();
+        self.status_label
+            .set_text(&format!("Restoring backup from {}...", created));
+
+        let (tx, rx) = mpsc::channel();
+        let rx_arc = Arc::new(Mutex::new(rx));
+
+        thread::spawn(move || {
+            let _ = tx.send(restore_config_backup(&backup));
+        });
+
+        let tab = self.clone();
+        let rx_timeout = Arc::clone(&rx_arc);
+        add_ui_timeout(Duration::from_millis(100), move || {
+            let rx_guard = rx_timeout.lock().unwrap();
+            match rx_guard.try_recv() {
+                Ok(Ok(restored)) => {
+                    end_apply();
+                    let message = format!(
+                        "Restored {} file(s) from the backup of {}",
+                        restored, created
+                    );
+                    tab.status_label.set_text(&message);
+                    show_success_dialog(&message);
+                    tab.scan_configs();
+                    ControlFlow::Break
+                }
+                Ok(Err(e)) => {
+                    end_apply();
+                    tab.status_label.set_text("Backup restore failed");
+                    show_config_error_dialog(&e);
+                    // Part of the files may have been written
+                    tab.scan_configs();
+                    ControlFlow::Break
+                }
+                Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    end_apply();
+                    ControlFlow::Break
+                }
+            }
+        });
+    }
+
+    fn create_backup_tree_view() -> (TreeView, ListStore) {
+        let store = ListStore::new(&[
+            gtk::glib::Type::STRING, // Created
+            gtk::glib::Type::STRING, // Origin
+            gtk::glib::Type::STRING, // File count
+            gtk::glib::Type::STRING, // Backup path
+        ]);
+
+        let tree_view = TreeView::with_model(&store);
+        tree_view.selection().set_mode(SelectionMode::Single);
+
+        for (column, title, min_width) in
+            [(0, "Created", 150), (1, "Origin", 250), (2, "Files", 60)]
+        {
+            let col = TreeViewColumn::new();
+            let cell = CellRendererText::new();
+            gtk::prelude::CellLayoutExt::pack_start(&col, &cell, true);
+            gtk::prelude::CellLayoutExt::add_attribute(&col, &cell, "text", column);
+            col.set_title(title);
+            col.set_resizable(true);
+            col.set_min_width(min_width);
+            tree_view.append_column(&col);
+        }
+
+        (tree_view, store)
+    }
+
+    fn add_backup_to_store(store: &ListStore, backup: &ConfigBackup) {
+        let origin = backup
+            .origin
+            .as_ref()
+            .map(|origin| origin.display().to_string())
+            .unwrap_or_else(|| "unknown (restore by hand)".to_string());
+
+        let iter = store.append();
+        store.set(
+            &iter,
+            &[
+                (0, &backup.created.format("%Y-%m-%d %H:%M:%S").to_string()),
+                (1, &origin),
+                (2, &backup.files.len().to_string()),
+                (3, &backup.path.to_string_lossy().to_string()),
+            ],
+        );
+    }
+
     fn create_config_tree_view() -> (TreeView, ListStore) {
         let store = ListStore::new(&[
             gtk::glib::Type::STRING, // Status indicator
@@ -185,6 +590,7 @@ impl ConfigInspectorTab {
         ]);
 
         let tree_view = TreeView::with_model(&store);
+        tree_view.selection().set_mode(SelectionMode::Multiple);
 
         // File name column with status indicator
         let name_col = TreeViewColumn::new();
@@ -240,10 +646,117 @@ impl ConfigInspectorTab {
         });
     }
 
+    fn setup_context_menu(&self, tree_view: &TreeView, is_system: bool) {
+        let store = if is_system {
+            self.system_store.clone()
+        } else {
+            self.user_store.clone()
+        };
+        let status_label = self.status_label.clone();
+
+        tree_view.connect_button_press_event(move |tree_view, event| {
+            // Only handle right mouse button
+            if event.button() != 3 {
+                return gtk::glib::Propagation::Proceed;
+            }
+
+            let (x, y) = event.position();
+            let Some((Some(path), _, _, _)) = tree_view.path_at_pos(x as i32, y as i32) else {
+                return gtk::glib::Propagation::Proceed;
+            };
+            let Some(iter) = store.iter(&path) else {
+                return gtk::glib::Propagation::Proceed;
+            };
+
+            // Keep a multi-selection when right-clicking one of its rows
+            let selection = tree_view.selection();
+            if !selection.path_is_selected(&path) {
+                selection.unselect_all();
+                selection.select_path(&path);
+            }
+            let full_path: String = store.value(&iter, 1).get().unwrap_or_default();
+
+            let menu = Menu::new();
+
+            let copy_item = MenuItem::with_label("Copy Full Path");
+            let path_for_copy = full_path.clone();
+            let status_for_copy = status_label.clone();
+            copy_item.connect_activate(move |_| {
+                Self::copy_path_to_clipboard(&path_for_copy);
+                status_for_copy.set_text(&format!("Copied path: {}", path_for_copy));
+            });
+            menu.append(&copy_item);
+
+            let open_item = MenuItem::with_label("Open File");
+            open_item.connect_activate(move |_| {
+                Self::open_config_file(&full_path, is_system);
+            });
+            menu.append(&open_item);
+
+            menu.show_all();
+            menu.popup_at_pointer(Some(&**event));
+
+            gtk::glib::Propagation::Stop
+        });
+    }
+
+    fn copy_path_to_clipboard(path: &str) {
+        let clipboard = Clipboard::get(&gtk::gdk::SELECTION_CLIPBOARD);
+        clipboard.set_text(path);
+        log::debug!("Copied config path to clipboard: {}", path);
+    }
+
+    fn show_diff_dialog(old_path: &str, new_path: &str) {
+        let read = |path: &str| {
+            fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path, e))
+        };
+        let (old, new) = match (read(old_path), read(new_path)) {
+            (Ok(old), Ok(new)) => (old, new),
+            (Err(e), _) | (_, Err(e)) => {
+                show_error_dialog(&e);
+                return;
+            }
+        };
+
+        let diff = unified_diff(old_path, &old, new_path, &new);
+        let text = if diff.is_empty() {
+            format!("Files are identical:\n{}\n{}", old_path, new_path)
+        } else {
+            diff
+        };
+
+        let dialog = gtk::Dialog::with_buttons(
+            Some("Config Diff"),
+            None::<&Window>,
+            DialogFlags::MODAL,
+            &[("Close", ResponseType::Close)],
+        );
+        dialog.set_default_size(800, 600);
+
+        let text_view = TextView::new();
+        text_view.set_editable(false);
+        text_view.set_monospace(true);
+        text_view.set_left_margin(6);
+        if let Some(buffer) = text_view.buffer() {
+            buffer.set_text(&text);
+        }
+
+        let scrolled = ScrolledWindow::new(None::<&gtk::Adjustment>, None::<&gtk::Adjustment>);
+        scrolled.set_policy(gtk::PolicyType::Automatic, gtk::PolicyType::Automatic);
+        scrolled.add(&text_view);
+        dialog.content_area().pack_start(&scrolled, true, true, 0);
+
+        dialog.connect_response(|dialog, _| {
+            dialog.close();
+        });
+
+        dialog.show_all();
+    }
+
     fn open_config_file(path: &str, is_system: bool) {
         if is_system {
             let path_clone = path.to_string();
-            println!("Opening system file: {}", path);
+            log::info!("Opening system file: {}", path);
 
             // Create a simple script that will ask for sudo and open editor
             let script = format!(
@@ -410,14 +923,14 @@ done"#,
                 // Try to execute the script
                 match Command::new("sh").arg(&temp_script).spawn() {
                     Ok(_) => {
-                        println!("Opened terminal for editing");
+                        log::info!("Opened terminal for editing");
                         show_success_dialog(&format!(
                             "Opened terminal for editing system file:\n{}",
                             path
                         ));
                     }
                     Err(e) => {
-                        println!("Failed to open terminal: {}", e);
+                        log::warn!("Failed to open terminal: {}", e);
                         Self::show_manual_instructions(path);
                         show_error_dialog(&format!(
                             "Failed to open terminal for editing:\n{}\n\nSee terminal for manual instructions.",
@@ -496,7 +1009,7 @@ done"#,
         {
             let mut in_progress = self.scan_in_progress.lock().unwrap();
             if *in_progress {
-                println!("DEBUG: Scan already in progress, skipping...");
+                log::debug!("Scan already in progress, skipping...");
                 return;
             }
             *in_progress = true;
@@ -504,6 +1017,8 @@ done"#,
         let status_label = self.status_label.clone();
         let user_store = self.user_store.clone();
         let system_store = self.system_store.clone();
+        let backup_store = self.backup_store.clone();
+        let backups = Arc::clone(&self.backups);
 
         status_label.set_text("Scanning configuration files...");
 
@@ -514,34 +1029,41 @@ done"#,
             let active_properties = match Self::get_active_config_properties() {
                 Ok(props) => props,
                 Err(e) => {
-                    println!("Warning: Could not get active properties: {}", e);
+                    log::warn!("Could not get active properties: {}", e);
                     HashMap::new()
                 }
             };
 
             // Scan BOTH user and system configs in the same thread
-            let (user_configs, _user_errors) =
+            let (user_configs, mut errors) =
                 Self::scan_config_directory_with_errors(false, &active_properties);
 
-            let (system_configs, _system_errors) =
+            let (system_configs, system_errors) =
                 Self::scan_config_directory_with_errors(true, &active_properties);
+            errors.extend(system_errors);
 
             let user_len = user_configs.len();
             let system_len = system_configs.len();
+            let found_backups = list_config_backups();
 
             // Send all results at once
-            let _ = tx.send((user_configs, system_configs, user_len, system_len));
+            let _ = tx.send((
+                user_configs,
+                system_configs,
+                user_len,
+                system_len,
+                errors,
+                found_backups,
+            ));
         });
 
         let rx_arc = Arc::new(Mutex::new(rx));
-        let rx_timeout: Arc<
-            Mutex<mpsc::Receiver<(Vec<ConfigFileInfo>, Vec<ConfigFileInfo>, usize, usize)>>,
-        > = Arc::clone(&rx_arc);
+        let rx_timeout: Arc<Mutex<mpsc::Receiver<ScanResult>>> = Arc::clone(&rx_arc);
 
-        glib::timeout_add_local(Duration::from_millis(100), move || {
+        add_ui_timeout(Duration::from_millis(100), move || {
             let rx_guard = rx_timeout.lock().unwrap();
             match rx_guard.try_recv() {
-                Ok((user_configs, system_configs, user_len, system_len)) => {
+                Ok((user_configs, system_configs, user_len, system_len, errors, found_backups)) => {
                     // Clear and update user store
                     user_store.clear();
                     for config in &user_configs {
@@ -554,6 +1076,12 @@ done"#,
                         ConfigInspectorTab::add_config_to_store(&system_store, config);
                     }
 
+                    backup_store.clear();
+                    for backup in &found_backups {
+                        ConfigInspectorTab::add_backup_to_store(&backup_store, backup);
+                    }
+                    *backups.lock().unwrap() = found_backups;
+
                     let total = user_len + system_len;
                     let status_text = format!(
                         "Scan complete: {} user configs, {} system configs ({} total)",
@@ -561,15 +1089,25 @@ done"#,
                     );
                     status_label.set_text(&status_text);
 
-                    // Show only ONE dialog with combined results
-                    if total > 0 {
+                    // Only directories that exist but cannot be read are worth a dialog
+                    if !errors.is_empty() {
+                        status_label.set_text(&format!(
+                            "{} ({} could not be read)",
+                            status_text,
+                            errors.len()
+                        ));
+                        show_error_dialog(&format!(
+                            "Some configuration locations could not be read:\n\n{}",
+                            errors.join("\n")
+                        ));
+                    } else if total > 0 {
                         show_success_dialog(&format!(
                             "Configuration scan complete!\n\nFound {} configuration files:\n• {} user files\n• {} system files\n\nDouble-click any file to open it.",
                             total, user_len, system_len
                         ));
                     } else {
-                        // Just update status, no error dialog needed
-                        status_label.set_text("No configuration files found. This may be normal if PipeWire/WirePlumber use default settings.");
+                        // Fresh install: nothing written yet, no error dialog needed
+                        status_label.set_text("No configuration files found. This is normal on a fresh install; PipeWire/WirePlumber use their default settings until you apply something.");
                     }
 
                     ControlFlow::Break
@@ -590,13 +1128,10 @@ done"#,
         let mut configs = Vec::new();
         let mut error_messages = Vec::new();
 
-        let username = username();
-        let home_path = format!("/home/{}", username);
-        let base_path = if is_system {
-            Path::new("/etc")
-        } else {
-            Path::new(&home_path)
-        };
+        let user_config = user_config_dir();
+        let user_data = directories::BaseDirs::new()
+            .map(|dirs| dirs.data_dir().to_path_buf())
+            .unwrap_or_else(|| user_home_dir().join(".local/share"));
 
         // Define all directories to scan
         let scan_dirs: Vec<(PathBuf, &str)> = if is_system {
@@ -628,62 +1163,65 @@ done"#,
             vec![
                 // PipeWire directories
                 (
-                    PathBuf::from(format!("{}/.config/pipewire", home_path)),
+                    PathBuf::from(format!("{}/pipewire", user_config)),
                     "User PipeWire",
                 ),
                 (
-                    PathBuf::from(format!("{}/.config/pipewire/pipewire.conf.d", home_path)),
+                    PathBuf::from(format!("{}/pipewire/pipewire.conf.d", user_config)),
                     "User PipeWire Config",
                 ),
                 // WirePlumber directories
                 (
-                    PathBuf::from(format!("{}/.config/wireplumber", home_path)),
+                    PathBuf::from(format!("{}/wireplumber", user_config)),
                     "User WirePlumber",
                 ),
                 (
-                    PathBuf::from(format!(
-                        "{}/.config/wireplumber/wireplumber.conf.d",
-                        home_path
-                    )),
+                    PathBuf::from(format!("{}/wireplumber/wireplumber.conf.d", user_config)),
                     "User WirePlumber Config",
                 ),
                 (
-                    PathBuf::from(format!("{}/.config/wireplumber/main.lua.d", home_path)),
+                    PathBuf::from(format!("{}/wireplumber/main.lua.d", user_config)),
                     "User WirePlumber Lua",
                 ),
                 // Alternative locations
+                (user_data.join("wireplumber"), "User WirePlumber Local"),
                 (
-                    PathBuf::from(format!("{}/.local/share/wireplumber", home_path)),
-                    "User WirePlumber Local",
-                ),
-                (
-                    PathBuf::from(format!(
-                        "{}/.local/share/wireplumber/wireplumber.conf.d",
-                        home_path
-                    )),
+                    user_data.join("wireplumber/wireplumber.conf.d"),
                     "User WirePlumber Local Config",
                 ),
             ]
         };
 
+        // Two entries can be the same directory through a symlink
+        let mut scanned_dirs = HashSet::new();
+
         for (dir_path, dir_label) in &scan_dirs {
             if !dir_path.exists() {
-                println!(
-                    "DEBUG: Directory does not exist: {} ({})",
+                log::debug!(
+                    "Not created yet, nothing to list: {} ({})",
+                    dir_path.display(),
+                    dir_label
+                );
+                continue;
+            }
+
+            if !scanned_dirs.insert(resolve_config_path(dir_path)) {
+                log::debug!(
+                    "Already scanned through a symlink: {} ({})",
                     dir_path.display(),
                     dir_label
                 );
                 continue;
             }
 
-            println!("DEBUG: Scanning: {} ({})", dir_path.display(), dir_label);
+            log::debug!("Scanning: {} ({})", dir_path.display(), dir_label);
 
             match fs::read_dir(dir_path) {
                 Ok(entries) => {
                     for entry in entries.flatten() {
                         match Self::process_config_entry(&entry, is_system, active_properties) {
                             Ok(Some(info)) => {
-                                println!("DEBUG: Found config: {}", info.filename);
+                                log::debug!("Found config: {}", info.filename);
                                 configs.push(info);
                             }
                             Ok(None) => {} // Not a config file
@@ -695,10 +1233,14 @@ done"#,
                         }
                     }
                 }
-                Err(e) => {
-                    // Directory not readable is NOT an error for the user, just skip it
-                    println!("DEBUG: Cannot read directory {}: {}", dir_path.display(), e);
-                }
+                Err(e) => match directory_scan_error(dir_path, &e) {
+                    Some(message) => {
+                        log::warn!("{}", message);
+                        error_messages.push(message);
+                    }
+                    // Removed between the check and the read
+                    None => log::debug!("Directory went away: {}", dir_path.display()),
+                },
             }
         }
 
@@ -710,17 +1252,14 @@ done"#,
             ]
         } else {
             vec![
-                PathBuf::from(format!("{}/.config/pipewire/pipewire.conf", home_path)),
-                PathBuf::from(format!(
-                    "{}/.config/wireplumber/wireplumber.conf",
-                    home_path
-                )),
+                PathBuf::from(format!("{}/pipewire/pipewire.conf", user_config)),
+                PathBuf::from(format!("{}/wireplumber/wireplumber.conf", user_config)),
             ]
         };
 
         for conf_path in &main_conf_files {
             if conf_path.exists() {
-                println!("DEBUG: Found main config file: {}", conf_path.display());
+                log::debug!("Found main config file: {}", conf_path.display());
                 if let Ok(metadata) = fs::metadata(conf_path)
                     && metadata.is_file()
                 {
@@ -739,15 +1278,12 @@ done"#,
         // Sort by modification time (newest first)
         configs.sort_by(|a, b| b.modified.cmp(&a.modified));
 
-        // Deduplicate by full path
+        // Deduplicate by real path, a file can be reachable through symlinks
         let mut seen = HashSet::new();
-        configs.retain(|config| {
-            let key = config.path.to_string_lossy().to_string();
-            seen.insert(key)
-        });
+        configs.retain(|config| seen.insert(resolve_config_path(&config.path)));
 
-        println!(
-            "DEBUG: Found {} config files in {} mode",
+        log::debug!(
+            "Found {} config files in {} mode",
             configs.len(),
             if is_system { "system" } else { "user" }
         );
@@ -760,11 +1296,11 @@ done"#,
         is_system: bool,
         active_properties: &HashMap<String, Vec<String>>,
     ) -> Result<Option<ConfigFileInfo>, String> {
-        let file_type = entry
-            .file_type()
+        // fs::metadata follows symlinks, so dotfile-managed configs are listed too
+        let metadata = fs::metadata(entry.path())
             .map_err(|e| format!("Cannot get file type for {:?}: {}", entry.path(), e))?;
 
-        if !file_type.is_file() {
+        if !metadata.is_file() {
             return Ok(None);
         }
 
@@ -928,7 +1464,7 @@ done"#,
     fn get_active_config_properties() -> Result<HashMap<String, Vec<String>>, String> {
         let mut properties = HashMap::new();
 
-        match Command::new("pw-dump").output() {
+        match pipewire_command("pw-dump").output() {
             Ok(output) => {
                 if !output.status.success() {
                     return Ok(properties); // Not an error, just no data
@@ -949,18 +1485,18 @@ done"#,
                             Ok(properties)
                         }
                         Err(e) => {
-                            println!("DEBUG: Failed to parse pw-dump JSON: {}", e);
+                            log::debug!("Failed to parse pw-dump JSON: {}", e);
                             Ok(properties)
                         }
                     },
                     Err(e) => {
-                        println!("DEBUG: Failed to parse pw-dump output: {}", e);
+                        log::debug!("Failed to parse pw-dump output: {}", e);
                         Ok(properties)
                     }
                 }
             }
             Err(e) => {
-                println!("DEBUG: pw-dump not available: {}", e);
+                log::debug!("pw-dump not available: {}", e);
                 Ok(properties)
             }
         }
@@ -1014,32 +1550,26 @@ done"#,
     }
 }
 
-// Helper functions
-fn create_section_box(title: &str) -> (Frame, GtkBox) {
-    let frame = Frame::new(None);
-    frame.set_margin_top(6);
-    frame.set_margin_bottom(6);
-
-    let section_box = GtkBox::new(Orientation::Vertical, 12);
-    section_box.set_margin_top(12);
-    section_box.set_margin_bottom(12);
-    section_box.set_margin_start(12);
-    section_box.set_margin_end(12);
-
-    let title_label = Label::new(None);
-    title_label.set_markup(&format!("<b>{}</b>", title));
-    title_label.set_halign(gtk::Align::Start);
-
-    section_box.pack_start(&title_label, false, false, 0);
-
-    let separator = Separator::new(Orientation::Horizontal);
-    section_box.pack_start(&separator, false, false, 0);
-
-    frame.add(&section_box);
-
-    (frame, section_box)
+/// Scanned user and system files, their counts and read errors
+type ScanResult = (
+    Vec<ConfigFileInfo>,
+    Vec<ConfigFileInfo>,
+    usize,
+    usize,
+    Vec<String>,
+    Vec<ConfigBackup>,
+);
+
+/// A config directory that cannot be listed. A missing one is normal on a
+/// fresh install, before anything was written, and is not an error.
+fn directory_scan_error(dir_path: &Path, error: &std::io::Error) -> Option<String> {
+    match error.kind() {
+        std::io::ErrorKind::NotFound => None,
+        _ => Some(format!("Cannot read {}: {}", dir_path.display(), error)),
+    }
 }
 
+// Helper functions
 fn show_error_dialog(message: &str) {
     let dialog = gtk::MessageDialog::new(
         None::<&Window>,
@@ -1097,4 +1627,15 @@ mod tests {
 
         std::fs::remove_file(temp_file).ok();
     }
+
+    #[test]
+    fn test_missing_directory_is_not_an_error() {
+        let missing = Path::new("/nonexistent/pipewire/pipewire.conf.d");
+        let not_found = fs::read_dir(missing).unwrap_err();
+        assert!(directory_scan_error(missing, &not_found).is_none());
+
+        let denied = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+        let message = directory_scan_error(Path::new("/etc/pipewire"), &denied).unwrap();
+        assert!(message.contains("/etc/pipewire"));
+    }
 }
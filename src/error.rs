@@ -0,0 +1,147 @@
+/*
+ * Pro Audio Config - Error Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * Error type for applying configs, so callers can tell a refused password
+ * prompt from a full disk or a missing device
+ */
+
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// Why an apply or a config operation failed. Functions that still return
+/// `Result<_, String>` convert with `?` in both directions; their messages
+/// end up in `Other`.
+#[derive(Debug)]
+pub enum AudioConfigError {
+    /// pkexec/sudo did not grant administrator privileges
+    PrivilegeDenied(String),
+    /// The user dismissed the polkit authentication dialog
+    AuthorizationCancelled,
+    /// No supported service manager restarted the audio services
+    ServiceManagerUnsupported(String),
+    ConfigWriteFailed(PathBuf, io::Error),
+    DeviceNotFound(String),
+    /// Configs were written, but the running server reports other values
+    VerificationMismatch(Vec<String>),
+    Other(String),
+}
+
+impl AudioConfigError {
+    /// Heading for error dialogs
+    pub fn title(&self) -> &'static str {
+        match self {
+            AudioConfigError::PrivilegeDenied(_) => "Administrator privileges not granted",
+            AudioConfigError::AuthorizationCancelled => "Authorization cancelled",
+            AudioConfigError::ServiceManagerUnsupported(_) => "Audio services not restarted",
+            AudioConfigError::ConfigWriteFailed(..) => "Could not write the configuration",
+            AudioConfigError::DeviceNotFound(_) => "Audio device not found",
+            AudioConfigError::VerificationMismatch(_) => "Settings not confirmed",
+            AudioConfigError::Other(_) => "Failed to apply audio settings",
+        }
+    }
+
+    /// Privileges were refused or the prompt was cancelled. Other ways of
+    /// applying would only ask again, so callers stop trying.
+    pub fn is_authorization_failure(&self) -> bool {
+        matches!(
+            self,
+            AudioConfigError::PrivilegeDenied(_) | AudioConfigError::AuthorizationCancelled
+        )
+    }
+}
+
+impl fmt::Display for AudioConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AudioConfigError::PrivilegeDenied(details) => write!(
+                f,
+                "Administrator privileges are needed for system-wide changes: {}",
+                details
+            ),
+            AudioConfigError::AuthorizationCancelled => {
+                f.write_str("No changes made — authorization was cancelled")
+            }
+            AudioConfigError::ServiceManagerUnsupported(manager) => write!(
+                f,
+                "The audio services were stopped and did not come back; restarting them \
+                 is not supported with service manager '{}'. Start PipeWire and \
+                 WirePlumber from your session or log in again.",
+                manager
+            ),
+            AudioConfigError::ConfigWriteFailed(path, e) => {
+                write!(f, "Failed to write {}: {}", path.display(), e)
+            }
+            AudioConfigError::DeviceNotFound(device) => {
+                write!(f, "Audio device {} not found", device)
+            }
+            AudioConfigError::VerificationMismatch(mismatches) => write!(
+                f,
+                "Configs were written, but the running settings differ: {}",
+                mismatches.join("; ")
+            ),
+            AudioConfigError::Other(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for AudioConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AudioConfigError::ConfigWriteFailed(_, e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<String> for AudioConfigError {
+    fn from(message: String) -> Self {
+        AudioConfigError::Other(message)
+    }
+}
+
+impl From<&str> for AudioConfigError {
+    fn from(message: &str) -> Self {
+        AudioConfigError::Other(message.to_string())
+    }
+}
+
+impl From<AudioConfigError> for String {
+    fn from(error: AudioConfigError) -> Self {
+        error.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_conversions() {
+        let error = AudioConfigError::ConfigWriteFailed(
+            PathBuf::from("/etc/pipewire/pipewire.conf.d/99-test.conf"),
+            io::Error::new(io::ErrorKind::PermissionDenied, "denied"),
+        );
+        assert_eq!(
+            error.to_string(),
+            "Failed to write /etc/pipewire/pipewire.conf.d/99-test.conf: denied"
+        );
+        assert!(std::error::Error::source(&error).is_some());
+        assert!(!error.is_authorization_failure());
+        assert!(AudioConfigError::AuthorizationCancelled.is_authorization_failure());
+
+        // Unmigrated String errors pass through unchanged in both directions
+        let error = AudioConfigError::from("Missing audio services: wireplumber".to_string());
+        assert!(matches!(error, AudioConfigError::Other(_)));
+        assert_eq!(String::from(error), "Missing audio services: wireplumber");
+
+        fn legacy() -> Result<(), String> {
+            Err(AudioConfigError::DeviceNotFound("alsa:3".to_string()))?
+        }
+        assert_eq!(legacy().unwrap_err(), "Audio device alsa:3 not found");
+    }
+}
@@ -0,0 +1,310 @@
+/*
+ * Pro Audio Config - Latency Test Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * Measures the real round-trip latency of a physically looped-back
+ * interface with jack_iodelay (run through pw-jack), for comparison with
+ * the latency the configured buffer and rate add up to
+ */
+
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::audio::{detect_current_audio_settings, pipewire_command};
+use crate::audio_capture::connect_port_pairs;
+use crate::utils::find_in_path;
+
+/// Ports jack_iodelay registers under its JACK client name
+const IODELAY_OUT_PORT: &str = "jack_delay:out";
+const IODELAY_IN_PORT: &str = "jack_delay:in";
+
+/// Readings to collect before reporting; jack_iodelay prints about four a second
+const LATENCY_READINGS: usize = 8;
+const MEASURE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// One jack_iodelay result
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatencyReading {
+    pub frames: f64,
+    pub ms: f64,
+}
+
+/// Collects readings from jack_iodelay output, line by line
+#[derive(Debug, Default)]
+pub struct IodelayParser {
+    pub readings: Vec<LatencyReading>,
+    /// "Signal below threshold" lines, i.e. nothing came back on the input
+    pub silent_lines: usize,
+}
+
+impl IodelayParser {
+    pub fn feed(&mut self, line: &str) {
+        if line.contains("Signal below threshold") {
+            self.silent_lines += 1;
+        } else if line.contains("total roundtrip latency") {
+            let numbers: Vec<f64> = line
+                .split_whitespace()
+                .filter_map(|word| word.parse().ok())
+                .collect();
+            if let [frames, ms, ..] = numbers[..] {
+                self.readings.push(LatencyReading { frames, ms });
+            }
+        } else if line.contains("??") {
+            // jack_iodelay marks the line after a doubtful reading with "??"
+            self.readings.pop();
+        }
+    }
+}
+
+/// Measured round trip next to what the running configuration predicts
+#[derive(Debug, Clone)]
+pub struct LatencyMeasurement {
+    pub playback_port: String,
+    pub capture_port: String,
+    pub readings: Vec<LatencyReading>,
+    /// Running quantum and rate, when they could be detected
+    pub configured: Option<(u32, u32)>,
+}
+
+impl LatencyMeasurement {
+    /// Median reading; jack_iodelay's first results can be off while it locks on
+    pub fn median(&self) -> Option<LatencyReading> {
+        let mut readings = self.readings.clone();
+        readings.sort_by(|a, b| a.frames.total_cmp(&b.frames));
+        readings.get(readings.len() / 2).copied()
+    }
+
+    /// Round trip the buffers alone account for: one quantum in, one out
+    pub fn configured_ms(&self) -> Option<f64> {
+        self.configured
+            .map(|(quantum, rate)| 2.0 * quantum as f64 * 1000.0 / rate as f64)
+    }
+
+    /// One line for the Monitoring tab, e.g.
+    /// "Measured Round Trip: 5.11 ms (4.00 ms from the buffers)"
+    pub fn summary(&self) -> String {
+        let Some(median) = self.median() else {
+            return "Measured Round Trip: no readings".to_string();
+        };
+
+        match self.configured_ms() {
+            Some(configured_ms) => format!(
+                "Measured Round Trip: {:.2} ms ({:.2} ms from the buffers)",
+                median.ms, configured_ms
+            ),
+            None => format!("Measured Round Trip: {:.2} ms", median.ms),
+        }
+    }
+
+    pub fn report(&self) -> String {
+        let Some(median) = self.median() else {
+            return "No latency readings were collected.".to_string();
+        };
+
+        let mut lines = vec![
+            format!(
+                "Measured round trip: {:.2} ms ({:.0} frames, median of {} readings)",
+                median.ms,
+                median.frames,
+                self.readings.len()
+            ),
+            format!("Loop: {} -> {}", self.playback_port, self.capture_port),
+        ];
+
+        if let (Some((quantum, rate)), Some(configured_ms)) =
+            (self.configured, self.configured_ms())
+        {
+            lines.push(format!(
+                "Configured buffers: {:.2} ms (2 x {} samples at {} Hz)",
+                configured_ms, quantum, rate
+            ));
+            lines.push(format!(
+                "Converters, driver and hardware add: {:.2} ms",
+                median.ms - configured_ms
+            ));
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Plays jack_iodelay's test signal on the default sink and listens for it on
+/// the default source. The sink's output must be cabled to the source's input.
+pub fn measure_round_trip_latency() -> Result<LatencyMeasurement, String> {
+    if !find_in_path("jack_iodelay") {
+        return Err(
+            "jack_iodelay was not found. Install jack-example-tools (jack_iodelay) \
+             and the PipeWire JACK support (pw-jack)."
+                .to_string(),
+        );
+    }
+
+    let playback_port = default_port("get-default-sink", "--input", "playback_")?;
+    let capture_port = default_port("get-default-source", "--output", "capture_")?;
+
+    // Line buffered, or the readings only arrive when jack_iodelay exits
+    let mut child = pipewire_command("stdbuf")
+        .args(["-oL", "pw-jack", "jack_iodelay"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to start jack_iodelay: {}", e))?;
+
+    let stdout = child.stdout.take().ok_or("jack_iodelay has no output")?;
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let links = connect_port_pairs(&[
+        (IODELAY_OUT_PORT.to_string(), playback_port.clone()),
+        (capture_port.clone(), IODELAY_IN_PORT.to_string()),
+    ]);
+    if links.verified < links.attempted {
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(format!(
+            "Could not connect jack_iodelay to {} and {}",
+            playback_port, capture_port
+        ));
+    }
+
+    let mut parser = IodelayParser::default();
+    let deadline = Instant::now() + MEASURE_TIMEOUT;
+    while parser.readings.len() < LATENCY_READINGS {
+        let left = deadline.saturating_duration_since(Instant::now());
+        match rx.recv_timeout(left) {
+            Ok(line) => parser.feed(&line),
+            Err(_) => break,
+        }
+    }
+
+    let _ = child.kill();
+    let _ = child.wait();
+
+    if parser.readings.is_empty() {
+        return Err(if parser.silent_lines > 0 {
+            format!(
+                "The test signal did not come back on {}. Connect the interface's \
+                 output to its input with a cable and raise the input gain.",
+                capture_port
+            )
+        } else {
+            "jack_iodelay gave no readings. Is pipewire-jack installed?".to_string()
+        });
+    }
+
+    Ok(LatencyMeasurement {
+        playback_port,
+        capture_port,
+        readings: parser.readings,
+        configured: detect_current_audio_settings()
+            .ok()
+            .map(|settings| (settings.buffer_size, settings.sample_rate)),
+    })
+}
+
+/// First port of the default sink or source, e.g. "alsa_output.usb-...:playback_FL"
+fn default_port(pactl_query: &str, port_list: &str, port_prefix: &str) -> Result<String, String> {
+    let output = Command::new("pactl")
+        .arg(pactl_query)
+        .output()
+        .map_err(|e| format!("Failed to run pactl: {}", e))?;
+    let node = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if node.is_empty() {
+        return Err(format!("pactl {} returned no device", pactl_query));
+    }
+
+    let output = pipewire_command("pw-link")
+        .arg(port_list)
+        .output()
+        .map_err(|e| format!("Failed to run pw-link: {}", e))?;
+
+    find_node_port(&String::from_utf8_lossy(&output.stdout), &node, port_prefix)
+        .ok_or_else(|| format!("No {}* port found on {}", port_prefix, node))
+}
+
+fn find_node_port(port_list: &str, node: &str, port_prefix: &str) -> Option<String> {
+    let prefix = format!("{}:{}", node, port_prefix);
+    port_list
+        .lines()
+        .map(str::trim)
+        .find(|port| port.starts_with(&prefix))
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_iodelay_output() {
+        let output = "\
+Signal below threshold...
+   245.312 frames      5.111 ms total roundtrip latency
+\textra loopback latency: 53 frames
+\tuse 26 for the backend arguments -I and -O
+   912.000 frames     19.000 ms total roundtrip latency
+\textra loopback latency: 720 frames
+\tuse 360 for the backend arguments -I and -O ??
+   245.250 frames      5.109 ms total roundtrip latency
+\textra loopback latency: 53 frames
+\tuse 26 for the backend arguments -I and -O Inv
+";
+        let mut parser = IodelayParser::default();
+        output.lines().for_each(|line| parser.feed(line));
+
+        assert_eq!(parser.silent_lines, 1);
+        assert_eq!(
+            parser.readings,
+            vec![
+                LatencyReading {
+                    frames: 245.312,
+                    ms: 5.111
+                },
+                LatencyReading {
+                    frames: 245.25,
+                    ms: 5.109
+                },
+            ]
+        );
+
+        let measurement = LatencyMeasurement {
+            playback_port: "out:playback_FL".to_string(),
+            capture_port: "in:capture_FL".to_string(),
+            readings: parser.readings,
+            configured: Some((96, 48000)),
+        };
+        assert_eq!(measurement.configured_ms(), Some(4.0));
+        assert!(measurement.report().contains("add: 1.11 ms"));
+        assert_eq!(
+            measurement.summary(),
+            "Measured Round Trip: 5.11 ms (4.00 ms from the buffers)"
+        );
+    }
+
+    #[test]
+    fn test_find_node_port() {
+        let ports = "alsa_output.usb-Focusrite:monitor_FL\n\
+                     alsa_output.usb-Focusrite:playback_FL\n\
+                     alsa_output.usb-Focusrite:playback_FR\n";
+        assert_eq!(
+            find_node_port(ports, "alsa_output.usb-Focusrite", "playback_").as_deref(),
+            Some("alsa_output.usb-Focusrite:playback_FL")
+        );
+        assert_eq!(
+            find_node_port(ports, "alsa_output.usb-Other", "playback_"),
+            None
+        );
+    }
+}
@@ -10,50 +10,209 @@
 
 pub mod audio;
 pub mod audio_capture;
+pub mod cli;
 pub mod config;
 pub mod config_inspector;
+pub mod error;
+pub mod latency_test;
+pub mod log_capture;
+pub mod meter;
 pub mod monitoring;
+pub mod pipewire_config;
+pub mod presets;
+pub mod privileged;
+pub mod setting_source;
 pub mod ui;
 pub mod utils;
+pub mod watch;
+pub mod wireplumber_config;
 
 // Re-export main functionality
 pub use audio::{
     AudioDevice,
     AudioSettings,
+    CardProfile,
+    CardProfiles,
+    DSP_LOAD_WARNING,
     DeviceCapabilities,
     DeviceType,
+    EffectsManagers,
+    MixerControl,
+    MixerState,
+    NodeLoad,
+    NodeVolume,
+    PIPEWIRE_DEFAULT_QUANTUM_FLOOR,
+    PRO_AUDIO_PROFILE,
+    PipeWireRemote,
+    ProcessBinaryCheck,
+    PwTopSample,
+    RateSwitching,
+    SettingField,
+    StreamTiming,
+    UseCase,
+    alsa_card_for_device,
+    alsa_mixer_controls,
+    apply_live_settings,
+    buffer_for_latency,
+    buffer_for_rate_change,
+    check_process_binary,
+    choose_audio_format,
+    clear_live_settings,
     detect_all_audio_devices,
     detect_audio_device,
+    detect_card_profiles,
     detect_current_audio_settings,
+    detect_device_capabilities,
+    detect_device_discriminators,
+    detect_device_formats,
+    detect_effects_managers,
     // Enhanced functions for exclusive mode
     detect_high_performance_devices,
     detect_input_audio_device,
     detect_input_audio_devices,
     detect_output_audio_device,
     detect_output_audio_devices,
+    detect_quantum_floor,
+    detect_rate_switching,
     detect_recommended_devices,
+    detect_stream_timings,
     get_device_capabilities,
+    get_node_volume,
+    is_device_present,
     is_device_suitable_for_exclusive_mode,
+    is_effects_node,
+    pipewire_command,
+    resolve_node_id,
     resolve_pipewire_device_name,
+    resolve_pipewire_remote,
     resolve_pulse_device_name,
+    sample_pw_top,
+    select_best_exclusive_device,
+    set_alsa_mixer_switch,
+    set_card_profile,
+    set_node_mute,
+    set_node_volume,
+    set_pipewire_remote,
+    suggest_settings,
+    validate_settings_against_device,
+    wait_for_card_output,
 };
 
 pub use config::{
     AdvancedAudioSettings,
+    ApplyMethod,
+    ApplyOutcome,
+    COMBINE_CONFIG_FILE,
+    COMBINE_SINK_NAME,
+    CONFIG_DIR_ENV,
+    CONFIG_FORMAT_VERSION,
+    ConfigBackup,
+    ConfigScope,
+    DEFAULT_JACK_PERIODS,
+    DEFAULT_PRIORITY_DRIVER,
+    DEFAULT_PRIORITY_SESSION,
+    DEFAULT_RT_TIME_US,
+    DEFAULT_SUSPEND_TIMEOUT_SECONDS,
+    DIRECT_ALSA_CAPTURE_PCM,
+    DIRECT_ALSA_PLAYBACK_PCM,
+    ExclusiveApp,
+    ExclusiveProfile,
+    ImportedSettings,
+    JACK_CONFIG_FILE,
+    JackBackend,
+    JackOutcome,
+    OFFLINE_ENV,
+    PROFILE_SCHEMA_VERSION,
+    PULSE_CONFIG_FILE,
+    PULSE_RESAMPLE_CONFIG_FILE,
+    ProfessionalOptions,
+    ProfessionalProfile,
+    ProfileBundle,
+    ProfileFile,
+    RESAMPLE_QUALITY_LEVELS,
+    RealtimePrivileges,
+    SAFE_CONFIG_FILE,
+    SAFE_QUANTUM,
+    SAFE_SAMPLE_RATE,
+    ServiceManager,
+    ServiceNames,
+    SettingCheck,
+    VIRTUAL_DEVICES_CONFIG_FILE,
+    VIRTUAL_SINK_PREFIX,
     apply_advanced_audio_settings,
+    apply_combined_output,
+    apply_direct_alsa_config,
     apply_input_audio_settings_with_auth_blocking,
+    apply_jack_settings,
+    apply_minimal_safe_config,
     apply_output_audio_settings_with_auth_blocking,
+    apply_pulse_resample_quality,
     apply_user_audio_settings,
     apply_wireplumber_device_config, // NEW: Export this
+    asoundrc_path,
     check_audio_services,
     check_exclusive_mode_status,
+    check_realtime_privileges,
     cleanup_config_files,
+    combined_output_members,
+    default_channel_positions,
+    detect_jack_backend,
+    detect_service_manager,
+    diff_pending_config,
+    diff_pending_professional_config,
+    export_profile,
+    import_profile,
+    list_config_backups,
+    managed_config_paths,
+    migrate_configs,
+    offline_mode,
+    parse_channel_positions,
+    parse_existing_config,
+    plan_quantum_verified_restart,
+    plan_restart,
+    preview_advanced_professional_settings,
+    preview_audio_settings,
+    pulse_clamped_quantum,
+    pulse_resample_quality,
+    read_exclusive_apps,
+    remove_combined_output,
+    remove_direct_alsa_config,
+    remove_pulse_resample_quality,
+    remove_user_audio_settings,
+    resample_quality_level,
+    resolve_config_path,
+    restore_config_backup,
     restore_standard_audio_mode,
+    rt_time_limits,
+    service_names,
+    set_default_device,
+    set_pulse_quantum_sync,
+    set_rt_time_limits,
+    set_service_names,
+    set_virtual_devices,
     update_audio_settings,
+    user_config_dir,
+    user_home_dir,
+    verify_current_settings,
+    virtual_devices,
+    virtual_sink_node_name,
 };
 
+pub use error::AudioConfigError;
+pub use latency_test::{
+    IodelayParser, LatencyMeasurement, LatencyReading, measure_round_trip_latency,
+};
 pub use monitoring::MonitoringTab;
+pub use pipewire_config::{
+    PipeWireConfig, PipeWireModule, PipeWireObject, SpaValue, parse_spa_json,
+};
+pub use presets::{Preset, builtin_presets};
+pub use setting_source::{
+    ConfigTier, SettingSource, SettingTrace, SourceOrigin, TracedSetting, explain_live_settings,
+    trace_setting,
+};
 pub use ui::{AudioApp, create_section_box, show_error_dialog, show_success_dialog};
+pub use wireplumber_config::{WirePlumberConfig, WirePlumberRule};
 
 #[cfg(test)]
 mod integration_tests {
@@ -129,6 +288,7 @@ mod integration_tests {
             min_buffer_size: 64,
             max_buffer_size: 4096,
             period_sizes: vec![32, 64, 128],
+            detected: true,
         };
 
         assert_eq!(capabilities.sample_rates.len(), 3);
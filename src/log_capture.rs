@@ -0,0 +1,185 @@
+/*
+ * Pro Audio Config - Log Capture Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * Sets up the log output and keeps the recent stdout/stderr output in
+ * memory so the GUI can show it. The output still reaches the terminal;
+ * it is only copied on the way.
+ */
+
+use lazy_static::lazy_static;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::os::fd::{FromRawFd, RawFd};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Older lines are dropped once the buffer holds this many
+pub const MAX_LOG_LINES: usize = 2000;
+
+lazy_static! {
+    static ref LOG_BUFFER: Mutex<LogBuffer> = Mutex::new(LogBuffer::default());
+}
+
+static CAPTURE_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Ring buffer of output lines, numbered so readers can fetch only new ones
+#[derive(Debug, Default)]
+struct LogBuffer {
+    lines: VecDeque<String>,
+    /// Sequence number of the next line to be pushed
+    next_seq: u64,
+}
+
+impl LogBuffer {
+    fn push(&mut self, line: String) {
+        if self.lines.len() == MAX_LOG_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+        self.next_seq += 1;
+    }
+
+    /// Lines pushed at or after `seq` that are still buffered, and the
+    /// sequence number to ask for next time
+    fn since(&self, seq: u64) -> (Vec<String>, u64) {
+        let first_seq = self.next_seq - self.lines.len() as u64;
+        let skip = seq.saturating_sub(first_seq) as usize;
+        (
+            self.lines.iter().skip(skip).cloned().collect(),
+            self.next_seq,
+        )
+    }
+}
+
+/// Sends `log` records to stderr as "LEVEL: message". Debug output is shown
+/// with `verbose`, info and above otherwise; RUST_LOG overrides both.
+pub fn init_logging(verbose: bool) {
+    let level = if verbose {
+        log::LevelFilter::Debug
+    } else {
+        log::LevelFilter::Info
+    };
+
+    let _ = env_logger::Builder::new()
+        .filter_level(level)
+        .parse_default_env()
+        .format(|buf, record| writeln!(buf, "{}: {}", record.level(), record.args()))
+        .try_init();
+}
+
+/// Adds a line to the in-app log without printing it
+pub fn push_log_line(line: &str) {
+    LOG_BUFFER.lock().unwrap().push(line.to_string());
+}
+
+/// Lines logged since `seq` (0 for everything still buffered) and the
+/// sequence number to pass on the next call
+pub fn log_lines_since(seq: u64) -> (Vec<String>, u64) {
+    LOG_BUFFER.lock().unwrap().since(seq)
+}
+
+/// Everything still buffered, one line per row
+pub fn log_text() -> String {
+    let (lines, _) = log_lines_since(0);
+    lines.join("\n")
+}
+
+/// Routes stdout and stderr through pipes whose reader threads copy every
+/// line into the log buffer and pass it on to the original descriptors.
+/// Only the first call does anything.
+pub fn start_log_capture() {
+    if CAPTURE_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    for (fd, name) in [
+        (libc::STDOUT_FILENO, "stdout"),
+        (libc::STDERR_FILENO, "stderr"),
+    ] {
+        if let Err(e) = tee_descriptor(fd) {
+            log::warn!("Could not capture {} for the log pane: {}", name, e);
+        }
+    }
+}
+
+fn tee_descriptor(fd: RawFd) -> Result<(), String> {
+    let mut pipe_fds = [0 as libc::c_int; 2];
+
+    // SAFETY: plain descriptor calls; every returned descriptor is checked and
+    // then owned by exactly one File
+    let original = unsafe {
+        if libc::pipe(pipe_fds.as_mut_ptr()) != 0 {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+
+        let original = libc::dup(fd);
+        if original < 0 || libc::dup2(pipe_fds[1], fd) < 0 {
+            let error = std::io::Error::last_os_error().to_string();
+            libc::close(pipe_fds[0]);
+            libc::close(pipe_fds[1]);
+            if original >= 0 {
+                libc::close(original);
+            }
+            return Err(error);
+        }
+        libc::close(pipe_fds[1]);
+
+        original
+    };
+
+    // SAFETY: both descriptors were just created above and are not used elsewhere
+    let reader = unsafe { File::from_raw_fd(pipe_fds[0]) };
+    let mut passthrough = unsafe { File::from_raw_fd(original) };
+
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+        let mut line = Vec::new();
+
+        while let Ok(read) = reader.read_until(b'\n', &mut line) {
+            if read == 0 {
+                break;
+            }
+            let _ = passthrough.write_all(&line);
+            push_log_line(String::from_utf8_lossy(&line).trim_end_matches(['\n', '\r']));
+            line.clear();
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_buffer_since() {
+        let mut buffer = LogBuffer::default();
+        buffer.push("first".to_string());
+        buffer.push("second".to_string());
+
+        let (lines, next) = buffer.since(0);
+        assert_eq!(lines, vec!["first", "second"]);
+        assert_eq!(next, 2);
+
+        buffer.push("third".to_string());
+        assert_eq!(buffer.since(next), (vec!["third".to_string()], 3));
+        assert_eq!(buffer.since(3), (Vec::new(), 3));
+
+        for i in 0..MAX_LOG_LINES {
+            buffer.push(format!("line {}", i));
+        }
+        assert_eq!(buffer.lines.len(), MAX_LOG_LINES);
+
+        // Lines that were dropped are skipped, not repeated
+        let (lines, next) = buffer.since(1);
+        assert_eq!(lines.len(), MAX_LOG_LINES);
+        assert_eq!(lines[0], "line 0");
+        assert_eq!(next, 3 + MAX_LOG_LINES as u64);
+    }
+}
@@ -11,8 +11,40 @@
 use gtk::Application;
 use gtk::prelude::*;
 use pro_audio_config::AudioApp;
+use pro_audio_config::cli;
 
 fn main() {
+    // --verbose can go anywhere; GTK would reject it, so it is taken out here
+    let mut args: Vec<String> = std::env::args().collect();
+    let verbose = args.iter().any(|arg| arg == "--verbose" || arg == "-v");
+    args.retain(|arg| arg != "--verbose" && arg != "-v");
+    pro_audio_config::log_capture::init_logging(verbose);
+
+    // Headless modes must be handled before GTK parses the arguments
+    match cli::parse_cli_args(&args[1..]) {
+        Ok(Some(command)) => {
+            if let Err(e) = cli::run_cli(command) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            return;
+        }
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("Error: {}\n\n{}", e, cli::CLI_USAGE);
+            std::process::exit(2);
+        }
+    }
+
+    // Without a display GTK would panic inside Application::run
+    if let Err(e) = gtk::init() {
+        eprintln!("{}", gui_unavailable_message(&e.to_string()));
+        std::process::exit(1);
+    }
+
+    // Desktop launches have no terminal; keep the output for the log pane
+    pro_audio_config::log_capture::start_log_capture();
+
     let app = Application::new(Some("com.example.audio-config"), Default::default());
 
     app.connect_activate(|app| {
@@ -20,5 +52,34 @@ fn main() {
         audio_app.window.show_all();
     });
 
-    app.run();
+    app.run_with_args(&args);
+}
+
+/// Guidance for headless sessions instead of a panic backtrace
+fn gui_unavailable_message(error: &str) -> String {
+    let display = std::env::var("DISPLAY").unwrap_or_default();
+    let wayland_display = std::env::var("WAYLAND_DISPLAY").unwrap_or_default();
+    let unset = |value: &str| {
+        if value.is_empty() {
+            "(not set)".to_string()
+        } else {
+            value.to_string()
+        }
+    };
+
+    format!(
+        "Error: Could not start the graphical interface: {}\n\n\
+         No usable display was found. Check that you are in a graphical session:\n  \
+         DISPLAY={}\n  \
+         WAYLAND_DISPLAY={}\n\n\
+         Without a display, use a command-line mode instead:\n  \
+         pro-audio-config --apply-output ...  apply output settings (see --help)\n  \
+         pro-audio-config --detect            list devices and the running settings\n  \
+         pro-audio-config --watch <process>   apply the session profile while <process> runs\n  \
+         pro-audio-config --meter [ms]        print left/right output levels in dBFS\n\n\
+         Add --verbose (or set RUST_LOG=debug) for detailed output.",
+        error,
+        unset(&display),
+        unset(&wayland_display)
+    )
 }
@@ -0,0 +1,95 @@
+/*
+ * Pro Audio Config - Level Meter Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * Headless level meter (--meter [interval_ms]): prints the left/right
+ * peaks of the monitor capture to stdout until interrupted
+ */
+
+use std::io::{self, Write};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::audio_capture::{AudioLevels, DEFAULT_UPDATE_INTERVAL_MS, PipeWireMonitor};
+
+/// Cleared by SIGINT/SIGTERM so the capture stream is closed before exiting
+static KEEP_METERING: AtomicBool = AtomicBool::new(true);
+
+extern "C" fn stop_metering(_signal: libc::c_int) {
+    KEEP_METERING.store(false, Ordering::SeqCst);
+}
+
+/// Prints one line per update, e.g. "L -12.3 R -14.1" (dBFS). Never falls
+/// back to simulated levels: no PipeWire capture is an error, so a script
+/// can tell silence from a meter that is not connected. Stops quietly when
+/// the reader goes away (`--meter | head`).
+pub fn run_meter(interval_ms: Option<u64>) -> Result<(), String> {
+    let interval_ms = interval_ms.unwrap_or(DEFAULT_UPDATE_INTERVAL_MS);
+
+    let handler = stop_metering as extern "C" fn(libc::c_int) as libc::sighandler_t;
+    unsafe {
+        libc::signal(libc::SIGINT, handler);
+        libc::signal(libc::SIGTERM, handler);
+    }
+
+    let monitor = PipeWireMonitor::new()
+        .with_update_interval(Arc::new(AtomicU64::new(interval_ms)))
+        .without_simulation();
+    let (tx, rx) = mpsc::channel();
+    let handle = monitor.start(tx)?;
+
+    let mut result = Ok(());
+    while KEEP_METERING.load(Ordering::SeqCst) {
+        match rx.recv_timeout(Duration::from_millis(200)) {
+            Ok(levels) => {
+                if writeln!(io::stdout().lock(), "{}", format_levels(&levels)).is_err() {
+                    break;
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                result = Err("Level capture stopped, is PipeWire running?".to_string());
+                break;
+            }
+        }
+    }
+
+    monitor.stop();
+    let _ = handle.join();
+    result
+}
+
+/// The optional `--meter` argument, in milliseconds
+pub fn parse_interval(value: &str) -> Result<u64, String> {
+    match value.parse::<u64>() {
+        Ok(ms) if (10..=5000).contains(&ms) => Ok(ms),
+        _ => Err(format!(
+            "--meter interval must be 10 to 5000 ms, got '{}'",
+            value
+        )),
+    }
+}
+
+fn format_levels(levels: &AudioLevels) -> String {
+    format!("L {:.1} R {:.1}", levels.left_dbfs, levels.right_dbfs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meter_output() {
+        assert_eq!(parse_interval("50"), Ok(50));
+        assert!(parse_interval("5").is_err());
+        assert!(parse_interval("fast").is_err());
+
+        let levels = AudioLevels::from_peaks(0.5, 0.0);
+        assert_eq!(format_levels(&levels), "L -6.0 R -80.0");
+    }
+}
@@ -8,19 +8,38 @@
  */
 
 use crate::audio::{
-    clear_cache as clear_audio_cache, detect_audio_system, detect_current_audio_settings,
-    detect_input_audio_device, detect_output_audio_device,
+    DEFAULT_SINK_TARGET, DEFAULT_SOURCE_TARGET, DSP_LOAD_WARNING, NodeLoad, NodeVolume,
+    StreamTiming, clear_cache as clear_audio_cache, detect_audio_system,
+    detect_current_audio_settings, detect_input_audio_device, detect_output_audio_device,
+    get_node_volume, pipewire_command, sample_pw_top, set_node_mute, set_node_volume,
 };
 use glib::ControlFlow;
 use gtk::prelude::*;
-use gtk::{Box as GtkBox, Button, Frame, Label, Orientation, ProgressBar, Separator};
+use gtk::{Box as GtkBox, Button, Label, Orientation, ProgressBar, Scale, Separator, ToggleButton};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 use std::time::Instant;
 
-use crate::audio_capture::PipeWireMonitor;
+use crate::audio_capture::{LinkResult, PipeWireMonitor, connect_port_pairs};
+use crate::latency_test::{LatencyMeasurement, measure_round_trip_latency};
+use crate::setting_source::explain_live_settings;
+use crate::ui::components::{
+    create_constrained_combo, create_section_box, mark_verbose_label, populate_combo_box,
+};
+use crate::ui::constants::MONITOR_INTERVALS;
+use crate::ui::dialogs::confirm_latency_measurement;
+use crate::ui::preferences::AppPreferences;
+use crate::ui::tasks::add_ui_timeout;
+
+/// Consecutive pw-top samples (5 s apart) a node must stay above
+/// `DSP_LOAD_WARNING` before it is reported as overloaded
+const DSP_LOAD_SUSTAINED_SAMPLES: u32 = 3;
 
 // Message types for thread communication
 #[derive(Debug, Clone)]
@@ -36,6 +55,26 @@ enum MonitorMessage {
         output: String,
         input: String,
     },
+    Volumes {
+        output: Option<NodeVolume>,
+        input: Option<NodeVolume>,
+    },
+    Streams {
+        streams: Result<Vec<StreamTiming>, String>,
+        default_quantum: u32,
+        default_rate: u32,
+    },
+    DspLoad(Result<Vec<NodeLoad>, String>),
+    /// Xruns counted since monitoring started
+    Xruns(u32),
+    /// Answer of the "What Sets These?" lookup
+    SettingSources(Result<String, String>),
+    /// Result of the loopback latency test; the full report is only shown
+    /// for measurements the user started with the button
+    LatencyMeasured {
+        result: Result<LatencyMeasurement, String>,
+        show_report: bool,
+    },
     Levels {
         left_level: f64,
         left_db: String,
@@ -57,26 +96,49 @@ pub struct MonitoringTab {
     status_label: Label,
     config_info_label: Label,
     device_info_label: Label,
+    output_volume_label: Label,
+    input_volume_label: Label,
+    output_volume_scale: Scale,
+    input_volume_scale: Scale,
+    output_mute_button: ToggleButton,
+    input_mute_button: ToggleButton,
+    /// Set while a refresh moves the mute buttons, so that is not sent back to wpctl
+    volume_updating: Rc<Cell<bool>>,
+    streams_label: Label,
+    /// Load of the busiest driver, i.e. of the whole graph cycle
+    dsp_load_bar: ProgressBar,
+    dsp_load_label: Label,
+    /// Per node name, how many samples in a row it has been above the warning load
+    dsp_overload_counts: Arc<Mutex<HashMap<String, u32>>>,
+    xrun_label: Label,
+    /// Count shown last, to highlight the label when it goes up
+    last_xrun_count: Rc<Cell<u32>>,
     sample_rate_label: Label,
     bit_depth_label: Label,
     buffer_size_label: Label,
+    /// Last measured round trip, next to the buffer latency it is compared with
+    measured_latency_label: Label,
+    /// Set once a measurement succeeded, so a reconnect offers to measure again
+    latency_measured: Rc<Cell<bool>>,
+    /// Measure again once the reconnect has restarted monitoring
+    remeasure_after_restart: Rc<Cell<bool>>,
+    sources_button: Button,
+    latency_button: Button,
     left_channel_meter: ProgressBar,
     right_channel_meter: ProgressBar,
     system_info_label: Label,
     reconnect_button: Button,
     update_thread_running: Arc<Mutex<bool>>,
+    /// Meter and UI refresh interval in ms, read by the capture and update loops
+    update_interval_ms: Arc<AtomicU64>,
     sender: mpsc::Sender<MonitorMessage>,
 }
 
-fn manual_pw_link_connection() -> Result<(), String> {
-    use std::process::Command;
-    use std::thread;
-    use std::time::Duration;
-
-    println!("MANUAL: Listing all monitor ports...");
+fn manual_pw_link_connection() -> Result<LinkResult, String> {
+    log::info!("MANUAL: Listing all monitor ports...");
 
     // First, list all monitor ports
-    let output = Command::new("pw-link")
+    let output = pipewire_command("pw-link")
         .args(["--output"])
         .output()
         .map_err(|e| format!("pw-link failed: {}", e))?;
@@ -91,7 +153,7 @@ fn manual_pw_link_connection() -> Result<(), String> {
     for line in output_str.lines() {
         if line.contains("monitor_") && !line.contains("pro_audio_config") {
             monitor_ports.push(line.trim().to_string());
-            println!("MANUAL: Found monitor port: {}", line.trim());
+            log::info!("MANUAL: Found monitor port: {}", line.trim());
         }
     }
 
@@ -100,7 +162,7 @@ fn manual_pw_link_connection() -> Result<(), String> {
     }
 
     // Get your app's input ports
-    let input_output = Command::new("pw-link")
+    let input_output = pipewire_command("pw-link")
         .args(["--input"])
         .output()
         .map_err(|e| format!("pw-link --input failed: {}", e))?;
@@ -111,7 +173,7 @@ fn manual_pw_link_connection() -> Result<(), String> {
     for line in input_str.lines() {
         if line.contains("pro_audio_config:input_") {
             input_ports.push(line.trim().to_string());
-            println!("MANUAL: Found input port: {}", line.trim());
+            log::info!("MANUAL: Found input port: {}", line.trim());
         }
     }
 
@@ -119,9 +181,8 @@ fn manual_pw_link_connection() -> Result<(), String> {
         return Err("No pro_audio_config input ports found. Is the app running?".to_string());
     }
 
-    // Try to connect matching channels
-    let mut connected = 0;
-    let mut errors = Vec::new();
+    // Collect matching channel pairs
+    let mut pairs = Vec::new();
 
     for monitor_port in &monitor_ports {
         // Extract channel name
@@ -133,60 +194,30 @@ fn manual_pw_link_connection() -> Result<(), String> {
             let target_port = format!("pro_audio_config:input_{}", simple_channel);
 
             if input_ports.iter().any(|p| p == &target_port) {
-                println!("MANUAL: Connecting {} -> {}", monitor_port, target_port);
-
-                for attempt in 1..=3 {
-                    match Command::new("pw-link")
-                        .args([monitor_port, &target_port])
-                        .status()
-                    {
-                        Ok(status) if status.success() => {
-                            println!("MANUAL: ✓ Connected {} channel", simple_channel);
-                            connected += 1;
-                            thread::sleep(Duration::from_millis(100));
-                            break;
-                        }
-                        Ok(_) if attempt < 3 => {
-                            println!("MANUAL: Retry {}...", attempt);
-                            thread::sleep(Duration::from_millis(300));
-                        }
-                        Err(e) if attempt < 3 => {
-                            println!("MANUAL: Error: {}, retrying...", e);
-                            thread::sleep(Duration::from_millis(300));
-                        }
-                        _ => {
-                            errors.push(format!("Failed to connect {}", simple_channel));
-                        }
-                    }
-                }
+                log::info!("MANUAL: Connecting {} -> {}", monitor_port, target_port);
+                pairs.push((monitor_port.clone(), target_port));
             }
         }
     }
 
-    if connected > 0 {
-        println!("MANUAL: Successfully connected {} channels", connected);
-
-        // Verify connections
-        thread::sleep(Duration::from_millis(500));
+    if pairs.is_empty() {
+        return Err("Failed to connect any channels: No matching channels found".to_string());
+    }
 
-        if let Ok(verify) = Command::new("pw-link").args(["--links"]).output() {
-            let verify_str = String::from_utf8_lossy(&verify.stdout);
-            let links = verify_str
-                .lines()
-                .filter(|line| line.contains("pro_audio_config"))
-                .count();
-            println!("MANUAL: Verified {} active connections", links);
-        }
+    let result = connect_port_pairs(&pairs);
 
-        Ok(())
+    if result.is_success() {
+        log::info!(
+            "MANUAL: Successfully connected {}/{} channels",
+            result.verified,
+            result.attempted
+        );
+        Ok(result)
     } else {
-        let all_errors = if errors.is_empty() {
-            "No matching channels found".to_string()
-        } else {
-            errors.join(", ")
-        };
-
-        Err(format!("Failed to connect any channels: {}", all_errors))
+        Err(format!(
+            "Failed to connect any channels: {} of {} links could be verified",
+            result.verified, result.attempted
+        ))
     }
 }
 
@@ -218,6 +249,7 @@ impl MonitoringTab {
         ));
         status_info_label.set_line_wrap(true);
         status_info_label.set_halign(gtk::Align::Start);
+        mark_verbose_label(&status_info_label);
 
         status_box.pack_start(&status_label, false, false, 0);
         status_box.pack_start(&status_info_label, false, false, 0);
@@ -238,6 +270,14 @@ impl MonitoringTab {
         let buffer_size_label = Label::new(Some("Buffer Size: --"));
         buffer_size_label.set_halign(gtk::Align::Start);
 
+        let measured_latency_label = Label::new(Some("Measured Round Trip: not measured"));
+        measured_latency_label.set_halign(gtk::Align::Start);
+        measured_latency_label.set_tooltip_text(Some(
+            "Round trip measured with Measure Latency, against the two buffers the \
+             calculated figure accounts for. The rest comes from converters, the driver \
+             and the ALSA period.",
+        ));
+
         let system_info_label = Label::new(Some("Audio System: --"));
         system_info_label.set_halign(gtk::Align::Start);
 
@@ -245,8 +285,27 @@ impl MonitoringTab {
         config_box.pack_start(&sample_rate_label, false, false, 0);
         config_box.pack_start(&bit_depth_label, false, false, 0);
         config_box.pack_start(&buffer_size_label, false, false, 0);
+        config_box.pack_start(&measured_latency_label, false, false, 0);
         config_box.pack_start(&system_info_label, false, false, 0);
 
+        let sources_button = Button::with_label("What Sets These?");
+        sources_button.set_tooltip_text(Some(
+            "Find the config file, runtime override or client responsible for the running \
+             sample rate and quantum",
+        ));
+        sources_button.set_halign(gtk::Align::Start);
+
+        let latency_button = Button::with_label("Measure Latency");
+        latency_button.set_tooltip_text(Some(
+            "Measure the real round-trip latency with jack_iodelay. Needs the output \
+             cabled back to the input.",
+        ));
+
+        let config_buttons = GtkBox::new(Orientation::Horizontal, 6);
+        config_buttons.pack_start(&sources_button, false, false, 0);
+        config_buttons.pack_start(&latency_button, false, false, 0);
+        config_box.pack_start(&config_buttons, false, false, 6);
+
         // ===== DEVICE INFO SECTION =====
         let (device_frame, device_box) = create_section_box("Audio Devices");
 
@@ -255,13 +314,64 @@ impl MonitoringTab {
         device_info_label.set_line_wrap(true);
         device_info_label.set_selectable(true);
 
+        let (input_volume_box, input_volume_label, input_volume_scale, input_mute_button) =
+            Self::create_volume_row("Input Volume");
+
         device_box.pack_start(&device_info_label, false, false, 0);
+        device_box.pack_start(&input_volume_box, false, false, 0);
+
+        // ===== ACTIVE STREAMS SECTION =====
+        let (streams_frame, streams_box) = create_section_box("Active Streams");
+
+        let streams_info_label = Label::new(Some(
+            "Quantum and rate each running stream actually gets. Streams run at the quantum of \
+             the device they play through, which a client can change for everyone on it.",
+        ));
+        streams_info_label.set_line_wrap(true);
+        streams_info_label.set_halign(gtk::Align::Start);
+        mark_verbose_label(&streams_info_label);
+
+        let streams_label = Label::new(Some("Loading active streams..."));
+        streams_label.set_halign(gtk::Align::Start);
+        streams_label.set_line_wrap(true);
+        streams_label.set_selectable(true);
+
+        streams_box.pack_start(&streams_info_label, false, false, 0);
+        streams_box.pack_start(&streams_label, false, false, 0);
+
+        // ===== DSP LOAD SECTION =====
+        let (dsp_load_frame, dsp_load_box) = create_section_box("DSP Load");
+
+        let dsp_load_info_label = Label::new(Some(
+            "Share of each cycle (quantum) a running node spends processing. A driver's load \
+             covers the whole graph. Above 80% xruns are close: raise the buffer size or \
+             lighten the plugin load.",
+        ));
+        dsp_load_info_label.set_line_wrap(true);
+        dsp_load_info_label.set_halign(gtk::Align::Start);
+        mark_verbose_label(&dsp_load_info_label);
+
+        let dsp_load_bar = ProgressBar::new();
+        dsp_load_bar.set_show_text(true);
+        dsp_load_bar.set_text(Some("Driver load: --"));
+        dsp_load_bar.set_fraction(0.0);
+        dsp_load_bar.set_width_request(300);
+
+        let dsp_load_label = Label::new(Some("Loading DSP load..."));
+        dsp_load_label.set_halign(gtk::Align::Start);
+        dsp_load_label.set_line_wrap(true);
+        dsp_load_label.set_selectable(true);
+
+        dsp_load_box.pack_start(&dsp_load_info_label, false, false, 0);
+        dsp_load_box.pack_start(&dsp_load_bar, false, false, 6);
+        dsp_load_box.pack_start(&dsp_load_label, false, false, 0);
 
         // ===== AUDIO METERS SECTION =====
         let (meter_frame, meter_box) = create_section_box("Audio Output Levels");
 
         let meter_info_label = Label::new(Some("Channel output levels (real-time monitoring):"));
         meter_info_label.set_halign(gtk::Align::Start);
+        mark_verbose_label(&meter_info_label);
 
         // Left channel meter
         let left_channel_box = GtkBox::new(Orientation::Horizontal, 6);
@@ -330,12 +440,70 @@ impl MonitoringTab {
         level_key_box.pack_start(&warning_box, false, false, 0);
         level_key_box.pack_start(&danger_box, false, false, 0);
 
+        // Refresh interval: fast for catching peaks, slow to save power
+        let update_interval_ms =
+            Arc::new(AtomicU64::new(AppPreferences::load().monitor_interval_ms()));
+
+        let interval_box = GtkBox::new(Orientation::Horizontal, 6);
+        let interval_label = Label::new(Some("Update Interval:"));
+        let interval_combo = create_constrained_combo();
+        populate_combo_box(&interval_combo, MONITOR_INTERVALS);
+        interval_combo.set_active_id(Some(
+            &update_interval_ms.load(Ordering::Relaxed).to_string(),
+        ));
+        interval_combo.set_tooltip_text(Some(
+            "How often the meters and the status are refreshed. Short intervals catch \
+             transients while tracking, long ones save power on battery.",
+        ));
+        interval_box.pack_start(&interval_label, false, false, 0);
+        interval_box.pack_start(&interval_combo, false, false, 0);
+
+        let xrun_label = Label::new(Some("XRUNs: --"));
+        xrun_label.set_halign(gtk::Align::Start);
+        xrun_label.set_tooltip_text(Some(
+            "Buffer under- and overruns on any node since monitoring started, read from \
+             pw-top every 5 seconds. Lower the buffer in the Advanced tab until this starts \
+             counting to find the lowest stable setting.",
+        ));
+
+        let interval_for_combo = Arc::clone(&update_interval_ms);
+        interval_combo.connect_changed(move |combo| {
+            let Some(interval) = combo.active_id().and_then(|id| id.parse::<u64>().ok()) else {
+                return;
+            };
+            interval_for_combo.store(interval, Ordering::Relaxed);
+            if let Err(e) =
+                AppPreferences::update(|prefs| prefs.monitor_interval_ms = Some(interval))
+            {
+                log::warn!("Failed to save preferences: {}", e);
+            }
+        });
+
         meter_box.pack_start(&meter_info_label, false, false, 0);
+        meter_box.pack_start(&interval_box, false, false, 0);
+        meter_box.pack_start(&xrun_label, false, false, 0);
         meter_box.pack_start(&left_channel_box, false, false, 6);
         meter_box.pack_start(&right_channel_box, false, false, 6);
         meter_box.pack_start(&Separator::new(Orientation::Horizontal), false, false, 12);
         meter_box.pack_start(&level_key_box, false, false, 0);
 
+        // ===== PLAYBACK CONTROL SECTION =====
+        let (playback_frame, playback_box) = create_section_box("Playback Control");
+
+        let playback_info_label = Label::new(Some(
+            "Volume and mute of the default output. Changes made in other mixers show up \
+             here within a few seconds.",
+        ));
+        playback_info_label.set_line_wrap(true);
+        playback_info_label.set_halign(gtk::Align::Start);
+        mark_verbose_label(&playback_info_label);
+
+        let (output_volume_box, output_volume_label, output_volume_scale, output_mute_button) =
+            Self::create_volume_row("Output Volume");
+
+        playback_box.pack_start(&playback_info_label, false, false, 0);
+        playback_box.pack_start(&output_volume_box, false, false, 0);
+
         // ===== RECONNECT BUTTON SECTION =====
         let (button_frame, button_box) = create_section_box("Manual Connection");
 
@@ -347,6 +515,7 @@ impl MonitoringTab {
         ));
         button_info_label.set_line_wrap(true);
         button_info_label.set_halign(gtk::Align::Start);
+        mark_verbose_label(&button_info_label);
 
         button_box.pack_start(&reconnect_button, false, false, 0);
         button_box.pack_start(&button_info_label, false, false, 0);
@@ -355,7 +524,10 @@ impl MonitoringTab {
         container.pack_start(&status_frame, false, false, 0);
         container.pack_start(&config_frame, false, false, 0);
         container.pack_start(&device_frame, false, false, 0);
+        container.pack_start(&streams_frame, false, false, 0);
+        container.pack_start(&dsp_load_frame, false, false, 0);
         container.pack_start(&meter_frame, false, false, 0);
+        container.pack_start(&playback_frame, false, false, 0);
         container.pack_start(&button_frame, false, false, 0);
 
         // Create channel for thread communication
@@ -366,35 +538,77 @@ impl MonitoringTab {
             status_label,
             config_info_label,
             device_info_label,
+            output_volume_label,
+            input_volume_label,
+            output_volume_scale,
+            input_volume_scale,
+            output_mute_button,
+            input_mute_button,
+            volume_updating: Rc::new(Cell::new(false)),
+            streams_label,
+            dsp_load_bar,
+            dsp_load_label,
+            dsp_overload_counts: Arc::new(Mutex::new(HashMap::new())),
+            xrun_label,
+            last_xrun_count: Rc::new(Cell::new(0)),
             sample_rate_label,
             bit_depth_label,
             buffer_size_label,
+            measured_latency_label,
+            latency_measured: Rc::new(Cell::new(false)),
+            remeasure_after_restart: Rc::new(Cell::new(false)),
+            sources_button,
+            latency_button,
             left_channel_meter,
             right_channel_meter,
             system_info_label,
             reconnect_button,
             update_thread_running: Arc::new(Mutex::new(false)),
+            update_interval_ms,
             sender,
         };
 
+        // Volume sliders only act on user input, not on periodic refreshes
+        Self::connect_volume_scale(&tab.output_volume_scale, DEFAULT_SINK_TARGET);
+        Self::connect_volume_scale(&tab.input_volume_scale, DEFAULT_SOURCE_TARGET);
+        Self::connect_mute_button(
+            &tab.output_mute_button,
+            DEFAULT_SINK_TARGET,
+            &tab.volume_updating,
+        );
+        Self::connect_mute_button(
+            &tab.input_mute_button,
+            DEFAULT_SOURCE_TARGET,
+            &tab.volume_updating,
+        );
+
         // Set up button click handler
         let tab_for_button = tab.clone();
         tab.reconnect_button.connect_clicked(move |_| {
             tab_for_button.manual_reconnect();
         });
 
-        // Set up receiver in the main thread
-        let tab_clone = tab.clone();
-        glib::timeout_add_local(Duration::from_millis(100), move || {
-            // Check for messages from the monitoring thread
-            while let Ok(message) = receiver.try_recv() {
-                tab_clone.handle_message(message);
+        let sources_sender = tab.sender.clone();
+        tab.sources_button.connect_clicked(move |button| {
+            button.set_sensitive(false);
+            let sender = sources_sender.clone();
+            thread::spawn(move || {
+                let _ = sender.send(MonitorMessage::SettingSources(explain_live_settings()));
+            });
+        });
+
+        let tab_for_latency = tab.clone();
+        tab.latency_button.connect_clicked(move |_| {
+            if confirm_latency_measurement() {
+                tab_for_latency.measure_latency(true);
             }
-            ControlFlow::Continue
         });
 
+        // Set up receiver in the main thread
+        Self::poll_messages(tab.clone(), Rc::new(receiver));
+
         // ====== CRITICAL: START MONITORING FIRST ======
-        println!("INFO: Starting audio monitoring...");
+        log::info!("Starting audio monitoring...");
         tab.start_monitoring();
 
         // ====== THEN SCHEDULE DELAYED AUTO-CONNECT ======
@@ -403,17 +617,18 @@ impl MonitoringTab {
             // Wait 5 seconds for monitor to be fully initialized
             std::thread::sleep(std::time::Duration::from_secs(5));
 
-            println!("INFO: Starting delayed auto-connect...");
+            log::info!("Starting delayed auto-connect...");
 
             match crate::audio_capture::auto_connect_monitor_delayed() {
-                Ok(_) => {
-                    println!("INFO: ✓ Delayed auto-connect successful!");
-                    let _ = tab_clone2
-                        .sender
-                        .send(MonitorMessage::Status("✓ Monitor connected".to_string()));
+                Ok(result) => {
+                    log::info!("✓ Delayed auto-connect successful!");
+                    let _ = tab_clone2.sender.send(MonitorMessage::Status(format!(
+                        "✓ Monitor connected ({} channels)",
+                        result.verified
+                    )));
                 }
                 Err(e) => {
-                    println!("WARN: Delayed auto-connect failed: {}", e);
+                    log::warn!("Delayed auto-connect failed: {}", e);
                     let _ = tab_clone2
                         .sender
                         .send(MonitorMessage::Status(format!("⚠ {}", e)));
@@ -424,6 +639,254 @@ impl MonitoringTab {
         tab
     }
 
+    /// Label plus slider for one default node's volume
+    fn create_volume_row(title: &str) -> (GtkBox, Label, Scale, ToggleButton) {
+        let row = GtkBox::new(Orientation::Horizontal, 6);
+
+        let label = Label::new(Some(&format!("{}: --", title)));
+        label.set_halign(gtk::Align::Start);
+        label.set_width_request(220);
+
+        let scale = Scale::with_range(Orientation::Horizontal, 0.0, 100.0, 1.0);
+        scale.set_draw_value(false);
+        scale.set_hexpand(true);
+        scale.set_tooltip_text(Some("Adjust volume (wpctl set-volume)"));
+
+        let mute_button = ToggleButton::with_label("Mute");
+        mute_button.set_tooltip_text(Some("Mute or unmute (wpctl set-mute)"));
+
+        row.pack_start(&label, false, false, 0);
+        row.pack_start(&scale, true, true, 0);
+        row.pack_start(&mute_button, false, false, 0);
+
+        (row, label, scale, mute_button)
+    }
+
+    /// A drag sends many values; one worker applies them in order and skips
+    /// to the newest, so the volume ends where the slider was let go
+    fn connect_volume_scale(scale: &Scale, target: &'static str) {
+        let (tx, rx) = mpsc::channel::<f64>();
+        thread::spawn(move || {
+            while let Ok(mut value) = rx.recv() {
+                while let Ok(newer) = rx.try_recv() {
+                    value = newer;
+                }
+                if let Err(e) = set_node_volume(target, value / 100.0) {
+                    log::warn!("{}", e);
+                }
+            }
+        });
+
+        scale.connect_change_value(move |_, _, value| {
+            let _ = tx.send(value.clamp(0.0, 100.0));
+            gtk::glib::Propagation::Proceed
+        });
+    }
+
+    fn connect_mute_button(button: &ToggleButton, target: &'static str, updating: &Rc<Cell<bool>>) {
+        let updating = Rc::clone(updating);
+        button.connect_toggled(move |button| {
+            if updating.get() {
+                return;
+            }
+            let muted = button.is_active();
+            thread::spawn(move || {
+                if let Err(e) = set_node_mute(target, muted) {
+                    log::warn!("{}", e);
+                }
+            });
+        });
+    }
+
+    fn update_volume_row(
+        label: &Label,
+        scale: &Scale,
+        mute_button: &ToggleButton,
+        updating: &Cell<bool>,
+        title: &str,
+        volume: Option<NodeVolume>,
+    ) {
+        updating.set(true);
+        mute_button.set_active(volume.is_some_and(|volume| volume.muted));
+        mute_button.set_sensitive(volume.is_some());
+        updating.set(false);
+
+        match volume {
+            Some(volume) => {
+                if volume.muted {
+                    label.set_markup(&format!(
+                        "{}: {}% <span foreground='red'><b>(MUTED)</b></span>",
+                        title,
+                        volume.percent()
+                    ));
+                } else {
+                    label.set_text(&format!("{}: {}%", title, volume.percent()));
+                }
+                scale.set_sensitive(true);
+                scale.set_value(volume.percent().min(100) as f64);
+            }
+            None => {
+                label.set_text(&format!("{}: unavailable", title));
+                scale.set_sensitive(false);
+            }
+        }
+    }
+
+    /// One line per stream; streams off the global default are highlighted
+    fn update_streams_label(
+        label: &Label,
+        streams: Result<Vec<StreamTiming>, String>,
+        default_quantum: u32,
+        default_rate: u32,
+    ) {
+        let streams = match streams {
+            Ok(streams) => streams,
+            Err(e) => {
+                label.set_text(&format!("Stream timing unavailable: {}", e));
+                return;
+            }
+        };
+
+        if streams.is_empty() {
+            label.set_text("No active streams");
+            return;
+        }
+
+        let mut markup = format!(
+            "Global default: {} samples @ {} Hz",
+            default_quantum, default_rate
+        );
+        for stream in &streams {
+            let mut line = format!(
+                "{} → {}: {} samples @ {} Hz ({:.1} ms)",
+                stream.name,
+                stream.driver,
+                stream.quantum,
+                stream.rate,
+                stream.quantum as f64 * 1000.0 / stream.rate.max(1) as f64
+            );
+            if let Some(requested) = stream.requested_quantum
+                && requested != stream.quantum
+            {
+                line.push_str(&format!(", asked for {}", requested));
+            }
+
+            let line = gtk::glib::markup_escape_text(&line);
+            if stream.differs_from(default_quantum, default_rate) {
+                markup.push_str(&format!(
+                    "\n<span foreground='orange'><b>⚠ {}</b></span>",
+                    line
+                ));
+            } else {
+                markup.push_str(&format!("\n{}", line));
+            }
+        }
+
+        label.set_markup(&markup);
+    }
+
+    /// Fills the bar with the busiest driver's load, red from `DSP_LOAD_WARNING`
+    fn update_dsp_load_bar(bar: &ProgressBar, loads: Result<&[NodeLoad], &String>) {
+        let context = bar.style_context();
+        context.remove_class("level-safe");
+        context.remove_class("level-danger");
+
+        let Some(driver) = loads.ok().and_then(busiest_driver) else {
+            bar.set_fraction(0.0);
+            bar.set_text(Some("Driver load: --"));
+            return;
+        };
+
+        bar.set_fraction(driver.load.clamp(0.0, 1.0));
+        bar.set_text(Some(&format!(
+            "Driver load: {:.0}% ({})",
+            driver.load * 100.0,
+            driver.name
+        )));
+        context.add_class(if driver.is_overloaded() {
+            "level-danger"
+        } else {
+            "level-safe"
+        });
+    }
+
+    /// One line per running node, busiest first. Nodes over the warning load
+    /// are highlighted, and called out once they stay there for several samples.
+    fn update_dsp_load_label(
+        label: &Label,
+        overload_counts: &Mutex<HashMap<String, u32>>,
+        loads: Result<Vec<NodeLoad>, String>,
+    ) {
+        let mut loads = match loads {
+            Ok(loads) => loads,
+            Err(e) => {
+                label.set_text(&format!("DSP load unavailable: {}", e));
+                return;
+            }
+        };
+
+        let mut counts = overload_counts.lock().unwrap();
+        let sustained = update_overload_counts(&mut counts, &loads);
+
+        if loads.is_empty() {
+            label.set_text("No running nodes");
+            return;
+        }
+
+        loads.sort_by(|a, b| b.load.total_cmp(&a.load));
+
+        let mut markup = String::new();
+        for name in &sustained {
+            markup.push_str(&format!(
+                "<span foreground='red'><b>⚠ {} has used over {:.0}% of every cycle for the \
+                 last {} samples. The buffer is too small for this load; raise the buffer size.</b></span>\n",
+                gtk::glib::markup_escape_text(name),
+                DSP_LOAD_WARNING * 100.0,
+                DSP_LOAD_SUSTAINED_SAMPLES
+            ));
+        }
+
+        let lines: Vec<String> = loads
+            .iter()
+            .map(|node| {
+                let mut line = format!(
+                    "{}: {:.0}% ({} samples @ {} Hz",
+                    node.name,
+                    node.load * 100.0,
+                    node.quantum,
+                    node.rate
+                );
+                match &node.driver {
+                    Some(driver) => line.push_str(&format!(", via {})", driver)),
+                    None => line.push_str(", driver)"),
+                }
+                if node.errors > 0 {
+                    line.push_str(&format!(", {} errors", node.errors));
+                }
+
+                let line = gtk::glib::markup_escape_text(&line);
+                if node.is_overloaded() {
+                    format!("<span foreground='orange'><b>{}</b></span>", line)
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect();
+        markup.push_str(&lines.join("\n"));
+
+        label.set_markup(&markup);
+    }
+
+    /// Red while the count went up since the previous sample
+    fn update_xrun_label(label: &Label, last_count: &Cell<u32>, count: u32) {
+        let text = format!("XRUNs: {}", count);
+        if count > last_count.replace(count) {
+            label.set_markup(&format!("<span foreground='red'><b>{}</b></span>", text));
+        } else {
+            label.set_text(&text);
+        }
+    }
+
     fn load_css() {
         // Try to load CSS from embedded data or file
         let css_data = if cfg!(debug_assertions) {
@@ -444,7 +907,7 @@ impl MonitoringTab {
         let provider = gtk::CssProvider::new();
 
         if let Err(e) = provider.load_from_data(css_data.as_bytes()) {
-            eprintln!("Failed to load CSS: {}", e);
+            log::warn!("Failed to load CSS: {}", e);
             return;
         }
 
@@ -519,6 +982,98 @@ impl MonitoringTab {
                 self.device_info_label
                     .set_text(&format!("Output: {}\nInput: {}", output, input));
             }
+            MonitorMessage::Volumes { output, input } => {
+                Self::update_volume_row(
+                    &self.output_volume_label,
+                    &self.output_volume_scale,
+                    &self.output_mute_button,
+                    &self.volume_updating,
+                    "Output Volume",
+                    output,
+                );
+                Self::update_volume_row(
+                    &self.input_volume_label,
+                    &self.input_volume_scale,
+                    &self.input_mute_button,
+                    &self.volume_updating,
+                    "Input Volume",
+                    input,
+                );
+            }
+            MonitorMessage::Streams {
+                streams,
+                default_quantum,
+                default_rate,
+            } => {
+                Self::update_streams_label(
+                    &self.streams_label,
+                    streams,
+                    default_quantum,
+                    default_rate,
+                );
+            }
+            MonitorMessage::DspLoad(loads) => {
+                Self::update_dsp_load_bar(&self.dsp_load_bar, loads.as_deref());
+                Self::update_dsp_load_label(&self.dsp_load_label, &self.dsp_overload_counts, loads);
+            }
+            MonitorMessage::Xruns(count) => {
+                Self::update_xrun_label(&self.xrun_label, &self.last_xrun_count, count);
+            }
+            MonitorMessage::SettingSources(result) => {
+                self.sources_button.set_sensitive(true);
+                let (message_type, text) = match result {
+                    Ok(report) => (gtk::MessageType::Info, report),
+                    Err(e) => (
+                        gtk::MessageType::Error,
+                        format!("Could not look up the setting sources: {}", e),
+                    ),
+                };
+                let dialog = gtk::MessageDialog::new(
+                    None::<&gtk::Window>,
+                    gtk::DialogFlags::MODAL,
+                    message_type,
+                    gtk::ButtonsType::Ok,
+                    "What Sets the Running Settings",
+                );
+                dialog.set_secondary_text(Some(&text));
+                dialog.connect_response(|dialog, _| dialog.close());
+                dialog.show_all();
+            }
+            MonitorMessage::LatencyMeasured {
+                result,
+                show_report,
+            } => {
+                self.latency_button.set_sensitive(true);
+                let (message_type, text) = match result {
+                    Ok(measurement) => {
+                        self.status_label.set_text("Latency measured");
+                        self.measured_latency_label.set_text(&measurement.summary());
+                        self.latency_measured.set(true);
+                        (gtk::MessageType::Info, measurement.report())
+                    }
+                    Err(e) => {
+                        self.status_label.set_text("Latency measurement failed");
+                        self.measured_latency_label
+                            .set_text("Measured Round Trip: measurement failed");
+                        (gtk::MessageType::Error, e)
+                    }
+                };
+                // A re-measurement after a reconnect only updates the label,
+                // unless it failed
+                if !show_report && message_type == gtk::MessageType::Info {
+                    return;
+                }
+                let dialog = gtk::MessageDialog::new(
+                    None::<&gtk::Window>,
+                    gtk::DialogFlags::MODAL,
+                    message_type,
+                    gtk::ButtonsType::Ok,
+                    "Round-Trip Latency",
+                );
+                dialog.set_secondary_text(Some(&text));
+                dialog.connect_response(|dialog, _| dialog.close());
+                dialog.show_all();
+            }
             MonitorMessage::Levels {
                 left_level,
                 left_db,
@@ -572,7 +1127,7 @@ impl MonitoringTab {
             MonitorMessage::Error(err) => {
                 // Normal error handling
                 self.status_label.set_text(&format!("Error: {}", err));
-                eprintln!("Monitoring error: {}", err);
+                log::warn!("Monitoring error: {}", err);
 
                 // Also re-enable the button if it was disabled
                 self.reconnect_button.set_sensitive(true);
@@ -582,6 +1137,9 @@ impl MonitoringTab {
                 // We're already on the main thread (handle_message is called from GLib timeout)
                 // So we can safely call restart_monitoring directly
                 self.restart_monitoring();
+                if self.remeasure_after_restart.take() {
+                    self.measure_latency(false);
+                }
             }
             MonitorMessage::UpdateButton { sensitive, label } => {
                 // Update button state
@@ -591,7 +1149,31 @@ impl MonitoringTab {
         }
     }
 
+    /// Runs the loopback test in the background; the caller has confirmed it
+    fn measure_latency(&self, show_report: bool) {
+        self.latency_button.set_sensitive(false);
+        self.status_label
+            .set_text("Measuring round-trip latency...");
+        self.measured_latency_label
+            .set_text("Measured Round Trip: measuring...");
+
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let result = measure_round_trip_latency();
+            let _ = sender.send(MonitorMessage::LatencyMeasured {
+                result,
+                show_report,
+            });
+        });
+    }
+
     pub fn manual_reconnect(&self) {
+        // A reconnect can change the path the test signal takes
+        if self.latency_measured.get() {
+            self.remeasure_after_restart
+                .set(confirm_latency_measurement());
+        }
+
         // Update UI on the main thread (we're already on main thread here)
         self.status_label.set_text("Restarting audio monitoring...");
         self.reconnect_button.set_sensitive(false);
@@ -612,18 +1194,19 @@ impl MonitoringTab {
 
             // Try manual connection first
             match manual_pw_link_connection() {
-                Ok(_) => {
-                    println!("INFO: Manual connection successful");
-                    let _ = sender.send(MonitorMessage::Status(
-                        "✓ Manual connection established".to_string(),
-                    ));
+                Ok(result) => {
+                    log::info!("Manual connection successful");
+                    let _ = sender.send(MonitorMessage::Status(format!(
+                        "✓ Manual connection established ({}/{} channels)",
+                        result.verified, result.attempted
+                    )));
 
                     // Still request a restart to ensure everything is fresh
                     thread::sleep(Duration::from_millis(500));
                     let _ = sender.send(MonitorMessage::RestartRequest);
                 }
                 Err(e) => {
-                    println!("WARN: Manual connection failed: {}", e);
+                    log::warn!("Manual connection failed: {}", e);
 
                     // Send error message
                     let _ = sender.send(MonitorMessage::Error(format!(
@@ -647,7 +1230,7 @@ impl MonitoringTab {
     }
 
     pub fn restart_monitoring(&self) {
-        println!("INFO: Restarting entire monitoring system...");
+        log::info!("Restarting entire monitoring system...");
 
         // First, stop current monitoring
         self.stop_monitoring();
@@ -677,10 +1260,10 @@ impl MonitoringTab {
     fn cleanup_pipewire_connections(&self) {
         use std::process::Command;
 
-        println!("INFO: Cleaning up PipeWire connections...");
+        log::info!("Cleaning up PipeWire connections...");
 
         // Method 1: Disconnect all monitor connections
-        if let Ok(output) = Command::new("pw-link").args(["--links"]).output() {
+        if let Ok(output) = pipewire_command("pw-link").args(["--links"]).output() {
             let output_str = String::from_utf8_lossy(&output.stdout);
 
             for line in output_str.lines() {
@@ -688,14 +1271,16 @@ impl MonitoringTab {
                     && line.contains("pro_audio_config")
                     && let Some(connection_id) = line.split_whitespace().next()
                 {
-                    println!("INFO: Disconnecting: {}", connection_id);
-                    let _ = Command::new("pw-link").args(["-d", connection_id]).status();
+                    log::info!("Disconnecting: {}", connection_id);
+                    let _ = pipewire_command("pw-link")
+                        .args(["-d", connection_id])
+                        .status();
                 }
             }
         }
 
         // Method 2: Use pattern matching
-        let _ = Command::new("pw-link")
+        let _ = pipewire_command("pw-link")
             .args(["-d", "-I", "pro_audio_config:input_*"])
             .status();
 
@@ -710,14 +1295,14 @@ impl MonitoringTab {
         let sender = self.sender.clone();
 
         thread::spawn(move || {
-            println!("INFO: Starting auto-connect with retry...");
+            log::info!("Starting auto-connect with retry...");
 
             for attempt in 1..=5 {
-                println!("INFO: Auto-connect attempt {}/5", attempt);
+                log::info!("Auto-connect attempt {}/5", attempt);
 
                 match crate::audio_capture::auto_connect_monitor_delayed() {
                     Ok(_) => {
-                        println!("INFO: Auto-connect successful on attempt {}", attempt);
+                        log::info!("Auto-connect successful on attempt {}", attempt);
                         let _ = sender.send(MonitorMessage::Status(format!(
                             "✓ Connected on attempt {}",
                             attempt
@@ -725,7 +1310,7 @@ impl MonitoringTab {
                         return;
                     }
                     Err(e) => {
-                        println!("WARN: Attempt {} failed: {}", attempt, e);
+                        log::warn!("Attempt {} failed: {}", attempt, e);
 
                         if attempt < 5 {
                             let _ = sender.send(MonitorMessage::Status(format!(
@@ -745,9 +1330,28 @@ impl MonitoringTab {
         });
     }
 
+    /// Handles messages from the monitoring thread every update interval. A
+    /// timeout's interval is fixed, so an interval change re-registers it.
+    fn poll_messages(tab: MonitoringTab, receiver: Rc<mpsc::Receiver<MonitorMessage>>) {
+        let interval_ms = tab.update_interval_ms.load(Ordering::Relaxed);
+
+        add_ui_timeout(Duration::from_millis(interval_ms), move || {
+            while let Ok(message) = receiver.try_recv() {
+                tab.handle_message(message);
+            }
+
+            if tab.update_interval_ms.load(Ordering::Relaxed) != interval_ms {
+                Self::poll_messages(tab.clone(), Rc::clone(&receiver));
+                return ControlFlow::Break;
+            }
+            ControlFlow::Continue
+        });
+    }
+
     pub fn start_monitoring(&self) {
         let sender = self.sender.clone();
         let running_clone = Arc::clone(&self.update_thread_running);
+        let interval_ms = Arc::clone(&self.update_interval_ms);
 
         // Store thread handles to avoid dropping them
         let _thread_handle = thread::spawn(move || {
@@ -756,11 +1360,11 @@ impl MonitoringTab {
                 *running = true;
             }
 
-            let mut iteration = 0;
+            let started = Instant::now();
 
             // ====== CRITICAL: START THE MONITOR FIRST ======
-            println!("INFO: Creating PipeWire monitor...");
-            let monitor = PipeWireMonitor::new();
+            log::info!("Creating PipeWire monitor...");
+            let monitor = PipeWireMonitor::new().with_update_interval(Arc::clone(&interval_ms));
 
             // Update status with monitoring mode
             let monitoring_mode = monitor.get_monitoring_mode();
@@ -770,10 +1374,10 @@ impl MonitoringTab {
             let (audio_tx, audio_rx) = mpsc::channel();
 
             // ====== START THE MONITOR ======
-            println!("INFO: Starting PipeWire audio monitoring...");
+            log::info!("Starting PipeWire audio monitoring...");
             let monitor_handle = match monitor.start(audio_tx) {
                 Ok(handle) => {
-                    println!("INFO: ✓ PipeWire monitor started successfully");
+                    log::info!("✓ PipeWire monitor started successfully");
                     handle
                 }
                 Err(e) => {
@@ -788,15 +1392,19 @@ impl MonitoringTab {
             // Time-based rate limiting for expensive operations
             let mut last_config_check = Instant::now();
             let mut last_device_check = Instant::now();
+            let mut last_stream_check = Instant::now();
             let config_check_interval = Duration::from_secs(2);
             let device_check_interval = Duration::from_secs(4);
+            let stream_check_interval = Duration::from_secs(5);
+            let mut default_timing = None;
+            let xrun_counter = Arc::new(Mutex::new(XrunCounter::default()));
 
             while {
                 let running = running_clone.lock().unwrap();
                 *running
             } {
-                // Check for audio level updates
-                if let Ok(levels) = audio_rx.try_recv() {
+                // Only the newest level update matters
+                if let Some(levels) = audio_rx.try_iter().last() {
                     // Use peak levels for the meter display
                     let _ = sender.send(MonitorMessage::Levels {
                         left_level: levels.left_peak,
@@ -806,8 +1414,8 @@ impl MonitoringTab {
                     });
                 }
 
-                // Update status indicator (blinking dot)
-                let status_text = if iteration % 4 == 0 {
+                // Update status indicator (dot blinking every 400 ms)
+                let status_text = if started.elapsed().as_millis() % 400 < 100 {
                     format!("{} ●", monitoring_mode)
                 } else {
                     format!("{} ○", monitoring_mode)
@@ -820,6 +1428,7 @@ impl MonitoringTab {
                     match detect_current_audio_settings() {
                         Ok(settings) => {
                             let audio_system = detect_audio_system();
+                            default_timing = Some((settings.buffer_size, settings.sample_rate));
 
                             let _ = sender.send(MonitorMessage::Config {
                                 sample_rate: settings.sample_rate,
@@ -835,6 +1444,11 @@ impl MonitoringTab {
                             )));
                         }
                     }
+                    // Follows volume changes made in other mixers
+                    let _ = sender.send(MonitorMessage::Volumes {
+                        output: get_node_volume(DEFAULT_SINK_TARGET).ok(),
+                        input: get_node_volume(DEFAULT_SOURCE_TARGET).ok(),
+                    });
                     last_config_check = Instant::now();
                 }
 
@@ -853,17 +1467,42 @@ impl MonitoringTab {
                     last_device_check = Instant::now();
                 }
 
-                iteration += 1;
-                thread::sleep(Duration::from_millis(100));
+                // pw-top samples for about a second, so it must not stall the meters
+                if last_stream_check.elapsed() >= stream_check_interval
+                    && let Some((default_quantum, default_rate)) = default_timing
+                {
+                    let sender = sender.clone();
+                    let xrun_counter = Arc::clone(&xrun_counter);
+                    thread::spawn(move || {
+                        let (streams, loads) = match sample_pw_top() {
+                            Ok(sample) => {
+                                let xruns =
+                                    xrun_counter.lock().unwrap().update(&sample.node_errors);
+                                let _ = sender.send(MonitorMessage::Xruns(xruns));
+                                (Ok(sample.streams), Ok(sample.loads))
+                            }
+                            Err(e) => (Err(e.clone()), Err(e)),
+                        };
+                        let _ = sender.send(MonitorMessage::DspLoad(loads));
+                        let _ = sender.send(MonitorMessage::Streams {
+                            streams,
+                            default_quantum,
+                            default_rate,
+                        });
+                    });
+                    last_stream_check = Instant::now();
+                }
+
+                thread::sleep(Duration::from_millis(interval_ms.load(Ordering::Relaxed)));
             }
 
             // Stop the audio monitor when the main loop ends
-            println!("INFO: Stopping PipeWire monitor...");
+            log::info!("Stopping PipeWire monitor...");
             monitor.stop();
 
             // Wait for monitor thread to finish
             let _ = monitor_handle.join();
-            println!("INFO: PipeWire monitor stopped");
+            log::info!("PipeWire monitor stopped");
         });
     }
 
@@ -912,7 +1551,7 @@ impl MonitoringTab {
         }
 
         // Force update of device info
-        glib::timeout_add_local(Duration::from_millis(10), {
+        add_ui_timeout(Duration::from_millis(10), {
             let device_info_label = self.device_info_label.clone();
             move || {
                 let output_device = detect_output_audio_device()
@@ -931,30 +1570,65 @@ impl MonitoringTab {
     }
 }
 
-fn create_section_box(title: &str) -> (Frame, GtkBox) {
-    let frame = Frame::new(None);
-    frame.set_margin_top(6);
-    frame.set_margin_bottom(6);
-
-    let section_box = GtkBox::new(Orientation::Vertical, 12);
-    section_box.set_margin_top(12);
-    section_box.set_margin_bottom(12);
-    section_box.set_margin_start(12);
-    section_box.set_margin_end(12);
+/// Counts, per node, the samples in a row it has been over the warning load
+/// and returns the nodes that reached `DSP_LOAD_SUSTAINED_SAMPLES`. Nodes
+/// that dropped below or stopped running start over.
+fn update_overload_counts(counts: &mut HashMap<String, u32>, loads: &[NodeLoad]) -> Vec<String> {
+    counts.retain(|name, _| {
+        loads
+            .iter()
+            .any(|node| &node.name == name && node.is_overloaded())
+    });
+
+    let mut sustained = Vec::new();
+    for node in loads.iter().filter(|node| node.is_overloaded()) {
+        let count = counts.entry(node.name.clone()).or_insert(0);
+        *count += 1;
+        if *count >= DSP_LOAD_SUSTAINED_SAMPLES && !sustained.contains(&node.name) {
+            sustained.push(node.name.clone());
+        }
+    }
 
-    let title_label = Label::new(None);
-    title_label.set_markup(&format!("<b>{}</b>", title));
-    title_label.set_halign(gtk::Align::Start);
+    sustained
+}
 
-    section_box.pack_start(&title_label, false, false, 0);
+/// The driver with the highest load. A driver's load covers its whole graph
+/// cycle, followers included.
+fn busiest_driver(loads: &[NodeLoad]) -> Option<&NodeLoad> {
+    loads
+        .iter()
+        .filter(|node| node.driver.is_none())
+        .max_by(|a, b| a.load.total_cmp(&b.load))
+}
 
-    // Add separator
-    let separator = Separator::new(Orientation::Horizontal);
-    section_box.pack_start(&separator, false, false, 0);
+/// Adds up the xruns of all nodes across pw-top samples. Errors a node had
+/// before the first sample are not counted; nodes that appear later, or
+/// whose counter went back (a reused id), count in full.
+#[derive(Debug, Default)]
+struct XrunCounter {
+    /// ERR counter per node id in the previous sample
+    last_errors: HashMap<u32, u32>,
+    total: u32,
+    primed: bool,
+}
 
-    frame.add(&section_box);
+impl XrunCounter {
+    /// Takes the counters of a new sample and returns the running total
+    fn update(&mut self, node_errors: &HashMap<u32, u32>) -> u32 {
+        if self.primed {
+            for (id, errors) in node_errors {
+                let gained = match self.last_errors.get(id) {
+                    Some(last) if errors >= last => errors - last,
+                    _ => *errors,
+                };
+                self.total = self.total.saturating_add(gained);
+            }
+        }
 
-    (frame, section_box)
+        self.last_errors = node_errors.clone();
+        self.primed = true;
+        self.total
+    }
 }
 
 #[cfg(test)]
@@ -999,6 +1673,57 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_update_overload_counts() {
+        let node = |name: &str, load: f64| NodeLoad {
+            id: 1,
+            name: name.to_string(),
+            driver: None,
+            quantum: 128,
+            rate: 48000,
+            load,
+            errors: 0,
+        };
+        let mut counts = HashMap::new();
+
+        for _ in 1..DSP_LOAD_SUSTAINED_SAMPLES {
+            let sustained =
+                update_overload_counts(&mut counts, &[node("dsp", 0.9), node("idle", 0.1)]);
+            assert!(sustained.is_empty());
+        }
+        let sustained = update_overload_counts(&mut counts, &[node("dsp", 0.9)]);
+        assert_eq!(sustained, vec!["dsp".to_string()]);
+
+        // One sample below the threshold resets the count
+        assert!(update_overload_counts(&mut counts, &[node("dsp", 0.5)]).is_empty());
+        assert!(counts.is_empty());
+        assert!(update_overload_counts(&mut counts, &[node("dsp", 0.9)]).is_empty());
+
+        let mut follower = node("plugin-host", 0.95);
+        follower.driver = Some("dsp".to_string());
+        let loads = [node("idle", 0.1), follower, node("dsp", 0.5)];
+        assert_eq!(
+            busiest_driver(&loads).map(|node| node.name.as_str()),
+            Some("dsp")
+        );
+        assert!(busiest_driver(&[]).is_none());
+    }
+
+    #[test]
+    fn test_xrun_counter() {
+        let mut counter = XrunCounter::default();
+
+        // Errors from before monitoring started are the baseline
+        assert_eq!(counter.update(&HashMap::from([(46, 12), (80, 3)])), 0);
+        assert_eq!(counter.update(&HashMap::from([(46, 14), (80, 3)])), 2);
+
+        // A new node counts in full, a removed one drops out
+        assert_eq!(counter.update(&HashMap::from([(46, 14), (90, 1)])), 3);
+
+        // A counter that went back belongs to a new node with a reused id
+        assert_eq!(counter.update(&HashMap::from([(46, 2), (90, 1)])), 5);
+    }
+
     // Mark tests that need audio as #[ignore] for CI
     #[test]
     #[ignore = "Requires audio system - run locally only"]
@@ -0,0 +1,600 @@
+/*
+ * Pro Audio Config - PipeWire Config Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * Builder for PipeWire config fragments (context.properties, context.modules
+ * and context.objects) written as SPA-JSON, plus a parser used to check that
+ * generated fragments load
+ */
+
+use serde_json::{Map, Number, Value};
+
+/// A value in a PipeWire config file
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpaValue {
+    Bool(bool),
+    Int(i64),
+    /// Always written quoted, with quotes and backslashes escaped
+    String(String),
+    Array(Vec<SpaValue>),
+    Object(Vec<(String, SpaValue)>),
+}
+
+impl From<bool> for SpaValue {
+    fn from(value: bool) -> Self {
+        SpaValue::Bool(value)
+    }
+}
+
+impl From<i32> for SpaValue {
+    fn from(value: i32) -> Self {
+        SpaValue::Int(value.into())
+    }
+}
+
+impl From<u32> for SpaValue {
+    fn from(value: u32) -> Self {
+        SpaValue::Int(value.into())
+    }
+}
+
+impl From<usize> for SpaValue {
+    fn from(value: usize) -> Self {
+        SpaValue::Int(value as i64)
+    }
+}
+
+impl From<&str> for SpaValue {
+    fn from(value: &str) -> Self {
+        SpaValue::String(value.to_string())
+    }
+}
+
+impl From<String> for SpaValue {
+    fn from(value: String) -> Self {
+        SpaValue::String(value)
+    }
+}
+
+impl<T: Into<SpaValue>> From<Vec<T>> for SpaValue {
+    fn from(values: Vec<T>) -> Self {
+        SpaValue::Array(values.into_iter().map(Into::into).collect())
+    }
+}
+
+impl SpaValue {
+    fn is_scalar(&self) -> bool {
+        !matches!(self, SpaValue::Array(_) | SpaValue::Object(_))
+    }
+
+    fn write(&self, out: &mut String, indent: usize) {
+        match self {
+            SpaValue::Bool(value) => out.push_str(&value.to_string()),
+            SpaValue::Int(value) => out.push_str(&value.to_string()),
+            SpaValue::String(value) => write_string(out, value),
+            // Short lists of plain values stay on one line: [ 44100, 48000 ]
+            SpaValue::Array(values) if values.iter().all(SpaValue::is_scalar) => {
+                out.push('[');
+                for (i, value) in values.iter().enumerate() {
+                    out.push_str(if i == 0 { " " } else { ", " });
+                    value.write(out, indent);
+                }
+                out.push_str(" ]");
+            }
+            SpaValue::Array(values) => {
+                out.push_str("[\n");
+                for value in values {
+                    push_indent(out, indent + 1);
+                    value.write(out, indent + 1);
+                    out.push('\n');
+                }
+                push_indent(out, indent);
+                out.push(']');
+            }
+            SpaValue::Object(members) => {
+                out.push_str("{\n");
+                write_members(out, members, indent + 1);
+                push_indent(out, indent);
+                out.push('}');
+            }
+        }
+    }
+}
+
+/// One entry of context.modules
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipeWireModule {
+    name: String,
+    args: Vec<(String, SpaValue)>,
+    flags: Vec<String>,
+}
+
+impl PipeWireModule {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            args: Vec::new(),
+            flags: Vec::new(),
+        }
+    }
+
+    /// libpipewire-module-rt with the given priorities and soft/hard RLIMIT_RTTIME (µs)
+    pub fn rt(nice_level: i32, rt_prio: u32, rt_time_soft: u32, rt_time_hard: u32) -> Self {
+        Self::new("libpipewire-module-rt")
+            .arg("nice.level", nice_level)
+            .arg("rt.prio", rt_prio)
+            .arg("rt.time.soft", rt_time_soft)
+            .arg("rt.time.hard", rt_time_hard)
+            .optional()
+    }
+
+    pub fn arg(mut self, key: &str, value: impl Into<SpaValue>) -> Self {
+        self.args.push((key.to_string(), value.into()));
+        self
+    }
+
+    /// Skipped without error when the module is not installed or fails to load
+    pub fn optional(mut self) -> Self {
+        self.flags = vec!["ifexists".to_string(), "nofail".to_string()];
+        self
+    }
+
+    fn to_value(&self) -> SpaValue {
+        let mut members = vec![("name".to_string(), SpaValue::from(self.name.as_str()))];
+        if !self.args.is_empty() {
+            members.push(("args".to_string(), SpaValue::Object(self.args.clone())));
+        }
+        if !self.flags.is_empty() {
+            members.push(("flags".to_string(), SpaValue::from(self.flags.clone())));
+        }
+        SpaValue::Object(members)
+    }
+}
+
+/// One entry of context.objects, e.g. an adapter node
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipeWireObject {
+    factory: String,
+    args: Vec<(String, SpaValue)>,
+}
+
+impl PipeWireObject {
+    pub fn new(factory: &str) -> Self {
+        Self {
+            factory: factory.to_string(),
+            args: Vec::new(),
+        }
+    }
+
+    /// A support.null-audio-sink adapter: a sink that plays nowhere, whose
+    /// monitor ports carry what was sent to it
+    pub fn null_sink(node_name: &str, description: &str) -> Self {
+        Self::new("adapter")
+            .arg("factory.name", "support.null-audio-sink")
+            .arg("node.name", node_name)
+            .arg("node.description", description)
+            .arg("media.class", "Audio/Sink")
+    }
+
+    pub fn arg(mut self, key: &str, value: impl Into<SpaValue>) -> Self {
+        self.args.push((key.to_string(), value.into()));
+        self
+    }
+
+    fn to_value(&self) -> SpaValue {
+        SpaValue::Object(vec![
+            ("factory".to_string(), SpaValue::from(self.factory.as_str())),
+            ("args".to_string(), SpaValue::Object(self.args.clone())),
+        ])
+    }
+}
+
+/// A pipewire.conf.d fragment. The clock keys have their own setters; other
+/// context.properties go through `property`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PipeWireConfig {
+    comments: Vec<String>,
+    properties: Vec<(String, SpaValue)>,
+    modules: Vec<PipeWireModule>,
+    objects: Vec<PipeWireObject>,
+}
+
+impl PipeWireConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a `#` comment line at the top of the file
+    pub fn comment(mut self, line: &str) -> Self {
+        self.comments.push(line.to_string());
+        self
+    }
+
+    /// Sets a context.property, replacing an earlier value of the same key
+    pub fn property(mut self, key: &str, value: impl Into<SpaValue>) -> Self {
+        let value = value.into();
+        match self
+            .properties
+            .iter_mut()
+            .find(|(existing, _)| existing == key)
+        {
+            Some((_, existing)) => *existing = value,
+            None => self.properties.push((key.to_string(), value)),
+        }
+        self
+    }
+
+    pub fn clock_rate(self, rate: u32) -> Self {
+        self.property("default.clock.rate", rate)
+    }
+
+    pub fn clock_quantum(self, quantum: u32) -> Self {
+        self.property("default.clock.quantum", quantum)
+    }
+
+    pub fn allowed_rates(self, rates: &[u32]) -> Self {
+        self.property("default.clock.allowed-rates", rates.to_vec())
+    }
+
+    pub fn min_quantum(self, quantum: u32) -> Self {
+        self.property("default.clock.min-quantum", quantum)
+    }
+
+    pub fn max_quantum(self, quantum: u32) -> Self {
+        self.property("default.clock.max-quantum", quantum)
+    }
+
+    pub fn quantum_floor(self, quantum: u32) -> Self {
+        self.property("default.clock.quantum-floor", quantum)
+    }
+
+    pub fn quantum_limit(self, quantum: u32) -> Self {
+        self.property("default.clock.quantum-limit", quantum)
+    }
+
+    pub fn force_quantum(self, quantum: u32) -> Self {
+        self.property("default.clock.force-quantum", quantum)
+    }
+
+    pub fn force_rate(self, rate: u32) -> Self {
+        self.property("default.clock.force-rate", rate)
+    }
+
+    pub fn module(mut self, module: PipeWireModule) -> Self {
+        self.modules.push(module);
+        self
+    }
+
+    pub fn object(mut self, object: PipeWireObject) -> Self {
+        self.objects.push(object);
+        self
+    }
+
+    /// The file contents as SPA-JSON, one `key = value` per line
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for line in &self.comments {
+            out.push_str(&format!("# {}\n", line));
+        }
+
+        let mut sections = Vec::new();
+        if !self.properties.is_empty() {
+            sections.push((
+                "context.properties",
+                SpaValue::Object(self.properties.clone()),
+            ));
+        }
+        if !self.modules.is_empty() {
+            sections.push((
+                "context.modules",
+                SpaValue::Array(self.modules.iter().map(PipeWireModule::to_value).collect()),
+            ));
+        }
+        if !self.objects.is_empty() {
+            sections.push((
+                "context.objects",
+                SpaValue::Array(self.objects.iter().map(PipeWireObject::to_value).collect()),
+            ));
+        }
+
+        for (key, value) in sections {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(key);
+            out.push_str(" = ");
+            value.write(&mut out, 0);
+            out.push('\n');
+        }
+
+        out
+    }
+}
+
+fn push_indent(out: &mut String, indent: usize) {
+    out.push_str(&"    ".repeat(indent));
+}
+
+fn write_members(out: &mut String, members: &[(String, SpaValue)], indent: usize) {
+    for (key, value) in members {
+        push_indent(out, indent);
+        write_key(out, key);
+        out.push_str(" = ");
+        value.write(out, indent);
+        out.push('\n');
+    }
+}
+
+/// Keys made of plain characters stay bare (`default.clock.rate`), others are quoted
+fn write_key(out: &mut String, key: &str) {
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+    if bare {
+        out.push_str(key);
+    } else {
+        write_string(out, key);
+    }
+}
+
+/// A value as a quoted SPA-JSON string, for config text assembled with `format!`
+pub fn quote_string(value: &str) -> String {
+    let mut out = String::new();
+    write_string(&mut out, value);
+    out
+}
+
+fn write_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Parses SPA-JSON the way PipeWire reads config files: `#` comments, bare
+/// keys and words, `=` or `:` between key and value, optional commas and an
+/// implicit object around the top level
+pub fn parse_spa_json(content: &str) -> Result<Value, String> {
+    let mut parser = SpaParser {
+        chars: content.chars().collect(),
+        pos: 0,
+    };
+
+    parser.skip_separators();
+    let value = match parser.peek() {
+        Some('{') | Some('[') => parser.value()?,
+        _ => Value::Object(parser.members(None)?),
+    };
+
+    parser.skip_separators();
+    if parser.pos < parser.chars.len() {
+        return Err(format!("Unexpected content at offset {}", parser.pos));
+    }
+    Ok(value)
+}
+
+struct SpaParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl SpaParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    /// Skips whitespace, commas and comments
+    fn skip_separators(&mut self) {
+        while let Some(c) = self.peek() {
+            if c == '#' {
+                while self.peek().is_some_and(|c| c != '\n') {
+                    self.pos += 1;
+                }
+            } else if c.is_whitespace() || c == ',' {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn members(&mut self, close: Option<char>) -> Result<Map<String, Value>, String> {
+        let mut members = Map::new();
+        loop {
+            self.skip_separators();
+            match self.peek() {
+                None if close.is_none() => return Ok(members),
+                None => return Err("Unterminated object".to_string()),
+                Some(c) if Some(c) == close => {
+                    self.pos += 1;
+                    return Ok(members);
+                }
+                Some(_) => {}
+            }
+
+            let key = match self.peek() {
+                Some('"') => self.string()?,
+                _ => self.word()?,
+            };
+            self.skip_separators();
+            if matches!(self.peek(), Some('=') | Some(':')) {
+                self.pos += 1;
+            }
+            self.skip_separators();
+            let value = self.value()?;
+            members.insert(key, value);
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        match self.peek() {
+            Some('{') => {
+                self.pos += 1;
+                Ok(Value::Object(self.members(Some('}'))?))
+            }
+            Some('[') => {
+                self.pos += 1;
+                let mut values = Vec::new();
+                loop {
+                    self.skip_separators();
+                    match self.peek() {
+                        None => return Err("Unterminated array".to_string()),
+                        Some(']') => {
+                            self.pos += 1;
+                            return Ok(Value::Array(values));
+                        }
+                        Some(_) => values.push(self.value()?),
+                    }
+                }
+            }
+            Some('"') => Ok(Value::String(self.string()?)),
+            Some(_) => {
+                let word = self.word()?;
+                Ok(match word.as_str() {
+                    "true" => Value::Bool(true),
+                    "false" => Value::Bool(false),
+                    "null" => Value::Null,
+                    _ => word
+                        .parse::<i64>()
+                        .map(Value::from)
+                        .ok()
+                        .or_else(|| {
+                            word.parse::<f64>()
+                                .ok()
+                                .and_then(Number::from_f64)
+                                .map(Value::Number)
+                        })
+                        .unwrap_or(Value::String(word)),
+                })
+            }
+            None => Err("Missing value at end of input".to_string()),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        // Opening quote
+        self.pos += 1;
+        let mut value = String::new();
+        loop {
+            let c = self.peek().ok_or("Unterminated string")?;
+            self.pos += 1;
+            match c {
+                '"' => return Ok(value),
+                '\\' => {
+                    let escaped = self.peek().ok_or("Unterminated string")?;
+                    self.pos += 1;
+                    match escaped {
+                        'n' => value.push('\n'),
+                        't' => value.push('\t'),
+                        'u' => {
+                            let hex: String = self.chars.iter().skip(self.pos).take(4).collect();
+                            let code = u32::from_str_radix(&hex, 16)
+                                .map_err(|_| format!("Bad escape \\u{}", hex))?;
+                            value.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                            self.pos += 4;
+                        }
+                        other => value.push(other),
+                    }
+                }
+                c => value.push(c),
+            }
+        }
+    }
+
+    fn word(&mut self) -> Result<String, String> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| {
+            !c.is_whitespace() && !matches!(c, '{' | '}' | '[' | ']' | '=' | ':' | ',' | '#' | '"')
+        }) {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Err(format!(
+                "Unexpected '{}' at offset {}",
+                self.peek().unwrap_or(' '),
+                start
+            ));
+        }
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pipewire_config_round_trip() {
+        let content = PipeWireConfig::new()
+            .comment("Pro Audio Config - Test")
+            .clock_rate(48000)
+            .clock_quantum(256)
+            .allowed_rates(&[44100, 48000])
+            .property("node.description", "Studio \"Mains\" \\ A")
+            .property("settings.check-quantum", false)
+            .clock_quantum(128)
+            .module(PipeWireModule::rt(-11, 88, 200000, 400000))
+            .object(
+                PipeWireObject::new("adapter")
+                    .arg("factory.name", "support.null-audio-sink")
+                    .arg("audio.position", vec!["FL", "FR"]),
+            )
+            .render();
+
+        assert!(content.starts_with("# Pro Audio Config - Test\n"));
+        assert!(content.contains("    default.clock.rate = 48000\n"));
+        assert!(content.contains("    default.clock.allowed-rates = [ 44100, 48000 ]\n"));
+        assert_eq!(content.matches("default.clock.quantum").count(), 1);
+
+        let parsed = parse_spa_json(&content).unwrap();
+        let properties = &parsed["context.properties"];
+        assert_eq!(properties["default.clock.quantum"], 128);
+        assert_eq!(properties["node.description"], "Studio \"Mains\" \\ A");
+        assert_eq!(properties["settings.check-quantum"], false);
+
+        let module = &parsed["context.modules"][0];
+        assert_eq!(module["name"], "libpipewire-module-rt");
+        assert_eq!(module["args"]["rt.prio"], 88);
+        assert_eq!(module["args"]["rt.time.hard"], 400000);
+        assert_eq!(module["flags"], serde_json::json!(["ifexists", "nofail"]));
+
+        let object = &parsed["context.objects"][0];
+        assert_eq!(object["factory"], "adapter");
+        assert_eq!(object["args"]["audio.position"][1], "FR");
+    }
+
+    #[test]
+    fn test_parse_spa_json() {
+        let parsed = parse_spa_json(
+            "# comment\ncontext.properties = { rate: 48000, name = bare.word # trailing\n \
+             list = [ 1 2, \"three\" ] }\n{ \"quoted key\" = 1.5 }",
+        );
+        assert!(parsed.is_err());
+
+        let parsed = parse_spa_json(
+            "context.properties = { rate: 48000, name = bare.word # trailing\n \
+             list = [ 1 2, \"three\" ] nested = { on = true } }",
+        )
+        .unwrap();
+        let properties = &parsed["context.properties"];
+        assert_eq!(properties["rate"], 48000);
+        assert_eq!(properties["name"], "bare.word");
+        assert_eq!(properties["list"], serde_json::json!([1, 2, "three"]));
+        assert_eq!(properties["nested"]["on"], true);
+
+        assert!(parse_spa_json("context.properties = { rate = 48000").is_err());
+        assert!(parse_spa_json("key = \"unterminated").is_err());
+    }
+}
@@ -0,0 +1,139 @@
+/*
+ * Pro Audio Config - Presets Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * Named settings for common DAW and desktop setups, built in or saved
+ * by the user
+ */
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::AudioSettings;
+use crate::config::AdvancedAudioSettings;
+
+/// Settings picked together under a name. The device id is not applied,
+/// presets only fill in the format and buffer combos.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Preset {
+    pub name: String,
+    pub settings: AudioSettings,
+    /// Exclusive-mode settings for the Advanced tab; None leaves its mode alone
+    #[serde(default)]
+    pub advanced: Option<AdvancedAudioSettings>,
+}
+
+impl Preset {
+    pub fn new(
+        name: &str,
+        settings: AudioSettings,
+        advanced: Option<AdvancedAudioSettings>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            settings,
+            advanced,
+        }
+    }
+
+    /// One line for tooltips, e.g. "48000 Hz, 24 bit, 128 samples (2.7 ms)"
+    pub fn summary(&self) -> String {
+        let settings = &self.settings;
+        format!(
+            "{} Hz, {} bit, {} samples ({:.1} ms)",
+            settings.sample_rate,
+            settings.bit_depth,
+            settings.buffer_size,
+            settings.buffer_size as f64 * 1000.0 / settings.sample_rate as f64
+        )
+    }
+}
+
+/// The presets shipped with the app, in combo order
+pub fn builtin_presets() -> Vec<Preset> {
+    let settings = |sample_rate, bit_depth, buffer_size| {
+        AudioSettings::new(sample_rate, bit_depth, buffer_size, "default".to_string())
+    };
+
+    vec![
+        Preset::new(
+            "Low-Latency Recording 48k/128",
+            settings(48000, 24, 128),
+            Some(AdvancedAudioSettings {
+                exclusive_mode: true,
+                direct_hardware: true,
+                low_latency: true,
+                buffer_size: 128,
+                sample_rate: 48000,
+            }),
+        ),
+        Preset::new("Mixing 48k/256", settings(48000, 24, 256), None),
+        Preset::new("Mastering 96k/1024", settings(96000, 32, 1024), None),
+        Preset::new("General Desktop 48k/512", settings(48000, 24, 512), None),
+        Preset::new("CD Audio 44.1k/1024", settings(44100, 16, 1024), None),
+    ]
+}
+
+pub fn is_builtin_preset(name: &str) -> bool {
+    builtin_presets().iter().any(|preset| preset.name == name)
+}
+
+/// Looks `name` up in the built-in presets first, then in `custom`
+pub fn find_preset(name: &str, custom: &[Preset]) -> Option<Preset> {
+    builtin_presets()
+        .into_iter()
+        .chain(custom.iter().cloned())
+        .find(|preset| preset.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_presets() {
+        let presets = builtin_presets();
+
+        for preset in &presets {
+            assert!(
+                preset.settings.validate().is_ok(),
+                "{} has invalid settings",
+                preset.name
+            );
+            if let Some(advanced) = &preset.advanced {
+                assert_eq!(advanced.sample_rate, preset.settings.sample_rate);
+            }
+        }
+
+        let mut names: Vec<&str> = presets.iter().map(|preset| preset.name.as_str()).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), presets.len());
+
+        assert!(is_builtin_preset("Mastering 96k/1024"));
+        assert!(!is_builtin_preset("My Setup"));
+
+        let custom = vec![Preset::new(
+            "My Setup",
+            AudioSettings::new(192000, 24, 256, "default".to_string()),
+            None,
+        )];
+        assert_eq!(
+            find_preset("My Setup", &custom)
+                .unwrap()
+                .settings
+                .sample_rate,
+            192000
+        );
+        assert_eq!(
+            find_preset("General Desktop 48k/512", &custom)
+                .unwrap()
+                .settings
+                .buffer_size,
+            512
+        );
+        assert!(find_preset("Missing", &custom).is_none());
+    }
+}
@@ -0,0 +1,374 @@
+/*
+ * Pro Audio Config - Privileged Helper Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * Batched file operations for system-wide configs. The app hands them to
+ * pro-audio-config-helper through pkexec, authorized by the polkit action
+ * "Configure system audio", instead of running one pkexec per file.
+ * Without the helper the batch runs as one `sh -c` script instead.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Read, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Component, Path};
+use std::process::{Command, ExitStatus, Stdio};
+
+use crate::error::AudioConfigError;
+
+/// Installed location of the helper; must match exec.path in the policy
+pub const HELPER_PATH: &str = "/usr/libexec/pro-audio-config-helper";
+
+/// Polkit action the helper runs under
+pub const POLKIT_ACTION_ID: &str = "com.github.peterlsvk.pro-audio-config.configure";
+
+/// pkexec exit code when the authentication dialog was dismissed or the
+/// user is not authorized
+pub const PKEXEC_DISMISSED: i32 = 126;
+
+const POLKIT_POLICY_PATH: &str =
+    "/usr/share/polkit-1/actions/com.github.peterlsvk.pro-audio-config.policy";
+
+/// The helper refuses to touch anything outside these directories
+const ALLOWED_ROOTS: [&str; 2] = ["/etc/pipewire", "/etc/wireplumber"];
+
+/// One file operation, sent to the helper as a JSON line on stdin
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "kebab-case")]
+pub enum PrivilegedOp {
+    CreateDir { path: String },
+    Write { path: String, content: String },
+    Copy { from: String, to: String },
+    Remove { path: String },
+}
+
+impl PrivilegedOp {
+    fn paths(&self) -> Vec<&str> {
+        match self {
+            PrivilegedOp::CreateDir { path }
+            | PrivilegedOp::Write { path, .. }
+            | PrivilegedOp::Remove { path } => vec![path],
+            PrivilegedOp::Copy { from, to } => vec![from, to],
+        }
+    }
+
+    /// Rejects paths outside the PipeWire/WirePlumber config directories
+    pub fn validate(&self) -> Result<(), String> {
+        match self.paths().into_iter().find(|path| !is_allowed_path(path)) {
+            Some(path) => Err(format!(
+                "Refusing to touch {}: only files under {} can be changed",
+                path,
+                ALLOWED_ROOTS.join(" and ")
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn execute(&self) -> Result<(), String> {
+        match self {
+            PrivilegedOp::CreateDir { path } => fs::create_dir_all(path)
+                .map_err(|e| format!("Failed to create directory {}: {}", path, e)),
+            PrivilegedOp::Write { path, content } => write_system_file(path, content),
+            PrivilegedOp::Copy { from, to } => fs::copy(from, to)
+                .map(|_| ())
+                .map_err(|e| format!("Failed to copy {} to {}: {}", from, to, e)),
+            PrivilegedOp::Remove { path } => match fs::remove_file(path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    Err(format!("Failed to remove {}: {}", path, e))
+                }
+                _ => Ok(()),
+            },
+        }
+    }
+}
+
+/// Whether the helper and its polkit policy are installed
+pub fn helper_available() -> bool {
+    Path::new(HELPER_PATH).is_file() && Path::new(POLKIT_POLICY_PATH).is_file()
+}
+
+/// Whether `ops` can go through the helper instead of per-command pkexec
+pub fn helper_handles(ops: &[PrivilegedOp]) -> bool {
+    helper_available() && ops.iter().all(|op| op.validate().is_ok())
+}
+
+/// Performs `ops` with a single pkexec call to the helper, so the whole batch
+/// needs one authentication
+pub fn run_with_helper(ops: &[PrivilegedOp]) -> Result<(), AudioConfigError> {
+    let mut batch = String::new();
+    for op in ops {
+        let line = serde_json::to_string(op)
+            .map_err(|e| format!("Failed to encode privileged operation: {}", e))?;
+        batch.push_str(&line);
+        batch.push('\n');
+    }
+
+    log::info!(
+        "Requesting administrator privileges for {} system config operation(s)...",
+        ops.len()
+    );
+
+    let mut child = Command::new("pkexec")
+        .arg(HELPER_PATH)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            format!(
+                "Failed to execute with pkexec: {}. Make sure pkexec is available.",
+                e
+            )
+        })?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(batch.as_bytes())
+            .map_err(|e| format!("Failed to send operations to the helper: {}", e))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to wait for the helper: {}", e))?;
+
+    if output.status.success() {
+        Ok(())
+    } else if pkexec_cancelled(&output.status) {
+        Err(AudioConfigError::AuthorizationCancelled)
+    } else {
+        let exit = output
+            .status
+            .code()
+            .map(|code| code.to_string())
+            .unwrap_or_else(|| "signal".to_string());
+        Err(AudioConfigError::Other(format!(
+            "pro-audio-config-helper exited with {}: {}",
+            exit,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+/// Arguments for `sh` that perform `ops` in order and stop at the first
+/// failure, for pkexec/sudo when the helper is not installed. Paths and file
+/// contents are passed as positional parameters, never spliced into the script.
+pub fn shell_batch_args(ops: &[PrivilegedOp]) -> Vec<String> {
+    let mut script = String::from("set -e\n");
+    let mut params = Vec::new();
+    let mut param = |value: &str| {
+        params.push(value.to_string());
+        format!("\"${{{}}}\"", params.len())
+    };
+
+    for op in ops {
+        let line = match op {
+            PrivilegedOp::CreateDir { path } => format!("mkdir -p {}", param(path)),
+            PrivilegedOp::Write { path, content } => {
+                let temp = param(&format!("{}.pro-audio-tmp", path));
+                let target = param(path);
+                let content = param(content);
+                format!("printf '%s' {content} > {temp}; chmod 644 {temp}; mv -f {temp} {target}")
+            }
+            PrivilegedOp::Copy { from, to } => format!("cp {} {}", param(from), param(to)),
+            PrivilegedOp::Remove { path } => format!("rm -f {}", param(path)),
+        };
+        script.push_str(&line);
+        script.push('\n');
+    }
+
+    let mut args = vec!["-c".to_string(), script, "sh".to_string()];
+    args.extend(params);
+    args
+}
+
+/// Whether pkexec ended with `status` because the user dismissed its dialog
+pub fn pkexec_cancelled(status: &ExitStatus) -> bool {
+    status.code() == Some(PKEXEC_DISMISSED)
+}
+
+/// Entry point of the helper binary: reads one JSON operation per line,
+/// validates the whole batch before touching anything, then runs it in order.
+/// Returns the number of operations performed.
+pub fn run_helper(mut input: impl Read) -> Result<usize, String> {
+    let mut batch = String::new();
+    input
+        .read_to_string(&mut batch)
+        .map_err(|e| format!("Failed to read operations: {}", e))?;
+
+    let ops = parse_batch(&batch)?;
+    for op in &ops {
+        op.validate()?;
+    }
+    for op in &ops {
+        op.execute()?;
+    }
+
+    Ok(ops.len())
+}
+
+fn parse_batch(batch: &str) -> Result<Vec<PrivilegedOp>, String> {
+    batch
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line).map_err(|e| format!("Invalid operation '{}': {}", line, e))
+        })
+        .collect()
+}
+
+/// Absolute, no `..`, and inside one of the allowed roots
+fn is_allowed_path(path: &str) -> bool {
+    let path = Path::new(path);
+    path.is_absolute()
+        && !path
+            .components()
+            .any(|component| matches!(component, Component::ParentDir))
+        && ALLOWED_ROOTS.iter().any(|root| path.starts_with(root))
+}
+
+/// Stages next to the target and renames into place, world-readable like
+/// the rest of /etc
+fn write_system_file(path: &str, content: &str) -> Result<(), String> {
+    let temp_path = format!("{}.pro-audio-tmp", path);
+
+    let result = fs::write(&temp_path, content)
+        .and_then(|_| fs::set_permissions(&temp_path, fs::Permissions::from_mode(0o644)))
+        .and_then(|_| fs::rename(&temp_path, path));
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp_path);
+        return Err(format!("Failed to write {}: {}", path, e));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_privileged_op_validation() {
+        let write = PrivilegedOp::Write {
+            path: "/etc/pipewire/pipewire.conf.d/99-pro-audio-high-priority.conf".to_string(),
+            content: String::new(),
+        };
+        assert!(write.validate().is_ok());
+
+        let outside = PrivilegedOp::Remove {
+            path: "/etc/passwd".to_string(),
+        };
+        assert!(outside.validate().is_err());
+
+        let escape = PrivilegedOp::Write {
+            path: "/etc/pipewire/../shadow".to_string(),
+            content: String::new(),
+        };
+        assert!(escape.validate().is_err());
+
+        let copy = PrivilegedOp::Copy {
+            from: "/etc/wireplumber/main.lua".to_string(),
+            to: "/tmp/main.lua".to_string(),
+        };
+        assert!(copy.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_batch() {
+        let ops = vec![
+            PrivilegedOp::CreateDir {
+                path: "/etc/pipewire/pipewire.conf.d".to_string(),
+            },
+            PrivilegedOp::Write {
+                path: "/etc/pipewire/pipewire.conf.d/99-test.conf".to_string(),
+                content: "context.properties = {\n}\n".to_string(),
+            },
+        ];
+        let batch: String = ops
+            .iter()
+            .map(|op| serde_json::to_string(op).unwrap() + "\n")
+            .collect();
+
+        assert!(batch.starts_with("{\"op\":\"create-dir\""));
+        assert_eq!(parse_batch(&batch).unwrap(), ops);
+        assert!(parse_batch("{\"op\":\"chmod\",\"path\":\"/etc/pipewire\"}").is_err());
+    }
+
+    #[test]
+    fn test_shell_batch_args() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path().join("conf.d");
+        let path = |name: &str| dir.join(name).to_string_lossy().to_string();
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(path("old.conf"), "old").unwrap();
+
+        let content = "a = \"$HOME\" `id` ${1}\n";
+        let ops = vec![
+            PrivilegedOp::CreateDir {
+                path: path("sub dir"),
+            },
+            PrivilegedOp::Write {
+                path: path("sub dir/new.conf"),
+                content: content.to_string(),
+            },
+            PrivilegedOp::Copy {
+                from: path("sub dir/new.conf"),
+                to: path("copy.conf"),
+            },
+            PrivilegedOp::Remove {
+                path: path("old.conf"),
+            },
+            PrivilegedOp::Remove {
+                path: path("missing.conf"),
+            },
+        ];
+
+        let status = Command::new("sh")
+            .args(shell_batch_args(&ops))
+            .status()
+            .unwrap();
+        assert!(status.success());
+        assert_eq!(
+            fs::read_to_string(path("sub dir/new.conf")).unwrap(),
+            content
+        );
+        assert_eq!(fs::read_to_string(path("copy.conf")).unwrap(), content);
+        assert!(!dir.join("old.conf").exists());
+        assert!(!dir.join("sub dir/new.conf.pro-audio-tmp").exists());
+
+        // A failing step stops the batch
+        let ops = vec![
+            PrivilegedOp::Copy {
+                from: path("missing.conf"),
+                to: path("never.conf"),
+            },
+            PrivilegedOp::Write {
+                path: path("after.conf"),
+                content: String::new(),
+            },
+        ];
+        let status = Command::new("sh")
+            .args(shell_batch_args(&ops))
+            .stderr(Stdio::null())
+            .status()
+            .unwrap();
+        assert!(!status.success());
+        assert!(!dir.join("after.conf").exists());
+    }
+
+    #[test]
+    fn test_pkexec_cancelled() {
+        use std::os::unix::process::ExitStatusExt;
+
+        // Raw wait statuses carry the exit code in the second byte
+        let exited = |code: i32| ExitStatus::from_raw(code << 8);
+        assert!(pkexec_cancelled(&exited(PKEXEC_DISMISSED)));
+        assert!(!pkexec_cancelled(&exited(127)));
+        assert!(!pkexec_cancelled(&exited(1)));
+        assert!(!pkexec_cancelled(&ExitStatus::from_raw(libc::SIGTERM)));
+    }
+}
@@ -0,0 +1,552 @@
+/*
+ * Pro Audio Config - Setting Source Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * Answers "why is the graph running at this rate/quantum?" by matching the
+ * live value against every config tier and the running clients' requests
+ */
+
+use serde_json::Value;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::audio::{detect_current_audio_settings, pipewire_command, sample_pw_top};
+use crate::config::{split_config_assignment, user_config_dir};
+
+/// PipeWire's own defaults when no config sets the clock
+const BUILTIN_RATE: u32 = 48000;
+const BUILTIN_QUANTUM: u32 = 1024;
+
+/// A live graph property the lookup can explain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TracedSetting {
+    SampleRate,
+    Quantum,
+}
+
+impl TracedSetting {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TracedSetting::SampleRate => "Sample rate",
+            TracedSetting::Quantum => "Quantum",
+        }
+    }
+
+    fn unit(&self) -> &'static str {
+        match self {
+            TracedSetting::SampleRate => "Hz",
+            TracedSetting::Quantum => "samples",
+        }
+    }
+
+    /// Graph-wide key in pipewire.conf
+    fn clock_key(&self) -> &'static str {
+        match self {
+            TracedSetting::SampleRate => "default.clock.rate",
+            TracedSetting::Quantum => "default.clock.quantum",
+        }
+    }
+
+    /// Per-device key in WirePlumber rules, if there is one
+    fn device_key(&self) -> Option<&'static str> {
+        match self {
+            TracedSetting::SampleRate => Some("audio.rate"),
+            TracedSetting::Quantum => None,
+        }
+    }
+
+    /// Runtime override in the "settings" metadata
+    fn force_key(&self) -> &'static str {
+        match self {
+            TracedSetting::SampleRate => "clock.force-rate",
+            TracedSetting::Quantum => "clock.force-quantum",
+        }
+    }
+
+    fn builtin_default(&self) -> u32 {
+        match self {
+            TracedSetting::SampleRate => BUILTIN_RATE,
+            TracedSetting::Quantum => BUILTIN_QUANTUM,
+        }
+    }
+
+    /// Value a client stream asks for through one of its node properties
+    fn client_request(&self, key: &str, value: &Value) -> Option<u32> {
+        let text = match value {
+            Value::String(text) => text.clone(),
+            Value::Number(number) => number.to_string(),
+            _ => return None,
+        };
+        let (numerator, denominator) = match text.split_once('/') {
+            Some((numerator, denominator)) => (numerator.trim(), Some(denominator.trim())),
+            None => (text.trim(), None),
+        };
+
+        match (self, key) {
+            // "1/44100"
+            (TracedSetting::SampleRate, "node.rate") => denominator?.parse().ok(),
+            (TracedSetting::SampleRate, "node.force-rate") => numerator.parse().ok(),
+            // "256/48000"
+            (TracedSetting::Quantum, "node.latency") => numerator.parse().ok(),
+            (TracedSetting::Quantum, "node.force-quantum") => numerator.parse().ok(),
+            _ => None,
+        }
+        .filter(|value| *value > 0)
+    }
+}
+
+/// Config directory tiers in load order; later tiers override earlier ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConfigTier {
+    /// Distribution defaults (/usr/share)
+    Distribution,
+    /// Administrator configs (/etc)
+    System,
+    /// The user's configs (~/.config), where this tool writes by default
+    User,
+}
+
+impl ConfigTier {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConfigTier::Distribution => "distribution default",
+            ConfigTier::System => "system config",
+            ConfigTier::User => "user config",
+        }
+    }
+}
+
+/// Where a value for the traced setting comes from
+#[derive(Debug, Clone, PartialEq)]
+pub enum SourceOrigin {
+    Config {
+        path: PathBuf,
+        tier: ConfigTier,
+    },
+    /// Forced at runtime through the "settings" metadata (pw-metadata)
+    Metadata,
+    /// A running stream asking for it, e.g. a DAW
+    Client {
+        id: u64,
+        name: String,
+    },
+    /// Nothing sets it, so PipeWire uses its compiled-in default
+    Builtin,
+}
+
+/// One place that sets the traced setting, and the value it sets
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingSource {
+    pub origin: SourceOrigin,
+    pub key: String,
+    pub value: u32,
+}
+
+impl fmt::Display for SettingSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.origin {
+            SourceOrigin::Config { path, tier } => write!(
+                f,
+                "{} = {} in {} ({})",
+                self.key,
+                self.value,
+                path.display(),
+                tier.label()
+            ),
+            SourceOrigin::Metadata => write!(
+                f,
+                "{} = {} forced at runtime (pw-metadata settings)",
+                self.key, self.value
+            ),
+            SourceOrigin::Client { id, name } => write!(
+                f,
+                "client \"{}\" (node {}) requests {} = {}",
+                name, id, self.key, self.value
+            ),
+            SourceOrigin::Builtin => write!(f, "PipeWire built-in default {}", self.value),
+        }
+    }
+}
+
+/// Everything found for one setting, and which of it explains the live value
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingTrace {
+    pub setting: TracedSetting,
+    pub live: u32,
+    /// Config assignments in load order, so the last clock one wins
+    pub config: Vec<SettingSource>,
+    /// Metadata overrides and client requests
+    pub runtime: Vec<SettingSource>,
+}
+
+impl SettingTrace {
+    /// The graph-wide config value PipeWire starts with
+    pub fn effective_config(&self) -> SettingSource {
+        self.config
+            .iter()
+            .rev()
+            .find(|source| source.key == self.setting.clock_key())
+            .cloned()
+            .unwrap_or(SettingSource {
+                origin: SourceOrigin::Builtin,
+                key: self.setting.clock_key().to_string(),
+                value: self.setting.builtin_default(),
+            })
+    }
+
+    /// The source that explains the live value. A forced value beats a
+    /// client request, which beats the config; a device rule or an
+    /// overridden assignment is the last guess.
+    pub fn responsible(&self) -> Option<SettingSource> {
+        let matches_live = |source: &&SettingSource| source.value == self.live;
+
+        self.runtime
+            .iter()
+            .filter(|source| source.origin == SourceOrigin::Metadata)
+            .find(matches_live)
+            .or_else(|| {
+                self.runtime
+                    .iter()
+                    .filter(|source| matches!(source.origin, SourceOrigin::Client { .. }))
+                    .find(matches_live)
+            })
+            .cloned()
+            .or_else(|| Some(self.effective_config()).filter(|source| source.value == self.live))
+            .or_else(|| self.config.iter().rev().find(matches_live).cloned())
+    }
+
+    pub fn report(&self) -> String {
+        let mut lines = vec![format!(
+            "{} {} {}",
+            self.setting.label(),
+            self.live,
+            self.setting.unit()
+        )];
+
+        match self.responsible() {
+            Some(source) => lines.push(format!("  Set by: {}", source)),
+            None => lines.push(format!(
+                "  No config or client sets {} (config says {})",
+                self.live,
+                self.effective_config().value
+            )),
+        }
+
+        if !self.config.is_empty() || !self.runtime.is_empty() {
+            lines.push("  Everything that sets it:".to_string());
+            for source in self.config.iter().chain(&self.runtime) {
+                let marker = if source.value == self.live {
+                    "✓"
+                } else {
+                    " "
+                };
+                lines.push(format!("    {} {}", marker, source));
+            }
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Explains the live value of `setting` from the config tiers and pw-dump
+pub fn trace_setting(setting: TracedSetting, live: u32) -> Result<SettingTrace, String> {
+    let output = pipewire_command("pw-dump")
+        .output()
+        .map_err(|e| format!("Failed to run pw-dump: {}", e))?;
+    if !output.status.success() {
+        return Err("pw-dump failed, PipeWire may not be running".to_string());
+    }
+
+    Ok(SettingTrace {
+        setting,
+        live,
+        config: config_sources(setting, &config_tier_dirs()),
+        runtime: parse_runtime_sources(setting, &String::from_utf8_lossy(&output.stdout))?,
+    })
+}
+
+/// Reports for the rate and quantum the graph runs at right now: those of
+/// the running streams' driver, or the configured ones when nothing plays
+pub fn explain_live_settings() -> Result<String, String> {
+    let running = sample_pw_top().ok().and_then(|sample| {
+        sample
+            .streams
+            .first()
+            .map(|stream| (stream.rate, stream.quantum))
+    });
+    let (rate, quantum) = match running {
+        Some(live) => live,
+        None => {
+            let settings = detect_current_audio_settings()?;
+            (settings.sample_rate, settings.buffer_size)
+        }
+    };
+
+    Ok(format!(
+        "{}\n\n{}",
+        trace_setting(TracedSetting::SampleRate, rate)?.report(),
+        trace_setting(TracedSetting::Quantum, quantum)?.report()
+    ))
+}
+
+/// Base config directories of each tier, lowest priority first
+fn config_tier_dirs() -> Vec<(ConfigTier, PathBuf)> {
+    vec![
+        (ConfigTier::Distribution, PathBuf::from("/usr/share")),
+        (ConfigTier::System, PathBuf::from("/etc")),
+        (ConfigTier::User, PathBuf::from(user_config_dir())),
+    ]
+}
+
+/// Assignments of `setting` in the PipeWire and WirePlumber configs under
+/// `bases`, in the order the daemons load them
+fn config_sources(setting: TracedSetting, bases: &[(ConfigTier, PathBuf)]) -> Vec<SettingSource> {
+    let mut trees = vec![("pipewire", setting.clock_key())];
+    if let Some(device_key) = setting.device_key() {
+        trees.push(("wireplumber", device_key));
+    }
+
+    let mut sources = Vec::new();
+    for (tree, key) in trees {
+        for (tier, path) in load_order(tree, bases) {
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            sources.extend(
+                content
+                    .lines()
+                    .filter_map(split_config_assignment)
+                    .filter(|(found, _)| *found == key)
+                    .map(|(found, value)| SettingSource {
+                        origin: SourceOrigin::Config {
+                            path: path.clone(),
+                            tier,
+                        },
+                        key: found.to_string(),
+                        value,
+                    }),
+            );
+        }
+    }
+    sources
+}
+
+/// Config files of `tree` ("pipewire") in load order: the main file from
+/// the highest tier that has one, then the conf.d fragments sorted by file
+/// name, where a fragment in a higher tier replaces one of the same name
+fn load_order(tree: &str, bases: &[(ConfigTier, PathBuf)]) -> Vec<(ConfigTier, PathBuf)> {
+    let mut files = Vec::new();
+
+    if let Some((tier, main)) = bases
+        .iter()
+        .rev()
+        .map(|(tier, base)| (*tier, base.join(tree).join(format!("{}.conf", tree))))
+        .find(|(_, main)| main.is_file())
+    {
+        files.push((tier, main));
+    }
+
+    let mut fragments: Vec<(String, ConfigTier, PathBuf)> = Vec::new();
+    for (tier, base) in bases {
+        let Ok(entries) = fs::read_dir(base.join(tree).join(format!("{}.conf.d", tree))) else {
+            continue;
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.extension().is_none_or(|extension| extension != "conf") {
+                continue;
+            }
+            let name = file_name(&path);
+            fragments.retain(|(existing, _, _)| *existing != name);
+            fragments.push((name, *tier, path));
+        }
+    }
+    fragments.sort_by(|a, b| a.0.cmp(&b.0));
+
+    files.extend(fragments.into_iter().map(|(_, tier, path)| (tier, path)));
+    files
+}
+
+/// A metadata value, stored as a number or a string; 0 means "not forced"
+fn metadata_number(value: &Value) -> Option<u32> {
+    let number = match value {
+        Value::Number(number) => number.as_u64()?,
+        Value::String(text) => text.trim().parse().ok()?,
+        _ => return None,
+    };
+    u32::try_from(number).ok().filter(|number| *number > 0)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Forced values from the "settings" metadata and requests of running
+/// client streams, from pw-dump output
+fn parse_runtime_sources(setting: TracedSetting, dump: &str) -> Result<Vec<SettingSource>, String> {
+    let objects: Vec<Value> =
+        serde_json::from_str(dump).map_err(|e| format!("Failed to parse pw-dump output: {}", e))?;
+    let mut sources = Vec::new();
+
+    for object in &objects {
+        let props = &object["info"]["props"];
+
+        if props["metadata.name"] == "settings"
+            && let Some(entries) = object["metadata"].as_array()
+        {
+            sources.extend(
+                entries
+                    .iter()
+                    .filter(|entry| entry["key"] == setting.force_key())
+                    .filter_map(|entry| metadata_number(&entry["value"]))
+                    .map(|value| SettingSource {
+                        origin: SourceOrigin::Metadata,
+                        key: setting.force_key().to_string(),
+                        value,
+                    }),
+            );
+            continue;
+        }
+
+        let is_stream = props["media.class"]
+            .as_str()
+            .is_some_and(|class| class.starts_with("Stream/"));
+        let Some(props_map) = props.as_object().filter(|_| is_stream) else {
+            continue;
+        };
+        let name = ["application.name", "node.description", "node.name"]
+            .iter()
+            .find_map(|key| props[*key].as_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        for (key, value) in props_map {
+            if let Some(value) = setting.client_request(key, value) {
+                sources.push(SettingSource {
+                    origin: SourceOrigin::Client {
+                        id: object["id"].as_u64().unwrap_or_default(),
+                        name: name.clone(),
+                    },
+                    key: key.clone(),
+                    value,
+                });
+            }
+        }
+    }
+
+    Ok(sources)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_load_order() {
+        let temp = tempfile::tempdir().unwrap();
+        let usr = temp.path().join("usr");
+        let user = temp.path().join("user");
+        let write = |base: &Path, file: &str, content: &str| {
+            let path = base.join("pipewire").join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+
+        write(&usr, "pipewire.conf", "#default.clock.rate = 48000\n");
+        write(
+            &usr,
+            "pipewire.conf.d/10-distro.conf",
+            "default.clock.rate = 44100\n",
+        );
+        write(
+            &usr,
+            "pipewire.conf.d/50-shadowed.conf",
+            "default.clock.rate = 192000\n",
+        );
+        write(&user, "pipewire.conf.d/50-shadowed.conf", "# nothing\n");
+        write(
+            &user,
+            "pipewire.conf.d/99-pro-audio.conf",
+            "default.clock.rate = 96000\n",
+        );
+
+        let bases = vec![
+            (ConfigTier::Distribution, usr),
+            (ConfigTier::User, user.clone()),
+        ];
+        let sources = config_sources(TracedSetting::SampleRate, &bases);
+        let values: Vec<u32> = sources.iter().map(|source| source.value).collect();
+        assert_eq!(values, vec![44100, 96000]);
+
+        let trace = SettingTrace {
+            setting: TracedSetting::SampleRate,
+            live: 96000,
+            config: sources,
+            runtime: Vec::new(),
+        };
+        assert_eq!(
+            trace.responsible().unwrap().origin,
+            SourceOrigin::Config {
+                path: user.join("pipewire/pipewire.conf.d/99-pro-audio.conf"),
+                tier: ConfigTier::User,
+            }
+        );
+    }
+
+    #[test]
+    fn test_runtime_sources() {
+        let dump = r#"[
+            { "id": 31, "type": "PipeWire:Interface:Metadata",
+              "info": { "props": { "metadata.name": "settings" } },
+              "metadata": [
+                { "subject": 0, "key": "clock.force-rate", "value": 0 },
+                { "subject": 0, "key": "clock.force-quantum", "value": 64 }
+              ] },
+            { "id": 57, "type": "PipeWire:Interface:Node",
+              "info": { "props": { "media.class": "Stream/Output/Audio",
+                "application.name": "Ardour", "node.rate": "1/44100",
+                "node.latency": "256/44100" } } },
+            { "id": 60, "type": "PipeWire:Interface:Node",
+              "info": { "props": { "media.class": "Audio/Sink",
+                "node.name": "alsa_output.usb", "node.latency": "1024/48000" } } }
+        ]"#;
+
+        let rate = parse_runtime_sources(TracedSetting::SampleRate, dump).unwrap();
+        assert_eq!(rate.len(), 1);
+        assert_eq!(rate[0].value, 44100);
+
+        let trace = SettingTrace {
+            setting: TracedSetting::SampleRate,
+            live: 44100,
+            config: Vec::new(),
+            runtime: rate,
+        };
+        assert_eq!(
+            trace.responsible().unwrap().origin,
+            SourceOrigin::Client {
+                id: 57,
+                name: "Ardour".to_string()
+            }
+        );
+        assert_eq!(trace.effective_config().origin, SourceOrigin::Builtin);
+
+        let quantum = parse_runtime_sources(TracedSetting::Quantum, dump).unwrap();
+        let trace = SettingTrace {
+            setting: TracedSetting::Quantum,
+            live: 64,
+            config: Vec::new(),
+            runtime: quantum,
+        };
+        assert_eq!(trace.responsible().unwrap().origin, SourceOrigin::Metadata);
+        assert!(
+            trace
+                .report()
+                .contains("client \"Ardour\" (node 57) requests node.latency = 256")
+        );
+    }
+}
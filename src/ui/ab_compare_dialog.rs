@@ -0,0 +1,236 @@
+/*
+ * Pro Audio Config - A/B Compare Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * Dialog for switching between two or three candidate buffer/rate settings
+ * live, to compare them by ear without restarting the audio services
+ */
+
+use glib::ControlFlow;
+use gtk::prelude::*;
+use gtk::{Button, ComboBoxText, DialogFlags, Grid, Label, ResponseType};
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::Duration;
+
+use super::app::AudioApp;
+use super::apply_guard::{APPLY_BUSY_TEXT, begin_apply, end_apply};
+use super::components::{create_constrained_combo, populate_combo_box};
+use super::constants::{BUFFER_SIZES, SAMPLE_RATES};
+use super::tasks::add_ui_timeout;
+use crate::audio::{
+    RateSwitching, apply_live_settings, clear_live_settings, detect_rate_switching,
+};
+
+/// Candidate names and their starting buffer sizes
+const CANDIDATES: &[(&str, u32)] = &[("A", 128), ("B", 256), ("C", 512)];
+
+/// One row of the comparison: its switch button and settings
+struct Candidate {
+    name: &'static str,
+    button: Button,
+    buffer_combo: ComboBoxText,
+    rate_combo: ComboBoxText,
+}
+
+impl Candidate {
+    fn settings(&self) -> Option<(u32, u32)> {
+        let value = |combo: &ComboBoxText| combo.active_id().and_then(|id| id.parse::<u32>().ok());
+        Some((value(&self.buffer_combo)?, value(&self.rate_combo)?))
+    }
+}
+
+/// Switches between candidate settings through the settings metadata, so
+/// each click changes the running graph within a moment. Closing the dialog
+/// drops the overrides; "Use in Output Tab" copies the active candidate over
+/// for a permanent apply.
+pub fn show_ab_compare_dialog(app_state: &AudioApp) {
+    let dialog = gtk::Dialog::with_buttons(
+        Some("A/B Compare Buffer Sizes"),
+        Some(&app_state.window),
+        DialogFlags::MODAL,
+        &[
+            ("Close", ResponseType::Close),
+            ("Use in Output Tab", ResponseType::Accept),
+        ],
+    );
+
+    let content = dialog.content_area();
+    content.set_spacing(6);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+
+    let info_label = Label::new(Some(
+        "Click a candidate to switch the running graph to it live, then listen for \
+         crackles or delay. Nothing is written to disk; closing this dialog goes back \
+         to the configured settings.",
+    ));
+    info_label.set_line_wrap(true);
+    info_label.set_halign(gtk::Align::Start);
+
+    let current_label = Label::new(None);
+    current_label.set_markup("<span size='x-large' weight='bold'>No candidate active</span>");
+    current_label.set_halign(gtk::Align::Start);
+
+    let status_label = Label::new(None);
+    status_label.set_halign(gtk::Align::Start);
+    status_label.set_line_wrap(true);
+
+    let current_rate = app_state
+        .output_tab
+        .sample_rate_combo
+        .active_id()
+        .map_or_else(|| "48000".to_string(), |id| id.to_string());
+
+    let grid = Grid::new();
+    grid.set_row_spacing(6);
+    grid.set_column_spacing(6);
+
+    let candidates: Rc<Vec<Candidate>> = Rc::new(
+        CANDIDATES
+            .iter()
+            .enumerate()
+            .map(|(row, (name, buffer_size))| {
+                let button = Button::with_label(&format!("Switch to {}", name));
+
+                let buffer_combo = create_constrained_combo();
+                populate_combo_box(&buffer_combo, BUFFER_SIZES);
+                buffer_combo.set_active_id(Some(&buffer_size.to_string()));
+
+                let rate_combo = create_constrained_combo();
+                populate_combo_box(&rate_combo, SAMPLE_RATES);
+                rate_combo.set_active_id(Some(&current_rate));
+
+                grid.attach(&button, 0, row as i32, 1, 1);
+                grid.attach(&buffer_combo, 1, row as i32, 1, 1);
+                grid.attach(&rate_combo, 2, row as i32, 1, 1);
+
+                Candidate {
+                    name,
+                    button,
+                    buffer_combo,
+                    rate_combo,
+                }
+            })
+            .collect(),
+    );
+
+    content.pack_start(&info_label, false, false, 0);
+    content.pack_start(&grid, false, false, 6);
+    content.pack_start(&current_label, false, false, 6);
+    content.pack_start(&status_label, false, false, 0);
+
+    // Buffer size and rate of the candidate the graph runs at now
+    let active: Rc<Cell<Option<(u32, u32)>>> = Rc::new(Cell::new(None));
+
+    for (index, candidate) in candidates.iter().enumerate() {
+        let candidates = Rc::clone(&candidates);
+        let active = Rc::clone(&active);
+        let current_label = current_label.clone();
+        let status_label = status_label.clone();
+
+        candidate.button.connect_clicked(move |_| {
+            let candidate = &candidates[index];
+            let Some((buffer_size, sample_rate)) = candidate.settings() else {
+                status_label.set_text("Pick a buffer size and sample rate first.");
+                return;
+            };
+            // A service restart from another tab would drop the override right away
+            if !begin_apply() {
+                status_label.set_text(APPLY_BUSY_TEXT);
+                return;
+            }
+            status_label.set_text(&format!("Switching to {}...", candidate.name));
+
+            let (tx, rx) = mpsc::channel();
+            let rx_arc = Arc::new(Mutex::new(rx));
+
+            std::thread::spawn(move || {
+                // Rate changes only take effect live on servers that support it
+                let live_rate = detect_rate_switching() == RateSwitching::Live;
+                let result = apply_live_settings(buffer_size, live_rate.then_some(sample_rate))
+                    .map(|_| live_rate);
+                let _ = tx.send(result);
+            });
+
+            let name = candidate.name;
+            let active = Rc::clone(&active);
+            let current_label = current_label.clone();
+            let status_label = status_label.clone();
+            let rx_timeout = Arc::clone(&rx_arc);
+            add_ui_timeout(Duration::from_millis(100), move || {
+                let rx_guard = rx_timeout.lock().unwrap();
+                match rx_guard.try_recv() {
+                    Ok(result) => {
+                        end_apply();
+                        match result {
+                            Ok(live_rate) => {
+                                active.set(Some((buffer_size, sample_rate)));
+                                current_label.set_markup(&format!(
+                                    "<span size='x-large' weight='bold'>{}: {} samples @ {} Hz = {:.1} ms</span>",
+                                    name,
+                                    buffer_size,
+                                    sample_rate,
+                                    buffer_size as f64 * 1000.0 / sample_rate as f64
+                                ));
+                                status_label.set_text(if live_rate {
+                                    "Running live"
+                                } else {
+                                    "Running live. This PipeWire needs a restart for rate \
+                                     changes, so only the buffer size was switched."
+                                });
+                            }
+                            Err(e) => {
+                                status_label.set_text(&format!("Live switch failed: {}", e));
+                            }
+                        }
+                        ControlFlow::Break
+                    }
+                    Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+                    Err(mpsc::TryRecvError::Disconnected) => {
+                        end_apply();
+                        ControlFlow::Break
+                    }
+                }
+            });
+        });
+    }
+
+    let app_state = app_state.clone();
+    dialog.connect_response(move |dialog, response| {
+        if response == ResponseType::Accept {
+            let Some((buffer_size, sample_rate)) = active.get() else {
+                status_label.set_text("Switch to a candidate first.");
+                return;
+            };
+
+            let output_tab = &app_state.output_tab;
+            output_tab
+                .sample_rate_combo
+                .set_active_id(Some(&sample_rate.to_string()));
+            output_tab
+                .buffer_size_combo
+                .set_active_id(Some(&buffer_size.to_string()));
+            output_tab
+                .status_label
+                .set_text("Settings from the A/B comparison loaded, click Apply to keep them");
+        }
+
+        if active.get().is_some() {
+            std::thread::spawn(|| {
+                if let Err(e) = clear_live_settings() {
+                    log::warn!("Failed to clear live A/B settings: {}", e);
+                }
+            });
+        }
+        dialog.close();
+    });
+
+    dialog.show_all();
+}
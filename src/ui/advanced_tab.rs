@@ -9,60 +9,156 @@
 
 use glib::ControlFlow;
 use gtk::prelude::*;
-use gtk::{Box as GtkBox, Button, CheckButton, ComboBoxText, Entry, Frame, Label, Orientation};
-use std::path::Path;
+use gtk::{
+    Box as GtkBox, Button, CheckButton, ComboBoxText, Entry, Frame, Label, ListBox, Orientation,
+};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
 use std::sync::{Arc, Mutex, mpsc};
 use std::time::Duration;
 
 use crate::audio::{
-    AudioDevice, AudioSettings, DeviceType, detect_recommended_devices, get_device_capabilities,
+    AudioDevice, AudioSettings, CardProfiles, DeviceCapabilities, PRO_AUDIO_PROFILE,
+    ProcessBinaryCheck, buffer_for_latency, check_process_binary, clear_cache,
+    detect_card_profiles, detect_device_discriminators, detect_quantum_floor,
+    detect_recommended_devices, get_device_capabilities, is_device_suitable_for_exclusive_mode,
+    select_best_exclusive_device, set_card_profile, wait_for_card_output,
+};
+use crate::config::{
+    AdvancedAudioSettings, ConfigScope, DEFAULT_JACK_PERIODS, DEFAULT_PRIORITY_DRIVER,
+    DEFAULT_PRIORITY_SESSION, DEFAULT_SUSPEND_TIMEOUT_SECONDS, EXCLUSIVE_CONFIG_FILE, ExclusiveApp,
+    ImportedSettings, ProfessionalOptions, RealtimePrivileges, apply_advanced_audio_settings,
+    apply_advanced_professional_settings, apply_duplex_audio_settings, apply_jack_settings,
+    check_realtime_privileges, default_channel_positions, detect_jack_backend,
+    diff_pending_professional_config, managed_config_paths, parse_channel_positions,
+    parse_existing_config, plan_quantum_verified_restart, preview_advanced_professional_settings,
+    read_exclusive_apps, set_rt_time_limits, user_config_dir,
 };
-use crate::config::apply_advanced_audio_settings;
+use crate::error::AudioConfigError;
+use crate::presets::Preset;
 
 use super::app::AudioApp;
-use super::components::{create_constrained_combo, create_section_box, populate_combo_box};
+use super::apply_guard::{APPLY_BUSY_TEXT, begin_apply, end_apply, register_apply_widget};
+use super::components::{
+    create_constrained_combo, create_section_box, mark_verbose_label, populate_combo_box,
+};
+use super::config_preview_dialog::show_config_preview_dialog;
 use super::constants::{
-    BIT_DEPTHS, BUFFER_SIZES, CONFIG_MODES, EXCLUSIVE_BUFFER_SIZES, SAMPLE_RATES,
+    BIT_DEPTHS, BUFFER_SIZES, CHANNEL_LAYOUTS, CONFIG_MODES, EXCLUSIVE_BUFFER_SIZES, JACK_DRIVERS,
+    JACK_PERIOD_COUNTS, NODE_PRIORITIES, RT_TIME_LIMITS, SAMPLE_RATES, SUSPEND_TIMEOUTS,
+};
+use super::dialogs::{
+    confirm_config_changes, confirm_pro_audio_profile, confirm_process_name_suggestion,
+    confirm_service_restart, confirm_unsupported_rates, show_config_error_dialog,
+    show_error_dialog, show_success_dialog,
 };
-use super::dialogs::{show_error_dialog, show_success_dialog};
-use super::helpers::clean_device_description;
+use super::helpers::{
+    colliding_device_ids, device_display_text, missing_device_message, unsupported_rate_message,
+};
+use super::preferences::{AppPreferences, ProfileRevert};
+use super::preset_picker::PresetPicker;
+use super::tab_types::TabType;
+use super::tasks::add_ui_timeout;
+
+/// What the apply handler looks up about the selected device off the UI thread
+struct DeviceChecks {
+    /// Error to show when the device was unplugged, from `missing_device_message`
+    missing_device: Option<String>,
+    /// Warning for rates the device does not list, from `unsupported_rate_message`
+    unsupported_rates: Option<String>,
+    /// Card profiles of the exclusive device, for the pro-audio switch
+    card_profiles: Option<CardProfiles>,
+    /// Capabilities of the exclusive device, for the buffer size warning
+    capabilities: Option<DeviceCapabilities>,
+}
+
+/// How likely a combination of professional settings is to destabilize the system
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum RiskLevel {
+    Low,
+    Elevated,
+    Dangerous,
+}
+
+/// The professional settings that matter for the risk assessment
+struct ProSettingsSnapshot<'a> {
+    thread_priority: &'a str,
+    clock_source: &'a str,
+    min_buffer: u32,
+    buffer_size: u32,
+    memory_lock: bool,
+    disable_resampling: bool,
+}
 
 #[derive(Clone)]
 pub struct AdvancedTab {
     pub container: GtkBox,
+    pub preset_picker: PresetPicker,
     pub config_mode_combo: ComboBoxText,
     pub global_settings_frame: Frame,
     pub exclusive_settings_frame: Frame,
     pub status_label: Label,
     pub apply_button: Button,
+    pub apply_both_button: Button,
+    /// Shows the files the global apply would write, without writing them
+    pub preview_button: Button,
     pub disable_exclusive_button: Button,
 
     // Global settings
     pub sample_rate_combo: ComboBoxText,
+    pub allowed_rate_checkboxes: Vec<(u32, CheckButton)>,
     pub bit_depth_combo: ComboBoxText,
     pub buffer_size_combo: ComboBoxText,
+    pub quantum_floor_label: Label,
+    /// Quantum-floor of the running server, once detected
+    pub quantum_floor: Rc<Cell<Option<u32>>>,
     pub device_combo: ComboBoxText,
+    pub import_button: Button,
 
     // Exclusive mode settings
     pub application_name_entry: Entry,
     pub process_name_entry: Entry,
+    pub exclusive_app_list: ListBox,
+    pub add_exclusive_app_button: Button,
+    pub remove_exclusive_app_button: Button,
+    pub exclusive_apps: Arc<Mutex<Vec<ExclusiveApp>>>,
     pub exclusive_device_combo: ComboBoxText,
     pub exclusive_sample_rate_combo: ComboBoxText,
     pub exclusive_bit_depth_combo: ComboBoxText,
     pub exclusive_buffer_size_combo: ComboBoxText,
+    pub exclusive_channels_combo: ComboBoxText,
+    pub target_latency_entry: Entry,
     pub latency_label: Label,
 
+    // JACK settings
+    pub jack_settings_frame: Frame,
+    /// Which JACK server the settings go to, refreshed on entering the mode
+    pub jack_backend_label: Label,
+    pub jack_periods_combo: ComboBoxText,
+    pub jack_driver_combo: ComboBoxText,
+
     // Professional settings
     pub pro_settings_frame: Frame,
     pub min_buffer_combo: ComboBoxText,
     pub max_buffer_combo: ComboBoxText,
     pub thread_priority_combo: ComboBoxText,
+    /// rt.time.soft/hard of the realtime module, used by every generated fragment
+    pub rt_time_soft_combo: ComboBoxText,
+    pub rt_time_hard_combo: ComboBoxText,
+    /// priority.driver/session of the ALSA device rule
+    pub priority_driver_combo: ComboBoxText,
+    pub priority_session_combo: ComboBoxText,
     pub memory_lock_checkbox: CheckButton,
-    pub prevent_suspend_checkbox: CheckButton,
+    /// session.suspend-timeout-seconds, globally and in the ALSA device rule
+    pub suspend_timeout_combo: ComboBoxText,
     pub disable_remixing_checkbox: CheckButton,
     pub disable_resampling_checkbox: CheckButton,
     pub resampler_combo: ComboBoxText,
     pub clock_source_combo: ComboBoxText,
+    pub channels_combo: ComboBoxText,
+    pub channel_positions_entry: Entry,
+    pub risk_label: Label,
 
     pub available_devices: Vec<AudioDevice>,
     pub current_default_device: Arc<Mutex<String>>,
@@ -76,29 +172,18 @@ impl Default for AdvancedTab {
 
 impl AdvancedTab {
     fn is_exclusive_mode_active() -> bool {
-        let username = whoami::username();
-
-        let exclusive_config_paths = [
-            "/etc/pipewire/pipewire.conf.d/99-pro-audio-exclusive.conf",
-            "/etc/wireplumber/wireplumber.conf.d/99-pro-audio-exclusive.conf",
-            &format!(
-                "/home/{}/.config/pipewire/pipewire.conf.d/99-pro-audio-exclusive.conf",
-                username
-            ),
-            &format!(
-                "/home/{}/.config/wireplumber/wireplumber.conf.d/99-pro-audio-exclusive.conf",
-                username
-            ),
-        ];
+        let exclusive_config_paths = managed_config_paths(ConfigScope::All)
+            .into_iter()
+            .filter(|path| path.ends_with(EXCLUSIVE_CONFIG_FILE));
 
-        for path in &exclusive_config_paths {
-            if Path::new(path).exists() {
-                println!("DEBUG: Found exclusive mode config at: {}", path);
+        for path in exclusive_config_paths {
+            if path.exists() {
+                log::debug!("Found exclusive mode config at: {}", path.display());
                 return true;
             }
         }
 
-        println!("DEBUG: No exclusive mode config found");
+        log::debug!("No exclusive mode config found");
         false
     }
 
@@ -112,6 +197,8 @@ impl AdvancedTab {
         // ===== CONFIGURATION MODE SELECTION =====
         let (mode_frame, mode_box) = create_section_box("Configuration Mode");
 
+        let preset_picker = PresetPicker::new();
+
         let mode_selection_label = Label::new(Some("Select Configuration Approach:"));
         mode_selection_label.set_halign(gtk::Align::Start);
 
@@ -125,7 +212,9 @@ impl AdvancedTab {
         mode_description_label.set_halign(gtk::Align::Start);
         mode_description_label.set_line_wrap(true);
         Self::update_mode_description(&mode_description_label, "global");
+        mark_verbose_label(&mode_description_label);
 
+        mode_box.pack_start(&preset_picker.container, false, false, 0);
         mode_box.pack_start(&mode_selection_label, false, false, 0);
         mode_box.pack_start(&config_mode_combo, false, false, 0);
         mode_box.pack_start(&mode_description_label, false, false, 0);
@@ -138,19 +227,51 @@ impl AdvancedTab {
         let global_info_label =
             Label::new(Some("These settings affect ALL applications system-wide"));
         global_info_label.set_halign(gtk::Align::Start);
+        mark_verbose_label(&global_info_label);
 
         let global_device_label = Label::new(Some("Audio Device:"));
         global_device_label.set_halign(gtk::Align::Start);
 
         let device_combo = create_constrained_combo();
 
-        let global_sample_rate_label = Label::new(Some("Sample Rate:"));
+        let global_sample_rate_label = Label::new(Some("Graph Rate (default.clock.rate):"));
         global_sample_rate_label.set_halign(gtk::Align::Start);
 
         let sample_rate_combo = create_constrained_combo();
         populate_combo_box(&sample_rate_combo, SAMPLE_RATES);
         sample_rate_combo.set_active_id(Some("48000"));
 
+        let allowed_rates_label = Label::new(Some("Allowed Rates (default.clock.allowed-rates):"));
+        allowed_rates_label.set_halign(gtk::Align::Start);
+
+        let allowed_rates_box = GtkBox::new(Orientation::Horizontal, 6);
+        allowed_rates_box.set_halign(gtk::Align::Start);
+        allowed_rates_box.set_tooltip_text(Some(
+            "Rates PipeWire may switch the graph to when a client asks for them. \
+             The graph rate is always allowed; with only the graph rate everything else is resampled",
+        ));
+
+        let allowed_rate_checkboxes: Vec<(u32, CheckButton)> = SAMPLE_RATES
+            .iter()
+            .map(|(rate, _)| {
+                let checkbox = CheckButton::with_label(&format_rate_khz(*rate));
+                allowed_rates_box.pack_start(&checkbox, false, false, 0);
+                (*rate, checkbox)
+            })
+            .collect();
+        sync_allowed_rates(&allowed_rate_checkboxes, 48000, None);
+
+        {
+            let allowed_rate_checkboxes = allowed_rate_checkboxes.clone();
+            let previous_rate = Rc::new(Cell::new(48000));
+            sample_rate_combo.connect_changed(move |combo| {
+                if let Some(rate) = combo.active_id().and_then(|id| id.parse::<u32>().ok()) {
+                    sync_allowed_rates(&allowed_rate_checkboxes, rate, Some(previous_rate.get()));
+                    previous_rate.set(rate);
+                }
+            });
+        }
+
         let global_bit_depth_label = Label::new(Some("Bit Depth:"));
         global_bit_depth_label.set_halign(gtk::Align::Start);
 
@@ -165,15 +286,29 @@ impl AdvancedTab {
         populate_combo_box(&buffer_size_combo, BUFFER_SIZES);
         buffer_size_combo.set_active_id(Some("512"));
 
+        let quantum_floor_label = Label::new(Some("Checking the server's quantum-floor..."));
+        quantum_floor_label.set_halign(gtk::Align::Start);
+        quantum_floor_label.set_line_wrap(true);
+
+        let import_button = Button::with_label("Import from Existing Config...");
+        import_button.set_halign(gtk::Align::Start);
+        import_button.set_tooltip_text(Some(
+            "Load clock rate, quantum and period size from a hand-written PipeWire config fragment",
+        ));
+
         global_settings_box.pack_start(&global_info_label, false, false, 0);
         global_settings_box.pack_start(&global_device_label, false, false, 0);
         global_settings_box.pack_start(&device_combo, false, false, 0);
         global_settings_box.pack_start(&global_sample_rate_label, false, false, 0);
         global_settings_box.pack_start(&sample_rate_combo, false, false, 0);
+        global_settings_box.pack_start(&allowed_rates_label, false, false, 0);
+        global_settings_box.pack_start(&allowed_rates_box, false, false, 0);
         global_settings_box.pack_start(&global_bit_depth_label, false, false, 0);
         global_settings_box.pack_start(&bit_depth_combo, false, false, 0);
         global_settings_box.pack_start(&global_buffer_size_label, false, false, 0);
         global_settings_box.pack_start(&buffer_size_combo, false, false, 0);
+        global_settings_box.pack_start(&quantum_floor_label, false, false, 0);
+        global_settings_box.pack_start(&import_button, false, false, 6);
 
         // ===== PROFESSIONAL SETTINGS SECTION =====
         let (pro_settings_frame, pro_settings_box) = create_section_box("Professional Settings");
@@ -184,6 +319,7 @@ impl AdvancedTab {
         ));
         pro_info_label.set_halign(gtk::Align::Start);
         pro_info_label.set_line_wrap(true);
+        mark_verbose_label(&pro_info_label);
 
         let buffer_range_label = Label::new(Some("Buffer Size Range (min - max):"));
         buffer_range_label.set_halign(gtk::Align::Start);
@@ -234,16 +370,88 @@ impl AdvancedTab {
         thread_priority_combo.append(Some("high"), "High Priority (-15 nice, 90 RT)");
         thread_priority_combo.append(Some("realtime"), "Real-time Priority (-20 nice, 99 RT)");
         thread_priority_combo.set_active_id(Some("high"));
+        thread_priority_combo.set_tooltip_text(Some(
+            "Higher priorities let audio threads preempt almost everything else. Real-time priority with a tiny buffer can starve the desktop if a client misbehaves",
+        ));
+        min_buffer_combo.set_tooltip_text(Some(
+            "Smallest quantum PipeWire may use. 64 samples needs a tuned system and causes xruns on busy machines",
+        ));
+        max_buffer_combo.set_tooltip_text(Some(
+            "Largest quantum PipeWire may use. Low risk; larger values only add latency",
+        ));
+
+        let rt_time_label = Label::new(Some("Real-time CPU Time Limit (soft - hard):"));
+        rt_time_label.set_halign(gtk::Align::Start);
+
+        let rt_time_box = GtkBox::new(Orientation::Horizontal, 6);
+        rt_time_box.set_halign(gtk::Align::Start);
+
+        let (rt_time_soft, rt_time_hard) = AppPreferences::load().rt_time_limits();
+        let rt_time_soft_combo = create_constrained_combo();
+        let rt_time_hard_combo = create_constrained_combo();
+        for (combo, value) in [
+            (&rt_time_soft_combo, rt_time_soft),
+            (&rt_time_hard_combo, rt_time_hard),
+        ] {
+            combo.set_width_request(180);
+            populate_combo_box(combo, RT_TIME_LIMITS);
+            if !combo.set_active_id(Some(&value.to_string())) {
+                combo.append(Some(&value.to_string()), &format!("{} µs", value));
+                combo.set_active_id(Some(&value.to_string()));
+            }
+            combo.set_tooltip_text(Some(
+                "rt.time.soft/hard: how long a real-time audio thread may use the CPU without \
+                 blocking. Past the soft limit the kernel sends SIGXCPU, past the hard limit it \
+                 kills the thread. Raise them for heavy DSP or very large buffers; the same \
+                 values go into every generated config.",
+            ));
+        }
+
+        let rt_time_separator = Label::new(Some("to"));
+        rt_time_separator.set_halign(gtk::Align::Center);
+
+        rt_time_box.pack_start(&rt_time_soft_combo, false, false, 0);
+        rt_time_box.pack_start(&rt_time_separator, false, false, 6);
+        rt_time_box.pack_start(&rt_time_hard_combo, false, false, 0);
+
+        let node_priority_label = Label::new(Some("Device Priority (driver - session):"));
+        node_priority_label.set_halign(gtk::Align::Start);
+
+        let node_priority_box = GtkBox::new(Orientation::Horizontal, 6);
+        node_priority_box.set_halign(gtk::Align::Start);
+
+        let priority_driver_combo = create_constrained_combo();
+        let priority_session_combo = create_constrained_combo();
+        for (combo, value) in [
+            (&priority_driver_combo, DEFAULT_PRIORITY_DRIVER),
+            (&priority_session_combo, DEFAULT_PRIORITY_SESSION),
+        ] {
+            combo.set_width_request(180);
+            populate_combo_box(combo, NODE_PRIORITIES);
+            combo.set_active_id(Some(&value.to_string()));
+        }
+        priority_driver_combo.set_tooltip_text(Some(
+            "priority.driver: with several interfaces, PipeWire picks the device with the \
+             highest value as the graph driver. Raise it to prefer your interface over \
+             onboard audio.",
+        ));
+        priority_session_combo.set_tooltip_text(Some(
+            "priority.session: the device with the highest value is preferred as the \
+             default when WirePlumber chooses one",
+        ));
+
+        let node_priority_separator = Label::new(Some("and"));
+        node_priority_separator.set_halign(gtk::Align::Center);
+
+        node_priority_box.pack_start(&priority_driver_combo, false, false, 0);
+        node_priority_box.pack_start(&node_priority_separator, false, false, 6);
+        node_priority_box.pack_start(&priority_session_combo, false, false, 0);
 
         let memory_lock_checkbox = CheckButton::with_label("Lock audio memory in RAM (mlock)");
-        let prevent_suspend_checkbox = CheckButton::with_label("Prevent audio device suspend");
         let disable_remixing_checkbox = CheckButton::with_label("Disable channel remixing");
         let disable_resampling_checkbox = CheckButton::with_label("Disable automatic resampling");
 
-        memory_lock_checkbox.set_tooltip_text(Some("Prevents audio buffers from being swapped to disk, reducing latency but using more RAM"));
-        prevent_suspend_checkbox.set_tooltip_text(Some(
-            "Keeps audio devices active even when idle, reducing wake-up latency",
-        ));
+        memory_lock_checkbox.set_tooltip_text(Some("Prevents audio buffers from being swapped to disk, reducing latency but using more RAM. Strongly recommended with high or real-time priority"));
         disable_remixing_checkbox.set_tooltip_text(Some(
             "Prevents automatic channel remixing, maintains original channel layout",
         ));
@@ -251,6 +459,18 @@ impl AdvancedTab {
             "Prevents automatic sample rate conversion, may cause issues if rates don't match",
         ));
 
+        let suspend_timeout_label = Label::new(Some("Suspend Idle Devices After:"));
+        suspend_timeout_label.set_halign(gtk::Align::Start);
+
+        let suspend_timeout_combo = create_constrained_combo();
+        populate_combo_box(&suspend_timeout_combo, SUSPEND_TIMEOUTS);
+        suspend_timeout_combo.set_active_id(Some(&DEFAULT_SUSPEND_TIMEOUT_SECONDS.to_string()));
+        suspend_timeout_combo.set_tooltip_text(Some(
+            "Written as the global default in the PipeWire fragment and into the ALSA \
+             device rule. \"Never suspend\" keeps devices active, avoiding wake-up latency \
+             and pops.",
+        ));
+
         let resampler_label = Label::new(Some("Resampler Quality (if resampling is needed):"));
         resampler_label.set_halign(gtk::Align::Start);
 
@@ -270,25 +490,68 @@ impl AdvancedTab {
         clock_source_combo.append(Some("monotonic"), "Monotonic (more stable)");
         clock_source_combo.append(Some("realtime"), "Real-time (most accurate)");
         clock_source_combo.set_active_id(Some("monotonic"));
+        clock_source_combo.set_tooltip_text(Some(
+            "The real-time clock can jump when the system time is adjusted (e.g. NTP), which can glitch running streams",
+        ));
+
+        let channels_label = Label::new(Some("Channels:"));
+        channels_label.set_halign(gtk::Align::Start);
+
+        let channels_combo = create_constrained_combo();
+        populate_combo_box(&channels_combo, CHANNEL_LAYOUTS);
+        channels_combo.set_active_id(Some("2"));
+
+        let channel_positions_label = Label::new(Some("Channel Positions (audio.position):"));
+        channel_positions_label.set_halign(gtk::Align::Start);
+
+        let channel_positions_entry = Entry::new();
+        channel_positions_entry.set_text(&default_channel_positions(2).join(", "));
+        channel_positions_entry.set_tooltip_text(Some(
+            "Filled in from the channel count. Override for interfaces with a different mapping, e.g. AUX0, AUX1",
+        ));
+
+        {
+            let channel_positions_entry = channel_positions_entry.clone();
+            channels_combo.connect_changed(move |combo| {
+                if let Some(channels) = combo.active_id().and_then(|id| id.parse::<u32>().ok()) {
+                    channel_positions_entry
+                        .set_text(&default_channel_positions(channels).join(", "));
+                }
+            });
+        }
+
+        let risk_label = Label::new(None);
+        risk_label.set_halign(gtk::Align::Start);
+        risk_label.set_line_wrap(true);
 
         pro_settings_box.pack_start(&pro_info_label, false, false, 0);
         pro_settings_box.pack_start(&buffer_range_label, false, false, 6);
         pro_settings_box.pack_start(&buffer_range_box, false, false, 0);
         pro_settings_box.pack_start(&thread_priority_label, false, false, 6);
         pro_settings_box.pack_start(&thread_priority_combo, false, false, 0);
+        pro_settings_box.pack_start(&rt_time_label, false, false, 6);
+        pro_settings_box.pack_start(&rt_time_box, false, false, 0);
+        pro_settings_box.pack_start(&node_priority_label, false, false, 6);
+        pro_settings_box.pack_start(&node_priority_box, false, false, 0);
         pro_settings_box.pack_start(&clock_source_label, false, false, 6);
         pro_settings_box.pack_start(&clock_source_combo, false, false, 0);
+        pro_settings_box.pack_start(&channels_label, false, false, 6);
+        pro_settings_box.pack_start(&channels_combo, false, false, 0);
+        pro_settings_box.pack_start(&channel_positions_label, false, false, 0);
+        pro_settings_box.pack_start(&channel_positions_entry, false, false, 0);
 
         let checkbox_grid = GtkBox::new(Orientation::Vertical, 4);
         checkbox_grid.set_margin_start(4);
         checkbox_grid.pack_start(&memory_lock_checkbox, false, false, 0);
-        checkbox_grid.pack_start(&prevent_suspend_checkbox, false, false, 0);
         checkbox_grid.pack_start(&disable_remixing_checkbox, false, false, 0);
         checkbox_grid.pack_start(&disable_resampling_checkbox, false, false, 0);
 
         pro_settings_box.pack_start(&checkbox_grid, false, false, 6);
+        pro_settings_box.pack_start(&suspend_timeout_label, false, false, 6);
+        pro_settings_box.pack_start(&suspend_timeout_combo, false, false, 0);
         pro_settings_box.pack_start(&resampler_label, false, false, 6);
         pro_settings_box.pack_start(&resampler_combo, false, false, 0);
+        pro_settings_box.pack_start(&risk_label, false, false, 6);
 
         // ===== EXCLUSIVE MODE SETTINGS SECTION =====
         let (exclusive_settings_frame, exclusive_settings_box) =
@@ -300,6 +563,7 @@ impl AdvancedTab {
         ));
         exclusive_info_label.set_halign(gtk::Align::Start);
         exclusive_info_label.set_line_wrap(true);
+        mark_verbose_label(&exclusive_info_label);
 
         let app_name_label = Label::new(Some("Application Name:"));
         app_name_label.set_halign(gtk::Align::Start);
@@ -313,6 +577,18 @@ impl AdvancedTab {
         let process_name_entry = Entry::new();
         process_name_entry.set_placeholder_text(Some("e.g., reaper, ardour, bitwig-studio"));
 
+        // Several clients (e.g. a DAW and a standalone synth) can share exclusive access
+        let app_buttons_box = GtkBox::new(Orientation::Horizontal, 6);
+        let add_exclusive_app_button = Button::with_label("Add Application");
+        let remove_exclusive_app_button = Button::with_label("Remove Selected");
+        app_buttons_box.pack_start(&add_exclusive_app_button, false, false, 0);
+        app_buttons_box.pack_start(&remove_exclusive_app_button, false, false, 0);
+
+        let exclusive_app_list = ListBox::new();
+        exclusive_app_list.set_selection_mode(gtk::SelectionMode::Single);
+        let exclusive_apps = Arc::new(Mutex::new(read_exclusive_apps()));
+        refresh_exclusive_app_list(&exclusive_app_list, &exclusive_apps.lock().unwrap());
+
         let exclusive_device_label = Label::new(Some("Audio Device:"));
         exclusive_device_label.set_halign(gtk::Align::Start);
 
@@ -339,6 +615,22 @@ impl AdvancedTab {
         populate_combo_box(&exclusive_buffer_size_combo, EXCLUSIVE_BUFFER_SIZES);
         exclusive_buffer_size_combo.set_active_id(Some("128"));
 
+        let exclusive_channels_label = Label::new(Some("Channel Layout:"));
+        exclusive_channels_label.set_halign(gtk::Align::Start);
+
+        let exclusive_channels_combo = create_constrained_combo();
+        populate_combo_box(&exclusive_channels_combo, CHANNEL_LAYOUTS);
+        exclusive_channels_combo.set_active_id(Some("2"));
+        exclusive_channels_combo.set_tooltip_text(Some(
+            "audio.channels and audio.position of the exclusive device and app streams",
+        ));
+
+        let target_latency_label = Label::new(Some("Target Latency (ms):"));
+        target_latency_label.set_halign(gtk::Align::Start);
+
+        let target_latency_entry = Entry::new();
+        target_latency_entry.set_placeholder_text(Some("e.g., 5 - picks the nearest buffer size"));
+
         let latency_label = Label::new(Some("Calculated Latency: 2.67ms @ 48kHz"));
         latency_label.set_halign(gtk::Align::Start);
 
@@ -347,6 +639,8 @@ impl AdvancedTab {
         exclusive_settings_box.pack_start(&application_name_entry, false, false, 0);
         exclusive_settings_box.pack_start(&process_name_label, false, false, 0);
         exclusive_settings_box.pack_start(&process_name_entry, false, false, 0);
+        exclusive_settings_box.pack_start(&app_buttons_box, false, false, 0);
+        exclusive_settings_box.pack_start(&exclusive_app_list, false, false, 0);
         exclusive_settings_box.pack_start(&exclusive_device_label, false, false, 0);
         exclusive_settings_box.pack_start(&exclusive_device_combo, false, false, 0);
         exclusive_settings_box.pack_start(&exclusive_sample_rate_label, false, false, 0);
@@ -355,8 +649,50 @@ impl AdvancedTab {
         exclusive_settings_box.pack_start(&exclusive_bit_depth_combo, false, false, 0);
         exclusive_settings_box.pack_start(&exclusive_buffer_size_label, false, false, 0);
         exclusive_settings_box.pack_start(&exclusive_buffer_size_combo, false, false, 0);
+        exclusive_settings_box.pack_start(&exclusive_channels_label, false, false, 0);
+        exclusive_settings_box.pack_start(&exclusive_channels_combo, false, false, 0);
+        exclusive_settings_box.pack_start(&target_latency_label, false, false, 0);
+        exclusive_settings_box.pack_start(&target_latency_entry, false, false, 0);
         exclusive_settings_box.pack_start(&latency_label, false, false, 0);
 
+        // ===== JACK SETTINGS SECTION =====
+        let (jack_settings_frame, jack_settings_box) = create_section_box("JACK Settings");
+        let jack_frame_clone = jack_settings_frame.clone();
+
+        let jack_info_label = Label::new(Some(
+            "Uses the sample rate and buffer size of Global System Settings for JACK clients. \
+             Period count and driver only apply to jackd; pipewire-jack runs on the PipeWire graph.",
+        ));
+        jack_info_label.set_halign(gtk::Align::Start);
+        jack_info_label.set_line_wrap(true);
+        mark_verbose_label(&jack_info_label);
+
+        let jack_backend_label = Label::new(Some("JACK server: not detected yet"));
+        jack_backend_label.set_halign(gtk::Align::Start);
+
+        let jack_periods_label = Label::new(Some("Periods per Buffer:"));
+        jack_periods_label.set_halign(gtk::Align::Start);
+
+        let jack_periods_combo = create_constrained_combo();
+        populate_combo_box(&jack_periods_combo, JACK_PERIOD_COUNTS);
+        jack_periods_combo.set_active_id(Some(&DEFAULT_JACK_PERIODS.to_string()));
+
+        let jack_driver_label = Label::new(Some("Driver:"));
+        jack_driver_label.set_halign(gtk::Align::Start);
+
+        let jack_driver_combo = create_constrained_combo();
+        for (id, label) in JACK_DRIVERS {
+            jack_driver_combo.append(Some(id), label);
+        }
+        jack_driver_combo.set_active_id(Some("alsa"));
+
+        jack_settings_box.pack_start(&jack_info_label, false, false, 0);
+        jack_settings_box.pack_start(&jack_backend_label, false, false, 0);
+        jack_settings_box.pack_start(&jack_periods_label, false, false, 0);
+        jack_settings_box.pack_start(&jack_periods_combo, false, false, 0);
+        jack_settings_box.pack_start(&jack_driver_label, false, false, 0);
+        jack_settings_box.pack_start(&jack_driver_combo, false, false, 0);
+
         // ===== ADVANCED ACTIONS SECTION =====
         let (actions_frame, actions_box) = create_section_box("Advanced Actions");
 
@@ -365,20 +701,37 @@ impl AdvancedTab {
         status_label.set_line_wrap(true);
 
         let apply_button = Button::with_label("Apply Global System Settings");
+        register_apply_widget(&apply_button);
+
+        let apply_both_button = Button::with_label("Apply to Both Input and Output");
+        apply_both_button.set_tooltip_text(Some(
+            "Write one configuration for the default output and input devices and restart audio services once",
+        ));
+        register_apply_widget(&apply_both_button);
+
+        let preview_button = Button::with_label("Preview Config...");
+        preview_button.set_tooltip_text(Some(
+            "Show the files and content the global apply would write, including the edit \
+             to the main pipewire.conf, without writing anything",
+        ));
 
         let disable_exclusive_button = Button::with_label("Disable Exclusive Mode");
 
         let is_exclusive_active = Self::is_exclusive_mode_active();
-        println!("DEBUG: Exclusive mode active: {}", is_exclusive_active);
+        log::debug!("Exclusive mode active: {}", is_exclusive_active);
         disable_exclusive_button.set_sensitive(is_exclusive_active);
+        register_apply_widget(&disable_exclusive_button);
 
         let info_label = Label::new(Some(
             "Note: Professional settings may require administrator privileges and can affect system stability",
         ));
         info_label.set_line_wrap(true);
+        mark_verbose_label(&info_label);
 
         actions_box.pack_start(&status_label, false, false, 0);
         actions_box.pack_start(&apply_button, false, false, 0);
+        actions_box.pack_start(&apply_both_button, false, false, 0);
+        actions_box.pack_start(&preview_button, false, false, 0);
         actions_box.pack_start(&disable_exclusive_button, false, false, 0);
         actions_box.pack_start(&info_label, false, false, 0);
 
@@ -387,41 +740,67 @@ impl AdvancedTab {
         container.pack_start(&global_settings_frame, false, false, 0);
         container.pack_start(&pro_settings_frame, false, false, 0);
         container.pack_start(&exclusive_settings_frame, false, false, 0);
+        container.pack_start(&jack_settings_frame, false, false, 0);
         container.pack_start(&actions_frame, false, false, 0);
 
         global_frame_clone.hide();
         pro_frame_clone.hide();
         exclusive_frame_clone.hide();
+        jack_frame_clone.hide();
 
         let tab = AdvancedTab {
             container,
+            preset_picker,
             config_mode_combo,
             global_settings_frame,
             exclusive_settings_frame,
             status_label,
             apply_button,
+            apply_both_button,
+            preview_button,
             disable_exclusive_button,
             sample_rate_combo,
+            allowed_rate_checkboxes,
             bit_depth_combo,
             buffer_size_combo,
+            quantum_floor_label,
+            quantum_floor: Rc::new(Cell::new(None)),
             device_combo,
+            import_button,
             application_name_entry,
             process_name_entry,
+            exclusive_app_list,
+            add_exclusive_app_button,
+            remove_exclusive_app_button,
+            exclusive_apps,
             exclusive_device_combo,
             exclusive_sample_rate_combo,
             exclusive_bit_depth_combo,
             exclusive_buffer_size_combo,
+            exclusive_channels_combo,
+            target_latency_entry,
             latency_label,
+            jack_settings_frame,
+            jack_backend_label,
+            jack_periods_combo,
+            jack_driver_combo,
             pro_settings_frame,
             min_buffer_combo,
             max_buffer_combo,
             thread_priority_combo,
+            rt_time_soft_combo,
+            rt_time_hard_combo,
+            priority_driver_combo,
+            priority_session_combo,
             memory_lock_checkbox,
-            prevent_suspend_checkbox,
+            suspend_timeout_combo,
             disable_remixing_checkbox,
             disable_resampling_checkbox,
             resampler_combo,
             clock_source_combo,
+            channels_combo,
+            channel_positions_entry,
+            risk_label,
             available_devices: Vec::new(),
             current_default_device: Arc::new(Mutex::new(String::new())),
         };
@@ -431,6 +810,9 @@ impl AdvancedTab {
             tab_clone.global_settings_frame.show();
             tab_clone.pro_settings_frame.show();
             tab_clone.exclusive_settings_frame.hide();
+            tab_clone.jack_settings_frame.hide();
+            tab_clone.apply_both_button.show();
+            tab_clone.preview_button.show();
             tab_clone
                 .apply_button
                 .set_label("Apply Global System Settings");
@@ -439,19 +821,305 @@ impl AdvancedTab {
         tab
     }
 
+    /// Flags combinations of professional settings that can wedge the system
+    fn assess_professional_risk(settings: &ProSettingsSnapshot) -> (RiskLevel, Vec<&'static str>) {
+        let mut level = RiskLevel::Low;
+        let mut reasons = Vec::new();
+        let elevated_priority = settings.thread_priority != "normal";
+        let smallest_buffer = settings.min_buffer.min(settings.buffer_size);
+
+        if settings.thread_priority == "realtime" && !settings.memory_lock && smallest_buffer <= 64
+        {
+            level = level.max(RiskLevel::Dangerous);
+            reasons.push(
+                "Real-time priority without memory lock at 64 samples can lock up the desktop; recovery may need a reboot",
+            );
+        } else if elevated_priority && !settings.memory_lock {
+            level = level.max(RiskLevel::Elevated);
+            reasons.push(
+                "High priority without memory lock: page faults in audio threads cause dropouts",
+            );
+        }
+
+        if settings.thread_priority == "realtime" && settings.memory_lock && smallest_buffer <= 64 {
+            level = level.max(RiskLevel::Elevated);
+            reasons.push("Real-time priority at 64 samples leaves little CPU time for other tasks");
+        } else if smallest_buffer <= 64 && settings.thread_priority != "realtime" {
+            level = level.max(RiskLevel::Elevated);
+            reasons.push("64-sample buffers need a tuned system and will xrun on busy machines");
+        }
+
+        if settings.min_buffer > settings.buffer_size {
+            level = level.max(RiskLevel::Elevated);
+            reasons.push("Minimum buffer is larger than the selected buffer size");
+        }
+
+        if settings.disable_resampling {
+            level = level.max(RiskLevel::Elevated);
+            reasons.push("Without resampling, streams at other sample rates may fail to play");
+        }
+
+        if settings.clock_source == "realtime" {
+            level = level.max(RiskLevel::Elevated);
+            reasons.push("The real-time clock can jump on time adjustments and glitch streams");
+        }
+
+        (level, reasons)
+    }
+
+    fn update_risk_label(label: &Label, level: RiskLevel, reasons: &[&str]) {
+        let (color, title) = match level {
+            RiskLevel::Low => ("green", "Risk: Low"),
+            RiskLevel::Elevated => ("orange", "Risk: Elevated"),
+            RiskLevel::Dangerous => ("red", "Risk: Dangerous"),
+        };
+
+        let mut markup = format!("<b><span foreground='{}'>{}</span></b>", color, title);
+        for reason in reasons {
+            markup.push_str(&format!("\n• {}", gtk::glib::markup_escape_text(reason)));
+        }
+        label.set_markup(&markup);
+    }
+
+    /// Selects imported values in the combos, returning values with no matching entry
+    fn apply_imported_settings(&self, imported: &ImportedSettings) -> Vec<String> {
+        let mut unmatched = Vec::new();
+        let targets = [
+            ("Sample rate", imported.sample_rate, &self.sample_rate_combo),
+            (
+                "Buffer size",
+                imported.buffer_size(),
+                &self.buffer_size_combo,
+            ),
+            (
+                "Minimum buffer",
+                imported.min_quantum,
+                &self.min_buffer_combo,
+            ),
+            (
+                "Maximum buffer",
+                imported.max_quantum,
+                &self.max_buffer_combo,
+            ),
+        ];
+
+        for (field, value, combo) in targets {
+            if let Some(value) = value
+                && !combo.set_active_id(Some(&value.to_string()))
+            {
+                unmatched.push(format!(
+                    "{} {} is not one of the offered values",
+                    field, value
+                ));
+            }
+        }
+
+        unmatched
+    }
+
+    /// Fills the global combos from a preset. Presets with exclusive-mode
+    /// settings also switch the mode and fill the exclusive combos.
+    fn select_preset(&self, preset: &Preset) {
+        let settings = &preset.settings;
+        self.sample_rate_combo
+            .set_active_id(Some(&settings.sample_rate.to_string()));
+        self.bit_depth_combo
+            .set_active_id(Some(&settings.bit_depth.to_string()));
+        self.buffer_size_combo
+            .set_active_id(Some(&settings.buffer_size.to_string()));
+        // Changing the count refills the positions with its standard layout
+        self.channels_combo
+            .set_active_id(Some(&settings.channels.to_string()));
+        if !settings.position.is_empty() {
+            self.channel_positions_entry
+                .set_text(&settings.position.join(", "));
+        }
+
+        let Some(advanced) = &preset.advanced else {
+            return;
+        };
+        if advanced.exclusive_mode {
+            self.exclusive_sample_rate_combo
+                .set_active_id(Some(&advanced.sample_rate.to_string()));
+            self.exclusive_bit_depth_combo
+                .set_active_id(Some(&settings.bit_depth.to_string()));
+            self.exclusive_buffer_size_combo
+                .set_active_id(Some(&advanced.buffer_size.to_string()));
+            self.exclusive_channels_combo
+                .set_active_id(Some(&settings.channels.to_string()));
+            self.config_mode_combo.set_active_id(Some("exclusive"));
+        } else {
+            self.config_mode_combo.set_active_id(Some("global"));
+        }
+    }
+
+    /// Settings of the global apply as currently picked, with the apply
+    /// defaults for anything not selected. Err for an invalid position list.
+    fn global_settings(&self) -> Result<AudioSettings, String> {
+        let value = |combo: &ComboBoxText| combo.active_id().and_then(|id| id.parse::<u32>().ok());
+        let (channels, position) =
+            selected_channel_layout(&self.channels_combo, &self.channel_positions_entry)?;
+
+        Ok(AudioSettings {
+            sample_rate: value(&self.sample_rate_combo).unwrap_or(48000),
+            bit_depth: value(&self.bit_depth_combo).unwrap_or(24),
+            buffer_size: value(&self.buffer_size_combo).unwrap_or(512),
+            device_id: self
+                .device_combo
+                .active_id()
+                .map_or_else(|| "default".to_string(), |id| id.to_string()),
+            allowed_rates: selected_allowed_rates(&self.allowed_rate_checkboxes),
+            node_description: None,
+            period_size: None,
+            channels,
+            position,
+        })
+    }
+
+    /// The Professional Settings as currently picked
+    fn professional_options(&self) -> ProfessionalOptions {
+        let value = |combo: &ComboBoxText| combo.active_id().and_then(|id| id.parse::<u32>().ok());
+        let text = |combo: &ComboBoxText, default: &str| {
+            combo
+                .active_id()
+                .map_or_else(|| default.to_string(), |id| id.to_string())
+        };
+
+        ProfessionalOptions {
+            min_buffer: value(&self.min_buffer_combo).unwrap_or(128),
+            max_buffer: value(&self.max_buffer_combo).unwrap_or(2048),
+            thread_priority: text(&self.thread_priority_combo, "high"),
+            memory_lock: self.memory_lock_checkbox.is_active(),
+            suspend_timeout: value(&self.suspend_timeout_combo)
+                .unwrap_or(DEFAULT_SUSPEND_TIMEOUT_SECONDS),
+            disable_remixing: self.disable_remixing_checkbox.is_active(),
+            disable_resampling: self.disable_resampling_checkbox.is_active(),
+            resampler_quality: text(&self.resampler_combo, "high"),
+            clock_source: text(&self.clock_source_combo, "monotonic"),
+            priority_driver: value(&self.priority_driver_combo).unwrap_or(DEFAULT_PRIORITY_DRIVER),
+            priority_session: value(&self.priority_session_combo)
+                .unwrap_or(DEFAULT_PRIORITY_SESSION),
+        }
+    }
+
+    /// The current selections as a preset. The exclusive combos are only
+    /// kept in exclusive mode; JACK mode is not stored.
+    fn preset_from_selections(&self, name: &str) -> Preset {
+        let value = |combo: &ComboBoxText| combo.active_id().and_then(|id| id.parse::<u32>().ok());
+        let mode = self.config_mode_combo.active_id();
+
+        // An unparsable position list is saved as the standard layout
+        let (channels, position) = if mode.as_deref() == Some("exclusive") {
+            (
+                value(&self.exclusive_channels_combo).unwrap_or(2),
+                Vec::new(),
+            )
+        } else {
+            selected_channel_layout(&self.channels_combo, &self.channel_positions_entry)
+                .unwrap_or_else(|_| (value(&self.channels_combo).unwrap_or(2), Vec::new()))
+        };
+        let settings = AudioSettings::new(
+            value(&self.sample_rate_combo).unwrap_or(48000),
+            value(&self.bit_depth_combo).unwrap_or(24),
+            value(&self.buffer_size_combo).unwrap_or(512),
+            "default".to_string(),
+        )
+        .with_channels(channels, position);
+
+        let advanced = match mode.as_deref() {
+            Some("exclusive") => Some(AdvancedAudioSettings {
+                exclusive_mode: true,
+                direct_hardware: true,
+                low_latency: true,
+                buffer_size: value(&self.exclusive_buffer_size_combo).unwrap_or(128),
+                sample_rate: value(&self.exclusive_sample_rate_combo).unwrap_or(48000),
+            }),
+            Some("global") => Some(AdvancedAudioSettings {
+                exclusive_mode: false,
+                direct_hardware: false,
+                low_latency: false,
+                buffer_size: settings.buffer_size,
+                sample_rate: settings.sample_rate,
+            }),
+            _ => None,
+        };
+
+        Preset::new(name, settings, advanced)
+    }
+
     fn update_mode_description(label: &Label, mode: &str) {
         let text = match mode {
             "global" => {
                 "Global System Settings: Configure audio settings system-wide for ALL applications. This is the standard approach for general audio configuration."
             }
             "exclusive" => {
-                "Exclusive Mode: Provide direct hardware access to a SINGLE application, bypassing mixing and processing. Similar to ASIO/WASAPI exclusive mode for low-latency professional audio work."
+                "Exclusive Mode: Provide direct hardware access to the listed applications, bypassing mixing and processing. Similar to ASIO/WASAPI exclusive mode for low-latency professional audio work."
+            }
+            "jack" => {
+                "JACK / Pro Audio Bridge: Configure the JACK server used by JACK-only applications and plugin hosts, either jackd or PipeWire's JACK bridge, with the sample rate and buffer size below."
             }
             _ => "Select a configuration mode above",
         };
         label.set_text(text);
     }
 
+    /// Reads the running server's quantum-floor for the note under the
+    /// buffer selector
+    pub fn detect_quantum_floor(&self) {
+        let quantum_floor = Rc::clone(&self.quantum_floor);
+        let quantum_floor_label = self.quantum_floor_label.clone();
+        let buffer_size_combo = self.buffer_size_combo.clone();
+
+        let (tx, rx) = mpsc::channel();
+        let rx_arc = Arc::new(Mutex::new(rx));
+
+        std::thread::spawn(move || {
+            let _ = tx.send(detect_quantum_floor());
+        });
+
+        let rx_timeout = Arc::clone(&rx_arc);
+        add_ui_timeout(Duration::from_millis(100), move || {
+            let rx_guard = rx_timeout.lock().unwrap();
+            match rx_guard.try_recv() {
+                Ok(Ok(floor)) => {
+                    quantum_floor.set(Some(floor));
+                    Self::update_quantum_floor_note(
+                        &quantum_floor_label,
+                        &buffer_size_combo,
+                        floor,
+                    );
+                    ControlFlow::Break
+                }
+                Ok(Err(e)) => {
+                    log::debug!("Quantum-floor detection failed: {}", e);
+                    quantum_floor_label
+                        .set_text("Server quantum-floor: unknown (PipeWire not running?)");
+                    ControlFlow::Break
+                }
+                Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+                Err(mpsc::TryRecvError::Disconnected) => ControlFlow::Break,
+            }
+        });
+    }
+
+    fn update_quantum_floor_note(label: &Label, buffer_size_combo: &ComboBoxText, floor: u32) {
+        let buffer_size = buffer_size_combo
+            .active_id()
+            .and_then(|id| id.parse::<u32>().ok());
+        label.set_text(&Self::quantum_floor_note(floor, buffer_size));
+    }
+
+    fn quantum_floor_note(floor: u32, buffer_size: Option<u32>) -> String {
+        match buffer_size {
+            Some(size) if size < floor => format!(
+                "Server quantum-floor: {} samples. Warning: {} samples is below it, \
+                 PipeWire will clamp the buffer to {}.",
+                floor, size, floor
+            ),
+            _ => format!("Server quantum-floor: {} samples", floor),
+        }
+    }
+
     pub fn detect_advanced_devices(&self) {
         let device_combo = self.device_combo.clone();
         let exclusive_device_combo = self.exclusive_device_combo.clone();
@@ -461,17 +1129,21 @@ impl AdvancedTab {
         let rx_arc = Arc::new(Mutex::new(rx));
 
         std::thread::spawn(move || {
-            let result = detect_recommended_devices();
+            // Devices reading the same get their card/port looked up here, off the UI thread
+            let result = detect_recommended_devices().map(|devices| {
+                let discriminators = detect_device_discriminators(&colliding_device_ids(&devices));
+                (devices, discriminators)
+            });
             let _ = tx.send(result);
         });
 
         let rx_timeout = Arc::clone(&rx_arc);
-        glib::timeout_add_local(Duration::from_millis(100), move || {
+        add_ui_timeout(Duration::from_millis(100), move || {
             let rx_guard = rx_timeout.lock().unwrap();
             match rx_guard.try_recv() {
                 Ok(result) => {
                     match result {
-                        Ok(devices) => {
+                        Ok((devices, discriminators)) => {
                             device_combo.remove_all();
                             exclusive_device_combo.remove_all();
 
@@ -487,6 +1159,8 @@ impl AdvancedTab {
                             device_combo.append(Some("default"), &default_device_name);
                             exclusive_device_combo.append(Some("default"), &default_device_name);
 
+                            let prefs = AppPreferences::load();
+                            let mut favorite_devices = Vec::new();
                             let mut usb_devices = Vec::new();
                             let mut hdmi_devices = Vec::new();
                             let mut pci_devices = Vec::new();
@@ -497,7 +1171,9 @@ impl AdvancedTab {
                                 let name_lower = device.name.to_lowercase();
                                 let id_lower = device.id.to_lowercase();
 
-                                if desc_lower.contains("usb")
+                                if prefs.is_favorite_device(device) {
+                                    favorite_devices.push(device);
+                                } else if desc_lower.contains("usb")
                                     || name_lower.contains("usb")
                                     || id_lower.contains("usb")
                                 {
@@ -518,14 +1194,40 @@ impl AdvancedTab {
                                 }
                             }
 
+                            if !favorite_devices.is_empty() {
+                                device_combo.append(Some("separator0"), "--- Favorites ---");
+                                exclusive_device_combo
+                                    .append(Some("separator0"), "--- Favorites ---");
+                                for device in favorite_devices {
+                                    Self::add_device_to_combo(
+                                        &device_combo,
+                                        device,
+                                        &discriminators,
+                                    );
+                                    Self::add_exclusive_device_to_combo(
+                                        &exclusive_device_combo,
+                                        device,
+                                        &discriminators,
+                                    );
+                                }
+                            }
+
                             if !usb_devices.is_empty() {
                                 device_combo
                                     .append(Some("separator1"), "--- USB Audio Devices ---");
                                 exclusive_device_combo
                                     .append(Some("separator1"), "--- USB Audio Devices ---");
                                 for device in usb_devices {
-                                    Self::add_device_to_combo(&device_combo, device);
-                                    Self::add_device_to_combo(&exclusive_device_combo, device);
+                                    Self::add_device_to_combo(
+                                        &device_combo,
+                                        device,
+                                        &discriminators,
+                                    );
+                                    Self::add_exclusive_device_to_combo(
+                                        &exclusive_device_combo,
+                                        device,
+                                        &discriminators,
+                                    );
                                 }
                             }
 
@@ -535,8 +1237,16 @@ impl AdvancedTab {
                                 exclusive_device_combo
                                     .append(Some("separator2"), "--- PCI Audio Devices ---");
                                 for device in pci_devices {
-                                    Self::add_device_to_combo(&device_combo, device);
-                                    Self::add_device_to_combo(&exclusive_device_combo, device);
+                                    Self::add_device_to_combo(
+                                        &device_combo,
+                                        device,
+                                        &discriminators,
+                                    );
+                                    Self::add_exclusive_device_to_combo(
+                                        &exclusive_device_combo,
+                                        device,
+                                        &discriminators,
+                                    );
                                 }
                             }
 
@@ -546,8 +1256,16 @@ impl AdvancedTab {
                                 exclusive_device_combo
                                     .append(Some("separator3"), "--- HDMI/DisplayPort Audio ---");
                                 for device in hdmi_devices {
-                                    Self::add_device_to_combo(&device_combo, device);
-                                    Self::add_device_to_combo(&exclusive_device_combo, device);
+                                    Self::add_device_to_combo(
+                                        &device_combo,
+                                        device,
+                                        &discriminators,
+                                    );
+                                    Self::add_exclusive_device_to_combo(
+                                        &exclusive_device_combo,
+                                        device,
+                                        &discriminators,
+                                    );
                                 }
                             }
 
@@ -557,13 +1275,29 @@ impl AdvancedTab {
                                 exclusive_device_combo
                                     .append(Some("separator4"), "--- Other Audio Devices ---");
                                 for device in other_devices {
-                                    Self::add_device_to_combo(&device_combo, device);
-                                    Self::add_device_to_combo(&exclusive_device_combo, device);
+                                    Self::add_device_to_combo(
+                                        &device_combo,
+                                        device,
+                                        &discriminators,
+                                    );
+                                    Self::add_exclusive_device_to_combo(
+                                        &exclusive_device_combo,
+                                        device,
+                                        &discriminators,
+                                    );
                                 }
                             }
+
+                            // Steer exclusive mode towards the most suitable interface
+                            if let Some(best) = select_best_exclusive_device(&devices) {
+                                log::debug!("Pre-selecting exclusive mode device: {}", best.id);
+                                exclusive_device_combo.set_active_id(Some(&best.id));
+                            } else {
+                                exclusive_device_combo.set_active_id(Some("default"));
+                            }
                         }
                         Err(e) => {
-                            println!("Error detecting advanced devices: {}", e);
+                            log::info!("Error detecting advanced devices: {}", e);
                             device_combo.append(Some("default"), "Default System Device");
                             exclusive_device_combo.append(Some("default"), "Default System Device");
                             device_combo.set_active_id(Some("default"));
@@ -584,25 +1318,46 @@ impl AdvancedTab {
         });
     }
 
-    fn add_device_to_combo(combo: &ComboBoxText, device: &AudioDevice) {
-        let device_type = match device.device_type {
-            DeviceType::Input => "🎤 Input",
-            DeviceType::Output => "🔊 Output",
-            DeviceType::Duplex => "🔄 Duplex",
-            _ => "🔊 Output",
-        };
-
-        let clean_description = clean_device_description(&device.description);
+    fn add_device_to_combo(
+        combo: &ComboBoxText,
+        device: &AudioDevice,
+        discriminators: &HashMap<String, String>,
+    ) {
+        let discriminator = discriminators.get(&device.id).map(String::as_str);
+        combo.append(
+            Some(&device.id),
+            &device_display_text(device, discriminator),
+        );
+    }
 
-        let display_text = if clean_description.is_empty() {
-            format!("{} {}", device_type, device.name)
+    fn add_exclusive_device_to_combo(
+        combo: &ComboBoxText,
+        device: &AudioDevice,
+        discriminators: &HashMap<String, String>,
+    ) {
+        let discriminator = discriminators.get(&device.id).map(String::as_str);
+        let display_text = device_display_text(device, discriminator);
+        let display_text = if is_device_suitable_for_exclusive_mode(device) {
+            display_text
         } else {
-            format!("{} {} - {}", device_type, device.name, clean_description)
+            format!("{} (not recommended)", display_text)
         };
         combo.append(Some(&device.id), &display_text);
     }
 
     pub fn setup_signals(&self, _app_state: AudioApp) {
+        {
+            let tab = self.clone();
+            self.preset_picker
+                .connect_preset_selected(move |preset| tab.select_preset(preset));
+
+            let tab = self.clone();
+            self.preset_picker
+                .connect_save(&self.status_label, move |name| {
+                    tab.preset_from_selections(name)
+                });
+        }
+
         let config_mode_combo = self.config_mode_combo.clone();
         let global_settings_frame = self.global_settings_frame.clone();
         let exclusive_settings_frame = self.exclusive_settings_frame.clone();
@@ -622,6 +1377,17 @@ impl AdvancedTab {
         let exclusive_buffer_size_combo = self.exclusive_buffer_size_combo.clone();
         let latency_label = self.latency_label.clone();
 
+        // Keep the quantum-floor note in step with the chosen buffer size
+        {
+            let quantum_floor = Rc::clone(&self.quantum_floor);
+            let quantum_floor_label = self.quantum_floor_label.clone();
+            buffer_size_combo.connect_changed(move |combo| {
+                if let Some(floor) = quantum_floor.get() {
+                    Self::update_quantum_floor_note(&quantum_floor_label, combo, floor);
+                }
+            });
+        }
+
         let disable_exclusive_button = self.disable_exclusive_button.clone();
 
         let is_exclusive_active = Self::is_exclusive_mode_active();
@@ -633,7 +1399,11 @@ impl AdvancedTab {
             let global_settings_frame = global_settings_frame.clone();
             let pro_settings_frame = self.pro_settings_frame.clone();
             let exclusive_settings_frame = exclusive_settings_frame.clone();
+            let jack_settings_frame = self.jack_settings_frame.clone();
+            let jack_backend_label = self.jack_backend_label.clone();
             let apply_button = apply_button.clone();
+            let apply_both_button = self.apply_both_button.clone();
+            let preview_button = self.preview_button.clone();
 
             config_mode_combo.connect_changed(move |combo| {
                 if let Some(mode) = combo.active_id() {
@@ -644,14 +1414,34 @@ impl AdvancedTab {
                             global_settings_frame.show();
                             pro_settings_frame.show();
                             exclusive_settings_frame.hide();
+                            jack_settings_frame.hide();
+                            apply_both_button.show();
+                            preview_button.show();
                             apply_button.set_label("Apply Advanced Global Settings");
                         }
                         "exclusive" => {
                             global_settings_frame.hide();
                             pro_settings_frame.hide();
                             exclusive_settings_frame.show();
+                            jack_settings_frame.hide();
+                            apply_both_button.hide();
+                            preview_button.hide();
                             apply_button.set_label("Apply Exclusive Mode Settings");
                         }
+                        "jack" => {
+                            global_settings_frame.show();
+                            pro_settings_frame.hide();
+                            exclusive_settings_frame.hide();
+                            jack_settings_frame.show();
+                            apply_both_button.hide();
+                            preview_button.hide();
+                            apply_button.set_label("Apply JACK Settings");
+                            jack_backend_label.set_text(&match detect_jack_backend() {
+                                Some(backend) => format!("JACK server: {}", backend.label()),
+                                None => "JACK server: neither jackd nor pipewire-jack is installed"
+                                    .to_string(),
+                            });
+                        }
                         _ => {}
                     }
                 }
@@ -704,163 +1494,271 @@ impl AdvancedTab {
             });
         }
 
+        // Target latency: pick the buffer size for the typed milliseconds
+        {
+            // Buffer sizes the selected device reports, refreshed per device
+            let device_buffer_sizes: Arc<Mutex<Vec<u32>>> = Arc::new(Mutex::new(Vec::new()));
+
+            let snap_to_target = {
+                let target_latency_entry = self.target_latency_entry.clone();
+                let exclusive_sample_rate_combo = exclusive_sample_rate_combo.clone();
+                let exclusive_buffer_size_combo = exclusive_buffer_size_combo.clone();
+                let device_buffer_sizes = device_buffer_sizes.clone();
+
+                move || {
+                    let Ok(latency_ms) = target_latency_entry.text().trim().parse::<f64>() else {
+                        return;
+                    };
+                    let Some(sample_rate) = exclusive_sample_rate_combo
+                        .active_id()
+                        .and_then(|id| id.parse::<u32>().ok())
+                    else {
+                        return;
+                    };
+
+                    let device_sizes = device_buffer_sizes.lock().unwrap().clone();
+                    let offered: Vec<u32> = EXCLUSIVE_BUFFER_SIZES
+                        .iter()
+                        .map(|(size, _)| *size)
+                        .collect();
+                    let supported: Vec<u32> = offered
+                        .iter()
+                        .copied()
+                        .filter(|size| device_sizes.contains(size))
+                        .collect();
+                    let candidates = if supported.is_empty() {
+                        &offered
+                    } else {
+                        &supported
+                    };
+
+                    if let Some(buffer_size) =
+                        buffer_for_latency(latency_ms, sample_rate, candidates)
+                    {
+                        exclusive_buffer_size_combo.set_active_id(Some(&buffer_size.to_string()));
+                    }
+                }
+            };
+
+            {
+                let snap_to_target = snap_to_target.clone();
+                self.target_latency_entry
+                    .connect_changed(move |_| snap_to_target());
+            }
+            {
+                let snap_to_target = snap_to_target.clone();
+                exclusive_sample_rate_combo.connect_changed(move |_| snap_to_target());
+            }
+
+            exclusive_device_combo.connect_changed(move |combo| {
+                let device_id = combo
+                    .active_id()
+                    .map(|id| id.to_string())
+                    .unwrap_or_else(|| "default".to_string());
+
+                let (tx, rx) = mpsc::channel();
+                let rx_arc = Arc::new(Mutex::new(rx));
+
+                std::thread::spawn(move || {
+                    let _ = tx.send(get_device_capabilities(&device_id));
+                });
+
+                let device_buffer_sizes = device_buffer_sizes.clone();
+                let snap_to_target = snap_to_target.clone();
+                let rx_timeout = Arc::clone(&rx_arc);
+                add_ui_timeout(Duration::from_millis(100), move || {
+                    let rx_guard = rx_timeout.lock().unwrap();
+                    match rx_guard.try_recv() {
+                        Ok(result) => {
+                            *device_buffer_sizes.lock().unwrap() = result
+                                .map(|capabilities| capabilities.buffer_sizes)
+                                .unwrap_or_default();
+                            snap_to_target();
+                            ControlFlow::Break
+                        }
+                        Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+                        Err(mpsc::TryRecvError::Disconnected) => ControlFlow::Break,
+                    }
+                });
+            });
+        }
+
         // Apply button handler for advanced tab
         {
-            let config_mode_combo = config_mode_combo.clone();
             let status_label = status_label.clone();
-            let apply_button = apply_button.clone();
 
             let sample_rate_combo = sample_rate_combo.clone();
             let bit_depth_combo = bit_depth_combo.clone();
             let buffer_size_combo = buffer_size_combo.clone();
-            let device_combo = device_combo.clone();
 
-            let min_buffer_combo = self.min_buffer_combo.clone();
-            let max_buffer_combo = self.max_buffer_combo.clone();
-            let thread_priority_combo = self.thread_priority_combo.clone();
-            let memory_lock_checkbox = self.memory_lock_checkbox.clone();
-            let prevent_suspend_checkbox = self.prevent_suspend_checkbox.clone();
-            let disable_remixing_checkbox = self.disable_remixing_checkbox.clone();
-            let disable_resampling_checkbox = self.disable_resampling_checkbox.clone();
-            let resampler_combo = self.resampler_combo.clone();
-            let clock_source_combo = self.clock_source_combo.clone();
+            let jack_periods_combo = self.jack_periods_combo.clone();
+            let jack_driver_combo = self.jack_driver_combo.clone();
 
             let exclusive_device_combo = exclusive_device_combo.clone();
             let exclusive_sample_rate_combo = exclusive_sample_rate_combo.clone();
             let exclusive_buffer_size_combo = exclusive_buffer_size_combo.clone();
+            let exclusive_channels_combo = self.exclusive_channels_combo.clone();
 
             let application_name_entry_clone = application_name_entry.clone();
             let process_name_entry_clone = process_name_entry.clone();
+            let exclusive_apps = self.exclusive_apps.clone();
 
-            let apply_button_clone = apply_button.clone();
             let disable_exclusive_button_inner = disable_exclusive_button.clone();
 
-            apply_button.connect_clicked(move |_| {
-                let mode = config_mode_combo
-                    .active_id()
-                    .map(|id| id.to_string())
-                    .unwrap_or_else(|| "global".to_string());
+            let tab = self.clone();
+
+            // The rest of the apply, on the UI thread once the device checks are back
+            let continue_apply = Rc::new(
+                move |mode: String, rate_device_id: String, checks: DeviceChecks| {
+                    // The device may have been unplugged since the list was loaded
+                    if let Some(message) = checks.missing_device {
+                        status_label.set_text("Selected device is no longer available");
+                        show_error_dialog(&message);
+                        clear_cache();
+                        tab.detect_advanced_devices();
+                        return;
+                    }
 
-                match mode.as_str() {
-                    "global" => {
-                        status_label.set_text("Applying advanced global system settings...");
-                        apply_button_clone.set_sensitive(false);
+                    if let Some(message) = checks.unsupported_rates
+                        && !confirm_unsupported_rates(&message)
+                    {
+                        status_label
+                            .set_text("Apply cancelled, sample rate not supported by the device");
+                        return;
+                    }
 
-                        let device_id = device_combo
-                            .active_id()
-                            .map(|id| id.to_string())
-                            .unwrap_or_else(|| "default".to_string());
+                    // Both global and exclusive applies restart services system-wide;
+                    // the JACK config is read by the JACK server, not by PipeWire
+                    if mode != "jack" && !confirm_service_restart(true) {
+                        status_label.set_text("Apply cancelled, audio services were not restarted");
+                        return;
+                    }
 
-                        let settings = AudioSettings {
-                            sample_rate: sample_rate_combo
-                                .active_id()
-                                .and_then(|id| id.parse::<u32>().ok())
-                                .unwrap_or(48000),
-                            bit_depth: bit_depth_combo
-                                .active_id()
-                                .and_then(|id| id.parse::<u32>().ok())
-                                .unwrap_or(24),
-                            buffer_size: buffer_size_combo
-                                .active_id()
-                                .and_then(|id| id.parse::<u32>().ok())
-                                .unwrap_or(512),
-                            device_id,
-                        };
+                    match mode.as_str() {
+                        "global" => {
+                            let settings = match tab.global_settings() {
+                                Ok(settings) => settings,
+                                Err(e) => {
+                                    show_error_dialog(&format!("Invalid channel positions: {}", e));
+                                    return;
+                                }
+                            };
+                            let options = tab.professional_options();
 
-                        let min_buffer = min_buffer_combo
-                            .active_id()
-                            .and_then(|id| id.parse::<u32>().ok())
-                            .unwrap_or(128);
-                        let max_buffer = max_buffer_combo
-                            .active_id()
-                            .and_then(|id| id.parse::<u32>().ok())
-                            .unwrap_or(2048);
-                        let thread_priority = thread_priority_combo
-                            .active_id()
-                            .map(|id| id.to_string())
-                            .unwrap_or_else(|| "high".to_string());
-                        let memory_lock = memory_lock_checkbox.is_active();
-                        let prevent_suspend = prevent_suspend_checkbox.is_active();
-                        let disable_remixing = disable_remixing_checkbox.is_active();
-                        let disable_resampling = disable_resampling_checkbox.is_active();
-                        let resampler_quality = resampler_combo
-                            .active_id()
-                            .map(|id| id.to_string())
-                            .unwrap_or_else(|| "high".to_string());
-                        let clock_source = clock_source_combo
-                            .active_id()
-                            .map(|id| id.to_string())
-                            .unwrap_or_else(|| "monotonic".to_string());
-
-                        let status_label_clone = status_label.clone();
-                        let apply_button_clone_inner = apply_button_clone.clone();
-
-                        let (tx, rx) = mpsc::channel();
-                        let rx_arc = Arc::new(Mutex::new(rx));
-
-                        std::thread::spawn(move || {
-                            let result = crate::config::apply_advanced_professional_settings(
-                                &settings,
-                                true,
-                                min_buffer,
-                                max_buffer,
-                                &thread_priority,
-                                memory_lock,
-                                prevent_suspend,
-                                disable_remixing,
-                                disable_resampling,
-                                &resampler_quality,
-                                &clock_source,
-                            );
-                            let _ = tx.send(result);
-                        });
-
-                        let rx_timeout = Arc::clone(&rx_arc);
-                        glib::timeout_add_local(Duration::from_millis(100), move || {
-                            let rx_guard = rx_timeout.lock().unwrap();
-                            match rx_guard.try_recv() {
-                                Ok(result) => {
-                                    match result {
-                                        Ok(()) => {
-                                            status_label_clone
-                                                .set_text("Global settings applied successfully!");
-                                            apply_button_clone_inner.set_sensitive(true);
-                                            show_success_dialog(
-                                                "Global audio settings applied successfully.",
-                                            );
-                                        }
-                                        Err(e) => {
-                                            status_label_clone
-                                                .set_text("Failed to apply advanced settings");
-                                            apply_button_clone_inner.set_sensitive(true);
-                                            show_error_dialog(&format!(
-                                                "Failed to apply advanced settings: {}",
-                                                e
-                                            ));
+                            // Includes the rewrite of the main pipewire.conf
+                            match diff_pending_professional_config(&settings, true, &options) {
+                                Ok(diffs)
+                                    if !diffs.is_empty() && !confirm_config_changes(&diffs) =>
+                                {
+                                    status_label.set_text("Apply cancelled, no files were changed");
+                                    return;
+                                }
+                                Ok(_) => {}
+                                Err(e) => log::warn!("Could not compare the pending config: {}", e),
+                            }
+
+                            if !begin_apply() {
+                                status_label.set_text(APPLY_BUSY_TEXT);
+                                return;
+                            }
+                            status_label.set_text("Applying advanced global system settings...");
+
+                            let status_label_clone = status_label.clone();
+
+                            let (tx, rx) = mpsc::channel();
+                            let rx_arc = Arc::new(Mutex::new(rx));
+
+                            std::thread::spawn(move || {
+                                let result =
+                                    apply_advanced_professional_settings(&settings, true, &options);
+                                let _ = tx.send(result);
+                            });
+
+                            let rx_timeout = Arc::clone(&rx_arc);
+                            add_ui_timeout(Duration::from_millis(100), move || {
+                                let rx_guard = rx_timeout.lock().unwrap();
+                                match rx_guard.try_recv() {
+                                    Ok(result) => {
+                                        match result {
+                                            Ok(()) => {
+                                                status_label_clone.set_text(
+                                                    "Global settings applied successfully!",
+                                                );
+                                                end_apply();
+                                                show_success_dialog(
+                                                    "Global audio settings applied successfully.",
+                                                );
+                                            }
+                                            Err(e) => {
+                                                if matches!(
+                                                    e,
+                                                    AudioConfigError::AuthorizationCancelled
+                                                ) {
+                                                    status_label_clone.set_text(&e.to_string());
+                                                } else {
+                                                    status_label_clone.set_text(
+                                                        "Failed to apply advanced settings",
+                                                    );
+                                                }
+                                                end_apply();
+                                                show_config_error_dialog(&e);
+                                            }
                                         }
+                                        ControlFlow::Break
+                                    }
+                                    Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+                                    Err(mpsc::TryRecvError::Disconnected) => {
+                                        status_label_clone.set_text("Unexpected error");
+                                        end_apply();
+                                        show_error_dialog("Unexpected error occurred");
+                                        ControlFlow::Break
                                     }
-                                    ControlFlow::Break
                                 }
-                                Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
-                                Err(mpsc::TryRecvError::Disconnected) => {
-                                    status_label_clone.set_text("Unexpected error");
-                                    apply_button_clone_inner.set_sensitive(true);
-                                    show_error_dialog("Unexpected error occurred");
-                                    ControlFlow::Break
+                            });
+                        }
+                        "exclusive" => {
+                            let device_pattern = exclusive_device_combo
+                                .active_id()
+                                .map(|id| id.to_string())
+                                .unwrap_or_else(|| "default".to_string());
+
+                            // The pro-audio profile gives the app every channel without UCM mixing
+                            let mut profile_switch = None;
+                            if let Some(card) = checks.card_profiles
+                                && card.pending_pro_audio().is_some()
+                                && let Some(active) = card.active.as_deref()
+                            {
+                                let current = card
+                                    .find(active)
+                                    .map(|profile| profile.description.as_str())
+                                    .unwrap_or(active);
+                                if confirm_pro_audio_profile(&card.device_description, current) {
+                                    profile_switch = Some(ProfileRevert {
+                                        device_name: card.device_name.clone(),
+                                        profile_name: active.to_string(),
+                                    });
                                 }
                             }
-                        });
-                    }
-                    "exclusive" => {
-                        status_label.set_text("Applying exclusive mode settings...");
-                        apply_button_clone.set_sensitive(false);
 
-                        let device_pattern = exclusive_device_combo
-                            .active_id()
-                            .map(|id| id.to_string())
-                            .unwrap_or_else(|| "default".to_string());
+                            if !begin_apply() {
+                                status_label.set_text(APPLY_BUSY_TEXT);
+                                return;
+                            }
+                            status_label.set_text("Applying exclusive mode settings...");
+
+                            if let Some(capabilities) = checks.capabilities {
+                                let buffer_size = exclusive_buffer_size_combo
+                                    .active_id()
+                                    .and_then(|id| id.parse::<u32>().ok())
+                                    .unwrap_or(128);
+
+                                if !capabilities.buffer_sizes.contains(&buffer_size) {
+                                    status_label.set_text(
+                                        "Warning: Buffer size may not be optimal for this device",
+                                    );
+                                }
+                            }
 
-                        if let Ok(capabilities) = get_device_capabilities(&device_pattern) {
                             let buffer_size = exclusive_buffer_size_combo
                                 .active_id()
                                 .and_then(|id| id.parse::<u32>().ok())
@@ -869,106 +1767,736 @@ impl AdvancedTab {
                                 .active_id()
                                 .and_then(|id| id.parse::<u32>().ok())
                                 .unwrap_or(48000);
+                            let channel_positions = default_channel_positions(
+                                exclusive_channels_combo
+                                    .active_id()
+                                    .and_then(|id| id.parse::<u32>().ok())
+                                    .unwrap_or(2),
+                            );
 
-                            if !capabilities.buffer_sizes.contains(&buffer_size) {
-                                status_label.set_text(
-                                    "Warning: Buffer size may not be optimal for this device",
-                                );
+                            // Entries that were typed but never added still count
+                            let mut apps = exclusive_apps.lock().unwrap().clone();
+                            if let Some(app) = exclusive_app_from_entries(
+                                &application_name_entry_clone,
+                                &process_name_entry_clone,
+                            ) && !apps.contains(&app)
+                            {
+                                apps.push(app);
                             }
-                            if !capabilities.sample_rates.contains(&sample_rate) {
-                                status_label.set_text(
-                                    "Warning: Sample rate may not be supported by this device",
+
+                            let status_label_clone = status_label.clone();
+                            let disable_exclusive_button_local =
+                                disable_exclusive_button_inner.clone();
+
+                            let (tx, rx) = mpsc::channel();
+                            let rx_arc = Arc::new(Mutex::new(rx));
+
+                            std::thread::spawn(move || {
+                                let mut device_pattern = device_pattern;
+                                if let Some(revert) = profile_switch {
+                                    // The old node disappears with the profile switch
+                                    match set_card_profile(&revert.device_name, PRO_AUDIO_PROFILE)
+                                        .and_then(|_| wait_for_card_output(&revert.device_name))
+                                    {
+                                        Ok(device_id) => {
+                                            device_pattern = device_id;
+                                            if let Err(e) = AppPreferences::update(|prefs| {
+                                                prefs.exclusive_profile_revert = Some(revert)
+                                            }) {
+                                                log::warn!(
+                                                    "Could not remember the previous profile: {}",
+                                                    e
+                                                );
+                                            }
+                                        }
+                                        Err(e) => {
+                                            let _ = tx.send(Err(format!(
+                                                "Could not switch to the Pro Audio profile: {}",
+                                                e
+                                            )
+                                            .into()));
+                                            return;
+                                        }
+                                    }
+                                }
+
+                                let result = apply_advanced_audio_settings(
+                                    true,
+                                    true,
+                                    true,
+                                    buffer_size,
+                                    sample_rate,
+                                    channel_positions,
+                                    Some(device_pattern),
+                                    apps,
                                 );
+                                let _ = tx.send(result);
+                            });
+
+                            let rx_timeout = Arc::clone(&rx_arc);
+                            add_ui_timeout(Duration::from_millis(100), move || {
+                                let rx_guard = rx_timeout.lock().unwrap();
+                                match rx_guard.try_recv() {
+                                    Ok(result) => {
+                                        match result {
+                                            Ok(()) => {
+                                                status_label_clone.set_text(
+                                                    "Exclusive mode settings applied successfully!",
+                                                );
+                                                end_apply();
+                                                disable_exclusive_button_local.set_sensitive(true);
+                                                show_success_dialog(
+                                                    "Exclusive mode settings applied successfully.",
+                                                );
+                                            }
+                                            Err(e) => {
+                                                status_label_clone.set_text(
+                                                    "Failed to apply exclusive mode settings",
+                                                );
+                                                end_apply();
+                                                show_config_error_dialog(&e);
+                                            }
+                                        }
+                                        ControlFlow::Break
+                                    }
+                                    Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+                                    Err(mpsc::TryRecvError::Disconnected) => {
+                                        status_label_clone.set_text("Unexpected error");
+                                        end_apply();
+                                        show_error_dialog("Unexpected error occurred");
+                                        ControlFlow::Break
+                                    }
+                                }
+                            });
+                        }
+                        "jack" => {
+                            if !begin_apply() {
+                                status_label.set_text(APPLY_BUSY_TEXT);
+                                return;
                             }
+                            status_label.set_text("Applying JACK settings...");
+
+                            let settings = AudioSettings::new(
+                                sample_rate_combo
+                                    .active_id()
+                                    .and_then(|id| id.parse::<u32>().ok())
+                                    .unwrap_or(48000),
+                                bit_depth_combo
+                                    .active_id()
+                                    .and_then(|id| id.parse::<u32>().ok())
+                                    .unwrap_or(24),
+                                buffer_size_combo
+                                    .active_id()
+                                    .and_then(|id| id.parse::<u32>().ok())
+                                    .unwrap_or(512),
+                                rate_device_id.clone(),
+                            );
+                            let periods = jack_periods_combo
+                                .active_id()
+                                .and_then(|id| id.parse::<u32>().ok())
+                                .unwrap_or(DEFAULT_JACK_PERIODS);
+                            let driver = jack_driver_combo
+                                .active_id()
+                                .map(|id| id.to_string())
+                                .unwrap_or_else(|| "alsa".to_string());
+
+                            let status_label_clone = status_label.clone();
+
+                            let (tx, rx) = mpsc::channel();
+                            let rx_arc = Arc::new(Mutex::new(rx));
+
+                            std::thread::spawn(move || {
+                                let _ = tx.send(apply_jack_settings(&settings, periods, &driver));
+                            });
+
+                            let rx_timeout = Arc::clone(&rx_arc);
+                            add_ui_timeout(Duration::from_millis(100), move || {
+                                let rx_guard = rx_timeout.lock().unwrap();
+                                match rx_guard.try_recv() {
+                                    Ok(Ok(outcome)) => {
+                                        end_apply();
+                                        let message = format!(
+                                            "JACK settings written for {}: {}",
+                                            outcome.backend.label(),
+                                            outcome.config_path
+                                        );
+                                        status_label_clone.set_text(&message);
+                                        if outcome.warnings.is_empty() {
+                                            show_success_dialog(&message);
+                                        } else {
+                                            show_success_dialog(&format!(
+                                                "{}\n\n{}",
+                                                message,
+                                                outcome.warnings.join("\n")
+                                            ));
+                                        }
+                                        ControlFlow::Break
+                                    }
+                                    Ok(Err(e)) => {
+                                        end_apply();
+                                        status_label_clone
+                                            .set_text("Failed to apply JACK settings");
+                                        show_error_dialog(&format!(
+                                            "Failed to apply JACK settings: {}",
+                                            e
+                                        ));
+                                        ControlFlow::Break
+                                    }
+                                    Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+                                    Err(mpsc::TryRecvError::Disconnected) => {
+                                        status_label_clone.set_text("Unexpected error");
+                                        end_apply();
+                                        show_error_dialog("Unexpected error occurred");
+                                        ControlFlow::Break
+                                    }
+                                }
+                            });
                         }
+                        _ => {}
+                    }
+                },
+            );
+
+            let tab = self.clone();
+            self.apply_button.connect_clicked(move |apply_button| {
+                let mode = tab
+                    .config_mode_combo
+                    .active_id()
+                    .map(|id| id.to_string())
+                    .unwrap_or_else(|| "global".to_string());
 
-                        let buffer_size = exclusive_buffer_size_combo
+                let selected_device_combo = if mode == "exclusive" {
+                    &tab.exclusive_device_combo
+                } else {
+                    &tab.device_combo
+                };
+
+                // PipeWire silently keeps another rate when the device can't do these
+                let rates = if mode == "exclusive" {
+                    vec![
+                        tab.exclusive_sample_rate_combo
                             .active_id()
                             .and_then(|id| id.parse::<u32>().ok())
-                            .unwrap_or(128);
-                        let sample_rate = exclusive_sample_rate_combo
+                            .unwrap_or(48000),
+                    ]
+                } else {
+                    let mut rates = selected_allowed_rates(&tab.allowed_rate_checkboxes);
+                    rates.push(
+                        tab.sample_rate_combo
                             .active_id()
                             .and_then(|id| id.parse::<u32>().ok())
-                            .unwrap_or(48000);
-
-                        let app_name = {
-                            let text = application_name_entry_clone.text();
-                            if text.is_empty() {
-                                None
-                            } else {
-                                Some(text.to_string())
-                            }
+                            .unwrap_or(48000),
+                    );
+                    rates.sort_unstable();
+                    rates.dedup();
+                    rates
+                };
+                let rate_device_id = selected_device_combo
+                    .active_id()
+                    .map(|id| id.to_string())
+                    .unwrap_or_else(|| "default".to_string());
+
+                // The checks run pw-cli/pw-dump, keep the UI responsive
+                tab.status_label.set_text("Checking the selected device...");
+                apply_button.set_sensitive(false);
+
+                let (tx, rx) = mpsc::channel();
+                let rx_arc = Arc::new(Mutex::new(rx));
+
+                let selected_device_id = selected_device_combo.active_id().map(|id| id.to_string());
+                let check_device_id = rate_device_id.clone();
+                let check_mode = mode.clone();
+                std::thread::spawn(move || {
+                    let missing_device = selected_device_id
+                        .as_deref()
+                        .and_then(missing_device_message);
+                    // Nothing to check the rates against once the device is gone
+                    let unsupported_rates = if missing_device.is_none() {
+                        unsupported_rate_message(&check_device_id, &rates)
+                    } else {
+                        None
+                    };
+                    // Only exclusive mode offers the profile switch and checks the buffer
+                    let (card_profiles, capabilities) =
+                        if check_mode == "exclusive" && missing_device.is_none() {
+                            (
+                                detect_card_profiles(&check_device_id).ok(),
+                                get_device_capabilities(&check_device_id).ok(),
+                            )
+                        } else {
+                            (None, None)
                         };
-                        let app_process_name = {
-                            let text = process_name_entry_clone.text();
-                            if text.is_empty() {
-                                None
-                            } else {
-                                Some(text.to_string())
+                    let _ = tx.send(DeviceChecks {
+                        missing_device,
+                        unsupported_rates,
+                        card_profiles,
+                        capabilities,
+                    });
+                });
+
+                let apply_button = apply_button.clone();
+                let continue_apply = Rc::clone(&continue_apply);
+                let rx_timeout = Arc::clone(&rx_arc);
+                add_ui_timeout(Duration::from_millis(100), move || {
+                    let rx_guard = rx_timeout.lock().unwrap();
+                    match rx_guard.try_recv() {
+                        Ok(checks) => {
+                            apply_button.set_sensitive(true);
+                            continue_apply(mode.clone(), rate_device_id.clone(), checks);
+                            ControlFlow::Break
+                        }
+                        Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+                        Err(mpsc::TryRecvError::Disconnected) => {
+                            apply_button.set_sensitive(true);
+                            ControlFlow::Break
+                        }
+                    }
+                });
+            });
+        }
+
+        // Warn about missing realtime privileges when high priority is requested.
+        // The check runs id/pgrep, so it runs once off the UI thread and is cached.
+        {
+            let privileges: Rc<RefCell<Option<RealtimePrivileges>>> = Rc::new(RefCell::new(None));
+            let warn_if_unprivileged = {
+                let status_label = status_label.clone();
+                let privileges = Rc::clone(&privileges);
+                Rc::new(move |priority: &str| {
+                    if priority == "normal" {
+                        return;
+                    }
+                    if let Some(warning) = privileges
+                        .borrow()
+                        .as_ref()
+                        .and_then(RealtimePrivileges::warning)
+                    {
+                        log::info!("{}", warning);
+                        status_label.set_text(&warning);
+                    }
+                })
+            };
+
+            let (tx, rx) = mpsc::channel();
+            std::thread::spawn(move || {
+                let _ = tx.send(check_realtime_privileges());
+            });
+
+            let priority_combo = self.thread_priority_combo.clone();
+            let warn_on_result = Rc::clone(&warn_if_unprivileged);
+            add_ui_timeout(Duration::from_millis(100), move || match rx.try_recv() {
+                Ok(result) => {
+                    *privileges.borrow_mut() = Some(result);
+                    if let Some(priority) = priority_combo.active_id() {
+                        warn_on_result(&priority);
+                    }
+                    ControlFlow::Break
+                }
+                Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+                Err(mpsc::TryRecvError::Disconnected) => ControlFlow::Break,
+            });
+
+            self.thread_priority_combo.connect_changed(move |combo| {
+                if let Some(priority) = combo.active_id() {
+                    warn_if_unprivileged(&priority);
+                }
+            });
+        }
+
+        // The hard limit can't be below the soft one; keep them ordered and store both
+        {
+            let soft_combo = self.rt_time_soft_combo.clone();
+            let hard_combo = self.rt_time_hard_combo.clone();
+            let status_label = self.status_label.clone();
+            let on_rt_time_changed = move |changed_soft: bool| {
+                let value =
+                    |combo: &ComboBoxText| combo.active_id().and_then(|id| id.parse::<u32>().ok());
+                let (Some(soft), Some(hard)) = (value(&soft_combo), value(&hard_combo)) else {
+                    return;
+                };
+                if soft > hard {
+                    // Setting the other combo re-enters this handler with an ordered pair
+                    if changed_soft {
+                        hard_combo.set_active_id(Some(&soft.to_string()));
+                    } else {
+                        soft_combo.set_active_id(Some(&hard.to_string()));
+                    }
+                    return;
+                }
+
+                if let Err(e) = set_rt_time_limits(soft, hard) {
+                    status_label.set_text(&e);
+                    return;
+                }
+                if let Err(e) = AppPreferences::update(|prefs| {
+                    prefs.rt_time_soft_us = Some(soft);
+                    prefs.rt_time_hard_us = Some(hard);
+                }) {
+                    log::warn!("Failed to save preferences: {}", e);
+                }
+            };
+
+            let on_soft_changed = on_rt_time_changed.clone();
+            self.rt_time_soft_combo
+                .connect_changed(move |_| on_soft_changed(true));
+            self.rt_time_hard_combo
+                .connect_changed(move |_| on_rt_time_changed(false));
+        }
+
+        // Import values from an existing config fragment
+        {
+            let tab = self.clone();
+
+            self.import_button.connect_clicked(move |_| {
+                let dialog = gtk::FileChooserDialog::with_buttons(
+                    Some("Import PipeWire Config"),
+                    None::<&gtk::Window>,
+                    gtk::FileChooserAction::Open,
+                    &[
+                        ("Cancel", gtk::ResponseType::Cancel),
+                        ("Import", gtk::ResponseType::Accept),
+                    ],
+                );
+                let _ = dialog
+                    .set_current_folder(format!("{}/pipewire/pipewire.conf.d", user_config_dir()));
+
+                let tab = tab.clone();
+                dialog.connect_response(move |dialog, response| {
+                    if response == gtk::ResponseType::Accept
+                        && let Some(path) = dialog.filename()
+                    {
+                        match parse_existing_config(&path) {
+                            Ok(imported) => {
+                                let unmatched = tab.apply_imported_settings(&imported);
+                                if unmatched.is_empty() {
+                                    tab.status_label.set_text(&format!(
+                                        "Imported settings from {}",
+                                        path.display()
+                                    ));
+                                } else {
+                                    tab.status_label.set_text(&format!(
+                                        "Imported settings from {} with warnings:\n{}",
+                                        path.display(),
+                                        unmatched.join("\n")
+                                    ));
+                                }
                             }
-                        };
+                            Err(e) => show_error_dialog(&e),
+                        }
+                    }
+                    dialog.close();
+                });
 
-                        let status_label_clone = status_label.clone();
-                        let apply_button_clone_inner = apply_button_clone.clone();
-                        let disable_exclusive_button_local = disable_exclusive_button_inner.clone();
-
-                        let (tx, rx) = mpsc::channel();
-                        let rx_arc = Arc::new(Mutex::new(rx));
-
-                        std::thread::spawn(move || {
-                            let result = apply_advanced_audio_settings(
-                                true,
-                                true,
-                                true,
-                                buffer_size,
-                                sample_rate,
-                                Some(device_pattern),
-                                app_name,
-                                app_process_name,
-                            );
-                            let _ = tx.send(result);
-                        });
-
-                        let rx_timeout = Arc::clone(&rx_arc);
-                        glib::timeout_add_local(Duration::from_millis(100), move || {
-                            let rx_guard = rx_timeout.lock().unwrap();
-                            match rx_guard.try_recv() {
-                                Ok(result) => {
-                                    match result {
-                                        Ok(()) => {
-                                            status_label_clone.set_text(
-                                                "Exclusive mode settings applied successfully!",
-                                            );
-                                            apply_button_clone_inner.set_sensitive(true);
-                                            disable_exclusive_button_local.set_sensitive(true);
-                                            show_success_dialog(
-                                                "Exclusive mode settings applied successfully.",
-                                            );
-                                        }
-                                        Err(e) => {
-                                            status_label_clone.set_text(
-                                                "Failed to apply exclusive mode settings",
-                                            );
-                                            apply_button_clone_inner.set_sensitive(true);
-                                            show_error_dialog(&format!(
-                                                "Failed to apply exclusive mode settings: {}",
-                                                e
-                                            ));
-                                        }
+                dialog.show_all();
+            });
+        }
+
+        // Live risk assessment of the professional settings
+        {
+            let risk_label = self.risk_label.clone();
+            let thread_priority_combo = self.thread_priority_combo.clone();
+            let clock_source_combo = self.clock_source_combo.clone();
+            let min_buffer_combo = self.min_buffer_combo.clone();
+            let buffer_size_combo = buffer_size_combo.clone();
+            let memory_lock_checkbox = self.memory_lock_checkbox.clone();
+            let disable_resampling_checkbox = self.disable_resampling_checkbox.clone();
+
+            let refresh_risk = Rc::new(move || {
+                let thread_priority = thread_priority_combo
+                    .active_id()
+                    .map(|id| id.to_string())
+                    .unwrap_or_default();
+                let clock_source = clock_source_combo
+                    .active_id()
+                    .map(|id| id.to_string())
+                    .unwrap_or_default();
+                let snapshot = ProSettingsSnapshot {
+                    thread_priority: &thread_priority,
+                    clock_source: &clock_source,
+                    min_buffer: min_buffer_combo
+                        .active_id()
+                        .and_then(|id| id.parse::<u32>().ok())
+                        .unwrap_or(128),
+                    buffer_size: buffer_size_combo
+                        .active_id()
+                        .and_then(|id| id.parse::<u32>().ok())
+                        .unwrap_or(512),
+                    memory_lock: memory_lock_checkbox.is_active(),
+                    disable_resampling: disable_resampling_checkbox.is_active(),
+                };
+
+                let (level, reasons) = Self::assess_professional_risk(&snapshot);
+                Self::update_risk_label(&risk_label, level, &reasons);
+            });
+
+            refresh_risk();
+
+            for combo in [
+                &self.thread_priority_combo,
+                &self.clock_source_combo,
+                &self.min_buffer_combo,
+                &self.buffer_size_combo,
+            ] {
+                let refresh_risk = Rc::clone(&refresh_risk);
+                combo.connect_changed(move |_| refresh_risk());
+            }
+
+            for checkbox in [
+                &self.memory_lock_checkbox,
+                &self.disable_resampling_checkbox,
+            ] {
+                let refresh_risk = Rc::clone(&refresh_risk);
+                checkbox.connect_toggled(move |_| refresh_risk());
+            }
+        }
+
+        // Apply to both input and output
+        {
+            let status_label = status_label.clone();
+            let sample_rate_combo = sample_rate_combo.clone();
+            let allowed_rate_checkboxes = self.allowed_rate_checkboxes.clone();
+            let bit_depth_combo = bit_depth_combo.clone();
+            let buffer_size_combo = buffer_size_combo.clone();
+            let device_combo = device_combo.clone();
+            let channels_combo = self.channels_combo.clone();
+            let channel_positions_entry = self.channel_positions_entry.clone();
+
+            self.apply_both_button.connect_clicked(move |_| {
+                let (channels, position) =
+                    match selected_channel_layout(&channels_combo, &channel_positions_entry) {
+                        Ok(layout) => layout,
+                        Err(e) => {
+                            show_error_dialog(&format!("Invalid channel positions: {}", e));
+                            return;
+                        }
+                    };
+
+                if !confirm_service_restart(true) {
+                    status_label.set_text("Apply cancelled, audio services were not restarted");
+                    return;
+                }
+
+                if !begin_apply() {
+                    status_label.set_text(APPLY_BUSY_TEXT);
+                    return;
+                }
+                status_label.set_text("Applying settings to input and output...");
+
+                let device_id = device_combo
+                    .active_id()
+                    .map(|id| id.to_string())
+                    .unwrap_or_else(|| "default".to_string());
+
+                let settings = AudioSettings {
+                    sample_rate: sample_rate_combo
+                        .active_id()
+                        .and_then(|id| id.parse::<u32>().ok())
+                        .unwrap_or(48000),
+                    bit_depth: bit_depth_combo
+                        .active_id()
+                        .and_then(|id| id.parse::<u32>().ok())
+                        .unwrap_or(24),
+                    buffer_size: buffer_size_combo
+                        .active_id()
+                        .and_then(|id| id.parse::<u32>().ok())
+                        .unwrap_or(512),
+                    device_id,
+                    allowed_rates: selected_allowed_rates(&allowed_rate_checkboxes),
+                    node_description: None,
+                    period_size: None,
+                    channels,
+                    position,
+                };
+
+                let status_label_clone = status_label.clone();
+
+                let (tx, rx) = mpsc::channel();
+                let rx_arc = Arc::new(Mutex::new(rx));
+
+                std::thread::spawn(move || {
+                    let result = apply_duplex_audio_settings(&settings, true);
+
+                    if result.is_ok()
+                        && let Err(e) = AppPreferences::update(|prefs| {
+                            prefs.set_last_applied(&TabType::Output, &settings);
+                            prefs.set_last_applied(&TabType::Input, &settings);
+                        })
+                    {
+                        log::warn!("Failed to save last applied settings: {}", e);
+                    }
+
+                    let _ = tx.send(result);
+                });
+
+                let rx_timeout = Arc::clone(&rx_arc);
+                add_ui_timeout(Duration::from_millis(100), move || {
+                    let rx_guard = rx_timeout.lock().unwrap();
+                    match rx_guard.try_recv() {
+                        Ok(result) => {
+                            end_apply();
+                            match result {
+                                Ok(outcome) => {
+                                    status_label_clone.set_text(&format!(
+                                        "Input and output: {}",
+                                        outcome.summary()
+                                    ));
+                                    if outcome.verified && outcome.warnings.is_empty() {
+                                        show_success_dialog(
+                                            "Input and output settings applied and confirmed.",
+                                        );
+                                    } else if outcome.verified {
+                                        show_success_dialog(&format!(
+                                            "Input and output settings applied and confirmed.\n\nNote:\n{}",
+                                            outcome.warnings.join("\n")
+                                        ));
+                                    } else {
+                                        show_success_dialog(&format!(
+                                            "Input and output settings were written, but the running audio system does not match yet:\n\n{}",
+                                            outcome.warnings.join("\n")
+                                        ));
                                     }
-                                    ControlFlow::Break
                                 }
-                                Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
-                                Err(mpsc::TryRecvError::Disconnected) => {
-                                    status_label_clone.set_text("Unexpected error");
-                                    apply_button_clone_inner.set_sensitive(true);
-                                    show_error_dialog("Unexpected error occurred");
-                                    ControlFlow::Break
+                                Err(e) => {
+                                    if matches!(e, AudioConfigError::AuthorizationCancelled) {
+                                        status_label_clone.set_text(&e.to_string());
+                                    } else {
+                                        status_label_clone
+                                            .set_text("Failed to apply input and output settings");
+                                    }
+                                    show_config_error_dialog(&e);
                                 }
                             }
-                        });
+                            ControlFlow::Break
+                        }
+                        Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+                        Err(mpsc::TryRecvError::Disconnected) => {
+                            status_label_clone.set_text("Unexpected error");
+                            end_apply();
+                            show_error_dialog("Unexpected error occurred");
+                            ControlFlow::Break
+                        }
+                    }
+                });
+            });
+        }
+
+        // Preview of the global apply
+        {
+            let tab = self.clone();
+            self.preview_button.connect_clicked(move |button| {
+                let settings = match tab.global_settings() {
+                    Ok(settings) => settings,
+                    Err(e) => {
+                        show_error_dialog(&format!("Invalid channel positions: {}", e));
+                        return;
+                    }
+                };
+                let options = tab.professional_options();
+                button.set_sensitive(false);
+
+                let (tx, rx) = mpsc::channel();
+                let rx_arc = Arc::new(Mutex::new(rx));
+
+                // The device rule reads the formats of the device from pw-dump
+                std::thread::spawn(move || {
+                    let result = preview_advanced_professional_settings(&settings, true, &options)
+                        .map(|files| (files, plan_quantum_verified_restart(true)));
+                    let _ = tx.send(result);
+                });
+
+                let button = button.clone();
+                let rx_timeout = Arc::clone(&rx_arc);
+                add_ui_timeout(Duration::from_millis(100), move || {
+                    let rx_guard = rx_timeout.lock().unwrap();
+                    match rx_guard.try_recv() {
+                        Ok(result) => {
+                            button.set_sensitive(true);
+                            match result {
+                                Ok((files, restart_plan)) => {
+                                    let parent = button
+                                        .toplevel()
+                                        .and_then(|widget| widget.downcast::<gtk::Window>().ok());
+                                    show_config_preview_dialog(
+                                        parent.as_ref(),
+                                        &files,
+                                        &restart_plan,
+                                    );
+                                }
+                                Err(e) => show_error_dialog(&format!(
+                                    "Cannot preview these settings: {}",
+                                    e
+                                )),
+                            }
+                            ControlFlow::Break
+                        }
+                        Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+                        Err(mpsc::TryRecvError::Disconnected) => {
+                            button.set_sensitive(true);
+                            ControlFlow::Break
+                        }
+                    }
+                });
+            });
+        }
+
+        // Exclusive application list
+        {
+            let application_name_entry = application_name_entry.clone();
+            let process_name_entry = process_name_entry.clone();
+            let exclusive_app_list = self.exclusive_app_list.clone();
+            let exclusive_apps = self.exclusive_apps.clone();
+            let status_label = self.status_label.clone();
+
+            self.add_exclusive_app_button.connect_clicked(move |_| {
+                let Some(mut app) =
+                    exclusive_app_from_entries(&application_name_entry, &process_name_entry)
+                else {
+                    return;
+                };
+
+                // A rule on a binary name PipeWire never reports silently never matches
+                if !app.process_name.is_empty() {
+                    match check_process_binary(&app.process_name, &app.name) {
+                        Ok(ProcessBinaryCheck::Matches) => {}
+                        Ok(ProcessBinaryCheck::Suggestion(binary)) => {
+                            if confirm_process_name_suggestion(&app.process_name, &binary) {
+                                app.process_name = binary;
+                            }
+                        }
+                        Ok(ProcessBinaryCheck::NotRunning) => {
+                            status_label.set_text(&format!(
+                                "Warning: No running app reports the process name '{}'. \
+                                 Start the app and add it again to check the name.",
+                                app.process_name
+                            ));
+                        }
+                        Err(e) => log::warn!("Could not check the process name: {}", e),
+                    }
+                }
+
+                let mut apps = exclusive_apps.lock().unwrap();
+                if !apps.contains(&app) {
+                    apps.push(app);
+                    refresh_exclusive_app_list(&exclusive_app_list, &apps);
+                }
+                application_name_entry.set_text("");
+                process_name_entry.set_text("");
+            });
+        }
+
+        {
+            let exclusive_app_list = self.exclusive_app_list.clone();
+            let exclusive_apps = self.exclusive_apps.clone();
+
+            self.remove_exclusive_app_button.connect_clicked(move |_| {
+                if let Some(row) = exclusive_app_list.selected_row()
+                    && let Ok(index) = usize::try_from(row.index())
+                {
+                    let mut apps = exclusive_apps.lock().unwrap();
+                    if index < apps.len() {
+                        apps.remove(index);
+                        refresh_exclusive_app_list(&exclusive_app_list, &apps);
                     }
-                    _ => {}
                 }
             });
         }
@@ -977,22 +2505,129 @@ impl AdvancedTab {
         {
             let disable_exclusive_button_clone = disable_exclusive_button.clone();
             disable_exclusive_button.connect_clicked(move |_| {
-                match crate::config::restore_standard_audio_mode() {
+                if !confirm_service_restart(true) {
+                    return;
+                }
+                if !begin_apply() {
+                    show_error_dialog(APPLY_BUSY_TEXT);
+                    return;
+                }
+
+                let result = crate::config::restore_standard_audio_mode();
+                let profile_result = result.as_ref().ok().map(|_| revert_exclusive_profile());
+                end_apply();
+
+                match result {
                     Ok(_) => {
-                        show_success_dialog(
-                            "Exclusive mode disabled. Standard shared audio mode restored.",
-                        );
+                        if let Some(Err(e)) = profile_result {
+                            show_error_dialog(&format!(
+                                "Exclusive mode disabled, but the card profile could not be restored: {}",
+                                e
+                            ));
+                        } else {
+                            show_success_dialog(
+                                "Exclusive mode disabled. Standard shared audio mode restored.",
+                            );
+                        }
                         disable_exclusive_button_clone.set_sensitive(false);
                     }
-                    Err(e) => {
-                        show_error_dialog(&format!("Failed to disable exclusive mode: {}", e));
-                    }
+                    Err(e) => show_config_error_dialog(&e),
                 }
             });
         }
     }
 }
 
+/// Puts the card back on the profile it had before exclusive mode switched it
+/// to pro-audio. The revert is kept when this fails, so the next disable retries.
+fn revert_exclusive_profile() -> Result<(), String> {
+    let Some(revert) = AppPreferences::load().exclusive_profile_revert else {
+        return Ok(());
+    };
+
+    set_card_profile(&revert.device_name, &revert.profile_name)?;
+    log::info!(
+        "✓ Restored profile {} on {}",
+        revert.profile_name,
+        revert.device_name
+    );
+    AppPreferences::update(|prefs| prefs.exclusive_profile_revert = None)
+}
+
+/// App typed into the name/process entries; the process name alone is enough
+fn exclusive_app_from_entries(name_entry: &Entry, process_entry: &Entry) -> Option<ExclusiveApp> {
+    let app = ExclusiveApp::new(&name_entry.text(), &process_entry.text());
+    match (app.name.is_empty(), app.process_name.is_empty()) {
+        (true, true) => None,
+        (true, false) => Some(ExclusiveApp::new(&app.process_name, &app.process_name)),
+        _ => Some(app),
+    }
+}
+
+fn refresh_exclusive_app_list(list: &ListBox, apps: &[ExclusiveApp]) {
+    for row in list.children() {
+        list.remove(&row);
+    }
+
+    for app in apps {
+        let label = Label::new(Some(&app.label()));
+        label.set_halign(gtk::Align::Start);
+        list.add(&label);
+    }
+    list.show_all();
+}
+
+fn format_rate_khz(rate: u32) -> String {
+    if rate % 1000 == 0 {
+        format!("{} kHz", rate / 1000)
+    } else {
+        format!("{:.1} kHz", rate as f64 / 1000.0)
+    }
+}
+
+/// Keeps the graph rate allowed (and locked). While nothing but the previous
+/// graph rate was allowed, the allowed rates simply follow the graph rate.
+fn sync_allowed_rates(checkboxes: &[(u32, CheckButton)], graph_rate: u32, previous: Option<u32>) {
+    let untouched = previous.is_some_and(|previous| {
+        checkboxes
+            .iter()
+            .all(|(rate, checkbox)| checkbox.is_active() == (*rate == previous))
+    });
+
+    for (rate, checkbox) in checkboxes {
+        if *rate == graph_rate {
+            checkbox.set_active(true);
+            checkbox.set_sensitive(false);
+        } else {
+            checkbox.set_sensitive(true);
+            if untouched {
+                checkbox.set_active(false);
+            }
+        }
+    }
+}
+
+fn selected_allowed_rates(checkboxes: &[(u32, CheckButton)]) -> Vec<u32> {
+    checkboxes
+        .iter()
+        .filter(|(_, checkbox)| checkbox.is_active())
+        .map(|(rate, _)| *rate)
+        .collect()
+}
+
+/// Channel count and the checked position list of the Professional Settings
+fn selected_channel_layout(
+    channels_combo: &ComboBoxText,
+    positions_entry: &Entry,
+) -> Result<(u32, Vec<String>), String> {
+    let channels = channels_combo
+        .active_id()
+        .and_then(|id| id.parse::<u32>().ok())
+        .unwrap_or(2);
+    let positions = parse_channel_positions(&positions_entry.text(), channels)?;
+    Ok((channels, positions))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1002,4 +2637,34 @@ mod tests {
         let _exists = AdvancedTab::is_exclusive_mode_active();
         assert!(true);
     }
+
+    #[test]
+    fn test_professional_risk_assessment() {
+        let safe = ProSettingsSnapshot {
+            thread_priority: "normal",
+            clock_source: "monotonic",
+            min_buffer: 128,
+            buffer_size: 512,
+            memory_lock: false,
+            disable_resampling: false,
+        };
+        let (level, reasons) = AdvancedTab::assess_professional_risk(&safe);
+        assert_eq!(level, RiskLevel::Low);
+        assert!(reasons.is_empty());
+
+        let wedging = ProSettingsSnapshot {
+            thread_priority: "realtime",
+            min_buffer: 64,
+            ..safe
+        };
+        let (level, _) = AdvancedTab::assess_professional_risk(&wedging);
+        assert_eq!(level, RiskLevel::Dangerous);
+
+        let locked = ProSettingsSnapshot {
+            memory_lock: true,
+            ..wedging
+        };
+        let (level, _) = AdvancedTab::assess_professional_risk(&locked);
+        assert_eq!(level, RiskLevel::Elevated);
+    }
 }
@@ -9,14 +9,32 @@
 
 use gtk::prelude::*;
 use gtk::{
-    Adjustment, Application, ApplicationWindow, Box as GtkBox, Label, Menu, MenuBar, MenuItem,
-    Notebook, Orientation, ScrolledWindow,
+    AccelGroup, Adjustment, Application, ApplicationWindow, Box as GtkBox, CheckMenuItem, Label,
+    Menu, MenuBar, MenuItem, Notebook, Orientation, ScrolledWindow,
 };
 
+use super::ab_compare_dialog::show_ab_compare_dialog;
 use super::advanced_tab::AdvancedTab;
 use super::audio_tab::AudioTab;
-use super::dialogs::show_about_dialog;
+use super::combine_dialog::show_combined_output_dialog;
+use super::components::{is_compact_mode, set_compact_mode};
+use super::device_watch::start_device_watch;
+use super::dialogs::{
+    show_about_dialog, show_error_dialog, show_pipewire_remote_dialog, show_success_dialog,
+};
+use super::log_pane::LogPane;
+use super::preferences::AppPreferences;
+use super::profiles_menu::populate_profiles_menu;
+use super::pulse_resampler_dialog::show_pulse_resampler_dialog;
+use super::safe_config::apply_minimal_safe_config_action;
 use super::tab_types::TabType;
+use super::tasks::cancel_ui_timeouts;
+use super::virtual_devices_dialog::show_virtual_devices_dialog;
+use super::wizard::show_first_run_wizard;
+use crate::audio::set_pipewire_remote;
+use crate::config::{
+    migrate_configs, set_pulse_quantum_sync, set_rt_time_limits, set_service_names,
+};
 use crate::config_inspector::ConfigInspectorTab;
 use crate::monitoring::MonitoringTab;
 
@@ -29,23 +47,134 @@ pub struct AudioApp {
     pub advanced_tab: AdvancedTab,
     pub monitoring_tab: MonitoringTab,
     pub config_inspector_tab: ConfigInspectorTab,
+    pub log_pane: LogPane,
 }
 
 impl AudioApp {
     pub fn new(app: &Application) -> Self {
+        // Layout mode must be known before any section is built
+        let prefs = AppPreferences::load();
+        // Checked before anything below gets a chance to write the preferences file
+        let first_run = !prefs.wizard_completed && !AppPreferences::file_exists();
+        set_compact_mode(prefs.compact_mode);
+        // Detection and monitoring must talk to the configured PipeWire instance
+        set_pipewire_remote(prefs.pipewire_remote());
+        set_pulse_quantum_sync(prefs.sync_pulse_quantum);
+        set_service_names(prefs.service_names());
+        let (rt_time_soft, rt_time_hard) = prefs.rt_time_limits();
+        if let Err(e) = set_rt_time_limits(rt_time_soft, rt_time_hard) {
+            log::warn!("{}, using the defaults", e);
+        }
+        // Retire files from older config formats before anything reads them
+        let migration_report = migrate_configs();
+
         let window = ApplicationWindow::new(app);
         window.set_title("Pro Audio Config");
-        window.set_default_size(900, 700);
+        if is_compact_mode() {
+            window.set_default_size(800, 560);
+        } else {
+            window.set_default_size(900, 700);
+        }
 
         // Set window icon
         Self::set_window_icon(&window);
 
+        // Pending polls hold widget clones; stop them before the widgets go away
+        window.connect_destroy(|_| cancel_ui_timeouts());
+
         // ===== MAIN VERTICAL BOX - Stacks menu bar on top of content =====
         let main_vbox = GtkBox::new(Orientation::Vertical, 0);
 
         // ===== MENU BAR - Fixed at the very top =====
         let menu_bar = MenuBar::new();
 
+        let view_menu = Menu::new();
+        let view_menu_item = MenuItem::with_label("View");
+        view_menu_item.set_submenu(Some(&view_menu));
+
+        let compact_item = CheckMenuItem::with_label("Compact Mode");
+        compact_item.set_active(is_compact_mode());
+        compact_item.connect_toggled(|item| {
+            let mut prefs = AppPreferences::load();
+            prefs.compact_mode = item.is_active();
+
+            match AppPreferences::save(&prefs) {
+                Ok(()) => show_success_dialog(
+                    "Layout preference saved. Restart Pro Audio Config to switch layouts.",
+                ),
+                Err(e) => show_error_dialog(&format!("Failed to save layout preference: {}", e)),
+            }
+        });
+
+        view_menu.append(&compact_item);
+        menu_bar.append(&view_menu_item);
+
+        let options_menu = Menu::new();
+        let options_menu_item = MenuItem::with_label("Options");
+        options_menu_item.set_submenu(Some(&options_menu));
+
+        let confirm_restart_item = CheckMenuItem::with_label("Confirm Before Restarting Audio");
+        confirm_restart_item.set_tooltip_text(Some(
+            "Ask before applying settings that interrupt audio. System-wide applies always ask.",
+        ));
+        confirm_restart_item.set_active(prefs.confirm_service_restart);
+        confirm_restart_item.connect_toggled(|item| {
+            let enabled = item.is_active();
+            if let Err(e) = AppPreferences::update(|prefs| prefs.confirm_service_restart = enabled)
+            {
+                show_error_dialog(&format!("Failed to save restart preference: {}", e));
+            }
+        });
+
+        let pulse_quantum_item = CheckMenuItem::with_label("Apply Buffer to PulseAudio Clients");
+        pulse_quantum_item.set_tooltip_text(Some(
+            "Also write pulse.min.quantum/pulse.min.req for pipewire-pulse so browsers and games \
+             are not clamped to a larger buffer",
+        ));
+        pulse_quantum_item.set_active(prefs.sync_pulse_quantum);
+        pulse_quantum_item.connect_toggled(|item| {
+            let enabled = item.is_active();
+            set_pulse_quantum_sync(enabled);
+            if let Err(e) = AppPreferences::update(|prefs| prefs.sync_pulse_quantum = enabled) {
+                show_error_dialog(&format!("Failed to save PulseAudio preference: {}", e));
+            }
+        });
+
+        let pipewire_remote_item = MenuItem::with_label("PipeWire Connection...");
+        pipewire_remote_item.connect_activate(|_| {
+            show_pipewire_remote_dialog();
+        });
+
+        options_menu.append(&confirm_restart_item);
+        options_menu.append(&pulse_quantum_item);
+        options_menu.append(&pipewire_remote_item);
+        menu_bar.append(&options_menu_item);
+
+        let tools_menu = Menu::new();
+        let tools_menu_item = MenuItem::with_label("Tools");
+        tools_menu_item.set_submenu(Some(&tools_menu));
+
+        // Connected once the app state exists, the dialog needs the main window
+        let combined_output_item = MenuItem::with_label("Combined Output...");
+        tools_menu.append(&combined_output_item);
+        let virtual_devices_item = MenuItem::with_label("Virtual Devices...");
+        tools_menu.append(&virtual_devices_item);
+        let pulse_resampler_item = MenuItem::with_label("PulseAudio Client Resampler...");
+        tools_menu.append(&pulse_resampler_item);
+        let ab_compare_item = MenuItem::with_label("A/B Compare Buffer Sizes...");
+        tools_menu.append(&ab_compare_item);
+        let safe_config_item = MenuItem::with_label("Apply Minimal Safe Config...");
+        tools_menu.append(&safe_config_item);
+        menu_bar.append(&tools_menu_item);
+
+        // Filled once the app state exists; Ctrl+<n> applies the nth profile
+        let profiles_menu = Menu::new();
+        let profiles_menu_item = MenuItem::with_label("Profiles");
+        profiles_menu_item.set_submenu(Some(&profiles_menu));
+        menu_bar.append(&profiles_menu_item);
+        let accel_group = AccelGroup::new();
+        window.add_accel_group(&accel_group);
+
         let help_menu = Menu::new();
         let help_menu_item = MenuItem::with_label("Help");
         help_menu_item.set_submenu(Some(&help_menu));
@@ -66,11 +195,12 @@ impl AudioApp {
         scrolled_window.set_propagate_natural_height(true);
         scrolled_window.set_propagate_natural_width(true);
 
+        let content_margin = if is_compact_mode() { 4 } else { 18 };
         let content_box = GtkBox::new(Orientation::Vertical, 12);
-        content_box.set_margin_top(18);
-        content_box.set_margin_bottom(18);
-        content_box.set_margin_start(18);
-        content_box.set_margin_end(18);
+        content_box.set_margin_top(content_margin);
+        content_box.set_margin_bottom(content_margin);
+        content_box.set_margin_start(content_margin);
+        content_box.set_margin_end(content_margin);
 
         // ===== CREATE NOTEBOOK (TABS) =====
         let notebook = Notebook::new();
@@ -104,6 +234,10 @@ impl AudioApp {
         // Pack scrollable content below the menu bar
         main_vbox.pack_start(&scrolled_window, true, true, 0);
 
+        // Collapsed by default; error dialogs can open it
+        let log_pane = LogPane::new();
+        main_vbox.pack_start(&log_pane.container, false, false, 0);
+
         // Add the main vbox to the window
         window.add(&main_vbox);
 
@@ -115,11 +249,64 @@ impl AudioApp {
             advanced_tab,
             monitoring_tab,
             config_inspector_tab,
+            log_pane,
         };
 
+        populate_profiles_menu(&profiles_menu, &accel_group, &app_state);
+
+        {
+            let dialog_state = app_state.clone();
+            combined_output_item.connect_activate(move |_| {
+                show_combined_output_dialog(&dialog_state);
+            });
+        }
+
+        {
+            let dialog_state = app_state.clone();
+            virtual_devices_item.connect_activate(move |_| {
+                show_virtual_devices_dialog(&dialog_state);
+            });
+        }
+
+        {
+            let dialog_state = app_state.clone();
+            pulse_resampler_item.connect_activate(move |_| {
+                show_pulse_resampler_dialog(&dialog_state);
+            });
+        }
+
+        {
+            let dialog_state = app_state.clone();
+            ab_compare_item.connect_activate(move |_| {
+                show_ab_compare_dialog(&dialog_state);
+            });
+        }
+
+        {
+            let safe_state = app_state.clone();
+            safe_config_item.connect_activate(move |_| {
+                apply_minimal_safe_config_action(&safe_state);
+            });
+        }
+
         app_state.setup_signals();
         app_state.setup_advanced_signals();
         app_state.initialize_tabs();
+        start_device_watch(&app_state);
+
+        if !migration_report.is_empty() {
+            glib::idle_add_local_once(move || {
+                show_success_dialog(&format!(
+                    "Configs written by an older version were updated:\n\n{}",
+                    migration_report.join("\n")
+                ));
+            });
+        }
+
+        if first_run {
+            let wizard_state = app_state.clone();
+            glib::idle_add_local_once(move || show_first_run_wizard(&wizard_state));
+        }
 
         app_state
     }
@@ -144,9 +331,14 @@ impl AudioApp {
         self.input_tab.detect_all_devices();
         self.output_tab.detect_current_settings();
         self.input_tab.detect_current_settings();
+        self.output_tab.detect_rate_switching();
+        self.input_tab.detect_rate_switching();
         self.output_tab.detect_current_device();
         self.input_tab.detect_current_device();
+        self.output_tab.detect_effects_managers();
+        self.input_tab.detect_effects_managers();
         self.advanced_tab.detect_advanced_devices();
+        self.advanced_tab.detect_quantum_floor();
     }
 
     fn setup_signals(&self) {
@@ -0,0 +1,66 @@
+/*
+ * Pro Audio Config - Apply Guard Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * Application-wide guard that lets only one apply run at a time and
+ * keeps every apply button insensitive while it does
+ */
+
+use gtk::prelude::*;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Shown when an apply is requested while another one is still running
+pub const APPLY_BUSY_TEXT: &str = "Another apply is still in progress, please wait";
+
+static APPLY_IN_PROGRESS: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// Widgets that start an apply, with their sensitivity before the current apply
+    static APPLY_WIDGETS: RefCell<Vec<(gtk::Widget, bool)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Adds a button to the set that is disabled while any apply runs
+pub fn register_apply_widget(widget: &impl IsA<gtk::Widget>) {
+    let widget = widget.clone().upcast::<gtk::Widget>();
+    APPLY_WIDGETS.with(|widgets| widgets.borrow_mut().push((widget, true)));
+}
+
+/// Marks an apply as started and disables every registered widget. Returns
+/// false, changing nothing, when another apply is still running.
+pub fn begin_apply() -> bool {
+    if APPLY_IN_PROGRESS.swap(true, Ordering::SeqCst) {
+        log::debug!("Apply requested while another apply is running, ignoring");
+        return false;
+    }
+
+    APPLY_WIDGETS.with(|widgets| {
+        for (widget, was_sensitive) in widgets.borrow_mut().iter_mut() {
+            *was_sensitive = widget.is_sensitive();
+            widget.set_sensitive(false);
+        }
+    });
+    true
+}
+
+/// Whether an apply is running, for background work that should wait for it
+pub fn is_apply_in_progress() -> bool {
+    APPLY_IN_PROGRESS.load(Ordering::SeqCst)
+}
+
+/// Ends the running apply and gives every registered widget back the
+/// sensitivity it had before
+pub fn end_apply() {
+    if !APPLY_IN_PROGRESS.swap(false, Ordering::SeqCst) {
+        return;
+    }
+
+    APPLY_WIDGETS.with(|widgets| {
+        for (widget, was_sensitive) in widgets.borrow().iter() {
+            widget.set_sensitive(*was_sensitive);
+        }
+    });
+}
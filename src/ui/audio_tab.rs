@@ -9,36 +9,105 @@
 
 use glib::ControlFlow;
 use gtk::prelude::*;
-use gtk::{Box as GtkBox, Button, CheckButton, ComboBoxText, Label};
+use gtk::{Box as GtkBox, Button, CheckButton, ComboBoxText, Entry, Label};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::rc::Rc;
 use std::sync::{Arc, Mutex, mpsc};
 use std::time::Duration;
 
-use crate::audio::{AudioDevice, AudioSettings, DeviceType, detect_current_audio_settings};
-use crate::config::{apply_user_audio_settings, apply_wireplumber_device_config};
+use crate::audio::{
+    AudioDevice, AudioSettings, DeviceCapabilities, buffer_for_rate_change, clear_cache,
+    detect_current_audio_settings, detect_device_capabilities, detect_device_discriminators,
+    detect_effects_managers, detect_rate_switching,
+};
+use crate::config::{
+    ApplyOutcome, apply_direct_alsa_config, apply_user_audio_settings,
+    apply_wireplumber_device_config, diff_pending_config, plan_restart, preview_audio_settings,
+    remove_direct_alsa_config, set_default_device, verify_current_settings,
+};
+use crate::error::AudioConfigError;
+use crate::presets::Preset;
 
 use super::app::AudioApp;
-use super::components::{create_constrained_combo, create_section_box, populate_combo_box};
-use super::constants::{BIT_DEPTHS, BUFFER_SIZES, SAMPLE_RATES};
-use super::dialogs::{show_error_dialog, show_success_dialog};
-use super::helpers::{clean_device_description, clean_display_text};
+use super::apply_guard::{APPLY_BUSY_TEXT, begin_apply, end_apply, register_apply_widget};
+use super::components::{
+    create_constrained_combo, create_section_box, mark_verbose_label, populate_combo_box,
+};
+use super::config_preview_dialog::show_config_preview_dialog;
+use super::constants::{BIT_DEPTHS, BUFFER_SIZES, CHANNEL_LAYOUTS, PERIOD_SIZES, SAMPLE_RATES};
+use super::dialogs::{
+    choose_setting_to_revert, confirm_config_changes, confirm_service_restart,
+    confirm_unsupported_settings, show_config_error_dialog, show_error_dialog, show_success_dialog,
+};
+use super::helpers::{
+    clean_display_text, colliding_device_ids, device_capability_message, device_display_text,
+    missing_device_message,
+};
+use super::mixer_controls::MixerControlsPanel;
 use super::preferences::AppPreferences;
+use super::preset_picker::PresetPicker;
 use super::tab_types::TabType;
+use super::tasks::add_ui_timeout;
+
+/// Shown instead of pretending the fallback values are what the system runs at
+const DETECTION_FAILED_TEXT: &str = "Current settings unknown (could not detect, showing defaults)";
+
+/// Period combo id for "api.alsa.period-size follows the buffer size"
+const PERIOD_SAME_AS_BUFFER: &str = "same";
+
+const FAVORITE_ADD_LABEL: &str = "☆ Add to Favorites";
+const FAVORITE_REMOVE_LABEL: &str = "★ Remove from Favorites";
+
+/// What the apply handler looks up about the selected device off the UI thread
+struct DeviceChecks {
+    /// Error to show when the device was unplugged, from `missing_device_message`
+    missing_device: Option<String>,
+    /// Warning for settings the device does not list, from `device_capability_message`
+    unsupported_settings: Option<String>,
+}
 
 #[derive(Clone)]
 pub struct AudioTab {
     pub container: GtkBox,
     pub status_label: Label,
+    pub preset_picker: PresetPicker,
     pub sample_rate_combo: ComboBoxText,
+    /// Whether rate changes take effect live or need a restart
+    pub rate_switching_label: Label,
     pub bit_depth_combo: ComboBoxText,
     pub buffer_size_combo: ComboBoxText,
+    /// ALSA period size, apart from the graph quantum in `buffer_size_combo`
+    pub period_size_combo: ComboBoxText,
+    /// audio.channels of the device, with the standard positions for the count
+    pub channel_layout_combo: ComboBoxText,
+    pub keep_latency_checkbox: CheckButton,
+    /// Opt-in: also write ~/.asoundrc for apps that bypass PipeWire
+    pub direct_alsa_checkbox: CheckButton,
+    /// Optional node.description; empty keeps the device's own name
+    pub device_description_entry: Entry,
     pub device_combo: ComboBoxText,
+    pub favorite_button: Button,
+    pub mixer_panel: MixerControlsPanel,
     pub current_device_label: Label,
+    /// Shown when EasyEffects or a similar app sits between apps and the device
+    pub effects_warning_label: Label,
     pub apply_button: Button,
+    /// Shows the config files Apply would write, without writing them
+    pub preview_button: Button,
+    /// Makes the selected device the default sink/source
+    pub default_button: Button,
+    pub verify_button: Button,
+    pub revert_button: Button,
     pub available_devices: Vec<AudioDevice>,
+    /// Devices from the last detection, to map combo ids back to node names
+    pub detected_devices: Arc<Mutex<Vec<AudioDevice>>>,
     pub current_default_device: Arc<Mutex<String>>,
     pub tab_type: TabType,
     pub system_wide_checkbox: CheckButton,
     pub preferences: Arc<Mutex<AppPreferences>>,
+    /// Bumped per capability lookup, so a slow one for a previous device is dropped
+    capability_generation: Rc<Cell<u64>>,
 }
 
 impl AudioTab {
@@ -59,6 +128,11 @@ impl AudioTab {
         current_device_label.set_halign(gtk::Align::Start);
         current_device_label.set_selectable(true);
 
+        let effects_warning_label = Label::new(None);
+        effects_warning_label.set_halign(gtk::Align::Start);
+        effects_warning_label.set_line_wrap(true);
+        effects_warning_label.set_no_show_all(true);
+
         let device_selection_label = Label::new(Some(&format!(
             "Select {} to Configure:",
             tab_type.device_label()
@@ -67,6 +141,13 @@ impl AudioTab {
 
         let device_combo = create_constrained_combo();
 
+        let favorite_button = Button::with_label(FAVORITE_ADD_LABEL);
+        favorite_button.set_tooltip_text(Some(
+            "Favorites are listed first in the device lists of all tabs",
+        ));
+        favorite_button.set_sensitive(false);
+        favorite_button.set_halign(gtk::Align::Start);
+
         let selection_info_label = Label::new(Some(&format!(
             "Select an {} device from the dropdown above",
             tab_type.title().to_lowercase()
@@ -74,10 +155,15 @@ impl AudioTab {
         selection_info_label.set_halign(gtk::Align::Start);
 
         device_box.pack_start(&current_device_label, false, false, 0);
+        device_box.pack_start(&effects_warning_label, false, false, 0);
         device_box.pack_start(&device_selection_label, false, false, 0);
         device_box.pack_start(&device_combo, false, false, 0);
+        device_box.pack_start(&favorite_button, false, false, 0);
         device_box.pack_start(&selection_info_label, false, false, 0);
 
+        let mixer_panel = MixerControlsPanel::new();
+        device_box.pack_start(&mixer_panel.container, false, false, 0);
+
         // ===== SETTINGS SECTION =====
         let (settings_frame, settings_box) = create_section_box(tab_type.settings_label());
         let preferences = Arc::new(Mutex::new(AppPreferences::load()));
@@ -89,8 +175,8 @@ impl AudioTab {
         // DEBUG: Print loaded preferences
         {
             let prefs = preferences.lock().unwrap();
-            println!(
-                "DEBUG: Loaded preferences - system_wide_config: {}",
+            log::debug!(
+                "Loaded preferences - system_wide_config: {}",
                 prefs.system_wide_config
             );
         }
@@ -99,9 +185,11 @@ impl AudioTab {
         {
             let prefs = preferences.lock().unwrap();
             system_wide_checkbox.set_active(prefs.system_wide_config);
-            println!("DEBUG: Setting checkbox to: {}", prefs.system_wide_config);
+            log::debug!("Setting checkbox to: {}", prefs.system_wide_config);
         }
 
+        let preset_picker = PresetPicker::new();
+
         // Sample Rate Selection
         let sample_rate_label = Label::new(Some("Sample Rate:"));
         sample_rate_label.set_halign(gtk::Align::Start);
@@ -113,6 +201,10 @@ impl AudioTab {
             sample_rate_combo.set_active_id(Some("48000"));
         }
 
+        let rate_switching_label = Label::new(Some("Checking sample rate switching support..."));
+        rate_switching_label.set_halign(gtk::Align::Start);
+        rate_switching_label.set_line_wrap(true);
+
         // Bit Depth Selection
         let bit_depth_label = Label::new(Some("Bit Depth:"));
         bit_depth_label.set_halign(gtk::Align::Start);
@@ -125,7 +217,7 @@ impl AudioTab {
         }
 
         // Buffer Size Selection
-        let buffer_size_label = Label::new(Some("Buffer Size:"));
+        let buffer_size_label = Label::new(Some("Buffer Size (Graph Quantum):"));
         buffer_size_label.set_halign(gtk::Align::Start);
 
         let buffer_size_combo = create_constrained_combo();
@@ -135,12 +227,74 @@ impl AudioTab {
             buffer_size_combo.set_active_id(Some("512"));
         }
 
+        // ALSA Period Size Selection
+        let period_size_label = Label::new(Some("Hardware Period Size (ALSA):"));
+        period_size_label.set_halign(gtk::Align::Start);
+
+        let period_size_combo = create_constrained_combo();
+        period_size_combo.append(Some(PERIOD_SAME_AS_BUFFER), "Same as buffer size");
+        populate_combo_box(&period_size_combo, PERIOD_SIZES);
+        period_size_combo.set_active_id(Some(PERIOD_SAME_AS_BUFFER));
+        period_size_combo.set_tooltip_text(Some(
+            "Sets api.alsa.period-size for the device. Some interfaces run best with a period \
+             different from the graph quantum.",
+        ));
+
+        // Channel Layout Selection
+        let channel_layout_label = Label::new(Some("Channel Layout:"));
+        channel_layout_label.set_halign(gtk::Align::Start);
+
+        let channel_layout_combo = create_constrained_combo();
+        populate_combo_box(&channel_layout_combo, CHANNEL_LAYOUTS);
+        channel_layout_combo.set_active_id(Some("2"));
+        channel_layout_combo.set_tooltip_text(Some(
+            "Sets audio.channels and audio.position for the device, e.g. FL FR FC LFE RL RR \
+             for 5.1. Interfaces with a custom mapping can be set in the Advanced tab.",
+        ));
+
+        let keep_latency_checkbox =
+            CheckButton::with_label("Keep latency constant when changing sample rate");
+        keep_latency_checkbox.set_tooltip_text(Some(
+            "Picks the buffer size closest to the current latency in ms whenever the sample rate changes",
+        ));
+        keep_latency_checkbox.set_active(preferences.lock().unwrap().keep_latency_constant);
+
+        let direct_alsa_checkbox =
+            CheckButton::with_label("Also configure direct ALSA (~/.asoundrc)");
+        direct_alsa_checkbox.set_tooltip_text(Some(
+            "For apps that open ALSA directly instead of PipeWire: writes rate and period/buffer \
+             defaults and a pro_audio_playback / pro_audio_capture device to ~/.asoundrc. \
+             Only works for sound cards with a direct ALSA path.",
+        ));
+        direct_alsa_checkbox.set_active(preferences.lock().unwrap().direct_alsa_config);
+
+        // Device name shown to apps (node.description)
+        let device_description_label = Label::new(Some("Device Name Shown to Apps:"));
+        device_description_label.set_halign(gtk::Align::Start);
+
+        let device_description_entry = Entry::new();
+        device_description_entry
+            .set_placeholder_text(Some("Leave empty to keep the device's own name"));
+        device_description_entry.set_tooltip_text(Some(
+            "Sets node.description for the selected device, which every app shows in its device list",
+        ));
+
+        settings_box.pack_start(&preset_picker.container, false, false, 0);
         settings_box.pack_start(&sample_rate_label, false, false, 0);
         settings_box.pack_start(&sample_rate_combo, false, false, 0);
+        settings_box.pack_start(&rate_switching_label, false, false, 0);
         settings_box.pack_start(&bit_depth_label, false, false, 0);
         settings_box.pack_start(&bit_depth_combo, false, false, 0);
         settings_box.pack_start(&buffer_size_label, false, false, 0);
         settings_box.pack_start(&buffer_size_combo, false, false, 0);
+        settings_box.pack_start(&period_size_label, false, false, 0);
+        settings_box.pack_start(&period_size_combo, false, false, 0);
+        settings_box.pack_start(&channel_layout_label, false, false, 0);
+        settings_box.pack_start(&channel_layout_combo, false, false, 0);
+        settings_box.pack_start(&keep_latency_checkbox, false, false, 0);
+        settings_box.pack_start(&direct_alsa_checkbox, false, false, 0);
+        settings_box.pack_start(&device_description_label, false, false, 0);
+        settings_box.pack_start(&device_description_entry, false, false, 0);
 
         // ===== ACTIONS SECTION =====
         let (actions_frame, actions_box) = create_section_box(tab_type.actions_label());
@@ -152,15 +306,43 @@ impl AudioTab {
         status_label.set_halign(gtk::Align::Start);
 
         let apply_button = Button::with_label(tab_type.apply_button_label());
+        register_apply_widget(&apply_button);
+
+        let preview_button = Button::with_label("Preview Config...");
+        preview_button.set_tooltip_text(Some(
+            "Show the files and content Apply would write, without writing anything",
+        ));
+
+        let default_button = Button::with_label("Set as Default");
+        default_button.set_tooltip_text(Some(&format!(
+            "Make the selected device the default {} for all applications (wpctl set-default)",
+            tab_type.title().to_lowercase()
+        )));
+
+        let verify_button = Button::with_label("Verify Settings");
+        verify_button.set_tooltip_text(Some(
+            "Compare the running audio system against the last applied settings without reapplying",
+        ));
+
+        let revert_button = Button::with_label("Revert One Setting...");
+        revert_button.set_tooltip_text(Some(
+            "Put a single setting (e.g. the buffer size) back to its value before the last apply and apply again",
+        ));
+        register_apply_widget(&revert_button);
 
         let info_label = Label::new(Some(&format!(
             "Note: Administrator privileges will be requested to apply system {} audio settings",
             tab_type.title().to_lowercase()
         )));
         info_label.set_line_wrap(true);
+        mark_verbose_label(&info_label);
 
         actions_box.pack_start(&status_label, false, false, 0);
         actions_box.pack_start(&apply_button, false, false, 0);
+        actions_box.pack_start(&preview_button, false, false, 0);
+        actions_box.pack_start(&default_button, false, false, 0);
+        actions_box.pack_start(&verify_button, false, false, 0);
+        actions_box.pack_start(&revert_button, false, false, 0);
         actions_box.pack_start(&info_label, false, false, 0);
 
         // ===== SYSTEM CONFIG SECTION =====
@@ -170,6 +352,7 @@ impl AudioTab {
             "System-wide changes affect all users and require authentication",
         ));
         system_info_label.set_line_wrap(true);
+        mark_verbose_label(&system_info_label);
 
         system_box.pack_start(&system_wide_checkbox, false, false, 0);
         system_box.pack_start(&system_info_label, false, false, 0);
@@ -184,17 +367,33 @@ impl AudioTab {
         Self {
             container,
             status_label,
+            preset_picker,
             sample_rate_combo,
+            rate_switching_label,
             bit_depth_combo,
             buffer_size_combo,
+            period_size_combo,
+            channel_layout_combo,
+            keep_latency_checkbox,
+            direct_alsa_checkbox,
+            device_description_entry,
             device_combo,
+            favorite_button,
+            mixer_panel,
             current_device_label,
+            effects_warning_label,
             apply_button,
+            preview_button,
+            default_button,
+            verify_button,
+            revert_button,
             available_devices: Vec::new(),
+            detected_devices: Arc::new(Mutex::new(Vec::new())),
             current_default_device: Arc::new(Mutex::new(String::new())),
             tab_type,
             system_wide_checkbox,
             preferences,
+            capability_generation: Rc::new(Cell::new(0)),
         }
     }
 
@@ -217,7 +416,7 @@ impl AudioTab {
         });
 
         let rx_timeout = Arc::clone(&rx_arc);
-        glib::timeout_add_local(Duration::from_millis(100), move || {
+        add_ui_timeout(Duration::from_millis(100), move || {
             let rx_guard = rx_timeout.lock().unwrap();
             match rx_guard.try_recv() {
                 Ok(result) => {
@@ -248,6 +447,7 @@ impl AudioTab {
     pub fn detect_all_devices(&self) {
         let device_combo = self.device_combo.clone();
         let current_default_device = Arc::clone(&self.current_default_device);
+        let detected_devices = Arc::clone(&self.detected_devices);
         let detect_fn = self.tab_type.detect_devices_fn();
         let tab_type = self.tab_type.clone();
 
@@ -255,17 +455,24 @@ impl AudioTab {
         let rx_arc = Arc::new(Mutex::new(rx));
 
         std::thread::spawn(move || {
-            let result = detect_fn();
+            // Devices reading the same get their card/port looked up here, off the UI thread
+            let result = detect_fn().map(|devices| {
+                let discriminators = detect_device_discriminators(&colliding_device_ids(&devices));
+                (devices, discriminators)
+            });
             let _ = tx.send(result);
         });
 
         let rx_timeout = Arc::clone(&rx_arc);
-        glib::timeout_add_local(Duration::from_millis(100), move || {
+        add_ui_timeout(Duration::from_millis(100), move || {
             let rx_guard = rx_timeout.lock().unwrap();
             match rx_guard.try_recv() {
                 Ok(result) => {
                     match result {
-                        Ok(devices) => {
+                        Ok((devices, discriminators)) => {
+                            // Before the combo changes, its handlers look devices up here
+                            *detected_devices.lock().unwrap() = devices.clone();
+                            let previous_selection = device_combo.active_id();
                             device_combo.remove_all();
 
                             let default_device_name = {
@@ -279,6 +486,8 @@ impl AudioTab {
 
                             device_combo.append(Some("default"), &default_device_name);
 
+                            let prefs = AppPreferences::load();
+                            let mut favorite_devices = Vec::new();
                             let mut usb_devices = Vec::new();
                             let mut hdmi_devices = Vec::new();
                             let mut pci_devices = Vec::new();
@@ -289,7 +498,9 @@ impl AudioTab {
                                 let name_lower = device.name.to_lowercase();
                                 let id_lower = device.id.to_lowercase();
 
-                                if desc_lower.contains("usb")
+                                if prefs.is_favorite_device(device) {
+                                    favorite_devices.push(device);
+                                } else if desc_lower.contains("usb")
                                     || name_lower.contains("usb")
                                     || id_lower.contains("usb")
                                 {
@@ -310,11 +521,26 @@ impl AudioTab {
                                 }
                             }
 
+                            if !favorite_devices.is_empty() {
+                                device_combo.append(Some("separator0"), "--- Favorites ---");
+                                for device in favorite_devices {
+                                    Self::add_device_to_combo(
+                                        &device_combo,
+                                        device,
+                                        &discriminators,
+                                    );
+                                }
+                            }
+
                             if !usb_devices.is_empty() {
                                 device_combo
                                     .append(Some("separator1"), "--- USB Audio Devices ---");
                                 for device in usb_devices {
-                                    Self::add_device_to_combo(&device_combo, device, &tab_type);
+                                    Self::add_device_to_combo(
+                                        &device_combo,
+                                        device,
+                                        &discriminators,
+                                    );
                                 }
                             }
 
@@ -322,7 +548,11 @@ impl AudioTab {
                                 device_combo
                                     .append(Some("separator2"), "--- PCI Audio Devices ---");
                                 for device in pci_devices {
-                                    Self::add_device_to_combo(&device_combo, device, &tab_type);
+                                    Self::add_device_to_combo(
+                                        &device_combo,
+                                        device,
+                                        &discriminators,
+                                    );
                                 }
                             }
 
@@ -330,7 +560,11 @@ impl AudioTab {
                                 device_combo
                                     .append(Some("separator3"), "--- HDMI/DisplayPort Audio ---");
                                 for device in hdmi_devices {
-                                    Self::add_device_to_combo(&device_combo, device, &tab_type);
+                                    Self::add_device_to_combo(
+                                        &device_combo,
+                                        device,
+                                        &discriminators,
+                                    );
                                 }
                             }
 
@@ -338,12 +572,28 @@ impl AudioTab {
                                 device_combo
                                     .append(Some("separator4"), "--- Other Audio Devices ---");
                                 for device in other_devices {
-                                    Self::add_device_to_combo(&device_combo, device, &tab_type);
+                                    Self::add_device_to_combo(
+                                        &device_combo,
+                                        device,
+                                        &discriminators,
+                                    );
                                 }
                             }
+
+                            // Keep the selection across refreshes unless that device is gone
+                            if let Some(previous) = previous_selection
+                                && !device_combo.set_active_id(Some(&previous))
+                            {
+                                log::info!(
+                                    "Selected {} device {} was removed, falling back to default",
+                                    tab_type.title().to_lowercase(),
+                                    previous
+                                );
+                                device_combo.set_active_id(Some("default"));
+                            }
                         }
                         Err(e) => {
-                            println!("Error detecting {} devices: {}", tab_type.title(), e);
+                            log::info!("Error detecting {} devices: {}", tab_type.title(), e);
                             device_combo.append(Some("default"), "Default System Device");
                             device_combo.set_active_id(Some("default"));
                         }
@@ -360,31 +610,213 @@ impl AudioTab {
         });
     }
 
-    fn add_device_to_combo(combo: &ComboBoxText, device: &AudioDevice, tab_type: &TabType) {
-        let device_type = match device.device_type {
-            DeviceType::Input => "🎤 Input",
-            DeviceType::Output => "🔊 Output",
-            DeviceType::Duplex => "🔄 Duplex",
-            _ => match tab_type {
-                TabType::Input => "🎤 Input",
-                TabType::Output => "🔊 Output",
-            },
-        };
+    fn add_device_to_combo(
+        combo: &ComboBoxText,
+        device: &AudioDevice,
+        discriminators: &HashMap<String, String>,
+    ) {
+        let discriminator = discriminators.get(&device.id).map(String::as_str);
+        combo.append(
+            Some(&device.id),
+            &device_display_text(device, discriminator),
+        );
+    }
+
+    /// Puts suggested settings into the tab (first-run wizard). The device is added
+    /// to the combo if detection has not listed it yet so the next apply targets it.
+    pub fn select_settings(
+        &self,
+        settings: &AudioSettings,
+        device: Option<&AudioDevice>,
+        system_wide: bool,
+    ) {
+        self.select_format(settings);
+
+        if !self.device_combo.set_active_id(Some(&settings.device_id)) {
+            match device {
+                Some(device) => {
+                    Self::add_device_to_combo(&self.device_combo, device, &HashMap::new());
+                    self.device_combo.set_active_id(Some(&device.id));
+                }
+                None => {
+                    self.device_combo
+                        .append(Some("default"), "Default System Device");
+                    self.device_combo.set_active_id(Some("default"));
+                }
+            }
+        }
 
-        let clean_description = clean_device_description(&device.description);
+        self.system_wide_checkbox.set_active(system_wide);
+    }
 
-        let display_text = if clean_description.is_empty() {
-            format!("{} {}", device_type, device.name)
-        } else {
-            format!("{} {} - {}", device_type, device.name, clean_description)
+    /// Puts rate, bit depth, buffer, period size and channels into their combos,
+    /// leaving the device and scope alone (presets)
+    pub fn select_format(&self, settings: &AudioSettings) {
+        self.sample_rate_combo
+            .set_active_id(Some(&settings.sample_rate.to_string()));
+        self.bit_depth_combo
+            .set_active_id(Some(&settings.bit_depth.to_string()));
+        self.buffer_size_combo
+            .set_active_id(Some(&settings.buffer_size.to_string()));
+        match settings.period_size {
+            Some(period_size) => self
+                .period_size_combo
+                .set_active_id(Some(&period_size.to_string())),
+            None => self
+                .period_size_combo
+                .set_active_id(Some(PERIOD_SAME_AS_BUFFER)),
         };
-        combo.append(Some(&device.id), &display_text);
+        self.channel_layout_combo
+            .set_active_id(Some(&settings.channels.to_string()));
+    }
+
+    /// Settings as currently picked in the tab, with the apply defaults for
+    /// anything not selected
+    pub fn selected_settings(&self) -> AudioSettings {
+        let value = |combo: &ComboBoxText| combo.active_id().and_then(|id| id.parse::<u32>().ok());
+
+        AudioSettings {
+            sample_rate: value(&self.sample_rate_combo).unwrap_or(48000),
+            bit_depth: value(&self.bit_depth_combo).unwrap_or(24),
+            buffer_size: value(&self.buffer_size_combo).unwrap_or(512),
+            device_id: self
+                .device_combo
+                .active_id()
+                .map_or_else(|| "default".to_string(), |id| id.to_string()),
+            allowed_rates: Vec::new(),
+            node_description: None,
+            period_size: value(&self.period_size_combo),
+            channels: value(&self.channel_layout_combo).unwrap_or(2),
+            position: Vec::new(),
+        }
+        .with_node_description(&self.device_description_entry.text())
+    }
+
+    /// Shows whether the running server switches rates live. The probe runs
+    /// once per session; later calls reuse the cached result.
+    /// Warns when an effects app (EasyEffects...) is running or owns the default device
+    /// Refills the rate, bit depth and buffer combos with the values the
+    /// device supports. Everything stays listed when detection fails or the
+    /// device reports nothing.
+    pub fn load_device_capabilities(&self, device_id: &str) {
+        let generation = self.capability_generation.get() + 1;
+        self.capability_generation.set(generation);
+
+        let device_id = device_id.to_string();
+        let stream_type = self.tab_type.title().to_lowercase();
+        let (tx, rx) = mpsc::channel();
+        let rx_arc = Arc::new(Mutex::new(rx));
+
+        std::thread::spawn(move || {
+            let result = detect_device_capabilities(&device_id, &stream_type);
+            let _ = tx.send((device_id, result));
+        });
+
+        let tab = self.clone();
+        let rx_timeout = Arc::clone(&rx_arc);
+        add_ui_timeout(Duration::from_millis(100), move || {
+            let rx_guard = rx_timeout.lock().unwrap();
+            match rx_guard.try_recv() {
+                Ok((device_id, result)) => {
+                    if tab.capability_generation.get() == generation {
+                        match result {
+                            Ok(capabilities) if capabilities.detected => {
+                                tab.show_supported_values(Some(&capabilities))
+                            }
+                            Ok(_) => tab.show_supported_values(None),
+                            Err(e) => {
+                                log::debug!(
+                                    "Showing all values, no capabilities for {}: {}",
+                                    device_id,
+                                    e
+                                );
+                                tab.show_supported_values(None);
+                            }
+                        }
+                    }
+                    ControlFlow::Break
+                }
+                Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+                Err(mpsc::TryRecvError::Disconnected) => ControlFlow::Break,
+            }
+        });
+    }
+
+    /// None lists every value again
+    fn show_supported_values(&self, capabilities: Option<&DeviceCapabilities>) {
+        let bit_depths = capabilities.map(DeviceCapabilities::bit_depths);
+
+        refill_supported(&self.sample_rate_combo, SAMPLE_RATES, |rate| {
+            capabilities.is_none_or(|c| c.sample_rates.contains(&rate))
+        });
+        refill_supported(&self.bit_depth_combo, BIT_DEPTHS, |depth| {
+            bit_depths
+                .as_ref()
+                .is_none_or(|depths| depths.contains(&depth))
+        });
+        refill_supported(&self.buffer_size_combo, BUFFER_SIZES, |size| {
+            capabilities.is_none_or(|c| (c.min_buffer_size..=c.max_buffer_size).contains(&size))
+        });
+    }
+
+    pub fn detect_effects_managers(&self) {
+        let effects_warning_label = self.effects_warning_label.clone();
+        let input = matches!(self.tab_type, TabType::Input);
+
+        let (tx, rx) = mpsc::channel();
+        let rx_arc = Arc::new(Mutex::new(rx));
+
+        std::thread::spawn(move || {
+            let _ = tx.send(detect_effects_managers().warning(input));
+        });
+
+        let rx_timeout = Arc::clone(&rx_arc);
+        add_ui_timeout(Duration::from_millis(100), move || {
+            let rx_guard = rx_timeout.lock().unwrap();
+            match rx_guard.try_recv() {
+                Ok(warning) => {
+                    if let Some(warning) = &warning {
+                        log::info!("{}", warning);
+                    }
+                    effects_warning_label.set_text(warning.as_deref().unwrap_or_default());
+                    effects_warning_label.set_visible(warning.is_some());
+                    ControlFlow::Break
+                }
+                Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+                Err(mpsc::TryRecvError::Disconnected) => ControlFlow::Break,
+            }
+        });
+    }
+
+    pub fn detect_rate_switching(&self) {
+        let rate_switching_label = self.rate_switching_label.clone();
+
+        let (tx, rx) = mpsc::channel();
+        let rx_arc = Arc::new(Mutex::new(rx));
+
+        std::thread::spawn(move || {
+            let _ = tx.send(detect_rate_switching());
+        });
+
+        let rx_timeout = Arc::clone(&rx_arc);
+        add_ui_timeout(Duration::from_millis(100), move || {
+            let rx_guard = rx_timeout.lock().unwrap();
+            match rx_guard.try_recv() {
+                Ok(rate_switching) => {
+                    rate_switching_label.set_text(rate_switching.description());
+                    ControlFlow::Break
+                }
+                Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+                Err(mpsc::TryRecvError::Disconnected) => ControlFlow::Break,
+            }
+        });
     }
 
     pub fn detect_current_settings(&self) {
         let sample_rate_combo = self.sample_rate_combo.clone();
         let bit_depth_combo = self.bit_depth_combo.clone();
         let buffer_size_combo = self.buffer_size_combo.clone();
+        let status_label = self.status_label.clone();
 
         let (tx, rx) = mpsc::channel();
         let rx_arc = Arc::new(Mutex::new(rx));
@@ -395,7 +827,7 @@ impl AudioTab {
         });
 
         let rx_timeout = Arc::clone(&rx_arc);
-        glib::timeout_add_local(Duration::from_millis(100), move || {
+        add_ui_timeout(Duration::from_millis(100), move || {
             let rx_guard = rx_timeout.lock().unwrap();
             match rx_guard.try_recv() {
                 Ok(result) => {
@@ -408,10 +840,12 @@ impl AudioTab {
                                 .set_active_id(Some(&settings.buffer_size.to_string()));
                         }
                         Err(e) => {
-                            println!("Failed to detect current audio settings: {}", e);
+                            log::warn!("Failed to detect current audio settings: {}", e);
                             sample_rate_combo.set_active_id(Some("48000"));
                             bit_depth_combo.set_active_id(Some("24"));
                             buffer_size_combo.set_active_id(Some("512"));
+                            status_label.set_text(DETECTION_FAILED_TEXT);
+                            status_label.set_tooltip_text(Some(&e));
                         }
                     }
                     ControlFlow::Break
@@ -421,6 +855,7 @@ impl AudioTab {
                     sample_rate_combo.set_active_id(Some("48000"));
                     bit_depth_combo.set_active_id(Some("24"));
                     buffer_size_combo.set_active_id(Some("512"));
+                    status_label.set_text(DETECTION_FAILED_TEXT);
                     ControlFlow::Break
                 }
             }
@@ -428,11 +863,27 @@ impl AudioTab {
     }
 
     pub fn setup_signals(&self, app_state: AudioApp) {
+        // Presets only fill in the combos; the device and scope stay as they are
+        {
+            let tab = self.clone();
+            self.preset_picker
+                .connect_preset_selected(move |preset| tab.select_format(&preset.settings));
+
+            let tab = self.clone();
+            self.preset_picker
+                .connect_save(&self.status_label, move |name| {
+                    Preset::new(name, tab.selected_settings(), None)
+                });
+        }
+
         let status_label = self.status_label.clone();
-        let apply_button = self.apply_button.clone();
         let sample_rate_combo = self.sample_rate_combo.clone();
         let bit_depth_combo = self.bit_depth_combo.clone();
         let buffer_size_combo = self.buffer_size_combo.clone();
+        let period_size_combo = self.period_size_combo.clone();
+        let channel_layout_combo = self.channel_layout_combo.clone();
+        let device_description_entry = self.device_description_entry.clone();
+        let direct_alsa_checkbox = self.direct_alsa_checkbox.clone();
         let device_combo = self.device_combo.clone();
         let current_device_label = self.current_device_label.clone();
         let current_default_device = Arc::clone(&self.current_default_device);
@@ -447,167 +898,693 @@ impl AudioTab {
             let mut prefs = preferences_clone.lock().unwrap();
             prefs.system_wide_config = system_wide;
 
-            println!(
-                "DEBUG: Checkbox toggled - system_wide: {}, saving preferences...",
+            log::debug!(
+                "Checkbox toggled - system_wide: {}, saving preferences...",
                 system_wide
             );
 
-            if let Err(e) = AppPreferences::save(&prefs) {
-                println!("Warning: Failed to save preferences: {}", e);
+            if let Err(e) = AppPreferences::update(|stored| stored.system_wide_config = system_wide)
+            {
+                log::warn!("Failed to save preferences: {}", e);
             } else {
-                println!("DEBUG: Preferences saved successfully");
+                log::debug!("Preferences saved successfully");
             }
 
             if system_wide {
-                println!("System-wide configuration enabled");
+                log::info!("System-wide configuration enabled");
             } else {
-                println!("User-specific configuration enabled");
+                log::info!("User-specific configuration enabled");
+            }
+        });
+
+        self.keep_latency_checkbox.connect_toggled(|checkbox| {
+            let enabled = checkbox.is_active();
+            if let Err(e) = AppPreferences::update(|prefs| prefs.keep_latency_constant = enabled) {
+                log::warn!("Failed to save preferences: {}", e);
+            }
+        });
+
+        self.direct_alsa_checkbox.connect_toggled(|checkbox| {
+            let enabled = checkbox.is_active();
+            if let Err(e) = AppPreferences::update(|prefs| prefs.direct_alsa_config = enabled) {
+                log::warn!("Failed to save preferences: {}", e);
             }
         });
 
+        // Rescale the buffer from the rate it was chosen for
+        {
+            let buffer_size_combo = buffer_size_combo.clone();
+            let keep_latency_checkbox = self.keep_latency_checkbox.clone();
+            let previous_rate = Rc::new(Cell::new(
+                sample_rate_combo
+                    .active_id()
+                    .and_then(|id| id.parse::<u32>().ok()),
+            ));
+
+            sample_rate_combo.connect_changed(move |combo| {
+                let new_rate = combo.active_id().and_then(|id| id.parse::<u32>().ok());
+                let old_rate = previous_rate.replace(new_rate);
+
+                if !keep_latency_checkbox.is_active() {
+                    return;
+                }
+
+                let sizes: Vec<u32> = BUFFER_SIZES.iter().map(|(size, _)| *size).collect();
+                if let (Some(old_rate), Some(new_rate)) = (old_rate, new_rate)
+                    && let Some(buffer_size) = buffer_size_combo
+                        .active_id()
+                        .and_then(|id| id.parse::<u32>().ok())
+                    && let Some(scaled) =
+                        buffer_for_rate_change(buffer_size, old_rate, new_rate, &sizes)
+                    && scaled != buffer_size
+                {
+                    log::debug!(
+                        "Rate {} -> {}, buffer {} -> {} to keep latency",
+                        old_rate,
+                        new_rate,
+                        buffer_size,
+                        scaled
+                    );
+                    buffer_size_combo.set_active_id(Some(&scaled.to_string()));
+                }
+            });
+        }
+
         let tab_type_for_apply = self.tab_type.clone();
         let tab_type_for_device = self.tab_type.clone();
         let preferences_clone = Arc::clone(&self.preferences);
 
-        // Apply button click handler
-        self.apply_button.connect_clicked(move |_| {
-            let tab_type = tab_type_for_apply.clone();
+        // The rest of the apply, on the UI thread once the device checks are back
+        let continue_apply = {
+            let status_label = status_label.clone();
+            let app_state = app_state.clone();
+            Rc::new(move |settings: AudioSettings, checks: DeviceChecks| {
+                let tab_type = tab_type_for_apply.clone();
+
+                // The device may have been unplugged since the list was loaded
+                if let Some(message) = checks.missing_device {
+                    status_label.set_text(&format!(
+                        "Selected {} device is no longer available",
+                        tab_type.title().to_lowercase()
+                    ));
+                    show_error_dialog(&message);
+                    clear_cache();
+                    app_state.output_tab.detect_all_devices();
+                    app_state.input_tab.detect_all_devices();
+                    return;
+                }
 
-            let system_wide = {
-                let prefs = preferences_clone.lock().unwrap();
-                prefs.system_wide_config
-            };
+                let system_wide = {
+                    let prefs = preferences_clone.lock().unwrap();
+                    prefs.system_wide_config
+                };
 
-            if system_wide {
-                status_label.set_text(&format!("Applying system-wide {} settings... (May prompt for admin password)", tab_type.title().to_lowercase()));
-            } else {
-                status_label.set_text(&format!("Applying user {} settings...", tab_type.title().to_lowercase()));
-            }
+                let device_id = settings.device_id.clone();
 
-            apply_button.set_sensitive(false);
+                // PipeWire silently falls back to values the device supports
+                if let Some(message) = checks.unsupported_settings
+                    && !confirm_unsupported_settings(&message)
+                {
+                    status_label.set_text("Apply cancelled, settings not supported by the device");
+                    return;
+                }
 
-            let device_id = device_combo.active_id()
-                .map(|id| id.to_string())
-                .unwrap_or_else(|| "default".to_string());
+                // System files change behind the password prompt, show how first
+                if system_wide {
+                    match diff_pending_config(&settings, true) {
+                        Ok(diffs) if !diffs.is_empty() && !confirm_config_changes(&diffs) => {
+                            status_label.set_text("Apply cancelled, no files were changed");
+                            return;
+                        }
+                        Ok(_) => {}
+                        Err(e) => log::warn!("Could not compare the pending config: {}", e),
+                    }
+                }
+
+                if !confirm_service_restart(system_wide) {
+                    status_label.set_text("Apply cancelled, audio services were not restarted");
+                    return;
+                }
+
+                // Another tab's apply may still be restarting the services
+                if !begin_apply() {
+                    status_label.set_text(APPLY_BUSY_TEXT);
+                    return;
+                }
+
+                if system_wide {
+                    status_label.set_text(&format!(
+                        "Applying system-wide {} settings... (May prompt for admin password)",
+                        tab_type.title().to_lowercase()
+                    ));
+                } else {
+                    status_label.set_text(&format!(
+                        "Applying user {} settings...",
+                        tab_type.title().to_lowercase()
+                    ));
+                }
 
-            // Get the actual device name from the system
-            let actual_device_name = if device_id == "default" {
-                let detect_fn = tab_type.detect_current_device_fn();
-                match detect_fn() {
-                    Ok(device_info) => {
-                        let cleaned = device_info
-                            .replace("PipeWire:", "")
-                            .replace("PulseAudio:", "")
-                            .replace("ALSA:", "")
-                            .trim()
-                            .to_string();
-                        if cleaned.is_empty() || cleaned == "Unknown Output Audio Device"
-                            || cleaned == "Unknown Input Audio Device" {
+                // Get the actual device name from the system
+                let actual_device_name = if device_id == "default" {
+                    let detect_fn = tab_type.detect_current_device_fn();
+                    match detect_fn() {
+                        Ok(device_info) => {
+                            let cleaned = device_info
+                                .replace("PipeWire:", "")
+                                .replace("PulseAudio:", "")
+                                .replace("ALSA:", "")
+                                .trim()
+                                .to_string();
+                            if cleaned.is_empty()
+                                || cleaned == "Unknown Output Audio Device"
+                                || cleaned == "Unknown Input Audio Device"
+                            {
+                                None
+                            } else {
+                                log::info!("Using detected default device: {}", cleaned);
+                                Some(cleaned)
+                            }
+                        }
+                        Err(e) => {
+                            log::warn!("Could not detect default device: {}", e);
                             None
-                        } else {
-                            println!("Using detected default device: {}", cleaned);
-                            Some(cleaned)
                         }
                     }
-                    Err(e) => {
-                        println!("Could not detect default device: {}", e);
-                        None
+                } else {
+                    log::info!("Using selected device: {}", device_id);
+                    Some(device_id.clone())
+                };
+
+                let status_label_clone = status_label.clone();
+                let app_state_clone = app_state.clone();
+
+                let (tx, rx) = mpsc::channel();
+                let rx_arc = Arc::new(Mutex::new(rx));
+
+                let tab_type_thread = tab_type.clone();
+                let actual_device_name_clone = actual_device_name.clone();
+                let settings_clone = settings.clone();
+                let direct_alsa = direct_alsa_checkbox.is_active();
+
+                std::thread::spawn(move || {
+                    // System-wide apply only reports success; user apply reports an outcome
+                    let mut result = if system_wide {
+                        apply_fn(settings_clone.clone()).map(|_| None)
+                    } else {
+                        apply_user_audio_settings(
+                            settings_clone.clone(),
+                            &tab_type_thread.title().to_lowercase(),
+                        )
+                        .map(Some)
+                    };
+
+                    // Also apply device-specific WirePlumber config
+                    if let Some(ref device_name) = actual_device_name_clone {
+                        match apply_wireplumber_device_config(
+                            &settings_clone,
+                            &tab_type_thread.title().to_lowercase(),
+                            Some(device_name),
+                        ) {
+                            // e.g. 24-bit sent as S32LE on a device without packed formats
+                            Ok(Some(format_note)) => {
+                                if let Ok(Some(outcome)) = &mut result {
+                                    outcome.warnings.push(format_note);
+                                }
+                            }
+                            Ok(None) => {}
+                            Err(e) => log::info!("WirePlumber device config failed: {}", e),
+                        }
                     }
-                }
-            } else {
-                println!("Using selected device: {}", device_id);
-                Some(device_id.clone())
-            };
+
+                    // Opt-in ~/.asoundrc block for apps that bypass PipeWire
+                    let stream_type = tab_type_thread.title().to_lowercase();
+                    let direct_alsa_result = if direct_alsa {
+                        let device = actual_device_name_clone
+                            .as_deref()
+                            .unwrap_or(&settings_clone.device_id);
+                        apply_direct_alsa_config(&settings_clone, &stream_type, device)
+                            .map(|pcm| log::info!("✓ Direct ALSA apps can open \"{}\"", pcm))
+                    } else {
+                        remove_direct_alsa_config(&stream_type)
+                    };
+                    if let Err(e) = direct_alsa_result {
+                        log::info!("Direct ALSA config failed: {}", e);
+                        if let Ok(Some(outcome)) = &mut result {
+                            outcome
+                                .warnings
+                                .push(format!("Direct ALSA config not written: {}", e));
+                        }
+                    }
+
+                    // Remember what was applied so it can be verified later
+                    if result.is_ok()
+                        && let Err(e) = AppPreferences::update(|prefs| {
+                            prefs.set_last_applied(&tab_type_thread, &settings_clone)
+                        })
+                    {
+                        log::warn!("Failed to save last applied settings: {}", e);
+                    }
+
+                    let _ = tx.send(result);
+                });
+
+                let tab_type_timeout = tab_type.clone();
+                let status_label_timeout = status_label_clone.clone();
+                let app_state_timeout = app_state_clone.clone();
+
+                let rx_timeout: Arc<
+                    Mutex<mpsc::Receiver<Result<Option<ApplyOutcome>, AudioConfigError>>>,
+                > = Arc::clone(&rx_arc);
+                add_ui_timeout(Duration::from_millis(100), move || {
+                    let rx_guard = rx_timeout.lock().unwrap();
+                    match rx_guard.try_recv() {
+                        Ok(result) => {
+                            match result {
+                                Ok(outcome) => {
+                                    end_apply();
+                                    match outcome {
+                                        Some(outcome) if !outcome.verified => {
+                                            status_label_timeout.set_text(&format!(
+                                                "{} settings {}",
+                                                tab_type_timeout.title(),
+                                                outcome.summary().to_lowercase()
+                                            ));
+                                            show_success_dialog(&format!(
+                                                "{} audio settings were written, but the running audio system does not match yet:\n\n{}",
+                                                tab_type_timeout.title(),
+                                                outcome.warnings.join("\n")
+                                            ));
+                                        }
+                                        Some(outcome) => {
+                                            status_label_timeout.set_text(&format!(
+                                                "{} settings: {}",
+                                                tab_type_timeout.title(),
+                                                outcome.summary()
+                                            ));
+                                            if outcome.warnings.is_empty() {
+                                                show_success_dialog(&format!(
+                                                    "{} audio settings applied and confirmed.",
+                                                    tab_type_timeout.title()
+                                                ));
+                                            } else {
+                                                show_success_dialog(&format!(
+                                                    "{} audio settings applied and confirmed.\n\nNote:\n{}",
+                                                    tab_type_timeout.title(),
+                                                    outcome.warnings.join("\n")
+                                                ));
+                                            }
+                                        }
+                                        None => {
+                                            status_label_timeout.set_text(&format!(
+                                                "{} settings applied successfully!",
+                                                tab_type_timeout.title()
+                                            ));
+                                            show_success_dialog(&format!(
+                                                "{} audio settings applied successfully. The audio system will restart.",
+                                                tab_type_timeout.title()
+                                            ));
+                                        }
+                                    }
+
+                                    let app_state_redetect = app_state_timeout.clone();
+                                    let status_label_for_closure = status_label_timeout.clone();
+                                    let tab_type_for_redetect = tab_type_timeout.clone();
+                                    add_ui_timeout(Duration::from_secs(4), move || {
+                                        log::info!(
+                                            "Redetecting audio devices after service restart..."
+                                        );
+                                        app_state_redetect.output_tab.detect_all_devices();
+                                        app_state_redetect.input_tab.detect_all_devices();
+                                        app_state_redetect.output_tab.detect_current_device();
+                                        app_state_redetect.input_tab.detect_current_device();
+                                        app_state_redetect.output_tab.detect_current_settings();
+                                        app_state_redetect.input_tab.detect_current_settings();
+
+                                        status_label_for_closure.set_text(&format!(
+                                            "{} settings applied - devices updated",
+                                            tab_type_for_redetect.title()
+                                        ));
+                                        ControlFlow::Break
+                                    });
+                                }
+                                Err(e) => {
+                                    if matches!(e, AudioConfigError::AuthorizationCancelled) {
+                                        status_label_timeout.set_text(&e.to_string());
+                                    } else {
+                                        status_label_timeout.set_text(&format!(
+                                            "Failed to apply {} settings",
+                                            tab_type_timeout.title().to_lowercase()
+                                        ));
+                                    }
+                                    end_apply();
+                                    show_config_error_dialog(&e);
+                                }
+                            }
+                            ControlFlow::Break
+                        }
+                        Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+                        Err(mpsc::TryRecvError::Disconnected) => {
+                            status_label_timeout.set_text("Unexpected error");
+                            end_apply();
+                            show_error_dialog("Unexpected error occurred");
+                            ControlFlow::Break
+                        }
+                    }
+                });
+            })
+        };
+
+        // Apply button click handler
+        self.apply_button.connect_clicked(move |apply_button| {
+            let device_id = device_combo
+                .active_id()
+                .map(|id| id.to_string())
+                .unwrap_or_else(|| "default".to_string());
 
             let settings = AudioSettings {
-                sample_rate: sample_rate_combo.active_id()
+                sample_rate: sample_rate_combo
+                    .active_id()
                     .and_then(|id| id.parse::<u32>().ok())
                     .unwrap_or(48000),
-                bit_depth: bit_depth_combo.active_id()
+                bit_depth: bit_depth_combo
+                    .active_id()
                     .and_then(|id| id.parse::<u32>().ok())
                     .unwrap_or(24),
-                buffer_size: buffer_size_combo.active_id()
+                buffer_size: buffer_size_combo
+                    .active_id()
                     .and_then(|id| id.parse::<u32>().ok())
                     .unwrap_or(512),
                 device_id: device_id.clone(),
-            };
+                allowed_rates: Vec::new(),
+                node_description: None,
+                period_size: period_size_combo
+                    .active_id()
+                    .and_then(|id| id.parse::<u32>().ok()),
+                channels: channel_layout_combo
+                    .active_id()
+                    .and_then(|id| id.parse::<u32>().ok())
+                    .unwrap_or(2),
+                position: Vec::new(),
+            }
+            .with_node_description(&device_description_entry.text());
 
-            let status_label_clone = status_label.clone();
-            let apply_button_clone = apply_button.clone();
-            let app_state_clone = app_state.clone();
+            // Both checks run pw-dump/pw-cli, keep the UI responsive
+            status_label.set_text("Checking the selected device...");
+            apply_button.set_sensitive(false);
 
             let (tx, rx) = mpsc::channel();
             let rx_arc = Arc::new(Mutex::new(rx));
 
-            let tab_type_thread = tab_type.clone();
-            let actual_device_name_clone = actual_device_name.clone();
-            let settings_clone = settings.clone();
-
+            let check_settings = settings.clone();
             std::thread::spawn(move || {
-                let result = if system_wide {
-                    apply_fn(settings_clone.clone())
+                let missing_device = missing_device_message(&check_settings.device_id);
+                // Nothing to compare against once the device is gone
+                let unsupported_settings = if missing_device.is_none() {
+                    device_capability_message(&check_settings)
                 } else {
-                    apply_user_audio_settings(settings_clone.clone(), &tab_type_thread.title().to_lowercase())
+                    None
+                };
+                let _ = tx.send(DeviceChecks {
+                    missing_device,
+                    unsupported_settings,
+                });
+            });
+
+            let apply_button = apply_button.clone();
+            let status_label = status_label.clone();
+            let continue_apply = Rc::clone(&continue_apply);
+            add_ui_timeout(Duration::from_millis(100), move || {
+                let rx_guard = rx_arc.lock().unwrap();
+                match rx_guard.try_recv() {
+                    Ok(checks) => {
+                        apply_button.set_sensitive(true);
+                        continue_apply(settings.clone(), checks);
+                        ControlFlow::Break
+                    }
+                    Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+                    Err(mpsc::TryRecvError::Disconnected) => {
+                        apply_button.set_sensitive(true);
+                        status_label.set_text("Unexpected error");
+                        ControlFlow::Break
+                    }
+                }
+            });
+        });
+
+        // Favorite toggle follows the selected device
+        {
+            let favorite_button = self.favorite_button.clone();
+            let detected_devices = Arc::clone(&self.detected_devices);
+            self.device_combo.connect_changed(move |combo| {
+                let prefs = AppPreferences::load();
+                let selected = combo
+                    .active_id()
+                    .and_then(|id| find_device(&detected_devices, &id));
+
+                favorite_button.set_sensitive(selected.is_some());
+                favorite_button.set_label(match selected {
+                    Some(device) if prefs.is_favorite_device(&device) => FAVORITE_REMOVE_LABEL,
+                    _ => FAVORITE_ADD_LABEL,
+                });
+            });
+        }
+
+        {
+            let mixer_panel = self.mixer_panel.clone();
+            let tab = self.clone();
+            self.device_combo.connect_changed(move |combo| {
+                if let Some(id) = combo.active_id()
+                    && !id.starts_with("separator")
+                    && id != "no_devices"
+                {
+                    mixer_panel.load(&id);
+                    tab.load_device_capabilities(&id);
+                }
+            });
+        }
+
+        {
+            let device_combo = self.device_combo.clone();
+            let detected_devices = Arc::clone(&self.detected_devices);
+            let app_state = app_state.clone();
+            self.favorite_button.connect_clicked(move |_| {
+                let Some(device) = device_combo
+                    .active_id()
+                    .and_then(|id| find_device(&detected_devices, &id))
+                else {
+                    return;
                 };
 
-                // Also apply device-specific WirePlumber config
-                if let Some(ref device_name) = actual_device_name_clone
-                    && let Err(e) = apply_wireplumber_device_config(
-                        &settings_clone,
-                        &tab_type_thread.title().to_lowercase(),
-                        Some(device_name)
-                    ) {
-                        println!("Note: WirePlumber device config failed: {}", e);
+                if let Err(e) = AppPreferences::update(|prefs| {
+                    prefs.toggle_favorite_device(&device.name);
+                }) {
+                    show_error_dialog(&format!("Failed to save favorites: {}", e));
+                    return;
+                }
+
+                // Favorites are grouped in every tab's device list
+                app_state.output_tab.detect_all_devices();
+                app_state.input_tab.detect_all_devices();
+                app_state.advanced_tab.detect_advanced_devices();
+            });
+        }
+
+        // Revert one field of the last apply, then apply as usual
+        {
+            let tab = self.clone();
+            self.revert_button.connect_clicked(move |_| {
+                let prefs = AppPreferences::load();
+                let title = tab.tab_type.title().to_lowercase();
+                let (Some(current), Some(previous)) = (
+                    prefs.last_applied(&tab.tab_type).cloned(),
+                    prefs.previous_applied(&tab.tab_type).cloned(),
+                ) else {
+                    show_error_dialog(&format!(
+                        "There are no earlier {} settings to go back to. Reverting a single setting is possible after the second apply.",
+                        title
+                    ));
+                    return;
+                };
+
+                let fields = current.changed_fields(&previous);
+                let Some(field) = choose_setting_to_revert(&current, &previous, &fields) else {
+                    return;
+                };
+                let reverted = current.with_field_from(&previous, field);
+
+                if reverted.device_id != "default"
+                    && !tab.device_combo.set_active_id(Some(&reverted.device_id))
+                {
+                    show_error_dialog(&format!(
+                        "The {} device of the last apply ({}) is not connected.",
+                        title, reverted.device_id
+                    ));
+                    return;
+                }
+
+                tab.select_settings(&reverted, None, tab.system_wide_checkbox.is_active());
+                tab.device_description_entry
+                    .set_text(reverted.node_description.as_deref().unwrap_or_default());
+                log::info!(
+                    "Reverting {} {} to {}",
+                    title,
+                    field.label(),
+                    field.describe(&previous)
+                );
+                tab.apply_button.clicked();
+            });
+        }
+
+        // Preview click handler
+        {
+            let tab = self.clone();
+            self.preview_button.connect_clicked(move |button| {
+                let settings = tab.selected_settings();
+                let system_wide = tab.system_wide_checkbox.is_active();
+                match preview_audio_settings(&settings, system_wide) {
+                    Ok(files) => {
+                        let parent = button
+                            .toplevel()
+                            .and_then(|widget| widget.downcast::<gtk::Window>().ok());
+                        show_config_preview_dialog(
+                            parent.as_ref(),
+                            &files,
+                            &plan_restart(system_wide),
+                        );
                     }
+                    Err(e) => show_error_dialog(&format!("Cannot preview these settings: {}", e)),
+                }
+            });
+        }
 
-                let _ = tx.send(result);
+        // Set as Default click handler
+        {
+            let tab = self.clone();
+            self.default_button.connect_clicked(move |button| {
+                let Some(device_id) = tab
+                    .device_combo
+                    .active_id()
+                    .filter(|id| !id.starts_with("separator") && id != "no_devices")
+                else {
+                    show_error_dialog("Please select a device first.");
+                    return;
+                };
+                if device_id == "default" {
+                    tab.status_label
+                        .set_text("The default device is already selected");
+                    return;
+                }
+
+                button.set_sensitive(false);
+                tab.status_label.set_text("Setting the default device...");
+
+                let (tx, rx) = mpsc::channel();
+                let rx_arc = Arc::new(Mutex::new(rx));
+
+                let device_type = tab.tab_type.device_type();
+                let device_id_thread = device_id.to_string();
+                std::thread::spawn(move || {
+                    let _ = tx.send(set_default_device(&device_id_thread, device_type));
+                });
+
+                let tab = tab.clone();
+                let button = button.clone();
+                let rx_timeout = Arc::clone(&rx_arc);
+                add_ui_timeout(Duration::from_millis(100), move || {
+                    let rx_guard = rx_timeout.lock().unwrap();
+                    match rx_guard.try_recv() {
+                        Ok(result) => {
+                            button.set_sensitive(true);
+                            match result {
+                                Ok(()) => {
+                                    tab.status_label.set_text(&format!(
+                                        "{} is now the default {} device",
+                                        clean_display_text(
+                                            &tab.device_combo.active_text().unwrap_or_default()
+                                        ),
+                                        tab.tab_type.title().to_lowercase()
+                                    ));
+                                    tab.detect_current_device();
+                                }
+                                Err(e) => {
+                                    tab.status_label
+                                        .set_text("Failed to set the default device");
+                                    show_error_dialog(&format!(
+                                        "Failed to set {} as the default device: {}",
+                                        device_id, e
+                                    ));
+                                }
+                            }
+                            ControlFlow::Break
+                        }
+                        Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+                        Err(mpsc::TryRecvError::Disconnected) => {
+                            button.set_sensitive(true);
+                            tab.status_label.set_text("Unexpected error");
+                            ControlFlow::Break
+                        }
+                    }
+                });
             });
+        }
+
+        // Verify button click handler
+        let tab_type_for_verify = self.tab_type.clone();
+        let status_label_for_verify = self.status_label.clone();
+        self.verify_button.connect_clicked(move |button| {
+            let tab_type = tab_type_for_verify.clone();
+
+            let Some(expected) = AppPreferences::load().last_applied(&tab_type).cloned() else {
+                show_error_dialog(&format!(
+                    "No {} settings have been applied with Pro Audio Config yet, so there is nothing to verify.",
+                    tab_type.title().to_lowercase()
+                ));
+                return;
+            };
 
-            let tab_type_timeout = tab_type.clone();
-            let status_label_timeout = status_label_clone.clone();
-            let apply_button_timeout = apply_button_clone.clone();
-            let app_state_timeout = app_state_clone.clone();
+            button.set_sensitive(false);
+            status_label_for_verify.set_text(&format!("Verifying {} settings...", tab_type.title().to_lowercase()));
 
-            let rx_timeout: Arc<Mutex<mpsc::Receiver<Result<(), String>>>> = Arc::clone(&rx_arc);
-            glib::timeout_add_local(Duration::from_millis(100), move || {
+            let (tx, rx) = mpsc::channel();
+            let rx_arc = Arc::new(Mutex::new(rx));
+
+            let expected_thread = expected.clone();
+            std::thread::spawn(move || {
+                let _ = tx.send(verify_current_settings(&expected_thread));
+            });
+
+            let button_timeout = button.clone();
+            let status_label_timeout = status_label_for_verify.clone();
+            let rx_timeout = Arc::clone(&rx_arc);
+            add_ui_timeout(Duration::from_millis(100), move || {
                 let rx_guard = rx_timeout.lock().unwrap();
                 match rx_guard.try_recv() {
                     Ok(result) => {
+                        button_timeout.set_sensitive(true);
                         match result {
-                            Ok(()) => {
-                                status_label_timeout.set_text(&format!("{} settings applied successfully!", tab_type_timeout.title()));
-                                apply_button_timeout.set_sensitive(true);
-                                show_success_dialog(&format!("{} audio settings applied successfully. The audio system will restart.", tab_type_timeout.title()));
-
-                                let app_state_redetect = app_state_timeout.clone();
-                                let status_label_for_closure = status_label_timeout.clone();
-                                let tab_type_for_redetect = tab_type_timeout.clone();
-                                glib::timeout_add_local(Duration::from_secs(4), move || {
-                                    println!("Redetecting audio devices after service restart...");
-                                    app_state_redetect.output_tab.detect_all_devices();
-                                    app_state_redetect.input_tab.detect_all_devices();
-                                    app_state_redetect.output_tab.detect_current_device();
-                                    app_state_redetect.input_tab.detect_current_device();
-                                    app_state_redetect.output_tab.detect_current_settings();
-                                    app_state_redetect.input_tab.detect_current_settings();
-
-                                    status_label_for_closure.set_text(&format!("{} settings applied - devices updated", tab_type_for_redetect.title()));
-                                    ControlFlow::Break
-                                });
+                            Ok(checks) => {
+                                let report = checks.iter().map(|check| check.describe()).collect::<Vec<_>>().join("\n");
+                                let drifted: Vec<&str> = checks.iter().filter(|check| !check.matches).map(|check| check.field).collect();
+
+                                if drifted.is_empty() {
+                                    status_label_timeout.set_text(&format!("{} settings match the last applied configuration", tab_type.title()));
+                                    show_success_dialog(&format!("Running audio system matches the last applied {} settings:\n\n{}", tab_type.title().to_lowercase(), report));
+                                } else {
+                                    status_label_timeout.set_text(&format!("{} settings drifted: {}", tab_type.title(), drifted.join(", ")));
+                                    show_error_dialog(&format!("Running audio system differs from the last applied {} settings:\n\n{}", tab_type.title().to_lowercase(), report));
+                                }
                             }
                             Err(e) => {
-                                status_label_timeout.set_text(&format!("Failed to apply {} settings", tab_type_timeout.title().to_lowercase()));
-                                apply_button_timeout.set_sensitive(true);
-                                show_error_dialog(&format!("Failed to apply {} settings: {}", tab_type_timeout.title().to_lowercase(), e));
+                                status_label_timeout.set_text("Verification failed");
+                                show_error_dialog(&format!("Failed to verify {} settings: {}", tab_type.title().to_lowercase(), e));
                             }
                         }
                         ControlFlow::Break
                     }
                     Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
                     Err(mpsc::TryRecvError::Disconnected) => {
+                        button_timeout.set_sensitive(true);
                         status_label_timeout.set_text("Unexpected error");
-                        apply_button_timeout.set_sensitive(true);
-                        show_error_dialog("Unexpected error occurred");
                         ControlFlow::Break
                     }
                 }
@@ -661,3 +1638,42 @@ impl AudioTab {
         }
     }
 }
+
+/// Refills `combo` with the `options` that pass `supported`, or all of them
+/// when none do. The selection stays, or moves to the closest value left.
+fn refill_supported(
+    combo: &ComboBoxText,
+    options: &[(u32, &str)],
+    supported: impl Fn(u32) -> bool,
+) {
+    let mut shown: Vec<(u32, &str)> = options
+        .iter()
+        .copied()
+        .filter(|(value, _)| supported(*value))
+        .collect();
+    if shown.is_empty() {
+        shown = options.to_vec();
+    }
+
+    let previous = combo.active_id().and_then(|id| id.parse::<u32>().ok());
+    combo.remove_all();
+    populate_combo_box(combo, &shown);
+
+    if let Some(previous) = previous
+        && let Some((closest, _)) = shown
+            .iter()
+            .min_by_key(|(value, _)| (value.abs_diff(previous), *value))
+    {
+        combo.set_active_id(Some(&closest.to_string()));
+    }
+}
+
+/// Looks up a detected device by its combo id; separators and "default" give None
+fn find_device(devices: &Mutex<Vec<AudioDevice>>, id: &str) -> Option<AudioDevice> {
+    devices
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|device| device.id == id)
+        .cloned()
+}
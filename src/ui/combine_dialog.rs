@@ -0,0 +1,220 @@
+/*
+ * Pro Audio Config - Combined Output Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * Dialog for routing the same audio to several outputs at once
+ * (e.g. monitors and headphones) through a combined sink
+ */
+
+use glib::ControlFlow;
+use gtk::prelude::*;
+use gtk::{CheckButton, DialogFlags, Label, ResponseType};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::Duration;
+
+use super::app::AudioApp;
+use super::apply_guard::{APPLY_BUSY_TEXT, begin_apply, end_apply};
+use super::dialogs::{
+    confirm_service_restart, show_config_error_dialog, show_error_dialog, show_success_dialog,
+};
+use super::helpers::clean_device_description;
+use super::tasks::add_ui_timeout;
+use crate::audio::detect_output_audio_devices;
+use crate::config::{apply_combined_output, combined_output_members, remove_combined_output};
+use crate::error::AudioConfigError;
+
+/// Lets the user pick member outputs and creates (or removes) the combined sink
+pub fn show_combined_output_dialog(app_state: &AudioApp) {
+    let dialog = gtk::Dialog::with_buttons(
+        Some("Combined Output"),
+        Some(&app_state.window),
+        DialogFlags::MODAL,
+        &[
+            ("Cancel", ResponseType::Cancel),
+            ("Remove Combined Output", ResponseType::Reject),
+            ("Create Combined Output", ResponseType::Accept),
+        ],
+    );
+
+    let content = dialog.content_area();
+    content.set_spacing(6);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+
+    let info_label = Label::new(Some(
+        "Play the same audio on several outputs at once. Pick at least two devices; \
+         a \"Combined Output\" device is added that sends to all of them.",
+    ));
+    info_label.set_line_wrap(true);
+    info_label.set_halign(gtk::Align::Start);
+
+    let devices_box = gtk::Box::new(gtk::Orientation::Vertical, 2);
+    let loading_label = Label::new(Some("Detecting output devices..."));
+    loading_label.set_halign(gtk::Align::Start);
+    devices_box.pack_start(&loading_label, false, false, 0);
+
+    let default_checkbox = CheckButton::with_label("Make it the default output");
+    default_checkbox.set_active(true);
+
+    content.pack_start(&info_label, false, false, 0);
+    content.pack_start(&devices_box, false, false, 0);
+    content.pack_start(&default_checkbox, false, false, 6);
+
+    let member_checkboxes: Arc<Mutex<Vec<(String, CheckButton)>>> =
+        Arc::new(Mutex::new(Vec::new()));
+    load_member_devices(&devices_box, Arc::clone(&member_checkboxes));
+
+    let status_label = app_state.output_tab.status_label.clone();
+    dialog.connect_response(move |dialog, response| {
+        match response {
+            ResponseType::Accept => {
+                let members: Vec<String> = member_checkboxes
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .filter(|(_, checkbox)| checkbox.is_active())
+                    .map(|(name, _)| name.clone())
+                    .collect();
+
+                if members.len() < 2 {
+                    show_error_dialog("Select at least two output devices to combine.");
+                    return;
+                }
+                if !confirm_service_restart(false) {
+                    return;
+                }
+
+                let make_default = default_checkbox.is_active();
+                run_in_background(
+                    status_label.clone(),
+                    "Creating combined output...",
+                    move || apply_combined_output(&members, make_default),
+                    "Combined output created. It is listed as \"Combined Output\" among your output devices.",
+                );
+            }
+            ResponseType::Reject => {
+                if !confirm_service_restart(false) {
+                    return;
+                }
+
+                run_in_background(
+                    status_label.clone(),
+                    "Removing combined output...",
+                    remove_combined_output,
+                    "Combined output removed.",
+                );
+            }
+            _ => {}
+        }
+
+        dialog.close();
+    });
+
+    dialog.show_all();
+}
+
+fn load_member_devices(devices_box: &gtk::Box, checkboxes: Arc<Mutex<Vec<(String, CheckButton)>>>) {
+    let devices_box = devices_box.clone();
+
+    let (tx, rx) = mpsc::channel();
+    let rx_arc = Arc::new(Mutex::new(rx));
+
+    std::thread::spawn(move || {
+        let _ = tx.send((detect_output_audio_devices(), combined_output_members()));
+    });
+
+    let rx_timeout = Arc::clone(&rx_arc);
+    add_ui_timeout(Duration::from_millis(100), move || {
+        let rx_guard = rx_timeout.lock().unwrap();
+        match rx_guard.try_recv() {
+            Ok((result, current_members)) => {
+                for child in devices_box.children() {
+                    devices_box.remove(&child);
+                }
+
+                match result {
+                    Ok(devices) => {
+                        let mut checkboxes = checkboxes.lock().unwrap();
+                        for device in devices.iter().filter(|device| device.available) {
+                            let description = clean_device_description(&device.description);
+                            let label = if description.is_empty() {
+                                device.name.clone()
+                            } else {
+                                description
+                            };
+
+                            let checkbox = CheckButton::with_label(&label);
+                            checkbox.set_tooltip_text(Some(&device.name));
+                            checkbox.set_active(current_members.contains(&device.name));
+                            devices_box.pack_start(&checkbox, false, false, 0);
+                            checkboxes.push((device.name.clone(), checkbox));
+                        }
+                    }
+                    Err(e) => {
+                        let error_label =
+                            Label::new(Some(&format!("Could not detect output devices: {}", e)));
+                        error_label.set_halign(gtk::Align::Start);
+                        devices_box.pack_start(&error_label, false, false, 0);
+                    }
+                }
+
+                devices_box.show_all();
+                ControlFlow::Break
+            }
+            Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+            Err(mpsc::TryRecvError::Disconnected) => ControlFlow::Break,
+        }
+    });
+}
+
+/// Runs a config change off the UI thread and reports the result
+fn run_in_background<F>(
+    status_label: Label,
+    progress_text: &str,
+    action: F,
+    success_text: &'static str,
+) where
+    F: FnOnce() -> Result<(), AudioConfigError> + Send + 'static,
+{
+    if !begin_apply() {
+        show_error_dialog(APPLY_BUSY_TEXT);
+        return;
+    }
+    status_label.set_text(progress_text);
+
+    let (tx, rx) = mpsc::channel();
+    let rx_arc = Arc::new(Mutex::new(rx));
+
+    std::thread::spawn(move || {
+        let _ = tx.send(action());
+    });
+
+    let rx_timeout = Arc::clone(&rx_arc);
+    add_ui_timeout(Duration::from_millis(100), move || {
+        let rx_guard = rx_timeout.lock().unwrap();
+        match rx_guard.try_recv() {
+            Ok(Ok(())) => {
+                end_apply();
+                status_label.set_text(success_text);
+                show_success_dialog(success_text);
+                ControlFlow::Break
+            }
+            Ok(Err(e)) => {
+                end_apply();
+                status_label.set_text("Combined output change failed");
+                show_config_error_dialog(&e);
+                ControlFlow::Break
+            }
+            Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                end_apply();
+                ControlFlow::Break
+            }
+        }
+    });
+}
@@ -10,7 +10,19 @@
  */
 
 use gtk::prelude::*;
-use gtk::{Box as GtkBox, ComboBoxText, Frame, Label, Orientation, Separator};
+use gtk::{Box as GtkBox, ComboBoxText, Expander, Frame, Label, Orientation, Separator};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Compact layout flag, set once from preferences before the window is built
+static COMPACT_MODE: AtomicBool = AtomicBool::new(false);
+
+pub fn set_compact_mode(enabled: bool) {
+    COMPACT_MODE.store(enabled, Ordering::SeqCst);
+}
+
+pub fn is_compact_mode() -> bool {
+    COMPACT_MODE.load(Ordering::SeqCst)
+}
 
 /// Helper function to create constrained combo boxes that don't expand too wide
 pub fn create_constrained_combo() -> ComboBoxText {
@@ -28,7 +40,12 @@ pub fn populate_combo_box(combo: &ComboBoxText, options: &[(u32, &str)]) {
     }
 }
 
+/// Creates a titled section. In compact mode the section collapses into an expander.
 pub fn create_section_box(title: &str) -> (Frame, GtkBox) {
+    if is_compact_mode() {
+        return create_collapsible_section_box(title, true);
+    }
+
     let frame = Frame::new(None);
     frame.set_margin_top(6);
     frame.set_margin_bottom(6);
@@ -52,3 +69,37 @@ pub fn create_section_box(title: &str) -> (Frame, GtkBox) {
 
     (frame, section_box)
 }
+
+/// Collapsible variant of `create_section_box` with tighter margins
+pub fn create_collapsible_section_box(title: &str, expanded: bool) -> (Frame, GtkBox) {
+    let frame = Frame::new(None);
+    frame.set_margin_top(2);
+    frame.set_margin_bottom(2);
+
+    let expander = Expander::new(None);
+    expander.set_expanded(expanded);
+    expander.set_margin_top(4);
+    expander.set_margin_bottom(4);
+    expander.set_margin_start(6);
+    expander.set_margin_end(6);
+
+    let title_label = Label::new(None);
+    title_label.set_markup(&format!("<b>{}</b>", title));
+    expander.set_label_widget(Some(&title_label));
+
+    let section_box = GtkBox::new(Orientation::Vertical, 6);
+    section_box.set_margin_top(4);
+
+    expander.add(&section_box);
+    frame.add(&expander);
+
+    (frame, section_box)
+}
+
+/// Hides explanatory labels when compact mode is active
+pub fn mark_verbose_label(label: &Label) {
+    if is_compact_mode() {
+        label.set_no_show_all(true);
+        label.hide();
+    }
+}
@@ -0,0 +1,115 @@
+/*
+ * Pro Audio Config - Config Preview Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * Read-only dialog listing generated config fragments, each with a button
+ * copying its content to the clipboard, and the restart the apply would run
+ */
+
+use gtk::prelude::*;
+use gtk::{
+    Button, Clipboard, DialogFlags, Frame, Label, Orientation, PolicyType, ResponseType,
+    ScrolledWindow, TextView,
+};
+use std::path::PathBuf;
+
+/// Shows `fragments` as (target path, file content) pairs, then the
+/// `restart_plan` command lines. Nothing is written or run; the copy buttons
+/// are for pasting into self-managed files.
+pub fn show_config_preview_dialog(
+    parent: Option<&gtk::Window>,
+    fragments: &[(PathBuf, String)],
+    restart_plan: &[String],
+) {
+    let dialog = gtk::Dialog::with_buttons(
+        Some("Preview Generated Config"),
+        parent,
+        DialogFlags::MODAL,
+        &[("Close", ResponseType::Close)],
+    );
+    dialog.set_default_size(800, 600);
+
+    let content = dialog.content_area();
+    content.set_spacing(6);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+
+    let status_label = Label::new(Some(if fragments.is_empty() {
+        "Nothing would be written."
+    } else {
+        "Nothing has been written yet. Copy a fragment to manage it yourself."
+    }));
+    status_label.set_halign(gtk::Align::Start);
+    status_label.set_line_wrap(true);
+
+    let fragments_box = gtk::Box::new(Orientation::Vertical, 12);
+
+    for (path, text) in fragments {
+        let path = path.display().to_string();
+        let frame = Frame::new(Some(&path));
+        let frame_box = gtk::Box::new(Orientation::Vertical, 4);
+        frame_box.set_margin_top(4);
+        frame_box.set_margin_bottom(6);
+        frame_box.set_margin_start(6);
+        frame_box.set_margin_end(6);
+
+        let text_view = TextView::new();
+        text_view.set_editable(false);
+        text_view.set_monospace(true);
+        text_view.set_left_margin(6);
+        if let Some(buffer) = text_view.buffer() {
+            buffer.set_text(text);
+        }
+
+        let copy_button = Button::with_label("Copy");
+        copy_button.set_halign(gtk::Align::End);
+        copy_button.set_tooltip_text(Some("Copy this fragment's content to the clipboard"));
+        {
+            let text = text.clone();
+            let status_label = status_label.clone();
+            copy_button.connect_clicked(move |_| {
+                let clipboard = Clipboard::get(&gtk::gdk::SELECTION_CLIPBOARD);
+                clipboard.set_text(&text);
+                status_label.set_text(&format!("Copied the content of {}", path));
+            });
+        }
+
+        frame_box.pack_start(&text_view, false, false, 0);
+        frame_box.pack_start(&copy_button, false, false, 0);
+        frame.add(&frame_box);
+        fragments_box.pack_start(&frame, false, false, 0);
+    }
+
+    if !fragments.is_empty() && !restart_plan.is_empty() {
+        let frame = Frame::new(Some("Then the audio services are restarted with"));
+        let text_view = TextView::new();
+        text_view.set_editable(false);
+        text_view.set_monospace(true);
+        text_view.set_left_margin(6);
+        text_view.set_margin_top(4);
+        text_view.set_margin_bottom(6);
+        if let Some(buffer) = text_view.buffer() {
+            buffer.set_text(&restart_plan.join("\n"));
+        }
+        frame.add(&text_view);
+        fragments_box.pack_start(&frame, false, false, 0);
+    }
+
+    let scrolled = ScrolledWindow::new(None::<&gtk::Adjustment>, None::<&gtk::Adjustment>);
+    scrolled.set_policy(PolicyType::Automatic, PolicyType::Automatic);
+    scrolled.add(&fragments_box);
+
+    content.pack_start(&status_label, false, false, 0);
+    content.pack_start(&scrolled, true, true, 0);
+
+    dialog.connect_response(|dialog, _| {
+        dialog.close();
+    });
+
+    dialog.show_all();
+}
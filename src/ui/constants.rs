@@ -34,6 +34,60 @@ pub const BUFFER_SIZES: &[(u32, &str)] = &[
     (8192, "8192 samples (170.7ms @48kHz)"),
 ];
 
+/// ALSA hardware period sizes; the period can be smaller than the graph quantum
+pub const PERIOD_SIZES: &[(u32, &str)] = &[
+    (32, "32 samples"),
+    (64, "64 samples"),
+    (128, "128 samples"),
+    (256, "256 samples"),
+    (512, "512 samples"),
+    (1024, "1024 samples"),
+    (2048, "2048 samples"),
+    (4096, "4096 samples"),
+    (8192, "8192 samples"),
+];
+
+/// Monitor update intervals in ms; fast for catching peaks, slow to save power
+pub const MONITOR_INTERVALS: &[(u32, &str)] = &[
+    (20, "20 ms - Catch transients"),
+    (50, "50 ms - Fast"),
+    (100, "100 ms - Default"),
+    (200, "200 ms - Relaxed"),
+    (1000, "1 s - Power saving"),
+];
+
+/// rt.time.soft/hard choices in µs: CPU time a realtime audio thread may use
+/// without blocking before the kernel signals (soft) or kills (hard) it
+pub const RT_TIME_LIMITS: &[(u32, &str)] = &[
+    (100000, "100 ms"),
+    (200000, "200 ms - Default"),
+    (500000, "500 ms"),
+    (1000000, "1 s"),
+    (2000000, "2 s - Heavy DSP"),
+];
+
+/// priority.driver/priority.session choices; the device with the highest
+/// driver priority becomes the graph driver
+pub const NODE_PRIORITIES: &[(u32, &str)] = &[
+    (200, "200 - Default"),
+    (1000, "1000"),
+    (1500, "1500"),
+    (2000, "2000 - Above onboard audio"),
+    (3000, "3000"),
+    (5000, "5000 - Always preferred"),
+];
+
+/// session.suspend-timeout-seconds choices of the professional apply
+pub const SUSPEND_TIMEOUTS: &[(u32, &str)] = &[
+    (0, "Never suspend"),
+    (1, "1 s"),
+    (3, "3 s"),
+    (5, "5 s - Default"),
+    (10, "10 s"),
+    (30, "30 s"),
+    (60, "60 s"),
+];
+
 pub const EXCLUSIVE_BUFFER_SIZES: &[(u32, &str)] = &[
     (64, "64 samples (1.3ms @48kHz) - Ultra Low Latency"),
     (128, "128 samples (2.7ms @48kHz) - Low Latency"),
@@ -42,7 +96,29 @@ pub const EXCLUSIVE_BUFFER_SIZES: &[(u32, &str)] = &[
     (1024, "1024 samples (21.3ms @48kHz) - High Latency"),
 ];
 
+pub const CHANNEL_LAYOUTS: &[(u32, &str)] = &[
+    (1, "1 channel - Mono (mic)"),
+    (2, "2 channels - Stereo"),
+    (3, "3 channels - 2.1"),
+    (4, "4 channels - 4.0 Quad"),
+    (6, "6 channels - 5.1 Surround"),
+    (8, "8 channels - 7.1 Surround"),
+];
+
 pub const CONFIG_MODES: &[(&str, &str)] = &[
     ("global", "Global System Settings (All Applications)"),
     ("exclusive", "Exclusive Mode (Single Application)"),
+    ("jack", "JACK / Pro Audio Bridge"),
+];
+
+/// Periods per buffer of the jackd ALSA driver
+pub const JACK_PERIOD_COUNTS: &[(u32, &str)] = &[
+    (2, "2 periods - Default"),
+    (3, "3 periods - USB interfaces"),
+    (4, "4 periods - Safer on busy systems"),
+];
+
+pub const JACK_DRIVERS: &[(&str, &str)] = &[
+    ("alsa", "ALSA - Sound card"),
+    ("dummy", "Dummy - No hardware, for testing"),
 ];
@@ -0,0 +1,233 @@
+/*
+ * Pro Audio Config - Device Watch Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * Hot-plug detection: polls the device list in the background, refreshes
+ * the device combos of every tab when it changes and offers the profile
+ * bound to a device that was plugged in
+ */
+
+use glib::ControlFlow;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::app::AudioApp;
+use super::apply_guard::is_apply_in_progress;
+use super::dialogs::confirm_device_profile;
+use super::preferences::AppPreferences;
+use super::profiles_menu::apply_profile;
+use super::tasks::add_ui_timeout;
+use crate::audio::{AudioDevice, clear_cache, detect_all_audio_devices};
+
+/// How often the device list is read; detection itself is cached for 2 s
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Quiet time after the last change before the tabs are refreshed. Plugging
+/// in an interface adds its card, then its nodes and profiles one by one.
+const SETTLE_TIME: Duration = Duration::from_secs(3);
+
+/// How long a bound profile waits for the Output tab to list its device
+/// after the refresh before it is applied anyway (and reports it missing)
+const PROFILE_DEVICE_WAIT: Duration = Duration::from_secs(5);
+
+/// Starts watching for devices being plugged in or removed. Runs for the
+/// lifetime of the window; the poll thread ends once the UI side is gone.
+pub fn start_device_watch(app_state: &AudioApp) {
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let mut last = None;
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let Ok(devices) = detect_all_audio_devices() else {
+                continue;
+            };
+
+            let current = device_signature(&devices);
+            if let Some(last) = &last
+                && *last != current
+                && tx.send(plugged_devices(last, devices)).is_err()
+            {
+                break;
+            }
+            last = Some(current);
+        }
+    });
+
+    let app_state = app_state.clone();
+    let mut settle = SettleTimer::default();
+    let mut plugged = Vec::new();
+    add_ui_timeout(Duration::from_millis(500), move || {
+        match rx.try_recv() {
+            Ok(devices) => {
+                log::debug!("Device list changed, waiting for it to settle");
+                settle.changed(Instant::now());
+                plugged.extend(devices);
+            }
+            Err(mpsc::TryRecvError::Empty) => {}
+            Err(mpsc::TryRecvError::Disconnected) => return ControlFlow::Break,
+        }
+
+        // An apply restarts the services and re-detects when it is done
+        if !is_apply_in_progress() && settle.due(Instant::now()) {
+            log::info!("Audio devices changed, refreshing the device lists");
+            clear_cache();
+            app_state.output_tab.detect_all_devices();
+            app_state.input_tab.detect_all_devices();
+            app_state.advanced_tab.detect_advanced_devices();
+            offer_device_profiles(&app_state, &std::mem::take(&mut plugged));
+        }
+
+        ControlFlow::Continue
+    });
+}
+
+/// Applies, or asks about, the profile bound to each plugged-in device. A
+/// card shows up as several nodes, so each profile is offered once.
+fn offer_device_profiles(app_state: &AudioApp, plugged: &[AudioDevice]) {
+    let prefs = AppPreferences::load();
+    let mut offered: Vec<&str> = Vec::new();
+
+    for device in plugged {
+        let Some(rule) = prefs.profile_for_device(device) else {
+            continue;
+        };
+        if offered.contains(&rule.profile_name.as_str()) {
+            continue;
+        }
+        offered.push(&rule.profile_name);
+
+        let Some(profile) = prefs.profile(&rule.profile_name).cloned() else {
+            log::warn!(
+                "{} is bound to profile \"{}\", which no longer exists",
+                device.description,
+                rule.profile_name
+            );
+            continue;
+        };
+
+        log::info!(
+            "{} plugged in, bound to profile \"{}\"",
+            device.description,
+            profile.name
+        );
+        let app_state = app_state.clone();
+        let description = device.description.clone();
+        let auto_apply = prefs.auto_apply_device_profiles;
+        let started = Instant::now();
+        // The tabs re-detect in the background; the profile selects its
+        // device in the Output tab, so wait until it is listed there
+        add_ui_timeout(Duration::from_millis(200), move || {
+            let device_id = &profile.settings.device_id;
+            let listed = device_id == "default"
+                || app_state
+                    .output_tab
+                    .detected_devices
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .any(|device| device.id == *device_id);
+            if !listed && started.elapsed() < PROFILE_DEVICE_WAIT {
+                return ControlFlow::Continue;
+            }
+
+            if !is_apply_in_progress()
+                && (auto_apply || confirm_device_profile(&description, &profile.name))
+            {
+                apply_profile(&app_state, &profile);
+            }
+            ControlFlow::Break
+        });
+    }
+}
+
+/// Devices in `devices` that are available now but were missing or
+/// unavailable in the `last` signature
+fn plugged_devices(last: &[(String, bool)], devices: Vec<AudioDevice>) -> Vec<AudioDevice> {
+    devices
+        .into_iter()
+        .filter(|device| device.available && !last.contains(&(device.id.clone(), true)))
+        .collect()
+}
+
+/// What identifies a device list for change detection, independent of order
+fn device_signature(devices: &[AudioDevice]) -> Vec<(String, bool)> {
+    let mut signature: Vec<(String, bool)> = devices
+        .iter()
+        .map(|device| (device.id.clone(), device.available))
+        .collect();
+    signature.sort();
+    signature
+}
+
+/// Debounces change notifications: due once, `SETTLE_TIME` after the last one
+#[derive(Debug, Default)]
+struct SettleTimer {
+    last_change: Option<Instant>,
+}
+
+impl SettleTimer {
+    fn changed(&mut self, now: Instant) {
+        self.last_change = Some(now);
+    }
+
+    /// True once per burst of changes, when they have stopped for long enough
+    fn due(&mut self, now: Instant) -> bool {
+        match self.last_change {
+            Some(last_change) if now.duration_since(last_change) >= SETTLE_TIME => {
+                self.last_change = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settle_timer() {
+        let start = Instant::now();
+        let mut settle = SettleTimer::default();
+        assert!(!settle.due(start));
+
+        // Three changes from one plug event refresh once
+        settle.changed(start);
+        settle.changed(start + Duration::from_secs(2));
+        assert!(!settle.due(start + Duration::from_secs(4)));
+        settle.changed(start + Duration::from_secs(4));
+        assert!(!settle.due(start + Duration::from_secs(6)));
+        assert!(settle.due(start + Duration::from_secs(7)));
+        assert!(!settle.due(start + Duration::from_secs(20)));
+    }
+
+    #[test]
+    fn test_plugged_devices() {
+        let device = |id: &str, available: bool| AudioDevice {
+            name: format!("alsa_output.{}", id),
+            description: id.to_string(),
+            id: id.to_string(),
+            device_type: crate::audio::DeviceType::Output,
+            available,
+        };
+        let last = device_signature(&[device("onboard", true), device("hdmi", false)]);
+
+        let plugged = plugged_devices(
+            &last,
+            vec![
+                device("onboard", true),
+                device("hdmi", true),
+                device("scarlett", true),
+                device("dock", false),
+            ],
+        );
+        let ids: Vec<&str> = plugged.iter().map(|device| device.id.as_str()).collect();
+        assert_eq!(ids, ["hdmi", "scarlett"]);
+    }
+}
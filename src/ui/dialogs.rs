@@ -8,32 +8,73 @@
  */
 
 use gtk::prelude::*;
-use gtk::{AboutDialog, ButtonsType, DialogFlags, MessageDialog, MessageType, Window};
+use gtk::{
+    AboutDialog, ButtonsType, DialogFlags, Frame, MessageDialog, MessageType, Orientation,
+    PolicyType, RadioButton, ResponseType, ScrolledWindow, TextView, Window,
+};
+use std::path::PathBuf;
+
+use super::log_pane::reveal_log_pane;
+use super::preferences::AppPreferences;
+use crate::audio::{AudioSettings, SettingField, set_pipewire_remote};
+use crate::config::plan_restart;
+use crate::error::AudioConfigError;
 
 pub fn show_error_dialog(message: &str) {
+    show_failure_dialog(
+        MessageType::Error,
+        "Failed to apply audio settings",
+        message,
+    );
+}
+
+/// Error dialog worded for the kind of failure. A cancelled password prompt
+/// is only a note and a refused one a warning that nothing changed.
+pub fn show_config_error_dialog(error: &AudioConfigError) {
+    match error {
+        AudioConfigError::AuthorizationCancelled => {
+            let dialog = MessageDialog::new::<Window>(
+                None,
+                DialogFlags::MODAL,
+                MessageType::Info,
+                ButtonsType::Ok,
+                error.title(),
+            );
+            dialog.set_secondary_text(Some(&error.to_string()));
+            dialog.connect_response(|dialog, _| dialog.close());
+            dialog.show_all();
+        }
+        AudioConfigError::PrivilegeDenied(details) => show_failure_dialog(
+            MessageType::Warning,
+            error.title(),
+            &format!(
+                "No changes were made. System-wide settings need administrator \
+                 privileges; untick \"Apply system-wide\" to apply them for your \
+                 user only.\n\n{}",
+                details
+            ),
+        ),
+        _ => show_failure_dialog(MessageType::Error, error.title(), &error.to_string()),
+    }
+}
+
+fn show_failure_dialog(message_type: MessageType, heading: &str, message: &str) {
     let dialog = MessageDialog::new::<Window>(
         None,
         DialogFlags::MODAL,
-        MessageType::Error,
+        message_type,
         ButtonsType::Ok,
-        "Failed to apply audio settings",
+        heading,
     );
 
     dialog.set_title("Error Applying Settings");
+    dialog.set_secondary_text(Some(message));
+    dialog.add_button("Show Log", ResponseType::Help);
 
-    let display_message = if message.contains("Script failed") {
-        if let Some(error_part) = message.split("\n\n").nth(1) {
-            error_part.to_string()
-        } else {
-            message.to_string()
+    dialog.connect_response(|dialog, response| {
+        if response == ResponseType::Help {
+            reveal_log_pane();
         }
-    } else {
-        message.to_string()
-    };
-
-    dialog.set_secondary_text(Some(&display_message));
-
-    dialog.connect_response(|dialog, _| {
         dialog.close();
     });
 
@@ -58,6 +99,397 @@ pub fn show_success_dialog(message: &str) {
     dialog.show_all();
 }
 
+/// Asks before an action that restarts PipeWire/WirePlumber. Returns true
+/// when the action may go ahead (no prompt needed or the user agreed).
+pub fn confirm_service_restart(system_wide: bool) -> bool {
+    if !AppPreferences::load().restart_needs_confirmation(system_wide) {
+        return true;
+    }
+
+    let dialog = MessageDialog::new::<Window>(
+        None,
+        DialogFlags::MODAL,
+        MessageType::Warning,
+        ButtonsType::YesNo,
+        "This will interrupt audio. Continue?",
+    );
+
+    dialog.set_title("Restart Audio Services");
+    let explanation = if system_wide {
+        "Applying system-wide settings restarts PipeWire and WirePlumber. \
+         All running audio streams will drop out for a few seconds."
+    } else {
+        "Applying these settings restarts PipeWire and WirePlumber. \
+         All running audio streams will drop out for a few seconds."
+    };
+    dialog.set_secondary_text(Some(&format!(
+        "{}\n\nCommands that will run:\n{}",
+        explanation,
+        plan_restart(system_wide)
+            .iter()
+            .map(|command| format!("  {}", command))
+            .collect::<Vec<_>>()
+            .join("\n")
+    )));
+
+    if let Some(window) = get_main_window() {
+        dialog.set_transient_for(Some(&window));
+    }
+
+    let response = dialog.run();
+    dialog.close();
+
+    response == ResponseType::Yes
+}
+
+/// Shows `message` (from `unsupported_rate_message`) and asks whether to
+/// write the rates anyway. Returns true when the user wants to go ahead.
+pub fn confirm_unsupported_rates(message: &str) -> bool {
+    let dialog = MessageDialog::new::<Window>(
+        None,
+        DialogFlags::MODAL,
+        MessageType::Warning,
+        ButtonsType::YesNo,
+        "Sample rate not supported by this device. Apply anyway?",
+    );
+
+    dialog.set_title("Unsupported Sample Rate");
+    dialog.set_secondary_text(Some(message));
+
+    if let Some(window) = get_main_window() {
+        dialog.set_transient_for(Some(&window));
+    }
+
+    let response = dialog.run();
+    dialog.close();
+
+    response == ResponseType::Yes
+}
+
+/// Shows `message` (from `device_capability_message`) and asks whether to
+/// apply settings the device does not support. True means go ahead.
+pub fn confirm_unsupported_settings(message: &str) -> bool {
+    let dialog = MessageDialog::new::<Window>(
+        None,
+        DialogFlags::MODAL,
+        MessageType::Warning,
+        ButtonsType::YesNo,
+        "Settings not supported by this device. Apply anyway?",
+    );
+
+    dialog.set_title("Unsupported Settings");
+    dialog.set_secondary_text(Some(message));
+
+    if let Some(window) = get_main_window() {
+        dialog.set_transient_for(Some(&window));
+    }
+
+    let response = dialog.run();
+    dialog.close();
+
+    response == ResponseType::Yes
+}
+
+/// Explains the loopback setup before the latency test plays its signal.
+/// Returns true when the user wants to start.
+pub fn confirm_latency_measurement() -> bool {
+    let dialog = MessageDialog::new::<Window>(
+        None,
+        DialogFlags::MODAL,
+        MessageType::Question,
+        ButtonsType::OkCancel,
+        "Measure the round-trip latency?",
+    );
+
+    dialog.set_title("Measure Latency");
+    dialog.set_secondary_text(Some(
+        "Connect an output of the default output device to an input of the default \
+         input device with a cable, and turn direct/hardware monitoring off.\n\n\
+         The test plays a loud signal for a few seconds. Unplug speakers and \
+         headphones first.",
+    ));
+
+    if let Some(window) = get_main_window() {
+        dialog.set_transient_for(Some(&window));
+    }
+
+    let response = dialog.run();
+    dialog.close();
+
+    response == ResponseType::Ok
+}
+
+/// Offers the binary a running client reports instead of the entered process
+/// name. Returns true when the suggestion should be used.
+pub fn confirm_process_name_suggestion(entered: &str, suggestion: &str) -> bool {
+    let dialog = MessageDialog::new::<Window>(
+        None,
+        DialogFlags::MODAL,
+        MessageType::Question,
+        ButtonsType::YesNo,
+        &format!("Use \"{}\" as the process name?", suggestion),
+    );
+
+    dialog.set_title("Process Name");
+    dialog.set_secondary_text(Some(&format!(
+        "No running PipeWire client reports \"{}\" as its process binary, but one \
+         reports \"{}\". The exclusive rule only matches the exact name.",
+        entered, suggestion
+    )));
+
+    if let Some(window) = get_main_window() {
+        dialog.set_transient_for(Some(&window));
+    }
+
+    let response = dialog.run();
+    dialog.close();
+
+    response == ResponseType::Yes
+}
+
+/// Offers to switch the card to its pro-audio profile before exclusive mode is enabled
+pub fn confirm_pro_audio_profile(card_description: &str, current_profile: &str) -> bool {
+    let dialog = MessageDialog::new::<Window>(
+        None,
+        DialogFlags::MODAL,
+        MessageType::Question,
+        ButtonsType::YesNo,
+        &format!("Switch {} to the Pro Audio profile?", card_description),
+    );
+
+    dialog.set_title("Pro Audio Profile");
+    dialog.set_secondary_text(Some(&format!(
+        "The Pro Audio profile exposes every channel of the interface directly, \
+         without the mixing and channel mapping of \"{}\". \
+         The current profile is restored when exclusive mode is disabled.",
+        current_profile
+    )));
+
+    if let Some(window) = get_main_window() {
+        dialog.set_transient_for(Some(&window));
+    }
+
+    let response = dialog.run();
+    dialog.close();
+
+    response == ResponseType::Yes
+}
+
+/// Asks whether to apply the profile bound to a device that was just plugged in
+pub fn confirm_device_profile(device_description: &str, profile_name: &str) -> bool {
+    let dialog = MessageDialog::new::<Window>(
+        None,
+        DialogFlags::MODAL,
+        MessageType::Question,
+        ButtonsType::YesNo,
+        &format!("Apply profile \"{}\"?", profile_name),
+    );
+
+    dialog.set_title("Device Connected");
+    dialog.set_secondary_text(Some(&format!(
+        "{} was plugged in and is bound to this profile. Applying it restarts \
+         the audio services.",
+        device_description
+    )));
+
+    if let Some(window) = get_main_window() {
+        dialog.set_transient_for(Some(&window));
+    }
+
+    let response = dialog.run();
+    dialog.close();
+
+    response == ResponseType::Yes
+}
+
+/// Shows the unified diff of every system file an apply is about to change,
+/// before the password prompt. Returns true when the user wants to go ahead.
+pub fn confirm_config_changes(diffs: &[(PathBuf, String)]) -> bool {
+    let dialog = gtk::Dialog::with_buttons(
+        Some("Review Changes"),
+        get_main_window().as_ref(),
+        DialogFlags::MODAL,
+        &[
+            ("Cancel", ResponseType::Cancel),
+            ("Continue", ResponseType::Accept),
+        ],
+    );
+    dialog.set_default_size(800, 600);
+
+    let content = dialog.content_area();
+    content.set_spacing(6);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+
+    let label = gtk::Label::new(Some(&format!(
+        "Applying will change {} file(s) as shown below. Nothing has been written \
+         yet; the administrator password is asked for next.",
+        diffs.len()
+    )));
+    label.set_line_wrap(true);
+    label.set_halign(gtk::Align::Start);
+
+    let diffs_box = gtk::Box::new(Orientation::Vertical, 12);
+    for (path, diff) in diffs {
+        let frame = Frame::new(Some(&path.display().to_string()));
+        let text_view = TextView::new();
+        text_view.set_editable(false);
+        text_view.set_monospace(true);
+        text_view.set_left_margin(6);
+        if let Some(buffer) = text_view.buffer() {
+            buffer.set_text(diff);
+        }
+        frame.add(&text_view);
+        diffs_box.pack_start(&frame, false, false, 0);
+    }
+
+    let scrolled = ScrolledWindow::new(None::<&gtk::Adjustment>, None::<&gtk::Adjustment>);
+    scrolled.set_policy(PolicyType::Automatic, PolicyType::Automatic);
+    scrolled.add(&diffs_box);
+
+    content.pack_start(&label, false, false, 0);
+    content.pack_start(&scrolled, true, true, 0);
+
+    dialog.show_all();
+    let response = dialog.run();
+    dialog.close();
+
+    response == ResponseType::Accept
+}
+
+/// Lists the fields the last apply changed, each with its value before and
+/// after. Returns the field to put back, None when cancelled.
+pub fn choose_setting_to_revert(
+    current: &AudioSettings,
+    previous: &AudioSettings,
+    fields: &[SettingField],
+) -> Option<SettingField> {
+    let dialog = gtk::Dialog::with_buttons(
+        Some("Revert One Setting"),
+        get_main_window().as_ref(),
+        DialogFlags::MODAL,
+        &[
+            ("Cancel", ResponseType::Cancel),
+            ("Revert and Apply", ResponseType::Accept),
+        ],
+    );
+
+    let content = dialog.content_area();
+    content.set_spacing(6);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+
+    let label = gtk::Label::new(Some(
+        "Put one setting back to its value before the last apply. \
+         Everything else stays as it is now.",
+    ));
+    label.set_line_wrap(true);
+    label.set_halign(gtk::Align::Start);
+    content.pack_start(&label, false, false, 0);
+
+    let mut buttons: Vec<(SettingField, RadioButton)> = Vec::new();
+    for field in fields {
+        let text = format!(
+            "{}: {} back to {}",
+            field.label(),
+            field.describe(current),
+            field.describe(previous)
+        );
+        let button = match buttons.first() {
+            Some((_, group)) => RadioButton::with_label_from_widget(group, &text),
+            None => RadioButton::with_label(&text),
+        };
+        content.pack_start(&button, false, false, 0);
+        buttons.push((*field, button));
+    }
+
+    dialog.show_all();
+    let response = dialog.run();
+    let chosen = buttons
+        .iter()
+        .find(|(_, button)| button.is_active())
+        .map(|(field, _)| *field);
+    dialog.close();
+
+    if response == ResponseType::Accept {
+        chosen
+    } else {
+        None
+    }
+}
+
+/// Lets the user point all PipeWire clients at a non-default runtime dir or socket
+pub fn show_pipewire_remote_dialog() {
+    let prefs = AppPreferences::load();
+
+    let dialog = gtk::Dialog::with_buttons(
+        Some("PipeWire Connection"),
+        get_main_window().as_ref(),
+        DialogFlags::MODAL,
+        &[
+            ("Cancel", ResponseType::Cancel),
+            ("Save", ResponseType::Accept),
+        ],
+    );
+
+    let content = dialog.content_area();
+    content.set_spacing(6);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+
+    let info_label = gtk::Label::new(Some(
+        "Leave empty to use PIPEWIRE_RUNTIME_DIR / PIPEWIRE_REMOTE from the environment \
+         or the session default.",
+    ));
+    info_label.set_line_wrap(true);
+    info_label.set_halign(gtk::Align::Start);
+
+    let runtime_dir_entry = gtk::Entry::new();
+    runtime_dir_entry.set_placeholder_text(Some("Runtime directory, e.g. /run/user/1001"));
+    runtime_dir_entry.set_text(prefs.pipewire_runtime_dir.as_deref().unwrap_or(""));
+
+    let remote_entry = gtk::Entry::new();
+    remote_entry.set_placeholder_text(Some("Remote name, e.g. pipewire-0"));
+    remote_entry.set_text(prefs.pipewire_remote.as_deref().unwrap_or(""));
+
+    content.pack_start(&info_label, false, false, 0);
+    content.pack_start(&runtime_dir_entry, false, false, 0);
+    content.pack_start(&remote_entry, false, false, 0);
+
+    dialog.connect_response(move |dialog, response| {
+        if response == ResponseType::Accept {
+            let non_empty = |text: gtk::glib::GString| {
+                let text = text.trim().to_string();
+                (!text.is_empty()).then_some(text)
+            };
+            let runtime_dir = non_empty(runtime_dir_entry.text());
+            let remote = non_empty(remote_entry.text());
+
+            match AppPreferences::update(|prefs| {
+                prefs.pipewire_runtime_dir = runtime_dir.clone();
+                prefs.pipewire_remote = remote.clone();
+            }) {
+                Ok(()) => {
+                    set_pipewire_remote(AppPreferences::load().pipewire_remote());
+                    show_success_dialog(
+                        "PipeWire connection saved. Restart Pro Audio Config so monitoring reconnects.",
+                    );
+                }
+                Err(e) => show_error_dialog(&format!("Failed to save PipeWire connection: {}", e)),
+            }
+        }
+        dialog.close();
+    });
+
+    dialog.show_all();
+}
+
 pub fn show_about_dialog() {
     let dialog = AboutDialog::new();
 
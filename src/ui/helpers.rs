@@ -9,6 +9,24 @@
  * device descriptions, display text, and device names
  */
 
+use std::collections::HashMap;
+
+use crate::audio::{
+    AudioDevice, AudioSettings, DeviceType, get_device_capabilities, is_device_present,
+    validate_settings_against_device,
+};
+
+/// Icon and label shown in device combos; Unknown gets a neutral marker
+/// instead of pretending to be an output or input
+pub fn device_type_label(device_type: &DeviceType) -> &'static str {
+    match device_type {
+        DeviceType::Input => "🎤 Input",
+        DeviceType::Output => "🔊 Output",
+        DeviceType::Duplex => "🔄 Duplex",
+        DeviceType::Unknown => "❔ Unknown",
+    }
+}
+
 /// Helper function to clean device description by removing status words like "SUSPENDED"
 pub fn clean_device_description(description: &str) -> String {
     description
@@ -21,6 +39,38 @@ pub fn clean_device_description(description: &str) -> String {
         .to_string()
 }
 
+/// Combo text of a device. `discriminator` (card index, USB port) is only
+/// given for devices that would otherwise read the same as another one.
+pub fn device_display_text(device: &AudioDevice, discriminator: Option<&str>) -> String {
+    let device_type = device_type_label(&device.device_type);
+    let clean_description = clean_device_description(&device.description);
+
+    let text = if clean_description.is_empty() {
+        format!("{} {}", device_type, device.name)
+    } else {
+        format!("{} {} - {}", device_type, device.name, clean_description)
+    };
+
+    match discriminator {
+        Some(discriminator) => format!("{} ({})", text, discriminator),
+        None => text,
+    }
+}
+
+/// Ids of the devices whose combo text is the same as another device's
+pub fn colliding_device_ids(devices: &[AudioDevice]) -> Vec<String> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for device in devices {
+        *counts.entry(device_display_text(device, None)).or_default() += 1;
+    }
+
+    devices
+        .iter()
+        .filter(|device| counts[&device_display_text(device, None)] > 1)
+        .map(|device| device.id.clone())
+        .collect()
+}
+
 /// Helper function to clean display text in the selection info
 pub fn clean_display_text(display_text: &str) -> String {
     display_text
@@ -42,3 +92,104 @@ pub fn clean_device_display(device_info: &str) -> String {
         .trim()
         .to_string()
 }
+
+/// Re-checks the selected device right before an apply. Returns the error to
+/// show when it disappeared (e.g. unplugged) after the device list was loaded.
+pub fn missing_device_message(device_id: &str) -> Option<String> {
+    match is_device_present(device_id) {
+        Ok(true) => None,
+        Ok(false) => Some(format!(
+            "The selected device ({}) is no longer available. It may have been unplugged.\n\n\
+             The device list has been refreshed, please select a device and apply again.",
+            device_id
+        )),
+        Err(e) => {
+            // Don't block applying just because the check itself failed
+            log::warn!("Could not check whether {} is present: {}", device_id, e);
+            None
+        }
+    }
+}
+
+/// Checks the rates about to be written to default.clock.rate/allowed-rates
+/// against the device's capabilities. Returns the warning to show when the
+/// device does not list some of them, since PipeWire then silently keeps
+/// running at another rate. Blocks on pw-cli/pw-dump, so call it off the
+/// UI thread.
+pub fn unsupported_rate_message(device_id: &str, rates: &[u32]) -> Option<String> {
+    let capabilities = match get_device_capabilities(device_id) {
+        Ok(capabilities) => capabilities,
+        Err(e) => {
+            log::warn!("Could not read capabilities of {}: {}", device_id, e);
+            return None;
+        }
+    };
+    // Generic default rates say nothing about what this device can do
+    if !capabilities.detected {
+        return None;
+    }
+
+    let unsupported = capabilities.unsupported_rates(rates);
+    if unsupported.is_empty() {
+        return None;
+    }
+
+    let format_rates = |rates: &[u32]| {
+        rates
+            .iter()
+            .map(|rate| format!("{} Hz", rate))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    Some(format!(
+        "The selected device ({}) does not list {} among its supported sample rates.\n\n\
+         Supported rates: {}\n\n\
+         PipeWire will keep running at a supported rate instead, and verification \
+         will report a mismatch.",
+        device_id,
+        format_rates(&unsupported),
+        format_rates(&capabilities.sample_rates)
+    ))
+}
+
+/// Checks the Output/Input settings against the capabilities of their
+/// device. Returns the message to confirm when the device can't run them;
+/// None when they fit or the capabilities could not be read.
+pub fn device_capability_message(settings: &AudioSettings) -> Option<String> {
+    let capabilities = match get_device_capabilities(&settings.device_id) {
+        Ok(capabilities) => capabilities,
+        Err(e) => {
+            log::warn!(
+                "Could not read capabilities of {}: {}",
+                settings.device_id,
+                e
+            );
+            return None;
+        }
+    };
+
+    let problems = validate_settings_against_device(settings, &capabilities).err()?;
+    let rates = capabilities
+        .sample_rates
+        .iter()
+        .map(|rate| format!("{} Hz", rate))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    Some(format!(
+        "The selected device ({}) does not support these settings:\n\n{}\n\n\
+         Supported rates: {}\nBuffer sizes: {} to {} samples\n\n\
+         PipeWire will run the device with values it supports instead, and \
+         verification will report a mismatch.",
+        settings.device_id,
+        problems
+            .iter()
+            .map(|problem| format!("• {}", problem))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        rates,
+        capabilities.min_buffer_size,
+        capabilities.max_buffer_size
+    ))
+}
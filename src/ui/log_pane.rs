@@ -0,0 +1,128 @@
+/*
+ * Pro Audio Config - Log Pane Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * Collapsible pane at the bottom of the window showing the recent output
+ * that desktop-launched users would otherwise never see
+ */
+
+use glib::ControlFlow;
+use gtk::prelude::*;
+use gtk::{
+    Adjustment, Box as GtkBox, Button, Expander, Orientation, PolicyType, ScrolledWindow, TextView,
+    WrapMode,
+};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+
+use super::tasks::add_ui_timeout;
+use crate::log_capture::{MAX_LOG_LINES, log_lines_since, log_text};
+
+thread_local! {
+    /// The window's log pane, so error dialogs can open it
+    static LOG_EXPANDER: RefCell<Option<Expander>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone)]
+pub struct LogPane {
+    pub container: Expander,
+    pub text_view: TextView,
+    pub copy_button: Button,
+}
+
+impl LogPane {
+    pub fn new() -> Self {
+        let container = Expander::new(Some("Log"));
+        container.set_margin_start(6);
+        container.set_margin_end(6);
+        container.set_margin_bottom(4);
+
+        let pane_box = GtkBox::new(Orientation::Vertical, 4);
+
+        let text_view = TextView::new();
+        text_view.set_editable(false);
+        text_view.set_cursor_visible(false);
+        text_view.set_monospace(true);
+        text_view.set_wrap_mode(WrapMode::WordChar);
+
+        let scrolled = ScrolledWindow::new(None::<&Adjustment>, None::<&Adjustment>);
+        scrolled.set_policy(PolicyType::Automatic, PolicyType::Automatic);
+        scrolled.set_min_content_height(160);
+        scrolled.add(&text_view);
+
+        let copy_button = Button::with_label("Copy Log");
+        copy_button.set_halign(gtk::Align::End);
+        copy_button.connect_clicked(|_| {
+            let clipboard = gtk::Clipboard::get(&gtk::gdk::SELECTION_CLIPBOARD);
+            clipboard.set_text(&log_text());
+        });
+
+        pane_box.pack_start(&scrolled, true, true, 0);
+        pane_box.pack_start(&copy_button, false, false, 0);
+        container.add(&pane_box);
+
+        LOG_EXPANDER.with(|expander| *expander.borrow_mut() = Some(container.clone()));
+
+        let pane = Self {
+            container,
+            text_view,
+            copy_button,
+        };
+        pane.start_updates();
+        pane
+    }
+
+    /// Appends new log lines twice a second, keeping the view scrolled to the
+    /// end and no longer than the capture buffer
+    fn start_updates(&self) {
+        let text_view = self.text_view.clone();
+        let next_seq = Rc::new(Cell::new(0u64));
+
+        add_ui_timeout(Duration::from_millis(500), move || {
+            let (lines, next) = log_lines_since(next_seq.get());
+            next_seq.set(next);
+            if lines.is_empty() {
+                return ControlFlow::Continue;
+            }
+
+            if let Some(buffer) = text_view.buffer() {
+                let mut end = buffer.end_iter();
+                for line in &lines {
+                    buffer.insert(&mut end, line);
+                    buffer.insert(&mut end, "\n");
+                }
+
+                let excess = buffer.line_count() - 1 - MAX_LOG_LINES as i32;
+                if excess > 0 {
+                    let mut start = buffer.start_iter();
+                    let mut cut = buffer.iter_at_line(excess);
+                    buffer.delete(&mut start, &mut cut);
+                }
+
+                let mut end = buffer.end_iter();
+                text_view.scroll_to_iter(&mut end, 0.0, false, 0.0, 1.0);
+            }
+
+            ControlFlow::Continue
+        });
+    }
+}
+
+impl Default for LogPane {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Expands the log pane so the output around an error is visible
+pub fn reveal_log_pane() {
+    LOG_EXPANDER.with(|expander| {
+        if let Some(expander) = expander.borrow().as_ref() {
+            expander.set_expanded(true);
+        }
+    });
+}
@@ -0,0 +1,155 @@
+/*
+ * Pro Audio Config - Mixer Controls Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * Expander listing the ALSA mixer controls of the selected interface
+ * (direct monitoring, gain, phantom power...), which PipeWire configs
+ * cannot reach. On/off controls can be toggled.
+ */
+
+use glib::ControlFlow;
+use gtk::prelude::*;
+use gtk::{Box as GtkBox, CheckButton, Expander, Label, Orientation};
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::Duration;
+
+use super::dialogs::show_error_dialog;
+use super::tasks::add_ui_timeout;
+use crate::audio::{
+    MixerControl, alsa_card_for_device, alsa_mixer_controls, set_alsa_mixer_switch,
+};
+
+#[derive(Clone)]
+pub struct MixerControlsPanel {
+    pub container: Expander,
+    pub controls_box: GtkBox,
+    /// Bumped on every load so a slow earlier detection does not overwrite a newer one
+    generation: Rc<Cell<u64>>,
+}
+
+impl MixerControlsPanel {
+    pub fn new() -> Self {
+        let container = Expander::new(Some("Hardware Mixer Controls"));
+
+        let controls_box = GtkBox::new(Orientation::Vertical, 2);
+        controls_box.set_margin_start(12);
+        controls_box.set_margin_top(4);
+        container.add(&controls_box);
+
+        let panel = Self {
+            container,
+            controls_box,
+            generation: Rc::new(Cell::new(0)),
+        };
+        panel.show_message("Select a device to list its mixer controls");
+        panel
+    }
+
+    /// Lists the mixer controls of the card behind `device_id`
+    pub fn load(&self, device_id: &str) {
+        let generation = self.generation.get() + 1;
+        self.generation.set(generation);
+        self.show_message("Reading mixer controls...");
+
+        let device_id = device_id.to_string();
+        let (tx, rx) = mpsc::channel();
+        let rx_arc = Arc::new(Mutex::new(rx));
+
+        std::thread::spawn(move || {
+            let result = alsa_card_for_device(&device_id)
+                .and_then(|card| alsa_mixer_controls(card).map(|controls| (card, controls)));
+            let _ = tx.send(result);
+        });
+
+        let panel = self.clone();
+        let rx_timeout = Arc::clone(&rx_arc);
+        add_ui_timeout(Duration::from_millis(100), move || {
+            let rx_guard = rx_timeout.lock().unwrap();
+            match rx_guard.try_recv() {
+                Ok(result) => {
+                    if panel.generation.get() == generation {
+                        match result {
+                            Ok((card, controls)) => panel.show_controls(card, &controls),
+                            Err(e) => panel.show_message(&format!("No mixer controls: {}", e)),
+                        }
+                    }
+                    ControlFlow::Break
+                }
+                Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+                Err(mpsc::TryRecvError::Disconnected) => ControlFlow::Break,
+            }
+        });
+    }
+
+    fn clear(&self) {
+        for child in self.controls_box.children() {
+            self.controls_box.remove(&child);
+        }
+    }
+
+    fn show_message(&self, message: &str) {
+        self.clear();
+        let label = Label::new(Some(message));
+        label.set_halign(gtk::Align::Start);
+        label.set_line_wrap(true);
+        self.controls_box.pack_start(&label, false, false, 0);
+        self.controls_box.show_all();
+    }
+
+    fn show_controls(&self, card: u32, controls: &[MixerControl]) {
+        if controls.is_empty() {
+            self.show_message(&format!("ALSA card {} has no mixer controls", card));
+            return;
+        }
+
+        self.clear();
+        let header = Label::new(Some(&format!(
+            "ALSA card {} (read with amixer; changes take effect immediately and are not saved by this tool)",
+            card
+        )));
+        header.set_halign(gtk::Align::Start);
+        header.set_line_wrap(true);
+        self.controls_box.pack_start(&header, false, false, 0);
+
+        for control in controls {
+            match control.switch {
+                Some(active) => {
+                    let checkbox = CheckButton::with_label(&control.name);
+                    checkbox.set_active(active);
+                    checkbox.set_tooltip_text(Some(&control.capabilities.join(" ")));
+
+                    let control = control.clone();
+                    checkbox.connect_toggled(move |checkbox| {
+                        let on = checkbox.is_active();
+                        if let Err(e) = set_alsa_mixer_switch(card, &control, on) {
+                            show_error_dialog(&e);
+                        } else {
+                            log::info!("✓ Set mixer control {} {}", control.amixer_id(), on);
+                        }
+                    });
+                    self.controls_box.pack_start(&checkbox, false, false, 0);
+                }
+                None => {
+                    let label = Label::new(Some(&format!("{}: {}", control.name, control.value)));
+                    label.set_halign(gtk::Align::Start);
+                    label.set_selectable(true);
+                    label.set_tooltip_text(Some(&control.capabilities.join(" ")));
+                    self.controls_box.pack_start(&label, false, false, 0);
+                }
+            }
+        }
+
+        self.controls_box.show_all();
+    }
+}
+
+impl Default for MixerControlsPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
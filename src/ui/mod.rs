@@ -9,23 +9,55 @@
  * Organizes all UI submodules for the application
  */
 
+pub mod ab_compare_dialog;
 pub mod advanced_tab;
 pub mod app;
+pub mod apply_guard;
 pub mod audio_tab;
+pub mod combine_dialog;
 pub mod components;
+pub mod config_preview_dialog;
 pub mod constants;
+pub mod device_watch;
 pub mod dialogs;
 pub mod helpers;
+pub mod log_pane;
+pub mod mixer_controls;
 pub mod preferences;
+pub mod preset_picker;
+pub mod profiles_menu;
+pub mod pulse_resampler_dialog;
+pub mod safe_config;
 pub mod tab_types;
+pub mod tasks;
+pub mod virtual_devices_dialog;
+pub mod wizard;
 
 // Re-export main types for backward compatibility
 pub use advanced_tab::AdvancedTab;
 pub use app::AudioApp;
+pub use apply_guard::{begin_apply, end_apply, is_apply_in_progress};
 pub use audio_tab::AudioTab;
 pub use components::create_section_box;
+pub use config_preview_dialog::show_config_preview_dialog;
 pub use constants::*;
-pub use dialogs::{show_about_dialog, show_error_dialog, show_success_dialog};
-pub use helpers::{clean_device_description, clean_device_display, clean_display_text};
-pub use preferences::AppPreferences;
+pub use device_watch::start_device_watch;
+pub use dialogs::{
+    choose_setting_to_revert, confirm_config_changes, confirm_device_profile,
+    confirm_latency_measurement, confirm_pro_audio_profile, confirm_process_name_suggestion,
+    confirm_service_restart, confirm_unsupported_rates, confirm_unsupported_settings,
+    show_about_dialog, show_config_error_dialog, show_error_dialog, show_success_dialog,
+};
+pub use helpers::{
+    clean_device_description, clean_device_display, clean_display_text, colliding_device_ids,
+    device_capability_message, device_display_text, device_type_label, missing_device_message,
+    unsupported_rate_message,
+};
+pub use log_pane::{LogPane, reveal_log_pane};
+pub use mixer_controls::MixerControlsPanel;
+pub use preferences::{AppPreferences, DeviceProfileRule, ProfileRevert, SavedProfile};
+pub use preset_picker::PresetPicker;
+pub use safe_config::apply_minimal_safe_config_action;
 pub use tab_types::TabType;
+pub use tasks::{add_ui_timeout, cancel_ui_timeouts};
+pub use wizard::show_first_run_wizard;
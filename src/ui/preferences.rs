@@ -12,9 +12,103 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
 
+use super::tab_types::TabType;
+use crate::audio::{AudioDevice, AudioSettings, PipeWireRemote};
+use crate::audio_capture::DEFAULT_UPDATE_INTERVAL_MS;
+use crate::config::{DEFAULT_RT_TIME_US, ServiceNames};
+use crate::presets::Preset;
+
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct AppPreferences {
     pub system_wide_config: bool,
+    /// Collapsible sections and fewer explanatory labels for small screens
+    pub compact_mode: bool,
+    /// Profiles to apply when a matching device shows up
+    pub device_profiles: Vec<DeviceProfileRule>,
+    /// Apply matching device profiles without asking first
+    pub auto_apply_device_profiles: bool,
+    /// Settings from the last successful apply, used by "Verify Settings"
+    pub last_applied_output: Option<AudioSettings>,
+    pub last_applied_input: Option<AudioSettings>,
+    /// Settings the last apply replaced, for reverting a single field
+    pub previous_applied_output: Option<AudioSettings>,
+    pub previous_applied_input: Option<AudioSettings>,
+    /// Ask before any action that restarts audio services (always asked for system-wide)
+    pub confirm_service_restart: bool,
+    /// PipeWire runtime dir/socket to use instead of the session default
+    pub pipewire_runtime_dir: Option<String>,
+    pub pipewire_remote: Option<String>,
+    /// Also write pulse.* quantum limits so PulseAudio clients follow the buffer
+    pub sync_pulse_quantum: bool,
+    /// Settings applied by `--watch` while the watched process runs
+    pub watch_session_settings: Option<AudioSettings>,
+    /// True while `--watch` has the session settings applied
+    pub watch_session_active: bool,
+    /// Set once the first-run wizard was finished or skipped
+    pub wizard_completed: bool,
+    /// Node names pinned to the top of the device combos. Names rather than
+    /// ids because PipeWire object ids change on every replug and restart.
+    pub favorite_devices: Vec<String>,
+    /// Card profile to go back to when exclusive mode is disabled, set when
+    /// enabling it switched the card to the pro-audio profile
+    pub exclusive_profile_revert: Option<ProfileRevert>,
+    /// Rescale the buffer on sample rate changes so the latency in ms stays put
+    pub keep_latency_constant: bool,
+    /// Also write ~/.asoundrc defaults for apps that use ALSA directly
+    pub direct_alsa_config: bool,
+    /// Monitor meter refresh interval in ms; None uses the default
+    pub monitor_interval_ms: Option<u64>,
+    /// rt.time.soft/hard for libpipewire-module-rt in µs; None uses the default
+    pub rt_time_soft_us: Option<u32>,
+    pub rt_time_hard_us: Option<u32>,
+    /// Service names for restarts where the init system packages them
+    /// differently; None uses the usual name
+    pub pipewire_service: Option<String>,
+    pub pipewire_pulse_service: Option<String>,
+    pub wireplumber_service: Option<String>,
+    /// Named output settings for the Profiles menu, in menu order; the
+    /// first ones get Ctrl+1, Ctrl+2...
+    pub profiles: Vec<SavedProfile>,
+    /// Presets saved from the Output or Advanced tab, listed after the
+    /// built-in ones
+    pub custom_presets: Vec<Preset>,
+}
+
+/// Output tab settings saved under a name
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SavedProfile {
+    pub name: String,
+    pub settings: AudioSettings,
+}
+
+/// A card and the profile it had before the switch to pro-audio
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ProfileRevert {
+    /// device.name of the card
+    pub device_name: String,
+    pub profile_name: String,
+}
+
+/// Maps a device id/name pattern to the profile that should be applied for it
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct DeviceProfileRule {
+    /// Case-insensitive substring matched against device id, name and description
+    pub device_pattern: String,
+    pub profile_name: String,
+}
+
+impl DeviceProfileRule {
+    pub fn matches(&self, device: &AudioDevice) -> bool {
+        let pattern = self.device_pattern.trim().to_lowercase();
+        if pattern.is_empty() {
+            return false;
+        }
+
+        device.id.to_lowercase().contains(&pattern)
+            || device.name.to_lowercase().contains(&pattern)
+            || device.description.to_lowercase().contains(&pattern)
+    }
 }
 
 impl AppPreferences {
@@ -32,18 +126,190 @@ impl AppPreferences {
         AppPreferences::default()
     }
 
+    /// True once preferences were saved at least once; a missing file means first run
+    pub fn file_exists() -> bool {
+        directories::ProjectDirs::from("com", "proaudioconfig", "Pro Audio Config")
+            .map(|prefs_dir| prefs_dir.config_dir().join("preferences.toml").exists())
+            .unwrap_or(false)
+    }
+
+    /// Loads the stored preferences, applies `change` and saves them again.
+    /// Use this instead of saving a long-lived copy so other fields are not clobbered.
+    pub fn update<F: FnOnce(&mut Self)>(change: F) -> Result<(), String> {
+        let mut prefs = Self::load();
+        change(&mut prefs);
+        Self::save(&prefs)
+    }
+
+    /// System-wide applies always ask; user applies only when the preference is on
+    pub fn restart_needs_confirmation(&self, system_wide: bool) -> bool {
+        system_wide || self.confirm_service_restart
+    }
+
+    pub fn pipewire_remote(&self) -> PipeWireRemote {
+        PipeWireRemote {
+            runtime_dir: self.pipewire_runtime_dir.clone(),
+            remote: self.pipewire_remote.clone(),
+        }
+    }
+
+    /// Service names for restarts, overrides filled in with the defaults
+    pub fn service_names(&self) -> ServiceNames {
+        let defaults = ServiceNames::default();
+        let name = |value: &Option<String>, default: String| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+                .unwrap_or(default)
+        };
+        ServiceNames {
+            pipewire: name(&self.pipewire_service, defaults.pipewire),
+            pipewire_pulse: name(&self.pipewire_pulse_service, defaults.pipewire_pulse),
+            wireplumber: name(&self.wireplumber_service, defaults.wireplumber),
+        }
+    }
+
+    /// Monitor refresh interval, defaulted and kept to a sane range
+    pub fn monitor_interval_ms(&self) -> u64 {
+        self.monitor_interval_ms
+            .unwrap_or(DEFAULT_UPDATE_INTERVAL_MS)
+            .clamp(10, 5000)
+    }
+
+    /// (soft, hard) rt.time limits in µs, defaulted
+    pub fn rt_time_limits(&self) -> (u32, u32) {
+        (
+            self.rt_time_soft_us.unwrap_or(DEFAULT_RT_TIME_US),
+            self.rt_time_hard_us.unwrap_or(DEFAULT_RT_TIME_US),
+        )
+    }
+
+    pub fn last_applied(&self, tab_type: &TabType) -> Option<&AudioSettings> {
+        match tab_type {
+            TabType::Output => self.last_applied_output.as_ref(),
+            TabType::Input => self.last_applied_input.as_ref(),
+        }
+    }
+
+    pub fn previous_applied(&self, tab_type: &TabType) -> Option<&AudioSettings> {
+        match tab_type {
+            TabType::Output => self.previous_applied_output.as_ref(),
+            TabType::Input => self.previous_applied_input.as_ref(),
+        }
+    }
+
+    /// Records an apply; the settings it replaced become the previous ones
+    /// unless nothing revertible changed (e.g. a plain reapply)
+    pub fn set_last_applied(&mut self, tab_type: &TabType, settings: &AudioSettings) {
+        let (last, previous) = match tab_type {
+            TabType::Output => (
+                &mut self.last_applied_output,
+                &mut self.previous_applied_output,
+            ),
+            TabType::Input => (
+                &mut self.last_applied_input,
+                &mut self.previous_applied_input,
+            ),
+        };
+
+        if let Some(replaced) = last.replace(settings.clone())
+            && !settings.changed_fields(&replaced).is_empty()
+        {
+            *previous = Some(replaced);
+        }
+    }
+
+    pub fn is_favorite_device(&self, device: &AudioDevice) -> bool {
+        self.favorite_devices.contains(&device.name)
+    }
+
+    /// Adds or removes a favorite; returns whether the device is a favorite now
+    pub fn toggle_favorite_device(&mut self, device_name: &str) -> bool {
+        if let Some(index) = self
+            .favorite_devices
+            .iter()
+            .position(|name| name == device_name)
+        {
+            self.favorite_devices.remove(index);
+            false
+        } else {
+            self.favorite_devices.push(device_name.to_string());
+            true
+        }
+    }
+
+    pub fn profile(&self, name: &str) -> Option<&SavedProfile> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+
+    /// Adds a profile, or updates the one with this name in place so its
+    /// shortcut stays the same
+    pub fn save_profile(&mut self, name: &str, settings: &AudioSettings) {
+        match self
+            .profiles
+            .iter_mut()
+            .find(|profile| profile.name == name)
+        {
+            Some(profile) => profile.settings = settings.clone(),
+            None => self.profiles.push(SavedProfile {
+                name: name.to_string(),
+                settings: settings.clone(),
+            }),
+        }
+    }
+
+    pub fn remove_profile(&mut self, name: &str) {
+        self.profiles.retain(|profile| profile.name != name);
+    }
+
+    /// Adds a custom preset, replacing the one with the same name
+    pub fn save_preset(&mut self, preset: Preset) {
+        match self
+            .custom_presets
+            .iter_mut()
+            .find(|existing| existing.name == preset.name)
+        {
+            Some(existing) => *existing = preset,
+            None => self.custom_presets.push(preset),
+        }
+    }
+
+    /// Returns the first profile rule that matches the given device
+    pub fn profile_for_device(&self, device: &AudioDevice) -> Option<&DeviceProfileRule> {
+        self.device_profiles
+            .iter()
+            .find(|rule| rule.matches(device))
+    }
+
+    /// Adds or replaces the profile rule for a device pattern
+    pub fn set_device_profile(&mut self, device_pattern: &str, profile_name: &str) {
+        self.device_profiles
+            .retain(|rule| !rule.device_pattern.eq_ignore_ascii_case(device_pattern));
+        self.device_profiles.push(DeviceProfileRule {
+            device_pattern: device_pattern.to_string(),
+            profile_name: profile_name.to_string(),
+        });
+    }
+
+    pub fn remove_device_profile(&mut self, device_pattern: &str) {
+        self.device_profiles
+            .retain(|rule| rule.device_pattern != device_pattern);
+    }
+
     pub fn save(prefs: &Self) -> Result<(), String> {
         if let Some(prefs_dir) =
             directories::ProjectDirs::from("com", "proaudioconfig", "Pro Audio Config")
         {
             let config_dir = prefs_dir.config_dir();
-            println!("DEBUG: Saving preferences to: {}", config_dir.display());
+            log::debug!("Saving preferences to: {}", config_dir.display());
 
             fs::create_dir_all(config_dir)
                 .map_err(|e| format!("Failed to create config directory: {}", e))?;
 
             let prefs_path = config_dir.join("preferences.toml");
-            println!("DEBUG: Preferences file path: {}", prefs_path.display());
+            log::debug!("Preferences file path: {}", prefs_path.display());
 
             let content = toml::to_string(prefs)
                 .map_err(|e| format!("Failed to serialize preferences: {}", e))?;
@@ -51,11 +317,196 @@ impl AppPreferences {
             fs::write(&prefs_path, content)
                 .map_err(|e| format!("Failed to write preferences: {}", e))?;
 
-            println!(
-                "DEBUG: Preferences saved successfully to: {}",
+            log::debug!(
+                "Preferences saved successfully to: {}",
                 prefs_path.display()
             );
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::DeviceType;
+    use crate::config::AdvancedAudioSettings;
+
+    #[test]
+    fn test_device_profile_matching() {
+        let mut prefs = AppPreferences::default();
+        prefs.set_device_profile("scarlett", "tracking");
+        prefs.set_device_profile("Scarlett", "mixing");
+
+        assert_eq!(prefs.device_profiles.len(), 1);
+
+        let device = AudioDevice {
+            name: "alsa_output.usb-Focusrite_Scarlett_2i2".to_string(),
+            description: "Scarlett 2i2 USB".to_string(),
+            id: "pipewire:42".to_string(),
+            device_type: DeviceType::Duplex,
+            available: true,
+        };
+
+        let rule = prefs.profile_for_device(&device).unwrap();
+        assert_eq!(rule.profile_name, "mixing");
+
+        prefs.remove_device_profile("Scarlett");
+        assert!(prefs.profile_for_device(&device).is_none());
+    }
+
+    #[test]
+    fn test_favorite_devices() {
+        let mut prefs = AppPreferences::default();
+        let device = AudioDevice {
+            name: "alsa_output.usb-Focusrite_Scarlett_2i2".to_string(),
+            description: "Scarlett 2i2 USB".to_string(),
+            id: "pipewire:42".to_string(),
+            device_type: DeviceType::Output,
+            available: true,
+        };
+
+        assert!(!prefs.is_favorite_device(&device));
+        assert!(prefs.toggle_favorite_device(&device.name));
+        assert!(prefs.is_favorite_device(&device));
+
+        // The object id changes after a replug, the node name does not
+        let replugged = AudioDevice {
+            id: "pipewire:77".to_string(),
+            ..device.clone()
+        };
+        assert!(prefs.is_favorite_device(&replugged));
+
+        assert!(!prefs.toggle_favorite_device(&device.name));
+        assert!(prefs.favorite_devices.is_empty());
+    }
+
+    #[test]
+    fn test_saved_profiles() {
+        let mut prefs = AppPreferences::default();
+        let tracking = AudioSettings::new(48000, 24, 128, "default".to_string());
+        let mixing = AudioSettings::new(48000, 24, 1024, "default".to_string());
+
+        prefs.save_profile("Tracking", &tracking);
+        prefs.save_profile("Mixing", &mixing);
+        prefs.save_profile("Tracking", &mixing);
+
+        assert_eq!(prefs.profiles.len(), 2);
+        assert_eq!(prefs.profiles[0].name, "Tracking");
+        assert_eq!(
+            prefs.profile("Tracking").unwrap().settings.buffer_size,
+            1024
+        );
+
+        let stored = toml::to_string(&prefs).unwrap();
+        let loaded: AppPreferences = toml::from_str(&stored).unwrap();
+        assert_eq!(loaded.profiles[1].name, "Mixing");
+
+        prefs.remove_profile("Tracking");
+        assert!(prefs.profile("Tracking").is_none());
+    }
+
+    #[test]
+    fn test_custom_presets() {
+        let mut prefs = AppPreferences::default();
+        let settings = AudioSettings::new(96000, 24, 256, "default".to_string());
+        prefs.save_preset(Preset::new("Overdubs", settings.clone(), None));
+        prefs.save_preset(Preset::new(
+            "Overdubs",
+            AudioSettings::new(48000, 24, 128, "default".to_string()),
+            Some(AdvancedAudioSettings {
+                exclusive_mode: true,
+                direct_hardware: true,
+                low_latency: true,
+                buffer_size: 128,
+                sample_rate: 48000,
+            }),
+        ));
+        prefs.save_preset(Preset::new("Bouncing", settings, None));
+
+        assert_eq!(prefs.custom_presets.len(), 2);
+        assert_eq!(prefs.custom_presets[0].settings.buffer_size, 128);
+
+        let stored = toml::to_string(&prefs).unwrap();
+        let loaded: AppPreferences = toml::from_str(&stored).unwrap();
+        assert_eq!(loaded.custom_presets, prefs.custom_presets);
+    }
+
+    #[test]
+    fn test_previous_applied_settings() {
+        let mut prefs = AppPreferences::default();
+        let first = AudioSettings::new(44100, 24, 512, "default".to_string());
+        let second = AudioSettings::new(48000, 24, 128, "default".to_string());
+
+        prefs.set_last_applied(&TabType::Output, &first);
+        assert!(prefs.previous_applied(&TabType::Output).is_none());
+
+        prefs.set_last_applied(&TabType::Output, &second);
+        prefs.set_last_applied(&TabType::Output, &second);
+        assert_eq!(
+            prefs
+                .previous_applied(&TabType::Output)
+                .unwrap()
+                .buffer_size,
+            512
+        );
+        assert!(prefs.previous_applied(&TabType::Input).is_none());
+    }
+
+    #[test]
+    fn test_old_preferences_still_load() {
+        let prefs: AppPreferences = toml::from_str("system_wide_config = true").unwrap();
+        assert!(prefs.system_wide_config);
+        assert!(prefs.device_profiles.is_empty());
+        assert!(!prefs.auto_apply_device_profiles);
+        assert!(!prefs.confirm_service_restart);
+        assert!(!prefs.wizard_completed);
+        assert!(prefs.favorite_devices.is_empty());
+        assert!(prefs.profiles.is_empty());
+        assert!(prefs.custom_presets.is_empty());
+        assert_eq!(prefs.monitor_interval_ms(), DEFAULT_UPDATE_INTERVAL_MS);
+        assert_eq!(
+            prefs.rt_time_limits(),
+            (DEFAULT_RT_TIME_US, DEFAULT_RT_TIME_US)
+        );
+    }
+
+    #[test]
+    fn test_monitor_interval() {
+        let mut prefs = AppPreferences {
+            monitor_interval_ms: Some(20),
+            ..Default::default()
+        };
+        assert_eq!(prefs.monitor_interval_ms(), 20);
+
+        prefs.monitor_interval_ms = Some(0);
+        assert_eq!(prefs.monitor_interval_ms(), 10);
+    }
+
+    #[test]
+    fn test_service_name_overrides() {
+        assert_eq!(
+            AppPreferences::default().service_names(),
+            ServiceNames::default()
+        );
+
+        let prefs = AppPreferences {
+            wireplumber_service: Some("wireplumber-user".to_string()),
+            pipewire_service: Some("  ".to_string()),
+            ..Default::default()
+        };
+        let names = prefs.service_names();
+        assert_eq!(names.wireplumber, "wireplumber-user");
+        assert_eq!(names.pipewire, "pipewire");
+    }
+
+    #[test]
+    fn test_restart_confirmation() {
+        let mut prefs = AppPreferences::default();
+        assert!(!prefs.restart_needs_confirmation(false));
+        assert!(prefs.restart_needs_confirmation(true));
+
+        prefs.confirm_service_restart = true;
+        assert!(prefs.restart_needs_confirmation(false));
+    }
+}
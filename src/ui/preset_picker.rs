@@ -0,0 +1,175 @@
+/*
+ * Pro Audio Config - Preset Picker Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * "Preset" combo with a save button, shown at the top of the Output,
+ * Input and Advanced tab settings
+ */
+
+use gtk::prelude::*;
+use gtk::{Box as GtkBox, Button, ComboBoxText, DialogFlags, Entry, Label, ResponseType};
+use std::cell::RefCell;
+
+use super::components::create_constrained_combo;
+use super::dialogs::show_error_dialog;
+use super::preferences::AppPreferences;
+use crate::presets::{Preset, builtin_presets, find_preset, is_builtin_preset};
+
+thread_local! {
+    /// Every preset combo, refilled together when a preset is saved
+    static PRESET_COMBOS: RefCell<Vec<ComboBoxText>> = const { RefCell::new(Vec::new()) };
+}
+
+#[derive(Clone)]
+pub struct PresetPicker {
+    pub container: GtkBox,
+    pub combo: ComboBoxText,
+    pub save_button: Button,
+}
+
+impl PresetPicker {
+    pub fn new() -> Self {
+        let container = GtkBox::new(gtk::Orientation::Horizontal, 6);
+
+        let label = Label::new(Some("Preset:"));
+        label.set_halign(gtk::Align::Start);
+
+        let combo = create_constrained_combo();
+        combo.set_tooltip_text(Some(
+            "Fills in the settings below; nothing is applied until you click Apply",
+        ));
+        fill_preset_combo(&combo);
+        PRESET_COMBOS.with(|combos| combos.borrow_mut().push(combo.clone()));
+
+        let save_button = Button::with_label("Save as Preset...");
+        save_button.set_tooltip_text(Some(
+            "Save the current selections under a name, stored in the preferences file",
+        ));
+
+        container.pack_start(&label, false, false, 0);
+        container.pack_start(&combo, true, true, 0);
+        container.pack_start(&save_button, false, false, 0);
+
+        Self {
+            container,
+            combo,
+            save_button,
+        }
+    }
+
+    /// Calls `select` with the preset picked in the combo. Refilling the
+    /// combo leaves nothing active and does not call it.
+    pub fn connect_preset_selected<F: Fn(&Preset) + 'static>(&self, select: F) {
+        self.combo.connect_changed(move |combo| {
+            let Some(name) = combo.active_id() else {
+                return;
+            };
+            match find_preset(&name, &AppPreferences::load().custom_presets) {
+                Some(preset) => {
+                    log::info!("Selected preset: {}", preset.name);
+                    select(&preset);
+                }
+                None => log::warn!("Preset '{}' no longer exists", name),
+            }
+        });
+    }
+
+    /// Asks for a name on save and stores the preset `snapshot` builds
+    /// for it, reporting the result in `status_label`
+    pub fn connect_save<F: Fn(&str) -> Preset + 'static>(&self, status_label: &Label, snapshot: F) {
+        let combo = self.combo.clone();
+        let status_label = status_label.clone();
+        self.save_button.connect_clicked(move |_| {
+            let Some(name) = ask_preset_name() else {
+                return;
+            };
+            if is_builtin_preset(&name) {
+                show_error_dialog(&format!(
+                    "\"{}\" is a built-in preset. Please choose another name.",
+                    name
+                ));
+                return;
+            }
+
+            let preset = snapshot(&name);
+            if let Err(e) = AppPreferences::update(|prefs| prefs.save_preset(preset)) {
+                show_error_dialog(&format!("Failed to save preset: {}", e));
+                return;
+            }
+
+            refresh_preset_combos();
+            combo.set_active_id(Some(&name));
+            status_label.set_text(&format!("Preset \"{}\" saved", name));
+        });
+    }
+}
+
+impl Default for PresetPicker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Built-in presets first, then the saved ones
+fn fill_preset_combo(combo: &ComboBoxText) {
+    combo.remove_all();
+    for preset in builtin_presets() {
+        combo.append(Some(&preset.name), &preset.name);
+    }
+    for preset in AppPreferences::load().custom_presets {
+        combo.append(Some(&preset.name), &format!("{} (saved)", preset.name));
+    }
+}
+
+/// Refills every preset combo after the saved presets changed
+fn refresh_preset_combos() {
+    PRESET_COMBOS.with(|combos| {
+        for combo in combos.borrow().iter() {
+            fill_preset_combo(combo);
+        }
+    });
+}
+
+fn ask_preset_name() -> Option<String> {
+    let dialog = gtk::Dialog::with_buttons(
+        Some("Save Preset"),
+        None::<&gtk::Window>,
+        DialogFlags::MODAL,
+        &[
+            ("Cancel", ResponseType::Cancel),
+            ("Save", ResponseType::Accept),
+        ],
+    );
+
+    let content = dialog.content_area();
+    content.set_spacing(6);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+
+    let label = Label::new(Some(
+        "Name for the current selections. Saving under an existing name \
+         updates that preset.",
+    ));
+    label.set_line_wrap(true);
+    label.set_halign(gtk::Align::Start);
+
+    let entry = Entry::new();
+    entry.set_placeholder_text(Some("e.g. Vocal Overdubs"));
+    entry.set_activates_default(true);
+    dialog.set_default_response(ResponseType::Accept);
+
+    content.pack_start(&label, false, false, 0);
+    content.pack_start(&entry, false, false, 0);
+    dialog.show_all();
+
+    let response = dialog.run();
+    let name = entry.text().trim().to_string();
+    dialog.close();
+
+    (response == ResponseType::Accept && !name.is_empty()).then_some(name)
+}
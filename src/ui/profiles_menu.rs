@@ -0,0 +1,335 @@
+/*
+ * Pro Audio Config - Profiles Menu Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * "Profiles" menu: saves the Output tab settings under a name, applies
+ * saved profiles from the menu or with Ctrl+1...Ctrl+9 and binds them to
+ * devices so they are offered when the device is plugged in
+ */
+
+use gtk::gdk::ModifierType;
+use gtk::prelude::*;
+use gtk::{
+    AccelFlags, AccelGroup, CheckMenuItem, ComboBoxText, DialogFlags, Entry, Menu, MenuItem,
+    ResponseType, SeparatorMenuItem,
+};
+
+use super::app::AudioApp;
+use super::dialogs::show_error_dialog;
+use super::preferences::{AppPreferences, SavedProfile};
+
+/// Profiles that get a Ctrl+<digit> shortcut, in menu order
+const PROFILE_SHORTCUTS: usize = 9;
+
+/// Fills `menu` from the saved profiles. Called again after every change so
+/// the items and their shortcuts follow the stored list.
+pub fn populate_profiles_menu(menu: &Menu, accel_group: &AccelGroup, app_state: &AudioApp) {
+    for child in menu.children() {
+        menu.remove(&child);
+    }
+
+    let prefs = AppPreferences::load();
+    let profiles = prefs.profiles;
+
+    let save_item = MenuItem::with_label("Save Output Settings as Profile...");
+    {
+        let menu = menu.clone();
+        let accel_group = accel_group.clone();
+        let app_state = app_state.clone();
+        save_item.connect_activate(move |_| {
+            let Some(name) = ask_profile_name(&app_state) else {
+                return;
+            };
+            let settings = app_state.output_tab.selected_settings();
+            if let Err(e) = AppPreferences::update(|prefs| prefs.save_profile(&name, &settings)) {
+                show_error_dialog(&format!("Failed to save profile: {}", e));
+                return;
+            }
+            app_state
+                .output_tab
+                .status_label
+                .set_text(&format!("Profile \"{}\" saved", name));
+            refresh_later(&menu, &accel_group, &app_state);
+        });
+    }
+    menu.append(&save_item);
+
+    if !profiles.is_empty() {
+        let remove_menu = Menu::new();
+        for profile in &profiles {
+            let item = MenuItem::with_label(&profile.name);
+            let name = profile.name.clone();
+            let menu = menu.clone();
+            let accel_group = accel_group.clone();
+            let app_state = app_state.clone();
+            item.connect_activate(move |_| {
+                if let Err(e) = AppPreferences::update(|prefs| prefs.remove_profile(&name)) {
+                    show_error_dialog(&format!("Failed to remove profile: {}", e));
+                    return;
+                }
+                refresh_later(&menu, &accel_group, &app_state);
+            });
+            remove_menu.append(&item);
+        }
+        let remove_item = MenuItem::with_label("Remove Profile");
+        remove_item.set_submenu(Some(&remove_menu));
+        menu.append(&remove_item);
+
+        let bind_item = MenuItem::with_label("Bind Profile to Device...");
+        {
+            let profile_names: Vec<String> = profiles
+                .iter()
+                .map(|profile| profile.name.clone())
+                .collect();
+            let menu = menu.clone();
+            let accel_group = accel_group.clone();
+            let app_state = app_state.clone();
+            bind_item.connect_activate(move |_| {
+                let Some((device_pattern, profile_name)) =
+                    ask_device_binding(&app_state, &profile_names)
+                else {
+                    return;
+                };
+                if let Err(e) = AppPreferences::update(|prefs| {
+                    prefs.set_device_profile(&device_pattern, &profile_name)
+                }) {
+                    show_error_dialog(&format!("Failed to save device binding: {}", e));
+                    return;
+                }
+                app_state.output_tab.status_label.set_text(&format!(
+                    "Profile \"{}\" bound to devices matching \"{}\"",
+                    profile_name, device_pattern
+                ));
+                refresh_later(&menu, &accel_group, &app_state);
+            });
+        }
+        menu.append(&bind_item);
+
+        if !prefs.device_profiles.is_empty() {
+            let unbind_menu = Menu::new();
+            for rule in &prefs.device_profiles {
+                let item = MenuItem::with_label(&format!(
+                    "{} \u{2192} {}",
+                    rule.device_pattern, rule.profile_name
+                ));
+                let pattern = rule.device_pattern.clone();
+                let menu = menu.clone();
+                let accel_group = accel_group.clone();
+                let app_state = app_state.clone();
+                item.connect_activate(move |_| {
+                    if let Err(e) =
+                        AppPreferences::update(|prefs| prefs.remove_device_profile(&pattern))
+                    {
+                        show_error_dialog(&format!("Failed to remove device binding: {}", e));
+                        return;
+                    }
+                    refresh_later(&menu, &accel_group, &app_state);
+                });
+                unbind_menu.append(&item);
+            }
+            let unbind_item = MenuItem::with_label("Remove Device Binding");
+            unbind_item.set_submenu(Some(&unbind_menu));
+            menu.append(&unbind_item);
+        }
+
+        let auto_apply_item = CheckMenuItem::with_label("Apply Bound Profiles Without Asking");
+        auto_apply_item.set_tooltip_text(Some(
+            "Apply a device's profile as soon as it is plugged in instead of asking first",
+        ));
+        auto_apply_item.set_active(prefs.auto_apply_device_profiles);
+        auto_apply_item.connect_toggled(|item| {
+            let enabled = item.is_active();
+            if let Err(e) =
+                AppPreferences::update(|prefs| prefs.auto_apply_device_profiles = enabled)
+            {
+                show_error_dialog(&format!("Failed to save device profile preference: {}", e));
+            }
+        });
+        menu.append(&auto_apply_item);
+        menu.append(&SeparatorMenuItem::new());
+    }
+
+    for (index, profile) in profiles.into_iter().enumerate() {
+        let item = MenuItem::with_label(&profile.name);
+        item.set_tooltip_text(Some(&profile_summary(&profile)));
+        if index < PROFILE_SHORTCUTS {
+            // Keysyms of the ASCII digits are their character codes
+            let key = '1' as u32 + index as u32;
+            item.add_accelerator(
+                "activate",
+                accel_group,
+                key,
+                ModifierType::CONTROL_MASK,
+                AccelFlags::VISIBLE,
+            );
+        }
+
+        let app_state = app_state.clone();
+        item.connect_activate(move |_| apply_profile(&app_state, &profile));
+        menu.append(&item);
+    }
+
+    menu.show_all();
+}
+
+/// Rebuilds the menu once the activating item's signal is done; it is one
+/// of the items being replaced
+fn refresh_later(menu: &Menu, accel_group: &AccelGroup, app_state: &AudioApp) {
+    let menu = menu.clone();
+    let accel_group = accel_group.clone();
+    let app_state = app_state.clone();
+    glib::idle_add_local_once(move || populate_profiles_menu(&menu, &accel_group, &app_state));
+}
+
+/// Loads the profile into the Output tab and runs its normal apply, with the
+/// same checks and confirmations as clicking Apply
+pub fn apply_profile(app_state: &AudioApp, profile: &SavedProfile) {
+    let tab = &app_state.output_tab;
+    let settings = &profile.settings;
+
+    if settings.device_id != "default" && !tab.device_combo.set_active_id(Some(&settings.device_id))
+    {
+        show_error_dialog(&format!(
+            "The output device of profile \"{}\" is not connected.",
+            profile.name
+        ));
+        return;
+    }
+
+    tab.select_settings(settings, None, tab.system_wide_checkbox.is_active());
+    tab.device_description_entry
+        .set_text(settings.node_description.as_deref().unwrap_or_default());
+    app_state
+        .notebook
+        .set_current_page(app_state.notebook.page_num(&tab.container));
+
+    log::info!("Applying profile: {}", profile.name);
+    tab.apply_button.clicked();
+    tab.status_label.set_text(&format!(
+        "Profile \"{}\": {}",
+        profile.name,
+        tab.status_label.text()
+    ));
+}
+
+fn profile_summary(profile: &SavedProfile) -> String {
+    let settings = &profile.settings;
+    format!(
+        "{} Hz, {} bit, {} samples on {}",
+        settings.sample_rate, settings.bit_depth, settings.buffer_size, settings.device_id
+    )
+}
+
+fn ask_profile_name(app_state: &AudioApp) -> Option<String> {
+    let dialog = gtk::Dialog::with_buttons(
+        Some("Save Profile"),
+        Some(&app_state.window),
+        DialogFlags::MODAL,
+        &[
+            ("Cancel", ResponseType::Cancel),
+            ("Save", ResponseType::Accept),
+        ],
+    );
+
+    let content = dialog.content_area();
+    content.set_spacing(6);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+
+    let label = gtk::Label::new(Some(
+        "Name for the current Output tab settings. Saving under an existing name \
+         updates that profile.",
+    ));
+    label.set_line_wrap(true);
+    label.set_halign(gtk::Align::Start);
+
+    let entry = Entry::new();
+    entry.set_placeholder_text(Some("e.g. Tracking"));
+    entry.set_activates_default(true);
+    dialog.set_default_response(ResponseType::Accept);
+
+    content.pack_start(&label, false, false, 0);
+    content.pack_start(&entry, false, false, 0);
+    dialog.show_all();
+
+    let response = dialog.run();
+    let name = entry.text().trim().to_string();
+    dialog.close();
+
+    (response == ResponseType::Accept && !name.is_empty()).then_some(name)
+}
+
+/// Asks for a device pattern and the profile to apply when a matching device
+/// is plugged in. The pattern list is prefilled with the connected devices.
+fn ask_device_binding(app_state: &AudioApp, profile_names: &[String]) -> Option<(String, String)> {
+    let dialog = gtk::Dialog::with_buttons(
+        Some("Bind Profile to Device"),
+        Some(&app_state.window),
+        DialogFlags::MODAL,
+        &[
+            ("Cancel", ResponseType::Cancel),
+            ("Bind", ResponseType::Accept),
+        ],
+    );
+
+    let content = dialog.content_area();
+    content.set_spacing(6);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+
+    let label = gtk::Label::new(Some(
+        "The profile is offered whenever a device whose name contains this text is \
+         plugged in. Shorten it (e.g. to \"Scarlett\") to match every model of a family.",
+    ));
+    label.set_line_wrap(true);
+    label.set_halign(gtk::Align::Start);
+
+    let device_combo = ComboBoxText::with_entry();
+    let mut descriptions: Vec<String> = [&app_state.output_tab, &app_state.input_tab]
+        .iter()
+        .flat_map(|tab| tab.detected_devices.lock().unwrap().clone())
+        .map(|device| device.description)
+        .collect();
+    descriptions.sort();
+    descriptions.dedup();
+    for description in &descriptions {
+        device_combo.append_text(description);
+    }
+    device_combo.set_active(Some(0));
+
+    let profile_combo = ComboBoxText::new();
+    for name in profile_names {
+        profile_combo.append(Some(name), name);
+    }
+    profile_combo.set_active(Some(0));
+
+    content.pack_start(&label, false, false, 0);
+    content.pack_start(&gtk::Label::new(Some("Device:")), false, false, 0);
+    content.pack_start(&device_combo, false, false, 0);
+    content.pack_start(&gtk::Label::new(Some("Profile:")), false, false, 0);
+    content.pack_start(&profile_combo, false, false, 0);
+    dialog.set_default_response(ResponseType::Accept);
+    dialog.show_all();
+
+    let response = dialog.run();
+    let pattern = device_combo
+        .active_text()
+        .map(|text| text.trim().to_string())
+        .unwrap_or_default();
+    let profile_name = profile_combo.active_id().map(|id| id.to_string());
+    dialog.close();
+
+    match profile_name {
+        Some(profile_name) if response == ResponseType::Accept && !pattern.is_empty() => {
+            Some((pattern, profile_name))
+        }
+        _ => None,
+    }
+}
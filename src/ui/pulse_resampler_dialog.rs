@@ -0,0 +1,165 @@
+/*
+ * Pro Audio Config - Pulse Resampler Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * Dialog for the resampler quality of PulseAudio clients (browsers, games),
+ * which pipewire-pulse sets on its own regardless of the global setting
+ */
+
+use glib::ControlFlow;
+use gtk::prelude::*;
+use gtk::{DialogFlags, Label, ResponseType};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::Duration;
+
+use super::app::AudioApp;
+use super::apply_guard::{APPLY_BUSY_TEXT, begin_apply, end_apply};
+use super::components::create_constrained_combo;
+use super::dialogs::{
+    confirm_service_restart, show_config_error_dialog, show_error_dialog, show_success_dialog,
+};
+use super::tasks::add_ui_timeout;
+use crate::config::{
+    RESAMPLE_QUALITY_LEVELS, apply_pulse_resample_quality, pulse_resample_quality,
+    remove_pulse_resample_quality, resample_quality_level,
+};
+use crate::error::AudioConfigError;
+
+/// Lets the user pick the resampler quality of Pulse client streams, or go
+/// back to pipewire-pulse's default
+pub fn show_pulse_resampler_dialog(app_state: &AudioApp) {
+    let dialog = gtk::Dialog::with_buttons(
+        Some("PulseAudio Client Resampler"),
+        Some(&app_state.window),
+        DialogFlags::MODAL,
+        &[
+            ("Cancel", ResponseType::Cancel),
+            ("Use Default", ResponseType::Reject),
+            ("Apply", ResponseType::Accept),
+        ],
+    );
+
+    let content = dialog.content_area();
+    content.set_spacing(6);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+
+    let current = pulse_resample_quality();
+    let info_label = Label::new(Some(&format!(
+        "Browsers, games and other PulseAudio clients play through pipewire-pulse, which \
+         resamples their streams with its own quality setting, not the global one from the \
+         Advanced tab.\n\nCurrent: {}",
+        match current {
+            Some(level) => format!("quality {}", level),
+            None => "pipewire-pulse default".to_string(),
+        }
+    )));
+    info_label.set_line_wrap(true);
+    info_label.set_halign(gtk::Align::Start);
+
+    let quality_combo = create_constrained_combo();
+    for (name, level) in RESAMPLE_QUALITY_LEVELS {
+        quality_combo.append(Some(*name), &format!("{} (quality {})", name, level));
+    }
+    // Preselect the closest named level at or below the configured one
+    let selected = current
+        .and_then(|current| {
+            RESAMPLE_QUALITY_LEVELS
+                .iter()
+                .rev()
+                .find(|(_, level)| *level <= current)
+        })
+        .map_or("high", |(name, _)| *name);
+    quality_combo.set_active_id(Some(selected));
+
+    content.pack_start(&info_label, false, false, 0);
+    content.pack_start(&quality_combo, false, false, 6);
+
+    let status_label = app_state.output_tab.status_label.clone();
+    dialog.connect_response(move |dialog, response| {
+        match response {
+            ResponseType::Accept => {
+                let Some(level) = quality_combo
+                    .active_id()
+                    .and_then(|id| resample_quality_level(id.as_str()))
+                else {
+                    show_error_dialog("Select a resampler quality.");
+                    return;
+                };
+                if !confirm_service_restart(false) {
+                    return;
+                }
+
+                run_in_background(
+                    status_label.clone(),
+                    move || apply_pulse_resample_quality(level),
+                    format!("PulseAudio clients now resample at quality {}.", level),
+                );
+            }
+            ResponseType::Reject => {
+                if !confirm_service_restart(false) {
+                    return;
+                }
+
+                run_in_background(
+                    status_label.clone(),
+                    remove_pulse_resample_quality,
+                    "PulseAudio clients use the pipewire-pulse default resampler again."
+                        .to_string(),
+                );
+            }
+            _ => {}
+        }
+
+        dialog.close();
+    });
+
+    dialog.show_all();
+}
+
+fn run_in_background<F>(status_label: Label, action: F, success_text: String)
+where
+    F: FnOnce() -> Result<(), AudioConfigError> + Send + 'static,
+{
+    if !begin_apply() {
+        show_error_dialog(APPLY_BUSY_TEXT);
+        return;
+    }
+    status_label.set_text("Applying PulseAudio client resampler...");
+
+    let (tx, rx) = mpsc::channel();
+    let rx_arc = Arc::new(Mutex::new(rx));
+
+    std::thread::spawn(move || {
+        let _ = tx.send(action());
+    });
+
+    let rx_timeout = Arc::clone(&rx_arc);
+    add_ui_timeout(Duration::from_millis(100), move || {
+        let rx_guard = rx_timeout.lock().unwrap();
+        match rx_guard.try_recv() {
+            Ok(Ok(())) => {
+                end_apply();
+                status_label.set_text(&success_text);
+                show_success_dialog(&success_text);
+                ControlFlow::Break
+            }
+            Ok(Err(e)) => {
+                end_apply();
+                status_label.set_text("PulseAudio client resampler change failed");
+                show_config_error_dialog(&e);
+                ControlFlow::Break
+            }
+            Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                end_apply();
+                ControlFlow::Break
+            }
+        }
+    });
+}
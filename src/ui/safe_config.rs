@@ -0,0 +1,119 @@
+/*
+ * Pro Audio Config - Safe Config Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * Tools menu action that swaps every config this tool wrote for a single
+ * conservative fragment, for when audio is broken and the cause is unclear
+ */
+
+use glib::ControlFlow;
+use gtk::prelude::*;
+use gtk::{ButtonsType, DialogFlags, MessageDialog, MessageType, ResponseType};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::Duration;
+
+use super::app::AudioApp;
+use super::apply_guard::{APPLY_BUSY_TEXT, begin_apply, end_apply};
+use super::dialogs::{show_error_dialog, show_success_dialog};
+use super::tasks::add_ui_timeout;
+use crate::config::{
+    ConfigScope, SAFE_QUANTUM, SAFE_SAMPLE_RATE, apply_minimal_safe_config, managed_config_paths,
+    plan_restart,
+};
+
+/// Asks first (the action deletes files), then applies the minimal safe
+/// config in the background and lists what was done
+pub fn apply_minimal_safe_config_action(app_state: &AudioApp) {
+    if !confirm_minimal_safe_config(app_state) {
+        return;
+    }
+
+    if !begin_apply() {
+        show_error_dialog(APPLY_BUSY_TEXT);
+        return;
+    }
+
+    let status_label = app_state.output_tab.status_label.clone();
+    status_label.set_text("Applying minimal safe config...");
+
+    let (tx, rx) = mpsc::channel();
+    let rx_arc = Arc::new(Mutex::new(rx));
+
+    std::thread::spawn(move || {
+        let _ = tx.send(apply_minimal_safe_config());
+    });
+
+    let rx_timeout = Arc::clone(&rx_arc);
+    add_ui_timeout(Duration::from_millis(100), move || {
+        let rx_guard = rx_timeout.lock().unwrap();
+        match rx_guard.try_recv() {
+            Ok(Ok(report)) => {
+                end_apply();
+                status_label.set_text(&format!(
+                    "Minimal safe config applied: {} Hz, {} samples",
+                    SAFE_SAMPLE_RATE, SAFE_QUANTUM
+                ));
+                show_success_dialog(&format!(
+                    "Minimal safe config applied:\n\n{}",
+                    report.join("\n")
+                ));
+                ControlFlow::Break
+            }
+            Ok(Err(e)) => {
+                end_apply();
+                status_label.set_text("Minimal safe config failed");
+                show_error_dialog(&format!("Minimal safe config failed: {}", e));
+                ControlFlow::Break
+            }
+            Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                end_apply();
+                ControlFlow::Break
+            }
+        }
+    });
+}
+
+fn confirm_minimal_safe_config(app_state: &AudioApp) -> bool {
+    let dialog = MessageDialog::new(
+        Some(&app_state.window),
+        DialogFlags::MODAL,
+        MessageType::Warning,
+        ButtonsType::YesNo,
+        "Replace your settings with a minimal safe config?",
+    );
+    dialog.set_title("Apply Minimal Safe Config");
+
+    let existing: Vec<String> = managed_config_paths(ConfigScope::User)
+        .into_iter()
+        .filter(|path| path.exists())
+        .map(|path| format!("  {}", path.display()))
+        .collect();
+    let removed = if existing.is_empty() {
+        "  (none found)".to_string()
+    } else {
+        existing.join("\n")
+    };
+
+    dialog.set_secondary_text(Some(&format!(
+        "All user configs written by this tool are deleted and replaced by one file \
+         setting only {} Hz and a {}-sample quantum. Realtime, exclusive mode and \
+         device settings are lost.\n\nFiles that will be removed:\n{}\n\n\
+         Audio services are restarted afterwards:\n{}",
+        SAFE_SAMPLE_RATE,
+        SAFE_QUANTUM,
+        removed,
+        plan_restart(false)
+            .iter()
+            .map(|command| format!("  {}", command))
+            .collect::<Vec<_>>()
+            .join("\n")
+    )));
+
+    let response = dialog.run();
+    dialog.close();
+    response == ResponseType::Yes
+}
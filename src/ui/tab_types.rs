@@ -11,12 +11,13 @@
 
 use crate::audio::AudioSettings;
 use crate::audio::{
-    AudioDevice, detect_input_audio_device, detect_input_audio_devices, detect_output_audio_device,
-    detect_output_audio_devices,
+    AudioDevice, DeviceType, detect_input_audio_device, detect_input_audio_devices,
+    detect_output_audio_device, detect_output_audio_devices,
 };
 use crate::config::{
     apply_input_audio_settings_with_auth_blocking, apply_output_audio_settings_with_auth_blocking,
 };
+use crate::error::AudioConfigError;
 
 #[derive(Clone, Debug)]
 pub enum TabType {
@@ -74,13 +75,21 @@ impl TabType {
         }
     }
 
-    pub fn apply_settings_fn(&self) -> fn(AudioSettings) -> Result<(), String> {
+    pub fn apply_settings_fn(&self) -> fn(AudioSettings) -> Result<(), AudioConfigError> {
         match self {
             TabType::Output => apply_output_audio_settings_with_auth_blocking,
             TabType::Input => apply_input_audio_settings_with_auth_blocking,
         }
     }
 
+    /// Kind of node the tab configures, e.g. for making it the default
+    pub fn device_type(&self) -> DeviceType {
+        match self {
+            TabType::Output => DeviceType::Output,
+            TabType::Input => DeviceType::Input,
+        }
+    }
+
     pub fn selection_prefix(&self) -> &'static str {
         match self {
             TabType::Output => "Selected Output",
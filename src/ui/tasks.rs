@@ -0,0 +1,59 @@
+/*
+ * Pro Audio Config - UI Tasks Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * Registry of the polling timeouts that wait for background work,
+ * so they can be cancelled before the window's widgets are destroyed
+ */
+
+use glib::{ControlFlow, SourceId};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::time::Duration;
+
+thread_local! {
+    /// Timeouts that have not returned `ControlFlow::Break` yet
+    static ACTIVE_TIMEOUTS: RefCell<HashMap<u64, SourceId>> = RefCell::new(HashMap::new());
+    static NEXT_TIMEOUT_KEY: Cell<u64> = const { Cell::new(0) };
+}
+
+/// `glib::timeout_add_local` for callbacks that touch widgets. The source is
+/// tracked until it breaks, so `cancel_ui_timeouts` can stop it on shutdown.
+pub fn add_ui_timeout<F>(interval: Duration, mut callback: F)
+where
+    F: FnMut() -> ControlFlow + 'static,
+{
+    let key = NEXT_TIMEOUT_KEY.with(|next| {
+        let key = next.get();
+        next.set(key + 1);
+        key
+    });
+
+    let source_id = glib::timeout_add_local(interval, move || {
+        let flow = callback();
+        if matches!(flow, ControlFlow::Break) {
+            ACTIVE_TIMEOUTS.with(|timeouts| timeouts.borrow_mut().remove(&key));
+        }
+        flow
+    });
+
+    ACTIVE_TIMEOUTS.with(|timeouts| timeouts.borrow_mut().insert(key, source_id));
+}
+
+/// Removes every timeout still pending. Called when the main window is
+/// destroyed so no callback runs against destroyed widgets.
+pub fn cancel_ui_timeouts() {
+    let pending: Vec<SourceId> =
+        ACTIVE_TIMEOUTS.with(|timeouts| timeouts.borrow_mut().drain().map(|(_, id)| id).collect());
+
+    if !pending.is_empty() {
+        log::debug!("Cancelling {} pending UI timeouts", pending.len());
+    }
+
+    for source_id in pending {
+        source_id.remove();
+    }
+}
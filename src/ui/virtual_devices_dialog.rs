@@ -0,0 +1,150 @@
+/*
+ * Pro Audio Config - Virtual Devices Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * Dialog for creating and removing virtual sinks, e.g. a "Stream Mix"
+ * to route apps to and capture in OBS
+ */
+
+use glib::ControlFlow;
+use gtk::prelude::*;
+use gtk::{CheckButton, DialogFlags, Entry, Label, ResponseType};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::Duration;
+
+use super::app::AudioApp;
+use super::apply_guard::{APPLY_BUSY_TEXT, begin_apply, end_apply};
+use super::dialogs::{
+    confirm_service_restart, show_config_error_dialog, show_error_dialog, show_success_dialog,
+};
+use super::tasks::add_ui_timeout;
+use crate::config::{set_virtual_devices, virtual_devices, virtual_sink_node_name};
+
+/// Lists the virtual devices (untick to remove) and adds a new one by name
+pub fn show_virtual_devices_dialog(app_state: &AudioApp) {
+    let dialog = gtk::Dialog::with_buttons(
+        Some("Virtual Devices"),
+        Some(&app_state.window),
+        DialogFlags::MODAL,
+        &[
+            ("Cancel", ResponseType::Cancel),
+            ("Save Virtual Devices", ResponseType::Accept),
+        ],
+    );
+
+    let content = dialog.content_area();
+    content.set_spacing(6);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+
+    let info_label = Label::new(Some(
+        "A virtual device is an output that plays nowhere. Route apps to it and \
+         record its monitor, e.g. \"Monitor of Stream Mix\" as an audio input in OBS.",
+    ));
+    info_label.set_line_wrap(true);
+    info_label.set_halign(gtk::Align::Start);
+    content.pack_start(&info_label, false, false, 0);
+
+    let existing: Vec<(String, CheckButton)> = virtual_devices()
+        .into_iter()
+        .map(|name| {
+            let checkbox = CheckButton::with_label(&name);
+            checkbox.set_tooltip_text(Some(&virtual_sink_node_name(&name)));
+            checkbox.set_active(true);
+            content.pack_start(&checkbox, false, false, 0);
+            (name, checkbox)
+        })
+        .collect();
+
+    if existing.is_empty() {
+        let none_label = Label::new(Some("No virtual devices yet."));
+        none_label.set_halign(gtk::Align::Start);
+        content.pack_start(&none_label, false, false, 0);
+    }
+
+    let new_label = Label::new(Some("New virtual device:"));
+    new_label.set_halign(gtk::Align::Start);
+    let name_entry = Entry::new();
+    name_entry.set_placeholder_text(Some("e.g. Stream Mix"));
+    content.pack_start(&new_label, false, false, 6);
+    content.pack_start(&name_entry, false, false, 0);
+
+    let status_label = app_state.output_tab.status_label.clone();
+    dialog.connect_response(move |dialog, response| {
+        if response == ResponseType::Accept {
+            let mut names: Vec<String> = existing
+                .iter()
+                .filter(|(_, checkbox)| checkbox.is_active())
+                .map(|(name, _)| name.clone())
+                .collect();
+            let new_name = name_entry.text().trim().to_string();
+            if !new_name.is_empty() {
+                names.push(new_name);
+            }
+
+            let current: Vec<String> = existing.iter().map(|(name, _)| name.clone()).collect();
+            if names != current {
+                if !confirm_service_restart(false) {
+                    return;
+                }
+                save_in_background(status_label.clone(), names);
+            }
+        }
+
+        dialog.close();
+    });
+
+    dialog.show_all();
+}
+
+fn save_in_background(status_label: Label, names: Vec<String>) {
+    if !begin_apply() {
+        show_error_dialog(APPLY_BUSY_TEXT);
+        return;
+    }
+    status_label.set_text("Updating virtual devices...");
+
+    let (tx, rx) = mpsc::channel();
+    let rx_arc = Arc::new(Mutex::new(rx));
+
+    std::thread::spawn(move || {
+        let _ = tx.send(set_virtual_devices(&names).map(|_| names));
+    });
+
+    let rx_timeout = Arc::clone(&rx_arc);
+    add_ui_timeout(Duration::from_millis(100), move || {
+        let rx_guard = rx_timeout.lock().unwrap();
+        match rx_guard.try_recv() {
+            Ok(Ok(names)) => {
+                end_apply();
+                let message = if names.is_empty() {
+                    "Virtual devices removed.".to_string()
+                } else {
+                    format!(
+                        "Virtual devices updated: {}. They are listed among your output devices.",
+                        names.join(", ")
+                    )
+                };
+                status_label.set_text(&message);
+                show_success_dialog(&message);
+                ControlFlow::Break
+            }
+            Ok(Err(e)) => {
+                end_apply();
+                status_label.set_text("Virtual device change failed");
+                show_config_error_dialog(&e);
+                ControlFlow::Break
+            }
+            Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                end_apply();
+                ControlFlow::Break
+            }
+        }
+    });
+}
@@ -0,0 +1,181 @@
+/*
+ * Pro Audio Config - First-Run Wizard Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * Optional first-run setup: picks a device, use case and scope,
+ * then applies a suggested preset through the regular tab apply flow
+ */
+
+use glib::ControlFlow;
+use gtk::prelude::*;
+use gtk::{DialogFlags, Label, RadioButton, ResponseType};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::Duration;
+
+use super::app::AudioApp;
+use super::components::create_constrained_combo;
+use super::helpers::clean_device_description;
+use super::preferences::AppPreferences;
+use super::tasks::add_ui_timeout;
+use crate::audio::{AudioDevice, DeviceType, UseCase, detect_all_audio_devices, suggest_settings};
+
+/// Shows the setup wizard. Finishing or skipping it sets `wizard_completed`
+/// so it is only offered once.
+pub fn show_first_run_wizard(app_state: &AudioApp) {
+    let dialog = gtk::Dialog::with_buttons(
+        Some("Welcome to Pro Audio Config"),
+        Some(&app_state.window),
+        DialogFlags::MODAL,
+        &[
+            ("Skip", ResponseType::Cancel),
+            ("Apply Suggested Settings", ResponseType::Accept),
+        ],
+    );
+
+    let content = dialog.content_area();
+    content.set_spacing(6);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+
+    let intro_label = Label::new(Some(
+        "Answer three questions to get a sensible starting setup. \
+         Everything can be fine-tuned later in the tabs.",
+    ));
+    intro_label.set_line_wrap(true);
+    intro_label.set_halign(gtk::Align::Start);
+
+    // 1. Device
+    let device_label = Label::new(Some("Which device do you want to configure?"));
+    device_label.set_halign(gtk::Align::Start);
+
+    let device_combo = create_constrained_combo();
+    device_combo.append(Some("default"), "Default System Device");
+    device_combo.set_active_id(Some("default"));
+
+    // 2. Use case
+    let use_case_label = Label::new(Some("What do you mainly use it for?"));
+    use_case_label.set_halign(gtk::Align::Start);
+
+    let use_case_combo = create_constrained_combo();
+    for use_case in UseCase::ALL {
+        use_case_combo.append(Some(use_case.id()), use_case.label());
+    }
+    use_case_combo.set_active_id(Some(UseCase::Music.id()));
+
+    // 3. Scope
+    let scope_label = Label::new(Some("Who should the settings apply to?"));
+    scope_label.set_halign(gtk::Align::Start);
+
+    let user_radio = RadioButton::with_label("Just me (no password needed)");
+    let system_radio = RadioButton::with_label_from_widget(
+        &user_radio,
+        "All users, system-wide (requires admin password)",
+    );
+
+    content.pack_start(&intro_label, false, false, 0);
+    content.pack_start(&device_label, false, false, 0);
+    content.pack_start(&device_combo, false, false, 0);
+    content.pack_start(&use_case_label, false, false, 0);
+    content.pack_start(&use_case_combo, false, false, 0);
+    content.pack_start(&scope_label, false, false, 0);
+    content.pack_start(&user_radio, false, false, 0);
+    content.pack_start(&system_radio, false, false, 0);
+
+    let devices: Arc<Mutex<Vec<AudioDevice>>> = Arc::new(Mutex::new(Vec::new()));
+    load_wizard_devices(&device_combo, Arc::clone(&devices));
+
+    let app_state = app_state.clone();
+    dialog.connect_response(move |dialog, response| {
+        if let Err(e) = AppPreferences::update(|prefs| prefs.wizard_completed = true) {
+            log::warn!("Failed to save wizard state: {}", e);
+        }
+
+        if response == ResponseType::Accept {
+            let use_case = use_case_combo
+                .active_id()
+                .and_then(|id| UseCase::from_id(&id))
+                .unwrap_or(UseCase::Music);
+            let device = device_combo.active_id().and_then(|id| {
+                devices
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .find(|device| device.id == id.as_str())
+                    .cloned()
+            });
+            let system_wide = system_radio.is_active();
+
+            let settings = suggest_settings(use_case, device.as_ref());
+            log::debug!(
+                "Wizard suggested {:?} for use case {:?}",
+                settings,
+                use_case
+            );
+
+            let (tab, page) = match device.as_ref().map(|device| &device.device_type) {
+                Some(DeviceType::Input) => (&app_state.input_tab, 1),
+                _ => (&app_state.output_tab, 0),
+            };
+
+            app_state
+                .output_tab
+                .system_wide_checkbox
+                .set_active(system_wide);
+            app_state
+                .input_tab
+                .system_wide_checkbox
+                .set_active(system_wide);
+            tab.select_settings(&settings, device.as_ref(), system_wide);
+            app_state.notebook.set_current_page(Some(page));
+
+            // Same path as a manual apply: restart confirmation, auth and verification
+            tab.apply_button.clicked();
+        }
+
+        dialog.close();
+    });
+
+    dialog.show_all();
+}
+
+fn load_wizard_devices(device_combo: &gtk::ComboBoxText, devices: Arc<Mutex<Vec<AudioDevice>>>) {
+    let device_combo = device_combo.clone();
+
+    let (tx, rx) = mpsc::channel();
+    let rx_arc = Arc::new(Mutex::new(rx));
+
+    std::thread::spawn(move || {
+        let _ = tx.send(detect_all_audio_devices());
+    });
+
+    let rx_timeout = Arc::clone(&rx_arc);
+    add_ui_timeout(Duration::from_millis(100), move || {
+        let rx_guard = rx_timeout.lock().unwrap();
+        match rx_guard.try_recv() {
+            Ok(Ok(detected)) => {
+                for device in detected.iter().filter(|device| device.available) {
+                    let description = clean_device_description(&device.description);
+                    let display_text = if description.is_empty() {
+                        device.name.clone()
+                    } else {
+                        description
+                    };
+                    device_combo.append(Some(&device.id), &display_text);
+                }
+                *devices.lock().unwrap() = detected;
+                ControlFlow::Break
+            }
+            Ok(Err(e)) => {
+                log::info!("Wizard device detection failed: {}", e);
+                ControlFlow::Break
+            }
+            Err(mpsc::TryRecvError::Empty) => ControlFlow::Continue,
+            Err(mpsc::TryRecvError::Disconnected) => ControlFlow::Break,
+        }
+    });
+}
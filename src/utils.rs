@@ -8,4 +8,178 @@
  * Utility functions and objects
  */
 
-// Utility functions will go here in future
+/// Lines of unchanged context around each change in `unified_diff`
+pub const DIFF_CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DiffOp {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Unified diff (as `diff -u` prints it) from `old` to `new`, using a line
+/// LCS. Empty when the contents are equal.
+pub fn unified_diff(old_label: &str, old: &str, new_label: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let ops = diff_lines(&old_lines, &new_lines);
+
+    if ops.iter().all(|op| matches!(op, DiffOp::Equal(..))) {
+        return String::new();
+    }
+
+    let mut out = format!("--- {}\n+++ {}\n", old_label, new_label);
+
+    // Hunks are runs of ops whose changes are at most 2 * context apart
+    let changes: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, op)| !matches!(op, DiffOp::Equal(..)))
+        .map(|(i, _)| i)
+        .collect();
+
+    let mut hunk_start = 0;
+    while hunk_start < changes.len() {
+        let mut hunk_end = hunk_start;
+        while hunk_end + 1 < changes.len()
+            && changes[hunk_end + 1] - changes[hunk_end] <= 2 * DIFF_CONTEXT_LINES + 1
+        {
+            hunk_end += 1;
+        }
+
+        let first = changes[hunk_start].saturating_sub(DIFF_CONTEXT_LINES);
+        let last = (changes[hunk_end] + DIFF_CONTEXT_LINES).min(ops.len() - 1);
+        let hunk = &ops[first..=last];
+
+        // Positions in each file where the hunk starts
+        let (old_start, new_start) = ops[..first].iter().fold((0, 0), |(o, n), op| match op {
+            DiffOp::Equal(..) => (o + 1, n + 1),
+            DiffOp::Delete(_) => (o + 1, n),
+            DiffOp::Insert(_) => (o, n + 1),
+        });
+        let old_count = hunk
+            .iter()
+            .filter(|op| !matches!(op, DiffOp::Insert(_)))
+            .count();
+        let new_count = hunk
+            .iter()
+            .filter(|op| !matches!(op, DiffOp::Delete(_)))
+            .count();
+
+        out.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(old_start, old_count),
+            hunk_range(new_start, new_count)
+        ));
+        for op in hunk {
+            match *op {
+                DiffOp::Equal(i, _) => out.push_str(&format!(" {}\n", old_lines[i])),
+                DiffOp::Delete(i) => out.push_str(&format!("-{}\n", old_lines[i])),
+                DiffOp::Insert(j) => out.push_str(&format!("+{}\n", new_lines[j])),
+            }
+        }
+
+        hunk_start = hunk_end + 1;
+    }
+
+    out
+}
+
+/// `start,count` of a hunk side, 1-based; an empty side names the line before it
+fn hunk_range(start: usize, count: usize) -> String {
+    match count {
+        0 => format!("{},0", start),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{}", start + 1, count),
+    }
+}
+
+fn diff_lines(old: &[&str], new: &[&str]) -> Vec<DiffOp> {
+    // lcs[i][j]: length of the LCS of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            ops.push(DiffOp::Equal(i, j));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            ops.push(DiffOp::Delete(i));
+            i += 1;
+        } else {
+            ops.push(DiffOp::Insert(j));
+            j += 1;
+        }
+    }
+    ops.extend((i..old.len()).map(DiffOp::Delete));
+    ops.extend((j..new.len()).map(DiffOp::Insert));
+    ops
+}
+
+/// Whether `program` is an executable file in one of the PATH directories
+pub fn find_in_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|path| std::env::split_paths(&path).any(|dir| dir.join(program).is_file()))
+}
+
+/// Levenshtein distance between two strings, counted in chars
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a_char != *b_char);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unified_diff() {
+        assert_eq!(unified_diff("a", "same\n", "b", "same\n"), "");
+
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n13\n14\n15\n";
+        let new = "1\n2\n3\n4\nfive\n6\n7\n8\n9\n10\n11\n12\n13\n14\n15\n16\n";
+        assert_eq!(
+            unified_diff("old.conf", old, "new.conf", new),
+            "--- old.conf\n+++ new.conf\n\
+             @@ -2,7 +2,7 @@\n 2\n 3\n 4\n-5\n+five\n 6\n 7\n 8\n\
+             @@ -13,3 +13,4 @@\n 13\n 14\n 15\n+16\n"
+        );
+
+        assert_eq!(
+            unified_diff("a", "", "b", "new\n"),
+            "--- a\n+++ b\n@@ -0,0 +1 @@\n+new\n"
+        );
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("reaper", "reaper"), 0);
+        assert_eq!(edit_distance("reaper", "reaper6"), 1);
+        assert_eq!(edit_distance("ardour", "ardour8"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+}
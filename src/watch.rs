@@ -0,0 +1,169 @@
+/*
+ * Pro Audio Config - Session Watch Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * Headless watch mode (--watch <process>): applies the session profile
+ * while a DAW is running and restores everyday settings once it exits
+ */
+
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crate::audio::{AudioSettings, UseCase, suggest_settings};
+use crate::config::{remove_user_audio_settings, update_audio_settings};
+use crate::ui::preferences::AppPreferences;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Cleared by SIGINT/SIGTERM so the watcher can revert before exiting
+static KEEP_WATCHING: AtomicBool = AtomicBool::new(true);
+
+extern "C" fn stop_watching(_signal: libc::c_int) {
+    KEEP_WATCHING.store(false, Ordering::SeqCst);
+}
+
+/// Watches for `process_name` and swaps between the session profile and the
+/// everyday settings. Runs until interrupted; always user scope, so it never
+/// needs a password prompt in the background.
+pub fn run_watch(process_name: &str) -> Result<(), String> {
+    let process_name = process_name.trim();
+    if process_name.is_empty() {
+        return Err("--watch needs a process name, e.g. --watch reaper".to_string());
+    }
+
+    let handler = stop_watching as extern "C" fn(libc::c_int) as libc::sighandler_t;
+    unsafe {
+        libc::signal(libc::SIGINT, handler);
+        libc::signal(libc::SIGTERM, handler);
+    }
+
+    let prefs = AppPreferences::load();
+    let session = session_settings(&prefs);
+    log::info!(
+        "Watching for '{}' - session profile {}Hz/{}bit/{} samples",
+        process_name,
+        session.sample_rate,
+        session.bit_depth,
+        session.buffer_size
+    );
+
+    // A previous watcher may have been killed while the session was active
+    let mut session_active = prefs.watch_session_active;
+
+    while KEEP_WATCHING.load(Ordering::SeqCst) {
+        let running = is_process_running(process_name);
+
+        if running && !session_active {
+            log::info!("'{}' started, applying session profile", process_name);
+            match update_audio_settings(&session, false, None) {
+                Ok(outcome) => log::info!("✓ {}", outcome.summary()),
+                Err(e) => log::warn!("Failed to apply session profile: {}", e),
+            }
+            session_active = true;
+            set_session_active(true);
+        } else if !running && session_active {
+            log::info!(
+                "'{}' is not running, restoring everyday settings",
+                process_name
+            );
+            restore_everyday_settings();
+            session_active = false;
+            set_session_active(false);
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+    }
+
+    if session_active {
+        log::info!("Watch stopped, restoring everyday settings");
+        restore_everyday_settings();
+        set_session_active(false);
+    }
+
+    Ok(())
+}
+
+/// Checks /proc for a process whose name or executable matches `process_name`
+pub fn is_process_running(process_name: &str) -> bool {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return false;
+    };
+
+    entries.flatten().any(|entry| {
+        let path = entry.path();
+        let comm = fs::read_to_string(path.join("comm")).unwrap_or_default();
+        let cmdline = fs::read(path.join("cmdline")).unwrap_or_default();
+        process_matches(&comm, &cmdline, process_name)
+    })
+}
+
+/// Settings used while the watched process runs: the stored session profile,
+/// otherwise the production preset
+fn session_settings(prefs: &AppPreferences) -> AudioSettings {
+    prefs
+        .watch_session_settings
+        .clone()
+        .unwrap_or_else(|| suggest_settings(UseCase::Production, None))
+}
+
+/// Re-applies the last settings applied from the Output tab, or drops the
+/// user-level configs so PipeWire falls back to its defaults
+fn restore_everyday_settings() {
+    let result = match AppPreferences::load().last_applied_output {
+        Some(everyday) => update_audio_settings(&everyday, false, None)
+            .map(|_| ())
+            .map_err(String::from),
+        None => remove_user_audio_settings(),
+    };
+
+    match result {
+        Ok(()) => log::info!("✓ Everyday settings restored"),
+        Err(e) => log::warn!("Failed to restore everyday settings: {}", e),
+    }
+}
+
+fn set_session_active(active: bool) {
+    if let Err(e) = AppPreferences::update(|prefs| prefs.watch_session_active = active) {
+        log::warn!("Failed to save watch state: {}", e);
+    }
+}
+
+/// `comm` is truncated to 15 characters by the kernel, so long names are
+/// also compared against the executable name from the command line
+fn process_matches(comm: &str, cmdline: &[u8], process_name: &str) -> bool {
+    let comm = comm.trim();
+    if !comm.is_empty() && comm == process_name {
+        return true;
+    }
+
+    let argv0 = cmdline.split(|byte| *byte == 0).next().unwrap_or_default();
+    let argv0 = String::from_utf8_lossy(argv0);
+    let executable = argv0.rsplit('/').next().unwrap_or_default();
+
+    !executable.is_empty() && executable == process_name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process_matches() {
+        assert!(process_matches("reaper\n", b"", "reaper"));
+        assert!(process_matches(
+            "bitwig-studio-e",
+            b"/opt/bitwig-studio/bitwig-studio-engine\0--flag\0",
+            "bitwig-studio-engine"
+        ));
+        assert!(!process_matches(
+            "reaper-helper",
+            b"/usr/bin/reaper-helper\0",
+            "reaper"
+        ));
+        assert!(!process_matches("", b"", "reaper"));
+    }
+}
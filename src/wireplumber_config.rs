@@ -0,0 +1,176 @@
+/*
+ * Pro Audio Config - WirePlumber Config Module
+ * Version: 2.1
+ * Copyright (c) 2025-2026 Peter Leukanič
+ * Under MIT License
+ * Feel free to share and modify
+ *
+ * Builder for WirePlumber rule fragments. Rules are serialized with
+ * serde_json, which SPA-JSON accepts as is, so a generated fragment is
+ * always well-formed.
+ */
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// One rule: objects matching any entry of `matches` (all properties of an
+/// entry must match) get the `update-props` properties
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WirePlumberRule {
+    pub matches: Vec<BTreeMap<String, Value>>,
+    pub actions: RuleActions,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuleActions {
+    #[serde(rename = "update-props")]
+    pub update_props: BTreeMap<String, Value>,
+}
+
+impl WirePlumberRule {
+    /// Rule for objects whose `property` equals `value` ("~" prefix for a regex)
+    pub fn matching(property: &str, value: &str) -> Self {
+        Self::default().or_matching(property, value)
+    }
+
+    /// Also applies the rule to objects whose `property` equals `value`
+    pub fn or_matching(mut self, property: &str, value: &str) -> Self {
+        let mut entry = BTreeMap::new();
+        entry.insert(property.to_string(), Value::from(value));
+        self.matches.push(entry);
+        self
+    }
+
+    /// Sets a property on matching objects
+    pub fn set(mut self, property: &str, value: impl Into<Value>) -> Self {
+        self.actions
+            .update_props
+            .insert(property.to_string(), value.into());
+        self
+    }
+
+    /// Sets a property only when there is a value
+    pub fn set_opt<V: Into<Value>>(self, property: &str, value: Option<V>) -> Self {
+        match value {
+            Some(value) => self.set(property, value),
+            None => self,
+        }
+    }
+}
+
+/// A fragment holding a list of rules under one section, e.g.
+/// `monitor.alsa.rules`, with optional `#` comment lines at the top
+#[derive(Debug, Clone, PartialEq)]
+pub struct WirePlumberConfig {
+    /// Keys from the top level down to the rule list, e.g. ["alsa-monitor", "rules"]
+    section: Vec<String>,
+    comments: Vec<String>,
+    rules: Vec<WirePlumberRule>,
+}
+
+impl WirePlumberConfig {
+    pub fn new(section: &[&str]) -> Self {
+        Self {
+            section: section.iter().map(|key| key.to_string()).collect(),
+            comments: Vec::new(),
+            rules: Vec::new(),
+        }
+    }
+
+    /// Adds a comment line above the rules; read back by the header parsers
+    pub fn comment(mut self, line: &str) -> Self {
+        self.comments.push(line.to_string());
+        self
+    }
+
+    pub fn rule(mut self, rule: WirePlumberRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn rules(mut self, rules: impl IntoIterator<Item = WirePlumberRule>) -> Self {
+        self.rules.extend(rules);
+        self
+    }
+
+    /// The fragment without comments, as a JSON value
+    pub fn to_json(&self) -> Value {
+        let rules = serde_json::to_value(&self.rules).unwrap_or_default();
+        self.section.iter().rev().fold(rules, |inner, key| {
+            let mut object = Map::new();
+            object.insert(key.clone(), inner);
+            Value::Object(object)
+        })
+    }
+
+    /// The file contents: comment lines, then the rules as indented JSON
+    pub fn render(&self) -> String {
+        let mut content: String = self
+            .comments
+            .iter()
+            .map(|line| format!("# {}\n", line))
+            .collect();
+        if !content.is_empty() {
+            content.push('\n');
+        }
+        content.push_str(&serde_json::to_string_pretty(&self.to_json()).unwrap_or_default());
+        content.push('\n');
+        content
+    }
+
+    /// Reads back the rules of a fragment written by `render`
+    pub fn parse(content: &str, section: &[&str]) -> Result<Vec<WirePlumberRule>, String> {
+        let json: String = content
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut value: Value = serde_json::from_str(&json)
+            .map_err(|e| format!("Invalid WirePlumber config: {}", e))?;
+
+        for key in section {
+            value = value
+                .get_mut(*key)
+                .map(Value::take)
+                .ok_or_else(|| format!("WirePlumber config has no \"{}\" section", key))?;
+        }
+
+        serde_json::from_value(value).map_err(|e| format!("Invalid WirePlumber rules: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wireplumber_config_round_trip() {
+        let rule = WirePlumberRule::matching("node.name", "alsa_output.\"quoted\"")
+            .or_matching("node.name", "~alsa_input.*")
+            .set("audio.rate", 48000)
+            .set("audio.allowed-rates", vec![44100, 48000])
+            .set("api.alsa.disable-batch", true)
+            .set_opt("node.description", None::<&str>)
+            .set_opt("audio.format", Some("S32LE"));
+        let config = WirePlumberConfig::new(&["monitor.alsa.rules"])
+            .comment("Pro Audio Config")
+            .rule(rule.clone());
+
+        let content = config.render();
+        assert!(content.starts_with("# Pro Audio Config\n"));
+        assert!(content.contains(r#""node.name": "alsa_output.\"quoted\"""#));
+        assert!(!content.contains("node.description"));
+
+        let parsed = WirePlumberConfig::parse(&content, &["monitor.alsa.rules"]).unwrap();
+        assert_eq!(parsed, vec![rule]);
+        assert_eq!(parsed[0].matches.len(), 2);
+
+        let nested = WirePlumberConfig::new(&["alsa-monitor", "rules"]).render();
+        assert_eq!(
+            WirePlumberConfig::parse(&nested, &["alsa-monitor", "rules"]).unwrap(),
+            Vec::new()
+        );
+        assert!(WirePlumberConfig::parse(&nested, &["monitor.alsa.rules"]).is_err());
+    }
+}
@@ -0,0 +1,145 @@
+//! Apply flow tests: update_audio_settings end-to-end against a temp config dir
+//!
+//! PRO_AUDIO_CONFIG_DIR redirects every user-level file and PRO_AUDIO_OFFLINE
+//! skips the service restart and live verification, so these run headless
+//! and never touch ~/.config or the running audio stack.
+
+use pro_audio_config::audio::AudioSettings;
+use pro_audio_config::config::{
+    ApplyMethod, CONFIG_DIR_ENV, OFFLINE_ENV, PULSE_CONFIG_FILE, SAFE_CONFIG_FILE,
+    apply_minimal_safe_config, set_pulse_quantum_sync, update_audio_settings,
+};
+use pro_audio_config::parse_spa_json;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// The environment and the pulse sync switch are process-wide
+static APPLY_LOCK: Mutex<()> = Mutex::new(());
+
+/// Points the config dir at a fresh temp dir and turns on offline mode
+fn offline_config_dir() -> tempfile::TempDir {
+    let temp_dir = tempfile::tempdir().unwrap();
+    unsafe {
+        std::env::set_var(CONFIG_DIR_ENV, temp_dir.path());
+        std::env::set_var(OFFLINE_ENV, "1");
+    }
+    temp_dir
+}
+
+fn fragment_dir(root: &Path) -> PathBuf {
+    root.join("pipewire/pipewire.conf.d")
+}
+
+/// Every file the apply left behind must be valid SPA-JSON
+fn assert_all_configs_parse(dir: &Path) {
+    for entry in fs::read_dir(dir).unwrap().flatten() {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "conf") {
+            let content = fs::read_to_string(&path).unwrap();
+            assert!(
+                parse_spa_json(&content).is_ok(),
+                "{} is not valid SPA-JSON",
+                path.display()
+            );
+        }
+    }
+}
+
+#[test]
+fn test_apply_writes_fragment() {
+    let _lock = APPLY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let temp_dir = offline_config_dir();
+
+    let settings = AudioSettings::new(96000, 24, 256, "default".to_string());
+    let outcome = update_audio_settings(&settings, false, None).unwrap();
+    assert_eq!(outcome.method_used, ApplyMethod::PipeWireFragment);
+    // Nothing was restarted, so nothing can have been verified
+    assert!(!outcome.verified);
+
+    let fragment = fragment_dir(temp_dir.path()).join("99-pro-audio-high-priority.conf");
+    let parsed = parse_spa_json(&fs::read_to_string(&fragment).unwrap()).unwrap();
+    let properties = &parsed["context.properties"];
+    assert_eq!(properties["default.clock.rate"], 96000);
+    assert_eq!(properties["default.clock.quantum"], 256);
+    assert_eq!(properties["default.clock.force-quantum"], 256);
+
+    assert_all_configs_parse(&fragment_dir(temp_dir.path()));
+}
+
+#[test]
+fn test_apply_removes_conflicting_fragments() {
+    let _lock = APPLY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let temp_dir = offline_config_dir();
+    let dir = fragment_dir(temp_dir.path());
+    fs::create_dir_all(&dir).unwrap();
+
+    // Left over from earlier applies, plus one file that isn't ours
+    for stale in [
+        "99-pro-audio.conf",
+        "99-pro-audio-advanced.conf",
+        "99-pro-audio-quantum-override.conf",
+    ] {
+        fs::write(dir.join(stale), "context.properties = { }\n").unwrap();
+    }
+    fs::write(dir.join("10-my-own.conf"), "context.properties = { }\n").unwrap();
+
+    let settings = AudioSettings::new(48000, 24, 512, "default".to_string());
+    update_audio_settings(&settings, false, None).unwrap();
+
+    assert!(!dir.join("99-pro-audio.conf").exists());
+    assert!(!dir.join("99-pro-audio-advanced.conf").exists());
+    assert!(!dir.join("99-pro-audio-quantum-override.conf").exists());
+    assert!(dir.join("10-my-own.conf").exists());
+    assert!(dir.join("99-pro-audio-high-priority.conf").exists());
+}
+
+#[test]
+fn test_apply_replaces_safe_config() {
+    let _lock = APPLY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let temp_dir = offline_config_dir();
+    let safe_fragment = fragment_dir(temp_dir.path()).join(SAFE_CONFIG_FILE);
+
+    apply_minimal_safe_config().unwrap();
+    assert!(safe_fragment.exists());
+
+    let settings = AudioSettings::new(96000, 24, 128, "default".to_string());
+    update_audio_settings(&settings, false, None).unwrap();
+
+    assert!(!safe_fragment.exists());
+    assert!(
+        fragment_dir(temp_dir.path())
+            .join("99-pro-audio-high-priority.conf")
+            .exists()
+    );
+}
+
+#[test]
+fn test_apply_syncs_pulse_fragment() {
+    let _lock = APPLY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let temp_dir = offline_config_dir();
+    let pulse_fragment = temp_dir
+        .path()
+        .join("pipewire/pipewire-pulse.conf.d")
+        .join(PULSE_CONFIG_FILE);
+
+    let settings = AudioSettings::new(48000, 24, 64, "default".to_string());
+
+    set_pulse_quantum_sync(true);
+    let result = update_audio_settings(&settings, false, None);
+    set_pulse_quantum_sync(false);
+    result.unwrap();
+
+    let parsed = parse_spa_json(&fs::read_to_string(&pulse_fragment).unwrap()).unwrap();
+    assert_eq!(parsed["pulse.properties"]["pulse.min.quantum"], "64/48000");
+
+    // With the sync off the next apply drops it and warns about the clamp
+    let outcome = update_audio_settings(&settings, false, None).unwrap();
+    assert!(!pulse_fragment.exists());
+    assert!(
+        outcome
+            .warnings
+            .iter()
+            .any(|warning| warning.contains("pulse.min.quantum"))
+    );
+}
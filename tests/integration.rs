@@ -9,6 +9,7 @@ use common::*;
 #[test]
 fn test_library_integration() {
     // Basic smoke test to ensure all modules work together
+    use pro_audio_config::AudioConfigError;
     use pro_audio_config::audio::AudioSettings;
     use pro_audio_config::config::{
         apply_input_audio_settings_with_auth_blocking,
@@ -23,9 +24,9 @@ fn test_library_integration() {
         // Just create the settings, don't actually apply them
         let _settings = settings.clone();
         // The functions exist and can be referenced
-        let _output_fn: fn(AudioSettings) -> Result<(), String> =
+        let _output_fn: fn(AudioSettings) -> Result<(), AudioConfigError> =
             apply_output_audio_settings_with_auth_blocking;
-        let _input_fn: fn(AudioSettings) -> Result<(), String> =
+        let _input_fn: fn(AudioSettings) -> Result<(), AudioConfigError> =
             apply_input_audio_settings_with_auth_blocking;
     });
     assert!(result.is_ok());
@@ -9,6 +9,11 @@ echo "=== Uninstalling Pro Audio Config ==="
 echo "Removing binary..."
 sudo rm -f /usr/local/bin/pro-audio-config
 
+# Remove privileged helper and polkit action
+echo "Removing privileged helper..."
+sudo rm -f /usr/libexec/pro-audio-config-helper
+sudo rm -f /usr/share/polkit-1/actions/com.github.peterlsvk.pro-audio-config.policy
+
 # Remove icons (all sizes that might have been installed)
 echo "Removing icons..."
 sudo rm -f /usr/local/share/icons/hicolor/16x16/apps/pro-audio-config.png